@@ -0,0 +1,14 @@
+#version 330 core
+
+precision lowp float;
+
+in vec4 v_color;
+
+out vec4 color;
+
+#include "grade.glsl"
+
+void main()
+{
+    color = vec4(grade(v_color.rgb), v_color.a);
+}
@@ -0,0 +1,101 @@
+#version 330 core
+
+// Variants:
+// SDF - the font textures hold signed distance fields rather than coverage.
+
+precision highp float;
+
+in vec4 v_color;
+in vec2 v_tex_coords;
+in float v_tex_index;
+in vec2 v_outline;
+
+out vec4 color;
+
+// Layers of the font textures of every tile style, their outlined versions and the dynamic glyphs.
+uniform sampler2DArray glyphs;
+
+#include "grade.glsl"
+
+#ifdef SDF
+
+#define SMOOTHING 0.09
+#define BUFFER 0.475
+
+// Distance the buffer moves per unit of outline thickness, and the smoothing of glows.
+#define THICKNESS_STEP 0.08
+#define GLOW_SMOOTHING 0.3
+
+vec4 calculate_frag_color(float distance) {
+    // Thicker outlines lower the buffer (the outline thickness is 1.0 for regular glyphs), and
+    // glows widen the smoothing into a soft falloff.
+    float edge = clamp(BUFFER - (v_outline.x - 1.0) * THICKNESS_STEP, 0.05, 0.95);
+    float smoothing = mix(SMOOTHING, GLOW_SMOOTHING, v_outline.y);
+    float alpha = smoothstep(edge - smoothing, edge + smoothing, distance);
+    vec4 frag_color = vec4(v_color.rgb, 1.0) * alpha * v_color.a;
+    frag_color.a += frag_color.a * 0.3;
+    return frag_color;
+}
+
+void main()
+{
+    float layer = floor(v_tex_index);
+    vec4 frag_color = calculate_frag_color(texture(glyphs, vec3(v_tex_coords, layer)).a);
+
+    color = vec4(grade(frag_color.rgb), frag_color.a);
+}
+
+#else
+
+// Apply a subtle blur to reduce scaling artifacts.
+// TODO: Render to framebuffer instead and use better shading techniques?
+const vec3 blur[9] = vec3[](
+    vec3(-1.0,  1.0, 1.0 / 16.0),
+    vec3(-1.0,  0.0, 2.0 / 16.0),
+    vec3(-1.0,  1.0, 1.0 / 16.0),
+    vec3( 0.0, -1.0, 2.0 / 16.0),
+    vec3( 0.0,  0.0, 4.0 / 16.0),
+    vec3( 0.0,  1.0, 2.0 / 16.0),
+    vec3( 1.0, -1.0, 1.0 / 16.0),
+    vec3( 1.0,  0.0, 2.0 / 16.0),
+    vec3( 1.0,  1.0, 1.0 / 16.0)
+);
+
+// Max distance (in texels) of the samples from a glyph's edge. Glyphs are padded by 2 texels in the
+// atlas and the dynamic glyph page, less half a texel so linear filtering stays within the padding.
+#define MAX_SPREAD 1.5
+
+// Samples the glyph texture layer with the blur. The samples are spread by the outline thickness
+// (which is 1.0 for regular glyphs), up to the glyph padding so neighboring glyphs never bleed in.
+// Thick outlines take the max of the samples to stay hard edged while glows spread the blur
+// further and keep it soft.
+vec4 sample_glyph(float layer)
+{
+    vec2 texel = vec2(1.0) / textureSize(glyphs, 0).xy;
+    float spread = min(v_outline.x * (1.0 + v_outline.y), MAX_SPREAD);
+    vec4 blurred = vec4(0.0);
+    vec4 dilated = vec4(0.0);
+
+    for (int i = 0; i < blur.length(); ++i) {
+        vec2 coords = v_tex_coords + texel * blur[i].xy * spread;
+        vec4 texel_color = texture(glyphs, vec3(coords, layer));
+        blurred += blur[i].z * texel_color;
+        dilated = max(dilated, texel_color);
+    }
+
+    if (v_outline.x <= 1.0 || v_outline.y > 0.0) {
+        return blurred;
+    }
+
+    return dilated;
+}
+
+void main()
+{
+    vec4 modifier = sample_glyph(floor(v_tex_index));
+
+    color = v_color * modifier;
+    color.rgb = grade(color.rgb);
+}
+
+#endif
@@ -0,0 +1,23 @@
+#version 330 core
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec4 color;
+layout(location = 2) in vec2 tex_coords;
+layout(location = 3) in float tex_index;
+layout(location = 4) in vec2 outline;
+
+out vec4 v_color;
+out vec2 v_tex_coords;
+out float v_tex_index;
+out vec2 v_outline;
+
+uniform mat4 projection;
+
+void main()
+{
+    v_color = color;
+    v_tex_coords = tex_coords;
+    v_tex_index = tex_index;
+    v_outline = outline;
+    gl_Position = projection * vec4(position, 1.0, 1.0);
+}
@@ -0,0 +1,12 @@
+// Color grading shared by the background and foreground programs.
+
+uniform vec3 grade_tint;
+uniform float grade_brightness;
+uniform float grade_saturation;
+
+// Applies the color grading to a (premultiplied) color.
+vec3 grade(vec3 rgb)
+{
+    float luma = dot(rgb, vec3(0.299, 0.587, 0.114));
+    return mix(vec3(luma), rgb, grade_saturation) * grade_tint * grade_brightness;
+}
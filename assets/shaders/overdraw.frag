@@ -0,0 +1,12 @@
+#version 330 core
+
+precision lowp float;
+
+out vec4 color;
+
+void main()
+{
+    // Added (with additive blending) for every quad covering the fragment, so that the color
+    // brightens from black with each layer drawn and saturates after ten.
+    color = vec4(0.1, 0.05, 0.025, 1.0);
+}
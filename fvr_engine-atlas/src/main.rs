@@ -1,12 +1,13 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::{self, File};
 use std::io::{prelude::*, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
-use clap::{App, AppSettings, SubCommand};
+use clap::{App, AppSettings, Arg, SubCommand};
 use image::{DynamicImage, GenericImage, GenericImageView, ImageBuffer, Rgba};
 use rect_packer::Packer;
+use serde_derive::Deserialize;
 use xml::reader::{EventReader, XmlEvent};
 
 use fvr_engine_core::prelude::*;
@@ -21,14 +22,8 @@ const OUTPUT_DIR: &str = "./assets/fonts";
 // Directory of input bmfont files.
 const FONTS_DIR: &str = "./fvr_engine-atlas/fonts";
 
-// Glyphs that are always copied from the default font.
-// const ALWAYS_DEFAULT_GLYPHS: &[i32] = &[
-// '♥' as i32,
-// '•' as i32,
-// '◘' as i32,
-// '○' as i32,
-// '◙' as i32,
-// ];
+// Name of the optional file in a font's directory listing its glyph substitutions.
+const SUBSTITUTIONS_FILE: &str = "substitutions.toml";
 
 // Dimensions of the output atlas.
 // 1024x1024 is enough for most 32px font rendering.
@@ -36,13 +31,36 @@ const FONTS_DIR: &str = "./fvr_engine-atlas/fonts";
 const OUTPUT_WIDTH: i32 = 1024;
 const OUTPUT_HEIGHT: i32 = 1024;
 
+// Glyph substitutions of a font, applied to every style when generating its atlases.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GlyphSubstitutions {
+    // Glyphs that are always copied from the default font (e.g. "♥" and "•").
+    default: Vec<char>,
+    // Glyphs that are always rendered as another glyph (e.g. "◙" = "◘").
+    remap: BTreeMap<char, char>,
+}
+
+// Loads the glyph substitutions of a font (none if the font has no substitutions file).
+fn load_substitutions(name: &str) -> Result<GlyphSubstitutions> {
+    let path = format!("{}/{}/{}", FONTS_DIR, name, SUBSTITUTIONS_FILE);
+
+    if !Path::new(&path).exists() {
+        return Ok(GlyphSubstitutions::default());
+    }
+
+    let text = fs::read_to_string(&path).context("Failed to read substitutions file.")?;
+    toml::from_str(&text).with_context(|| format!("Failed to parse substitutions {}.", path))
+}
+
 fn load_image(file_path: &str) -> Result<DynamicImage> {
     let img = image::open(file_path).context("Failed to open image")?;
     Ok(img)
 }
 
-fn parse_metrics(file_path: &str) -> Result<Vec<GlyphMetric>> {
+fn parse_metrics(file_path: &str) -> Result<FontMetricsV2> {
     let mut char_metrics = Vec::new();
+    let mut kernings = Vec::new();
 
     // File IO plumbing.
     let file = File::open(file_path).context("Failed to open fnt file.")?;
@@ -54,7 +72,26 @@ fn parse_metrics(file_path: &str) -> Result<Vec<GlyphMetric>> {
         let element = event.context("Failed to parse an XML event.")?;
 
         if let XmlEvent::StartElement { name, attributes, .. } = element {
-            // We only care about the char elements.
+            // Kerning attributes follow this order: first, second, amount.
+            if name.to_string() == "kerning" {
+                let mut values = [0; 3];
+
+                for (value, attribute) in values.iter_mut().zip(attributes.iter()) {
+                    *value = attribute
+                        .value
+                        .parse::<i32>()
+                        .context(format!("Failed to parse kerning: <{}>.", attribute))?;
+                }
+
+                kernings.push(GlyphKerning {
+                    first: values[0],
+                    second: values[1],
+                    amount: values[2],
+                });
+                continue;
+            }
+
+            // Otherwise we only care about the char elements.
             if name.to_string() != "char" {
                 continue;
             }
@@ -89,14 +126,28 @@ fn parse_metrics(file_path: &str) -> Result<Vec<GlyphMetric>> {
                 .parse::<i32>()
                 .context(format!("Failed to parse y_offset: <{}>.", attributes[5]))?;
 
-            char_metrics.push(GlyphMetric { codepoint, x, y, width, height, x_offset, y_offset });
+            let x_advance = attributes[7]
+                .value
+                .parse::<i32>()
+                .context(format!("Failed to parse x_advance: <{}>.", attributes[7]))?;
+
+            char_metrics.push(GlyphMetric {
+                codepoint,
+                x,
+                y,
+                width,
+                height,
+                x_offset,
+                y_offset,
+                x_advance,
+            });
         }
     }
 
-    Ok(char_metrics)
+    Ok(FontMetricsV2 { metrics: char_metrics, kernings })
 }
 
-fn generate(name: &str, font_name: &str) -> Result<()> {
+fn generate(name: &str, font_name: &str, substitutions: &GlyphSubstitutions) -> Result<()> {
     // Load default metric and atlas.
     let default_metrics =
         parse_metrics(&format!("{}/{}/{}.fnt", FONTS_DIR, DEFAULT_FONT, font_name))?;
@@ -111,18 +162,31 @@ fn generate(name: &str, font_name: &str) -> Result<()> {
     let mut output_buffer =
         ImageBuffer::<Rgba<u8>, Vec<u8>>::new(OUTPUT_WIDTH as u32, OUTPUT_HEIGHT as u32);
 
+    // Glyphs always copied from the default font are left out of the font's own glyphs.
+    let default_set: HashSet<i32> = substitutions.default.iter().map(|&c| c as i32).collect();
+
     // Vectors for capturing the new metrics lists to serialize.
-    let mut output_metrics = FontMetricsV2 { metrics: Vec::new() };
+    // (kernings only apply to the font's own glyphs)
+    let kernings = metrics
+        .kernings
+        .into_iter()
+        .filter(|k| !default_set.contains(&k.first) && !default_set.contains(&k.second))
+        .collect();
+    let mut output_metrics =
+        FontMetricsV2 { metrics: Vec::new(), kernings, substitutions: Vec::new() };
 
     // Gather a set of the font's codepoints.
     let mut codepoint_set = HashSet::new();
 
     // This codepoint will be skipped when processing the default font later.
-    for metric in metrics.iter() {
-        codepoint_set.insert(metric.codepoint);
+    for metric in metrics.metrics.iter() {
+        if !default_set.contains(&metric.codepoint) {
+            codepoint_set.insert(metric.codepoint);
+        }
     }
 
     // Initialize the rect packer.
+    // NOTE: The foreground shader keeps its samples within the padding (see MAX_SPREAD).
     let config = rect_packer::Config {
         width: OUTPUT_WIDTH,
         height: OUTPUT_HEIGHT,
@@ -132,7 +196,12 @@ fn generate(name: &str, font_name: &str) -> Result<()> {
     let mut packer = Packer::new(config);
 
     // Iterate over all regular metrics, copying the glyphs into the output buffer.
-    for metric in metrics.iter() {
+    for metric in metrics.metrics.iter() {
+        // Skip chars that are always copied from the default font.
+        if !codepoint_set.contains(&metric.codepoint) {
+            continue;
+        }
+
         // Copy the glyph.
         let view = atlas.view(
             metric.x as u32,
@@ -157,12 +226,13 @@ fn generate(name: &str, font_name: &str) -> Result<()> {
             height: metric.height,
             x_offset: metric.x_offset,
             y_offset: metric.y_offset,
+            x_advance: metric.x_advance,
         };
         output_metrics.metrics.push(output_metric);
     }
 
     // Ensure all glyphs are covered by iterating default font.
-    for metric in default_metrics.iter() {
+    for metric in default_metrics.metrics.iter() {
         // Skip chars that where included in the main font.
         if codepoint_set.contains(&metric.codepoint) {
             continue;
@@ -192,8 +262,36 @@ fn generate(name: &str, font_name: &str) -> Result<()> {
             height: metric.height,
             x_offset: metric.x_offset,
             y_offset: metric.y_offset,
+            x_advance: metric.x_advance,
         };
         output_metrics.metrics.push(output_metric);
+
+        // Record the chars copied from the default font on purpose.
+        if default_set.contains(&metric.codepoint) {
+            output_metrics.substitutions.push(GlyphSubstitution {
+                codepoint: metric.codepoint,
+                source: metric.codepoint,
+                default_font: true,
+            });
+        }
+    }
+
+    // Remap glyphs by reusing the packed glyph of their source.
+    for (&codepoint, &source) in substitutions.remap.iter() {
+        let (codepoint, source) = (codepoint as i32, source as i32);
+        let output_metric = *output_metrics
+            .metrics
+            .iter()
+            .find(|metric| metric.codepoint == source)
+            .with_context(|| format!("Failed to find remapped glyph <{}>.", source))?;
+
+        output_metrics.metrics.retain(|metric| metric.codepoint != codepoint);
+        output_metrics.metrics.push(GlyphMetric { codepoint, ..output_metric });
+        output_metrics.substitutions.push(GlyphSubstitution {
+            codepoint,
+            source,
+            default_font: !codepoint_set.contains(&source),
+        });
     }
 
     // Save the atlas and metrics.
@@ -246,14 +344,59 @@ fn generate_all() -> Result<()> {
         }
 
         // Generate the fonts.
+        let substitutions = load_substitutions(name)?;
+
         for font_name in FONT_NAMES.iter() {
-            generate(name, font_name).context("Failed to generate font.")?;
+            generate(name, font_name, &substitutions).context("Failed to generate font.")?;
         }
     }
 
     Ok(())
 }
 
+// Upgrades a legacy metrics file to the V2 layout, saving it beside the input as JSON.
+// The atlas texture is left as is, as both layouts locate glyphs in the same way.
+fn convert_metrics(input_path: &Path) -> Result<PathBuf> {
+    let text = fs::read_to_string(input_path)
+        .with_context(|| format!("Failed to read metrics file {}.", input_path.display()))?;
+    let metrics = FontMetrics::parse(&text)
+        .with_context(|| format!("Failed to parse legacy metrics {}.", input_path.display()))?;
+
+    let output_path = input_path.with_extension("json");
+    let json = serde_json::to_string(&FontMetricsV2::from(metrics))
+        .context("Failed to serialize output metrics.")?;
+    fs::write(&output_path, json).context("Failed to save output metrics.")?;
+
+    Ok(output_path)
+}
+
+// Upgrades a legacy metrics file, or every legacy metrics file in a directory.
+fn convert(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    let mut input_paths = Vec::new();
+
+    if path.is_dir() {
+        for entry in fs::read_dir(path).context("Failed to read metrics directory.")? {
+            let entry_path = entry?.path();
+
+            if entry_path.extension().map_or(false, |extension| extension == "toml") {
+                input_paths.push(entry_path);
+            }
+        }
+
+        input_paths.sort();
+    } else {
+        input_paths.push(path.to_path_buf());
+    }
+
+    for input_path in input_paths.iter() {
+        let output_path = convert_metrics(input_path)?;
+        println!("Converted {} to {}.", input_path.display(), output_path.display());
+    }
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let matches = App::new("FVR_ENGINE-ATLAS")
         .setting(AppSettings::ArgRequiredElseHelp)
@@ -262,12 +405,21 @@ fn main() -> Result<()> {
         .about("CLI tool for generating atlas textures from TTF fonts for glyphs on codepage 437.")
         .subcommand(SubCommand::with_name("run").about("Generate all atlases"))
         .subcommand(SubCommand::with_name("list").about("List atlases to be generated"))
+        .subcommand(
+            SubCommand::with_name("convert").about("Upgrade legacy metrics to the V2 layout").arg(
+                Arg::with_name("PATH")
+                    .help("Legacy metrics file, or directory of legacy metrics files")
+                    .required(true),
+            ),
+        )
         .get_matches();
 
     if matches.subcommand_matches("run").is_some() {
         generate_all()?;
     } else if matches.subcommand_matches("list").is_some() {
         println!("Listing!");
+    } else if let Some(matches) = matches.subcommand_matches("convert") {
+        convert(matches.value_of("PATH").context("Missing metrics path.")?)?;
     }
 
     Ok(())
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use criterion::*;
+use sdl2::video::{GLContext, GLProfile, Window};
+use sdl2::Sdl;
+
+use fvr_engine_client::prelude::*;
+
+// Fonts switched between, so that every set_font replaces the textures.
+const FONT_NAMES: [&str; 2] = ["fantasque_sans_mono", "deja_vu_sans_mono"];
+
+// Holds the SDL2 and OpenGL state the renderer needs (dropped after the renderer).
+struct Context {
+    renderer: RendererV2,
+    _gl_context: GLContext,
+    _window: Window,
+    _sdl2_context: Sdl,
+}
+
+// Creates a renderer drawing into a hidden window.
+fn context() -> Result<Context> {
+    let sdl2_context = sdl2::init().map_err(|e| anyhow!(e))?;
+    let video_subsystem = sdl2_context.video().map_err(|e| anyhow!(e))?;
+
+    let gl_attr = video_subsystem.gl_attr();
+    gl_attr.set_context_profile(GLProfile::Core);
+    gl_attr.set_context_version(REQUIRED_GL_VERSION.0 as u8, REQUIRED_GL_VERSION.1 as u8);
+
+    let window =
+        video_subsystem.window("set_font_benchmark", 640, 480).hidden().opengl().build()?;
+    let gl_context = window.gl_create_context().map_err(|e| anyhow!(e))?;
+    gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as _);
+
+    let profile = RendererProfile::select(&GpuCapabilities::probe())?;
+    let renderer = RendererV2::new(profile)?;
+
+    Ok(Context { renderer, _gl_context: gl_context, _window: window, _sdl2_context: sdl2_context })
+}
+
+// Benchmarks replacing the font textures, after printing the texture binds and bytes uploaded by a
+// single font change (as recorded in the render stats).
+// (skipped when no OpenGL context can be created, e.g. without a display)
+fn benchmark_set_font(c: &mut Criterion) {
+    // The config, fonts and shaders are loaded from the workspace.
+    std::env::set_var(PLATFORM_DIRS_RESOURCES_VAR, concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+
+    let mut context = match context() {
+        Ok(context) => context,
+        Err(e) => {
+            eprintln!("Skipping set_font: {:#}", e);
+            return;
+        }
+    };
+
+    // Finish the frame of the initial font load, so that the next frame only has the font change.
+    context.renderer.render().unwrap();
+    context.renderer.set_font(FONT_NAMES[1]).unwrap();
+    context.renderer.render().unwrap();
+
+    if let Some(stats) = context.renderer.stats().last() {
+        println!(
+            "set_font: {} texture binds, {} bytes uploaded",
+            stats.texture_binds, stats.bytes_uploaded
+        );
+    }
+
+    let mut next = 0;
+
+    c.bench_function("set_font", |b| {
+        b.iter(|| {
+            context.renderer.set_font(FONT_NAMES[next]).unwrap();
+            next = (next + 1) % FONT_NAMES.len();
+        })
+    });
+}
+
+criterion_group!(benches, benchmark_set_font);
+criterion_main!(benches);
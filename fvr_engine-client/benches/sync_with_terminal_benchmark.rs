@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Result};
+use criterion::*;
+use sdl2::video::{GLContext, GLProfile, Window};
+use sdl2::Sdl;
+
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+
+// Region of the map layer, matching the scratch scene's map view.
+const MAP_LAYER_REGION: (i32, i32) = (55, 32);
+
+// Holds the SDL2 and OpenGL state the renderer needs (dropped after the renderer).
+struct Context {
+    renderer: RendererV2,
+    _gl_context: GLContext,
+    _window: Window,
+    _sdl2_context: Sdl,
+}
+
+// Creates a renderer drawing into a hidden window.
+fn context() -> Result<Context> {
+    let sdl2_context = sdl2::init().map_err(|e| anyhow!(e))?;
+    let video_subsystem = sdl2_context.video().map_err(|e| anyhow!(e))?;
+
+    let gl_attr = video_subsystem.gl_attr();
+    gl_attr.set_context_profile(GLProfile::Core);
+    gl_attr.set_context_version(REQUIRED_GL_VERSION.0 as u8, REQUIRED_GL_VERSION.1 as u8);
+
+    let window = video_subsystem
+        .window("sync_with_terminal_benchmark", 640, 480)
+        .hidden()
+        .opengl()
+        .build()?;
+    let gl_context = window.gl_create_context().map_err(|e| anyhow!(e))?;
+    gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as _);
+
+    let profile = RendererProfile::select(&GpuCapabilities::probe())?;
+    let renderer = RendererV2::new(profile)?;
+
+    Ok(Context { renderer, _gl_context: gl_context, _window: window, _sdl2_context: sdl2_context })
+}
+
+// Creates a randomized terminal of the default size with a map layer.
+fn terminal() -> Terminal {
+    let mut terminal = Terminal::default();
+    terminal.randomize();
+
+    let map_layer = TileLayer::new(Rect::new((0, 0), MAP_LAYER_REGION.0, MAP_LAYER_REGION.1));
+    terminal.set_map_layer(Some(map_layer));
+
+    terminal
+}
+
+// Iterates the tiles of the terminal and its map layer.
+fn tiles(terminal: &Terminal) -> impl Iterator<Item = &Tile> {
+    terminal.coords_and_tiles_iter().map(|(_, tile)| tile).chain(
+        terminal
+            .map_layer()
+            .into_iter()
+            .flat_map(|layer| layer.coords_and_tiles_iter().map(|(_, tile)| tile)),
+    )
+}
+
+// Benchmarks detecting whether the terminal changed, by revision and by comparing its tiles with
+// a copy from the last sync.
+fn benchmark_change_detection(c: &mut Criterion) {
+    let terminal = terminal();
+    let synced_revision = Some(terminal.revision());
+    let synced: Vec<Tile> = tiles(&terminal).copied().collect();
+
+    let mut group = c.benchmark_group("sync_change_detection");
+
+    group.bench_function("revision", |b| {
+        b.iter(|| black_box(Some(terminal.revision())) == synced_revision)
+    });
+
+    group.bench_function("compare_tiles", |b| {
+        b.iter(|| black_box(tiles(&terminal).eq(synced.iter())))
+    });
+
+    group.finish();
+}
+
+// Benchmarks syncing the renderer with an unchanged and a changed terminal.
+// (skipped when no OpenGL context can be created, e.g. without a display)
+fn benchmark_sync_with_terminal(c: &mut Criterion) {
+    // The config, fonts and shaders are loaded from the workspace.
+    std::env::set_var(PLATFORM_DIRS_RESOURCES_VAR, concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+
+    let mut context = match context() {
+        Ok(context) => context,
+        Err(e) => {
+            eprintln!("Skipping sync_with_terminal: {:#}", e);
+            return;
+        }
+    };
+    let mut terminal = terminal();
+
+    let mut group = c.benchmark_group("sync_with_terminal");
+
+    // Every sync after the first few is skipped.
+    group.bench_function("unchanged", |b| {
+        b.iter(|| context.renderer.sync_with_terminal(&terminal).unwrap())
+    });
+
+    // Every sync rebuilds and uploads the vertices.
+    group.bench_function("one_tile_changed", |b| {
+        b.iter(|| {
+            let tile = terminal.get_xy_mut((0, 0));
+            tile.glyph = if tile.glyph == 'a' { 'b' } else { 'a' };
+            context.renderer.sync_with_terminal(&terminal).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_change_detection, benchmark_sync_with_terminal);
+criterion_main!(benches);
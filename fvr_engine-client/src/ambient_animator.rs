@@ -0,0 +1,137 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Max phase offset of a cell, so that neighboring cells do not animate in lockstep.
+const MAX_PHASE_OFFSET_MS: u64 = 1000;
+
+//-------------------------------------------------------------------------------------------------
+// Helper struct for holding an animated cell and its unanimated glyph and color.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+struct AnimatedCell {
+    // Coord of the cell in the animated map.
+    xy: ICoord,
+    // The cell's animation.
+    animation: &'static AmbientAnimation,
+    // Offset of the cell's animation.
+    phase: Duration,
+    // Glyph of the cell when tracked.
+    glyph: char,
+    // Foreground color of the cell when tracked.
+    color: TileColor,
+}
+
+//-------------------------------------------------------------------------------------------------
+// AmbientAnimator animates the idle cells of a map (e.g. flickering torches) each frame. The
+// server only stores the animation ids of things, so the cells to animate are tracked again
+// whenever the map is redrawn, and only their glyphs and foreground colors are touched.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct AmbientAnimator {
+    // Time elapsed animating.
+    elapsed: Duration,
+    // The animated cells.
+    cells: Vec<AnimatedCell>,
+}
+
+impl AmbientAnimator {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new animator with no animated cells.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops animating all cells (e.g. before the map is redrawn).
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Starts animating a cell of a map from its current tile. Unknown animation ids are ignored.
+    // The world coord of the cell seeds its phase, so that it does not change as the map scrolls.
+    //---------------------------------------------------------------------------------------------
+    pub fn track<M>(&mut self, map: &M, xy: ICoord, world_xy: ICoord, id: AmbientAnimationId)
+    where
+        M: Map2d<Tile>,
+    {
+        let animation = match AmbientAnimation::get(id) {
+            Some(animation) if map.in_bounds(xy) => animation,
+            _ => return,
+        };
+
+        let hash = (world_xy.0 as u64).wrapping_mul(73_856_093)
+            ^ (world_xy.1 as u64).wrapping_mul(19_349_663);
+        let phase = Duration::from_millis(hash % MAX_PHASE_OFFSET_MS);
+        let tile = map.get_xy(xy);
+
+        self.cells.push(AnimatedCell {
+            xy,
+            animation,
+            phase,
+            glyph: tile.glyph,
+            color: tile.foreground_color,
+        });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of animated cells.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no cells are animated.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Advances the animations by a frame delta time (scaled and paused per the accessibility
+    // options) and updates the animated cells of a map. Returns whether any cell changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, dt: Duration, map: &mut M) -> bool
+    where
+        M: Map2d<Tile>,
+    {
+        if self.cells.is_empty() || !Accessibility::motion_enabled() {
+            return false;
+        }
+
+        self.elapsed += Accessibility::animation_dt(dt);
+        let mut changed = false;
+
+        for cell in self.cells.iter() {
+            if !map.in_bounds(cell.xy) {
+                continue;
+            }
+
+            let (glyph, color) =
+                cell.animation.sample(self.elapsed + cell.phase, cell.glyph, cell.color);
+            let tile = map.get_xy_mut(cell.xy);
+
+            if tile.glyph != glyph || tile.foreground_color != color {
+                tile.glyph = glyph;
+                tile.foreground_color = color;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
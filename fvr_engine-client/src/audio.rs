@@ -0,0 +1,471 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::path::Path;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use fnv::FnvHashMap;
+use sdl2::mixer::{self, Channel, Chunk, InitFlag, Sdl2MixerContext, MAX_VOLUME};
+use sdl2::{AudioSubsystem, Sdl};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Output format of the mixer.
+const AUDIO_FREQUENCY: i32 = 44_100;
+const AUDIO_CHUNK_SIZE: i32 = 1024;
+
+// # of mixer channels. The first two are reserved for crossfading music.
+const MIXER_CHANNELS: i32 = 32;
+const MUSIC_CHANNELS: [Channel; 2] = [Channel(0), Channel(1)];
+
+// Distance (in terminal cells) from the listener beyond which positional sounds are inaudible.
+const AUDIBLE_DISTANCE: f32 = 24.0;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the channels sound effects are played on, each with its own volume.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SoundChannel {
+    // Sounds of the game world (e.g. footsteps and combat).
+    World,
+    // Background loops of the game world (e.g. wind and rain).
+    Ambient,
+    // Sounds of the interface (e.g. menu clicks).
+    Interface,
+}
+
+impl SoundChannel {
+    // All of the channels.
+    pub const ALL: [SoundChannel; 3] =
+        [SoundChannel::World, SoundChannel::Ambient, SoundChannel::Interface];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the channel in ALL.
+    //---------------------------------------------------------------------------------------------
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A sound effect playing on a mixer channel.
+//-------------------------------------------------------------------------------------------------
+struct PlayingSound {
+    // The mixer channel the sound plays on.
+    mixer_channel: Channel,
+    // The channel the sound was played on.
+    channel: SoundChannel,
+    // Gain of the sound itself, before the channel and settings volumes.
+    gain: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A music track playing (or fading) on one of the music channels.
+//-------------------------------------------------------------------------------------------------
+struct MusicTrack {
+    // Name of the track's file.
+    name: String,
+    // The mixer channel the track plays on.
+    mixer_channel: Channel,
+    // Current level of the fade, in the range [0.0, 1.0].
+    level: f32,
+    // Level the track is fading towards (0.0 to fade out and stop).
+    target: f32,
+    // Change in level per second.
+    rate: f32,
+}
+
+impl MusicTrack {
+    //---------------------------------------------------------------------------------------------
+    // Starts fading the track towards a level over a duration.
+    //---------------------------------------------------------------------------------------------
+    fn fade_to(&mut self, target: f32, duration: Duration) {
+        self.target = target;
+        self.rate = if duration.as_secs_f32() > 0.0 {
+            (target - self.level).abs() / duration.as_secs_f32()
+        } else {
+            f32::INFINITY
+        };
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Advances the fade. Returns whether the track faded out completely.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, dt: Duration) -> bool {
+        let step = self.rate * dt.as_secs_f32();
+
+        self.level = if self.level < self.target {
+            (self.level + step).min(self.target)
+        } else {
+            (self.level - step).max(self.target)
+        };
+
+        self.target == 0.0 && self.level == 0.0
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Audio plays sound effects and music with SDL2 mixer. Sounds are loaded from the audio dir (of
+// the topmost content layer providing them) on first use and cached.
+// Sound effects can be positioned relative to a listener (usually the player) by terminal coords,
+// and are panned and attenuated accordingly. Music crossfades between tracks.
+//-------------------------------------------------------------------------------------------------
+pub struct Audio {
+    // The SDL2 audio subsystem (not used after initialization, but it must stay in scope).
+    _audio_subsystem: AudioSubsystem,
+    // The SDL2 mixer context (not used after initialization, but it must stay in scope).
+    _mixer_context: Sdl2MixerContext,
+    // Loaded sounds by file name, relative to the audio dir.
+    sounds: FnvHashMap<String, Chunk>,
+    // Sound effects that may still be playing.
+    playing: Vec<PlayingSound>,
+    // The music tracks playing, the current track last.
+    music: Vec<MusicTrack>,
+    // Volume of each sound channel, indexed by SoundChannel::index.
+    channel_volumes: [f32; SoundChannel::ALL.len()],
+    // Terminal coord positional sounds are heard from.
+    listener: ICoord,
+    // Picks up changes to the volume settings.
+    settings_watcher: SettingsWatcher,
+    // Volume settings last applied.
+    settings: AudioSettings,
+    // Whether every sound is silenced (e.g. while the window does not have focus).
+    muted: bool,
+}
+
+impl Audio {
+    //---------------------------------------------------------------------------------------------
+    // Opens the audio device.
+    // (there should only ever be one)
+    //---------------------------------------------------------------------------------------------
+    pub fn new(sdl2_context: &Sdl) -> Result<Self> {
+        let _audio_subsystem = sdl2_context
+            .audio()
+            .map_err(|e| anyhow!(e))
+            .context("Failed to initialize SDL2 audio subsystem.")?;
+
+        mixer::open_audio(
+            AUDIO_FREQUENCY,
+            mixer::DEFAULT_FORMAT,
+            mixer::DEFAULT_CHANNELS,
+            AUDIO_CHUNK_SIZE,
+        )
+        .map_err(|e| anyhow!(e))
+        .context("Failed to open the audio device.")?;
+
+        let _mixer_context = mixer::init(InitFlag::OGG)
+            .map_err(|e| anyhow!(e))
+            .context("Failed to initialize SDL2 mixer.")?;
+
+        // Keep the music channels out of the channels picked for sound effects.
+        mixer::allocate_channels(MIXER_CHANNELS);
+        mixer::reserve_channels(MUSIC_CHANNELS.len() as i32);
+
+        Ok(Self {
+            _audio_subsystem,
+            _mixer_context,
+            sounds: FnvHashMap::default(),
+            playing: Vec::new(),
+            music: Vec::new(),
+            channel_volumes: [1.0; SoundChannel::ALL.len()],
+            listener: (0, 0),
+            settings_watcher: SettingsWatcher::new(),
+            settings: Settings::current().audio,
+            muted: false,
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that converts a gain into a mixer volume.
+    //---------------------------------------------------------------------------------------------
+    fn volume(gain: f32) -> i32 {
+        (gain.clamp(0.0, 1.0) * MAX_VOLUME as f32).round() as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads a sound into the cache (e.g. ahead of a scene using it), if it is not already.
+    //---------------------------------------------------------------------------------------------
+    pub fn preload(&mut self, name: &str) -> Result<()> {
+        if self.sounds.contains_key(name) {
+            return Ok(());
+        }
+
+        let path = CONTENT_LAYERS.resolve(Path::new(CONFIG_AUDIO_DIR).join(name));
+        let chunk = Chunk::from_file(&path)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("Failed to load sound {}.", path.display()))?;
+        let _ = self.sounds.insert(name.to_string(), chunk);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes every sound from the cache, stopping all sound effects and music.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear_cache(&mut self) {
+        Channel::all().halt();
+        self.playing.clear();
+        self.music.clear();
+        self.sounds.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether every sound is silenced.
+    //---------------------------------------------------------------------------------------------
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Silences or restores every sound. Sounds keep playing (silently) while muted.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.apply_volumes();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the gain of every sound effect from the settings.
+    //---------------------------------------------------------------------------------------------
+    fn effects_gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.settings.effects_gain()
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the gain of the music from the settings.
+    //---------------------------------------------------------------------------------------------
+    fn music_gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.settings.music_gain()
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the volume of a sound channel.
+    //---------------------------------------------------------------------------------------------
+    pub fn channel_volume(&self, channel: SoundChannel) -> f32 {
+        self.channel_volumes[channel.index()]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the volume of a sound channel, in the range [0.0, 1.0]. Applies to the sounds already
+    // playing on the channel.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_channel_volume(&mut self, channel: SoundChannel, volume: f32) {
+        self.channel_volumes[channel.index()] = volume.clamp(0.0, 1.0);
+        self.apply_volumes();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the terminal coord positional sounds are heard from.
+    //---------------------------------------------------------------------------------------------
+    pub fn listener(&self) -> ICoord {
+        self.listener
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the terminal coord positional sounds are heard from (e.g. the player's coord).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_listener(&mut self, xy: ICoord) {
+        self.listener = xy;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Plays a sound effect on a channel at a gain in the range [0.0, 1.0], loading it first if
+    // necessary. Sounds that cannot be played (e.g. when all mixer channels are busy) are skipped.
+    //---------------------------------------------------------------------------------------------
+    pub fn play_sfx(&mut self, name: &str, channel: SoundChannel, gain: f32) -> Result<()> {
+        if let Some(mixer_channel) = self.play_chunk(name, channel, gain)? {
+            // Clear any position left over from a previous sound on the mixer channel.
+            let _ = mixer_channel.unset_position();
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Plays a sound effect at a terminal coord, panned and attenuated by its direction and
+    // distance from the listener. Sounds beyond the audible distance are skipped.
+    //---------------------------------------------------------------------------------------------
+    pub fn play_sfx_at(
+        &mut self,
+        name: &str,
+        channel: SoundChannel,
+        gain: f32,
+        xy: ICoord,
+    ) -> Result<()> {
+        let (dx, dy) = ((xy.0 - self.listener.0) as f32, (xy.1 - self.listener.1) as f32);
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance > AUDIBLE_DISTANCE {
+            return Ok(());
+        }
+
+        // The mixer's angles go clockwise from directly in front, which is up the terminal.
+        let angle = (dx.atan2(-dy).to_degrees().round() as i16).rem_euclid(360);
+        let attenuation = (distance / AUDIBLE_DISTANCE * u8::MAX as f32) as u8;
+
+        if let Some(mixer_channel) = self.play_chunk(name, channel, gain)? {
+            mixer_channel.set_position(angle, attenuation).map_err(|e| anyhow!(e))?;
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that plays a sound on a free mixer channel, returning the mixer channel (or
+    // none if every mixer channel is busy).
+    //---------------------------------------------------------------------------------------------
+    fn play_chunk(
+        &mut self,
+        name: &str,
+        channel: SoundChannel,
+        gain: f32,
+    ) -> Result<Option<Channel>> {
+        self.preload(name)?;
+
+        let chunk = &self.sounds[name];
+        let mixer_channel = match Channel::all().play(chunk, 0) {
+            Ok(mixer_channel) => mixer_channel,
+            Err(_) => return Ok(None),
+        };
+
+        let gain = gain.clamp(0.0, 1.0);
+        let volume = gain * self.channel_volumes[channel.index()] * self.effects_gain();
+        mixer_channel.set_volume(Self::volume(volume));
+
+        self.playing.retain(|sound| sound.mixer_channel != mixer_channel);
+        self.playing.push(PlayingSound { mixer_channel, channel, gain });
+
+        Ok(Some(mixer_channel))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the music track playing (or fading in), if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn current_music(&self) -> Option<&str> {
+        self.music.last().filter(|track| track.target > 0.0).map(|track| track.name.as_str())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Plays a music track on loop, crossfading from the current track over a duration.
+    // Does nothing if the track is already playing.
+    //---------------------------------------------------------------------------------------------
+    pub fn play_music(&mut self, name: &str, crossfade: Duration) -> Result<()> {
+        if self.current_music() == Some(name) {
+            return Ok(());
+        }
+
+        self.preload(name)?;
+
+        // Only two tracks can play at once, so drop a track still fading out from before.
+        if self.music.len() == MUSIC_CHANNELS.len() {
+            let oldest = self.music.remove(0);
+            oldest.mixer_channel.halt();
+        }
+
+        for track in self.music.iter_mut() {
+            track.fade_to(0.0, crossfade);
+        }
+
+        let mixer_channel = MUSIC_CHANNELS
+            .iter()
+            .copied()
+            .find(|channel| self.music.iter().all(|track| track.mixer_channel != *channel))
+            .expect("Unreachable.");
+
+        mixer_channel.set_volume(0);
+        mixer_channel.play(&self.sounds[name], -1).map_err(|e| anyhow!(e))?;
+
+        let mut track = MusicTrack {
+            name: name.to_string(),
+            mixer_channel,
+            level: 0.0,
+            target: 0.0,
+            rate: 0.0,
+        };
+        track.fade_to(1.0, crossfade);
+        self.music.push(track);
+        self.apply_volumes();
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fades out the music over a duration.
+    //---------------------------------------------------------------------------------------------
+    pub fn stop_music(&mut self, fade: Duration) {
+        for track in self.music.iter_mut() {
+            track.fade_to(0.0, fade);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Advances the music fades, forgets finished sound effects and applies changed volume
+    // settings. (should be called once per game loop)
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) {
+        if self.settings_watcher.changed() {
+            self.settings = Settings::current().audio;
+        }
+
+        self.playing.retain(|sound| sound.mixer_channel.is_playing());
+
+        self.music.retain_mut(|track| {
+            let finished = track.update(dt);
+
+            if finished {
+                track.mixer_channel.halt();
+            }
+
+            !finished
+        });
+
+        self.apply_volumes();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that sets the volumes of the playing sounds and music.
+    //---------------------------------------------------------------------------------------------
+    fn apply_volumes(&self) {
+        let effects_gain = self.effects_gain();
+        let music_gain = self.music_gain();
+
+        for sound in self.playing.iter() {
+            let volume = sound.gain * self.channel_volumes[sound.channel.index()] * effects_gain;
+            sound.mixer_channel.set_volume(Self::volume(volume));
+        }
+
+        for track in self.music.iter() {
+            track.mixer_channel.set_volume(Self::volume(track.level * music_gain));
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Stop every sound and close the audio device on drop.
+//-------------------------------------------------------------------------------------------------
+impl Drop for Audio {
+    fn drop(&mut self) {
+        Channel::all().halt();
+        self.sounds.clear();
+        mixer::close_audio();
+    }
+}
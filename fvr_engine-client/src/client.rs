@@ -1,16 +1,19 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
+use std::fs;
+use std::path::PathBuf;
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use anyhow::{anyhow, Context, Result};
+use sdl2::controller::GameController;
 use sdl2::event::Event;
-use sdl2::video::{GLContext, GLProfile, SwapInterval, Window};
-use sdl2::{EventPump, Sdl, VideoSubsystem};
+use sdl2::video::{FullscreenType, GLContext, GLProfile, SwapInterval, Window};
+use sdl2::{EventPump, GameControllerSubsystem, Sdl, VideoSubsystem};
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
@@ -20,23 +23,41 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::audio::*;
 use crate::debug_gui::*;
+use crate::engine_event::*;
+use crate::gl_helpers::*;
+use crate::gpu_profile::*;
 use crate::input_manager::*;
+use crate::render_graph::*;
 use crate::renderer_v2::*;
 use crate::terminal::*;
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Interval to render frames at when the render interval is none (caps at 1000 fps).
+const UNCAPPED_RENDER_INTERVAL: Duration = Duration::from_millis(1);
+
 //-------------------------------------------------------------------------------------------------
 // Client holds the window and rendering context and provides access to the terminal.
 //-------------------------------------------------------------------------------------------------
 pub struct Client {
-    // The SDL2 context (not used after initialization, but it must stay in scope).
-    _sdl2_context: Sdl,
-    // The SDL2 video context (not used after initialization, but it must stay in scope).
-    _video_subsystem: VideoSubsystem,
+    // The SDL2 context.
+    sdl2_context: Sdl,
+    // The SDL2 video context.
+    video_subsystem: VideoSubsystem,
     // The SDL2 window's event pump for handling user input events.
     event_pump: EventPump,
     // The SDL2 window.
     window: Window,
+    // Plays sound effects and music (none if the audio device could not be opened).
+    audio: Option<Audio>,
+    // The SDL2 game controller context (none if it could not be initialized).
+    controller_subsystem: Option<GameControllerSubsystem>,
+    // The connected controllers (they only send events while open).
+    controllers: Vec<GameController>,
     // The OpenGL context (not used after initialization, but it must stay in scope).
     _gl_context: GLContext,
     // The debug gui manages the ImGUI debug gui.
@@ -45,6 +66,10 @@ pub struct Client {
     renderer: RendererV2,
     // Whether to display the debug gui.
     debug_enabled: bool,
+    // Whether to save a screenshot of the next rendered frame.
+    screenshot_requested: bool,
+    // Whether the window has keyboard focus.
+    focused: bool,
     // Time that the last frame began. Used to calculate frame delta time.
     last_frame: Instant,
     // Delta time for the current frame.
@@ -57,6 +82,14 @@ pub struct Client {
     fps_counter: i32,
     // Whether the window has been resized this frame.
     resized: bool,
+    // Whether the window has been resized since the terminal was last fit to it.
+    terminal_resize_pending: bool,
+    // Whether the hardware cursor is shown (it is hidden while the software cursor is enabled).
+    hardware_cursor_shown: bool,
+    // Picks up settings applied while the game is running.
+    settings_watcher: SettingsWatcher,
+    // Settings last applied to the client.
+    settings: Settings,
 }
 
 impl Client {
@@ -79,10 +112,13 @@ impl Client {
         {
             let gl_attr = video_subsystem.gl_attr();
             gl_attr.set_context_profile(GLProfile::Core);
-            gl_attr.set_context_version(3, 3);
+            gl_attr.set_context_version(REQUIRED_GL_VERSION.0 as u8, REQUIRED_GL_VERSION.1 as u8);
 
             debug_assert_eq!(gl_attr.context_profile(), GLProfile::Core);
-            debug_assert_eq!(gl_attr.context_version(), (3, 3));
+            debug_assert_eq!(
+                gl_attr.context_version(),
+                (REQUIRED_GL_VERSION.0 as u8, REQUIRED_GL_VERSION.1 as u8)
+            );
         }
 
         // Initialize SDL2 objects.
@@ -146,18 +182,46 @@ impl Client {
         //-----------------------------------------------------------------------------------------
 
         // Query and load the OpenGL context.
-        let _gl_context = window
-            .gl_create_context()
-            .map_err(|e| anyhow!(e))
-            .context("Failed to create the OpenGL context.")?;
+        let _gl_context =
+            window.gl_create_context().map_err(|e| anyhow!(e)).with_context(|| {
+                format!(
+                    "Failed to create the OpenGL context (OpenGL {}.{} core is required).",
+                    REQUIRED_GL_VERSION.0, REQUIRED_GL_VERSION.1
+                )
+            })?;
         gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as _);
 
-        // Set the OpenGL swap interval to immediate.
-        // TODO: Handle vsync.
-        video_subsystem
-            .gl_set_swap_interval(SwapInterval::Immediate)
-            .map_err(|e| anyhow!(e))
-            .context("Failed to set OpenGL swap interval.")?;
+        // Probe the GPU and select the renderer features it can handle.
+        let capabilities = GpuCapabilities::probe();
+        let profile = RendererProfile::select(&capabilities)
+            .with_context(|| format!("Unsupported GPU: {}.", capabilities))?;
+        profile.log(&capabilities);
+
+        // Set the OpenGL swap interval.
+        let settings = Settings::current();
+        Self::set_swap_interval(&video_subsystem, settings.vsync)?;
+
+        // Initialize audio.
+        // (the game is playable without sound, so failing to open the audio device is not fatal)
+        //-----------------------------------------------------------------------------------------
+        let audio = match Audio::new(&sdl2_context) {
+            Ok(audio) => Some(audio),
+            Err(e) => {
+                eprintln!("[Client] Audio disabled: {:#}", e);
+                None
+            }
+        };
+
+        // Initialize controllers.
+        // (controllers connected before this are announced by events, so none are opened here)
+        //-----------------------------------------------------------------------------------------
+        let controller_subsystem = match sdl2_context.game_controller() {
+            Ok(controller_subsystem) => Some(controller_subsystem),
+            Err(e) => {
+                eprintln!("[Client] Controllers disabled: {}", e);
+                None
+            }
+        };
 
         // Initialize the debug gui.
         //-----------------------------------------------------------------------------------------
@@ -165,54 +229,192 @@ impl Client {
 
         // Initialize the renderer.
         //-----------------------------------------------------------------------------------------
-        let renderer = RendererV2::new().context("Failed to create the renderer.")?;
+        let mut renderer = RendererV2::new(profile).context("Failed to create the renderer.")?;
+        renderer.set_vignette_enabled(settings.enable_vignette)?;
 
         // If the render interval is none, cap at 1000 fps.
-        let render_interval = CONFIG.render_interval.unwrap_or_else(|| Duration::from_millis(1));
+        let render_interval = CONFIG.render_interval.unwrap_or(UNCAPPED_RENDER_INTERVAL);
 
         // ...and that's it!
         //-----------------------------------------------------------------------------------------
         Ok(Self {
-            _sdl2_context: sdl2_context,
-            _video_subsystem: video_subsystem,
+            sdl2_context,
+            video_subsystem,
             event_pump,
             window,
+            audio,
+            controller_subsystem,
+            controllers: Vec::new(),
             _gl_context,
             debug_gui,
             renderer,
             debug_enabled: false,
+            screenshot_requested: false,
+            focused: true,
             last_frame: Instant::now(),
             delta_time: Duration::from_secs(0),
             render_timer: Timer::new(render_interval),
             fps_log_timer: Timer::new(CONFIG_FPS_LOG_INTERVAL),
             fps_counter: 0,
             resized: true,
+            terminal_resize_pending: true,
+            hardware_cursor_shown: true,
+            settings_watcher: SettingsWatcher::new(),
+            settings,
         })
     }
 
     //---------------------------------------------------------------------------------------------
-    // Polls a user input event from the event pump.
-    // (or returns none if the event pump is empty)
+    // Helper function that sets the OpenGL swap interval to wait for vertical sync or not.
+    //---------------------------------------------------------------------------------------------
+    fn set_swap_interval(video_subsystem: &VideoSubsystem, vsync: bool) -> Result<()> {
+        let interval = if vsync { SwapInterval::VSync } else { SwapInterval::Immediate };
+
+        video_subsystem
+            .gl_set_swap_interval(interval)
+            .map_err(|e| anyhow!(e))
+            .context("Failed to set OpenGL swap interval.")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies the settings that have changed since they were last synced (e.g. by the options
+    // menu) to the window, renderer and input. A font that fails to load is reverted.
+    // (should be called once per game loop)
     //---------------------------------------------------------------------------------------------
-    pub fn poll_event(&mut self) -> Option<Event> {
-        let event_option = self.event_pump.poll_event();
+    pub fn sync_settings(&mut self, input: &mut InputManager) -> Result<()> {
+        if !self.settings_watcher.changed() {
+            return Ok(());
+        }
+
+        let mut settings = Settings::current();
 
-        // If an event is present, check for resized and also pass to debug gui.
-        if let Some(event) = event_option {
-            if let Event::Window { .. } = event {
-                self.resized = true;
+        if settings.vsync != self.settings.vsync {
+            Self::set_swap_interval(&self.video_subsystem, settings.vsync)?;
+        }
+
+        if settings.font_name != self.settings.font_name {
+            if let Err(e) = self.renderer.set_font(&settings.font_name) {
+                eprintln!("[Client] {:#}", e);
+
+                // Keep the settings in line with the font actually in use.
+                settings.font_name = self.settings.font_name.clone();
+                Settings::apply(settings.clone());
+                let _ = self.settings_watcher.changed();
             }
+        }
+
+        if settings.enable_vignette != self.settings.enable_vignette {
+            self.renderer.set_vignette_enabled(settings.enable_vignette)?;
+        }
+
+        input.software_cursor_mut().set_enabled(settings.software_cursor);
+
+        if settings.show_fps != self.settings.show_fps {
+            self.fps_log_timer.reset();
+            self.fps_counter = 0;
+        }
 
-            if self.debug_enabled {
+        self.settings = settings;
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Polls an engine event, skipping the events of the event pump the engine does not handle.
+    // (or returns none if the event pump is empty)
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_event(&mut self) -> Option<EngineEvent> {
+        while let Some(event) = self.event_pump.poll_event() {
+            // Pass every event to the debug gui.
+            if self.debug_gui_shown() {
                 self.debug_gui.handle_event(&event);
             }
 
-            Some(event)
-        } else {
-            None
+            let engine_event = match event {
+                Event::ControllerDeviceAdded { which, .. } => self.open_controller(which),
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    self.controllers.retain(|controller| controller.instance_id() != which);
+                    EngineEvent::from_sdl(&event)
+                }
+                _ => EngineEvent::from_sdl(&event),
+            };
+
+            match engine_event {
+                // Update the viewport (and fit the terminal) after the window is resized.
+                Some(EngineEvent::WindowResized(_)) => {
+                    self.resized = true;
+                    self.terminal_resize_pending = true;
+                }
+                Some(EngineEvent::FocusGained) => self.set_focused(true),
+                Some(EngineEvent::FocusLost) => self.set_focused(false),
+                _ => {}
+            }
+
+            if engine_event.is_some() {
+                return engine_event;
+            }
+        }
+
+        None
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that opens a newly connected controller, returning its connected event.
+    // (a controller that fails to open is logged and ignored)
+    //---------------------------------------------------------------------------------------------
+    fn open_controller(&mut self, index: u32) -> Option<EngineEvent> {
+        let controller = match self.controller_subsystem.as_ref()?.open(index) {
+            Ok(controller) => controller,
+            Err(e) => {
+                eprintln!("[Client] Failed to open controller {}: {}", index, e);
+                return None;
+            }
+        };
+
+        let id = controller.instance_id();
+        println!("[Client] Connected controller {} ({}).", id, controller.name());
+        self.controllers.push(controller);
+
+        Some(EngineEvent::ControllerConnected(id))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that throttles rendering and mutes audio (as configured) while the window
+    // does not have focus, restoring both when it regains focus.
+    //---------------------------------------------------------------------------------------------
+    fn set_focused(&mut self, focused: bool) {
+        if focused == self.focused {
+            return;
+        }
+
+        self.focused = focused;
+
+        let render_interval =
+            if focused { CONFIG.render_interval } else { CONFIG.background.render_interval };
+
+        // Not rendering at all is handled in render_frame.
+        self.render_timer.interval = render_interval.unwrap_or(UNCAPPED_RENDER_INTERVAL);
+        self.render_timer.reset();
+
+        if let (true, Some(audio)) = (CONFIG.background.mute_audio, self.audio.as_mut()) {
+            audio.set_muted(!focused);
         }
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the window has keyboard focus.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the audio player, if the audio device could be opened.
+    //---------------------------------------------------------------------------------------------
+    pub fn audio_mut(&mut self) -> Option<&mut Audio> {
+        self.audio.as_mut()
+    }
+
     //---------------------------------------------------------------------------------------------
     // Toggles the debug gui.
     //---------------------------------------------------------------------------------------------
@@ -220,23 +422,200 @@ impl Client {
         self.debug_enabled = !self.debug_enabled;
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Shows or hides the debug console, returning whether it is now shown.
+    //---------------------------------------------------------------------------------------------
+    pub fn toggle_console(&mut self) -> bool {
+        self.debug_gui.toggle_console()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the commands entered into the debug console since they were last taken.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_console_commands(&mut self) -> Vec<String> {
+        self.debug_gui.take_console_commands()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Appends a line to the debug console log.
+    //---------------------------------------------------------------------------------------------
+    pub fn console_print(&mut self, line: String) {
+        self.debug_gui.console_print(line);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns whether any part of the debug gui is shown (and so takes the
+    // user's input).
+    //---------------------------------------------------------------------------------------------
+    fn debug_gui_shown(&self) -> bool {
+        self.debug_enabled || self.debug_gui.console_open()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Switches the window between windowed and desktop fullscreen, returning whether it is now
+    // fullscreen.
+    //---------------------------------------------------------------------------------------------
+    pub fn toggle_fullscreen(&mut self) -> Result<bool> {
+        let fullscreen = self.window.fullscreen_state() == FullscreenType::Off;
+        let fullscreen_type =
+            if fullscreen { FullscreenType::Desktop } else { FullscreenType::Off };
+
+        self.window
+            .set_fullscreen(fullscreen_type)
+            .map_err(|e| anyhow!(e))
+            .context("Failed to toggle fullscreen.")?;
+
+        self.resized = true;
+        self.terminal_resize_pending = true;
+
+        Ok(fullscreen)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Requests a screenshot of the next rendered frame (without the debug gui).
+    //---------------------------------------------------------------------------------------------
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that saves the rendered frame as a png in the screenshots directory,
+    // returning the path it was saved to.
+    //---------------------------------------------------------------------------------------------
+    fn save_screenshot(&self) -> Result<PathBuf> {
+        let dir = PLATFORM_DIRS.save_dir().join(CONFIG_SCREENSHOTS_DIR);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create screenshots dir {}.", dir.display()))?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let path = dir.join(format!("screenshot_{}.png", timestamp.as_millis()));
+
+        read_frame_rgba(self.window.drawable_size())?
+            .save(&path)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("Failed to save screenshot {}.", path.display()))?;
+
+        Ok(path)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reloads the shaders from disk (e.g. after editing them while the game is running).
+    // (a shader that fails to build keeps the previous shaders in use)
+    //---------------------------------------------------------------------------------------------
+    pub fn reload_shaders(&mut self) -> Result<()> {
+        self.renderer.reload_shaders().context("Failed to reload shaders.")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the render passes drawn each frame for inserting, removing or toggling passes.
+    //---------------------------------------------------------------------------------------------
+    pub fn render_graph_mut(&mut self) -> &mut RenderGraph {
+        self.renderer.render_graph_mut()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Toggles the overdraw debug view, returning whether it is now shown.
+    //---------------------------------------------------------------------------------------------
+    pub fn toggle_overdraw_view(&mut self) -> Result<bool> {
+        let graph = self.renderer.render_graph_mut();
+
+        if graph.get(RenderPassKind::Overdraw).is_some() {
+            graph.remove(RenderPassKind::Overdraw)?;
+            Ok(false)
+        } else {
+            graph.insert(RenderPassKind::Overdraw.default_pass())?;
+            Ok(true)
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the glyph rendered in place of glyphs missing from the font.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_fallback_glyph(&mut self, glyph: char) {
+        self.renderer.set_fallback_glyph(glyph);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the hook called the first time each glyph missing from the font is rendered.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_missing_glyph_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(char) + 'static,
+    {
+        self.renderer.set_missing_glyph_hook(hook);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resizes the terminal to fill the window with whole tiles if terminal resizing is enabled
+    // and the window has been resized. Returns the new dimensions if the terminal was resized.
+    // (should be called once per game loop, after polling events)
+    //---------------------------------------------------------------------------------------------
+    pub fn resize_terminal(&mut self, terminal: &mut Terminal) -> Result<Option<ICoord>> {
+        if !CONFIG.resize_terminal || !self.terminal_resize_pending {
+            return Ok(None);
+        }
+
+        self.terminal_resize_pending = false;
+
+        let dimensions = self.renderer.fit_terminal_dimensions(Misc::utoi(self.window.size()));
+
+        if dimensions == terminal.dimensions() {
+            return Ok(None);
+        }
+
+        terminal.resize(dimensions);
+        self.renderer
+            .resize_terminal(dimensions)
+            .context("Failed to resize the renderer terminal.")?;
+
+        // The viewport must be refreshed for the new terminal dimensions.
+        self.resized = true;
+
+        Ok(Some(dimensions))
+    }
+
     //---------------------------------------------------------------------------------------------
     // Sets the the current input state and returns the delta time.
     // (should be consumed once per game loop)
     //---------------------------------------------------------------------------------------------
     pub fn update_input(&mut self, input: &mut InputManager) -> Duration {
+        // Give input access to the clipboard (e.g. for pasting into text inputs).
+        if !input.has_clipboard() {
+            input.set_clipboard(self.video_subsystem.clipboard());
+        }
+
         // Skip updating input if the debug gui is currently enabled.
-        if self.debug_enabled {
+        if self.debug_gui_shown() {
             return self.delta_time;
         }
 
-        // Calculate the terminal coord of the mouse if it is within bounds.
+        // Calculate the terminal and pixel coords of the mouse if it is within bounds.
         let mouse_state = &self.event_pump.mouse_state();
         let mouse_coord =
             self.renderer.screen_to_terminal_coords((mouse_state.x(), mouse_state.y()));
+        let mouse_world_coord =
+            self.renderer.screen_to_world_coords((mouse_state.x(), mouse_state.y()));
 
         // Update input.
-        input.update(&self.event_pump.keyboard_state(), mouse_state, mouse_coord);
+        input.update(
+            &self.event_pump.keyboard_state(),
+            mouse_state,
+            mouse_coord,
+            mouse_world_coord,
+        );
+
+        // Render the virtual cursor (if enabled) as a highlighted cell.
+        self.renderer.set_highlighted_cell(input.virtual_cursor_coord());
+
+        // Hide the hardware cursor while the software cursor is drawn in its place.
+        let hardware_cursor_shown = !input.software_cursor().enabled();
+
+        if hardware_cursor_shown != self.hardware_cursor_shown {
+            self.sdl2_context.mouse().show_cursor(hardware_cursor_shown);
+            self.hardware_cursor_shown = hardware_cursor_shown;
+        }
+
+        self.renderer.set_software_cursor(input.software_cursor_placement());
 
         // Calculate and return the delta time since input was last updated.
         let now = Instant::now();
@@ -248,22 +627,40 @@ impl Client {
 
     //---------------------------------------------------------------------------------------------
     // Renders a frame if enough time as passed and returns whether a frame was rendererd.
+    // The terminal's effects and particles are composited only while syncing, so its tiles are
+    // left unchanged.
     // (this should be called in a loop)
     //---------------------------------------------------------------------------------------------
-    pub fn render_frame(&mut self, terminal: &Terminal) -> Result<bool> {
+    pub fn render_frame(&mut self, terminal: &mut Terminal) -> Result<bool> {
         // Print FPS.
         // TODO: Handle this elsewhere?
         //-----------------------------------------------------------------------------------------
-        if self.fps_log_timer.update(&self.delta_time) {
+        if self.settings.show_fps && self.fps_log_timer.update(&self.delta_time) {
             const FPS_LOG_SECONDS: i32 = CONFIG_FPS_LOG_INTERVAL.as_secs() as i32;
             println!("FPS: {}", self.fps_counter / FPS_LOG_SECONDS);
 
             self.fps_counter = 0;
         }
 
-        // Return early if minimum frame duration has not yet passed.
+        // Advance the palette cycles every loop so that no time is dropped between frames.
+        //-----------------------------------------------------------------------------------------
+        self.renderer.update_palette_cycles(self.delta_time);
+
+        // Advance the music fades and the terminal's effects and particles every loop as well.
+        //-----------------------------------------------------------------------------------------
+        if let Some(audio) = self.audio.as_mut() {
+            audio.update(self.delta_time);
+        }
+
+        terminal.effects_mut().update(self.delta_time);
+        terminal.particles_mut().update(self.delta_time);
+
+        // Return early if minimum frame duration has not yet passed (or rendering is disabled
+        // while the window does not have focus).
         //-----------------------------------------------------------------------------------------
-        if !self.render_timer.update(&self.delta_time) {
+        let render_disabled = !self.focused && CONFIG.background.render_interval.is_none();
+
+        if render_disabled || !self.render_timer.update(&self.delta_time) {
             // Sleep for a bit.
             thread::sleep(CONFIG.sleep_interval);
 
@@ -284,20 +681,38 @@ impl Client {
             self.resized = false;
         }
 
-        // Sync the render with the terminal every frame.
+        // Sync the render with the terminal (and its effects and particles) every frame.
         //-----------------------------------------------------------------------------------------
-        self.renderer
-            .sync_with_terminal(terminal)
-            .context("Failed to sync renderer state with terminal.")?;
+        terminal.composite_effects();
+        let synced = self.renderer.sync_with_terminal(terminal);
+        terminal.restore_effects();
+        synced.context("Failed to sync renderer state with terminal.")?;
 
         // Render a frame.
         //-----------------------------------------------------------------------------------------
         self.renderer.render()?;
 
+        // Save a screenshot of the frame if requested (a failure is not fatal).
+        //-----------------------------------------------------------------------------------------
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+
+            match self.save_screenshot() {
+                Ok(path) => println!("[Client] Saved screenshot {}.", path.display()),
+                Err(e) => eprintln!("[Client] {:#}", e),
+            }
+        }
+
         // Optionally render the debug gui as well.
         //-----------------------------------------------------------------------------------------
-        if self.debug_enabled {
-            self.debug_gui.render(&self.delta_time, &self.window, &self.event_pump.mouse_state());
+        if self.debug_gui_shown() {
+            self.debug_gui.render(
+                &self.delta_time,
+                &self.window,
+                &self.event_pump.mouse_state(),
+                self.renderer.stats(),
+                self.debug_enabled,
+            );
         }
 
         // Swap the window buffers and return the delta time.
@@ -7,23 +7,58 @@ use std::time::Duration;
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use imgui::Context as ImguiContext;
+use imgui::{ChildWindow, Condition, PlotLines, Ui, Window};
 use imgui_opengl_renderer::Renderer as ImguiOpenglRenderer;
 use imgui_sdl2::ImguiSdl2;
 use sdl2::event::Event;
 use sdl2::mouse::MouseState;
-use sdl2::video::Window;
+use sdl2::video::Window as SdlWindow;
 use sdl2::VideoSubsystem;
 
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::render_stats::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Initial size of the renderer stats window.
+const STATS_WINDOW_SIZE: [f32; 2] = [360.0, 480.0];
+
+// Height of the sparklines of the renderer stats.
+const STATS_GRAPH_HEIGHT: f32 = 32.0;
+
+// Initial size of the console window.
+const CONSOLE_WINDOW_SIZE: [f32; 2] = [520.0, 260.0];
+
+// Height left below the console log for the command input.
+const CONSOLE_INPUT_HEIGHT: f32 = 28.0;
+
+// # of lines kept in the console log.
+const CONSOLE_LOG_LINES: usize = 200;
+
 // DebugGui contains everything related to the ImGui debug gui.
 // TODO: Build this out.
 pub struct DebugGui {
     imgui: ImguiContext,
     imgui_sdl2: ImguiSdl2,
     imgui_renderer: ImguiOpenglRenderer,
+    // Whether the console window is shown.
+    console_open: bool,
+    // Command being typed into the console.
+    console_input: String,
+    // Commands entered and their responses, oldest first.
+    console_log: Vec<String>,
+    // Commands entered since they were last taken.
+    console_commands: Vec<String>,
+    // Whether to drop the text typed by the key that opened the console.
+    console_opening: bool,
 }
 
 impl DebugGui {
-    pub fn new(video_subsystem: &VideoSubsystem, window: &Window) -> Self {
+    pub fn new(video_subsystem: &VideoSubsystem, window: &SdlWindow) -> Self {
         let mut imgui = ImguiContext::create();
         imgui.set_ini_filename(None);
 
@@ -32,20 +67,162 @@ impl DebugGui {
             video_subsystem.gl_get_proc_address(s) as *const _
         });
 
-        Self { imgui, imgui_sdl2, imgui_renderer }
+        Self {
+            imgui,
+            imgui_sdl2,
+            imgui_renderer,
+            console_open: false,
+            console_input: String::new(),
+            console_log: Vec::new(),
+            console_commands: Vec::new(),
+            console_opening: false,
+        }
     }
 
     pub fn handle_event(&mut self, event: &Event) {
+        // The key that opens the console should not also be typed into it.
+        if let (true, Event::TextInput { .. }) = (self.console_opening, event) {
+            self.console_opening = false;
+            return;
+        }
+
         self.imgui_sdl2.handle_event(&mut self.imgui, event);
     }
 
-    pub fn render(&mut self, dt: &Duration, window: &Window, mouse_state: &MouseState) {
+    // Shows or hides the console, returning whether it is now shown.
+    pub fn toggle_console(&mut self) -> bool {
+        self.console_open = !self.console_open;
+        self.console_opening = self.console_open;
+        self.console_open
+    }
+
+    // Returns whether the console is shown.
+    pub fn console_open(&self) -> bool {
+        self.console_open
+    }
+
+    // Returns the commands entered into the console since they were last taken.
+    pub fn take_console_commands(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.console_commands)
+    }
+
+    // Appends a line to the console log (e.g. the response to a command).
+    pub fn console_print(&mut self, line: String) {
+        if self.console_log.len() >= CONSOLE_LOG_LINES {
+            self.console_log.remove(0);
+        }
+
+        self.console_log.push(line);
+    }
+
+    // Renders the debug windows (if shown) and the console (if open).
+    pub fn render(
+        &mut self,
+        dt: &Duration,
+        window: &SdlWindow,
+        mouse_state: &MouseState,
+        stats: &RenderStats,
+        show_debug: bool,
+    ) {
         self.imgui_sdl2.prepare_frame(self.imgui.io_mut(), window, mouse_state);
         self.imgui.io_mut().delta_time =
             dt.as_secs() as f32 + dt.subsec_nanos() as f32 / 1_000_000_000.0;
 
+        // Any text typed by the key that opened the console has arrived by the first frame.
+        self.console_opening = false;
+
         let ui = self.imgui.frame();
-        ui.show_demo_window(&mut true);
+
+        if show_debug {
+            ui.show_demo_window(&mut true);
+            Self::render_stats_window(&ui, stats);
+        }
+
+        let entered = if self.console_open {
+            Self::render_console_window(
+                &ui,
+                &mut self.console_open,
+                &mut self.console_input,
+                &self.console_log,
+            )
+        } else {
+            None
+        };
+
         self.imgui_renderer.render(ui);
+
+        if let Some(command) = entered {
+            self.console_print(format!("> {}", command));
+            self.console_commands.push(command);
+        }
+    }
+
+    // Renders the console log and command input, returning the command entered this frame.
+    fn render_console_window(
+        ui: &Ui,
+        open: &mut bool,
+        input: &mut String,
+        log: &[String],
+    ) -> Option<String> {
+        let mut entered = None;
+
+        Window::new("Console")
+            .size(CONSOLE_WINDOW_SIZE, Condition::FirstUseEver)
+            .opened(open)
+            .build(ui, || {
+                ChildWindow::new("Log").size([0.0, -CONSOLE_INPUT_HEIGHT]).build(ui, || {
+                    for line in log.iter() {
+                        ui.text(line);
+                    }
+
+                    // Keep the newest line in view.
+                    if ui.scroll_y() >= ui.scroll_max_y() {
+                        ui.set_scroll_here_y_with_ratio(1.0);
+                    }
+                });
+
+                if ui.input_text("##Command", input).enter_returns_true(true).build() {
+                    let command = input.trim().to_string();
+                    input.clear();
+
+                    if !command.is_empty() {
+                        entered = Some(command);
+                    }
+                }
+            });
+
+        entered
+    }
+
+    // Renders the renderer counters of the last frame and sparklines of the recent frames.
+    fn render_stats_window(ui: &Ui, stats: &RenderStats) {
+        let graphs = [
+            ("Quads", stats.series(|frame| frame.quads as f32)),
+            ("Draw calls", stats.series(|frame| frame.draw_calls as f32)),
+            ("KB uploaded", stats.series(|frame| frame.bytes_uploaded as f32 / 1024.0)),
+            ("Texture binds", stats.series(|frame| frame.texture_binds as f32)),
+            ("Map stalls", stats.series(|frame| frame.map_stalls as f32)),
+            ("Map time (us)", stats.series(|frame| frame.map_time.as_micros() as f32)),
+        ];
+
+        Window::new("Renderer").size(STATS_WINDOW_SIZE, Condition::FirstUseEver).build(ui, || {
+            if let Some(last) = stats.last() {
+                ui.text(format!("Synced with terminal: {}", last.synced));
+
+                for (kind, quads) in last.pass_quads.iter() {
+                    ui.text(format!("{:?} pass: {} quads", kind, quads));
+                }
+            }
+
+            for (label, values) in graphs.iter() {
+                let overlay = format!("{:.0}", values.last().copied().unwrap_or(0.0));
+
+                PlotLines::new(ui, label, values)
+                    .overlay_text(&overlay)
+                    .scale_min(0.0)
+                    .graph_size([0.0, STATS_GRAPH_HEIGHT])
+                    .build();
+            }
+        });
     }
 }
@@ -0,0 +1,227 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use fnv::FnvHashMap;
+use fontdue::{Font, FontSettings};
+use gl::types::*;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::gl_helpers::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Name of the bundled TTF (in the fonts dir) missing glyphs are rasterized from.
+const DYNAMIC_GLYPHS_FONT: &str = "fallback.ttf";
+
+// Size in pixels glyphs are rasterized at (matching the 64px baked atlases).
+const DYNAMIC_GLYPH_SIZE: f32 = 64.0;
+
+// Dimensions of the dynamic glyph page in pixels.
+pub const DYNAMIC_GLYPHS_PAGE_DIMENSIONS: (u32, u32) = (1024, 1024);
+
+// Space in pixels between the glyphs of the page (and around its border).
+// NOTE: The foreground shader keeps its samples within this padding (see MAX_SPREAD).
+const GLYPH_PADDING: i32 = 2;
+
+//-------------------------------------------------------------------------------------------------
+// ShelfPacker packs rects into a page in rows ("shelves") as tall as their tallest rect.
+// Glyphs are similar in height, so little space is wasted and packing is cheap.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+struct ShelfPacker {
+    // Dimensions of the page.
+    dimensions: ICoord,
+    // Position of the next rect on the current shelf.
+    cursor: ICoord,
+    // Height of the current shelf.
+    shelf_height: i32,
+}
+
+impl ShelfPacker {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty packer for a page.
+    //---------------------------------------------------------------------------------------------
+    fn new(dimensions: ICoord) -> Self {
+        Self { dimensions, cursor: (GLYPH_PADDING, GLYPH_PADDING), shelf_height: 0 }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position of a rect packed into the page (none if the page is full).
+    //---------------------------------------------------------------------------------------------
+    fn pack(&mut self, (width, height): ICoord) -> Option<ICoord> {
+        // Start a new shelf when the rect does not fit on the current one.
+        if self.cursor.0 + width + GLYPH_PADDING > self.dimensions.0 {
+            self.cursor = (GLYPH_PADDING, self.cursor.1 + self.shelf_height + GLYPH_PADDING);
+            self.shelf_height = 0;
+        }
+
+        if self.cursor.0 + width + GLYPH_PADDING > self.dimensions.0
+            || self.cursor.1 + height + GLYPH_PADDING > self.dimensions.1
+        {
+            return None;
+        }
+
+        let xy = self.cursor;
+        self.cursor.0 += width + GLYPH_PADDING;
+        self.shelf_height = self.shelf_height.max(height);
+
+        Some(xy)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// DynamicGlyphs rasterizes glyphs missing from the baked atlases on demand from a bundled TTF into
+// a single page (a layer of the glyphs texture array), caching their metrics. Every style and
+// outline shares the page.
+// NOTE: The page holds coverage rather than distance values, so SDF rendering thresholds it.
+//-------------------------------------------------------------------------------------------------
+pub struct DynamicGlyphs {
+    // Font glyphs are rasterized from (none if the bundled TTF failed to load).
+    font: Option<Font>,
+    // Distance from the top of a line to the baseline at the rasterized size.
+    ascent: i32,
+    // RGBA pixels of the page.
+    pixels: Vec<u8>,
+    // Packs the glyphs into the page.
+    packer: ShelfPacker,
+    // Metrics of the glyphs rasterized so far (none for glyphs that could not be rasterized).
+    metrics: FnvHashMap<char, Option<GlyphMetric>>,
+    // Whether the pixels have changed since the page was last uploaded.
+    dirty: bool,
+}
+
+impl DynamicGlyphs {
+    //---------------------------------------------------------------------------------------------
+    // Creates an empty page, loading the bundled TTF.
+    // (a missing TTF is reported, leaving every glyph to the fallback glyph)
+    //---------------------------------------------------------------------------------------------
+    pub fn load() -> Self {
+        let path = CONTENT_LAYERS.resolve(Path::new(CONFIG_FONTS_DIR).join(DYNAMIC_GLYPHS_FONT));
+        let font = match Self::load_font(&path) {
+            Ok(font) => Some(font),
+            Err(e) => {
+                eprintln!("[DynamicGlyphs] {:#}", e);
+                None
+            }
+        };
+        let ascent = font
+            .as_ref()
+            .and_then(|font| font.horizontal_line_metrics(DYNAMIC_GLYPH_SIZE))
+            .map_or(0, |line| line.ascent.round() as i32);
+
+        let (width, height) = DYNAMIC_GLYPHS_PAGE_DIMENSIONS;
+
+        Self {
+            font,
+            ascent,
+            pixels: vec![0; (width * height * 4) as usize],
+            packer: ShelfPacker::new((width as i32, height as i32)),
+            metrics: FnvHashMap::default(),
+            dirty: true,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that loads a TTF.
+    //---------------------------------------------------------------------------------------------
+    fn load_font(path: &Path) -> Result<Font> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("Failed to read font file {}.", path.display()))?;
+
+        Font::from_bytes(data, FontSettings::default())
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("Failed to parse font file {}.", path.display()))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Marks the page for uploading (e.g. after the texture array has been reallocated).
+    //---------------------------------------------------------------------------------------------
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the metric of a glyph, rasterizing it into the page the first time it is requested.
+    // Returns none if the TTF lacks the glyph or the page is full.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&mut self, glyph: char) -> Option<GlyphMetric> {
+        if let Some(metric) = self.metrics.get(&glyph) {
+            return *metric;
+        }
+
+        let metric = self.rasterize(glyph);
+        self.metrics.insert(glyph, metric);
+
+        metric
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that rasterizes a glyph into the page and returns its metric.
+    //---------------------------------------------------------------------------------------------
+    fn rasterize(&mut self, glyph: char) -> Option<GlyphMetric> {
+        let font = self.font.as_ref()?;
+
+        if font.lookup_glyph_index(glyph) == 0 {
+            return None;
+        }
+
+        let (raster, coverage) = font.rasterize(glyph, DYNAMIC_GLYPH_SIZE);
+        let page_width = DYNAMIC_GLYPHS_PAGE_DIMENSIONS.0;
+        let (width, height) = (raster.width as i32, raster.height as i32);
+        let (x, y) = self.packer.pack((width, height))?;
+
+        // Copy the coverage into the alpha channel of white pixels.
+        for (row, coverage_row) in coverage.chunks(raster.width.max(1)).enumerate() {
+            let start = (((y + row as i32) * page_width as i32 + x) * 4) as usize;
+            let pixels = &mut self.pixels[start..start + coverage_row.len() * 4];
+
+            for (pixel, &alpha) in pixels.chunks_mut(4).zip(coverage_row) {
+                pixel.copy_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+
+        self.dirty = true;
+
+        Some(GlyphMetric {
+            codepoint: glyph as i32,
+            x,
+            y,
+            width,
+            height,
+            x_offset: raster.xmin,
+            y_offset: self.ascent - (raster.ymin + height),
+            x_advance: raster.advance_width.round() as i32,
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Uploads the page into a layer of a texture array if it has changed since it was last
+    // uploaded. Returns whether the page was uploaded.
+    //---------------------------------------------------------------------------------------------
+    pub fn upload(&mut self, texture: GLuint, active: GLenum, layer: usize) -> Result<bool> {
+        if !self.dirty {
+            return Ok(false);
+        }
+
+        upload_texture_layer(texture, active, layer, DYNAMIC_GLYPHS_PAGE_DIMENSIONS, &self.pixels)
+            .context("Failed to upload dynamic glyphs page.")?;
+        self.dirty = false;
+
+        Ok(true)
+    }
+}
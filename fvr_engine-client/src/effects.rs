@@ -0,0 +1,387 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::f32::consts::PI;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Easing enumerates the curves that map the linear progress of an effect to its strength.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    // Constant speed.
+    #[default]
+    Linear,
+    // Starts slow and speeds up.
+    EaseIn,
+    // Starts fast and slows down.
+    EaseOut,
+    // Starts and ends slow.
+    EaseInOut,
+    // Smoothly rises to the end and back to the start (for pulsing effects).
+    Pulse,
+}
+
+impl Easing {
+    //---------------------------------------------------------------------------------------------
+    // Returns the eased value of a progress in the range [0.0, 1.0].
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Pulse => (1.0 - (t * 2.0 * PI).cos()) * 0.5,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// EffectKind enumerates the ways an effect changes the tiles it covers. Effects are relative to
+// the tiles drawn beneath them, so scenes can keep redrawing while they run.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum EffectKind {
+    // Blends the foreground and/or background colors towards other colors.
+    ColorLerp { foreground: Option<TileColor>, background: Option<TileColor> },
+    // Replaces the glyphs, stepping through a list (the easing affects the pace).
+    GlyphCycle { glyphs: Vec<char> },
+    // Sets the background to a color that then fades back out (skipped if flashing is disabled).
+    Flash { color: TileColor },
+    // Scales the opacities from one value to another.
+    Fade { from: f32, to: f32 },
+}
+
+//-------------------------------------------------------------------------------------------------
+// Effect is a time-based animation over a region of the terminal. It starts after its delay and
+// runs for its duration (or loops until cancelled), with each tile of the region optionally
+// starting later than the last to sweep across it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Effect {
+    // How the effect changes the tiles.
+    kind: EffectKind,
+    // Tiles covered by the effect.
+    region: Rect,
+    // How long a tile is affected.
+    duration: Duration,
+    // Time before the effect starts.
+    delay: Duration,
+    // Additional delay of each tile by its distance from the origin of the region.
+    stagger: Duration,
+    // Curve of the effect's progress.
+    easing: Easing,
+    // Whether the effect restarts when done.
+    looping: bool,
+    // Time since the effect was queued.
+    age: Duration,
+}
+
+impl Effect {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new effect.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(kind: EffectKind, region: Rect, duration: Duration) -> Self {
+        Self {
+            kind,
+            region,
+            duration,
+            delay: Duration::default(),
+            stagger: Duration::default(),
+            easing: Easing::default(),
+            looping: false,
+            age: Duration::default(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates an effect that blends the colors of a region towards other colors.
+    //---------------------------------------------------------------------------------------------
+    pub fn color_lerp(
+        region: Rect,
+        foreground: Option<TileColor>,
+        background: Option<TileColor>,
+        duration: Duration,
+    ) -> Self {
+        Self::new(EffectKind::ColorLerp { foreground, background }, region, duration)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates an effect that steps the glyphs of a region through a list over its duration.
+    //---------------------------------------------------------------------------------------------
+    pub fn glyph_cycle(region: Rect, glyphs: &[char], duration: Duration) -> Self {
+        Self::new(EffectKind::GlyphCycle { glyphs: glyphs.to_vec() }, region, duration)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates an effect that flashes the background of a region.
+    //---------------------------------------------------------------------------------------------
+    pub fn flash(region: Rect, color: TileColor, duration: Duration) -> Self {
+        Self::new(EffectKind::Flash { color }, region, duration)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates an effect that fades a region from one opacity to another.
+    //---------------------------------------------------------------------------------------------
+    pub fn fade(region: Rect, from: f32, to: f32, duration: Duration) -> Self {
+        Self::new(EffectKind::Fade { from, to }, region, duration)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effect with an easing curve.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effect starting after a delay.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effect with each tile starting later by its distance from the region's origin.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_stagger(mut self, stagger: Duration) -> Self {
+        self.stagger = stagger;
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effect restarting whenever it is done, until cancelled.
+    //---------------------------------------------------------------------------------------------
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the region covered by the effect.
+    //---------------------------------------------------------------------------------------------
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the delay of a tile of the region.
+    //---------------------------------------------------------------------------------------------
+    fn tile_delay(&self, xy: ICoord) -> Duration {
+        let distance = (xy.0 - self.region.x) + (xy.1 - self.region.y);
+        self.delay + self.stagger * distance.max(0) as u32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the effect is done with all of its tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn finished(&self) -> bool {
+        let last = self.region.max_entent();
+        !self.looping && self.age >= self.tile_delay(last) + self.duration
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the linear progress of a tile, if the tile is affected.
+    // While motion is reduced, one-shot effects skip straight to their end.
+    //---------------------------------------------------------------------------------------------
+    fn progress(&self, xy: ICoord) -> Option<f32> {
+        let age = self.age.checked_sub(self.tile_delay(xy))?;
+
+        if self.duration.is_zero() || (!self.looping && !Accessibility::motion_enabled()) {
+            return Some(1.0);
+        }
+
+        let t = age.as_secs_f32() / self.duration.as_secs_f32();
+
+        match self.looping {
+            true => Some(t.fract()),
+            false if t <= 1.0 => Some(t),
+            // Done with this tile, so the tile is no longer affected.
+            false => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that applies the effect to a tile of the region.
+    //---------------------------------------------------------------------------------------------
+    fn apply(&self, xy: ICoord, tile: &mut Tile) {
+        let t = match self.progress(xy) {
+            Some(t) => self.easing.apply(t),
+            None => return,
+        };
+
+        match &self.kind {
+            EffectKind::ColorLerp { foreground, background } => {
+                if let Some(color) = foreground {
+                    tile.foreground_color = tile.foreground_color.lerp(color, t);
+                }
+
+                if let Some(color) = background {
+                    tile.background_color = tile.background_color.lerp(color, t);
+                }
+            }
+            EffectKind::GlyphCycle { glyphs } if !glyphs.is_empty() => {
+                let i = ((t * glyphs.len() as f32) as usize).min(glyphs.len() - 1);
+                tile.glyph = glyphs[i];
+            }
+            EffectKind::Flash { color } if Accessibility::flashing_enabled() => {
+                tile.background_color = color.lerp(&tile.background_color, t);
+                tile.background_opacity += (1.0 - tile.background_opacity) * (1.0 - t);
+            }
+            EffectKind::Fade { from, to } => {
+                let opacity = from + (to - from) * t;
+                tile.background_opacity *= opacity;
+                tile.foreground_opacity *= opacity;
+                tile.outline_opacity *= opacity;
+            }
+            _ => {}
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Identifies a queued effect (e.g. to cancel a looping effect).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EffectId(u64);
+
+//-------------------------------------------------------------------------------------------------
+// Effects runs the queued effects of the terminal. The client advances them by the render delta
+// time and composites them onto the terminal's tiles only for as long as it syncs the renderer,
+// so the tiles scenes drew are never changed by an effect. Effects composite in queue order.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct Effects {
+    // The queued effects.
+    effects: Vec<(EffectId, Effect)>,
+    // Id of the next queued effect.
+    next_id: u64,
+    // Tiles overwritten by compositing, to restore afterwards.
+    overwritten: FnvHashMap<ICoord, Tile>,
+}
+
+impl Effects {
+    //---------------------------------------------------------------------------------------------
+    // Creates new effects with none queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Queues an effect, returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn queue(&mut self, effect: Effect) -> EffectId {
+        let id = EffectId(self.next_id);
+        self.next_id += 1;
+        self.effects.push((id, effect));
+
+        id
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes a queued effect. Returns whether it was still queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel(&mut self, id: EffectId) -> bool {
+        let len = self.effects.len();
+        self.effects.retain(|(effect_id, _)| *effect_id != id);
+
+        self.effects.len() != len
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an effect is still queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn running(&self, id: EffectId) -> bool {
+        self.effects.iter().any(|(effect_id, _)| *effect_id == id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all queued effects.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of queued effects.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no effects are queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Ages the effects by a frame delta time (scaled per the accessibility options), removing
+    // the finished effects.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) {
+        if self.effects.is_empty() {
+            return;
+        }
+
+        let dt = Accessibility::animation_dt(dt);
+
+        for (_, effect) in self.effects.iter_mut() {
+            effect.age += dt;
+        }
+
+        self.effects.retain(|(_, effect)| !effect.finished());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Composites the effects onto the tiles of a map, remembering the tiles they overwrote.
+    // Must be followed by a restore before the map is otherwise used.
+    //---------------------------------------------------------------------------------------------
+    pub fn composite<M>(&mut self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        for (_, effect) in self.effects.iter() {
+            for xy in effect.region.points() {
+                if !map.in_bounds(xy) {
+                    continue;
+                }
+
+                let tile = map.get_xy_mut(xy);
+                self.overwritten.entry(xy).or_insert(*tile);
+                effect.apply(xy, tile);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores the tiles of a map overwritten by the last composite.
+    //---------------------------------------------------------------------------------------------
+    pub fn restore<M>(&mut self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        for (xy, tile) in self.overwritten.drain() {
+            if map.in_bounds(xy) {
+                *map.get_xy_mut(xy) = tile;
+            }
+        }
+    }
+}
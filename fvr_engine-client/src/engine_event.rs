@@ -0,0 +1,122 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+pub use sdl2::controller::Button as ControllerButton;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Mod;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// KeyModifiers holds which modifier keys were held when a key event occurred.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct KeyModifiers {
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl KeyModifiers {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a modifier key was held.
+    //---------------------------------------------------------------------------------------------
+    pub fn held(&self, modifier: &ModifierKey) -> bool {
+        match modifier {
+            ModifierKey::Alt => self.alt,
+            ModifierKey::Ctrl => self.ctrl,
+            ModifierKey::Shift => self.shift,
+        }
+    }
+}
+
+impl From<Mod> for KeyModifiers {
+    fn from(keymod: Mod) -> Self {
+        Self {
+            alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+            ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+            shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// EngineEvent enumerates the window, keyboard, text and controller events the engine handles,
+// translated from the platform's events so that nothing above the client depends on SDL's.
+// Controllers are identified by an id that stays the same while they are connected.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum EngineEvent {
+    // The user asked to close the game (e.g. by closing the window).
+    Quit,
+    // The window's drawable area changed size (in pixels).
+    WindowResized(ICoord),
+    // The window gained keyboard focus.
+    FocusGained,
+    // The window lost keyboard focus.
+    FocusLost,
+    // A key was pressed (or repeated while held).
+    KeyDown { key: InputKey, modifiers: KeyModifiers, repeat: bool },
+    // A key was released.
+    KeyUp { key: InputKey, modifiers: KeyModifiers },
+    // Text was typed (already composed by the platform's input method).
+    TextInput(String),
+    // The mouse wheel was scrolled (positive is away from the user).
+    MouseWheel(i32),
+    // A controller was connected (including the ones connected before the game started).
+    ControllerConnected(u32),
+    // A controller was disconnected.
+    ControllerDisconnected(u32),
+    // A controller button was pressed.
+    ControllerButtonDown { controller: u32, button: ControllerButton },
+    // A controller button was released.
+    ControllerButtonUp { controller: u32, button: ControllerButton },
+}
+
+impl EngineEvent {
+    //---------------------------------------------------------------------------------------------
+    // Translates an SDL event, returning none for events the engine does not handle.
+    // (controller connections are translated by the client, which opens the controllers)
+    //---------------------------------------------------------------------------------------------
+    pub fn from_sdl(event: &Event) -> Option<Self> {
+        let event = match event {
+            Event::Quit { .. } => EngineEvent::Quit,
+            Event::Window { win_event, .. } => match win_event {
+                WindowEvent::SizeChanged(width, height) => {
+                    EngineEvent::WindowResized((*width, *height))
+                }
+                WindowEvent::FocusGained => EngineEvent::FocusGained,
+                WindowEvent::FocusLost => EngineEvent::FocusLost,
+                _ => return None,
+            },
+            Event::KeyDown { keycode: Some(key), keymod, repeat, .. } => {
+                EngineEvent::KeyDown { key: *key, modifiers: (*keymod).into(), repeat: *repeat }
+            }
+            Event::KeyUp { keycode: Some(key), keymod, .. } => {
+                EngineEvent::KeyUp { key: *key, modifiers: (*keymod).into() }
+            }
+            Event::TextInput { text, .. } => EngineEvent::TextInput(text.clone()),
+            Event::MouseWheel { y, .. } => EngineEvent::MouseWheel(*y),
+            Event::ControllerDeviceRemoved { which, .. } => {
+                EngineEvent::ControllerDisconnected(*which)
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                EngineEvent::ControllerButtonDown { controller: *which, button: *button }
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                EngineEvent::ControllerButtonUp { controller: *which, button: *button }
+            }
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
@@ -0,0 +1,243 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+use fvr_engine_parser::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::terminal::*;
+use crate::tile_layer::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// How long floating texts are shown by default.
+pub const DEFAULT_FLOATING_TEXT_LIFETIME: Duration = Duration::from_millis(900);
+
+// Distance floating texts rise over their lifetime, in tiles.
+const RISE_TILES: f32 = 1.5;
+
+// Fraction of the lifetime after which floating texts begin to fade.
+const FADE_START: f32 = 0.5;
+
+// How long projectiles take to fly one tile.
+pub const PROJECTILE_TILE_DURATION: Duration = Duration::from_millis(35);
+
+//-------------------------------------------------------------------------------------------------
+// A short-lived string of text that rises and fades from where it was spawned, or that flies
+// from one tile to another (e.g. a thrown item).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatingText {
+    // The text run drawn, positioned where the text was spawned.
+    run: TextRun,
+    // Distance the text moves over its lifetime, in pixels.
+    travel: (f32, f32),
+    // Whether the text fades out over the end of its lifetime.
+    fades: bool,
+    // Time since the text was spawned.
+    age: Duration,
+    // How long the text is shown.
+    lifetime: Duration,
+}
+
+impl FloatingText {
+    //---------------------------------------------------------------------------------------------
+    // Creates a floating text from rich text at a position (in pixels, relative to the terminal).
+    // A text run has a single format, so the format hints apply to the whole text and the last
+    // hint of each kind wins (layout, size and background hints are ignored).
+    //---------------------------------------------------------------------------------------------
+    pub fn new(position: (f32, f32), text: &str, lifetime: Duration) -> Result<Self> {
+        let parsed = parse_rich_text(text).context("Failed to parse floating text.")?;
+        let mut run = TextRun::new(position, "");
+
+        for value in parsed.into_iter() {
+            match value {
+                RichTextValue::FormatHint { key, value } => match key {
+                    RichTextHintType::Style => run.style = TileStyle::from_format_hint(&value)?,
+                    RichTextHintType::Outlined => {
+                        run.outlined = match value.as_str() {
+                            "t" => true,
+                            "f" => false,
+                            _ => return Err(anyhow!("Failed to parse outlined value.")),
+                        }
+                    }
+                    RichTextHintType::ForegroundColor => {
+                        run.color = PaletteColor::from_format_hint(&value)?.into();
+                    }
+                    RichTextHintType::OutlineColor => {
+                        run.outline_color = PaletteColor::from_format_hint(&value)?.into();
+                    }
+                    _ => {}
+                },
+                RichTextValue::Newline => run.text.push('\n'),
+                RichTextValue::Text(text) => run.text.push_str(&text),
+            }
+        }
+
+        let travel = (0.0, -RISE_TILES * CONFIG.tile_dimensions.1 as f32);
+
+        Ok(Self { run, travel, fades: true, age: Duration::default(), lifetime })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a floating text from rich text, roughly centered above a tile of a layer (the text
+    // does not follow the tile if the layer is later scrolled or zoomed).
+    //---------------------------------------------------------------------------------------------
+    pub fn above_tile(
+        layer: &TileLayer,
+        xy: ICoord,
+        text: &str,
+        lifetime: Duration,
+    ) -> Result<Self> {
+        let (width, height) = layer.tile_dimensions();
+        let (x, y) = layer.tile_position(xy);
+        let mut floating_text = Self::new((x, y), text, lifetime)?;
+
+        // Proportional glyphs are narrower than tiles, so this only approximates the center.
+        let chars = floating_text.run.text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        floating_text.run.position = (x + (width - chars as f32 * width * 0.5) * 0.5, y - height);
+        floating_text.travel = (0.0, -RISE_TILES * height);
+
+        Ok(floating_text)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a floating text from rich text that flies from a tile of a layer to another without
+    // fading, taking PROJECTILE_TILE_DURATION per tile of distance.
+    //---------------------------------------------------------------------------------------------
+    pub fn projectile(layer: &TileLayer, from: ICoord, to: ICoord, text: &str) -> Result<Self> {
+        let (from_x, from_y) = layer.tile_position(from);
+        let (to_x, to_y) = layer.tile_position(to);
+        let distance = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+        let mut floating_text =
+            Self::new((from_x, from_y), text, PROJECTILE_TILE_DURATION * distance as u32)?;
+
+        floating_text.travel = (to_x - from_x, to_y - from_y);
+        floating_text.fades = false;
+
+        Ok(floating_text)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns how far through its lifetime the text is, in the range [0.0, 1.0].
+    //---------------------------------------------------------------------------------------------
+    pub fn progress(&self) -> f32 {
+        if self.lifetime.is_zero() {
+            return 1.0;
+        }
+
+        (self.age.as_secs_f32() / self.lifetime.as_secs_f32()).min(1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the text has outlived its lifetime.
+    //---------------------------------------------------------------------------------------------
+    pub fn expired(&self) -> bool {
+        self.age >= self.lifetime
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text run to draw for the current age: moved and faded by the progress.
+    //---------------------------------------------------------------------------------------------
+    pub fn current_run(&self) -> TextRun {
+        let progress = self.progress();
+        let mut run = self.run.clone();
+
+        if Accessibility::motion_enabled() {
+            run.position.0 += progress * self.travel.0;
+            run.position.1 += progress * self.travel.1;
+        }
+
+        if self.fades && progress > FADE_START {
+            run.opacity *= 1.0 - (progress - FADE_START) / (1.0 - FADE_START);
+        }
+
+        run
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// FloatingTextLayer batches the floating texts of a scene (e.g. damage numbers, "miss" or the
+// names of picked up items) and draws them as text runs over the terminal's tiles. Only the runs
+// the layer drew are replaced each frame, so other text runs of the terminal are untouched.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct FloatingTextLayer {
+    // The floating texts shown.
+    texts: Vec<FloatingText>,
+    // Text runs drawn last frame.
+    drawn: Vec<TextRun>,
+}
+
+impl FloatingTextLayer {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new floating text layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether any floating texts are shown.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.texts.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Shows a floating text.
+    //---------------------------------------------------------------------------------------------
+    pub fn spawn(&mut self, text: FloatingText) {
+        self.texts.push(text);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all floating texts (they disappear from the terminal on the next draw).
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.texts.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Ages the floating texts, removing the expired texts.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) {
+        let dt = Accessibility::animation_dt(dt);
+
+        for text in self.texts.iter_mut() {
+            text.age += dt;
+        }
+
+        self.texts.retain(|text| !text.expired());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the text runs drawn last frame with the runs of the current floating texts.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw(&mut self, terminal: &mut Terminal) {
+        if self.texts.is_empty() && self.drawn.is_empty() {
+            return;
+        }
+
+        terminal.remove_text_runs(&self.drawn);
+        self.drawn = self.texts.iter().map(FloatingText::current_run).collect();
+
+        for run in self.drawn.iter() {
+            terminal.push_text_run(run.clone());
+        }
+    }
+}
@@ -12,7 +12,7 @@ use std::str;
 //-------------------------------------------------------------------------------------------------
 use anyhow::{anyhow, bail, Context, Result};
 use gl::types::*;
-use image::DynamicImage;
+use image::{DynamicImage, RgbaImage};
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
@@ -148,26 +148,6 @@ pub fn link_program(vertex_shader: GLuint, fragment_shader: GLuint) -> Result<GL
     }
 }
 
-//-------------------------------------------------------------------------------------------------
-// Creates and links a new program from vertex and fragment shader sources.
-//-------------------------------------------------------------------------------------------------
-pub fn link_program_from_sources<S>(vertex_source: S, fragment_source: S) -> Result<GLuint>
-where
-    S: AsRef<str>,
-{
-    let vertex_shader = compile_shader(vertex_source.as_ref(), gl::VERTEX_SHADER)?;
-    let fragment_shader = compile_shader(fragment_source.as_ref(), gl::FRAGMENT_SHADER)?;
-    let program = link_program(vertex_shader, fragment_shader)?;
-
-    // Shaders are no longer needed.
-    unsafe {
-        gl::DeleteShader(vertex_shader);
-        gl::DeleteShader(fragment_shader);
-    }
-
-    Ok(program)
-}
-
 //-------------------------------------------------------------------------------------------------
 // Returns location of an attrib within a program.
 //-------------------------------------------------------------------------------------------------
@@ -221,81 +201,149 @@ pub fn generate_indices(num_quads: usize) -> Vec<GLuint> {
     indices
 }
 
-// Binds and uploads the data from an image to a texture, returning the size.
-pub fn load_texture<P>(path: P, texture: GLuint, active: GLenum) -> Result<(u32, u32)>
+// Loads an image from disk, converting it to RGBA8 if it is not already.
+pub fn load_image_rgba<P>(path: P) -> Result<RgbaImage>
 where
     P: AsRef<Path>,
 {
-    // Load the texture image into memory and convert to the proper format.
-    //-----------------------------------------------------------------------------------------
-
-    // Load the image data from disk.
-    let texture_data = image::open(&path)
+    let image = image::open(&path)
         .map_err(|e| anyhow!(e))
         .with_context(|| format!("Failed to load file at {}.", path.as_ref().display()))?;
 
-    // Convert to RGBA8 if not already.
-    let texture_data = match texture_data {
+    Ok(match image {
         DynamicImage::ImageRgba8(data) => data,
         other => other.to_rgba8(),
-    };
-
-    // Query the dimensions
-    let texture_dimensions = texture_data.dimensions();
+    })
+}
 
-    // Set the texture settings and upload the texture data.
-    //-----------------------------------------------------------------------------------------
+// Binds a texture array and (re)allocates storage for a # of layers of the same dimensions.
+// The layers start out undefined (nothing is uploaded) and must each be filled in with
+// upload_texture_layer.
+pub fn allocate_texture_array(
+    texture: GLuint,
+    active: GLenum,
+    dimensions: (u32, u32),
+    layers: usize,
+) -> Result<()> {
     unsafe {
         // Set the active texture.
         gl::ActiveTexture(active);
-        gl_error_unwrap!("Failed to bind texture.");
+        gl_error_unwrap!("Failed to set active texture.");
 
-        // Bind the texture.
-        gl::BindTexture(gl::TEXTURE_2D, texture);
-        gl_error_unwrap!("Failed to bind texture.");
+        // Bind the texture array.
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture);
+        gl_error_unwrap!("Failed to bind texture array.");
 
         // Set the wrap to CLAMP_TO_EDGE to avoid seams at the edge of tiles.
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
         gl_error_unwrap!("Failed to set TEXTURE_WRAP_S parameter.");
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
         gl_error_unwrap!("Failed to set TEXTURE_WRAP_T parameter.");
 
         // Set the filter to LINEAR to apply a blurring effect.
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
         gl_error_unwrap!("Failed to set TEXTURE_MIN_FILTER parameter.");
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
         gl_error_unwrap!("Failed to set TEXTURE_MAG_FILTER parameter.");
 
-        // gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 4 as GLint);
-        // gl_error_unwrap!("Failed to set TEXTURE_MAX_LEVEL parameter.");
-
-        // Upload the texture data.
-        gl::TexImage2D(
+        // Allocate the storage for every layer.
+        gl::TexImage3D(
             // Target.
-            gl::TEXTURE_2D,
+            gl::TEXTURE_2D_ARRAY,
             // Level.
             0,
             // Internal format.
             gl::RGBA as GLint,
             // Width.
-            texture_dimensions.0 as GLsizei,
+            dimensions.0 as GLsizei,
             // Height.
-            texture_dimensions.1 as GLsizei,
+            dimensions.1 as GLsizei,
+            // Depth.
+            layers as GLsizei,
             // Border.
             0,
             // Format.
             gl::RGBA,
             // Type.
             gl::UNSIGNED_BYTE,
+            // Pointer (null, so that no data is uploaded).
+            ptr::null(),
+        );
+        gl_error_unwrap!("Failed to allocate texture array.");
+    }
+
+    Ok(())
+}
+
+// Uploads RGBA8 pixels to the top left of a layer of a texture array.
+// (the pixels may be smaller than the layers, but not larger)
+pub fn upload_texture_layer(
+    texture: GLuint,
+    active: GLenum,
+    layer: usize,
+    dimensions: (u32, u32),
+    pixels: &[u8],
+) -> Result<()> {
+    unsafe {
+        gl::ActiveTexture(active);
+        gl_error_unwrap!("Failed to set active texture.");
+
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture);
+        gl_error_unwrap!("Failed to bind texture array.");
+
+        gl::TexSubImage3D(
+            // Target.
+            gl::TEXTURE_2D_ARRAY,
+            // Level.
+            0,
+            // Offset [X, Y, Layer].
+            0,
+            0,
+            layer as GLint,
+            // Width.
+            dimensions.0 as GLsizei,
+            // Height.
+            dimensions.1 as GLsizei,
+            // Depth.
+            1,
+            // Format.
+            gl::RGBA,
+            // Type.
+            gl::UNSIGNED_BYTE,
             // Pointer.
-            texture_data.as_ptr() as *const c_void,
+            pixels.as_ptr() as *const c_void,
         );
-        gl_error_unwrap!("Failed to upload texture data.");
+        gl_error_unwrap!("Failed to upload texture layer data.");
+    }
 
-        // Generate Mipmaps. TODO: Do we need to do this?
-        // gl::GenerateMipmap(gl::TEXTURE_2D);
-        // gl_error_unwrap!("Failed to generate mipmaps.");
+    Ok(())
+}
+
+// Reads the RGBA8 pixels of the frame drawn to the default framebuffer (before it is swapped),
+// flipped so that the first row is the top of the frame.
+pub fn read_frame_rgba(dimensions: (u32, u32)) -> Result<RgbaImage> {
+    let mut pixels = vec![0u8; dimensions.0 as usize * dimensions.1 as usize * 4];
+
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        gl_error_unwrap!("Failed to bind the default framebuffer.");
+
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            dimensions.0 as GLsizei,
+            dimensions.1 as GLsizei,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut c_void,
+        );
+        gl_error_unwrap!("Failed to read the frame pixels.");
     }
 
-    Ok(texture_dimensions)
+    let mut image = RgbaImage::from_raw(dimensions.0, dimensions.1, pixels)
+        .ok_or_else(|| anyhow!("Frame pixels do not match the frame dimensions."))?;
+    image::imageops::flip_vertical_in_place(&mut image);
+
+    Ok(image)
 }
@@ -0,0 +1,269 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::ffi::CStr;
+use std::fmt;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Result};
+use gl::types::*;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Lowest OpenGL version the renderer supports.
+pub const REQUIRED_GL_VERSION: (i32, i32) = (3, 3);
+
+// Substrings of the renderer strings of software (CPU) OpenGL implementations.
+const SOFTWARE_RENDERERS: [&str; 5] =
+    ["llvmpipe", "softpipe", "swiftshader", "software rasterizer", "gdi generic"];
+
+// Extensions (and their enums, which the gl crate does not include) for querying free video
+// memory. The values are in kilobytes.
+const NVX_GPU_MEMORY_INFO: &str = "GL_NVX_gpu_memory_info";
+const GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX: GLenum = 0x9049;
+const ATI_MEMINFO: &str = "GL_ATI_meminfo";
+const TEXTURE_FREE_MEMORY_ATI: GLenum = 0x87FC;
+
+// Free video memory (in kilobytes) below which the font textures are downscaled and
+// post-processing is skipped.
+const LOW_VIDEO_MEMORY_KB: i32 = 256 * 1024;
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that returns an OpenGL string (empty if unavailable).
+//-------------------------------------------------------------------------------------------------
+fn gl_string(name: GLenum) -> String {
+    unsafe {
+        let ptr = gl::GetString(name);
+
+        if ptr.is_null() {
+            return String::new();
+        }
+
+        CStr::from_ptr(ptr as *const GLchar).to_string_lossy().into_owned()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that returns an OpenGL integer.
+//-------------------------------------------------------------------------------------------------
+fn gl_integer(name: GLenum) -> GLint {
+    let mut value = 0;
+
+    unsafe {
+        gl::GetIntegerv(name, &mut value);
+    }
+
+    value
+}
+
+//-------------------------------------------------------------------------------------------------
+// GpuCapabilities describes what the GPU (and its driver) supports, queried from the current
+// OpenGL context.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct GpuCapabilities {
+    // Vendor of the OpenGL implementation.
+    pub vendor: String,
+    // Name of the GPU (or software renderer).
+    pub renderer: String,
+    // Full version string of the OpenGL implementation.
+    pub version_string: String,
+    // Major and minor OpenGL version.
+    pub version: (i32, i32),
+    // Largest width or height of a texture.
+    pub max_texture_size: i32,
+    // Largest # of layers of a texture array.
+    pub max_array_texture_layers: i32,
+    // # of texture units available to the fragment shader.
+    pub max_texture_units: i32,
+    // Free video memory in kilobytes, if the driver reports it.
+    pub free_video_memory_kb: Option<i32>,
+    // Names of the supported extensions.
+    pub extensions: Vec<String>,
+}
+
+impl GpuCapabilities {
+    //---------------------------------------------------------------------------------------------
+    // Queries the capabilities of the current OpenGL context.
+    // (the OpenGL functions must have been loaded)
+    //---------------------------------------------------------------------------------------------
+    pub fn probe() -> Self {
+        let extension_count = gl_integer(gl::NUM_EXTENSIONS).max(0) as GLuint;
+        let extensions = (0..extension_count)
+            .filter_map(|i| unsafe {
+                let ptr = gl::GetStringi(gl::EXTENSIONS, i);
+
+                if ptr.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr(ptr as *const GLchar).to_string_lossy().into_owned())
+                }
+            })
+            .collect();
+
+        let mut capabilities = Self {
+            vendor: gl_string(gl::VENDOR),
+            renderer: gl_string(gl::RENDERER),
+            version_string: gl_string(gl::VERSION),
+            version: (gl_integer(gl::MAJOR_VERSION), gl_integer(gl::MINOR_VERSION)),
+            max_texture_size: gl_integer(gl::MAX_TEXTURE_SIZE),
+            max_array_texture_layers: gl_integer(gl::MAX_ARRAY_TEXTURE_LAYERS),
+            max_texture_units: gl_integer(gl::MAX_TEXTURE_IMAGE_UNITS),
+            free_video_memory_kb: None,
+            extensions,
+        };
+
+        // Only vendor extensions report free video memory.
+        capabilities.free_video_memory_kb = if capabilities.has_extension(NVX_GPU_MEMORY_INFO) {
+            Some(gl_integer(GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX))
+        } else if capabilities.has_extension(ATI_MEMINFO) {
+            // The first of the four values is the total free memory.
+            let mut values = [0; 4];
+            unsafe {
+                gl::GetIntegerv(TEXTURE_FREE_MEMORY_ATI, &mut values[0]);
+            }
+            Some(values[0])
+        } else {
+            None
+        };
+
+        // Clear any error raised by queries the driver does not support.
+        unsafe { while gl::GetError() != gl::NO_ERROR {} }
+
+        capabilities
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an extension is supported.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.iter().any(|extension| extension == name)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the OpenGL implementation renders on the CPU.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_software(&self) -> bool {
+        let renderer = self.renderer.to_lowercase();
+        SOFTWARE_RENDERERS.iter().any(|name| renderer.contains(name))
+    }
+}
+
+impl fmt::Display for GpuCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}), OpenGL {}, max texture size {}, max array layers {}, {} texture units",
+            self.renderer,
+            self.vendor,
+            self.version_string,
+            self.max_texture_size,
+            self.max_array_texture_layers,
+            self.max_texture_units
+        )?;
+
+        if let Some(free) = self.free_video_memory_kb {
+            write!(f, ", {} MB video memory free", free / 1024)?;
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// RendererProfile is the set of renderer features selected for the GPU's capabilities, along with
+// the reasons for each feature that was turned off.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct RendererProfile {
+    // Whether to alternate between two sets of vertex buffers every frame.
+    pub alternating_vbos: bool,
+    // Factor the font textures are (at least) downscaled by. Always a power of two.
+    pub atlas_scale: u32,
+    // Largest width or height of a texture.
+    pub max_texture_size: u32,
+    // Whether to render post-processing effects (e.g. the vignette).
+    pub post_processing: bool,
+    // Whether to build the vertices on a worker thread while the previous frame renders.
+    pub threaded_sync: bool,
+    // Why features were turned off, for logging.
+    pub notes: Vec<String>,
+}
+
+impl RendererProfile {
+    //---------------------------------------------------------------------------------------------
+    // Selects the profile for a GPU's capabilities, starting from the configured features.
+    // Returns an error describing the shortfall if the GPU cannot run the renderer at all.
+    //---------------------------------------------------------------------------------------------
+    pub fn select(capabilities: &GpuCapabilities) -> Result<Self> {
+        if capabilities.version < REQUIRED_GL_VERSION {
+            return Err(anyhow!(
+                "OpenGL {}.{} is required, but {} only supports OpenGL {}.",
+                REQUIRED_GL_VERSION.0,
+                REQUIRED_GL_VERSION.1,
+                capabilities.renderer,
+                capabilities.version_string
+            ));
+        }
+
+        if capabilities.max_texture_units < 1 {
+            return Err(anyhow!("{} has no texture units available.", capabilities.renderer));
+        }
+
+        let mut profile = Self {
+            alternating_vbos: CONFIG.use_alternating_vbos,
+            atlas_scale: 1,
+            max_texture_size: capabilities.max_texture_size.max(0) as u32,
+            post_processing: true,
+            threaded_sync: CONFIG.use_threaded_sync,
+            notes: Vec::new(),
+        };
+
+        if capabilities.is_software() {
+            profile.notes.push(format!(
+                "{} renders on the CPU: using single vertex buffers, half resolution font \
+                 textures and no post-processing.",
+                capabilities.renderer
+            ));
+            profile.alternating_vbos = false;
+            profile.atlas_scale = 2;
+            profile.post_processing = false;
+        } else if let Some(free) =
+            capabilities.free_video_memory_kb.filter(|free| *free < LOW_VIDEO_MEMORY_KB)
+        {
+            profile.notes.push(format!(
+                "Only {} MB of video memory is free: using half resolution font textures and no \
+                 post-processing.",
+                free / 1024
+            ));
+            profile.atlas_scale = 2;
+            profile.post_processing = false;
+        }
+
+        Ok(profile)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Logs the capabilities and the features turned off for them.
+    //---------------------------------------------------------------------------------------------
+    pub fn log(&self, capabilities: &GpuCapabilities) {
+        println!("[Renderer] {}", capabilities);
+
+        if self.notes.is_empty() {
+            println!("[Renderer] Using the full renderer profile.");
+        }
+
+        for note in self.notes.iter() {
+            println!("[Renderer] {}", note);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fmt;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+use serde_derive::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::engine_event::*;
+use crate::input_context::*;
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// Hotkey enumerates the engine-level actions that are handled before scenes see any input.
+// Their bindings are kept under the reserved "engine" namespace of the keybindings file.
+//-------------------------------------------------------------------------------------------------
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, PartialEq, Hash, Serialize)]
+pub enum Hotkey {
+    Screenshot,
+    ToggleFullscreen,
+    ToggleConsole,
+    ToggleDebugGui,
+    TogglePause,
+    StepTurn,
+    SlowDown,
+    SpeedUp,
+    ReloadShaders,
+    ToggleOverdraw,
+}
+
+impl Hotkey {
+    //---------------------------------------------------------------------------------------------
+    // Returns a short, human readable description of the hotkey.
+    //---------------------------------------------------------------------------------------------
+    pub fn description(&self) -> &'static str {
+        match self {
+            Hotkey::Screenshot => "Take a screenshot",
+            Hotkey::ToggleFullscreen => "Toggle fullscreen",
+            Hotkey::ToggleConsole => "Toggle console",
+            Hotkey::ToggleDebugGui => "Toggle debug gui",
+            Hotkey::TogglePause => "Pause / resume world",
+            Hotkey::StepTurn => "Step one turn",
+            Hotkey::SlowDown => "Halve world speed",
+            Hotkey::SpeedUp => "Double world speed",
+            Hotkey::ReloadShaders => "Reload shaders",
+            Hotkey::ToggleOverdraw => "Toggle overdraw view",
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates what a hotkey's bindings can conflict with.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HotkeyConflictWith {
+    // Another hotkey (only the first of the two is ever triggered).
+    Hotkey(Hotkey),
+    // An action's regular bindings (the action can no longer be triggered by that combination).
+    Action(InputAction),
+    // An action's bindings within a context.
+    ContextAction(InputContext, InputAction),
+}
+
+//-------------------------------------------------------------------------------------------------
+// HotkeyConflict describes a key combination that triggers a hotkey and something else.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HotkeyConflict {
+    // The hotkey that takes the key combination.
+    pub hotkey: Hotkey,
+    // What else is bound to the key combination.
+    pub with: HotkeyConflictWith,
+    // Human readable key combination of the hotkey (e.g. "Alt+Return").
+    pub keys: String,
+}
+
+impl fmt::Display for HotkeyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let with = match self.with {
+            HotkeyConflictWith::Hotkey(hotkey) => format!("hotkey '{}'", hotkey.description()),
+            HotkeyConflictWith::Action(action) => format!("action '{}'", action.description()),
+            HotkeyConflictWith::ContextAction(context, action) => {
+                format!("action '{}' in the {:?} context", action.description(), context)
+            }
+        };
+
+        write!(
+            f,
+            "Hotkey '{}' ({}) conflicts with {}.",
+            self.hotkey.description(),
+            self.keys,
+            with
+        )
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Hotkeys maps key presses to engine-level hotkeys and tracks the bindings they conflict with.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct Hotkeys {
+    // Map of hotkeys to their bound key combinations.
+    bindings: FnvHashMap<Hotkey, Vec<InputBinding>>,
+    // Conflicts found the last time the bindings changed.
+    conflicts: Vec<HotkeyConflict>,
+}
+
+impl Hotkeys {
+    //---------------------------------------------------------------------------------------------
+    // Creates new hotkeys from their bindings.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(bindings: FnvHashMap<Hotkey, Vec<InputBinding>>) -> Self {
+        Self { bindings, conflicts: Vec::new() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns whether one set of bindings can be satisfied by the same key
+    // combination as another (e.g. "Return" and "Alt+Return" both accept Alt+Return).
+    //---------------------------------------------------------------------------------------------
+    fn bindings_overlap(a: &[InputBinding], b: &[InputBinding]) -> bool {
+        let specific_keys = |bindings: &[InputBinding]| {
+            let mut keys = bindings
+                .iter()
+                .filter_map(|binding| match binding {
+                    InputBinding::SpecificKey(k) => Some(*k),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            keys.sort_unstable();
+            keys
+        };
+
+        let keys = specific_keys(a);
+
+        if keys.is_empty() || keys != specific_keys(b) {
+            return false;
+        }
+
+        let excludes = |x: &[InputBinding], y: &[InputBinding]| {
+            x.iter().any(|binding| match binding {
+                InputBinding::SpecificKey(k) => y.contains(&InputBinding::ExcludeSpecificKey(*k)),
+                InputBinding::ModifierKey(m) => y.contains(&InputBinding::ExcludeModifierKey(*m)),
+                _ => false,
+            })
+        };
+
+        !excludes(a, b) && !excludes(b, a)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the hotkey triggered by a key event, if any. Repeated key events never trigger
+    // hotkeys, and hotkeys are checked in declaration order.
+    //---------------------------------------------------------------------------------------------
+    pub fn triggered_by(&self, event: &EngineEvent) -> Option<Hotkey> {
+        let (key, modifiers) = match event {
+            EngineEvent::KeyDown { key, modifiers, repeat: false } => (*key, *modifiers),
+            _ => return None,
+        };
+
+        Hotkey::iter().find(|hotkey| {
+            let bindings = match self.bindings.get(hotkey) {
+                Some(bindings) if !bindings.is_empty() => bindings,
+                _ => return false,
+            };
+
+            bindings.iter().all(|binding| match binding {
+                InputBinding::SpecificKey(k) => *k == key as InputKeycode,
+                InputBinding::ModifierKey(m) => modifiers.held(m),
+                InputBinding::ExcludeSpecificKey(k) => *k != key as InputKeycode,
+                InputBinding::ExcludeModifierKey(m) => !modifiers.held(m),
+            })
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the bindings of a hotkey, if it is bound.
+    //---------------------------------------------------------------------------------------------
+    pub fn bindings(&self, hotkey: Hotkey) -> Option<&[InputBinding]> {
+        self.bindings.get(&hotkey).map(|bindings| bindings.as_slice())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the bindings of a hotkey. The conflicts must be refreshed afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn bind(&mut self, hotkey: Hotkey, bindings: &[InputBinding]) {
+        self.bindings.insert(hotkey, bindings.to_vec());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the conflicts found the last time they were refreshed.
+    //---------------------------------------------------------------------------------------------
+    pub fn conflicts(&self) -> &[HotkeyConflict] {
+        &self.conflicts
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the conflicts of a single hotkey.
+    //---------------------------------------------------------------------------------------------
+    pub fn conflicts_of(&self, hotkey: Hotkey) -> impl Iterator<Item = &HotkeyConflict> {
+        self.conflicts.iter().filter(move |conflict| conflict.hotkey == hotkey)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Finds the bindings the hotkeys conflict with, returning whether any were found.
+    //---------------------------------------------------------------------------------------------
+    pub fn refresh_conflicts(
+        &mut self,
+        action_bindings: &FnvHashMap<InputAction, Vec<InputBinding>>,
+        context_bindings: &FnvHashMap<InputContext, FnvHashMap<InputAction, Vec<InputBinding>>>,
+    ) -> bool {
+        let mut conflicts = Vec::new();
+        let hotkeys = Hotkey::iter().collect::<Vec<_>>();
+
+        for (i, hotkey) in hotkeys.iter().enumerate() {
+            let bindings = match self.bindings.get(hotkey) {
+                Some(bindings) if !bindings.is_empty() => bindings,
+                _ => continue,
+            };
+            let mut push = |with| {
+                conflicts.push(HotkeyConflict {
+                    hotkey: *hotkey,
+                    with,
+                    keys: InputBinding::describe_all(bindings),
+                })
+            };
+
+            for other in hotkeys[i + 1..].iter() {
+                if let Some(other_bindings) = self.bindings.get(other) {
+                    if Self::bindings_overlap(bindings, other_bindings) {
+                        push(HotkeyConflictWith::Hotkey(*other));
+                    }
+                }
+            }
+
+            for action in InputAction::iter() {
+                if let Some(action_bindings) = action_bindings.get(&action) {
+                    if Self::bindings_overlap(bindings, action_bindings) {
+                        push(HotkeyConflictWith::Action(action));
+                    }
+                }
+
+                for context in InputContext::iter() {
+                    if let Some(context_bindings) =
+                        context_bindings.get(&context).and_then(|bindings| bindings.get(&action))
+                    {
+                        if Self::bindings_overlap(bindings, context_bindings) {
+                            push(HotkeyConflictWith::ContextAction(context, action));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.conflicts = conflicts;
+        !self.conflicts.is_empty()
+    }
+}
@@ -0,0 +1,101 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Actions available in the map context.
+const MAP_ACTIONS: &[InputAction] = &[
+    InputAction::Accept,
+    InputAction::Decline,
+    InputAction::Quit,
+    InputAction::North,
+    InputAction::Northeast,
+    InputAction::East,
+    InputAction::Southeast,
+    InputAction::South,
+    InputAction::Southwest,
+    InputAction::West,
+    InputAction::Northwest,
+    InputAction::ToggleVirtualCursor,
+    InputAction::RecordMacro,
+    InputAction::CloseDoor,
+    InputAction::Inventory,
+    InputAction::PickUp,
+    InputAction::Drop,
+    InputAction::Dash,
+];
+
+// Actions available in the menu context (diagonal movement is suppressed).
+const MENU_ACTIONS: &[InputAction] = &[
+    InputAction::Accept,
+    InputAction::Decline,
+    InputAction::Quit,
+    InputAction::North,
+    InputAction::East,
+    InputAction::South,
+    InputAction::West,
+    InputAction::ToggleVirtualCursor,
+    InputAction::RecordMacro,
+];
+
+// Actions available in the targeting context.
+const TARGETING_ACTIONS: &[InputAction] = MAP_ACTIONS;
+
+// Actions available in the text entry context (keys are otherwise consumed as text).
+const TEXT_ENTRY_ACTIONS: &[InputAction] = &[InputAction::Accept, InputAction::Decline];
+
+//-------------------------------------------------------------------------------------------------
+// InputContext enumerates the named layers of input handling.
+// The input manager only reports the actions available in the context on top of its stack, so
+// e.g. pushing the menu context suppresses map movement.
+//-------------------------------------------------------------------------------------------------
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, PartialEq, Hash, Serialize)]
+pub enum InputContext {
+    Map,
+    Menu,
+    Targeting,
+    TextEntry,
+}
+
+impl InputContext {
+    //---------------------------------------------------------------------------------------------
+    // Returns the actions available in the context.
+    //---------------------------------------------------------------------------------------------
+    pub fn actions(&self) -> &'static [InputAction] {
+        match self {
+            InputContext::Map => MAP_ACTIONS,
+            InputContext::Menu => MENU_ACTIONS,
+            InputContext::Targeting => TARGETING_ACTIONS,
+            InputContext::TextEntry => TEXT_ENTRY_ACTIONS,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an action is available in the context.
+    //---------------------------------------------------------------------------------------------
+    pub fn allows(&self, action: InputAction) -> bool {
+        self.actions().contains(&action)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the cursor shown while the context is current, if it requests one.
+    //---------------------------------------------------------------------------------------------
+    pub fn cursor(&self) -> Option<Cursor> {
+        match self {
+            InputContext::Targeting => Some(Cursor::Crosshair),
+            InputContext::TextEntry => Some(Cursor::IBeam),
+            InputContext::Map | InputContext::Menu => None,
+        }
+    }
+}
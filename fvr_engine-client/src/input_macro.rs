@@ -0,0 +1,142 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// A sequence of input actions bound to a key.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputMacro {
+    // Keycode of the key that replays the macro.
+    pub key: InputKeycode,
+    // Actions replayed in order, one per update.
+    pub actions: Vec<InputAction>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// InputMacros holds the bound macros and the state of macro recording and replay.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct InputMacros {
+    // Bound macros.
+    macros: Vec<InputMacro>,
+    // Actions recorded so far, if recording.
+    recording: Option<Vec<InputAction>>,
+    // Remaining actions of the macro being replayed.
+    queue: VecDeque<InputAction>,
+}
+
+impl InputMacros {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new input macros from a vec of bound macros.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(macros: Vec<InputMacro>) -> Self {
+        Self { macros, ..Default::default() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the bound macros.
+    //---------------------------------------------------------------------------------------------
+    pub fn macros(&self) -> &[InputMacro] {
+        &self.macros
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Binds a sequence of actions to a key, replacing any macro bound to the same key.
+    //---------------------------------------------------------------------------------------------
+    pub fn bind(&mut self, key: InputKeycode, actions: Vec<InputAction>) {
+        self.unbind(key);
+        self.macros.push(InputMacro { key, actions });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the macro bound to a key, returning whether there was one.
+    //---------------------------------------------------------------------------------------------
+    pub fn unbind(&mut self, key: InputKeycode) -> bool {
+        let len = self.macros.len();
+        self.macros.retain(|input_macro| input_macro.key != key);
+        self.macros.len() != len
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Begins recording actions, discarding any unfinished recording.
+    //---------------------------------------------------------------------------------------------
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops recording actions, returning those recorded (if recording).
+    //---------------------------------------------------------------------------------------------
+    pub fn stop_recording(&mut self) -> Option<Vec<InputAction>> {
+        self.recording.take()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether actions are being recorded.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records an action if recording.
+    //---------------------------------------------------------------------------------------------
+    pub fn record(&mut self, action: InputAction) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(action);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Begins replaying the macro bound to a key, returning whether there was one.
+    //---------------------------------------------------------------------------------------------
+    pub fn play(&mut self, key: InputKeycode) -> bool {
+        match self.macros.iter().find(|input_macro| input_macro.key == key) {
+            Some(input_macro) => {
+                self.queue = input_macro.actions.iter().copied().collect();
+                true
+            }
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a macro is being replayed.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_playing(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the next action of the macro being replayed without removing it.
+    //---------------------------------------------------------------------------------------------
+    pub fn peek(&self) -> Option<InputAction> {
+        self.queue.front().copied()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes and returns the next action of the macro being replayed.
+    //---------------------------------------------------------------------------------------------
+    pub fn next_step(&mut self) -> Option<InputAction> {
+        self.queue.pop_front()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops replaying the current macro.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel(&mut self) {
+        self.queue.clear();
+    }
+}
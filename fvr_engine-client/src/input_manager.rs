@@ -1,14 +1,22 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::Path;
+
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use fnv::{FnvHashMap, FnvHashSet};
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
-use anyhow::{anyhow, Result};
-pub use sdl2::event::Event as InputEvent;
+use anyhow::{anyhow, Context, Result};
+use sdl2::clipboard::ClipboardUtil;
 use sdl2::keyboard::KeyboardState;
 pub use sdl2::keyboard::Keycode as InputKey;
 use sdl2::mouse::{Cursor as SdlCursor, MouseState, SystemCursor};
@@ -20,6 +28,18 @@ use strum_macros::EnumIter;
 //-------------------------------------------------------------------------------------------------
 use fvr_engine_core::prelude::*;
 
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::engine_event::*;
+use crate::hotkeys::*;
+use crate::input_context::*;
+use crate::input_macro::*;
+use crate::input_repeat::*;
+use crate::interaction_map::*;
+use crate::software_cursor::*;
+use crate::virtual_cursor::*;
+
 //-------------------------------------------------------------------------------------------------
 // InputAction enumerates the kinds of input the user can make.
 // These actions are meant to be composite and remappable and used alongside individual key inputs.
@@ -38,6 +58,100 @@ pub enum InputAction {
     Southwest,
     West,
     Northwest,
+    ToggleVirtualCursor,
+    RecordMacro,
+    CloseDoor,
+    Inventory,
+    PickUp,
+    Drop,
+    Dash,
+}
+
+impl InputAction {
+    //---------------------------------------------------------------------------------------------
+    // Returns the direction of a directional action.
+    //---------------------------------------------------------------------------------------------
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            InputAction::North => Some(NORTH_DIRECTION),
+            InputAction::Northeast => Some(NORTHEAST_DIRECTION),
+            InputAction::East => Some(EAST_DIRECTION),
+            InputAction::Southeast => Some(SOUTHEAST_DIRECTION),
+            InputAction::South => Some(SOUTH_DIRECTION),
+            InputAction::Southwest => Some(SOUTHWEST_DIRECTION),
+            InputAction::West => Some(WEST_DIRECTION),
+            InputAction::Northwest => Some(NORTHWEST_DIRECTION),
+            _ => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the category of the action (for grouping actions in e.g. the keybinding help).
+    //---------------------------------------------------------------------------------------------
+    pub fn category(&self) -> InputActionCategory {
+        match self {
+            InputAction::Accept | InputAction::Decline => InputActionCategory::Menu,
+            InputAction::Quit | InputAction::ToggleVirtualCursor | InputAction::RecordMacro => {
+                InputActionCategory::System
+            }
+            InputAction::CloseDoor
+            | InputAction::Inventory
+            | InputAction::PickUp
+            | InputAction::Drop => InputActionCategory::Interaction,
+            _ => InputActionCategory::Movement,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a short, human readable description of the action.
+    //---------------------------------------------------------------------------------------------
+    pub fn description(&self) -> &'static str {
+        match self {
+            InputAction::Accept => "Accept",
+            InputAction::Decline => "Decline / back",
+            InputAction::Quit => "Quit",
+            InputAction::North => "Move north",
+            InputAction::Northeast => "Move northeast",
+            InputAction::East => "Move east",
+            InputAction::Southeast => "Move southeast",
+            InputAction::South => "Move south",
+            InputAction::Southwest => "Move southwest",
+            InputAction::West => "Move west",
+            InputAction::Northwest => "Move northwest",
+            InputAction::ToggleVirtualCursor => "Toggle virtual cursor",
+            InputAction::RecordMacro => "Record macro",
+            InputAction::CloseDoor => "Close door",
+            InputAction::Inventory => "Use an item",
+            InputAction::PickUp => "Pick up items",
+            InputAction::Drop => "Drop items",
+            InputAction::Dash => "Dash",
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// InputActionCategory enumerates the groups that input actions are presented in.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, EnumIter, Eq, PartialEq, Hash)]
+pub enum InputActionCategory {
+    Menu,
+    Movement,
+    Interaction,
+    System,
+}
+
+impl InputActionCategory {
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable name of the category.
+    //---------------------------------------------------------------------------------------------
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputActionCategory::Menu => "Menu",
+            InputActionCategory::Movement => "Movement",
+            InputActionCategory::Interaction => "Interaction",
+            InputActionCategory::System => "System",
+        }
+    }
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -77,11 +191,59 @@ pub enum InputBinding {
     ExcludeModifierKey(ModifierKey),
 }
 
+impl InputBinding {
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable name of the key a binding requires (or none for exclusions).
+    //---------------------------------------------------------------------------------------------
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            InputBinding::SpecificKey(k) => Some(
+                InputKey::from_i32(*k).map(|key| key.name()).unwrap_or_else(|| format!("#{}", k)),
+            ),
+            InputBinding::ModifierKey(m) => Some(format!("{:?}", m)),
+            InputBinding::ExcludeSpecificKey(_) | InputBinding::ExcludeModifierKey(_) => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable key combination of a set of bindings, modifiers first
+    // (e.g. "Ctrl+S").
+    //---------------------------------------------------------------------------------------------
+    pub fn describe_all(bindings: &[InputBinding]) -> String {
+        let modifiers = bindings.iter().filter(|b| matches!(b, InputBinding::ModifierKey(_)));
+        let keys = bindings.iter().filter(|b| !matches!(b, InputBinding::ModifierKey(_)));
+
+        modifiers.chain(keys).filter_map(|b| b.describe()).collect::<Vec<_>>().join("+")
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes the contents of a keybindings file: either just the action bindings, or the action
+// bindings along with the per context bindings, the repeat profiles of actions, macros and the
+// hotkeys (which are reserved to the engine).
+//-------------------------------------------------------------------------------------------------
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeybindingsFile {
+    Full {
+        bindings: FnvHashMap<InputAction, Vec<InputBinding>>,
+        #[serde(default)]
+        contexts: FnvHashMap<InputContext, FnvHashMap<InputAction, Vec<InputBinding>>>,
+        #[serde(default)]
+        repeat_profiles: FnvHashMap<InputAction, RepeatProfile>,
+        #[serde(default)]
+        macros: Vec<InputMacro>,
+        #[serde(default)]
+        engine: FnvHashMap<Hotkey, Vec<InputBinding>>,
+    },
+    Bindings(FnvHashMap<InputAction, Vec<InputBinding>>),
+}
+
 //-------------------------------------------------------------------------------------------------
 // Cursor enumerates the types of mouse cursors available.
 //-------------------------------------------------------------------------------------------------
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum Cursor {
     Arrow,
     Crosshair,
@@ -104,12 +266,32 @@ pub struct InputManager {
     mouse_coord: Option<ICoord>,
     // Whether the mouse changed coords.
     mouse_moved: bool,
+    // Current coord of the mouse in pixels within the faux terminal (or none if out of bounds).
+    mouse_world_coord: Option<ICoord>,
+    // Mouse wheel scroll accumulated since the last reset (positive is away from the user).
+    mouse_wheel: i32,
+    // Engine events handled since the last reset (other than the ones that triggered hotkeys).
+    events: Vec<EngineEvent>,
+    // Text typed since the last reset.
+    text_input: String,
+    // Whether the window has lost keyboard focus (and not regained it).
+    focus_lost: bool,
+    // Ids of the connected controllers, in the order they were connected.
+    controllers: Vec<u32>,
+    // Set of controller buttons that are currently pressed, by controller.
+    pressed_buttons: FnvHashSet<(u32, ControllerButton)>,
+    // Set of controller buttons that have become pressed since the last reset, by controller.
+    just_pressed_buttons: FnvHashSet<(u32, ControllerButton)>,
+    // Access to the system clipboard (set by the client).
+    clipboard: Option<ClipboardUtil>,
     // Set of keys that are currently pressed.
     pressed_keys: FnvHashSet<InputKey>,
     // Set of keys that have become pressed this frame.
     just_pressed_keys: FnvHashSet<InputKey>,
     // Set of keys that have been released.
     released_keys: FnvHashSet<InputKey>,
+    // Set of keys that triggered a hotkey, hidden from scenes until they are released.
+    hotkey_keys: FnvHashSet<InputKey>,
     // Set of actions that are currently pressed.
     pressed_actions: FnvHashSet<InputAction>,
     // Set of actions that have become pressed this frame.
@@ -118,19 +300,52 @@ pub struct InputManager {
     released_actions: FnvHashSet<InputAction>,
     // Map of input actions to their bound key combinations.
     action_bindings: FnvHashMap<InputAction, Vec<InputBinding>>,
+    // Per context bindings that override the action bindings while the context is active.
+    context_bindings: FnvHashMap<InputContext, FnvHashMap<InputAction, Vec<InputBinding>>>,
+    // Stack of input contexts (the map context is active when empty).
+    contexts: Vec<InputContext>,
+    // Engine-level hotkeys, checked before scenes see any input.
+    hotkeys: Hotkeys,
+    // Map of input actions to their repeat profiles.
+    repeat_profiles: FnvHashMap<InputAction, RepeatProfile>,
+    // Bound macros and the state of macro recording and replay.
+    macros: InputMacros,
+    // Macro action injected since the last reset, if any.
+    macro_step: Option<InputAction>,
+    // Whether a scene asked for the macro being replayed to stop.
+    macro_cancelled: Cell<bool>,
+    // Actions recorded when macro recording was last stopped with the record macro action, until
+    // a scene binds or discards them.
+    recorded_macro: RefCell<Option<Vec<InputAction>>>,
+    // Macro bound by a scene, added to the bound macros at the next update.
+    pending_macro: RefCell<Option<InputMacro>>,
     // Whether any key was pressed.
     pressed_any_key: bool,
     // Whether any action was pressed.
     pressed_any_action: bool,
     // Vec of cursors.
     cursors: Vec<SdlCursor>,
+    // Cursor style currently shown.
+    cursor: Cell<Option<Cursor>>,
+    // Cursor style requested by the hovered region or current context at the last update.
+    requested_cursor: Option<Cursor>,
+    // Glyph cursor drawn in place of the hardware cursor while enabled.
+    software_cursor: SoftwareCursor,
+    // Hot regions that the mouse coord is resolved against each frame.
+    interactions: InteractionMap,
+    // Events emitted by the interaction map since the last reset.
+    interaction_events: Vec<InteractionEvent>,
+    // Keyboard driven cursor that replaces the mouse while enabled.
+    virtual_cursor: VirtualCursor,
+    // Last coord of the real mouse, for detecting when it moves while the virtual cursor is enabled.
+    real_mouse_coord: Option<ICoord>,
 }
 
 impl InputManager {
     //---------------------------------------------------------------------------------------------
     // Helper function for create a new input manager.
     //---------------------------------------------------------------------------------------------
-    fn new(keybindings_path: &str) -> Result<Self> {
+    fn new(keybindings_path: &Path) -> Result<Self> {
         let cursors = vec![
             SdlCursor::from_system(SystemCursor::Arrow).map_err(|e| anyhow!(e))?,
             SdlCursor::from_system(SystemCursor::Crosshair).map_err(|e| anyhow!(e))?,
@@ -139,13 +354,39 @@ impl InputManager {
             SdlCursor::from_system(SystemCursor::No).map_err(|e| anyhow!(e))?,
             SdlCursor::from_system(SystemCursor::Wait).map_err(|e| anyhow!(e))?,
         ];
-        let keybindings_json = std::fs::read_to_string(keybindings_path)?;
+        let keybindings_json = std::fs::read_to_string(keybindings_path).with_context(|| {
+            format!("Failed to read keybindings file {}.", keybindings_path.display())
+        })?;
+        let (action_bindings, context_bindings, repeat_profiles, macros, hotkey_bindings) =
+            match serde_json::from_str(&keybindings_json)? {
+                KeybindingsFile::Full { bindings, contexts, repeat_profiles, macros, engine } => {
+                    (bindings, contexts, repeat_profiles, macros, engine)
+                }
+                KeybindingsFile::Bindings(bindings) => (
+                    bindings,
+                    FnvHashMap::default(),
+                    FnvHashMap::default(),
+                    Vec::new(),
+                    FnvHashMap::default(),
+                ),
+            };
 
-        Ok(Self {
+        let mut software_cursor = SoftwareCursor::default();
+        software_cursor.set_enabled(CONFIG.software_cursor);
+
+        let mut input_manager = Self {
             cursors,
-            action_bindings: serde_json::from_str(&keybindings_json)?,
+            software_cursor,
+            action_bindings,
+            context_bindings,
+            hotkeys: Hotkeys::new(hotkey_bindings),
+            repeat_profiles,
+            macros: InputMacros::new(macros),
             ..Default::default()
-        })
+        };
+        input_manager.refresh_hotkey_conflicts();
+
+        Ok(input_manager)
     }
 
     //---------------------------------------------------------------------------------------------
@@ -153,7 +394,7 @@ impl InputManager {
     // (there should only ever be one)
     //---------------------------------------------------------------------------------------------
     pub fn with_keybindings() -> Result<Self> {
-        Self::new(CONFIG_KEYBINDINGS_PATH)
+        Self::new(&PLATFORM_DIRS.config_file(CONFIG_KEYBINDINGS_FILE))
     }
 
     //---------------------------------------------------------------------------------------------
@@ -161,7 +402,7 @@ impl InputManager {
     // (there should only ever be one)
     //---------------------------------------------------------------------------------------------
     pub fn with_default_bindings() -> Result<Self> {
-        Self::new(CONFIG_DEFAULT_KEYBINDINGS_PATH)
+        Self::new(&PLATFORM_DIRS.default_config_file(CONFIG_DEFAULT_KEYBINDINGS_FILE))
     }
 
     //---------------------------------------------------------------------------------------------
@@ -204,6 +445,7 @@ impl InputManager {
         keyboard_state: &KeyboardState,
         mouse_state: &MouseState,
         mouse_coord: Option<ICoord>,
+        mouse_world_coord: Option<ICoord>,
     ) {
         // Update key states.
         //-----------------------------------------------------------------------------------------
@@ -211,6 +453,16 @@ impl InputManager {
         // Iterate over all keys.
         for (scancode, pressed) in keyboard_state.scancodes() {
             if let Some(keycode) = InputKey::from_scancode(scancode) {
+                // Keys that triggered a hotkey stay hidden until they are released.
+                if self.hotkey_keys.contains(&keycode) {
+                    if !pressed {
+                        self.hotkey_keys.remove(&keycode);
+                        self.released_keys.insert(keycode);
+                    }
+
+                    continue;
+                }
+
                 // If pressed:
                 // - insert into the pressed key set.
                 // - insert into the just pressed key set if the key had previously been released.
@@ -239,9 +491,24 @@ impl InputManager {
         //-----------------------------------------------------------------------------------------
 
         // Iterate over all actions.
+        let context = self.context();
+
         for input_action in InputAction::iter() {
+            // Actions not available in the current context are treated as released.
+            if !context.allows(input_action) {
+                self.released_actions.insert(input_action);
+                continue;
+            }
+
+            // Prefer the current context's bindings for the action over the regular bindings.
+            let bindings = self
+                .context_bindings
+                .get(&context)
+                .and_then(|bindings| bindings.get(&input_action))
+                .or_else(|| self.action_bindings.get(&input_action));
+
             // If the action has keybindings...
-            if let Some(bindings) = self.action_bindings.get(&input_action) {
+            if let Some(bindings) = bindings {
                 // ...and if all of the bindings are pressed:
                 // - insert into the the pressed action set.
                 // - insert into the just pressed action set if the action had previously been
@@ -261,23 +528,167 @@ impl InputManager {
             }
         }
 
+        // Update macro state.
+        //-----------------------------------------------------------------------------------------
+        self.update_macros();
+
+        // Update virtual cursor state.
+        //-----------------------------------------------------------------------------------------
+        let (mouse_coord, virtual_pressed) = self.update_virtual_cursor(mouse_coord);
+
         // Update mouse states.
         //-----------------------------------------------------------------------------------------
+        let pressed =
+            (mouse_state.left() || virtual_pressed.0, mouse_state.right() || virtual_pressed.1);
+
+        // Determine which buttons were clicked this update.
+        let just_clicked =
+            (!self.mouse_pressed.0 && pressed.0, !self.mouse_pressed.1 && pressed.1);
 
         // Set clicked to true if the mouse button was not pressed last frame.
-        self.mouse_clicked.0 = self.mouse_clicked.0 || !self.mouse_pressed.0 && mouse_state.left();
-        self.mouse_clicked.1 =
-            self.mouse_clicked.1 || !self.mouse_pressed.1 && mouse_state.right();
+        self.mouse_clicked.0 = self.mouse_clicked.0 || just_clicked.0;
+        self.mouse_clicked.1 = self.mouse_clicked.1 || just_clicked.1;
 
         // Set remaining state.
-        self.mouse_pressed.0 = mouse_state.left();
-        self.mouse_pressed.1 = mouse_state.right();
+        self.mouse_pressed = pressed;
 
         // Previous mouse coord should be a record of the last different mouse coord.
         if self.mouse_coord != mouse_coord {
             self.mouse_coord = mouse_coord;
             self.mouse_moved = true;
         }
+
+        // The virtual cursor is over the center of its cell.
+        self.mouse_world_coord = match self.virtual_cursor_coord() {
+            Some((x, y)) => Some((
+                x * CONFIG.tile_dimensions.0 + CONFIG.tile_dimensions.0 / 2,
+                y * CONFIG.tile_dimensions.1 + CONFIG.tile_dimensions.1 / 2,
+            )),
+            None => mouse_world_coord,
+        };
+
+        // Resolve the mouse against the interaction map.
+        self.interactions.resolve(self.mouse_coord, just_clicked, &mut self.interaction_events);
+
+        // Show the cursor requested by the hovered region (or current context) when it changes,
+        // so that cursors set by scenes persist until then.
+        let requested_cursor = self.requested_cursor();
+
+        if requested_cursor != self.requested_cursor {
+            self.requested_cursor = requested_cursor;
+            self.reset_cursor();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the cursor requested by the hovered region or current context.
+    //---------------------------------------------------------------------------------------------
+    fn requested_cursor(&self) -> Option<Cursor> {
+        self.interactions.hovered_cursor().or_else(|| self.context().cursor())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that updates macro recording and replay.
+    // Macros are replayed one action per reset, and replay stops when the next action is not
+    // available in the current context, when any other key is pressed, or when cancelled.
+    //---------------------------------------------------------------------------------------------
+    fn update_macros(&mut self) {
+        // Bind the macro bound by a scene since the last update.
+        if let Some(input_macro) = self.pending_macro.get_mut().take() {
+            self.macros.bind(input_macro.key, input_macro.actions);
+        }
+
+        // Toggle recording.
+        if self.just_pressed_actions.remove(&InputAction::RecordMacro) {
+            if self.macros.is_recording() {
+                *self.recorded_macro.get_mut() = self.macros.stop_recording();
+            } else {
+                self.macros.start_recording();
+            }
+        }
+
+        // Stop replaying if cancelled or any key other than a macro key was pressed.
+        let macros = &self.macros;
+        let interrupted = self.just_pressed_keys.iter().any(|key| {
+            !macros.macros().iter().any(|input_macro| input_macro.key == *key as InputKeycode)
+        });
+
+        if self.macro_cancelled.replace(false) || (self.macros.is_playing() && interrupted) {
+            self.macros.cancel();
+        }
+
+        // Begin replaying a macro if its key was pressed (and not while recording).
+        if !self.macros.is_recording() && !self.macros.is_playing() {
+            for key in self.just_pressed_keys.iter() {
+                if self.macros.play(*key as InputKeycode) {
+                    break;
+                }
+            }
+        }
+
+        // Inject the next action once the previous one has been consumed.
+        if self.macro_step.is_some() {
+            return;
+        }
+
+        if let Some(action) = self.macros.peek() {
+            if !self.context().allows(action) {
+                self.macros.cancel();
+                return;
+            }
+
+            let _ = self.macros.next_step();
+            self.pressed_actions.insert(action);
+            self.just_pressed_actions.insert(action);
+            self.pressed_any_action = true;
+            self.macro_step = Some(action);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that updates the virtual cursor from the action state.
+    // Directional actions move the virtual cursor while it is enabled, and accept/decline act as
+    // the left/right mouse buttons. These actions are consumed so they do not also reach scenes.
+    // Returns the effective mouse coord and the virtual (left, right) pressed state.
+    //---------------------------------------------------------------------------------------------
+    fn update_virtual_cursor(
+        &mut self,
+        mouse_coord: Option<ICoord>,
+    ) -> (Option<ICoord>, (bool, bool)) {
+        if self.just_pressed_actions.remove(&InputAction::ToggleVirtualCursor) {
+            self.set_virtual_cursor_enabled(!self.virtual_cursor.enabled());
+        }
+
+        // Moving the real mouse moves the virtual cursor along with it.
+        let real_mouse_moved = self.real_mouse_coord != mouse_coord;
+        self.real_mouse_coord = mouse_coord;
+
+        if !self.virtual_cursor.enabled() {
+            return (mouse_coord, (false, false));
+        }
+
+        if let (true, Some(xy)) = (real_mouse_moved, mouse_coord) {
+            self.virtual_cursor.set_xy(xy);
+        }
+
+        for input_action in InputAction::iter() {
+            if let Some(direction) = input_action.direction() {
+                if self.just_pressed_actions.remove(&input_action) {
+                    self.virtual_cursor.step(direction);
+                }
+
+                self.pressed_actions.remove(&input_action);
+            }
+        }
+
+        let virtual_pressed = (
+            self.pressed_actions.remove(&InputAction::Accept),
+            self.pressed_actions.remove(&InputAction::Decline),
+        );
+        self.just_pressed_actions.remove(&InputAction::Accept);
+        self.just_pressed_actions.remove(&InputAction::Decline);
+
+        (Some(self.virtual_cursor.xy()), virtual_pressed)
     }
 
     //---------------------------------------------------------------------------------------------
@@ -285,6 +696,19 @@ impl InputManager {
     // (should be called once after input is consumed)
     //---------------------------------------------------------------------------------------------
     pub fn reset(&mut self) {
+        // Record the actions that were pressed (other than replayed macro actions).
+        if self.macros.is_recording() {
+            for input_action in InputAction::iter() {
+                if self.just_pressed_actions.contains(&input_action)
+                    && self.macro_step != Some(input_action)
+                {
+                    self.macros.record(input_action);
+                }
+            }
+        }
+
+        self.macro_step = None;
+
         // Clear the key state.
         self.pressed_keys.clear();
         self.just_pressed_keys.clear();
@@ -299,6 +723,15 @@ impl InputManager {
         self.mouse_clicked.0 = false;
         self.mouse_clicked.1 = false;
         self.mouse_moved = false;
+        self.mouse_wheel = 0;
+
+        // Clear the interaction events.
+        self.interaction_events.clear();
+
+        // Clear the engine events.
+        self.events.clear();
+        self.text_input.clear();
+        self.just_pressed_buttons.clear();
     }
 
     //---------------------------------------------------------------------------------------------
@@ -342,6 +775,65 @@ impl InputManager {
         self.mouse_coord
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns the interaction map for registering hot regions.
+    //---------------------------------------------------------------------------------------------
+    pub fn interactions(&self) -> &InteractionMap {
+        &self.interactions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the interaction map mutably for registering hot regions.
+    //---------------------------------------------------------------------------------------------
+    pub fn interactions_mut(&mut self) -> &mut InteractionMap {
+        &mut self.interactions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the hover and click events emitted by the interaction map since the last reset.
+    //---------------------------------------------------------------------------------------------
+    pub fn interaction_events(&self) -> &[InteractionEvent] {
+        &self.interaction_events
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the virtual cursor is replacing the mouse.
+    //---------------------------------------------------------------------------------------------
+    pub fn virtual_cursor_enabled(&self) -> bool {
+        self.virtual_cursor.enabled()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Enables or disables the virtual cursor, starting it at the current mouse coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_virtual_cursor_enabled(&mut self, enabled: bool) {
+        if enabled && !self.virtual_cursor.enabled() {
+            if let Some(xy) = self.mouse_coord {
+                self.virtual_cursor.set_xy(xy);
+            }
+        }
+
+        self.virtual_cursor.set_enabled(enabled);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord of the virtual cursor if it is enabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn virtual_cursor_coord(&self) -> Option<ICoord> {
+        if self.virtual_cursor.enabled() {
+            Some(self.virtual_cursor.xy())
+        } else {
+            None
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the virtual cursor mutably, e.g. for moving it or rebinding its dimensions.
+    //---------------------------------------------------------------------------------------------
+    pub fn virtual_cursor_mut(&mut self) -> &mut VirtualCursor {
+        &mut self.virtual_cursor
+    }
+
     //---------------------------------------------------------------------------------------------
     // Returns whether the mouse has moved to a new coord.
     //---------------------------------------------------------------------------------------------
@@ -349,6 +841,142 @@ impl InputManager {
         self.mouse_moved
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns current mouse coord in pixels within the faux terminal (or none if out of bounds).
+    // (for resolving the mouse against layers with their own tile scale)
+    //---------------------------------------------------------------------------------------------
+    pub fn mouse_world_coord(&self) -> Option<ICoord> {
+        self.mouse_world_coord
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Accumulates mouse wheel scroll from a mouse wheel event.
+    //---------------------------------------------------------------------------------------------
+    pub fn scroll_mouse_wheel(&mut self, y: i32) {
+        self.mouse_wheel += y;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the input manager from an engine event, keeping the event for scenes until the next
+    // reset.
+    // (should be called for every polled event that did not trigger a hotkey)
+    //---------------------------------------------------------------------------------------------
+    pub fn handle_event(&mut self, event: &EngineEvent) {
+        match event {
+            EngineEvent::MouseWheel(y) => self.scroll_mouse_wheel(*y),
+            EngineEvent::TextInput(text) => self.text_input.push_str(text),
+            EngineEvent::FocusGained => self.focus_lost = false,
+            // Nothing should keep happening on its own while the user is away.
+            EngineEvent::FocusLost => {
+                self.focus_lost = true;
+                self.macros.cancel();
+                self.pressed_buttons.clear();
+            }
+            EngineEvent::ControllerConnected(controller)
+                if !self.controllers.contains(controller) =>
+            {
+                self.controllers.push(*controller);
+            }
+            EngineEvent::ControllerDisconnected(controller) => {
+                self.controllers.retain(|other| other != controller);
+                self.pressed_buttons.retain(|(other, _)| other != controller);
+            }
+            EngineEvent::ControllerButtonDown { controller, button }
+                if !self.pressed_buttons.contains(&(*controller, *button)) =>
+            {
+                self.pressed_buttons.insert((*controller, *button));
+                self.just_pressed_buttons.insert((*controller, *button));
+            }
+            EngineEvent::ControllerButtonUp { controller, button } => {
+                self.pressed_buttons.remove(&(*controller, *button));
+            }
+            _ => {}
+        }
+
+        self.events.push(event.clone());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the engine events handled since the last reset, oldest first.
+    //---------------------------------------------------------------------------------------------
+    pub fn events(&self) -> &[EngineEvent] {
+        &self.events
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text typed since the last reset.
+    //---------------------------------------------------------------------------------------------
+    pub fn text_input(&self) -> &str {
+        &self.text_input
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the input manager has access to the system clipboard.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_clipboard(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Gives the input manager access to the system clipboard.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_clipboard(&mut self, clipboard: ClipboardUtil) {
+        self.clipboard = Some(clipboard);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text on the system clipboard, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn clipboard_text(&self) -> Option<String> {
+        match self.clipboard.as_ref() {
+            Some(clipboard) if clipboard.has_clipboard_text() => clipboard.clipboard_text().ok(),
+            _ => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Puts text on the system clipboard.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_clipboard_text(&self, text: &str) -> Result<()> {
+        let clipboard = self.clipboard.as_ref().ok_or_else(|| anyhow!("No clipboard access."))?;
+        clipboard.set_clipboard_text(text).map_err(|e| anyhow!(e))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the window has keyboard focus.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_focus(&self) -> bool {
+        !self.focus_lost
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the ids of the connected controllers, in the order they were connected.
+    //---------------------------------------------------------------------------------------------
+    pub fn controllers(&self) -> &[u32] {
+        &self.controllers
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks whether a button is pressed on any controller.
+    //---------------------------------------------------------------------------------------------
+    pub fn controller_button_pressed(&self, button: ControllerButton) -> bool {
+        self.pressed_buttons.iter().any(|(_, pressed)| *pressed == button)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks whether a button has become pressed on any controller since the last reset.
+    //---------------------------------------------------------------------------------------------
+    pub fn controller_button_just_pressed(&self, button: ControllerButton) -> bool {
+        self.just_pressed_buttons.iter().any(|(_, pressed)| *pressed == button)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the mouse wheel scroll since the last reset (positive is away from the user).
+    //---------------------------------------------------------------------------------------------
+    pub fn mouse_wheel(&self) -> i32 {
+        self.mouse_wheel
+    }
+
     //---------------------------------------------------------------------------------------------
     // Checks whether a modifier key is pressed.
     //---------------------------------------------------------------------------------------------
@@ -383,6 +1011,41 @@ impl InputManager {
         self.just_pressed_keys.contains(&key)
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns a key other than a modifier that was just pressed this frame, if any.
+    // (the lowest keycode is returned if several were pressed)
+    //---------------------------------------------------------------------------------------------
+    pub fn just_pressed_key(&self) -> Option<InputKey> {
+        self.just_pressed_keys
+            .iter()
+            .copied()
+            .filter(|key| !Self::is_modifier(*key))
+            .min_by_key(|key| *key as InputKeycode)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the lowercase letters and digits of the keys just pressed this frame, sorted.
+    // (useful for type-to-search, not as a replacement for text input)
+    //---------------------------------------------------------------------------------------------
+    pub fn just_pressed_chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self
+            .just_pressed_keys
+            .iter()
+            .filter_map(|key| {
+                let name = key.name();
+                let mut name_chars = name.chars();
+
+                match (name_chars.next(), name_chars.next()) {
+                    (Some(c), None) if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        chars.sort_unstable();
+        chars
+    }
+
     //---------------------------------------------------------------------------------------------
     // Checks whether an action is pressed.
     //---------------------------------------------------------------------------------------------
@@ -422,17 +1085,291 @@ impl InputManager {
 
         // Insert the new action binding.
         self.action_bindings.insert(action, bindings.to_vec());
+        self.refresh_hotkey_conflicts();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the key bindings of an action, if it is bound.
+    //---------------------------------------------------------------------------------------------
+    pub fn action_bindings(&self, action: InputAction) -> Option<&[InputBinding]> {
+        self.action_bindings.get(&action).map(|bindings| bindings.as_slice())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the first action (in declaration order) whose bindings require a key, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn key_action(&self, key: InputKey) -> Option<InputAction> {
+        let binding = InputBinding::SpecificKey(key as InputKeycode);
+
+        InputAction::iter().find(|action| {
+            self.action_bindings.get(action).map_or(false, |bindings| bindings.contains(&binding))
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the key bindings overriding an action's bindings within a context, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn context_action_bindings(
+        &self,
+        context: InputContext,
+        action: InputAction,
+    ) -> Option<&[InputBinding]> {
+        self.context_bindings
+            .get(&context)
+            .and_then(|bindings| bindings.get(&action))
+            .map(|bindings| bindings.as_slice())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Update the key bindings for an action within a context, overriding the regular bindings
+    // while the context is active.
+    //---------------------------------------------------------------------------------------------
+    pub fn bind_context_action(
+        &mut self,
+        context: InputContext,
+        action: InputAction,
+        bindings: &[InputBinding],
+    ) {
+        // Do not bind empty key set.
+        if bindings.is_empty() {
+            debug_assert!(false);
+        }
+
+        // Insert the new action binding.
+        self.context_bindings.entry(context).or_default().insert(action, bindings.to_vec());
+        self.refresh_hotkey_conflicts();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the hotkey triggered by an input event, if any. The key that triggered it is hidden
+    // from the key and action state until it is released, so that scenes never see it.
+    // (should be called for every polled event before it is handled otherwise)
+    //---------------------------------------------------------------------------------------------
+    pub fn handle_hotkey(&mut self, event: &EngineEvent) -> Option<Hotkey> {
+        let hotkey = self.hotkeys.triggered_by(event)?;
+
+        if let EngineEvent::KeyDown { key, .. } = event {
+            self.hotkey_keys.insert(*key);
+        }
+
+        Some(hotkey)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the hotkeys, along with the bindings they conflict with.
+    //---------------------------------------------------------------------------------------------
+    pub fn hotkeys(&self) -> &Hotkeys {
+        &self.hotkeys
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Update the key bindings for a hotkey (an empty set of bindings unbinds it).
+    //---------------------------------------------------------------------------------------------
+    pub fn bind_hotkey(&mut self, hotkey: Hotkey, bindings: &[InputBinding]) {
+        self.hotkeys.bind(hotkey, bindings);
+        self.refresh_hotkey_conflicts();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that finds the bindings the hotkeys conflict with and logs them.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_hotkey_conflicts(&mut self) {
+        if self.hotkeys.refresh_conflicts(&self.action_bindings, &self.context_bindings) {
+            for conflict in self.hotkeys.conflicts() {
+                eprintln!("[Input] {}", conflict);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the repeat profile of an action (or the default profile if none is configured).
+    //---------------------------------------------------------------------------------------------
+    pub fn repeat_profile(&self, action: InputAction) -> RepeatProfile {
+        self.repeat_profiles.get(&action).copied().unwrap_or_default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the repeat profile of an action.
+    // (input repeats created from the previous profile must be updated with set_profile)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_repeat_profile(&mut self, action: InputAction, profile: RepeatProfile) {
+        self.repeat_profiles.insert(action, profile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the bound macros and the state of macro recording and replay.
+    //---------------------------------------------------------------------------------------------
+    pub fn macros(&self) -> &InputMacros {
+        &self.macros
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the macros mutably, e.g. for binding macros or recording.
+    //---------------------------------------------------------------------------------------------
+    pub fn macros_mut(&mut self) -> &mut InputMacros {
+        &mut self.macros
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the actions recorded when macro recording was last stopped by the record action, if
+    // they have not been bound or discarded yet.
+    //---------------------------------------------------------------------------------------------
+    pub fn recorded_macro(&self) -> Option<Vec<InputAction>> {
+        self.recorded_macro.borrow().clone()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Binds the recorded macro to a key and saves it to the user's keybindings file.
+    // (the macro can be replayed from the next update)
+    //---------------------------------------------------------------------------------------------
+    pub fn bind_recorded_macro(&self, key: InputKey) -> Result<()> {
+        let actions = self.recorded_macro().ok_or_else(|| anyhow!("No recorded macro."))?;
+        let input_macro = InputMacro { key: key as InputKeycode, actions };
+
+        // Save the bound macros as they will be after the next update.
+        let mut macros: Vec<InputMacro> = self
+            .macros
+            .macros()
+            .iter()
+            .filter(|bound| bound.key != input_macro.key)
+            .cloned()
+            .collect();
+        macros.push(input_macro.clone());
+        Self::save_macros(&macros)?;
+
+        // The macro stays recorded if it could not be saved.
+        self.discard_recorded_macro();
+        *self.pending_macro.borrow_mut() = Some(input_macro);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Discards the recorded macro without binding it.
+    //---------------------------------------------------------------------------------------------
+    pub fn discard_recorded_macro(&self) {
+        let _ = self.recorded_macro.borrow_mut().take();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that saves macros to the user's keybindings file (copying the shipped
+    // keybindings if necessary).
+    //---------------------------------------------------------------------------------------------
+    fn save_macros(macros: &[InputMacro]) -> Result<()> {
+        let source = PLATFORM_DIRS.config_file(CONFIG_KEYBINDINGS_FILE);
+        let json = fs::read_to_string(&source)
+            .with_context(|| format!("Failed to read keybindings file {}.", source.display()))?;
+        let mut json: Value =
+            serde_json::from_str(&json).context("Failed to parse keybindings json.")?;
+
+        // Files holding just the action bindings are converted to the full format.
+        if json.get("bindings").is_none() {
+            json = serde_json::json!({ "bindings": json });
+        }
+
+        let object = json.as_object_mut().ok_or_else(|| anyhow!("Invalid keybindings json."))?;
+        let _ = object.insert("macros".into(), serde_json::to_value(macros)?);
+
+        fs::create_dir_all(PLATFORM_DIRS.config_dir()).with_context(|| {
+            format!("Failed to create config directory {}.", PLATFORM_DIRS.config_dir().display())
+        })?;
+
+        let target = PLATFORM_DIRS.config_dir().join(CONFIG_KEYBINDINGS_FILE);
+        fs::write(&target, serde_json::to_string_pretty(&json)?)
+            .with_context(|| format!("Failed to write keybindings file {}.", target.display()))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the macro action injected this update, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn macro_step(&self) -> Option<InputAction> {
+        self.macro_step
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops the macro being replayed, e.g. because its current action could not be performed.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel_macro(&self) {
+        self.macro_cancelled.set(true);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the current input context.
+    //---------------------------------------------------------------------------------------------
+    pub fn context(&self) -> InputContext {
+        self.contexts.last().copied().unwrap_or(InputContext::Map)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes an input context, making it current.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_context(&mut self, context: InputContext) {
+        self.contexts.push(context);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pops the current input context, returning it.
+    //---------------------------------------------------------------------------------------------
+    pub fn pop_context(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of input contexts on the stack.
+    //---------------------------------------------------------------------------------------------
+    pub fn context_depth(&self) -> usize {
+        self.contexts.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pops input contexts until the stack is no deeper than a depth.
+    //---------------------------------------------------------------------------------------------
+    pub fn truncate_contexts(&mut self, depth: usize) {
+        self.contexts.truncate(depth);
     }
 
     //---------------------------------------------------------------------------------------------
     // Set the current cursor.
     //---------------------------------------------------------------------------------------------
     pub fn set_cursor(&self, cursor: Cursor) {
-        self.cursors[cursor as usize].set();
+        if self.cursor.replace(Some(cursor)) != Some(cursor) {
+            self.cursors[cursor as usize].set();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the cursor style currently shown.
+    //---------------------------------------------------------------------------------------------
+    pub fn cursor(&self) -> Cursor {
+        self.cursor.get().unwrap_or(Cursor::Arrow)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns to the cursor requested by the hovered region or current context.
+    // Equivalent to calling set_cursor with Cursor::Arrow when neither requests one.
+    //---------------------------------------------------------------------------------------------
+    pub fn reset_cursor(&self) {
+        self.set_cursor(self.requested_cursor().unwrap_or(Cursor::Arrow));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the software cursor.
+    //---------------------------------------------------------------------------------------------
+    pub fn software_cursor(&self) -> &SoftwareCursor {
+        &self.software_cursor
     }
 
     //---------------------------------------------------------------------------------------------
-    // Equivalent to calling set_cursor with CursorStyle::Arrow.
+    // Returns the software cursor mutably, e.g. for enabling it or replacing its sprites.
     //---------------------------------------------------------------------------------------------
-    pub fn reset_cursor(&self) {}
+    pub fn software_cursor_mut(&mut self) -> &mut SoftwareCursor {
+        &mut self.software_cursor
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position (in pixels, relative to the terminal) and tile of the software cursor
+    // if it is enabled and the mouse is over the terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn software_cursor_placement(&self) -> Option<((f32, f32), Tile)> {
+        self.mouse_world_coord.and_then(|xy| self.software_cursor.placement(self.cursor(), xy))
+    }
 }
@@ -3,6 +3,11 @@
 //-------------------------------------------------------------------------------------------------
 use std::time::Duration;
 
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use serde_derive::{Deserialize, Serialize};
+
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
@@ -13,6 +18,45 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 use crate::input_manager::*;
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Default repeat profile values.
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(250);
+const DEFAULT_REPEAT_RATE: Duration = Duration::from_millis(100);
+const DEFAULT_MIN_REPEAT_RATE: Duration = Duration::from_millis(16);
+
+//-------------------------------------------------------------------------------------------------
+// Describes how a held input repeats. Profiles are configured per action in the keybindings.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RepeatProfile {
+    // Duration between the press and the first repeat (the repeat rate is used if none).
+    pub initial_delay: Option<Duration>,
+    // Duration between repeats while the input is held.
+    pub repeat_rate: Duration,
+    // Multiplier applied to the repeat rate after each repeat (below 1.0 accelerates).
+    pub acceleration: f32,
+    // Lower bound on the repeat rate when accelerating.
+    pub min_repeat_rate: Duration,
+}
+
+impl Default for RepeatProfile {
+    //---------------------------------------------------------------------------------------------
+    // Returns a profile with a short initial delay and a constant repeat rate.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self {
+            initial_delay: Some(DEFAULT_INITIAL_DELAY),
+            repeat_rate: DEFAULT_REPEAT_RATE,
+            acceleration: 1.0,
+            min_repeat_rate: DEFAULT_MIN_REPEAT_RATE,
+        }
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Represents the possible states of the input repeat.
 //-------------------------------------------------------------------------------------------------
@@ -51,6 +95,10 @@ pub struct InputRepeat {
     pub held_step: Duration,
     // Duration between when the input is pressed and the first pressed event.
     pub initial_step: Option<Duration>,
+    // Multiplier applied to the held step after each held pressed event (below 1.0 accelerates).
+    pub acceleration: f32,
+    // Lower bound on the held step when accelerating.
+    pub min_held_step: Duration,
 }
 
 impl InputRepeat {
@@ -70,6 +118,8 @@ impl InputRepeat {
             to_repeat: InputToRepeat::Key(key),
             held_step,
             initial_step,
+            acceleration: 1.0,
+            min_held_step: held_step,
         }
     }
 
@@ -93,6 +143,8 @@ impl InputRepeat {
             to_repeat: InputToRepeat::Action(action),
             held_step,
             initial_step,
+            acceleration: 1.0,
+            min_held_step: held_step,
         }
     }
 
@@ -116,9 +168,32 @@ impl InputRepeat {
             to_repeat: InputToRepeat::Mouse(mouse),
             held_step,
             initial_step,
+            acceleration: 1.0,
+            min_held_step: held_step,
         }
     }
 
+    //-----------------------------------------------------------------------------------------------
+    // Creates a new input repeat for an input action with a repeat profile.
+    // (see InputManager::repeat_profile for the configured profile of an action)
+    //-----------------------------------------------------------------------------------------------
+    pub fn for_action_with_profile(action: InputAction, profile: &RepeatProfile) -> Self {
+        let mut repeat = Self::for_action(action, profile.repeat_rate, profile.initial_delay);
+        repeat.set_profile(profile);
+        repeat
+    }
+
+    //-----------------------------------------------------------------------------------------------
+    // Applies a repeat profile, resetting the state of the input repeat.
+    //-----------------------------------------------------------------------------------------------
+    pub fn set_profile(&mut self, profile: &RepeatProfile) {
+        self.held_step = profile.repeat_rate;
+        self.initial_step = profile.initial_delay;
+        self.acceleration = profile.acceleration;
+        self.min_held_step = profile.min_repeat_rate.min(profile.repeat_rate);
+        self.reset();
+    }
+
     //-----------------------------------------------------------------------------------------------
     // Sets the input repeat to track an input key.
     //-----------------------------------------------------------------------------------------------
@@ -256,8 +331,17 @@ impl InputRepeat {
             }
         }
 
-        // Otherwise, return the timer state.
-        self.timer.update(dt)
+        // Otherwise, return the timer state, accelerating the held step if it has passed.
+        if !self.timer.update(dt) {
+            return false;
+        }
+
+        if self.acceleration != 1.0 {
+            self.timer.interval =
+                self.timer.interval.mul_f32(self.acceleration.max(0.0)).max(self.min_held_step);
+        }
+
+        true
     }
 
     //-----------------------------------------------------------------------------------------------
@@ -0,0 +1,191 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::{FnvHashMap, FnvHashSet};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type InteractionId = u32;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the shapes of hot regions in the interaction map.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub enum InteractionRegion {
+    // A rectangle of cells.
+    Rect(Rect),
+    // An arbitrary set of cells.
+    Cells(FnvHashSet<ICoord>),
+}
+
+impl InteractionRegion {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the region contains a cell.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, xy: ICoord) -> bool {
+        match self {
+            InteractionRegion::Rect(rect) => rect.contains(xy),
+            InteractionRegion::Cells(cells) => cells.contains(&xy),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the events emitted when the mouse interacts with hot regions.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionEvent {
+    // The mouse moved into a region.
+    HoverEnter(InteractionId),
+    // The mouse moved out of a region (or the region was removed).
+    HoverLeave(InteractionId),
+    // A mouse button was clicked over a region.
+    Click(InteractionId, InputMouse),
+}
+
+//-------------------------------------------------------------------------------------------------
+// InteractionMap holds the hot regions registered by scenes and widgets and resolves the mouse
+// coord against them, so that hit testing is done in a single place.
+// Regions registered later are on top of (and take priority over) earlier regions.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct InteractionMap {
+    // Registered regions, from bottom to top.
+    regions: Vec<(InteractionId, InteractionRegion)>,
+    // Id of the region the mouse is currently over.
+    hovered: Option<InteractionId>,
+    // Cursors requested while hovering regions, by id.
+    cursors: FnvHashMap<InteractionId, Cursor>,
+}
+
+impl InteractionMap {
+    //---------------------------------------------------------------------------------------------
+    // Registers a region with an id, replacing any region with the same id.
+    //---------------------------------------------------------------------------------------------
+    pub fn register(&mut self, id: InteractionId, region: InteractionRegion) {
+        self.unregister(id);
+        self.regions.push((id, region));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Registers a rectangular region with an id.
+    //---------------------------------------------------------------------------------------------
+    pub fn register_rect(&mut self, id: InteractionId, rect: Rect) {
+        self.register(id, InteractionRegion::Rect(rect));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Registers a region of individual cells with an id.
+    //---------------------------------------------------------------------------------------------
+    pub fn register_cells(&mut self, id: InteractionId, cells: &[ICoord]) {
+        self.register(id, InteractionRegion::Cells(cells.iter().copied().collect()));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the region with an id, returning whether it was found.
+    //---------------------------------------------------------------------------------------------
+    pub fn unregister(&mut self, id: InteractionId) -> bool {
+        let _ = self.cursors.remove(&id);
+        let len = self.regions.len();
+        self.regions.retain(|(region_id, _)| *region_id != id);
+        self.regions.len() != len
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all regions (no hover leave event is emitted for the hovered region).
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.regions.clear();
+        self.cursors.clear();
+        self.hovered = None;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the cursor shown while the mouse is over the region with an id (or none).
+    // (registering or unregistering the region clears its cursor)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_cursor(&mut self, id: InteractionId, cursor: Option<Cursor>) {
+        match cursor {
+            Some(cursor) => {
+                let _ = self.cursors.insert(id, cursor);
+            }
+            None => {
+                let _ = self.cursors.remove(&id);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the cursor shown while the mouse is over the region with an id, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn cursor(&self, id: InteractionId) -> Option<Cursor> {
+        self.cursors.get(&id).copied()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the cursor requested by the region the mouse is currently over, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn hovered_cursor(&self) -> Option<Cursor> {
+        self.hovered.and_then(|id| self.cursor(id))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the topmost region containing a cell, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn region_at(&self, xy: ICoord) -> Option<InteractionId> {
+        self.regions.iter().rev().find(|(_, region)| region.contains(xy)).map(|(id, _)| *id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the region the mouse is currently over, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn hovered(&self) -> Option<InteractionId> {
+        self.hovered
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resolves the mouse coord and clicked (left, right) buttons against the regions, pushing
+    // any resulting events.
+    //---------------------------------------------------------------------------------------------
+    pub fn resolve(
+        &mut self,
+        mouse_coord: Option<ICoord>,
+        clicked: (bool, bool),
+        events: &mut Vec<InteractionEvent>,
+    ) {
+        let hovered = mouse_coord.and_then(|xy| self.region_at(xy));
+
+        // Emit hover events if the hovered region changed.
+        if hovered != self.hovered {
+            if let Some(id) = self.hovered {
+                events.push(InteractionEvent::HoverLeave(id));
+            }
+            if let Some(id) = hovered {
+                events.push(InteractionEvent::HoverEnter(id));
+            }
+
+            self.hovered = hovered;
+        }
+
+        // Emit click events for the hovered region.
+        if let Some(id) = hovered {
+            if clicked.0 {
+                events.push(InteractionEvent::Click(id, InputMouse::Left));
+            }
+            if clicked.1 {
+                events.push(InteractionEvent::Click(id, InputMouse::Right));
+            }
+        }
+    }
+}
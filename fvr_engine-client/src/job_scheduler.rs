@@ -0,0 +1,292 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Default time budget per frame for running jobs.
+pub const DEFAULT_JOB_BUDGET: Duration = Duration::from_millis(4);
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type JobId = u32;
+pub type JobProgressHook = Box<dyn FnMut(JobId, &str, f32)>;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the results of running a single step of a job.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobStep {
+    // The job has more work to do, and has reached a progress in the range [0.0, 1.0].
+    Continue(f32),
+    // The job is waiting on work done elsewhere (e.g. on a worker thread) and has reached a
+    // progress in the range [0.0, 1.0]. The scheduler stops until the next update.
+    Wait(f32),
+    // The job is complete.
+    Complete,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Job describes a long running operation that is split into small incremental steps.
+// Each call to step should do a small amount of work and return, so that the scheduler can stop
+// between steps once the frame's time budget has been spent.
+// Steps are given mutable access to a context owned by whoever updates the scheduler (e.g. the
+// server for zone generation and saving).
+//-------------------------------------------------------------------------------------------------
+pub trait Job<C = ()> {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the job (used for reporting progress).
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &str;
+
+    //---------------------------------------------------------------------------------------------
+    // Runs the next step of the job.
+    //---------------------------------------------------------------------------------------------
+    fn step(&mut self, context: &mut C) -> Result<JobStep>;
+}
+
+//-------------------------------------------------------------------------------------------------
+// FnJob adapts a closure into a job, allowing for coroutine-style jobs that keep their state in
+// captured variables.
+//-------------------------------------------------------------------------------------------------
+pub struct FnJob<F> {
+    // Name of the job.
+    name: String,
+    // Closure called for each step.
+    step_fn: F,
+}
+
+impl<F> FnJob<F> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new job from a closure.
+    //---------------------------------------------------------------------------------------------
+    pub fn new<S: Into<String>>(name: S, step_fn: F) -> Self {
+        Self { name: name.into(), step_fn }
+    }
+}
+
+impl<C, F> Job<C> for FnJob<F>
+where
+    F: FnMut(&mut C) -> Result<JobStep>,
+{
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the job.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs the next step of the job by calling the closure.
+    //---------------------------------------------------------------------------------------------
+    fn step(&mut self, context: &mut C) -> Result<JobStep> {
+        (self.step_fn)(context)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes a job that has finished running.
+//-------------------------------------------------------------------------------------------------
+pub struct JobOutcome {
+    // Id of the job.
+    pub id: JobId,
+    // Name of the job.
+    pub name: String,
+    // Result of the job (the error of the failed step if the job failed).
+    pub result: Result<()>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper struct for holding a job and its latest progress.
+//-------------------------------------------------------------------------------------------------
+struct ScheduledJob<C> {
+    // Id of the job.
+    id: JobId,
+    // The job.
+    job: Box<dyn Job<C>>,
+    // Latest progress reported by the job.
+    progress: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// JobScheduler runs jobs incrementally on the main thread within a per-frame time budget.
+// Jobs are run in the order they were pushed. At least one step is run per update (so that jobs
+// always make progress), after which steps are run until the budget has been spent.
+//-------------------------------------------------------------------------------------------------
+pub struct JobScheduler<C = ()> {
+    // Queue of unfinished jobs.
+    jobs: VecDeque<ScheduledJob<C>>,
+    // Time budget per update.
+    budget: Duration,
+    // Id to give the next pushed job.
+    next_id: JobId,
+    // Optional hook called whenever a job reports progress.
+    progress_hook: Option<JobProgressHook>,
+}
+
+impl<C> Default for JobScheduler<C> {
+    //---------------------------------------------------------------------------------------------
+    // Default impl.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self::new(DEFAULT_JOB_BUDGET)
+    }
+}
+
+impl<C> JobScheduler<C> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new job scheduler with a time budget per update.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(budget: Duration) -> Self {
+        Self { jobs: VecDeque::new(), budget, next_id: 0, progress_hook: None }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the time budget per update.
+    //---------------------------------------------------------------------------------------------
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the time budget per update.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the hook called with (id, name, progress) whenever a job reports progress.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_progress_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(JobId, &str, f32) + 'static,
+    {
+        self.progress_hook = Some(Box::new(hook));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the progress hook.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear_progress_hook(&mut self) {
+        self.progress_hook = None;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes a job onto the queue, returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn push(&mut self, job: Box<dyn Job<C>>) -> JobId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.jobs.push_back(ScheduledJob { id, job, progress: 0.0 });
+        id
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes a closure as a job onto the queue, returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_fn<S, F>(&mut self, name: S, step_fn: F) -> JobId
+    where
+        S: Into<String>,
+        F: FnMut(&mut C) -> Result<JobStep> + 'static,
+        C: 'static,
+    {
+        self.push(Box::new(FnJob::new(name, step_fn)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Cancels a job, returning whether it was found.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel(&mut self, id: JobId) -> bool {
+        match self.jobs.iter().position(|j| j.id == id) {
+            Some(index) => self.jobs.remove(index).is_some(),
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the latest progress of a job, or None if the job is not queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn progress(&self, id: JobId) -> Option<f32> {
+        self.jobs.iter().find(|j| j.id == id).map(|j| j.progress)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name and latest progress of the job currently being run, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn current(&self) -> Option<(&str, f32)> {
+        self.jobs.front().map(|j| (j.job.name(), j.progress))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of unfinished jobs.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no unfinished jobs.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs job steps with a context until the time budget is spent, a job is waiting or the queue
+    // is empty.
+    // Returns the outcomes of any jobs that finished during the update.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, context: &mut C) -> Vec<JobOutcome> {
+        let start = Instant::now();
+        let mut outcomes = Vec::new();
+
+        while let Some(scheduled) = self.jobs.front_mut() {
+            let result = scheduled.job.step(context);
+            let waiting = matches!(result, Ok(JobStep::Wait(_)));
+
+            // Update the progress and remove the job if it has finished.
+            let finished = match result {
+                Ok(JobStep::Continue(progress)) | Ok(JobStep::Wait(progress)) => {
+                    scheduled.progress = progress.clamp(0.0, 1.0);
+                    None
+                }
+                Ok(JobStep::Complete) => {
+                    scheduled.progress = 1.0;
+                    Some(Ok(()))
+                }
+                Err(e) => Some(Err(e)),
+            };
+
+            if let Some(hook) = self.progress_hook.as_mut() {
+                hook(scheduled.id, scheduled.job.name(), scheduled.progress);
+            }
+
+            if let Some(result) = finished {
+                let scheduled = self.jobs.pop_front().expect("Job queue unexpectedly empty.");
+                outcomes.push(JobOutcome {
+                    id: scheduled.id,
+                    name: scheduled.job.name().into(),
+                    result,
+                });
+            }
+
+            // Stop once the budget has been spent (or the job is waiting).
+            if waiting || start.elapsed() >= self.budget {
+                break;
+            }
+        }
+
+        outcomes
+    }
+}
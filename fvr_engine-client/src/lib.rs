@@ -1,20 +1,63 @@
+mod ambient_animator;
+mod audio;
 mod client;
 mod debug_gui;
+mod dynamic_glyphs;
+mod effects;
+mod engine_event;
+mod floating_text;
 #[macro_use]
 mod gl_helpers;
+mod gpu_profile;
+mod hotkeys;
+mod input_context;
+mod input_macro;
 mod input_manager;
 mod input_repeat;
+mod interaction_map;
+mod job_scheduler;
+mod particles;
+mod pixel_plot;
+mod render_graph;
+mod render_stats;
 mod renderer_v2;
-mod shader_strings;
+mod shader_manager;
+mod software_cursor;
 mod terminal;
+mod terminal_draw;
+mod tile_image;
+mod tile_layer;
+mod vertex_builder;
+mod virtual_cursor;
 
 mod widgets;
 
 pub mod prelude {
+    pub use crate::ambient_animator::*;
+    pub use crate::audio::*;
     pub use crate::client::*;
+    pub use crate::effects::*;
+    pub use crate::engine_event::*;
+    pub use crate::floating_text::*;
+    pub use crate::gpu_profile::*;
+    pub use crate::hotkeys::*;
+    pub use crate::input_context::*;
+    pub use crate::input_macro::*;
     pub use crate::input_manager::*;
     pub use crate::input_repeat::*;
+    pub use crate::interaction_map::*;
+    pub use crate::job_scheduler::*;
+    pub use crate::particles::*;
+    pub use crate::pixel_plot::*;
+    pub use crate::render_graph::*;
+    pub use crate::render_stats::*;
+    pub use crate::renderer_v2::*;
+    pub use crate::software_cursor::*;
     pub use crate::terminal::*;
+    pub use crate::terminal_draw::*;
+    pub use crate::tile_image::*;
+    pub use crate::tile_layer::*;
+    pub use crate::virtual_cursor::*;
 
     pub use crate::widgets::prelude::*;
 }
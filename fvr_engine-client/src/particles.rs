@@ -0,0 +1,359 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::f32::consts::PI;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+use rand::{thread_rng, Rng as _};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Max # of live particles. Emitting past it spawns fewer particles.
+const MAX_PARTICLES: usize = 4096;
+
+//-------------------------------------------------------------------------------------------------
+// ParticleBlend enumerates how a particle is blended into the tile it is over.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleBlend {
+    // Replaces the glyph and blends the foreground color.
+    Foreground,
+    // Blends the background color, leaving the glyph.
+    Background,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ParticleEmitter describes a burst of particles. Ranges are sampled for each particle, with
+// positions and velocities in tiles (and tiles per second).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParticleEmitter {
+    // # of particles in the burst.
+    pub count: usize,
+    // Glyphs picked from for each particle (ignored when blended into the background).
+    pub glyphs: &'static [char],
+    // Max distance of a particle's spawn position from the origin on each axis.
+    pub spread: (f32, f32),
+    // Range of the initial speed.
+    pub speed: (f32, f32),
+    // Range of the initial direction, in radians (0 is east and PI / 2 is south).
+    pub angle: (f32, f32),
+    // Acceleration added to the y velocity every second (negative rises).
+    pub gravity: f32,
+    // Range of the lifetime.
+    pub lifetime: (Duration, Duration),
+    // Colors at the start and end of a particle's lifetime.
+    pub colors: (TileColor, TileColor),
+    // Opacities at the start and end of a particle's lifetime.
+    pub opacity: (f32, f32),
+    // How the particles are blended into the tiles.
+    pub blend: ParticleBlend,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ParticlePreset enumerates the built-in kinds of particle bursts.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticlePreset {
+    // Bright, fast sparks that fall and cool.
+    Sparks,
+    // Slow, rising smoke that darkens the background.
+    Smoke,
+    // Streaks of rain falling across a wide area.
+    Rain,
+    // Drops of blood sprayed out and falling.
+    BloodSplatter,
+}
+
+impl ParticlePreset {
+    //---------------------------------------------------------------------------------------------
+    // Returns the emitter of the preset.
+    //---------------------------------------------------------------------------------------------
+    pub fn emitter(&self) -> ParticleEmitter {
+        match self {
+            ParticlePreset::Sparks => ParticleEmitter {
+                count: 16,
+                glyphs: &['*', '\'', '`', '.'],
+                spread: (0.0, 0.0),
+                speed: (6.0, 14.0),
+                angle: (PI, 2.0 * PI),
+                gravity: 24.0,
+                lifetime: (Duration::from_millis(200), Duration::from_millis(500)),
+                colors: (PaletteColor::Yellow.const_into(), PaletteColor::DarkRed.const_into()),
+                opacity: (1.0, 0.5),
+                blend: ParticleBlend::Foreground,
+            },
+            ParticlePreset::Smoke => ParticleEmitter {
+                count: 10,
+                glyphs: &[],
+                spread: (0.5, 0.0),
+                speed: (0.5, 1.5),
+                angle: (1.25 * PI, 1.75 * PI),
+                gravity: -1.0,
+                lifetime: (Duration::from_millis(1000), Duration::from_millis(2000)),
+                colors: (PaletteColor::DarkGrey.const_into(), PaletteColor::Black.const_into()),
+                opacity: (0.6, 0.0),
+                blend: ParticleBlend::Background,
+            },
+            ParticlePreset::Rain => ParticleEmitter {
+                count: 48,
+                glyphs: &['|', '\'', '.'],
+                spread: (16.0, 8.0),
+                speed: (16.0, 20.0),
+                angle: (0.55 * PI, 0.6 * PI),
+                gravity: 0.0,
+                lifetime: (Duration::from_millis(150), Duration::from_millis(400)),
+                colors: (
+                    PaletteColor::BrightBlue.const_into(),
+                    PaletteColor::DarkBlue.const_into(),
+                ),
+                opacity: (0.8, 0.3),
+                blend: ParticleBlend::Foreground,
+            },
+            ParticlePreset::BloodSplatter => ParticleEmitter {
+                count: 12,
+                glyphs: &['.', ',', '\'', ':'],
+                spread: (0.0, 0.0),
+                speed: (3.0, 8.0),
+                angle: (0.0, 2.0 * PI),
+                gravity: 12.0,
+                lifetime: (Duration::from_millis(250), Duration::from_millis(600)),
+                colors: (PaletteColor::BrightRed.const_into(), PaletteColor::DarkRed.const_into()),
+                opacity: (1.0, 0.8),
+                blend: ParticleBlend::Foreground,
+            },
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Particle is a short-lived glyph moving over the terminal.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Particle {
+    // Position, in tiles.
+    xy: (f32, f32),
+    // Velocity, in tiles per second.
+    velocity: (f32, f32),
+    // Acceleration added to the y velocity every second.
+    gravity: f32,
+    // Time since the particle was emitted.
+    age: Duration,
+    // Time the particle lives for.
+    lifetime: Duration,
+    // Glyph of the particle.
+    glyph: char,
+    // Colors at the start and end of the particle's lifetime.
+    colors: (TileColor, TileColor),
+    // Opacities at the start and end of the particle's lifetime.
+    opacity: (f32, f32),
+    // How the particle is blended into the tiles.
+    blend: ParticleBlend,
+}
+
+impl Particle {
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord of the tile the particle is over.
+    //---------------------------------------------------------------------------------------------
+    fn tile_xy(&self) -> ICoord {
+        (self.xy.0.floor() as i32, self.xy.1.floor() as i32)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Blends the particle into a tile.
+    //---------------------------------------------------------------------------------------------
+    fn apply(&self, tile: &mut Tile) {
+        let t = (self.age.as_secs_f32() / self.lifetime.as_secs_f32()).min(1.0);
+        let color = self.colors.0.lerp(&self.colors.1, t);
+        let opacity = self.opacity.0 + (self.opacity.1 - self.opacity.0) * t;
+
+        match self.blend {
+            ParticleBlend::Foreground => {
+                tile.glyph = self.glyph;
+                tile.foreground_color = tile.foreground_color.lerp(&color, opacity);
+                tile.foreground_opacity = tile.foreground_opacity.max(opacity);
+            }
+            ParticleBlend::Background => {
+                tile.background_color = tile.background_color.lerp(&color, opacity);
+            }
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ParticleSystem runs the particles emitted over the terminal. Like the terminal's effects, the
+// client advances them by the render delta time and composites them onto the tiles only for as
+// long as it syncs the renderer. Particles composite in emission order, over the effects.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ParticleSystem {
+    // The live particles.
+    particles: Vec<Particle>,
+    // Source of the particles' randomness.
+    rng: Rng,
+    // Tiles overwritten by compositing, to restore afterwards.
+    overwritten: FnvHashMap<ICoord, Tile>,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::with_seed(thread_rng().gen())
+    }
+}
+
+impl ParticleSystem {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new particle system with no particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new particle system whose particles are randomized from a seed.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_seed(seed: u64) -> Self {
+        Self { particles: Vec::new(), rng: Rng::new(seed), overwritten: FnvHashMap::default() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Emits a burst of particles from a preset at a coord of the terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn emit(&mut self, preset: ParticlePreset, origin: ICoord) {
+        self.emit_with(&preset.emitter(), origin);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Emits a burst of particles from an emitter at a coord of the terminal.
+    // Nothing is emitted while motion is reduced.
+    //---------------------------------------------------------------------------------------------
+    pub fn emit_with(&mut self, emitter: &ParticleEmitter, origin: ICoord) {
+        if !Accessibility::motion_enabled() {
+            return;
+        }
+
+        let count = emitter.count.min(MAX_PARTICLES - self.particles.len());
+        let rng = &mut self.rng;
+        let mut sample = |(min, max): (f32, f32)| {
+            if min < max {
+                rng.gen_range(min..max)
+            } else {
+                min
+            }
+        };
+
+        for _ in 0..count {
+            let x = origin.0 as f32 + 0.5 + sample((-emitter.spread.0, emitter.spread.0));
+            let y = origin.1 as f32 + 0.5 + sample((-emitter.spread.1, emitter.spread.1));
+            let speed = sample(emitter.speed);
+            let angle = sample(emitter.angle);
+            let lifetime =
+                sample((emitter.lifetime.0.as_secs_f32(), emitter.lifetime.1.as_secs_f32()));
+            let glyph = match emitter.glyphs.len() {
+                0 => ' ',
+                len => emitter.glyphs[sample((0.0, len as f32)) as usize % len],
+            };
+
+            self.particles.push(Particle {
+                xy: (x, y),
+                velocity: (angle.cos() * speed, angle.sin() * speed),
+                gravity: emitter.gravity,
+                age: Duration::default(),
+                lifetime: Duration::from_secs_f32(lifetime),
+                glyph,
+                colors: emitter.colors,
+                opacity: emitter.opacity,
+                blend: emitter.blend,
+            });
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of live particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no live particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves and ages the particles by a frame delta time (scaled per the accessibility options),
+    // removing the expired particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) {
+        if self.particles.is_empty() {
+            return;
+        }
+
+        let dt = Accessibility::animation_dt(dt);
+        let secs = dt.as_secs_f32();
+
+        for particle in self.particles.iter_mut() {
+            particle.velocity.1 += particle.gravity * secs;
+            particle.xy.0 += particle.velocity.0 * secs;
+            particle.xy.1 += particle.velocity.1 * secs;
+            particle.age += dt;
+        }
+
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Composites the particles onto the tiles of a map, remembering the tiles they overwrote.
+    // Must be followed by a restore before the map is otherwise used.
+    //---------------------------------------------------------------------------------------------
+    pub fn composite<M>(&mut self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        for particle in self.particles.iter() {
+            let xy = particle.tile_xy();
+
+            if !map.in_bounds(xy) {
+                continue;
+            }
+
+            let tile = map.get_xy_mut(xy);
+            self.overwritten.entry(xy).or_insert(*tile);
+            particle.apply(tile);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores the tiles of a map overwritten by the last composite.
+    //---------------------------------------------------------------------------------------------
+    pub fn restore<M>(&mut self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        for (xy, tile) in self.overwritten.drain() {
+            if map.in_bounds(xy) {
+                *map.get_xy_mut(xy) = tile;
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// First codepoint of the unicode braille patterns block (no dots raised).
+const BRAILLE_BASE_CODEPOINT: u32 = 0x2800;
+
+// Bit for each dot in a braille cell, indexed by [y][x].
+const BRAILLE_DOT_BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// Half-block glyphs (all of which are present in CP437).
+const HALF_BLOCK_EMPTY: char = ' ';
+const HALF_BLOCK_UPPER: char = '▀';
+const HALF_BLOCK_LOWER: char = '▄';
+const HALF_BLOCK_FULL: char = '█';
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the ways pseudo-pixels can be packed into a single tile.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelPlotMode {
+    // 2x4 pixels per tile using braille glyphs.
+    // NOTE: The font atlas must contain the braille block (U+2800..U+28FF).
+    Braille,
+    // 1x2 pixels per tile using half-block glyphs.
+    HalfBlock,
+}
+
+impl PixelPlotMode {
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of pixels covered by a single tile.
+    //---------------------------------------------------------------------------------------------
+    pub const fn cell_dimensions(&self) -> ICoord {
+        match self {
+            PixelPlotMode::Braille => (2, 4),
+            PixelPlotMode::HalfBlock => (1, 2),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of tiles needed to plot a buffer of pixels.
+    //---------------------------------------------------------------------------------------------
+    pub fn tile_dimensions(&self, (width, height): ICoord) -> ICoord {
+        let (cell_width, cell_height) = self.cell_dimensions();
+        ((width + cell_width - 1) / cell_width, (height + cell_height - 1) / cell_height)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// PixelPlot provides a static API for plotting higher resolution buffers onto a Map2d<Tile>.
+// Pixels are greyscale values in the range [0.0..1.0]. Pixels at or above the threshold are lit
+// and the foreground opacity of each tile is the average intensity of its lit pixels.
+//-------------------------------------------------------------------------------------------------
+pub struct PixelPlot;
+
+impl PixelPlot {
+    //---------------------------------------------------------------------------------------------
+    // Returns the intensity of a pixel, treating out of bounds pixels as unlit.
+    //---------------------------------------------------------------------------------------------
+    fn intensity(pixels: &GridMap<f32>, xy: ICoord) -> f32 {
+        if pixels.in_bounds(xy) {
+            *pixels.get_xy(xy)
+        } else {
+            0.0
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Finds the glyph and average lit intensity for the tile covering a cell of pixels.
+    //---------------------------------------------------------------------------------------------
+    fn cell_glyph(
+        pixels: &GridMap<f32>,
+        (px, py): ICoord,
+        mode: PixelPlotMode,
+        threshold: f32,
+    ) -> (char, f32) {
+        let (cell_width, cell_height) = mode.cell_dimensions();
+        let mut bits = 0;
+        let mut lit = 0;
+        let mut total = 0.0;
+
+        for y in 0..cell_height {
+            for x in 0..cell_width {
+                let value = Self::intensity(pixels, (px + x, py + y));
+
+                if value < threshold {
+                    continue;
+                }
+
+                lit += 1;
+                total += value;
+                bits |= match mode {
+                    PixelPlotMode::Braille => BRAILLE_DOT_BITS[y as usize][x as usize],
+                    PixelPlotMode::HalfBlock => 1 << y,
+                };
+            }
+        }
+
+        let glyph = match mode {
+            PixelPlotMode::Braille => {
+                std::char::from_u32(BRAILLE_BASE_CODEPOINT + bits).unwrap_or(HALF_BLOCK_EMPTY)
+            }
+            PixelPlotMode::HalfBlock => match bits {
+                0 => HALF_BLOCK_EMPTY,
+                1 => HALF_BLOCK_UPPER,
+                2 => HALF_BLOCK_LOWER,
+                _ => HALF_BLOCK_FULL,
+            },
+        };
+
+        let average = if lit > 0 { total / lit as f32 } else { 0.0 };
+        (glyph, average.min(1.0))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Plots a greyscale pixel buffer onto a map with its top-left tile at origin.
+    //---------------------------------------------------------------------------------------------
+    pub fn plot<M>(
+        map: &mut M,
+        origin: ICoord,
+        pixels: &GridMap<f32>,
+        mode: PixelPlotMode,
+        threshold: f32,
+        color: TileColor,
+    ) where
+        M: Map2d<Tile>,
+    {
+        let (cell_width, cell_height) = mode.cell_dimensions();
+        let (width, height) = mode.tile_dimensions(pixels.dimensions());
+
+        for y in 0..height {
+            for x in 0..width {
+                let xy = (origin.0 + x, origin.1 + y);
+
+                if !map.in_bounds(xy) {
+                    continue;
+                }
+
+                let (glyph, intensity) =
+                    Self::cell_glyph(pixels, (x * cell_width, y * cell_height), mode, threshold);
+
+                let tile = map.get_xy_mut(xy);
+                tile.glyph = glyph;
+                tile.layout = TileLayout::Center;
+                tile.foreground_color = color;
+                tile.foreground_opacity = intensity;
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Plots a boolean pixel buffer onto a map with its top-left tile at origin.
+    //---------------------------------------------------------------------------------------------
+    pub fn plot_bool<M>(
+        map: &mut M,
+        origin: ICoord,
+        pixels: &GridMap<bool>,
+        mode: PixelPlotMode,
+        color: TileColor,
+    ) where
+        M: Map2d<Tile>,
+    {
+        let mut greyscale = GridMap::new(pixels.dimensions());
+
+        for (value, lit) in greyscale.data_mut().iter_mut().zip(pixels.data().iter()) {
+            *value = if *lit { 1.0 } else { 0.0 };
+        }
+
+        Self::plot(map, origin, &greyscale, mode, 0.5, color);
+    }
+}
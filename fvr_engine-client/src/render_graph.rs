@@ -0,0 +1,256 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Resources produced outside of the render passes (by syncing with the terminal).
+const EXTERNAL_RESOURCES: [PassResource; 3] =
+    [PassResource::BackgroundQuads, PassResource::ForegroundQuads, PassResource::TextQuads];
+
+//-------------------------------------------------------------------------------------------------
+// Resources read and written by the render passes.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PassResource {
+    // Colored quads of the tile backgrounds (uploaded by the terminal sync).
+    BackgroundQuads,
+    // Textured quads of the glyphs and outlines (uploaded by the terminal sync).
+    ForegroundQuads,
+    // Textured quads of the proportional text runs (uploaded by the terminal sync).
+    TextQuads,
+    // The frame being rendered.
+    Frame,
+}
+
+//-------------------------------------------------------------------------------------------------
+// The draws a render pass can perform. Each kind appears in a render graph at most once.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderPassKind {
+    // Clears the frame to the clear color.
+    Clear,
+    // Draws the solid colored background quads.
+    Background,
+    // Draws the regular and outline glyph quads.
+    Foreground,
+    // Draws the proportional text quads.
+    Text,
+    // Darkens the edges of the frame.
+    Vignette,
+    // Debug view that replaces the frame with every quad drawn additively in a flat color, so that
+    // areas drawn many times per frame stand out.
+    Overdraw,
+}
+
+impl RenderPassKind {
+    //---------------------------------------------------------------------------------------------
+    // Returns the default pass of the kind, with its order and resources.
+    //---------------------------------------------------------------------------------------------
+    pub fn default_pass(self) -> RenderPass {
+        use PassResource::*;
+
+        let (order, inputs, outputs): (i32, &[PassResource], &[PassResource]) = match self {
+            RenderPassKind::Clear => (0, &[], &[Frame]),
+            RenderPassKind::Background => (100, &[BackgroundQuads, Frame], &[Frame]),
+            RenderPassKind::Foreground => (200, &[ForegroundQuads, Frame], &[Frame]),
+            RenderPassKind::Text => (300, &[TextQuads, Frame], &[Frame]),
+            RenderPassKind::Vignette => (900, &[Frame], &[Frame]),
+            RenderPassKind::Overdraw => {
+                (1000, &[BackgroundQuads, ForegroundQuads, TextQuads, Frame], &[Frame])
+            }
+        };
+
+        RenderPass {
+            kind: self,
+            order,
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+            enabled: true,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// RenderPass describes a draw of the renderer: the resources it reads and writes and where it
+// runs in the frame.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct RenderPass {
+    // The draw the pass performs.
+    pub kind: RenderPassKind,
+    // Passes run from lowest to highest order (ties keep their insertion order).
+    pub order: i32,
+    // Resources that must be available before the pass runs.
+    pub inputs: Vec<PassResource>,
+    // Resources the pass makes available to later passes.
+    pub outputs: Vec<PassResource>,
+    // Whether the pass runs (disabled passes are kept in the graph but skipped).
+    pub enabled: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// RenderGraph holds the ordered render passes of a frame. Passes can be inserted, removed, enabled
+// and disabled at runtime, and every change is validated so that each enabled pass only reads
+// resources made available before it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct RenderGraph {
+    // The passes, sorted by order.
+    passes: Vec<RenderPass>,
+    // Kinds of the enabled passes in the order they run, cached for rendering.
+    schedule: Vec<RenderPassKind>,
+}
+
+impl Default for RenderGraph {
+    fn default() -> Self {
+        let mut graph = Self { passes: Vec::new(), schedule: Vec::new() };
+
+        for kind in [
+            RenderPassKind::Clear,
+            RenderPassKind::Background,
+            RenderPassKind::Foreground,
+            RenderPassKind::Text,
+            RenderPassKind::Vignette,
+        ]
+        .iter()
+        {
+            graph.passes.push(kind.default_pass());
+        }
+
+        graph.rebuild_schedule().expect("The default render graph is invalid.");
+        graph
+    }
+}
+
+impl RenderGraph {
+    //---------------------------------------------------------------------------------------------
+    // Returns the passes, sorted by order.
+    //---------------------------------------------------------------------------------------------
+    pub fn passes(&self) -> &[RenderPass] {
+        &self.passes
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the kinds of the enabled passes in the order they run.
+    //---------------------------------------------------------------------------------------------
+    pub fn schedule(&self) -> &[RenderPassKind] {
+        &self.schedule
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a pass of the graph, if present.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, kind: RenderPassKind) -> Option<&RenderPass> {
+        self.passes.iter().find(|pass| pass.kind == kind)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a pass is present and enabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_enabled(&self, kind: RenderPassKind) -> bool {
+        matches!(self.get(kind), Some(pass) if pass.enabled)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Inserts a pass into the graph.
+    // Returns an error (and leaves the graph unchanged) if a pass of the same kind is present or
+    // the pass would read a resource unavailable to it.
+    //---------------------------------------------------------------------------------------------
+    pub fn insert(&mut self, pass: RenderPass) -> Result<()> {
+        if self.get(pass.kind).is_some() {
+            return Err(anyhow!("Render graph already has a {:?} pass.", pass.kind));
+        }
+
+        let previous = self.passes.clone();
+        let index = self.passes.iter().take_while(|other| other.order <= pass.order).count();
+        self.passes.insert(index, pass);
+
+        self.rebuild_or_restore(previous)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes a pass from the graph, returning it.
+    // Returns an error (and leaves the graph unchanged) if the pass is not present or a later pass
+    // reads a resource only it made available.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove(&mut self, kind: RenderPassKind) -> Result<RenderPass> {
+        let index = self
+            .passes
+            .iter()
+            .position(|pass| pass.kind == kind)
+            .ok_or_else(|| anyhow!("Render graph has no {:?} pass.", kind))?;
+
+        let previous = self.passes.clone();
+        let pass = self.passes.remove(index);
+        self.rebuild_or_restore(previous)?;
+
+        Ok(pass)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Enables or disables a pass.
+    // Returns an error (and leaves the graph unchanged) if the pass is not present or the change
+    // would leave a pass reading a resource unavailable to it.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_enabled(&mut self, kind: RenderPassKind, enabled: bool) -> Result<()> {
+        let previous = self.passes.clone();
+        let pass = self
+            .passes
+            .iter_mut()
+            .find(|pass| pass.kind == kind)
+            .ok_or_else(|| anyhow!("Render graph has no {:?} pass.", kind))?;
+
+        if pass.enabled == enabled {
+            return Ok(());
+        }
+
+        pass.enabled = enabled;
+        self.rebuild_or_restore(previous)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that rebuilds the schedule, restoring the previous passes if it is invalid.
+    //---------------------------------------------------------------------------------------------
+    fn rebuild_or_restore(&mut self, previous: Vec<RenderPass>) -> Result<()> {
+        if let Err(e) = self.rebuild_schedule() {
+            self.passes = previous;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that validates the resources of the enabled passes and caches their order.
+    //---------------------------------------------------------------------------------------------
+    fn rebuild_schedule(&mut self) -> Result<()> {
+        let mut available = EXTERNAL_RESOURCES.to_vec();
+        let mut schedule = Vec::with_capacity(self.passes.len());
+
+        for pass in self.passes.iter().filter(|pass| pass.enabled) {
+            if let Some(missing) = pass.inputs.iter().find(|input| !available.contains(input)) {
+                return Err(anyhow!(
+                    "{:?} pass reads {:?}, which no earlier pass writes.",
+                    pass.kind,
+                    missing
+                ));
+            }
+
+            for output in pass.outputs.iter() {
+                if !available.contains(output) {
+                    available.push(*output);
+                }
+            }
+
+            schedule.push(pass.kind);
+        }
+
+        self.schedule = schedule;
+
+        Ok(())
+    }
+}
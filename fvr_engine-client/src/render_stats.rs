@@ -0,0 +1,141 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::render_graph::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// # of frames of stats kept (e.g. for the sparklines of the debug gui).
+pub const RENDER_STATS_HISTORY: usize = 120;
+
+// Time spent mapping a vertex buffer above which the map is counted as a stall (the driver waiting
+// for the GPU to finish with the buffer).
+const MAP_STALL_THRESHOLD: Duration = Duration::from_micros(500);
+
+//-------------------------------------------------------------------------------------------------
+// FrameStats holds the renderer counters of a single frame.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    // # of quads submitted by each render pass that drew any, in the order they ran.
+    pub pass_quads: Vec<(RenderPassKind, usize)>,
+    // Total # of quads submitted.
+    pub quads: usize,
+    // # of draw calls.
+    pub draw_calls: usize,
+    // # of bytes uploaded to vertex buffers and textures.
+    pub bytes_uploaded: usize,
+    // # of textures bound.
+    pub texture_binds: usize,
+    // # of vertex buffers mapped.
+    pub buffer_maps: usize,
+    // # of vertex buffer maps that took long enough to be stalls.
+    pub map_stalls: usize,
+    // Total time spent mapping and unmapping vertex buffers.
+    pub map_time: Duration,
+    // Whether the vertices were rebuilt from the terminal (false when the sync was skipped).
+    pub synced: bool,
+}
+
+impl FrameStats {
+    //---------------------------------------------------------------------------------------------
+    // Records the quads submitted by a render pass in a draw call.
+    //---------------------------------------------------------------------------------------------
+    pub fn record_draw(&mut self, kind: RenderPassKind, quads: usize) {
+        match self.pass_quads.iter_mut().find(|(pass, _)| *pass == kind) {
+            Some((_, pass_quads)) => *pass_quads += quads,
+            None => self.pass_quads.push((kind, quads)),
+        }
+
+        self.quads += quads;
+        self.draw_calls += 1;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records the time spent mapping and unmapping a vertex buffer.
+    //---------------------------------------------------------------------------------------------
+    pub fn record_map(&mut self, elapsed: Duration) {
+        self.buffer_maps += 1;
+        self.map_time += elapsed;
+
+        if elapsed >= MAP_STALL_THRESHOLD {
+            self.map_stalls += 1;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets the counters, keeping the allocation of the pass quads.
+    //---------------------------------------------------------------------------------------------
+    fn clear(&mut self) {
+        let mut pass_quads = std::mem::take(&mut self.pass_quads);
+        pass_quads.clear();
+
+        *self = Self { pass_quads, ..Default::default() };
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// RenderStats aggregates the renderer counters per frame and keeps the recent frames.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct RenderStats {
+    // Counters of the frame being rendered.
+    current: FrameStats,
+    // Counters of the recent frames, oldest first.
+    history: VecDeque<FrameStats>,
+}
+
+impl RenderStats {
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters of the frame being rendered.
+    //---------------------------------------------------------------------------------------------
+    pub fn current_mut(&mut self) -> &mut FrameStats {
+        &mut self.current
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the counters of the frame being rendered into the history and starts a new frame.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_frame(&mut self) {
+        let mut next = if self.history.len() >= RENDER_STATS_HISTORY {
+            self.history.pop_front().unwrap_or_default()
+        } else {
+            FrameStats::default()
+        };
+
+        next.clear();
+        self.history.push_back(std::mem::replace(&mut self.current, next));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters of the last rendered frame, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn last(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters of the recent frames, oldest first.
+    //---------------------------------------------------------------------------------------------
+    pub fn history(&self) -> impl Iterator<Item = &FrameStats> {
+        self.history.iter()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a value of every recent frame, oldest first (e.g. for plotting).
+    //---------------------------------------------------------------------------------------------
+    pub fn series<F>(&self, value: F) -> Vec<f32>
+    where
+        F: Fn(&FrameStats) -> f32,
+    {
+        self.history.iter().map(value).collect()
+    }
+}
@@ -1,9 +1,9 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
-use fnv::FnvHashMap;
 use std::ffi::c_void;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::{mem, ptr};
 
 //-------------------------------------------------------------------------------------------------
@@ -11,7 +11,9 @@ use std::{mem, ptr};
 //-------------------------------------------------------------------------------------------------
 use anyhow::{Context, Result};
 use gl::types::*;
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec3, Vec4};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
@@ -21,33 +23,161 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::dynamic_glyphs::*;
 use crate::gl_helpers::*;
-use crate::shader_strings::*;
+use crate::gpu_profile::*;
+use crate::render_graph::*;
+use crate::render_stats::*;
+use crate::shader_manager::*;
 use crate::terminal::*;
+use crate::vertex_builder::*;
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
 //-------------------------------------------------------------------------------------------------
 
-// Normalization value to convert u8 color to OpenGL float representation.
-const COLOR_NORMALIZE_8BIT: GLfloat = 1.0 / 255.0;
+// # of layers of the glyphs texture array.
+const GLYPHS_LAYER_COUNT: usize = DYNAMIC_GLYPHS_INDEX + 1;
+
+// Texture unit the glyphs texture array is bound to.
+const GLYPHS_TEXTURE_UNIT: GLenum = gl::TEXTURE0;
+
+// Largest factor the font textures are downscaled by to fit the max texture size.
+const MAX_ATLAS_SCALE: u32 = 8;
+
+// Vertex and fragment shader files of each program, relative to the shaders dir.
+const BACKGROUND_SHADERS: (&str, &str) = ("background.vert", "background.frag");
+const FOREGROUND_SHADERS: (&str, &str) = ("foreground.vert", "foreground.frag");
+const VIGNETTE_SHADERS: (&str, &str) = ("full_frame.vert", "vignette.frag");
+const OVERDRAW_SHADERS: (&str, &str) = ("background.vert", "overdraw.frag");
+
+// Variant define of the foreground shaders for SDF font textures.
+const SDF_DEFINE: &str = "SDF";
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type MissingGlyphHook = Box<dyn FnMut(char)>;
 
 //-------------------------------------------------------------------------------------------------
-// Describes a vertex for a colored (+ alpha) and texture-mapped quad.
-// The background shader program will only use position and color[3].
-// The foreground shader program will use all properties.
+// Helper function that returns the path of a file of a font for a tile style.
 //-------------------------------------------------------------------------------------------------
-#[repr(C, packed)]
-#[derive(Clone, Copy, Default, Debug)]
-struct Vertex {
-    // Position of the vertex [X, Y].
-    position: [GLfloat; 2],
-    // Color of the vertex [R, G, B, A].
-    color: [GLfloat; 4],
-    // Texture Position of the vertex [U, V].
-    tex_coords: [GLfloat; 2],
-    // Index of the texture to sample.
-    tex_index: GLfloat,
+fn font_file_path(font_name: &str, style_name: &str, suffix: &str) -> PathBuf {
+    CONTENT_LAYERS
+        .resolve(Path::new(CONFIG_FONTS_DIR).join(font_name).join([style_name, suffix].concat()))
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that uploads the textures of a font for every tile style (and its outlined
+// version) into the layers of the glyphs texture array, reallocating it to fit the largest.
+// The textures are downscaled by the profile's atlas scale, or further to fit the max texture size.
+// Returns the texel normalization values of the font textures (in their original pixels) and the
+// factor they were downscaled by. The uploads are recorded in the stats.
+//-------------------------------------------------------------------------------------------------
+fn load_font_textures(
+    font_name: &str,
+    texture: GLuint,
+    profile: &RendererProfile,
+    stats: &mut FrameStats,
+) -> Result<((f32, f32), u32)> {
+    // Load the non-outlined textures and then the outlined textures.
+    let mut images = Vec::with_capacity(TILE_STYLE_COUNT * 2);
+
+    for i in 0..TILE_STYLE_COUNT * 2 {
+        let style_name = TILE_STYLE_NAMES[i % TILE_STYLE_COUNT];
+        let extension = match (i < TILE_STYLE_COUNT, CONFIG.use_sdf_fonts) {
+            (true, true) => "_sdf.png",
+            (true, false) => ".png",
+            (false, true) => "_outline_sdf.png",
+            (false, false) => "_outline.png",
+        };
+
+        images.push(load_image_rgba(font_file_path(font_name, style_name, extension))?);
+    }
+
+    // Downscale by powers of two until the largest texture fits.
+    let largest = images.iter().map(|image| image.width().max(image.height())).max().unwrap_or(0);
+    let mut scale = profile.atlas_scale.max(1);
+
+    while largest / scale > profile.max_texture_size && scale < MAX_ATLAS_SCALE {
+        scale *= 2;
+    }
+
+    if scale > profile.atlas_scale {
+        println!(
+            "[Renderer] Downscaling the textures of font {} by {} to fit the max texture size of \
+             {}.",
+            font_name, scale, profile.max_texture_size
+        );
+    }
+
+    if scale > 1 {
+        for image in images.iter_mut() {
+            let (width, height) =
+                ((image.width() / scale).max(1), (image.height() / scale).max(1));
+            *image = imageops::resize(image, width, height, FilterType::Triangle);
+        }
+    }
+
+    // Every layer must have the same dimensions, so fit the array to the largest texture (and the
+    // dynamic glyphs page). The allocated layers are undefined, so smaller textures are padded
+    // with transparent pixels to fill their layers.
+    let dimensions = images
+        .iter()
+        .map(|image| image.dimensions())
+        .fold(DYNAMIC_GLYPHS_PAGE_DIMENSIONS, |max, dimensions| {
+            (max.0.max(dimensions.0), max.1.max(dimensions.1))
+        });
+
+    for image in images.iter_mut().filter(|image| image.dimensions() != dimensions) {
+        let mut padded = RgbaImage::new(dimensions.0, dimensions.1);
+        imageops::replace(&mut padded, &*image, 0, 0);
+        *image = padded;
+    }
+
+    allocate_texture_array(texture, GLYPHS_TEXTURE_UNIT, dimensions, GLYPHS_LAYER_COUNT)?;
+    stats.texture_binds += 1;
+
+    for (layer, image) in images.iter().enumerate() {
+        upload_texture_layer(texture, GLYPHS_TEXTURE_UNIT, layer, dimensions, image)?;
+        stats.bytes_uploaded += image.len();
+        stats.texture_binds += 1;
+    }
+
+    Ok(((1.0 / (dimensions.0 * scale) as f32, 1.0 / (dimensions.1 * scale) as f32), scale))
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that loads the glyph metrics of a font for every tile style.
+// The first half of the vec will contain tables for the non-outlined metrics.
+// The second half of the vec will contain tables for the outlined metrics.
+//-------------------------------------------------------------------------------------------------
+fn load_font_metrics(font_name: &str) -> Result<Vec<GlyphMetricTable>> {
+    let mut metrics = vec![GlyphMetricTable::new(); TILE_STYLE_COUNT * 2];
+
+    for i in 0..TILE_STYLE_COUNT * 2 {
+        // Get the path string for the (outline) font metrics.
+        // (fonts without V2 metrics fall back to their legacy metrics)
+        let style_name = TILE_STYLE_NAMES[i % TILE_STYLE_COUNT];
+        let outline = if i < TILE_STYLE_COUNT { "" } else { "_outline" };
+        let mut path = font_file_path(font_name, style_name, &[outline, ".json"].concat());
+
+        if !path.exists() {
+            path = font_file_path(font_name, style_name, &[outline, ".toml"].concat());
+        }
+
+        // Read in the data from the metrics file and parse it in either layout.
+        let metrics_text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read contents of file {}.", path.display()))?;
+
+        let font_metrics = FontMetricsV2::parse(&metrics_text)
+            .with_context(|| format!("Failed to parse font metrics {}.", path.display()))?;
+
+        // Populate tables with metrics for easy access.
+        metrics[i] = GlyphMetricTable::from_font_metrics(font_metrics);
+    }
+
+    Ok(metrics)
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -57,7 +187,8 @@ struct Vertex {
 // avoid tying up the CPU and GPU. Drawing is done via DrawElements with a single index buffer.
 //
 // Every frame vertex data is generated from the current terminal state and uploaded to the array
-// buffer that is not currently in use (being drawn from).
+// buffer that is not currently in use (being drawn from). With threaded sync, the vertex data is
+// generated on a worker thread while the previous frame renders, and uploaded on the next sync.
 //
 // A single vertex specification is used for both the "background" (basic colored quads) and
 // "foreground" (colored and textured quads of glyphs or outlines). The background shader program
@@ -82,51 +213,96 @@ pub struct RendererV2 {
     // Double vertex buffers to not tie the CPU and GPU.
     // (one will be mapped to memory and updated during the frame, the other rendered from)
     vertex_buffers: [GLuint; 2],
+    // Loads and caches the shader programs.
+    shaders: ShaderManager,
     // Shader program used for rendering the background.
     background_program: GLuint,
     // Vertex Arrays for storing background vertex attributes.
     background_vertex_arrays: [GLuint; 2],
-    // Vec for collecting background quads each frame.
-    background_vertices: Vec<Vertex>,
     // Location of the projection matrix in the background shader program.
     background_projection_location: GLint,
+    // Locations of the color grading uniforms in the background shader program.
+    background_grade_locations: [GLint; 3],
     // Cached count of background indices for use when drawing.
     background_indices_len: [GLsizei; 2],
     // Shader program used for rendering the foreground.
     foreground_program: GLuint,
     // Vertex Arrays for storing foreground vertex attributes.
     foreground_vertex_arrays: [GLuint; 2],
-    // Vec for collecting foreground quads each frame.
-    foreground_vertices: Vec<Vertex>,
     // Location of the projection matrix in the foreground shader program.
     foreground_projection_location: GLint,
+    // Locations of the color grading uniforms in the foreground shader program.
+    foreground_grade_locations: [GLint; 3],
     // Cached count of foreground indices for use when drawing.
     foreground_indices_len: [GLsizei; 2],
+    // Cached count of proportional text indices for use when drawing.
+    text_indices_len: [GLsizei; 2],
     // Shader program used for rendering the vignette.
     vignette_program: GLuint,
     // A blank vertex array used when rendering the vignette.
     vignette_vertex_array: GLuint,
-    // Array of font textures for every tile style.
-    // The first half of the array will contain the non-outlined textures.
-    // The second half of the array will contain the outlined textures.
-    textures: [GLuint; TILE_STYLE_COUNT * 2],
-    // Normalization values for texel in pixels to texel in OpenGL space for every font texture.
-    // The first half of the array will contain the non-outlined texture normalization values.
-    // The second half of the array will contain the outlined texture normalization values.
-    texel_normalize: [(f32, f32); TILE_STYLE_COUNT * 2],
-    // Vec of maps of u32 codepoint to corresponding glyph metrics for every font texture.
-    // Length will equal TILE_STYLE_COUNT * 2.
-    // The first half of the vec will contain maps for the non-outlined metrics.
-    // The second half of the vec will contain maps for the outlined metrics.
-    metrics: Vec<FnvHashMap<i32, GlyphMetric>>,
+    // Shader program used for rendering the overdraw debug view.
+    overdraw_program: GLuint,
+    // Location of the projection matrix in the overdraw shader program.
+    overdraw_projection_location: GLint,
+    // Texture array of the glyphs, with a layer for the font texture of every tile style and one
+    // for the dynamic glyphs.
+    // The first half of the font layers will contain the non-outlined textures.
+    // The second half of the font layers will contain the outlined textures.
+    glyphs_texture: GLuint,
+    // Renderer features selected for the GPU.
+    profile: RendererProfile,
+    // Id and revision of the terminal from the last sync, for skipping syncs when nothing has
+    // changed (none to force the next sync).
+    synced_revision: Option<(u64, u64)>,
+    // Terminal opacity from the last sync.
+    synced_opacity: f32,
+    // Terminal text runs from the last sync.
+    synced_text_runs: Vec<TextRun>,
+    // Glyph rendered in place of glyphs missing from the font metrics.
+    fallback_glyph: char,
+    // Optional hook called the first time each missing glyph is encountered.
+    missing_glyph_hook: Option<MissingGlyphHook>,
+    // Number of consecutive syncs in which the terminal has not changed.
+    unchanged_syncs: usize,
+    // Cell rendered highlighted (e.g. under the virtual cursor).
+    highlighted_cell: Option<ICoord>,
+    // Highlighted cell from the last sync.
+    synced_highlighted_cell: Option<ICoord>,
+    // Scale of the tiles when the terminal is resized with the window.
+    tile_scale: f32,
+    // # of tiles (terminal and map layer) the buffers are allocated for.
+    allocated_tiles: usize,
+    // Region and zoom of the map layer from the last sync.
+    synced_map_layer: Option<(Rect, f32)>,
+    // Position (in pixels) and tile of the software cursor, if drawn.
+    software_cursor: Option<((f32, f32), Tile)>,
+    // Position and tile of the software cursor from the last sync.
+    synced_software_cursor: Option<((f32, f32), Tile)>,
+    // Rotates the colors of tiles colored for palette cycling.
+    palette_cycler: PaletteCycler,
+    // Palette cycling rotations from the last sync.
+    synced_palette_offsets: [usize; PALETTE_CYCLE_COUNT],
+    // Color grading uploaded to the shader programs, if any has been yet.
+    synced_color_grade: Option<ColorGrade>,
+    // Builds the vertices of each frame (none while building on the vertex worker).
+    builder: Option<VertexBuilder>,
+    // Worker thread the vertices are built on, if threaded sync is enabled.
+    worker: Option<VertexWorker>,
+    // Whether the builder has built vertices that have not been uploaded yet.
+    built: bool,
+    // Passes drawn each frame, in order.
+    render_graph: RenderGraph,
+    // Counters of the recent frames.
+    stats: RenderStats,
 }
 
 impl RendererV2 {
     //---------------------------------------------------------------------------------------------
-    // Creates a new renderer.
+    // Creates a new renderer with the features of a profile.
     // (there should only ever be one)
     //---------------------------------------------------------------------------------------------
-    pub fn new() -> Result<Self> {
+    pub fn new(profile: RendererProfile) -> Result<Self> {
         // Default clear color (this will change).
         let clear_color = SdlColor::RGB(15, 25, 35);
 
@@ -156,11 +332,10 @@ impl RendererV2 {
         }
         gl_error_unwrap!("Failed to generate vertex buffer.");
 
-        // Generate the background program (compile shaders and link).
-        let background_program = link_program_from_sources(
-            BACKGROUND_VERTEX_SHADER_SOURCE,
-            BACKGROUND_FRAGMENT_SHADER_SOURCE,
-        )?;
+        // Load the shader programs (compile shaders and link).
+        let mut shaders = ShaderManager::default();
+        let [background_program, foreground_program, vignette_program, overdraw_program] =
+            Self::load_programs(&mut shaders)?;
 
         // Generate the background vertex arrays.
         let mut background_vertex_arrays: [GLuint; 2] = [0; 2];
@@ -169,19 +344,6 @@ impl RendererV2 {
         }
         gl_error_unwrap!("Failed to generate background vertex arrays.");
 
-        // Generate the foreground program (compile shaders and link).
-        let foreground_program = if CONFIG.use_sdf_fonts {
-            link_program_from_sources(
-                FOREGROUND_VERTEX_SHADER_SOURCE,
-                FOREGROUND_FRAGMENT_SHADER_SDF_SOURCE,
-            )
-        } else {
-            link_program_from_sources(
-                FOREGROUND_VERTEX_SHADER_SOURCE,
-                FOREGROUND_FRAGMENT_SHADER_SOURCE,
-            )
-        }?;
-
         // Generate the foreground vertex array.
         let mut foreground_vertex_arrays: [GLuint; 2] = [0; 2];
         unsafe {
@@ -189,12 +351,6 @@ impl RendererV2 {
         }
         gl_error_unwrap!("Failed to generate foreground vertex arrays.");
 
-        // Generate the vignette program (compile shaders and link).
-        let vignette_program = link_program_from_sources(
-            FULL_FRAME_VERTEX_SHADER_SOURCE,
-            VIGNETTE_FRAGMENT_SHADER_SOURCE,
-        )?;
-
         // Generate the vignette vertex array.
         let mut vignette_vertex_array = 0;
         unsafe {
@@ -202,12 +358,12 @@ impl RendererV2 {
         }
         gl_error_unwrap!("Failed to generate vignette vertex array.");
 
-        // Generate the style textures.
-        let mut textures = [0; TILE_STYLE_COUNT * 2];
+        // Generate the glyphs texture array.
+        let mut glyphs_texture = 0;
         unsafe {
-            gl::GenTextures((TILE_STYLE_COUNT * 2) as GLint, &mut textures[0]);
+            gl::GenTextures(1, &mut glyphs_texture);
         }
-        gl_error_unwrap!("Failed to generate texture.");
+        gl_error_unwrap!("Failed to generate glyphs texture array.");
 
         // Find the location of the projection matrix uniforms.
         //-----------------------------------------------------------------------------------------
@@ -219,79 +375,26 @@ impl RendererV2 {
             get_uniform_location(foreground_program, "projection")
                 .context("Failed to obtain foreground projection matrix uniform location.")?;
 
-        // Indices len will be updated whenever the vertex data is updated.
-        //-----------------------------------------------------------------------------------------
-        let background_indices_len = [Default::default(); 2];
-        let foreground_indices_len = [Default::default(); 2];
+        let overdraw_projection_location = get_uniform_location(overdraw_program, "projection")
+            .context("Failed to obtain overdraw projection matrix uniform location.")?;
 
-        // Populate index buffer with max # of quads.
+        // Find the locations of the color grading uniforms.
         //-----------------------------------------------------------------------------------------
+        let background_grade_locations = Self::grade_uniform_locations(background_program)
+            .context("Failed to obtain background color grading uniform locations.")?;
 
-        // The max # of quads is the total # of tiles in the terminal * 3.
-        // (for background, foreground, and outline).
-        let num_quads = (CONFIG.terminal_dimensions.0 * CONFIG.terminal_dimensions.1) as usize;
-        let indices = generate_indices(num_quads * 3);
-
-        // Bind the index buffer and upload the index data (we only need to do this once).
-        unsafe {
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer);
-        }
-        gl_error_unwrap!("Failed to bind index buffer.");
-
-        unsafe {
-            gl::BufferData(
-                // Type of buffer.
-                gl::ELEMENT_ARRAY_BUFFER,
-                // Size of the data.
-                (indices.len() as usize * mem::size_of::<GLuint>()) as GLsizeiptr,
-                // Pointer.
-                mem::transmute(&indices[0]),
-                // Type of storage access.
-                gl::STATIC_DRAW,
-            );
-        }
-        gl_error_unwrap!("Failed to upload index buffer data.");
+        let foreground_grade_locations = Self::grade_uniform_locations(foreground_program)
+            .context("Failed to obtain foreground color grading uniform locations.")?;
 
-        // Populate the vertex buffers with blank data.
+        // Indices len will be updated whenever the vertex data is updated.
         //-----------------------------------------------------------------------------------------
+        let background_indices_len = [Default::default(); 2];
+        let foreground_indices_len = [Default::default(); 2];
 
-        // The max # of bytes in the vertex buffers is:
-        // max # of bytes in the background...
-        let max_background_len = num_quads * VERTICES_PER_QUAD * mem::size_of::<Vertex>();
-        // plus the max # of bytes in the foreground...
-        let max_foreground_len = (num_quads * VERTICES_PER_QUAD * mem::size_of::<Vertex>()) * 2;
-        // (times 2 to account for the regular and outline glyphs).
-        let max_vertex_len = max_background_len + max_foreground_len;
-
-        // Create an empty byte vec.
-        let blank_vertex_data = vec![u8::default(); max_vertex_len];
-
-        // Bind the buffers and upload the empty data.
-        for buffer in vertex_buffers {
-            unsafe {
-                gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
-            }
-            gl_error_unwrap!("Failed to bind vertex buffer.");
-
-            unsafe {
-                gl::BufferData(
-                    // Storage type.
-                    gl::ARRAY_BUFFER,
-                    // Size of the data.
-                    blank_vertex_data.len() as GLsizeiptr,
-                    // Pointer.
-                    mem::transmute(&blank_vertex_data[0]),
-                    // Type of storage access.
-                    gl::STREAM_DRAW,
-                );
-            }
-            gl_error_unwrap!("Failed to upload vertex buffer data.");
-        }
-
-        // Initialize the vec vertex buffers to max capacity.
+        // Allocate the index and vertex buffers for the max # of quads.
         //-----------------------------------------------------------------------------------------
-        let background_vertices = Vec::with_capacity(num_quads as usize);
-        let foreground_vertices = Vec::with_capacity(num_quads as usize * 2);
+        let num_quads = (CONFIG.terminal_dimensions.0 * CONFIG.terminal_dimensions.1) as usize;
+        Self::allocate_buffers(index_buffer, &vertex_buffers, num_quads)?;
 
         // Setup the background VAOs.
         //-----------------------------------------------------------------------------------------
@@ -495,68 +598,43 @@ impl RendererV2 {
                 gl::EnableVertexAttribArray(location as GLuint);
                 gl_error_unwrap!("Failed to enable tex_index attrib for foreground vertex array.");
             }
-        }
-
-        // Load and bind the style textures.
-        //-----------------------------------------------------------------------------------------
-
-        // Double length to account for outline versions.
-        let mut texel_normalize = [Default::default(); TILE_STYLE_COUNT * 2];
-
-        // Make sure the foreground program is in use before updating uniforms.
-        unsafe {
-            gl::UseProgram(foreground_program);
-            gl_error_unwrap!("Failed to use foreground program when binding textures.");
-        }
 
-        // Bind and upload the non-outlined textures.
-        for i in 0..TILE_STYLE_COUNT {
-            // Get the texture path string.
-            let extension = if CONFIG.use_sdf_fonts { "_sdf.png" } else { ".png" };
-            let path_string =
-                [CONFIG_FONTS_DIR, CONFIG.font_name.as_ref(), "/", TILE_STYLE_NAMES[i], extension]
-                    .concat();
+            let location = get_attrib_location(foreground_program, "outline")
+                .context("Failed to get foreground outline attrib location.")?;
 
-            let dimensions =
-                load_texture(Path::new(&path_string), textures[i], gl::TEXTURE0 + i as GLuint)?;
-            texel_normalize[i] = (1.0 / dimensions.0 as f32, 1.0 / dimensions.1 as f32);
-
-            let location = get_uniform_location(foreground_program, TILE_STYLE_NAMES[i])?;
             unsafe {
-                gl::Uniform1i(location, i as GLint);
-                gl_error_unwrap!("Failed to set non-outlined sampler2D uniform value.");
-            }
-        }
+                gl::VertexAttribPointer(
+                    // Attribute location.
+                    location as GLuint,
+                    // Size.
+                    2,
+                    // Type.
+                    gl::FLOAT,
+                    // Normalized.
+                    gl::FALSE as GLboolean,
+                    // Stride.
+                    mem::size_of::<Vertex>() as GLsizei,
+                    // Offset.
+                    (mem::size_of::<GLfloat>() * 9) as *const c_void,
+                );
+                gl_error_unwrap!(
+                    "Failed to set outline attrib pointer for foreground vertex array."
+                );
 
-        // Bind and upload the outlined textures.
-        #[allow(clippy::needless_range_loop)]
-        for i in 0..TILE_STYLE_COUNT {
-            // Get the outline texture path string.
-            let extension = if CONFIG.use_sdf_fonts { "_outline_sdf.png" } else { "_outline.png" };
-            let path_string =
-                [CONFIG_FONTS_DIR, CONFIG.font_name.as_ref(), "/", TILE_STYLE_NAMES[i], extension]
-                    .concat();
-
-            // Offset the index for outlined textures.
-            let index = i + TILE_STYLE_COUNT;
-
-            let dimensions = load_texture(
-                Path::new(&path_string),
-                textures[index],
-                gl::TEXTURE0 + index as GLuint,
-            )?;
-            texel_normalize[index] = (1.0 / dimensions.0 as f32, 1.0 / dimensions.1 as f32);
-
-            let location = get_uniform_location(
-                foreground_program,
-                &format!("{}_outline", TILE_STYLE_NAMES[i]),
-            )?;
-            unsafe {
-                gl::Uniform1i(location, index as GLint);
-                gl_error_unwrap!("Failed to set outlined sampler2D uniform value.");
+                gl::EnableVertexAttribArray(location as GLuint);
+                gl_error_unwrap!("Failed to enable outline attrib for foreground vertex array.");
             }
         }
 
+        // Load the style textures and bind the glyphs texture array to its sampler.
+        //-----------------------------------------------------------------------------------------
+        let mut stats = RenderStats::default();
+        let (texel_normalize, atlas_scale) =
+            load_font_textures(&CONFIG.font_name, glyphs_texture, &profile, stats.current_mut())?;
+        let dynamic_glyphs = DynamicGlyphs::load();
+
+        Self::bind_glyphs_sampler(foreground_program)?;
+
         // Misc. OpenGL settings.
         //-----------------------------------------------------------------------------------------
         unsafe {
@@ -583,58 +661,23 @@ impl RendererV2 {
             gl_error_unwrap!("Failed to set clear color.");
         }
 
-        // Load the glyph metrics.
+        // Load the glyph metrics and set up the vertex builder (on a worker, if threaded).
         //-----------------------------------------------------------------------------------------
+        let metrics = load_font_metrics(&CONFIG.font_name)?;
+        let builder = VertexBuilder::new(
+            clear_color,
+            metrics,
+            texel_normalize,
+            atlas_scale,
+            dynamic_glyphs,
+            num_quads,
+        );
+        let worker = if profile.threaded_sync { Some(VertexWorker::spawn()?) } else { None };
 
-        let mut metrics = vec![FnvHashMap::default(); TILE_STYLE_COUNT * 2];
-
-        // Load the non-outlined metrics.
-        for i in 0..TILE_STYLE_COUNT {
-            // Get the path string for the font metrics.
-            let path_string =
-                [CONFIG_FONTS_DIR, CONFIG.font_name.as_ref(), "/", TILE_STYLE_NAMES[i], ".json"]
-                    .concat();
-            let path = Path::new(&path_string);
-
-            // Read in the data from the metrics file and parse it as JSON.
-            let metrics_json = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read contents of file {}.", path.display()))?;
-
-            let font_metrics: FontMetricsV2 =
-                serde_json::from_str(&metrics_json).context("Failed to parse font metrics.")?;
-
-            // Populate hash maps with non-outlined metrics for easy access.
-            for metric in font_metrics.metrics {
-                metrics[i].insert(metric.codepoint, metric);
-            }
-        }
-
-        // Load the outlined metrics.
-        for i in 0..TILE_STYLE_COUNT {
-            // Get the path string for the outline font metrics.
-            let path_string = [
-                CONFIG_FONTS_DIR,
-                CONFIG.font_name.as_ref(),
-                "/",
-                TILE_STYLE_NAMES[i],
-                "_outline.json",
-            ]
-            .concat();
-            let path = Path::new(&path_string);
-
-            // Read in the data from the metrics file and parse it as JSON.
-            let metrics_json = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read contents of file {}.", path.display()))?;
-
-            let font_metrics: FontMetricsV2 =
-                serde_json::from_str(&metrics_json).context("Failed to parse font metrics.")?;
-
-            // Populate hash maps with outlined metrics for easy access.
-            // (remembering to offset the index for outlined metrics)
-            for metric in font_metrics.metrics {
-                metrics[i + TILE_STYLE_COUNT].insert(metric.codepoint, metric);
-            }
-        }
+        // Set up the default render passes.
+        //-----------------------------------------------------------------------------------------
+        let mut render_graph = RenderGraph::default();
+        render_graph.set_enabled(RenderPassKind::Vignette, CONFIG.enable_vignette)?;
 
         // ...and that's it!
         //-----------------------------------------------------------------------------------------
@@ -647,24 +690,325 @@ impl RendererV2 {
             target_backbuffer,
             index_buffer,
             vertex_buffers,
+            shaders,
             background_program,
             background_vertex_arrays,
-            background_vertices,
             background_projection_location,
+            background_grade_locations,
             background_indices_len,
             foreground_program,
             foreground_vertex_arrays,
-            foreground_vertices,
             foreground_projection_location,
+            foreground_grade_locations,
             foreground_indices_len,
+            text_indices_len: [0; 2],
             vignette_program,
             vignette_vertex_array,
-            textures,
-            texel_normalize,
-            metrics,
+            overdraw_program,
+            overdraw_projection_location,
+            glyphs_texture,
+            synced_revision: None,
+            synced_opacity: 0.0,
+            synced_text_runs: Vec::new(),
+            fallback_glyph: DEFAULT_FALLBACK_GLYPH,
+            missing_glyph_hook: None,
+            unchanged_syncs: 0,
+            highlighted_cell: None,
+            synced_highlighted_cell: None,
+            tile_scale: Self::fit_scale(CONFIG.window_dimensions, CONFIG.terminal_dimensions),
+            allocated_tiles: num_quads,
+            synced_map_layer: None,
+            software_cursor: None,
+            synced_software_cursor: None,
+            palette_cycler: PaletteCycler::default(),
+            synced_palette_offsets: [0; PALETTE_CYCLE_COUNT],
+            synced_color_grade: None,
+            builder: Some(builder),
+            worker,
+            built: false,
+            profile,
+            render_graph,
+            stats,
         })
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the background, foreground, vignette and overdraw programs.
+    //---------------------------------------------------------------------------------------------
+    fn load_programs(shaders: &mut ShaderManager) -> Result<[GLuint; 4]> {
+        let foreground_defines: &[&str] = if CONFIG.use_sdf_fonts { &[SDF_DEFINE] } else { &[] };
+
+        Ok([
+            shaders
+                .program(BACKGROUND_SHADERS.0, BACKGROUND_SHADERS.1, &[])
+                .context("Failed to load background program.")?,
+            shaders
+                .program(FOREGROUND_SHADERS.0, FOREGROUND_SHADERS.1, foreground_defines)
+                .context("Failed to load foreground program.")?,
+            shaders
+                .program(VIGNETTE_SHADERS.0, VIGNETTE_SHADERS.1, &[])
+                .context("Failed to load vignette program.")?,
+            shaders
+                .program(OVERDRAW_SHADERS.0, OVERDRAW_SHADERS.1, &[])
+                .context("Failed to load overdraw program.")?,
+        ])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that binds the glyphs sampler of the foreground program to the glyphs
+    // texture unit.
+    //---------------------------------------------------------------------------------------------
+    fn bind_glyphs_sampler(foreground_program: GLuint) -> Result<()> {
+        let location = get_uniform_location(foreground_program, "glyphs")?;
+
+        unsafe {
+            gl::UseProgram(foreground_program);
+            gl_error_unwrap!("Failed to use foreground program when binding textures.");
+
+            gl::Uniform1i(location, (GLYPHS_TEXTURE_UNIT - gl::TEXTURE0) as GLint);
+            gl_error_unwrap!("Failed to set sampler2DArray uniform value.");
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reloads the shader programs from disk, restoring their uniforms.
+    // (a shader that fails to build is reported and the previous programs are kept)
+    //---------------------------------------------------------------------------------------------
+    pub fn reload_shaders(&mut self) -> Result<()> {
+        self.shaders.reload()?;
+
+        let [background_program, foreground_program, vignette_program, overdraw_program] =
+            Self::load_programs(&mut self.shaders)?;
+
+        self.background_projection_location =
+            get_uniform_location(background_program, "projection")
+                .context("Failed to obtain background projection matrix uniform location.")?;
+        self.foreground_projection_location =
+            get_uniform_location(foreground_program, "projection")
+                .context("Failed to obtain foreground projection matrix uniform location.")?;
+        self.overdraw_projection_location =
+            get_uniform_location(overdraw_program, "projection")
+                .context("Failed to obtain overdraw projection matrix uniform location.")?;
+        self.background_grade_locations = Self::grade_uniform_locations(background_program)
+            .context("Failed to obtain background color grading uniform locations.")?;
+        self.foreground_grade_locations = Self::grade_uniform_locations(foreground_program)
+            .context("Failed to obtain foreground color grading uniform locations.")?;
+        Self::bind_glyphs_sampler(foreground_program)?;
+
+        self.background_program = background_program;
+        self.foreground_program = foreground_program;
+        self.vignette_program = vignette_program;
+        self.overdraw_program = overdraw_program;
+
+        // The new programs start with default uniform values.
+        self.update_viewport((self.viewport[2], self.viewport[3]))?;
+
+        if let Some(color_grade) = self.synced_color_grade {
+            self.upload_color_grade(&color_grade)?;
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the locations of the tint, brightness and saturation color
+    // grading uniforms within a program.
+    //---------------------------------------------------------------------------------------------
+    fn grade_uniform_locations(program: GLuint) -> Result<[GLint; 3]> {
+        Ok([
+            get_uniform_location(program, "grade_tint")?,
+            get_uniform_location(program, "grade_brightness")?,
+            get_uniform_location(program, "grade_saturation")?,
+        ])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Uploads a color grading to the background and foreground shader programs and grades the
+    // clear color to match.
+    //---------------------------------------------------------------------------------------------
+    fn upload_color_grade(&mut self, grade: &ColorGrade) -> Result<()> {
+        let [r, g, b] = grade.tint_rgb();
+        let programs = [
+            (self.background_program, self.background_grade_locations),
+            (self.foreground_program, self.foreground_grade_locations),
+        ];
+
+        for (program, [tint, brightness, saturation]) in programs.iter() {
+            unsafe {
+                gl::UseProgram(*program);
+                gl_error_unwrap!("Failed to use program for updating color grading.");
+
+                gl::Uniform3f(*tint, r, g, b);
+                gl::Uniform1f(*brightness, grade.brightness);
+                gl::Uniform1f(*saturation, grade.saturation);
+                gl_error_unwrap!("Failed to update color grading uniforms.");
+            }
+        }
+
+        let clear_color = grade.apply(&TileColor(self.clear_color));
+
+        unsafe {
+            gl::ClearColor(
+                clear_color.0.r as GLfloat * COLOR_NORMALIZE_8BIT,
+                clear_color.0.g as GLfloat * COLOR_NORMALIZE_8BIT,
+                clear_color.0.b as GLfloat * COLOR_NORMALIZE_8BIT,
+                1.0,
+            );
+            gl_error_unwrap!("Failed to set clear color.");
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that (re)allocates the index and vertex buffers for a # of tiles.
+    //---------------------------------------------------------------------------------------------
+    fn allocate_buffers(
+        index_buffer: GLuint,
+        vertex_buffers: &[GLuint; 2],
+        num_tiles: usize,
+    ) -> Result<()> {
+        // Unbind any vertex array so that binding the index buffer does not modify it.
+        unsafe {
+            gl::BindVertexArray(0);
+        }
+        gl_error_unwrap!("Failed to unbind vertex array.");
+
+        // Populate index buffer with max # of quads.
+        //-----------------------------------------------------------------------------------------
+
+        // The max # of quads is the total # of tiles in the terminal (and map layer) * 3.
+        // (for background, foreground, and outline) plus the max # of text quads.
+        let num_quads = num_tiles;
+        let indices = generate_indices(num_quads * 3 + MAX_TEXT_QUADS);
+
+        // Bind the index buffer and upload the index data (only needed when the terminal resizes).
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer);
+        }
+        gl_error_unwrap!("Failed to bind index buffer.");
+
+        unsafe {
+            gl::BufferData(
+                // Type of buffer.
+                gl::ELEMENT_ARRAY_BUFFER,
+                // Size of the data.
+                (indices.len() as usize * mem::size_of::<GLuint>()) as GLsizeiptr,
+                // Pointer.
+                mem::transmute(&indices[0]),
+                // Type of storage access.
+                gl::STATIC_DRAW,
+            );
+        }
+        gl_error_unwrap!("Failed to upload index buffer data.");
+
+        // Populate the vertex buffers with blank data.
+        //-----------------------------------------------------------------------------------------
+
+        // The max # of bytes in the vertex buffers is:
+        // max # of bytes in the background...
+        let max_background_len = num_quads * VERTICES_PER_QUAD * mem::size_of::<Vertex>();
+        // plus the max # of bytes in the foreground...
+        let max_foreground_len = (num_quads * VERTICES_PER_QUAD * mem::size_of::<Vertex>()) * 2;
+        // (times 2 to account for the regular and outline glyphs)...
+        // plus the max # of bytes in the proportional text.
+        let max_text_len = MAX_TEXT_QUADS * VERTICES_PER_QUAD * mem::size_of::<Vertex>();
+        let max_vertex_len = max_background_len + max_foreground_len + max_text_len;
+
+        // Create an empty byte vec.
+        let blank_vertex_data = vec![u8::default(); max_vertex_len];
+
+        // Bind the buffers and upload the empty data.
+        for &buffer in vertex_buffers {
+            unsafe {
+                gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+            }
+            gl_error_unwrap!("Failed to bind vertex buffer.");
+
+            unsafe {
+                gl::BufferData(
+                    // Storage type.
+                    gl::ARRAY_BUFFER,
+                    // Size of the data.
+                    blank_vertex_data.len() as GLsizeiptr,
+                    // Pointer.
+                    mem::transmute(&blank_vertex_data[0]),
+                    // Type of storage access.
+                    gl::STREAM_DRAW,
+                );
+            }
+            gl_error_unwrap!("Failed to upload vertex buffer data.");
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the scale at which a terminal fits within a window.
+    //---------------------------------------------------------------------------------------------
+    fn fit_scale((width, height): ICoord, terminal_dimensions: ICoord) -> f32 {
+        let effective_width = (terminal_dimensions.0 * CONFIG.tile_dimensions.0) as f32;
+        let effective_height = (terminal_dimensions.1 * CONFIG.tile_dimensions.1) as f32;
+
+        (width as f32 / effective_width).min(height as f32 / effective_height)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the terminal in # of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn terminal_dimensions(&self) -> ICoord {
+        self.terminal_dimensions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the terminal dimensions that fill a window with whole tiles at the scale the
+    // configured terminal has in the configured window (but no smaller than the configured
+    // terminal, which is scaled down to fit smaller windows instead).
+    //---------------------------------------------------------------------------------------------
+    pub fn fit_terminal_dimensions(&self, (width, height): ICoord) -> ICoord {
+        let tile_width = self.tile_dimensions.0 as f32 * self.tile_scale;
+        let tile_height = self.tile_dimensions.1 as f32 * self.tile_scale;
+
+        (
+            ((width as f32 / tile_width).floor() as i32).max(CONFIG.terminal_dimensions.0),
+            ((height as f32 / tile_height).floor() as i32).max(CONFIG.terminal_dimensions.1),
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resizes the terminal, reallocating the buffers and forcing the next sync.
+    // (the viewport should be updated afterwards)
+    //---------------------------------------------------------------------------------------------
+    pub fn resize_terminal(&mut self, terminal_dimensions: ICoord) -> Result<()> {
+        if terminal_dimensions == self.terminal_dimensions {
+            return Ok(());
+        }
+
+        self.reallocate_buffers((terminal_dimensions.0 * terminal_dimensions.1) as usize)
+            .context("Failed to reallocate buffers for the resized terminal.")?;
+        self.terminal_dimensions = terminal_dimensions;
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that reallocates the buffers for a # of tiles and forces the next sync.
+    //---------------------------------------------------------------------------------------------
+    fn reallocate_buffers(&mut self, num_tiles: usize) -> Result<()> {
+        Self::allocate_buffers(self.index_buffer, &self.vertex_buffers, num_tiles)?;
+
+        self.allocated_tiles = num_tiles;
+        self.synced_revision = None;
+        self.unchanged_syncs = 0;
+        self.background_indices_len = [0; 2];
+        self.foreground_indices_len = [0; 2];
+        self.text_indices_len = [0; 2];
+
+        Ok(())
+    }
+
     //---------------------------------------------------------------------------------------------
     // Update the OpenGL viewport and projection matrices for a new window size.
     // (should be called whenever the window size changes and no more than once per frame)
@@ -687,21 +1031,18 @@ impl RendererV2 {
         let x_ratio = width as f32 / effective_width;
         let y_ratio = height as f32 / effective_height;
 
-        // Depending on which ratio is larger, set the translation and scale to center the quad grid.
-        let x_translate;
-        let y_translate;
-        let scale;
+        // Scale the quad grid by the smaller ratio, but when the terminal is resized with the window
+        // keep the tile scale and leave the leftover pixels as a border.
+        let mut scale = x_ratio.min(y_ratio);
 
-        if x_ratio > y_ratio {
-            x_translate = ((width as f32 - (effective_width * y_ratio)) / 2.0).floor();
-            y_translate = 0.0;
-            scale = y_ratio;
-        } else {
-            x_translate = 0.0;
-            y_translate = ((height as f32 - (effective_height * x_ratio)) / 2.0).floor();
-            scale = x_ratio;
+        if CONFIG.resize_terminal {
+            scale = scale.min(self.tile_scale);
         }
 
+        // Set the translation to center the quad grid.
+        let x_translate = ((width as f32 - (effective_width * scale)) / 2.0).floor();
+        let y_translate = ((height as f32 - (effective_height * scale)) / 2.0).floor();
+
         // Calculate an orthographic projection matrix with our translation and scale.
         let projection =
             Mat4::orthographic_lh(0.0, width as f32, height as f32, 0.0, -100.0, 100.0);
@@ -711,7 +1052,7 @@ impl RendererV2 {
 
         let uniform_data = combined.to_cols_array();
 
-        // Upload the new uniform data to both the background and foreground shader programs.
+        // Upload the new uniform data to the background, foreground and overdraw shader programs.
         unsafe {
             gl::UseProgram(self.background_program);
             gl_error_unwrap!("Failed to use background program for updating projection.");
@@ -734,6 +1075,17 @@ impl RendererV2 {
                 &uniform_data as *const f32,
             );
             gl_error_unwrap!("Failed to update foreground projection matrix.");
+
+            gl::UseProgram(self.overdraw_program);
+            gl_error_unwrap!("Failed to use overdraw program for updating projection.");
+
+            gl::UniformMatrix4fv(
+                self.overdraw_projection_location,
+                1,
+                gl::FALSE as GLboolean,
+                &uniform_data as *const f32,
+            );
+            gl_error_unwrap!("Failed to update overdraw projection matrix.");
         }
 
         // Save the inverse projection matrix for converting screen coords to world coords.
@@ -751,12 +1103,8 @@ impl RendererV2 {
         let normalized_y = 1.0 - 2.0 * y as f32 / self.viewport[3] as f32;
 
         // Apply the inverse projection matrix to convert to world coords.
-        let projected = self.inverse_projection.mul_vec4(glam::Vec4::new(
-            normalized_x,
-            normalized_y,
-            1.0,
-            1.0,
-        ));
+        let projected =
+            self.inverse_projection.mul_vec4(Vec4::new(normalized_x, normalized_y, 1.0, 1.0));
 
         // Cast the coords to int.
         let x = projected.x as i32;
@@ -784,198 +1132,300 @@ impl RendererV2 {
     }
 
     //---------------------------------------------------------------------------------------------
-    // Push a colored quad onto the background vertices, based on a tile.
-    //---------------------------------------------------------------------------------------------
-    fn push_background_quad(&mut self, (x, y): ICoord, tile: &Tile, opacity: GLfloat) {
-        let mut vertex = Vertex::default();
-
-        // Each vertex of the quad shares the same color values (for now).
-        vertex.color[0] = tile.background_color.0.r as GLfloat
-            * COLOR_NORMALIZE_8BIT
-            * opacity
-            * tile.background_opacity;
-        vertex.color[1] = tile.background_color.0.g as GLfloat
-            * COLOR_NORMALIZE_8BIT
-            * opacity
-            * tile.background_opacity;
-        vertex.color[2] = tile.background_color.0.b as GLfloat
-            * COLOR_NORMALIZE_8BIT
-            * opacity
-            * tile.background_opacity;
-
-        // Top left.
-        vertex.position[0] = (x * self.tile_dimensions.0) as GLfloat;
-        vertex.position[1] = (y * self.tile_dimensions.1) as GLfloat;
-        self.background_vertices.push(vertex);
-
-        // Top right.
-        vertex.position[0] = ((x * self.tile_dimensions.0) + self.tile_dimensions.0) as GLfloat;
-        vertex.position[1] = (y * self.tile_dimensions.1) as GLfloat;
-        self.background_vertices.push(vertex);
-
-        // Bottom left.
-        vertex.position[0] = ((x * self.tile_dimensions.0) + self.tile_dimensions.0) as GLfloat;
-        vertex.position[1] = ((y * self.tile_dimensions.1) + self.tile_dimensions.1) as GLfloat;
-        self.background_vertices.push(vertex);
-
-        // Bottom right.
-        vertex.position[0] = (x * self.tile_dimensions.0) as GLfloat;
-        vertex.position[1] = ((y * self.tile_dimensions.1) + self.tile_dimensions.1) as GLfloat;
-        self.background_vertices.push(vertex);
+    // Sets the glyph rendered in place of glyphs missing from the font metrics.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_fallback_glyph(&mut self, glyph: char) {
+        self.fallback_glyph = glyph;
     }
 
     //---------------------------------------------------------------------------------------------
-    // Calculate the offset for a glyph (in pixels) given a tile layout.
-    // TODO: Which produces fewer scaling artifacts - floor() or round()?
-    //---------------------------------------------------------------------------------------------
-    fn calculate_glyph_offset(&self, metric: &GlyphMetric, layout: TileLayout) -> (f32, f32) {
-        match layout {
-            // Center the glyph.
-            TileLayout::Center => (
-                ((self.tile_dimensions.0 - metric.width) as f32 / 2.0).floor(),
-                ((self.tile_dimensions.1 - metric.height) as f32 / 2.0).floor(),
-            ),
-            // Center the glyph horizontally but align with the base of the quad vertically.
-            TileLayout::Floor => (
-                ((self.tile_dimensions.0 - metric.width) as f32 / 2.0).floor(),
-                (self.tile_dimensions.1 - metric.height) as f32,
-            ),
-            // Adjust the glyph based on font metrics.
-            TileLayout::Text => (metric.x_offset as f32, metric.y_offset as f32),
-            // Adjust the glyph from the center position by an exact offset.
-            TileLayout::Exact((x, y)) => (
-                (((self.tile_dimensions.0 - metric.width) as f32 / 2.0) + x as f32).floor(),
-                (((self.tile_dimensions.1 - metric.height) as f32 / 2.0) + y as f32).floor(),
-            ),
+    // Replaces the font textures and metrics with those of another font, forcing the next sync.
+    // (a font whose metrics fail to load is rejected before any texture is replaced)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_font(&mut self, font_name: &str) -> Result<()> {
+        // Load the metrics first, so that a missing font does not leave mismatched textures.
+        let metrics = load_font_metrics(font_name)
+            .with_context(|| format!("Failed to load metrics of font {}.", font_name))?;
+        let (texel_normalize, atlas_scale) = load_font_textures(
+            font_name,
+            self.glyphs_texture,
+            &self.profile,
+            self.stats.current_mut(),
+        )
+        .with_context(|| format!("Failed to load textures of font {}.", font_name))?;
+
+        // Reallocating the texture array left the dynamic glyphs layer undefined (and the vertices
+        // built with the old metrics are dropped).
+        self.builder_mut()?.set_font(metrics, texel_normalize, atlas_scale);
+        self.built = false;
+        self.synced_revision = None;
+        self.unchanged_syncs = 0;
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets whether to render the full frame vignette (never rendered without post-processing).
+    // (does nothing if the vignette pass has been removed from the render graph)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_vignette_enabled(&mut self, enabled: bool) -> Result<()> {
+        if self.render_graph.get(RenderPassKind::Vignette).is_none() {
+            return Ok(());
         }
+
+        self.render_graph.set_enabled(RenderPassKind::Vignette, enabled)
     }
 
     //---------------------------------------------------------------------------------------------
-    // Push a colored and textured quad onto the foreground vertices, based on a tile.
+    // Returns the render passes drawn each frame.
     //---------------------------------------------------------------------------------------------
-    fn push_foreground_quad(
-        &mut self,
-        (x, y): ICoord,
-        tile: &Tile,
-        outline_quad: bool,
-        opacity: GLfloat,
-    ) -> Result<()> {
-        let mut vertex = Vertex::default();
+    pub fn render_graph(&self) -> &RenderGraph {
+        &self.render_graph
+    }
 
-        // Find and set the texture/metric index.
-        let index = if outline_quad {
-            tile.style as usize + TILE_STYLE_COUNT
-        } else {
-            tile.style as usize
-        };
+    //---------------------------------------------------------------------------------------------
+    // Returns the render passes drawn each frame for inserting, removing or toggling passes.
+    //---------------------------------------------------------------------------------------------
+    pub fn render_graph_mut(&mut self) -> &mut RenderGraph {
+        &mut self.render_graph
+    }
 
-        vertex.tex_index = index as GLfloat;
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters of the recent frames.
+    //---------------------------------------------------------------------------------------------
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
 
-        // Retrieve the metrics for the tile's glyph and style.
-        let metric = self.metrics[index]
-            .get(&(tile.glyph as i32))
-            .with_context(|| format!("Failed to load outline metric for glyph {}.", tile.glyph))?;
+    //---------------------------------------------------------------------------------------------
+    // Sets the hook called the first time each missing glyph is encountered.
+    // (without a hook a warning is printed instead)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_missing_glyph_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(char) + 'static,
+    {
+        self.missing_glyph_hook = Some(Box::new(hook));
+    }
 
-        // Use either the foreground or outline color from the tile.
-        let color = if outline_quad { tile.outline_color } else { tile.foreground_color };
+    //---------------------------------------------------------------------------------------------
+    // Sets the cell rendered highlighted (with inverted colors), if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_highlighted_cell(&mut self, xy: Option<ICoord>) {
+        self.highlighted_cell = xy;
+    }
 
-        // Calculate the glyph offset for the tile's layout.
-        let offset = self.calculate_glyph_offset(metric, tile.layout);
+    //---------------------------------------------------------------------------------------------
+    // Sets the position (in pixels, relative to the terminal) and tile of the software cursor,
+    // which is drawn over the terminal tiles (or none to not draw it).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_software_cursor(&mut self, cursor: Option<((f32, f32), Tile)>) {
+        self.software_cursor = cursor;
+    }
 
-        // Get the texel normalize values.
-        let texel_normalize = &self.texel_normalize[index];
+    //---------------------------------------------------------------------------------------------
+    // Advances the palette cycling rotations by a frame delta time.
+    // (should be called once per game loop)
+    //---------------------------------------------------------------------------------------------
+    pub fn update_palette_cycles(&mut self, dt: Duration) {
+        let _ = self.palette_cycler.update(dt);
+    }
 
-        // Each vertex of the quad shares the same color values (for now).
-        vertex.color[0] = color.0.r as GLfloat * COLOR_NORMALIZE_8BIT;
-        vertex.color[1] = color.0.g as GLfloat * COLOR_NORMALIZE_8BIT;
-        vertex.color[2] = color.0.b as GLfloat * COLOR_NORMALIZE_8BIT;
+    //---------------------------------------------------------------------------------------------
+    // Returns a mutable ref to the palette cycler (e.g. for resetting the rotations).
+    //---------------------------------------------------------------------------------------------
+    pub fn palette_cycler_mut(&mut self) -> &mut PaletteCycler {
+        &mut self.palette_cycler
+    }
 
-        if outline_quad {
-            vertex.color[3] = opacity as GLfloat * tile.outline_opacity;
-        } else {
-            vertex.color[3] = opacity as GLfloat * tile.foreground_opacity;
+    //---------------------------------------------------------------------------------------------
+    // Helper function that waits for the vertices being built on the vertex worker, if any, and
+    // takes the vertex builder back. Missing glyphs encountered while building are reported.
+    //---------------------------------------------------------------------------------------------
+    fn finish_build(&mut self) -> Result<()> {
+        if self.builder.is_none() {
+            let worker =
+                self.worker.as_ref().context("Vertex builder missing without a worker.")?;
+            let (builder, result) = worker.receive()?;
+            self.builder = Some(builder);
+            self.built = result.is_ok();
+            self.report_missing_glyphs();
+            result.context("Failed to build vertices on the vertex worker.")?;
         }
 
-        // Top left.
-        vertex.position[0] = (x * self.tile_dimensions.0) as f32 + offset.0;
-        vertex.position[1] = (y * self.tile_dimensions.1) as f32 + offset.1;
-        vertex.tex_coords[0] = (metric.x as f32) * texel_normalize.0;
-        vertex.tex_coords[1] = (metric.y as f32) * texel_normalize.1;
-        self.foreground_vertices.push(vertex);
-
-        // Top right.
-        vertex.position[0] = ((x * self.tile_dimensions.0) + metric.width) as f32 + offset.0;
-        vertex.position[1] = (y * self.tile_dimensions.1) as f32 + offset.1;
-        vertex.tex_coords[0] = ((metric.x + metric.width) as f32) * texel_normalize.0;
-        vertex.tex_coords[1] = (metric.y as f32) * texel_normalize.1;
-        self.foreground_vertices.push(vertex);
-
-        // Bottom left.
-        vertex.position[0] = ((x * self.tile_dimensions.0) + metric.width) as f32 + offset.0;
-        vertex.position[1] = ((y * self.tile_dimensions.1) + metric.height) as f32 + offset.1;
-        vertex.tex_coords[0] = ((metric.x + metric.width) as f32) * texel_normalize.0;
-        vertex.tex_coords[1] = ((metric.y + metric.height) as f32) * texel_normalize.1;
-        self.foreground_vertices.push(vertex);
-
-        // Bottom right.
-        vertex.position[0] = (x * self.tile_dimensions.0) as f32 + offset.0;
-        vertex.position[1] = ((y * self.tile_dimensions.1) + metric.height) as f32 + offset.1;
-        vertex.tex_coords[0] = (metric.x as f32) * texel_normalize.0;
-        vertex.tex_coords[1] = ((metric.y + metric.height) as f32) * texel_normalize.1;
-        self.foreground_vertices.push(vertex);
-
         Ok(())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the vertex builder, waiting for the vertex worker if needed.
+    //---------------------------------------------------------------------------------------------
+    fn builder_mut(&mut self) -> Result<&mut VertexBuilder> {
+        self.finish_build()?;
+        self.builder.as_mut().context("Vertex builder missing.")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that reports the glyphs missing from the font metrics encountered since
+    // the last report, to the hook if set (or as warnings otherwise).
+    //---------------------------------------------------------------------------------------------
+    fn report_missing_glyphs(&mut self) {
+        let glyphs = match self.builder.as_mut() {
+            Some(builder) => builder.take_unreported_glyphs(),
+            None => return,
+        };
+
+        for glyph in glyphs {
+            match self.missing_glyph_hook.as_mut() {
+                Some(hook) => hook(glyph),
+                None => eprintln!(
+                    "[RendererV2] Missing metric for glyph {:?} (U+{:04X}), using {:?}.",
+                    glyph, glyph as u32, self.fallback_glyph
+                ),
+            }
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Sync the vertex state with the terminal.
-    // (should be called once per frame)
+    // (should be called once per frame, and is skipped if the terminal has not changed)
+    // With threaded sync, the vertices built since the last sync are uploaded (to be rendered
+    // this frame) and the vertices of the terminal are then built on the vertex worker, to be
+    // uploaded on the next sync. This puts the rendered frame one sync behind the terminal.
     //---------------------------------------------------------------------------------------------
     pub fn sync_with_terminal(&mut self, terminal: &Terminal) -> Result<()> {
-        // Clear the vertex vecs.
-        self.background_vertices.clear();
-        self.foreground_vertices.clear();
-
         // Get the opacity modifier for the entire terminal.
         let opacity = terminal.opacity();
 
-        // Iterate over all tiles, pushing quads for those that are visible.
+        // Upload the terminal's color grading if it has changed (this does not affect vertices).
+        let color_grade = terminal.color_grade();
+
+        if self.synced_color_grade != Some(color_grade) {
+            self.upload_color_grade(&color_grade).context("Failed to upload color grading.")?;
+            self.synced_color_grade = Some(color_grade);
+        }
+
+        // Grow the buffers if the terminal, map layer and software cursor have more tiles than
+        // allocated for.
         //-----------------------------------------------------------------------------------------
-        for (coord, tile) in terminal.coords_and_tiles_iter() {
-            // Skip the background if it would not be visible.
-            if tile.background_color.0.a != 0
-                && tile.background_opacity > 0.0
-                && tile.background_color.0 != self.clear_color
-            {
-                self.push_background_quad(coord, tile, opacity);
-            }
+        let map_layer = terminal.map_layer().map(|layer| (layer.region(), layer.zoom()));
+        let software_cursor = self.software_cursor;
+        let num_tiles = (terminal.width() * terminal.height()) as usize
+            + terminal.map_layer().map_or(0, |layer| (layer.width() * layer.height()) as usize)
+            + software_cursor.is_some() as usize;
+
+        if num_tiles > self.allocated_tiles {
+            self.reallocate_buffers(num_tiles)
+                .context("Failed to reallocate buffers for the map layer.")?;
+        }
 
-            // Skip the foreground if it would not be visible
-            if tile.glyph != ' ' && tile.foreground_color.0.a != 0 && tile.foreground_opacity > 0.0
-            // TODO: Is this check worth fixing, performance wise? It is currently broken.
-            // && tile.foreground_color != tile.background_color
-            {
-                self.push_foreground_quad(coord, tile, false, opacity)
-                    .context("Failed to push foreground regular quad")?;
+        // Upload the vertices built on the vertex worker since the last sync (this is where the
+        // main thread waits for the worker, if it has not finished yet).
+        //-----------------------------------------------------------------------------------------
+        self.finish_build()?;
+
+        if self.built {
+            self.upload_vertices()?;
+        }
+
+        // Skip the sync if the terminal has not changed since the last one.
+        // When alternating vbos both buffers must first be brought up to date.
+        //-----------------------------------------------------------------------------------------
+        let revision = Some(terminal.revision());
+
+        if revision == self.synced_revision
+            && opacity == self.synced_opacity
+            && terminal.text_runs() == &self.synced_text_runs[..]
+            && self.highlighted_cell == self.synced_highlighted_cell
+            && map_layer == self.synced_map_layer
+            && software_cursor == self.synced_software_cursor
+            && self.palette_cycler.offsets() == self.synced_palette_offsets
+        {
+            let buffer_count = if self.profile.alternating_vbos { 2 } else { 1 };
+
+            if self.unchanged_syncs >= buffer_count {
+                return Ok(());
             }
 
-            // Skip the foreground outline if it is not enabled or would not be visible.
-            if tile.outlined && tile.outline_color.0.a != 0 && tile.outline_opacity > 0.0 {
-                self.push_foreground_quad(coord, tile, true, opacity)
-                    .context("Failed to push foreground outline quad")?;
+            self.unchanged_syncs += 1;
+        } else {
+            self.synced_revision = revision;
+            self.synced_opacity = opacity;
+            self.synced_text_runs.clear();
+            self.synced_text_runs.extend_from_slice(terminal.text_runs());
+            self.synced_highlighted_cell = self.highlighted_cell;
+            self.synced_map_layer = map_layer;
+            self.synced_software_cursor = software_cursor;
+            self.synced_palette_offsets = self.palette_cycler.offsets();
+            self.unchanged_syncs = 1;
+        }
+
+        self.stats.current_mut().synced = true;
+
+        // Snapshot the frame and build its vertices, either on the vertex worker or right away.
+        //-----------------------------------------------------------------------------------------
+        let mut builder = self.builder.take().context("Vertex builder missing.")?;
+        builder.snapshot(
+            terminal,
+            self.highlighted_cell,
+            self.software_cursor,
+            &self.palette_cycler,
+            self.fallback_glyph,
+        );
+
+        match self.worker.as_ref() {
+            Some(worker) => worker.send(builder)?,
+            None => {
+                let result = builder.build();
+                self.builder = Some(builder);
+                self.built = result.is_ok();
+                self.report_missing_glyphs();
+                result?;
+
+                self.upload_vertices()?;
             }
         }
 
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that uploads the built vertices (and the glyphs rasterized while building
+    // them) to the vertex buffer rendered next.
+    //---------------------------------------------------------------------------------------------
+    fn upload_vertices(&mut self) -> Result<()> {
+        let builder = self.builder.as_mut().context("Vertex builder missing.")?;
+        self.built = false;
+
+        // Upload the glyphs rasterized while pushing the quads.
+        //-----------------------------------------------------------------------------------------
+        if builder.dynamic_glyphs_mut().upload(
+            self.glyphs_texture,
+            GLYPHS_TEXTURE_UNIT,
+            DYNAMIC_GLYPHS_INDEX,
+        )? {
+            let (width, height) = DYNAMIC_GLYPHS_PAGE_DIMENSIONS;
+            let stats = self.stats.current_mut();
+            stats.bytes_uploaded += (width * height * 4) as usize;
+            stats.texture_binds += 1;
+        }
+
         // Update the vertex buffer with the new vertex data.
         //-----------------------------------------------------------------------------------------
 
         // Determine index for the current vertex buffer and vertex arrays.
-        let noncurrent_index = if CONFIG.use_alternating_vbos {
+        // Vertices built on the worker are a sync behind, so they go to the buffer rendered this
+        // frame (last rendered two frames ago when alternating) rather than the next one.
+        let noncurrent_index = if self.profile.alternating_vbos && self.worker.is_none() {
             !self.target_backbuffer
         } else {
             self.target_backbuffer
         } as usize;
 
+        let [background_vertices, foreground_vertices, text_vertices] = builder.vertices();
+
+        // Time the map and unmap, which stall if the GPU is still using the buffer.
+        let map_start = Instant::now();
+
         // Bind the vertex buffer not currently being rendered.
         unsafe {
             gl::BindBuffer(gl::ARRAY_BUFFER, self.vertex_buffers[noncurrent_index]);
@@ -986,14 +1436,13 @@ impl RendererV2 {
             gl_error_unwrap!("Failed to map vertex buffer.");
 
             // Determine size of background vertices.
-            let background_vertices_size =
-                self.background_vertices.len() * mem::size_of::<Vertex>();
+            let background_vertices_size = mem::size_of_val(background_vertices);
 
             // If background vertices are present, copy them into the buffer.
-            if !self.background_vertices.is_empty() {
+            if !background_vertices.is_empty() {
                 ptr::copy_nonoverlapping(
                     // Source pointer.
-                    mem::transmute(&self.background_vertices[0]),
+                    mem::transmute(&background_vertices[0]),
                     // Destination pointer.
                     ptr,
                     // Size.
@@ -1002,17 +1451,34 @@ impl RendererV2 {
             }
 
             // // If foreground vertices are present, copy them into the buffer.
-            if !self.foreground_vertices.is_empty() {
+            if !foreground_vertices.is_empty() {
                 // Determine the starting offset in the buffer for the foreground.
                 let ptr = (ptr as usize) + background_vertices_size;
 
                 ptr::copy_nonoverlapping(
                     // Source pointer.
-                    mem::transmute(&self.foreground_vertices[0]),
+                    mem::transmute(&foreground_vertices[0]),
+                    // Destination pointer.
+                    ptr as *mut c_void,
+                    // Size.
+                    mem::size_of_val(foreground_vertices),
+                );
+            }
+
+            // If text vertices are present, copy them into the buffer.
+            if !text_vertices.is_empty() {
+                // Determine the starting offset in the buffer for the text.
+                let ptr = (ptr as usize)
+                    + background_vertices_size
+                    + mem::size_of_val(foreground_vertices);
+
+                ptr::copy_nonoverlapping(
+                    // Source pointer.
+                    mem::transmute(&text_vertices[0]),
                     // Destination pointer.
                     ptr as *mut c_void,
                     // Size.
-                    self.foreground_vertices.len() * mem::size_of::<Vertex>(),
+                    mem::size_of_val(text_vertices),
                 );
             }
 
@@ -1021,114 +1487,220 @@ impl RendererV2 {
             gl_error_unwrap!("Failed to unmap vertex buffer.");
         }
 
+        let vertices_len =
+            background_vertices.len() + foreground_vertices.len() + text_vertices.len();
+        let stats = self.stats.current_mut();
+        stats.record_map(map_start.elapsed());
+        stats.bytes_uploaded += vertices_len * mem::size_of::<Vertex>();
+
         // Calculate and cache the indices counts.
         self.background_indices_len[noncurrent_index] =
-            ((self.background_vertices.len() / VERTICES_PER_QUAD) * INDICES_PER_QUAD) as GLsizei;
+            ((background_vertices.len() / VERTICES_PER_QUAD) * INDICES_PER_QUAD) as GLsizei;
 
         self.foreground_indices_len[noncurrent_index] =
-            ((self.foreground_vertices.len() / VERTICES_PER_QUAD) * INDICES_PER_QUAD) as GLsizei;
+            ((foreground_vertices.len() / VERTICES_PER_QUAD) * INDICES_PER_QUAD) as GLsizei;
+
+        self.text_indices_len[noncurrent_index] =
+            ((text_vertices.len() / VERTICES_PER_QUAD) * INDICES_PER_QUAD) as GLsizei;
 
         Ok(())
     }
 
     //---------------------------------------------------------------------------------------------
-    // Render a frame and flip the backbuffer.
+    // Render a frame by drawing the enabled passes of the render graph in order, and flip the
+    // backbuffer.
     // (should be called once per frame (obviously lol)).
     //---------------------------------------------------------------------------------------------
     pub fn render(&mut self) -> Result<()> {
-        // Clear the frame.
-        unsafe {
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-        }
-
         // Determine index for the current vertex arrays.
         let current_index = self.target_backbuffer as usize;
 
-        // Draw the background (solid colored quads).
-        unsafe {
-            // Disable blending.
-            gl::Disable(gl::BLEND);
-            gl_error_unwrap!("Failed to disable blending.");
-
-            // Disable depth testing.
-            gl::DepthMask(gl::FALSE);
-            gl_error_unwrap!("Failed to disable depth testing.");
+        for i in 0..self.render_graph.schedule().len() {
+            let kind = self.render_graph.schedule()[i];
+            self.draw_pass(kind, current_index)
+                .with_context(|| format!("Failed to draw {:?} render pass.", kind))?;
+        }
 
-            // Enable the background shader program and vertex array.
-            gl::UseProgram(self.background_program);
-            gl_error_unwrap!("Failed to use background program for rendering.");
+        // Flip the targeted buffer / vertex arrays.
+        if self.profile.alternating_vbos {
+            self.target_backbuffer = !self.target_backbuffer;
+        }
 
-            gl::BindVertexArray(self.background_vertex_arrays[current_index]);
-            gl_error_unwrap!("Failed to enable background vertex array for rendering.");
+        self.stats.end_frame();
 
-            // Draw the background quads.
-            gl::DrawElements(
-                // Mode.
-                gl::TRIANGLES,
-                // Size.
-                self.background_indices_len[current_index],
-                // Type.
-                gl::UNSIGNED_INT,
-                // Pointer (null because the background starts at the beginning of the VBO).
-                ptr::null(),
-            );
-            gl_error_unwrap!("Failed to draw background elements.");
-        }
+        Ok(())
+    }
 
-        // Calculate the foreground offset.
-        let foreground_indices_offset =
-            mem::size_of::<GLuint>() * self.background_indices_len[current_index] as usize;
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the byte offsets into the index buffer of the background,
+    // foreground and text indices.
+    //---------------------------------------------------------------------------------------------
+    fn indices_offsets(&self, index: usize) -> [usize; 3] {
+        let foreground_offset =
+            mem::size_of::<GLuint>() * self.background_indices_len[index] as usize;
+        let text_offset = foreground_offset
+            + mem::size_of::<GLuint>() * self.foreground_indices_len[index] as usize;
 
-        // Draw the foreground (regular + outline glyphs).
-        unsafe {
-            // Enable blending.
-            gl::Enable(gl::BLEND);
-            gl_error_unwrap!("Failed to enable blending.");
+        [0, foreground_offset, text_offset]
+    }
 
-            // Enable depth testing.
-            gl::DepthMask(gl::TRUE);
-            gl_error_unwrap!("Failed to enable depth testing.");
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws a range of the current quads with a program and vertex array,
+    // recording them for a render pass.
+    //---------------------------------------------------------------------------------------------
+    fn draw_quads(
+        stats: &mut FrameStats,
+        kind: RenderPassKind,
+        program: GLuint,
+        vertex_array: GLuint,
+        indices_len: GLsizei,
+        indices_offset: usize,
+    ) -> Result<()> {
+        if indices_len == 0 {
+            return Ok(());
+        }
 
-            // Enable the foreground shader program and vertex array.
-            gl::UseProgram(self.foreground_program);
-            gl_error_unwrap!("Failed to use foreground program for rendering.");
+        unsafe {
+            gl::UseProgram(program);
+            gl_error_unwrap!("Failed to use program for rendering.");
 
-            gl::BindVertexArray(self.foreground_vertex_arrays[current_index]);
-            gl_error_unwrap!("Failed to enable foreground vertex array for rendering.");
+            gl::BindVertexArray(vertex_array);
+            gl_error_unwrap!("Failed to enable vertex array for rendering.");
 
-            // Draw the foreground quads.
             gl::DrawElements(
                 // Mode.
                 gl::TRIANGLES,
                 // Size.
-                self.foreground_indices_len[current_index],
+                indices_len,
                 // Type.
                 gl::UNSIGNED_INT,
-                // Pointer (offset by # of background indices).
-                foreground_indices_offset as *const c_void,
+                // Pointer (offset by # of indices of the preceding quads).
+                indices_offset as *const c_void,
             );
-            gl_error_unwrap!("Failed to draw foreground elements.");
+            gl_error_unwrap!("Failed to draw elements.");
         }
 
-        // Draw the vignette.
-        if CONFIG.enable_vignette {
-            unsafe {
-                // Enable the vignette shader program and vertex array.
-                gl::UseProgram(self.vignette_program);
-                gl_error_unwrap!("Failed to use vignette program for rendering.");
+        stats.record_draw(kind, indices_len as usize / INDICES_PER_QUAD);
 
-                gl::BindVertexArray(self.vignette_vertex_array);
-                gl_error_unwrap!("Failed to enable vignette vertex array for rendering.");
+        Ok(())
+    }
 
-                // Draw the single vignette quad (generated by the vertex shader).
-                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
-                gl_error_unwrap!("Failed to draw vignette.");
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws a render pass, setting up the OpenGL state it needs.
+    //---------------------------------------------------------------------------------------------
+    fn draw_pass(&mut self, kind: RenderPassKind, index: usize) -> Result<()> {
+        let [background_offset, foreground_offset, text_offset] = self.indices_offsets(index);
+
+        match kind {
+            RenderPassKind::Clear => unsafe {
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                gl_error_unwrap!("Failed to clear the frame.");
+            },
+            RenderPassKind::Background => {
+                // Solid quads need neither blending nor depth writes.
+                unsafe {
+                    gl::Disable(gl::BLEND);
+                    gl_error_unwrap!("Failed to disable blending.");
+
+                    gl::DepthMask(gl::FALSE);
+                    gl_error_unwrap!("Failed to disable depth testing.");
+                }
+
+                Self::draw_quads(
+                    self.stats.current_mut(),
+                    kind,
+                    self.background_program,
+                    self.background_vertex_arrays[index],
+                    self.background_indices_len[index],
+                    background_offset,
+                )?;
             }
-        }
+            RenderPassKind::Foreground | RenderPassKind::Text => {
+                unsafe {
+                    gl::Enable(gl::BLEND);
+                    gl_error_unwrap!("Failed to enable blending.");
+
+                    gl::DepthMask(gl::TRUE);
+                    gl_error_unwrap!("Failed to enable depth testing.");
+                }
+
+                let (indices_len, indices_offset) = if kind == RenderPassKind::Foreground {
+                    (self.foreground_indices_len[index], foreground_offset)
+                } else {
+                    (self.text_indices_len[index], text_offset)
+                };
+
+                Self::draw_quads(
+                    self.stats.current_mut(),
+                    kind,
+                    self.foreground_program,
+                    self.foreground_vertex_arrays[index],
+                    indices_len,
+                    indices_offset,
+                )?;
+            }
+            RenderPassKind::Vignette => {
+                // The vignette is post-processing, which the GPU's profile may rule out.
+                if !self.profile.post_processing {
+                    return Ok(());
+                }
 
-        // Flip the targeted buffer / vertex arrays.
-        if CONFIG.use_alternating_vbos {
-            self.target_backbuffer = !self.target_backbuffer;
+                unsafe {
+                    gl::Enable(gl::BLEND);
+                    gl_error_unwrap!("Failed to enable blending.");
+
+                    gl::UseProgram(self.vignette_program);
+                    gl_error_unwrap!("Failed to use vignette program for rendering.");
+
+                    gl::BindVertexArray(self.vignette_vertex_array);
+                    gl_error_unwrap!("Failed to enable vignette vertex array for rendering.");
+
+                    // Draw the single vignette quad (generated by the vertex shader).
+                    gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+                    gl_error_unwrap!("Failed to draw vignette.");
+                }
+
+                self.stats.current_mut().record_draw(kind, 1);
+            }
+            RenderPassKind::Overdraw => {
+                // Replace the frame with black and add up every quad over it.
+                let mut clear_color = [0.0; 4];
+
+                unsafe {
+                    gl::GetFloatv(gl::COLOR_CLEAR_VALUE, &mut clear_color[0]);
+                    gl_error_unwrap!("Failed to query the clear color.");
+
+                    gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+                    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                    gl_error_unwrap!("Failed to clear the frame for the overdraw view.");
+
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::ONE, gl::ONE);
+                    gl_error_unwrap!("Failed to set additive blending.");
+                }
+
+                // The background vertex array covers every quad, and only positions are needed.
+                let indices_len = self.background_indices_len[index]
+                    + self.foreground_indices_len[index]
+                    + self.text_indices_len[index];
+                let result = Self::draw_quads(
+                    self.stats.current_mut(),
+                    kind,
+                    self.overdraw_program,
+                    self.background_vertex_arrays[index],
+                    indices_len,
+                    background_offset,
+                );
+
+                // Restore the clear color and blend func of the other passes.
+                unsafe {
+                    gl::ClearColor(clear_color[0], clear_color[1], clear_color[2], clear_color[3]);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+                    gl_error_unwrap!("Failed to restore the clear color and blend func.");
+                }
+
+                result?;
+            }
         }
 
         Ok(())
@@ -1136,17 +1708,15 @@ impl RendererV2 {
 }
 
 //-------------------------------------------------------------------------------------------------
-// Delete OpenGL objects on drop.
+// Delete OpenGL objects on drop (the shader manager deletes the programs).
 //-------------------------------------------------------------------------------------------------
 impl Drop for RendererV2 {
     fn drop(&mut self) {
         unsafe {
-            gl::DeleteTextures((TILE_STYLE_COUNT * 2) as GLint, &self.textures[0]);
+            gl::DeleteTextures(1, &self.glyphs_texture);
             gl::DeleteVertexArrays(1, &self.vignette_vertex_array);
             gl::DeleteVertexArrays(2, &self.foreground_vertex_arrays[0]);
-            gl::DeleteProgram(self.foreground_program);
             gl::DeleteVertexArrays(2, &self.background_vertex_arrays[0]);
-            gl::DeleteProgram(self.background_program);
             gl::DeleteBuffers(2, &self.vertex_buffers[0]);
             gl::DeleteBuffers(1, &self.index_buffer);
         }
@@ -0,0 +1,315 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::path::{Path, PathBuf};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use fnv::FnvHashMap;
+use gl::types::*;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::gl_helpers::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Directive that is replaced with the contents of another shader file.
+const INCLUDE_DIRECTIVE: &str = "#include";
+
+// Directive that must begin every (non-included) shader file.
+const VERSION_DIRECTIVE: &str = "#version";
+
+//-------------------------------------------------------------------------------------------------
+// Identifies a cached program by the names of its shader files and its variant defines.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProgramKey {
+    // Name of the vertex shader file, relative to the shaders dir.
+    pub vertex: String,
+    // Name of the fragment shader file, relative to the shaders dir.
+    pub fragment: String,
+    // Names defined at the top of both shaders, sorted so that their order does not matter.
+    pub defines: Vec<String>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ShaderSource is the preprocessed source of a shader file, with its includes expanded and its
+// variant defines inserted. Every file gets a GLSL source string number (via #line directives)
+// so that compile errors can be traced back to the file and line they occurred in.
+//-------------------------------------------------------------------------------------------------
+pub struct ShaderSource {
+    // The preprocessed source.
+    pub text: String,
+    // Paths of the files of the source, indexed by their source string numbers.
+    files: Vec<PathBuf>,
+}
+
+impl ShaderSource {
+    //---------------------------------------------------------------------------------------------
+    // Loads and preprocesses a shader file from the shaders dir.
+    //---------------------------------------------------------------------------------------------
+    pub fn load(name: &str, defines: &[String]) -> Result<Self> {
+        let mut source = Self { text: String::new(), files: Vec::new() };
+        source.append_file(name, defines, &mut Vec::new())?;
+
+        Ok(source)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that appends a file to the source, recursively expanding its includes.
+    // The stack holds the names of the files being included to detect cycles.
+    //---------------------------------------------------------------------------------------------
+    fn append_file(
+        &mut self,
+        name: &str,
+        defines: &[String],
+        stack: &mut Vec<String>,
+    ) -> Result<()> {
+        let path = CONTENT_LAYERS.resolve(Path::new(CONFIG_SHADERS_DIR).join(name));
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read shader file {}.", path.display()))?;
+
+        let id = self.files.len();
+        self.files.push(path.clone());
+        stack.push(name.to_string());
+
+        // Included files begin counting lines from the top (the root file is source 0 already).
+        if id > 0 {
+            self.text.push_str(&format!("#line 1 {}\n", id));
+        }
+
+        for (i, line) in text.lines().enumerate() {
+            let directive = line.trim_start();
+
+            // Insert the defines straight after the version of the root file.
+            if directive.starts_with(VERSION_DIRECTIVE) && id == 0 {
+                self.text.push_str(line);
+                self.text.push('\n');
+
+                for define in defines {
+                    self.text.push_str(&format!("#define {}\n", define));
+                }
+
+                self.text.push_str(&format!("#line {} {}\n", i + 2, id));
+                continue;
+            }
+
+            if let Some(argument) = directive.strip_prefix(INCLUDE_DIRECTIVE) {
+                let include = argument.trim().trim_matches('"');
+
+                if include.is_empty() {
+                    return Err(anyhow!("Empty include at {}:{}.", path.display(), i + 1));
+                }
+
+                if stack.iter().any(|name| name == include) {
+                    return Err(anyhow!(
+                        "Cyclic include of {} at {}:{}.",
+                        include,
+                        path.display(),
+                        i + 1
+                    ));
+                }
+
+                self.append_file(include, defines, stack)
+                    .with_context(|| format!("Included at {}:{}.", path.display(), i + 1))?;
+
+                // Resume counting the lines of this file after the include.
+                self.text.push_str(&format!("#line {} {}\n", i + 2, id));
+                continue;
+            }
+
+            self.text.push_str(line);
+            self.text.push('\n');
+        }
+
+        stack.pop();
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Rewrites the "<source>:<line>" (or "<source>(<line>)") locations of a GL info log into the
+    // "<path>:<line>" of the file they refer to.
+    //---------------------------------------------------------------------------------------------
+    pub fn map_log(&self, log: &str) -> String {
+        log.lines()
+            .map(|line| match Self::find_location(line) {
+                Some((start, end, file, number)) if file < self.files.len() => format!(
+                    "{}{}:{}{}",
+                    &line[..start],
+                    self.files[file].display(),
+                    number,
+                    &line[end..]
+                ),
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that finds the first location in a line of a GL info log. Returns the byte
+    // range of the location and its source string and line numbers.
+    // (drivers differ: Mesa logs "0:12(5)", Nvidia "0(12)" and others "0:12")
+    //---------------------------------------------------------------------------------------------
+    fn find_location(line: &str) -> Option<(usize, usize, usize, usize)> {
+        let bytes = line.as_bytes();
+        let digits = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let mut start = 0;
+
+        while start < bytes.len() {
+            if !bytes[start].is_ascii_digit() {
+                start += 1;
+                continue;
+            }
+
+            let file_end = start + digits(start);
+
+            if file_end < bytes.len() && (bytes[file_end] == b':' || bytes[file_end] == b'(') {
+                let number_end = file_end + 1 + digits(file_end + 1);
+
+                if number_end > file_end + 1 {
+                    let file = line[start..file_end].parse().ok()?;
+                    let number = line[file_end + 1..number_end].parse().ok()?;
+                    let closed = bytes[file_end] == b'(' && bytes.get(number_end) == Some(&b')');
+                    let end = if closed { number_end + 1 } else { number_end };
+
+                    return Some((start, end, file, number));
+                }
+            }
+
+            start = file_end;
+        }
+
+        None
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ShaderManager loads shader programs from the shaders dir and caches them by their files and
+// variant defines. Programs can be reloaded from disk while the game is running.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct ShaderManager {
+    // Linked programs by their key.
+    programs: FnvHashMap<ProgramKey, GLuint>,
+}
+
+impl ShaderManager {
+    //---------------------------------------------------------------------------------------------
+    // Returns the program of a vertex and fragment shader with a set of variant defines, building
+    // it the first time it is requested.
+    //---------------------------------------------------------------------------------------------
+    pub fn program(&mut self, vertex: &str, fragment: &str, defines: &[&str]) -> Result<GLuint> {
+        let mut defines: Vec<_> = defines.iter().map(|define| define.to_string()).collect();
+        defines.sort();
+        defines.dedup();
+
+        let key =
+            ProgramKey { vertex: vertex.to_string(), fragment: fragment.to_string(), defines };
+
+        if let Some(program) = self.programs.get(&key) {
+            return Ok(*program);
+        }
+
+        let program = Self::build(&key)?;
+        self.programs.insert(key, program);
+
+        Ok(program)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that compiles a preprocessed shader, mapping errors back to its files.
+    //---------------------------------------------------------------------------------------------
+    fn compile(source: &ShaderSource, shader_type: GLenum) -> Result<GLuint> {
+        compile_shader(&source.text, shader_type)
+            .map_err(|e| anyhow!("Failed to compile shader:\n{}", source.map_log(&e.to_string())))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that loads, compiles and links the program of a key.
+    //---------------------------------------------------------------------------------------------
+    fn build(key: &ProgramKey) -> Result<GLuint> {
+        let vertex_source = ShaderSource::load(&key.vertex, &key.defines)?;
+        let fragment_source = ShaderSource::load(&key.fragment, &key.defines)?;
+
+        let vertex_shader = Self::compile(&vertex_source, gl::VERTEX_SHADER)?;
+        let fragment_shader = match Self::compile(&fragment_source, gl::FRAGMENT_SHADER) {
+            Ok(shader) => shader,
+            Err(e) => {
+                unsafe {
+                    gl::DeleteShader(vertex_shader);
+                }
+                return Err(e);
+            }
+        };
+
+        let program = link_program(vertex_shader, fragment_shader);
+
+        // Shaders are no longer needed.
+        unsafe {
+            gl::DeleteShader(vertex_shader);
+            gl::DeleteShader(fragment_shader);
+        }
+
+        program.with_context(|| format!("Failed to link {} and {}.", key.vertex, key.fragment))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Rebuilds every cached program from disk (e.g. after editing a shader).
+    // If any program fails to build, the previous programs are all kept.
+    // NOTE: Rebuilt programs are new GL objects with default uniform values, so callers must fetch
+    // the programs and their uniform locations again.
+    //---------------------------------------------------------------------------------------------
+    pub fn reload(&mut self) -> Result<()> {
+        let mut rebuilt = Vec::with_capacity(self.programs.len());
+
+        for key in self.programs.keys() {
+            match Self::build(key) {
+                Ok(program) => rebuilt.push((key.clone(), program)),
+                Err(e) => {
+                    for (_, program) in rebuilt {
+                        unsafe {
+                            gl::DeleteProgram(program);
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        for (key, program) in rebuilt {
+            if let Some(previous) = self.programs.insert(key, program) {
+                unsafe {
+                    gl::DeleteProgram(previous);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Delete the cached programs on drop.
+//-------------------------------------------------------------------------------------------------
+impl Drop for ShaderManager {
+    fn drop(&mut self) {
+        for program in self.programs.values() {
+            unsafe {
+                gl::DeleteProgram(*program);
+            }
+        }
+    }
+}
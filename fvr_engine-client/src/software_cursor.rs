@@ -0,0 +1,124 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// CursorSprite is the tile drawn for a cursor style when the software cursor is enabled.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct CursorSprite {
+    // Tile drawn at the mouse position.
+    pub tile: Tile,
+    // Point of the tile over the mouse position, as a fraction of the tile dimensions.
+    pub hotspot: (f32, f32),
+}
+
+impl CursorSprite {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new cursor sprite from a glyph in a color.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(glyph: char, color: TileColor, hotspot: (f32, f32)) -> Self {
+        Self {
+            tile: Tile {
+                glyph,
+                foreground_color: color,
+                outlined: true,
+                outline_color: TileColor::BLACK,
+                ..BLANK_TILE
+            },
+            hotspot,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the default sprite for a cursor style.
+    //---------------------------------------------------------------------------------------------
+    pub fn default_for(cursor: Cursor) -> Self {
+        match cursor {
+            Cursor::Arrow => Self::new('▲', TileColor::WHITE, (0.5, 0.25)),
+            Cursor::Crosshair => Self::new('+', TileColor::WHITE, (0.5, 0.5)),
+            Cursor::Hand => Self::new('↑', TileColor::WHITE, (0.5, 0.2)),
+            Cursor::IBeam => Self::new('|', TileColor::WHITE, (0.5, 0.5)),
+            Cursor::No => Self::new('○', TileColor::RED, (0.5, 0.5)),
+            Cursor::Wait => Self::new('☼', TileColor::WHITE, (0.5, 0.5)),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// SoftwareCursor draws the mouse cursor as a terminal-space glyph in place of the hardware cursor,
+// e.g. so that it matches the terminal's font and scale.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct SoftwareCursor {
+    // Whether the software cursor replaces the hardware cursor.
+    enabled: bool,
+    // Sprites overriding the default sprite of a cursor style.
+    sprites: FnvHashMap<Cursor, CursorSprite>,
+}
+
+impl SoftwareCursor {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the software cursor is enabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Enables or disables the software cursor.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the sprite drawn for a cursor style.
+    //---------------------------------------------------------------------------------------------
+    pub fn sprite(&self, cursor: Cursor) -> CursorSprite {
+        self.sprites.get(&cursor).copied().unwrap_or_else(|| CursorSprite::default_for(cursor))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the sprite drawn for a cursor style (or restores the default if none).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_sprite(&mut self, cursor: Cursor, sprite: Option<CursorSprite>) {
+        match sprite {
+            Some(sprite) => {
+                let _ = self.sprites.insert(cursor, sprite);
+            }
+            None => {
+                let _ = self.sprites.remove(&cursor);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position (in pixels, relative to the terminal) of the top left of the tile and
+    // the tile to draw for a cursor style at a mouse position, if enabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn placement(&self, cursor: Cursor, (x, y): ICoord) -> Option<((f32, f32), Tile)> {
+        if !self.enabled {
+            return None;
+        }
+
+        let sprite = self.sprite(cursor);
+        let position = (
+            x as f32 - sprite.hotspot.0 * CONFIG.tile_dimensions.0 as f32,
+            y as f32 - sprite.hotspot.1 * CONFIG.tile_dimensions.1 as f32,
+        );
+
+        Some((position, sprite.tile))
+    }
+}
@@ -1,6 +1,12 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::sync::atomic::{AtomicU64, Ordering};
+
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
 use itertools::Itertools;
 use rand::seq::SliceRandom;
 use rand::Rng;
@@ -10,6 +16,138 @@ use rand::Rng;
 //-------------------------------------------------------------------------------------------------
 use fvr_engine_core::prelude::*;
 
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::effects::*;
+use crate::particles::*;
+use crate::tile_layer::*;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Id given to the next terminal created, so that revisions of different terminals never match.
+static NEXT_TERMINAL_ID: AtomicU64 = AtomicU64::new(0);
+
+//-------------------------------------------------------------------------------------------------
+// TextRun describes proportional text drawn over the terminal at a pixel position.
+// Unlike tiles, characters are laid out using the advances and kernings of the font rather than
+// snapped to the tile grid, which suits titles and dialogue.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    // Position of the top left of the run in pixels, relative to the terminal.
+    pub position: (f32, f32),
+    // The text (newlines start a new line at the run's x position).
+    pub text: String,
+    // Style of the glyphs.
+    pub style: TileStyle,
+    // Whether the glyphs are outlined.
+    pub outlined: bool,
+    // Color of the glyphs.
+    pub color: TileColor,
+    // Color of the glyph outlines.
+    pub outline_color: TileColor,
+    // Opacity of the run.
+    pub opacity: f32,
+}
+
+impl TextRun {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new white, regular text run at a pixel position.
+    //---------------------------------------------------------------------------------------------
+    pub fn new<S: Into<String>>(position: (f32, f32), text: S) -> Self {
+        Self {
+            position,
+            text: text.into(),
+            style: TileStyle::Regular,
+            outlined: false,
+            color: TileColor::WHITE,
+            outline_color: TileColor::BLACK,
+            opacity: 1.0,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new white, regular text run aligned with the top left of a tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn at_tile<S: Into<String>>((x, y): ICoord, text: S) -> Self {
+        let position =
+            ((x * CONFIG.tile_dimensions.0) as f32, (y * CONFIG.tile_dimensions.1) as f32);
+        Self::new(position, text)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// TerminalSnapshot is a copy of the visible state of the terminal, for restoring it later (e.g. the
+// screen under a modal scene) or crossfading between two states.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone)]
+pub struct TerminalSnapshot {
+    // Grid map of the terminal's tiles.
+    tiles: GridMap<Tile>,
+    // Opacity of the terminal.
+    opacity: f32,
+    // Proportional text runs drawn over the tiles.
+    text_runs: Vec<TextRun>,
+    // Optional map layer drawn beneath the tiles.
+    map_layer: Option<TileLayer>,
+    // Color grading applied to the entire rendered terminal.
+    color_grade: ColorGrade,
+}
+
+impl TerminalSnapshot {
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the snapshotted terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn dimensions(&self) -> ICoord {
+        self.tiles.dimensions()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the tile at a coord (blank if out of bounds).
+    //---------------------------------------------------------------------------------------------
+    fn tile(&self, xy: ICoord) -> Tile {
+        if self.tiles.in_bounds(xy) {
+            *self.tiles.get_xy(xy)
+        } else {
+            BLANK_TILE
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the opacity of the snapshotted terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text runs of the snapshotted terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn text_runs(&self) -> &[TextRun] {
+        &self.text_runs
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the map layer of the snapshotted terminal, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn map_layer(&self) -> Option<&TileLayer> {
+        self.map_layer.as_ref()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Iterates the xy coords in the snapshotted terminal and their corresponding tiles.
+    // (in the same order as the terminal's iterator)
+    //---------------------------------------------------------------------------------------------
+    pub fn coords_and_tiles_iter(&self) -> impl Iterator<Item = (ICoord, &Tile)> {
+        (0..self.tiles.width())
+            .cartesian_product(0..self.tiles.height())
+            .map(move |xy| (xy, self.tiles.get_xy(xy)))
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Terminal contains the state of the faux terminal and exposes an API for updating it.
 //-------------------------------------------------------------------------------------------------
@@ -18,9 +156,60 @@ pub struct Terminal {
     tiles: GridMap<Tile>,
     // Opacity of the terminal.
     opacity: f32,
+    // Proportional text runs drawn over the tiles.
+    text_runs: Vec<TextRun>,
+    // Optional map layer drawn beneath the tiles at its own tile scale.
+    map_layer: Option<TileLayer>,
+    // Color grading applied to the entire rendered terminal.
+    color_grade: ColorGrade,
+    // Snapshots stashed by name (e.g. by modal scenes or debug tools).
+    stash: FnvHashMap<String, TerminalSnapshot>,
+    // Animated effects composited over the tiles when rendered.
+    effects: Effects,
+    // Particles composited over the tiles (and effects) when rendered.
+    particles: ParticleSystem,
+    // Unique id of the terminal.
+    id: u64,
+    // Incremented whenever the tiles (or those of the map layer) may have changed.
+    revision: u64,
 }
 
 impl Terminal {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new terminal of some dimensions. The game's terminal is the default terminal,
+    // sized by the config.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord) -> Self {
+        Self {
+            tiles: GridMap::new(dimensions),
+            opacity: 1.0,
+            text_runs: Vec::new(),
+            map_layer: None,
+            color_grade: ColorGrade::default(),
+            stash: FnvHashMap::default(),
+            effects: Effects::new(),
+            particles: ParticleSystem::new(),
+            id: NEXT_TERMINAL_ID.fetch_add(1, Ordering::Relaxed),
+            revision: 0,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the terminal and the revision of its tiles. The revision changes whenever
+    // the tiles (or those of the map layer) are accessed mutably, so an unchanged revision means
+    // unchanged tiles without comparing them.
+    //---------------------------------------------------------------------------------------------
+    pub fn revision(&self) -> (u64, u64) {
+        (self.id, self.revision)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that marks the tiles as possibly changed.
+    //---------------------------------------------------------------------------------------------
+    fn touch(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
     //---------------------------------------------------------------------------------------------
     // Returns the opacity of the entire terminal.
     //---------------------------------------------------------------------------------------------
@@ -49,10 +238,33 @@ impl Terminal {
         self.opacity = 1.0;
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns the color grading applied to the entire rendered terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn color_grade(&self) -> ColorGrade {
+        self.color_grade
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the color grading applied to the entire rendered terminal (e.g. for a zone's mood).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_color_grade(&mut self, color_grade: ColorGrade) {
+        self.color_grade = color_grade;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resizes the terminal, resetting all tiles to default.
+    //---------------------------------------------------------------------------------------------
+    pub fn resize(&mut self, dimensions: ICoord) {
+        self.tiles.resize(dimensions);
+        self.set_all_tiles_default();
+    }
+
     //---------------------------------------------------------------------------------------------
     // Sets all tiles to default.
     //---------------------------------------------------------------------------------------------
     pub fn set_all_tiles_default(&mut self) {
+        self.touch();
         self.tiles.data_mut().fill(Default::default());
     }
 
@@ -60,6 +272,7 @@ impl Terminal {
     // Sets all tiles to blank.
     //---------------------------------------------------------------------------------------------
     pub fn set_all_tiles_blank(&mut self) {
+        self.touch();
         self.tiles.data_mut().fill(BLANK_TILE);
     }
 
@@ -79,6 +292,7 @@ impl Terminal {
         foreground_color: Option<TileColor>,
         outline_color: Option<TileColor>,
     ) {
+        self.touch();
         let tile = self.tiles.get_xy_mut(xy);
 
         if let Some(glyph) = glyph {
@@ -125,6 +339,8 @@ impl Terminal {
         foreground_opacity: Option<f32>,
         outline_opacity: Option<f32>,
     ) {
+        self.touch();
+
         for tile in self.tiles.data_mut().iter_mut() {
             if let Some(glyph) = glyph {
                 tile.glyph = glyph;
@@ -171,14 +387,259 @@ impl Terminal {
             .map(move |xy| (xy, self.tiles.get_xy(xy)))
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns the map layer, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn map_layer(&self) -> Option<&TileLayer> {
+        self.map_layer.as_ref()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the map layer mutably, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn map_layer_mut(&mut self) -> Option<&mut TileLayer> {
+        self.touch();
+        self.map_layer.as_mut()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets or removes the map layer drawn beneath the tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_map_layer(&mut self, map_layer: Option<TileLayer>) {
+        self.touch();
+        self.map_layer = map_layer;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the proportional text runs drawn over the tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn text_runs(&self) -> &[TextRun] {
+        &self.text_runs
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds a proportional text run to draw over the tiles (until the runs are cleared).
+    //---------------------------------------------------------------------------------------------
+    pub fn push_text_run(&mut self, run: TextRun) {
+        self.text_runs.push(run);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the proportional text runs equal to any of a slice of runs.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove_text_runs(&mut self, runs: &[TextRun]) {
+        if !runs.is_empty() {
+            self.text_runs.retain(|run| !runs.contains(run));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all proportional text runs.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear_text_runs(&mut self) {
+        self.text_runs.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a snapshot of the visible state of the terminal (excluding the stash).
+    //---------------------------------------------------------------------------------------------
+    pub fn snapshot(&self) -> TerminalSnapshot {
+        TerminalSnapshot {
+            tiles: self.tiles.clone(),
+            opacity: self.opacity,
+            text_runs: self.text_runs.clone(),
+            map_layer: self.map_layer.clone(),
+            color_grade: self.color_grade,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies the visible state of the terminal into an existing snapshot, reusing its allocations
+    // (e.g. when snapshotting every frame).
+    //---------------------------------------------------------------------------------------------
+    pub fn snapshot_into(&self, snapshot: &mut TerminalSnapshot) {
+        if snapshot.dimensions() == self.dimensions() {
+            snapshot.tiles.data_mut().clone_from_slice(self.tiles.data());
+        } else {
+            snapshot.tiles = self.tiles.clone();
+        }
+
+        snapshot.opacity = self.opacity;
+        snapshot.text_runs.clone_from(&self.text_runs);
+        snapshot.map_layer.clone_from(&self.map_layer);
+        snapshot.color_grade = self.color_grade;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores the visible state of the terminal from a snapshot.
+    // (the terminal keeps its dimensions, and tiles outside of the snapshot are reset to default)
+    //---------------------------------------------------------------------------------------------
+    pub fn restore(&mut self, snapshot: &TerminalSnapshot) {
+        self.touch();
+
+        if snapshot.dimensions() == self.dimensions() {
+            self.tiles.data_mut().clone_from_slice(snapshot.tiles.data());
+        } else {
+            self.set_all_tiles_default();
+
+            for x in 0..self.width().min(snapshot.tiles.width()) {
+                for y in 0..self.height().min(snapshot.tiles.height()) {
+                    *self.tiles.get_xy_mut((x, y)) = *snapshot.tiles.get_xy((x, y));
+                }
+            }
+        }
+
+        self.opacity = snapshot.opacity;
+        self.text_runs.clone_from(&snapshot.text_runs);
+        self.map_layer.clone_from(&snapshot.map_layer);
+        self.color_grade = snapshot.color_grade;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the terminal to a crossfade between two snapshots, where 0.0 is the first and 1.0 is
+    // the second. Backgrounds and colors blend, while glyphs and text runs of the first snapshot
+    // fade out over the first half and those of the second fade in over the second half.
+    // (the map layer switches over at the halfway point)
+    //---------------------------------------------------------------------------------------------
+    pub fn restore_crossfade(&mut self, from: &TerminalSnapshot, to: &TerminalSnapshot, t: f32) {
+        self.touch();
+
+        let t = t.clamp(0.0, 1.0);
+        let (nearest, fade) = if t < 0.5 { (from, 1.0 - t * 2.0) } else { (to, t * 2.0 - 1.0) };
+
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                let (a, b) = (from.tile((x, y)), to.tile((x, y)));
+                let mut tile = if t < 0.5 { a } else { b };
+
+                tile.background_color = a.background_color.lerp(&b.background_color, t);
+                tile.background_opacity += (b.background_opacity - a.background_opacity) * t;
+                tile.foreground_opacity *= fade;
+                tile.outline_opacity *= fade;
+
+                *self.tiles.get_xy_mut((x, y)) = tile;
+            }
+        }
+
+        self.opacity = from.opacity + (to.opacity - from.opacity) * t;
+        self.text_runs.clear();
+        self.text_runs.extend(nearest.text_runs.iter().map(|run| {
+            let mut run = run.clone();
+            run.opacity *= fade;
+            run
+        }));
+        self.map_layer.clone_from(&nearest.map_layer);
+        self.color_grade = from.color_grade.lerp(&to.color_grade, t);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stashes a snapshot of the terminal by name, replacing any stashed under the same name.
+    //---------------------------------------------------------------------------------------------
+    pub fn stash<S: Into<String>>(&mut self, name: S) {
+        let snapshot = self.snapshot();
+        self.stash.insert(name.into(), snapshot);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the snapshot stashed by name, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn stashed(&self, name: &str) -> Option<&TerminalSnapshot> {
+        self.stash.get(name)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores and removes the snapshot stashed by name. Returns whether one was stashed.
+    //---------------------------------------------------------------------------------------------
+    pub fn unstash(&mut self, name: &str) -> bool {
+        match self.stash.remove(name) {
+            Some(snapshot) => {
+                self.restore(&snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the snapshot stashed by name without restoring it, returning it.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_stashed(&mut self, name: &str) -> Option<TerminalSnapshot> {
+        self.stash.remove(name)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effects of the terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn effects(&self) -> &Effects {
+        &self.effects
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the effects of the terminal mutably (e.g. to queue an effect).
+    //---------------------------------------------------------------------------------------------
+    pub fn effects_mut(&mut self) -> &mut Effects {
+        &mut self.effects
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the particles of the terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn particles(&self) -> &ParticleSystem {
+        &self.particles
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the particles of the terminal mutably (e.g. to emit particles).
+    //---------------------------------------------------------------------------------------------
+    pub fn particles_mut(&mut self) -> &mut ParticleSystem {
+        &mut self.particles
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Composites the effects and then the particles onto the tiles. The tiles must be restored
+    // afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn composite_effects(&mut self) {
+        if !self.effects.is_empty() || !self.particles.is_empty() {
+            self.touch();
+        }
+
+        self.effects.composite(&mut self.tiles);
+        self.particles.composite(&mut self.tiles);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores the tiles overwritten by compositing the effects and particles.
+    //---------------------------------------------------------------------------------------------
+    pub fn restore_effects(&mut self) {
+        if !self.effects.is_empty() || !self.particles.is_empty() {
+            self.touch();
+        }
+
+        // Restore in the reverse order of compositing, so the original tiles are restored last.
+        self.particles.restore(&mut self.tiles);
+        self.effects.restore(&mut self.tiles);
+    }
+
     //---------------------------------------------------------------------------------------------
     // Randomizes the tiles in the terminal for debugging purposes.
     //---------------------------------------------------------------------------------------------
     pub fn randomize(&mut self) {
-        let mut rng = rand::thread_rng();
+        self.randomize_with(&mut rand::thread_rng());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Randomizes the tiles in the terminal from a source of randomness (e.g. a seeded rng, so that
+    // the tiles can be reproduced).
+    //---------------------------------------------------------------------------------------------
+    pub fn randomize_with<R>(&mut self, rng: &mut R)
+    where
+        R: Rng,
+    {
+        self.touch();
 
         for tile in self.tiles.data_mut().iter_mut() {
-            tile.glyph = *CP437_CHARS.choose(&mut rng).unwrap();
+            tile.glyph = *CP437_CHARS.choose(rng).unwrap();
             tile.style = rng.gen();
             tile.outlined = rng.gen();
             tile.background_color = TileColor::TRANSPARENT;
@@ -193,7 +654,7 @@ impl Default for Terminal {
     // Returns the default terminal. There should only ever be one.
     //---------------------------------------------------------------------------------------------
     fn default() -> Self {
-        Self { tiles: GridMap::new(CONFIG.terminal_dimensions), opacity: 1.0 }
+        Self::new(CONFIG.terminal_dimensions)
     }
 }
 
@@ -249,6 +710,7 @@ impl Map2dViewMut for Terminal {
     // Get mut ref to contents of the Map2dView at an index.
     //---------------------------------------------------------------------------------------------
     fn get_mut(&mut self, index: usize) -> &mut Self::Type {
+        self.touch();
         self.tiles.get_mut(index)
     }
 
@@ -256,6 +718,7 @@ impl Map2dViewMut for Terminal {
     // Get mut ref to contents of the Map2dView at a coord.
     //---------------------------------------------------------------------------------------------
     fn get_xy_mut(&mut self, xy: ICoord) -> &mut Self::Type {
+        self.touch();
         self.tiles.get_xy_mut(xy)
     }
 }
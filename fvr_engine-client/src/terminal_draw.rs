@@ -0,0 +1,274 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// TerminalDraw provides a static API for drawing shapes of tiles onto a Map2d<Tile>.
+// Every shape has a variant that sets a tile and a "_with" variant that calls a mutator closure.
+// Coords outside of the map are silently clipped.
+//-------------------------------------------------------------------------------------------------
+pub struct TerminalDraw;
+
+impl TerminalDraw {
+    //---------------------------------------------------------------------------------------------
+    // Calls a mutator on the tile at a coord if the coord is in bounds.
+    //---------------------------------------------------------------------------------------------
+    fn plot<M, F>(map: &mut M, xy: ICoord, f: &mut F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        if map.in_bounds(xy) {
+            f(map.get_xy_mut(xy));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether two tiles look the same (used for flood fill boundaries).
+    //---------------------------------------------------------------------------------------------
+    pub fn tiles_match(a: &Tile, b: &Tile) -> bool {
+        a.glyph == b.glyph
+            && a.background_color == b.background_color
+            && a.foreground_color == b.foreground_color
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an offset from the center falls within an ellipse's radii.
+    //---------------------------------------------------------------------------------------------
+    fn in_ellipse((dx, dy): ICoord, (rx, ry): ICoord) -> bool {
+        // Pad the radii by half a cell so the edges are not overly sharp.
+        let nx = dx as f32 / (rx as f32 + 0.5);
+        let ny = dy as f32 / (ry as f32 + 0.5);
+        nx * nx + ny * ny <= 1.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws a line between two coords, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_line_with<M, F>(map: &mut M, start: ICoord, end: ICoord, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for xy in Lines::bresenham(start, end) {
+            Self::plot(map, xy, &mut f);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws a line of tiles between two coords.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_line<M>(map: &mut M, start: ICoord, end: ICoord, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::draw_line_with(map, start, end, |t| *t = *tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of a rect, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_rect_with<M, F>(map: &mut M, rect: &Rect, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for xy in rect.perimeter_points() {
+            Self::plot(map, xy, &mut f);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of a rect of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_rect<M>(map: &mut M, rect: &Rect, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::draw_rect_with(map, rect, |t| *t = *tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a rect, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_rect_with<M, F>(map: &mut M, rect: &Rect, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for xy in rect.points() {
+            Self::plot(map, xy, &mut f);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a rect with tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_rect<M>(map: &mut M, rect: &Rect, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::fill_rect_with(map, rect, |t| *t = *tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of an ellipse, calling a mutator for each tile.
+    // (a coord is on the outline if it is inside the ellipse but a cardinal neighbor is not)
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_ellipse_with<M, F>(map: &mut M, center: ICoord, radii: ICoord, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for dy in -radii.1..=radii.1 {
+            for dx in -radii.0..=radii.0 {
+                if !Self::in_ellipse((dx, dy), radii) {
+                    continue;
+                }
+
+                let edge = CARDINAL_ADJACENCIES
+                    .iter()
+                    .any(|d| !Self::in_ellipse((dx + d.dx(), dy + d.dy()), radii));
+
+                if edge {
+                    Self::plot(map, (center.0 + dx, center.1 + dy), &mut f);
+                }
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of an ellipse of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_ellipse<M>(map: &mut M, center: ICoord, radii: ICoord, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::draw_ellipse_with(map, center, radii, |t| *t = *tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills an ellipse, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_ellipse_with<M, F>(map: &mut M, center: ICoord, radii: ICoord, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for dy in -radii.1..=radii.1 {
+            for dx in -radii.0..=radii.0 {
+                if Self::in_ellipse((dx, dy), radii) {
+                    Self::plot(map, (center.0 + dx, center.1 + dy), &mut f);
+                }
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills an ellipse with tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_ellipse<M>(map: &mut M, center: ICoord, radii: ICoord, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::fill_ellipse_with(map, center, radii, |t| *t = *tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of a circle, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_circle_with<M, F>(map: &mut M, center: ICoord, radius: i32, f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        Self::draw_ellipse_with(map, center, (radius, radius), f);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the outline of a circle of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw_circle<M>(map: &mut M, center: ICoord, radius: i32, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::draw_ellipse(map, center, (radius, radius), tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a circle, calling a mutator for each tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_circle_with<M, F>(map: &mut M, center: ICoord, radius: i32, f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        Self::fill_ellipse_with(map, center, (radius, radius), f);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a circle with tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_circle<M>(map: &mut M, center: ICoord, radius: i32, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::fill_ellipse(map, center, (radius, radius), tile);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Flood fills the cardinally connected area of matching tiles, calling a mutator for each.
+    // (tiles match if their glyph, background color, and foreground color are equal)
+    //---------------------------------------------------------------------------------------------
+    pub fn flood_fill_with<M, F>(map: &mut M, xy: ICoord, mut f: F)
+    where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        if !map.in_bounds(xy) {
+            return;
+        }
+
+        // Track visited coords so mutators that leave the tile matching do not loop forever.
+        let target = *map.get_xy(xy);
+        let mut visited = GridMap::<bool>::new(map.dimensions());
+        let mut frontier = VecDeque::new();
+
+        *visited.get_xy_mut(xy) = true;
+        frontier.push_back(xy);
+
+        while let Some(current) = frontier.pop_front() {
+            f(map.get_xy_mut(current));
+
+            for direction in CARDINAL_ADJACENCIES.iter() {
+                let neighbor = (current.0 + direction.dx(), current.1 + direction.dy());
+
+                if !map.in_bounds(neighbor)
+                    || *visited.get_xy(neighbor)
+                    || !Self::tiles_match(map.get_xy(neighbor), &target)
+                {
+                    continue;
+                }
+
+                *visited.get_xy_mut(neighbor) = true;
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Flood fills the cardinally connected area of matching tiles with a tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn flood_fill<M>(map: &mut M, xy: ICoord, tile: &Tile)
+    where
+        M: Map2d<Tile>,
+    {
+        Self::flood_fill_with(map, xy, |t| *t = *tile);
+    }
+}
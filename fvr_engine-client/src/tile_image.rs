@@ -0,0 +1,100 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{Context, Result};
+use image::{Rgba, RgbaImage};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Glyph used to split a tile into a top (foreground) and bottom (background) pixel.
+const UPPER_HALF_BLOCK: char = '▀';
+
+// Normalization value to convert u8 alpha to an opacity.
+const ALPHA_NORMALIZE: f32 = 1.0 / 255.0;
+
+//-------------------------------------------------------------------------------------------------
+// TileImage provides a static API for converting images into grids of tiles.
+// Each pixel becomes the background color of a tile, or optionally each pair of vertical pixels
+// becomes a single half-block tile for doubled vertical resolution.
+//-------------------------------------------------------------------------------------------------
+pub struct TileImage;
+
+impl TileImage {
+    //---------------------------------------------------------------------------------------------
+    // Splits a pixel into an opaque tile color and an opacity.
+    //---------------------------------------------------------------------------------------------
+    fn split_pixel(pixel: &Rgba<u8>) -> (TileColor, f32) {
+        let [r, g, b, a] = pixel.0;
+        (TileColor::rgb(r, g, b), a as f32 * ALPHA_NORMALIZE)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Converts an RGBA image into a grid map of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_rgba(image: &RgbaImage, half_blocks: bool) -> GridMap<Tile> {
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let tiles_height = if half_blocks { (height + 1) / 2 } else { height };
+        let mut tiles = GridMap::new((width, tiles_height));
+        tiles.data_mut().fill(BLANK_TILE);
+
+        for y in 0..tiles_height {
+            for x in 0..width {
+                let tile = tiles.get_xy_mut((x, y));
+
+                if half_blocks {
+                    let top_y = y * 2;
+                    let (top_color, top_opacity) =
+                        Self::split_pixel(image.get_pixel(x as u32, top_y as u32));
+
+                    tile.glyph = UPPER_HALF_BLOCK;
+                    tile.foreground_color = top_color;
+                    tile.foreground_opacity = top_opacity;
+
+                    // The bottom half of the last row is transparent for odd image heights.
+                    if top_y + 1 < height {
+                        let (bottom_color, bottom_opacity) =
+                            Self::split_pixel(image.get_pixel(x as u32, (top_y + 1) as u32));
+
+                        tile.background_color = bottom_color;
+                        tile.background_opacity = bottom_opacity;
+                    } else {
+                        tile.background_color = TileColor::TRANSPARENT;
+                    }
+                } else {
+                    let (color, opacity) = Self::split_pixel(image.get_pixel(x as u32, y as u32));
+
+                    tile.background_color = color;
+                    tile.background_opacity = opacity;
+                }
+            }
+        }
+
+        tiles
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads an image file (PNG or any other supported format) into a grid map of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P>(path: P, half_blocks: bool) -> Result<GridMap<Tile>>
+    where
+        P: AsRef<Path>,
+    {
+        let image = image::open(&path)
+            .with_context(|| format!("Failed to open image {}.", path.as_ref().display()))?
+            .into_rgba8();
+
+        Ok(Self::from_rgba(&image, half_blocks))
+    }
+}
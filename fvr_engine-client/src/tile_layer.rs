@@ -0,0 +1,235 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use itertools::Itertools;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Zoom levels that tile layers step through, from furthest out to furthest in.
+pub const TILE_LAYER_ZOOM_LEVELS: [f32; 3] = [0.5, 1.0, 2.0];
+
+// Index of the native zoom level.
+const NATIVE_ZOOM_INDEX: usize = 1;
+
+//-------------------------------------------------------------------------------------------------
+// TileLayer is a grid of tiles rendered within a region of the terminal at its own tile scale,
+// independently of the terminal tiles (e.g. so that the map can be zoomed while the UI is not).
+// At 2x zoom the region holds half as many tiles in each axis, and at 0.5x zoom twice as many.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone)]
+pub struct TileLayer {
+    // Region of the terminal (in terminal tiles) the layer is rendered within.
+    region: Rect,
+    // Index of the current zoom level.
+    zoom_index: usize,
+    // Grid map of the layer's tiles.
+    tiles: GridMap<Tile>,
+}
+
+impl TileLayer {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new tile layer at native zoom within a region of the terminal.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(region: Rect) -> Self {
+        let mut layer =
+            Self { region, zoom_index: NATIVE_ZOOM_INDEX, tiles: GridMap::new((0, 0)) };
+        layer.resize_tiles();
+        layer
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that resizes the tiles to fill the region at the current zoom.
+    //---------------------------------------------------------------------------------------------
+    fn resize_tiles(&mut self) {
+        let zoom = self.zoom();
+        let dimensions = (
+            ((self.region.width as f32 / zoom).floor() as i32).max(1),
+            ((self.region.height as f32 / zoom).floor() as i32).max(1),
+        );
+
+        self.tiles.resize(dimensions);
+        self.tiles.data_mut().fill(BLANK_TILE);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the region of the terminal the layer is rendered within.
+    //---------------------------------------------------------------------------------------------
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the layer to a new region of the terminal, clearing its tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_region(&mut self, region: Rect) {
+        self.region = region;
+        self.resize_tiles();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the scale of the layer's tiles relative to the terminal tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn zoom(&self) -> f32 {
+        TILE_LAYER_ZOOM_LEVELS[self.zoom_index]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that changes the zoom level, clearing the tiles if it changed.
+    //---------------------------------------------------------------------------------------------
+    fn set_zoom_index(&mut self, zoom_index: usize) -> bool {
+        let zoom_index = zoom_index.min(TILE_LAYER_ZOOM_LEVELS.len() - 1);
+
+        if zoom_index == self.zoom_index {
+            return false;
+        }
+
+        self.zoom_index = zoom_index;
+        self.resize_tiles();
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Steps to the next larger tile scale. Returns whether the zoom changed.
+    // (the tiles are cleared and must be redrawn when the zoom changes)
+    //---------------------------------------------------------------------------------------------
+    pub fn zoom_in(&mut self) -> bool {
+        self.set_zoom_index(self.zoom_index + 1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Steps to the next smaller tile scale. Returns whether the zoom changed.
+    // (the tiles are cleared and must be redrawn when the zoom changes)
+    //---------------------------------------------------------------------------------------------
+    pub fn zoom_out(&mut self) -> bool {
+        match self.zoom_index {
+            0 => false,
+            i => self.set_zoom_index(i - 1),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns to native zoom. Returns whether the zoom changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn reset_zoom(&mut self) -> bool {
+        self.set_zoom_index(NATIVE_ZOOM_INDEX)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions (in pixels) of the layer's tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn tile_dimensions(&self) -> (f32, f32) {
+        (
+            CONFIG.tile_dimensions.0 as f32 * self.zoom(),
+            CONFIG.tile_dimensions.1 as f32 * self.zoom(),
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position (in pixels, relative to the terminal) of the top left of a tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn tile_position(&self, (x, y): ICoord) -> (f32, f32) {
+        let tile_dimensions = self.tile_dimensions();
+
+        (
+            (self.region.x * CONFIG.tile_dimensions.0) as f32 + x as f32 * tile_dimensions.0,
+            (self.region.y * CONFIG.tile_dimensions.1) as f32 + y as f32 * tile_dimensions.1,
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Converts a coord in pixels (relative to the terminal) to the layer tile coord it is over.
+    //---------------------------------------------------------------------------------------------
+    pub fn world_to_layer_coords(&self, (x, y): ICoord) -> Option<ICoord> {
+        let origin = self.tile_position((0, 0));
+        let tile_dimensions = self.tile_dimensions();
+        let xy = (
+            ((x as f32 - origin.0) / tile_dimensions.0).floor() as i32,
+            ((y as f32 - origin.1) / tile_dimensions.1).floor() as i32,
+        );
+
+        if self.in_bounds(xy) {
+            Some(xy)
+        } else {
+            None
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Iterates the xy coords in the layer and their corresponding tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn coords_and_tiles_iter(&self) -> impl Iterator<Item = (ICoord, &Tile)> {
+        (0..self.width())
+            .cartesian_product(0..self.height())
+            .map(move |xy| (xy, self.tiles.get_xy(xy)))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Map2dView for TileLayer.
+//-------------------------------------------------------------------------------------------------
+impl Map2dView for TileLayer {
+    type Type = Tile;
+
+    //---------------------------------------------------------------------------------------------
+    // Return the width of the Map2dView.
+    //---------------------------------------------------------------------------------------------
+    fn width(&self) -> i32 {
+        self.tiles.width()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Return the height of the Map2dView.
+    //---------------------------------------------------------------------------------------------
+    fn height(&self) -> i32 {
+        self.tiles.height()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Return the dimensions of the Map2dView.
+    //---------------------------------------------------------------------------------------------
+    fn dimensions(&self) -> ICoord {
+        self.tiles.dimensions()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Get ref to contents of the Map2dView at an index.
+    //---------------------------------------------------------------------------------------------
+    fn get(&self, index: usize) -> &Self::Type {
+        self.tiles.get(index)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Get ref to contents of the Map2dView at a coord.
+    //---------------------------------------------------------------------------------------------
+    fn get_xy(&self, xy: ICoord) -> &Self::Type {
+        self.tiles.get_xy(xy)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Map2dViewMut for TileLayer.
+//-------------------------------------------------------------------------------------------------
+impl Map2dViewMut for TileLayer {
+    type Type = Tile;
+
+    //---------------------------------------------------------------------------------------------
+    // Get mut ref to contents of the Map2dView at an index.
+    //---------------------------------------------------------------------------------------------
+    fn get_mut(&mut self, index: usize) -> &mut Self::Type {
+        self.tiles.get_mut(index)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Get mut ref to contents of the Map2dView at a coord.
+    //---------------------------------------------------------------------------------------------
+    fn get_xy_mut(&mut self, xy: ICoord) -> &mut Self::Type {
+        self.tiles.get_xy_mut(xy)
+    }
+}
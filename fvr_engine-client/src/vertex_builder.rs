@@ -0,0 +1,779 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::mem;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use fnv::FnvHashSet;
+use gl::types::*;
+use glam::Vec4;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::dynamic_glyphs::*;
+use crate::gl_helpers::*;
+use crate::terminal::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Normalization value to convert u8 color to OpenGL float representation.
+pub const COLOR_NORMALIZE_8BIT: GLfloat = 1.0 / 255.0;
+
+// Max # of quads for proportional text runs (additional glyphs are dropped).
+pub const MAX_TEXT_QUADS: usize = 4096;
+
+// Default glyph rendered in place of glyphs missing from the font metrics.
+pub const DEFAULT_FALLBACK_GLYPH: char = '?';
+
+// Layer of the glyphs texture array holding the dynamically rasterized glyphs, after the layers
+// of the font textures.
+pub const DYNAMIC_GLYPHS_INDEX: usize = TILE_STYLE_COUNT * 2;
+
+// Outline thickness and glow of quads that are not tile outlines (regular glyphs and text runs).
+const DEFAULT_QUAD_OUTLINE: [GLfloat; 2] = [DEFAULT_OUTLINE_THICKNESS, 0.0];
+
+// Name of the vertex worker thread.
+const VERTEX_WORKER_NAME: &str = "vertex-worker";
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+type CachedMetric = Option<(usize, char, GlyphMetric, usize)>;
+
+//-------------------------------------------------------------------------------------------------
+// Describes a vertex for a colored (+ alpha) and texture-mapped quad.
+// The background shader program will only use position and color[3].
+// The foreground shader program will use all properties.
+//-------------------------------------------------------------------------------------------------
+#[repr(C, packed)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Vertex {
+    // Position of the vertex [X, Y].
+    position: [GLfloat; 2],
+    // Color of the vertex [R, G, B, A].
+    color: [GLfloat; 4],
+    // Texture Position of the vertex [U, V].
+    tex_coords: [GLfloat; 2],
+    // Index of the texture to sample.
+    tex_index: GLfloat,
+    // Outline thickness and glow (0.0 or 1.0) of the glyph [Thickness, Glow].
+    outline: [GLfloat; 2],
+}
+
+//-------------------------------------------------------------------------------------------------
+// VertexBuilder generates the background, foreground and text quads of a frame from a snapshot of
+// the terminal. It owns everything needed to do so (the glyph metrics and the dynamic glyphs),
+// so that it can be sent to the vertex worker and build a frame while the previous one renders.
+// Uploading the vertices and the dynamic glyphs is left to the renderer, on the main thread.
+//-------------------------------------------------------------------------------------------------
+pub struct VertexBuilder {
+    // Dimensions of each tile in the terminal in # of pixels.
+    tile_dimensions: ICoord,
+    // Frame clear color (backgrounds of this color are skipped).
+    clear_color: SdlColor,
+    // Normalization values for texel in pixels to texel in OpenGL space of the font textures.
+    texel_normalize: (f32, f32),
+    // Factor the font textures were downscaled by (the dynamic glyphs are never downscaled).
+    atlas_scale: u32,
+    // Vec of tables of codepoint to corresponding glyph metrics for every font texture.
+    // Length will equal TILE_STYLE_COUNT * 2.
+    // The first half of the vec will contain tables for the non-outlined metrics.
+    // The second half of the vec will contain tables for the outlined metrics.
+    metrics: Vec<GlyphMetricTable>,
+    // Page of glyphs missing from the font textures, rasterized when first requested.
+    dynamic_glyphs: DynamicGlyphs,
+    // Glyph rendered in place of glyphs missing from the font metrics.
+    fallback_glyph: char,
+    // Missing glyphs that have already been encountered.
+    missing_glyphs: FnvHashSet<char>,
+    // Missing glyphs encountered since they were last taken for reporting.
+    unreported_glyphs: Vec<char>,
+    // Snapshot of the terminal to build the vertices from (none until the first snapshot).
+    terminal: Option<TerminalSnapshot>,
+    // Cell rendered highlighted (e.g. under the virtual cursor).
+    highlighted_cell: Option<ICoord>,
+    // Position (in pixels) and tile of the software cursor, if drawn.
+    software_cursor: Option<((f32, f32), Tile)>,
+    // Palette cycling rotations of the snapshot.
+    palette_cycler: PaletteCycler,
+    // Vec for collecting background quads each frame.
+    background_vertices: Vec<Vertex>,
+    // Vec for collecting foreground quads each frame.
+    foreground_vertices: Vec<Vertex>,
+    // Vec for collecting proportional text quads each frame (drawn with the foreground program).
+    text_vertices: Vec<Vertex>,
+}
+
+impl VertexBuilder {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new vertex builder for the glyphs of a font, with room for a # of tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(
+        clear_color: SdlColor,
+        metrics: Vec<GlyphMetricTable>,
+        texel_normalize: (f32, f32),
+        atlas_scale: u32,
+        dynamic_glyphs: DynamicGlyphs,
+        num_tiles: usize,
+    ) -> Self {
+        Self {
+            tile_dimensions: CONFIG.tile_dimensions,
+            clear_color,
+            texel_normalize,
+            atlas_scale,
+            metrics,
+            dynamic_glyphs,
+            fallback_glyph: DEFAULT_FALLBACK_GLYPH,
+            missing_glyphs: FnvHashSet::default(),
+            unreported_glyphs: Vec::new(),
+            terminal: None,
+            highlighted_cell: None,
+            software_cursor: None,
+            palette_cycler: PaletteCycler::default(),
+            background_vertices: Vec::with_capacity(num_tiles * VERTICES_PER_QUAD),
+            foreground_vertices: Vec::with_capacity(num_tiles * VERTICES_PER_QUAD * 2),
+            text_vertices: Vec::with_capacity(MAX_TEXT_QUADS * VERTICES_PER_QUAD),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the glyph metrics with those of another font.
+    // (the dynamic glyphs must be invalidated as well, as their layer was reallocated)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_font(
+        &mut self,
+        metrics: Vec<GlyphMetricTable>,
+        texel_normalize: (f32, f32),
+        atlas_scale: u32,
+    ) {
+        self.metrics = metrics;
+        self.texel_normalize = texel_normalize;
+        self.atlas_scale = atlas_scale;
+        self.dynamic_glyphs.invalidate();
+
+        // Glyphs missing from the old font may be present in the new one.
+        self.missing_glyphs.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a mutable ref to the dynamic glyphs (e.g. for uploading them).
+    //---------------------------------------------------------------------------------------------
+    pub fn dynamic_glyphs_mut(&mut self) -> &mut DynamicGlyphs {
+        &mut self.dynamic_glyphs
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Takes the missing glyphs encountered since they were last taken (each is only taken once).
+    //---------------------------------------------------------------------------------------------
+    pub fn take_unreported_glyphs(&mut self) -> Vec<char> {
+        mem::take(&mut self.unreported_glyphs)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the background, foreground and text vertices of the last built frame.
+    //---------------------------------------------------------------------------------------------
+    pub fn vertices(&self) -> [&[Vertex]; 3] {
+        [&self.background_vertices, &self.foreground_vertices, &self.text_vertices]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies the state of a frame to build the vertices from, reusing the previous snapshot's
+    // allocations.
+    //---------------------------------------------------------------------------------------------
+    pub fn snapshot(
+        &mut self,
+        terminal: &Terminal,
+        highlighted_cell: Option<ICoord>,
+        software_cursor: Option<((f32, f32), Tile)>,
+        palette_cycler: &PaletteCycler,
+        fallback_glyph: char,
+    ) {
+        match self.terminal.as_mut() {
+            Some(snapshot) => terminal.snapshot_into(snapshot),
+            None => self.terminal = Some(terminal.snapshot()),
+        }
+
+        self.highlighted_cell = highlighted_cell;
+        self.software_cursor = software_cursor;
+        self.palette_cycler.clone_from(palette_cycler);
+        self.fallback_glyph = fallback_glyph;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Builds the vertices of the last snapshot.
+    //---------------------------------------------------------------------------------------------
+    pub fn build(&mut self) -> Result<()> {
+        // Clear the vertex vecs.
+        self.background_vertices.clear();
+        self.foreground_vertices.clear();
+        self.text_vertices.clear();
+
+        // Take the snapshot while pushing quads for it, and return it afterwards.
+        let terminal = match self.terminal.take() {
+            Some(terminal) => terminal,
+            None => return Ok(()),
+        };
+        let result = self.push_terminal(&terminal);
+        self.terminal = Some(terminal);
+
+        result
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that pushes the quads of a terminal snapshot, the highlighted cell and the
+    // software cursor.
+    //---------------------------------------------------------------------------------------------
+    fn push_terminal(&mut self, terminal: &TerminalSnapshot) -> Result<()> {
+        // Get the opacity modifier for the entire terminal.
+        let opacity = terminal.opacity();
+
+        // Cached glyph metrics for the regular and outline quads of the previous tile.
+        let mut metrics = (None, None);
+
+        // Iterate over all map layer tiles first so that they are drawn beneath the terminal.
+        //-----------------------------------------------------------------------------------------
+        if let Some(layer) = terminal.map_layer() {
+            let scale = layer.zoom();
+
+            for (coord, tile) in layer.coords_and_tiles_iter() {
+                let position = layer.tile_position(coord);
+                self.push_tile(position, scale, tile, opacity, &mut metrics)?;
+            }
+        }
+
+        // Iterate over all tiles, pushing quads for those that are visible.
+        //-----------------------------------------------------------------------------------------
+        for (coord, tile) in terminal.coords_and_tiles_iter() {
+            // Swap in an inverted copy of the highlighted tile.
+            let highlighted;
+            let tile = if Some(coord) == self.highlighted_cell {
+                highlighted = Self::highlight_tile(tile);
+                &highlighted
+            } else {
+                tile
+            };
+
+            let position = (
+                (coord.0 * self.tile_dimensions.0) as f32,
+                (coord.1 * self.tile_dimensions.1) as f32,
+            );
+            self.push_tile(position, 1.0, tile, opacity, &mut metrics)?;
+        }
+
+        // Push the software cursor over the tiles (unaffected by the terminal opacity).
+        //-----------------------------------------------------------------------------------------
+        if let Some((position, tile)) = self.software_cursor {
+            self.push_tile(position, 1.0, &tile, 1.0, &mut metrics)?;
+        }
+
+        // Push quads for the proportional text runs over the tiles.
+        //-----------------------------------------------------------------------------------------
+        for run in terminal.text_runs() {
+            if run.opacity > 0.0 {
+                self.push_text_run(run, opacity);
+            }
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Push a colored (or gradient) quad onto the background vertices, based on a tile at a pixel
+    // position and tile scale.
+    //---------------------------------------------------------------------------------------------
+    fn push_background_quad(
+        &mut self,
+        (left, top): (f32, f32),
+        scale: f32,
+        tile: &Tile,
+        opacity: GLfloat,
+    ) {
+        let rgb_scale = opacity * tile.background_opacity;
+        let start = Self::normalize_color(&tile.background_color, rgb_scale, 0.0);
+
+        // Vertices on the end edge of a gradient take the end color, and the colors are lerped
+        // between the edges when the quad is rasterized.
+        let end = tile
+            .background_gradient
+            .end_color()
+            .map_or(start, |color| Self::normalize_color(&color, rgb_scale, 0.0));
+        let (top_right, bottom_left) = match tile.background_gradient {
+            TileGradient::None => (start, start),
+            TileGradient::Vertical(_) => (start, end),
+            TileGradient::Horizontal(_) => (end, start),
+        };
+
+        // Calculate the bounds of the quad.
+        let right = left + self.tile_dimensions.0 as GLfloat * scale;
+        let bottom = top + self.tile_dimensions.1 as GLfloat * scale;
+
+        // Top left.
+        self.background_vertices.push(Vertex {
+            position: [left, top],
+            color: start,
+            ..Default::default()
+        });
+
+        // Top right.
+        self.background_vertices.push(Vertex {
+            position: [right, top],
+            color: top_right,
+            ..Default::default()
+        });
+
+        // Bottom left.
+        self.background_vertices.push(Vertex {
+            position: [right, bottom],
+            color: end,
+            ..Default::default()
+        });
+
+        // Bottom right.
+        self.background_vertices.push(Vertex {
+            position: [left, bottom],
+            color: bottom_left,
+            ..Default::default()
+        });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Calculate the offset for a glyph (in pixels) given a tile layout.
+    // TODO: Which produces fewer scaling artifacts - floor() or round()?
+    //---------------------------------------------------------------------------------------------
+    fn calculate_glyph_offset(&self, metric: &GlyphMetric, layout: TileLayout) -> (f32, f32) {
+        match layout {
+            // Center the glyph.
+            TileLayout::Center => (
+                ((self.tile_dimensions.0 - metric.width) as f32 / 2.0).floor(),
+                ((self.tile_dimensions.1 - metric.height) as f32 / 2.0).floor(),
+            ),
+            // Center the glyph horizontally but align with the base of the quad vertically.
+            TileLayout::Floor => (
+                ((self.tile_dimensions.0 - metric.width) as f32 / 2.0).floor(),
+                (self.tile_dimensions.1 - metric.height) as f32,
+            ),
+            // Adjust the glyph based on font metrics.
+            TileLayout::Text => (metric.x_offset as f32, metric.y_offset as f32),
+            // Adjust the glyph from the center position by an exact offset.
+            TileLayout::Exact((x, y)) => (
+                (((self.tile_dimensions.0 - metric.width) as f32 / 2.0) + x as f32).floor(),
+                (((self.tile_dimensions.1 - metric.height) as f32 / 2.0) + y as f32).floor(),
+            ),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Normalizes an 8 bit color into [R, G, B, A] floats in a single vector multiply, scaling the
+    // RGB channels by a modifier and replacing the alpha channel.
+    //---------------------------------------------------------------------------------------------
+    fn normalize_color(color: &TileColor, rgb_scale: GLfloat, alpha: GLfloat) -> [GLfloat; 4] {
+        let scale = COLOR_NORMALIZE_8BIT * rgb_scale;
+        let color =
+            Vec4::new(color.0.r as GLfloat, color.0.g as GLfloat, color.0.b as GLfloat, alpha);
+
+        (color * Vec4::new(scale, scale, scale, 1.0)).into()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a copy of a tile with its colors inverted for highlighting.
+    //---------------------------------------------------------------------------------------------
+    fn highlight_tile(tile: &Tile) -> Tile {
+        let foreground_color = if tile.glyph == ' ' || tile.foreground_opacity <= 0.0 {
+            TileColor::WHITE
+        } else {
+            tile.foreground_color
+        };
+
+        Tile {
+            background_color: foreground_color,
+            background_opacity: 1.0,
+            foreground_color: tile.background_color,
+            foreground_opacity: 1.0,
+            outlined: false,
+            background_gradient: TileGradient::None,
+            ..*tile
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records a glyph missing from the font metrics (to be reported once per glyph) and returns
+    // the fallback.
+    //---------------------------------------------------------------------------------------------
+    fn missing_glyph(&mut self, glyph: char) -> char {
+        if self.missing_glyphs.insert(glyph) {
+            self.unreported_glyphs.push(glyph);
+        }
+
+        self.fallback_glyph
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Retrieve the metric for a glyph and the index of the texture it is in. Glyphs missing from
+    // the font texture are rasterized into the dynamic glyphs page (if the bundled TTF has them).
+    //---------------------------------------------------------------------------------------------
+    fn glyph_metric(&mut self, index: usize, glyph: char) -> Option<(GlyphMetric, usize)> {
+        match self.metrics[index].get(glyph as i32) {
+            Some(metric) => Some((*metric, index)),
+            None => self.dynamic_glyphs.get(glyph).map(|metric| (metric, DYNAMIC_GLYPHS_INDEX)),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Retrieve the metric for a glyph, reusing the cached metric if it is for the same glyph.
+    // (consecutive tiles often share a glyph, so this avoids most of the hash lookups)
+    // Glyphs missing from the metrics and the dynamic glyphs are replaced by the fallback glyph.
+    //---------------------------------------------------------------------------------------------
+    fn cached_metric(
+        &mut self,
+        cache: &mut CachedMetric,
+        index: usize,
+        glyph: char,
+    ) -> Result<(GlyphMetric, usize)> {
+        if let Some((cached_index, cached_glyph, metric, texture_index)) = *cache {
+            if cached_index == index && cached_glyph == glyph {
+                return Ok((metric, texture_index));
+            }
+        }
+
+        let (metric, texture_index) = match self.glyph_metric(index, glyph) {
+            Some(found) => found,
+            None => {
+                let fallback = self.missing_glyph(glyph);
+
+                self.glyph_metric(index, fallback).with_context(|| {
+                    format!("Failed to load metric for fallback glyph {}.", fallback)
+                })?
+            }
+        };
+        *cache = Some((index, glyph, metric, texture_index));
+
+        Ok((metric, texture_index))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Generate the vertices of a colored and textured quad for a glyph at a pixel position and
+    // scale, with an outline thickness and glow (only used by outline quads).
+    //---------------------------------------------------------------------------------------------
+    fn glyph_quad(
+        &self,
+        (left, top): (f32, f32),
+        scale: f32,
+        metric: &GlyphMetric,
+        index: usize,
+        color: [GLfloat; 4],
+        outline: [GLfloat; 2],
+    ) -> [Vertex; VERTICES_PER_QUAD] {
+        // Each vertex of the quad shares the same color values (for now).
+        let mut vertex =
+            Vertex { color, tex_index: index as GLfloat, outline, ..Default::default() };
+
+        // Calculate the bounds of the quad.
+        let right = left + metric.width as f32 * scale;
+        let bottom = top + metric.height as f32 * scale;
+
+        // Calculate the bounds of the glyph in the texture (the dynamic glyphs are not downscaled).
+        let mut texel_normalize = self.texel_normalize;
+
+        if index == DYNAMIC_GLYPHS_INDEX {
+            texel_normalize.0 *= self.atlas_scale as f32;
+            texel_normalize.1 *= self.atlas_scale as f32;
+        }
+
+        let tex_left = (metric.x as f32) * texel_normalize.0;
+        let tex_top = (metric.y as f32) * texel_normalize.1;
+        let tex_right = ((metric.x + metric.width) as f32) * texel_normalize.0;
+        let tex_bottom = ((metric.y + metric.height) as f32) * texel_normalize.1;
+
+        let mut quad = [vertex; VERTICES_PER_QUAD];
+
+        // Top left.
+        vertex.position = [left, top];
+        vertex.tex_coords = [tex_left, tex_top];
+        quad[0] = vertex;
+
+        // Top right.
+        vertex.position = [right, top];
+        vertex.tex_coords = [tex_right, tex_top];
+        quad[1] = vertex;
+
+        // Bottom left.
+        vertex.position = [right, bottom];
+        vertex.tex_coords = [tex_right, tex_bottom];
+        quad[2] = vertex;
+
+        // Bottom right.
+        vertex.position = [left, bottom];
+        vertex.tex_coords = [tex_left, tex_bottom];
+        quad[3] = vertex;
+
+        quad
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Push a colored and textured quad onto the foreground vertices, based on a tile at a pixel
+    // position and tile scale.
+    //---------------------------------------------------------------------------------------------
+    #[allow(clippy::too_many_arguments)]
+    fn push_foreground_quad(
+        &mut self,
+        (left, top): (f32, f32),
+        scale: f32,
+        tile: &Tile,
+        outline_quad: bool,
+        opacity: GLfloat,
+        cache: &mut CachedMetric,
+    ) -> Result<()> {
+        // Find the texture/metric index.
+        let index = if outline_quad {
+            tile.style as usize + TILE_STYLE_COUNT
+        } else {
+            tile.style as usize
+        };
+
+        // Retrieve the metrics for the tile's glyph and style (and the texture it is in).
+        let (metric, index) = self.cached_metric(cache, index, tile.glyph)?;
+
+        // Use either the foreground or outline color, opacity, thickness and glow from the tile.
+        let (color, alpha, outline) = if outline_quad {
+            (
+                &tile.outline_color,
+                opacity * tile.outline_opacity,
+                [
+                    tile.outline_thickness.clamp(0.0, MAX_OUTLINE_THICKNESS),
+                    tile.outline_glow as u8 as GLfloat,
+                ],
+            )
+        } else {
+            (&tile.foreground_color, opacity * tile.foreground_opacity, DEFAULT_QUAD_OUTLINE)
+        };
+
+        // Calculate the glyph offset for the tile's layout.
+        let offset = self.calculate_glyph_offset(&metric, tile.layout);
+        let position = (left + offset.0 * scale, top + offset.1 * scale);
+
+        let quad = self.glyph_quad(
+            position,
+            scale,
+            &metric,
+            index,
+            Self::normalize_color(color, 1.0, alpha),
+            outline,
+        );
+        self.foreground_vertices.extend_from_slice(&quad);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Push colored and textured quads onto the text vertices for a proportional text run.
+    // Glyphs are positioned by their advances and kernings, and missing glyphs are replaced by
+    // dynamic glyphs or the fallback glyph.
+    //---------------------------------------------------------------------------------------------
+    fn push_text_run(&mut self, run: &TextRun, opacity: GLfloat) {
+        let regular_index = run.style as usize;
+        let outline_index = regular_index + TILE_STYLE_COUNT;
+        let color = Self::normalize_color(&run.color, 1.0, opacity * run.opacity);
+        let outline_color = Self::normalize_color(&run.outline_color, 1.0, opacity * run.opacity);
+
+        // Position of the next glyph and the previous codepoint (for kerning).
+        let mut pen = run.position;
+        let mut previous = None;
+
+        for c in run.text.chars() {
+            // Newlines return to the start of the run on the next line.
+            if c == '\n' {
+                pen = (run.position.0, pen.1 + self.tile_dimensions.1 as f32);
+                previous = None;
+                continue;
+            }
+
+            // Replace glyphs missing from the metrics and the dynamic glyphs with the fallback.
+            let (codepoint, (metric, index)) = match self.glyph_metric(regular_index, c) {
+                Some(found) => (c as i32, found),
+                None => {
+                    let fallback = self.missing_glyph(c);
+
+                    match self.glyph_metric(regular_index, fallback) {
+                        Some(found) => (fallback as i32, found),
+                        None => continue,
+                    }
+                }
+            };
+
+            if let Some(previous) = previous {
+                pen.0 += self.metrics[regular_index].kerning(previous, codepoint) as f32;
+            }
+
+            previous = Some(codepoint);
+
+            // Stop pushing quads once the max has been reached.
+            let quads = self.text_vertices.len() / VERTICES_PER_QUAD;
+            let glyph_quads = if run.outlined { 2 } else { 1 };
+
+            if c != ' ' && quads + glyph_quads <= MAX_TEXT_QUADS {
+                let position = (pen.0 + metric.x_offset as f32, pen.1 + metric.y_offset as f32);
+                let quad =
+                    self.glyph_quad(position, 1.0, &metric, index, color, DEFAULT_QUAD_OUTLINE);
+                self.text_vertices.extend_from_slice(&quad);
+
+                if run.outlined {
+                    // Dynamic glyphs are outlined with the glyph itself.
+                    let outline = if index == DYNAMIC_GLYPHS_INDEX {
+                        Some((metric, index))
+                    } else {
+                        self.metrics[outline_index].get(codepoint).map(|m| (*m, outline_index))
+                    };
+
+                    if let Some((metric, index)) = outline {
+                        let position =
+                            (pen.0 + metric.x_offset as f32, pen.1 + metric.y_offset as f32);
+                        let quad = self.glyph_quad(
+                            position,
+                            1.0,
+                            &metric,
+                            index,
+                            outline_color,
+                            DEFAULT_QUAD_OUTLINE,
+                        );
+                        self.text_vertices.extend_from_slice(&quad);
+                    }
+                }
+            }
+
+            pen.0 += metric.advance() as f32;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Push the quads that are visible for a tile at a pixel position and tile scale, caching the
+    // glyph metrics of the regular and outline quads.
+    //---------------------------------------------------------------------------------------------
+    fn push_tile(
+        &mut self,
+        position: (f32, f32),
+        scale: f32,
+        tile: &Tile,
+        opacity: GLfloat,
+        (regular_metric, outline_metric): &mut (CachedMetric, CachedMetric),
+    ) -> Result<()> {
+        // Swap in a copy of the tile with its palette cycling colors remapped.
+        let cycled;
+        let tile = match self.palette_cycler.cycle_tile(tile) {
+            Some(tile) => {
+                cycled = tile;
+                &cycled
+            }
+            None => tile,
+        };
+
+        // Skip the background if neither end of it would be visible.
+        let visible = |color: &TileColor| color.0.a != 0 && color.0 != self.clear_color;
+        if tile.background_opacity > 0.0
+            && (visible(&tile.background_color)
+                || tile.background_gradient.end_color().is_some_and(|color| visible(&color)))
+        {
+            self.push_background_quad(position, scale, tile, opacity);
+        }
+
+        // Skip the foreground if it would not be visible
+        if tile.glyph != ' ' && tile.foreground_color.0.a != 0 && tile.foreground_opacity > 0.0
+        // TODO: Is this check worth fixing, performance wise? It is currently broken.
+        // && tile.foreground_color != tile.background_color
+        {
+            self.push_foreground_quad(position, scale, tile, false, opacity, regular_metric)
+                .context("Failed to push foreground regular quad")?;
+        }
+
+        // Skip the foreground outline if it is not enabled or would not be visible.
+        if tile.outlined
+            && tile.outline_color.0.a != 0
+            && tile.outline_opacity > 0.0
+            && (tile.outline_thickness > 0.0 || tile.outline_glow)
+        {
+            self.push_foreground_quad(position, scale, tile, true, opacity, outline_metric)
+                .context("Failed to push foreground outline quad")?;
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// VertexWorker builds frames on a worker thread. The vertex builder is sent to the worker with
+// a snapshot of the frame and is received back once the vertices are built, so the main thread
+// never shares it (and must wait for it before building the next frame).
+//-------------------------------------------------------------------------------------------------
+pub struct VertexWorker {
+    // Sends vertex builders to the worker (none once dropping).
+    sender: Option<Sender<VertexBuilder>>,
+    // Receives the vertex builders back with the results of building.
+    receiver: Receiver<(VertexBuilder, Result<()>)>,
+    // Handle of the worker thread (none once joined).
+    thread: Option<JoinHandle<()>>,
+}
+
+impl VertexWorker {
+    //---------------------------------------------------------------------------------------------
+    // Spawns the worker thread.
+    //---------------------------------------------------------------------------------------------
+    pub fn spawn() -> Result<Self> {
+        let (sender, jobs) = mpsc::channel::<VertexBuilder>();
+        let (results, receiver) = mpsc::channel();
+
+        let thread = thread::Builder::new()
+            .name(VERTEX_WORKER_NAME.into())
+            .spawn(move || {
+                for mut builder in jobs.iter() {
+                    let result = builder.build();
+
+                    if results.send((builder, result)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .context("Failed to spawn the vertex worker thread.")?;
+
+        Ok(Self { sender: Some(sender), receiver, thread: Some(thread) })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sends a vertex builder to the worker to build its snapshot.
+    //---------------------------------------------------------------------------------------------
+    pub fn send(&self, builder: VertexBuilder) -> Result<()> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(builder).ok())
+            .ok_or_else(|| anyhow!("The vertex worker thread has stopped."))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Waits for the vertex builder sent to the worker, returning it and the result of building.
+    //---------------------------------------------------------------------------------------------
+    pub fn receive(&self) -> Result<(VertexBuilder, Result<()>)> {
+        self.receiver.recv().map_err(|_| anyhow!("The vertex worker thread has stopped."))
+    }
+}
+
+impl Drop for VertexWorker {
+    fn drop(&mut self) {
+        // Disconnecting the sender ends the worker's loop.
+        self.sender = None;
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
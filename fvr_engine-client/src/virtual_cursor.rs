@@ -0,0 +1,92 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// VirtualCursor is a keyboard driven stand-in for the mouse, for keyboard-only play.
+// While enabled, the input manager reports the virtual cursor's coord as the mouse coord so that
+// widgets and the interaction map treat it exactly like the real mouse.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct VirtualCursor {
+    // Whether the virtual cursor is replacing the mouse.
+    enabled: bool,
+    // Coord of the virtual cursor within the faux terminal.
+    xy: ICoord,
+    // Dimensions the virtual cursor is bound to.
+    dimensions: ICoord,
+}
+
+impl Default for VirtualCursor {
+    //---------------------------------------------------------------------------------------------
+    // Returns a disabled virtual cursor bound to the configured terminal dimensions.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self::new(CONFIG.terminal_dimensions)
+    }
+}
+
+impl VirtualCursor {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, disabled virtual cursor bound to dimensions.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord) -> Self {
+        Self { enabled: false, xy: (0, 0), dimensions }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the virtual cursor is enabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Enables or disables the virtual cursor.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord of the virtual cursor.
+    //---------------------------------------------------------------------------------------------
+    pub fn xy(&self) -> ICoord {
+        self.xy
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the virtual cursor to a coord, clamped to its dimensions.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_xy(&mut self, xy: ICoord) {
+        self.xy = (
+            xy.0.clamp(0, (self.dimensions.0 - 1).max(0)),
+            xy.1.clamp(0, (self.dimensions.1 - 1).max(0)),
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the virtual cursor one cell in a direction. Returns whether it moved.
+    //---------------------------------------------------------------------------------------------
+    pub fn step(&mut self, direction: Direction) -> bool {
+        let xy = self.xy;
+        self.set_xy((xy.0 + direction.dx(), xy.1 + direction.dy()));
+        self.xy != xy
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions the virtual cursor is bound to.
+    //---------------------------------------------------------------------------------------------
+    pub fn dimensions(&self) -> ICoord {
+        self.dimensions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Rebinds the virtual cursor to new dimensions, clamping its coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_dimensions(&mut self, dimensions: ICoord) {
+        self.dimensions = dimensions;
+        self.set_xy(self.xy);
+    }
+}
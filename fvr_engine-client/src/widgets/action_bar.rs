@@ -0,0 +1,162 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::widgets::rich_text_writer::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns of space between the entries.
+const ENTRY_GAP: i32 = 2;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Format settings for the keys of the entries.
+static KEYS_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Bold),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::Black.const_into()),
+    foreground_color: Some(PaletteColor::Gold.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the labels of the entries (and the blank row beneath them).
+static LABEL_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::Black.const_into()),
+    foreground_color: Some(PaletteColor::BrightGrey.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// An action listed in an action bar.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActionBarEntry {
+    // Human readable keys bound to the action (e.g. "Ctrl+S").
+    pub keys: String,
+    // Human readable name of the action.
+    pub label: String,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ActionBar lists the actions currently available in a single row, each with the keys bound to
+// it. Entries that do not fit within the width are left out.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ActionBar {
+    // Origin of the row.
+    origin: ICoord,
+    // Width of the row in tiles.
+    width: i32,
+    // Entries listed.
+    entries: Vec<ActionBarEntry>,
+}
+
+impl ActionBar {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty action bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, width: i32) -> Self {
+        Self { origin, width: width.max(1), entries: Vec::new() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the row.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the origin of the row. The bar must be redrawn afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the row in tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the entries listed.
+    //---------------------------------------------------------------------------------------------
+    pub fn entries(&self) -> &[ActionBarEntry] {
+        &self.entries
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the entries. Returns whether they changed (and so should be redrawn).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_entries(&mut self, entries: Vec<ActionBarEntry>) -> bool {
+        if entries == self.entries {
+            return false;
+        }
+
+        self.entries = entries;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let blank = " ".repeat(self.width as usize);
+        RichTextWriter::write_plain_with_settings(map, self.origin, &blank, &LABEL_SETTINGS);
+
+        let mut x = self.origin.0;
+        let end = self.origin.0 + self.width;
+
+        for entry in self.entries.iter() {
+            let keys = format!("[{}]", entry.keys);
+            let keys_width = RichTextWriter::display_width(&keys) as i32;
+            let label_width = RichTextWriter::display_width(&entry.label) as i32;
+
+            if x + keys_width + 1 + label_width > end {
+                break;
+            }
+
+            RichTextWriter::write_plain_with_settings(
+                map,
+                (x, self.origin.1),
+                &keys,
+                &KEYS_SETTINGS,
+            );
+            x += keys_width + 1;
+
+            RichTextWriter::write_plain_with_settings(
+                map,
+                (x, self.origin.1),
+                &entry.label,
+                &LABEL_SETTINGS,
+            );
+            x += label_width + ENTRY_GAP;
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+use once_cell::sync::Lazy;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::widgets::rich_text_writer::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Height of a banner letter in tiles.
+pub const BANNER_HEIGHT: i32 = 5;
+
+// Spacing between banner letters and lines in tiles.
+const BANNER_LETTER_SPACING: i32 = 1;
+const BANNER_LINE_SPACING: i32 = 1;
+
+// Default glyph used for the filled cells of a banner letter.
+const BANNER_DEFAULT_GLYPH: char = '█';
+
+// Letter used in place of characters missing from the font.
+const BANNER_MISSING_LETTER: char = '?';
+
+// Characters within the font data that represent filled cells.
+const BANNER_FILLED_CHAR: char = '#';
+
+// Embedded block font data (FIGlet-like, one row string per line of tiles).
+#[rustfmt::skip]
+const BANNER_FONT_DATA: &[(char, [&str; BANNER_HEIGHT as usize])] = &[
+    (' ', ["...", "...", "...", "...", "..."]),
+    ('A', [".###.", "#...#", "#####", "#...#", "#...#"]),
+    ('B', ["####.", "#...#", "####.", "#...#", "####."]),
+    ('C', [".####", "#....", "#....", "#....", ".####"]),
+    ('D', ["####.", "#...#", "#...#", "#...#", "####."]),
+    ('E', ["#####", "#....", "####.", "#....", "#####"]),
+    ('F', ["#####", "#....", "####.", "#....", "#...."]),
+    ('G', [".####", "#....", "#..##", "#...#", ".###."]),
+    ('H', ["#...#", "#...#", "#####", "#...#", "#...#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..###", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#...#", "#..#.", "###..", "#..#.", "#...#"]),
+    ('L', ["#....", "#....", "#....", "#....", "#####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    ('N', ["#...#", "##..#", "#.#.#", "#..##", "#...#"]),
+    ('O', [".###.", "#...#", "#...#", "#...#", ".###."]),
+    ('P', ["####.", "#...#", "####.", "#....", "#...."]),
+    ('Q', [".###.", "#...#", "#.#.#", "#..#.", ".##.#"]),
+    ('R', ["####.", "#...#", "####.", "#..#.", "#...#"]),
+    ('S', [".####", "#....", ".###.", "....#", "####."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('V', ["#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    ('X', ["#...#", ".#.#.", "..#..", ".#.#.", "#...#"]),
+    ('Y', ["#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["#####", "...#.", "..#..", ".#...", "#####"]),
+    ('0', [".###.", "#..##", "#.#.#", "##..#", ".###."]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["####.", "....#", ".###.", "#....", "#####"]),
+    ('3', ["####.", "....#", ".###.", "....#", "####."]),
+    ('4', ["#...#", "#...#", "#####", "....#", "....#"]),
+    ('5', ["#####", "#....", "####.", "....#", "####."]),
+    ('6', [".###.", "#....", "####.", "#...#", ".###."]),
+    ('7', ["#####", "....#", "...#.", "..#..", "..#.."]),
+    ('8', [".###.", "#...#", ".###.", "#...#", ".###."]),
+    ('9', [".###.", "#...#", ".####", "....#", ".###."]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["####.", "....#", "..##.", ".....", "..#.."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    (',', ["..", "..", "..", ".#", "#."]),
+    (':', [".", "#", ".", "#", "."]),
+    ('\'', ["#", "#", ".", ".", "."]),
+    ('-', ["....", "....", "####", "....", "...."]),
+];
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Map of characters to their banner letter rows.
+static BANNER_FONT: Lazy<FnvHashMap<char, &[&str; BANNER_HEIGHT as usize]>> =
+    Lazy::new(|| BANNER_FONT_DATA.iter().map(|(c, rows)| (*c, rows)).collect());
+
+//-------------------------------------------------------------------------------------------------
+// Banner exposes a static API for writing large multi-tile letters into types that impl Map2d.
+// Lowercase letters are drawn as uppercase and unknown characters are drawn as '?'.
+//-------------------------------------------------------------------------------------------------
+pub struct Banner;
+
+impl Banner {
+    //---------------------------------------------------------------------------------------------
+    // Returns the rows of the banner letter for a character.
+    //---------------------------------------------------------------------------------------------
+    fn letter(c: char) -> &'static [&'static str; BANNER_HEIGHT as usize] {
+        let upper = c.to_ascii_uppercase();
+
+        BANNER_FONT
+            .get(&upper)
+            .or_else(|| BANNER_FONT.get(&BANNER_MISSING_LETTER))
+            .expect("Banner font is missing the fallback letter.")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width in tiles of a single line of banner text.
+    //---------------------------------------------------------------------------------------------
+    fn line_width(line: &str) -> i32 {
+        let letters_width: i32 = line.chars().map(|c| Self::letter(c)[0].len() as i32).sum();
+        let spacing = (line.chars().count() as i32 - 1).max(0) * BANNER_LETTER_SPACING;
+        letters_width + spacing
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions in tiles of (possibly multi-line) banner text.
+    //---------------------------------------------------------------------------------------------
+    pub fn measure(text: &str) -> ICoord {
+        let width = text.lines().map(Self::line_width).max().unwrap_or(0);
+        let lines = text.lines().count() as i32;
+        let height = (lines * BANNER_HEIGHT) + ((lines - 1).max(0) * BANNER_LINE_SPACING);
+        (width, height)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Write banner text using the default block glyph, returning the dimensions written.
+    //---------------------------------------------------------------------------------------------
+    pub fn write<M>(
+        map: &mut M,
+        xy: ICoord,
+        text: &str,
+        settings: &RichTextFormatSettings,
+    ) -> ICoord
+    where
+        M: Map2d<Tile>,
+    {
+        Self::write_with_glyph(map, xy, text, BANNER_DEFAULT_GLYPH, settings)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Write banner text using a specific glyph, returning the dimensions written.
+    // (only the filled cells of each letter are updated, the rest are left untouched)
+    //---------------------------------------------------------------------------------------------
+    pub fn write_with_glyph<M>(
+        map: &mut M,
+        xy: ICoord,
+        text: &str,
+        glyph: char,
+        settings: &RichTextFormatSettings,
+    ) -> ICoord
+    where
+        M: Map2d<Tile>,
+    {
+        let mut y = xy.1;
+
+        for line in text.lines() {
+            let mut x = xy.0;
+
+            for c in line.chars() {
+                let rows = Self::letter(c);
+
+                for (row_index, row) in rows.iter().enumerate() {
+                    for (column_index, cell) in row.chars().enumerate() {
+                        let tile_xy = (x + column_index as i32, y + row_index as i32);
+
+                        if cell != BANNER_FILLED_CHAR || !map.in_bounds(tile_xy) {
+                            continue;
+                        }
+
+                        let tile = map.get_xy_mut(tile_xy);
+                        tile.glyph = glyph;
+                        settings.apply(tile);
+                    }
+                }
+
+                x += rows[0].len() as i32 + BANNER_LETTER_SPACING;
+            }
+
+            y += BANNER_HEIGHT + BANNER_LINE_SPACING;
+        }
+
+        Self::measure(text)
+    }
+}
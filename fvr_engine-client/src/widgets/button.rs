@@ -91,6 +91,58 @@ static TEXT_PRESSED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
     outline_opacity: None,
 };
 
+// Format settings for selected (multi-select) default state button.
+static CENTER_SELECTED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Center),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::White.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+static TEXT_SELECTED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::White.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for disabled button.
+static CENTER_DISABLED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Center),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::DarkGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+static TEXT_DISABLED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::DarkGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
 //-------------------------------------------------------------------------------------------------
 // Represents the possible states of the button.
 //-------------------------------------------------------------------------------------------------
@@ -141,6 +193,10 @@ pub struct Button {
     pub layout: ButtonLayout,
     // State of the button.
     state: State,
+    // Whether the button is disabled (ignores input and draws greyed out).
+    disabled: bool,
+    // Whether the button is selected (e.g. in a multi-select button list).
+    selected: bool,
 }
 
 impl Button {
@@ -148,7 +204,7 @@ impl Button {
     // Creates a new button.
     //---------------------------------------------------------------------------------------------
     pub fn new(origin: ICoord, text: String, layout: ButtonLayout) -> Self {
-        Self { origin, text, layout, state: State::Default }
+        Self { origin, text, layout, state: State::Default, disabled: false, selected: false }
     }
 
     //---------------------------------------------------------------------------------------------
@@ -167,6 +223,42 @@ impl Button {
         self.state = State::Default;
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the mouse is over the button (focused or pressed).
+    //---------------------------------------------------------------------------------------------
+    pub fn focused(&self) -> bool {
+        self.state != State::Default
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the button is disabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn disabled(&self) -> bool {
+        self.disabled
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Disables or enables the button, resetting it to the default state. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.state = State::Default;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the button is selected.
+    //---------------------------------------------------------------------------------------------
+    pub fn selected(&self) -> bool {
+        self.selected
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Selects or deselects the button. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+
     //---------------------------------------------------------------------------------------------
     // Updates the button, potentially redrawing if the state changes.
     //---------------------------------------------------------------------------------------------
@@ -174,6 +266,11 @@ impl Button {
     where
         M: Map2d<Tile>,
     {
+        // Disabled buttons ignore input.
+        if self.disabled {
+            return ButtonAction::Noop;
+        }
+
         match self.state {
             // In default state, wait for mouse to hover over button.
             State::Default => {
@@ -230,6 +327,22 @@ impl Button {
         M: Map2d<Tile>,
     {
         match self.state {
+            _ if self.disabled => {
+                let settings = match self.layout {
+                    ButtonLayout::Center => &CENTER_DISABLED_SETTINGS,
+                    ButtonLayout::Text => &TEXT_DISABLED_SETTINGS,
+                };
+
+                RichTextWriter::write_plain_with_settings(map, self.origin, &self.text, settings);
+            }
+            State::Default if self.selected => {
+                let settings = match self.layout {
+                    ButtonLayout::Center => &CENTER_SELECTED_SETTINGS,
+                    ButtonLayout::Text => &TEXT_SELECTED_SETTINGS,
+                };
+
+                RichTextWriter::write_plain_with_settings(map, self.origin, &self.text, settings);
+            }
             State::Default => {
                 let settings = match self.layout {
                     ButtonLayout::Center => &CENTER_DEFAULT_SETTINGS,
@@ -1,3 +1,8 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeSet;
+
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
@@ -20,6 +25,8 @@ pub enum ButtonListAction {
     Interactable,
     // Index of the button in the button list that was triggered.
     Triggered(i32),
+    // The selection of a multi-select button list changed.
+    SelectionChanged,
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -32,6 +39,8 @@ pub struct ButtonList {
     buttons: Vec<Button>,
     // Whether to add a space between the buttons.
     spacing: bool,
+    // Whether buttons can be selected with space or ctrl+click.
+    multi_select: bool,
 }
 
 impl ButtonList {
@@ -39,7 +48,7 @@ impl ButtonList {
     // Creates a new button list.
     //---------------------------------------------------------------------------------------------
     pub fn new(origin: ICoord, spacing: bool) -> Self {
-        Self { origin, buttons: Vec::new(), spacing }
+        Self { origin, buttons: Vec::new(), spacing, multi_select: false }
     }
 
     //---------------------------------------------------------------------------------------------
@@ -103,7 +112,7 @@ impl ButtonList {
     // Creates a new button list from a vec of buttons.
     //---------------------------------------------------------------------------------------------
     pub fn from_buttons_vec(origin: ICoord, buttons: Vec<Button>, spacing: bool) -> Self {
-        let mut button_list = Self { origin, buttons, spacing };
+        let mut button_list = Self { origin, buttons, spacing, multi_select: false };
         button_list.refresh_button_origins();
         button_list
     }
@@ -125,6 +134,58 @@ impl ButtonList {
         self.buttons.clear();
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns whether buttons can be selected with space or ctrl+click.
+    //---------------------------------------------------------------------------------------------
+    pub fn multi_select(&self) -> bool {
+        self.multi_select
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Enables or disables multi-select, clearing the selection when disabled. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.multi_select = multi_select;
+
+        if !multi_select {
+            self.clear_selection();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the indices of the selected buttons.
+    //---------------------------------------------------------------------------------------------
+    pub fn selection(&self) -> BTreeSet<i32> {
+        self.buttons
+            .iter()
+            .enumerate()
+            .filter(|(_, button)| button.selected())
+            .map(|(i, _)| i as i32)
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deselects all of the contained buttons. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear_selection(&mut self) {
+        for button in self.buttons.iter_mut() {
+            button.set_selected(false);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Disables or enables the button at an index, deselecting it when disabled. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_disabled(&mut self, index: i32, disabled: bool) {
+        if let Some(button) = self.buttons.get_mut(index as usize) {
+            button.set_disabled(disabled);
+
+            if disabled {
+                button.set_selected(false);
+            }
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Resets all of the contained buttons to the default state.
     //---------------------------------------------------------------------------------------------
@@ -136,6 +197,7 @@ impl ButtonList {
 
     //---------------------------------------------------------------------------------------------
     // Updates each of the contained buttons, returning the index of any that are triggered.
+    // With multi-select, ctrl+click or space over a button toggles its selection instead.
     //---------------------------------------------------------------------------------------------
     pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> ButtonListAction
     where
@@ -152,6 +214,21 @@ impl ButtonList {
                 continue;
             }
 
+            let toggle = self.multi_select
+                && match button_action {
+                    ButtonAction::Triggered => input.modifier_pressed(&ModifierKey::Ctrl),
+                    ButtonAction::Interactable => input.key_just_pressed(InputKey::Space),
+                    ButtonAction::Noop => false,
+                };
+
+            if toggle {
+                button.set_selected(!button.selected());
+                button.redraw(map);
+                action = ButtonListAction::SelectionChanged;
+                consumed = true;
+                continue;
+            }
+
             match button_action {
                 ButtonAction::Triggered => {
                     action = ButtonListAction::Triggered(i as i32);
@@ -1,3 +1,8 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::cmp;
+
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
@@ -13,6 +18,16 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 use crate::widgets::rich_text_writer::*;
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Cells between the border corners and the frame text.
+const TEXT_MARGIN: i32 = 2;
+
+// Minimum cells between the left and right text on the same edge.
+const TEXT_GAP: usize = 1;
+
 //-------------------------------------------------------------------------------------------------
 // Statics.
 //-------------------------------------------------------------------------------------------------
@@ -30,6 +45,9 @@ static FANCY_CORNER_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static FANCY_HORIZONTAL_THIN_TILE: Tile = Tile {
@@ -44,6 +62,9 @@ static FANCY_HORIZONTAL_THIN_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static FANCY_HORIZONTAL_THICK_TILE: Tile = Tile {
@@ -58,6 +79,9 @@ static FANCY_HORIZONTAL_THICK_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static FANCY_VERTICAL_THIN_TILE: Tile = Tile {
@@ -72,6 +96,9 @@ static FANCY_VERTICAL_THIN_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static FANCY_VERTICAL_THICK_TILE: Tile = Tile {
@@ -86,6 +113,9 @@ static FANCY_VERTICAL_THICK_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 // Line border tiles.
@@ -101,6 +131,9 @@ static LINE_TOP_LEFT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static LINE_TOP_RIGHT_TILE: Tile = Tile {
@@ -115,6 +148,9 @@ static LINE_TOP_RIGHT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static LINE_BOTTOM_LEFT_TILE: Tile = Tile {
@@ -129,6 +165,9 @@ static LINE_BOTTOM_LEFT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static LINE_BOTTOM_RIGHT_TILE: Tile = Tile {
@@ -143,6 +182,9 @@ static LINE_BOTTOM_RIGHT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static LINE_HORIZONTAL_TILE: Tile = Tile {
@@ -157,6 +199,9 @@ static LINE_HORIZONTAL_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static LINE_VERTICAL_TILE: Tile = Tile {
@@ -171,6 +216,9 @@ static LINE_VERTICAL_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 // Double line border tiles.
@@ -186,6 +234,9 @@ static DOUBLE_LINE_TOP_LEFT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static DOUBLE_LINE_TOP_RIGHT_TILE: Tile = Tile {
@@ -200,6 +251,9 @@ static DOUBLE_LINE_TOP_RIGHT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static DOUBLE_LINE_BOTTOM_LEFT_TILE: Tile = Tile {
@@ -214,6 +268,9 @@ static DOUBLE_LINE_BOTTOM_LEFT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static DOUBLE_LINE_BOTTOM_RIGHT_TILE: Tile = Tile {
@@ -228,6 +285,9 @@ static DOUBLE_LINE_BOTTOM_RIGHT_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static DOUBLE_LINE_HORIZONTAL_TILE: Tile = Tile {
@@ -242,6 +302,9 @@ static DOUBLE_LINE_HORIZONTAL_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 static DOUBLE_LINE_VERTICAL_TILE: Tile = Tile {
@@ -256,6 +319,9 @@ static DOUBLE_LINE_VERTICAL_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 // Simple line border tile.
@@ -271,6 +337,9 @@ static SIMPLE_LINE_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 // System line border tile.
@@ -286,6 +355,9 @@ static SYSTEM_LINE_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 // Format settings for frame text.
@@ -339,6 +411,7 @@ pub enum FrameStyle {
 
 //-------------------------------------------------------------------------------------------------
 // Frame handles drawing decorated rects. Used by other widgets.
+// Text that does not fit on an edge is truncated with an ellipsis when drawn.
 //-------------------------------------------------------------------------------------------------
 pub struct Frame {
     // Origin of the frame when drawing.
@@ -373,6 +446,13 @@ impl Frame {
         }
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Creates a new frame sized to snugly wrap content (e.g. the bounds of another widget).
+    //---------------------------------------------------------------------------------------------
+    pub fn around(content: &Rect, style: FrameStyle) -> Self {
+        Self::new((content.x - 1, content.y - 1), content.dimensions(), style)
+    }
+
     //---------------------------------------------------------------------------------------------
     // Returns the origin of the frame.
     //---------------------------------------------------------------------------------------------
@@ -384,14 +464,14 @@ impl Frame {
     // Returns the width of the frame.
     //---------------------------------------------------------------------------------------------
     pub fn width(&self) -> i32 {
-        self.inner_dimensions.0 + 1
+        self.inner_dimensions.0 + 2
     }
 
     //---------------------------------------------------------------------------------------------
     // Returns the height of the frame.
     //---------------------------------------------------------------------------------------------
     pub fn height(&self) -> i32 {
-        self.inner_dimensions.1 + 1
+        self.inner_dimensions.1 + 2
     }
 
     //---------------------------------------------------------------------------------------------
@@ -411,6 +491,90 @@ impl Frame {
         self.bottom_right_text = None;
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Resizes the area inside the frame, returning whether all of the frame's text still fits.
+    //---------------------------------------------------------------------------------------------
+    pub fn resize(&mut self, inner_dimensions: ICoord) -> bool {
+        self.inner_dimensions = inner_dimensions;
+        self.text_fits()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the # of cells available for text on an edge.
+    //---------------------------------------------------------------------------------------------
+    fn text_space(&self) -> usize {
+        (self.inner_dimensions.0 + 2 - TEXT_MARGIN * 2).max(0) as usize
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the # of cells needed for the text of an edge.
+    //---------------------------------------------------------------------------------------------
+    fn edge_text_len(left: Option<&String>, right: Option<&String>) -> usize {
+        let left_len = left.map_or(0, |text| RichTextWriter::display_width(text));
+        let right_len = right.map_or(0, |text| RichTextWriter::display_width(text));
+
+        match (left, right) {
+            (Some(_), Some(_)) => left_len + TEXT_GAP + right_len,
+            _ => left_len + right_len,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to fit the left and right text of an edge within the available space.
+    // The left text has priority, but each keeps at least half of the space if both are present.
+    //---------------------------------------------------------------------------------------------
+    fn fit_edge_text(
+        &self,
+        left: Option<&String>,
+        right: Option<&String>,
+    ) -> (Option<String>, Option<String>) {
+        let space = self.text_space();
+
+        match (left, right) {
+            (Some(left), Some(right)) => {
+                let shared = space.saturating_sub(TEXT_GAP);
+                let left_len = RichTextWriter::display_width(left);
+                let right_len = RichTextWriter::display_width(right);
+                let left_width = left_len.min(shared.saturating_sub(right_len).max(shared / 2));
+                let right_width = right_len.min(shared - left_width);
+
+                (
+                    Some(RichTextWriter::truncate_plain(left, left_width)),
+                    Some(RichTextWriter::truncate_plain(right, right_width)),
+                )
+            }
+            (left, right) => (
+                left.map(|text| RichTextWriter::truncate_plain(text, space)),
+                right.map(|text| RichTextWriter::truncate_plain(text, space)),
+            ),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether all of the frame's text fits without truncation.
+    //---------------------------------------------------------------------------------------------
+    pub fn text_fits(&self) -> bool {
+        let space = self.text_space();
+
+        Self::edge_text_len(self.top_left_text.as_ref(), self.top_right_text.as_ref()) <= space
+            && Self::edge_text_len(self.bottom_left_text.as_ref(), self.bottom_right_text.as_ref())
+                <= space
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Widens the area inside the frame (if necessary) so that all of the frame's text fits.
+    //---------------------------------------------------------------------------------------------
+    pub fn grow_to_fit_text(&mut self) {
+        let needed = cmp::max(
+            Self::edge_text_len(self.top_left_text.as_ref(), self.top_right_text.as_ref()),
+            Self::edge_text_len(self.bottom_left_text.as_ref(), self.bottom_right_text.as_ref()),
+        );
+
+        if needed > self.text_space() {
+            self.inner_dimensions.0 = needed as i32 + TEXT_MARGIN * 2 - 2;
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Centers the frame within a Map2dView.
     //---------------------------------------------------------------------------------------------
@@ -684,49 +848,37 @@ impl Frame {
             FrameStyle::System => self.draw_system_border(map),
         }
 
-        // Draw top-left text if populated.
-        if let Some(top_left_text) = self.top_left_text.as_ref() {
-            RichTextWriter::write_plain_with_settings(
-                map,
-                (self.origin.0 + 2, self.origin.1),
-                top_left_text,
-                &TEXT_FORMAT_SETTINGS,
-            );
-        }
-
-        // Draw top-right text if populated.
-        if let Some(top_right_text) = self.top_right_text.as_ref() {
-            let stripped_len = RichTextWriter::stripped_len(top_right_text)?;
-            RichTextWriter::write_plain_with_settings(
-                map,
-                (self.origin.0 + self.inner_dimensions.0 - stripped_len as i32, self.origin.1),
-                top_right_text,
-                &TEXT_FORMAT_SETTINGS,
-            );
-        }
-
-        // Draw bottom-left text if populated.
-        if let Some(bottom_left_text) = self.bottom_left_text.as_ref() {
-            RichTextWriter::write_plain_with_settings(
-                map,
-                (self.origin.0 + 2, self.origin.1 + self.inner_dimensions.1 + 1),
-                bottom_left_text,
-                &TEXT_FORMAT_SETTINGS,
-            );
+        // Fit the text of each edge, truncating if necessary.
+        let (top_left_text, top_right_text) =
+            self.fit_edge_text(self.top_left_text.as_ref(), self.top_right_text.as_ref());
+        let (bottom_left_text, bottom_right_text) =
+            self.fit_edge_text(self.bottom_left_text.as_ref(), self.bottom_right_text.as_ref());
+        let top_y = self.origin.1;
+        let bottom_y = self.origin.1 + self.inner_dimensions.1 + 1;
+
+        // Draw the left-aligned text if populated.
+        for (text, y) in [(top_left_text, top_y), (bottom_left_text, bottom_y)] {
+            if let Some(text) = text {
+                RichTextWriter::write_plain_with_settings(
+                    map,
+                    (self.origin.0 + TEXT_MARGIN, y),
+                    &text,
+                    &TEXT_FORMAT_SETTINGS,
+                );
+            }
         }
 
-        // Draw bottom-right text if populated.
-        if let Some(bottom_right_text) = self.bottom_right_text.as_ref() {
-            let stripped_len = RichTextWriter::stripped_len(bottom_right_text)?;
-            RichTextWriter::write_plain_with_settings(
-                map,
-                (
-                    self.origin.0 + self.inner_dimensions.0 - stripped_len as i32,
-                    self.origin.1 + self.inner_dimensions.1 + 1,
-                ),
-                bottom_right_text,
-                &TEXT_FORMAT_SETTINGS,
-            );
+        // Draw the right-aligned text if populated.
+        for (text, y) in [(top_right_text, top_y), (bottom_right_text, bottom_y)] {
+            if let Some(text) = text {
+                let len = RichTextWriter::display_width(&text) as i32;
+                RichTextWriter::write_plain_with_settings(
+                    map,
+                    (self.origin.0 + self.inner_dimensions.0 + 2 - TEXT_MARGIN - len, y),
+                    &text,
+                    &TEXT_FORMAT_SETTINGS,
+                );
+            }
         }
 
         Ok(())
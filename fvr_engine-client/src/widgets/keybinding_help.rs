@@ -0,0 +1,201 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+use strum::IntoEnumIterator;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::hotkeys::*;
+use crate::input_context::*;
+use crate::input_manager::*;
+use crate::widgets::frame::*;
+use crate::widgets::panel::*;
+use crate::widgets::table::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Widths of the action and keys columns (the context column takes the remaining width).
+const ACTION_COLUMN_WIDTH: i32 = 22;
+const KEYS_COLUMN_WIDTH: i32 = 16;
+
+// Texts of the panel's frame.
+const TITLE_TEXT: &str = "Keybindings";
+const HINT_TEXT: &str = "type to search";
+
+// Context column text for bindings that apply in every context.
+const ALL_CONTEXTS_TEXT: &str = "Any";
+
+// Keys column text for unbound actions.
+const UNBOUND_TEXT: &str = "(unbound)";
+
+// Heading of the macros group.
+const MACROS_HEADING: &str = "Macros";
+
+// Heading of the hotkeys group and its context column text (hotkeys apply above every context).
+const HOTKEYS_HEADING: &str = "Engine";
+const HOTKEYS_CONTEXT_TEXT: &str = "Global";
+
+// Context column text prefix for hotkeys that conflict with other bindings.
+const CONFLICT_TEXT: &str = "Conflict!";
+
+//-------------------------------------------------------------------------------------------------
+// KeybindingHelp is a searchable keybinding reference generated from the live bindings of the
+// input manager, grouped by action category, so that it never drifts from the actual bindings.
+//-------------------------------------------------------------------------------------------------
+pub struct KeybindingHelp {
+    // Panel drawn behind the table.
+    panel: Panel,
+    // Table of the actions and their bindings.
+    table: Table,
+}
+
+impl KeybindingHelp {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new keybinding help with the bindings of an input manager.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, inner_dimensions: ICoord, input: &InputManager) -> Self {
+        let mut panel = Panel::new(origin, inner_dimensions, FrameStyle::Line);
+        panel.frame.top_left_text = Some(TITLE_TEXT.into());
+        panel.frame.bottom_right_text = Some(HINT_TEXT.into());
+
+        let columns = vec![
+            TableColumn { header: "Action".into(), width: ACTION_COLUMN_WIDTH },
+            TableColumn { header: "Keys".into(), width: KEYS_COLUMN_WIDTH },
+            TableColumn { header: "Context".into(), width: 0 },
+        ];
+        let table = Table::new(panel.inner_origin(), inner_dimensions, columns);
+
+        let mut help = Self { panel, table };
+        help.refresh(input);
+        help
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Rebuilds the reference from the bindings of an input manager (e.g. after rebinding).
+    //---------------------------------------------------------------------------------------------
+    pub fn refresh(&mut self, input: &InputManager) {
+        self.table.clear();
+
+        for category in InputActionCategory::iter() {
+            self.table.push_heading(category.name());
+
+            for action in InputAction::iter().filter(|action| action.category() == category) {
+                let keys = input
+                    .action_bindings(action)
+                    .map(InputBinding::describe_all)
+                    .unwrap_or_else(|| UNBOUND_TEXT.into());
+
+                self.table.push_row(vec![
+                    action.description().into(),
+                    keys,
+                    ALL_CONTEXTS_TEXT.into(),
+                ]);
+
+                // List the context overrides of the action after its regular bindings.
+                for context in InputContext::iter() {
+                    if let Some(bindings) = input.context_action_bindings(context, action) {
+                        self.table.push_row(vec![
+                            action.description().into(),
+                            InputBinding::describe_all(bindings),
+                            format!("{:?}", context),
+                        ]);
+                    }
+                }
+            }
+        }
+
+        if !input.macros().macros().is_empty() {
+            self.table.push_heading(MACROS_HEADING);
+
+            for input_macro in input.macros().macros() {
+                let key =
+                    InputBinding::SpecificKey(input_macro.key).describe().unwrap_or_default();
+
+                self.table.push_row(vec![
+                    format!("Replay {} actions", input_macro.actions.len()),
+                    key,
+                    ALL_CONTEXTS_TEXT.into(),
+                ]);
+            }
+        }
+
+        // List the hotkeys last, flagging any that take the keys of other bindings.
+        self.table.push_heading(HOTKEYS_HEADING);
+
+        for hotkey in Hotkey::iter() {
+            let keys = input
+                .hotkeys()
+                .bindings(hotkey)
+                .filter(|bindings| !bindings.is_empty())
+                .map(InputBinding::describe_all)
+                .unwrap_or_else(|| UNBOUND_TEXT.into());
+            let context = match input.hotkeys().conflicts_of(hotkey).count() {
+                0 => HOTKEYS_CONTEXT_TEXT.into(),
+                count => format!("{} ({})", CONFLICT_TEXT, count),
+            };
+
+            self.table.push_row(vec![hotkey.description().into(), keys, context]);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the table of the keybinding help.
+    //---------------------------------------------------------------------------------------------
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text typed to search the bindings.
+    //---------------------------------------------------------------------------------------------
+    pub fn filter(&self) -> &str {
+        self.table.filter()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area covered by the keybinding help, including the panel's shadow.
+    //---------------------------------------------------------------------------------------------
+    pub fn bounds(&self) -> Rect {
+        self.panel.bounds()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the keybinding help. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.panel.set_origin(origin);
+        self.table.set_origin(self.panel.inner_origin());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the keybinding help, potentially redrawing the table if the state changes.
+    // Typing searches the bindings (escape clears the search) and the arrow keys scroll.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> TableAction
+    where
+        M: Map2d<Tile>,
+    {
+        self.table.update(input, map)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the keybinding help. Only necessary initially and when moving the keybinding help.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M) -> Result<()>
+    where
+        M: Map2d<Tile>,
+    {
+        self.panel.draw(map)?;
+        self.table.redraw(map);
+        Ok(())
+    }
+}
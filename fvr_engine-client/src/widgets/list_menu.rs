@@ -1 +1,698 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeSet;
 
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+use crate::widgets::rich_text_writer::*;
+use crate::widgets::scrollbar::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Markers drawn before the items of a multi-select list menu.
+const SELECTED_MARKER: &str = "[x] ";
+const UNSELECTED_MARKER: &str = "[ ] ";
+
+// Prefix of the search line drawn while filtering.
+const SEARCH_PREFIX: &str = "/";
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Format settings for an item.
+static ITEM_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::BrightGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the item under the cursor.
+static CURSOR_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::Gold.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for a disabled item.
+static DISABLED_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::DarkGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the part of an item matching the filter (applied over the item settings).
+static MATCH_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: None,
+    style: Some(TileStyle::Bold),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::Yellow.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the response codes when updating a list menu.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListMenuAction {
+    // The list menu was not interacted with.
+    Noop,
+    // The list menu consumed user input (e.g. moved the cursor or changed the filter).
+    Interactable,
+    // Index of the item in the list menu that was triggered.
+    Triggered(i32),
+    // The selection of a multi-select list menu changed.
+    SelectionChanged,
+}
+
+//-------------------------------------------------------------------------------------------------
+// An item of a list menu.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListMenuItem {
+    // Text of the item (plain only).
+    pub text: String,
+    // Whether the item is disabled (drawn greyed out and skipped by the cursor).
+    pub disabled: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ListMenu manages a scrolling, vertical list of items that can be filtered by typing, navigated
+// with the arrow keys or mouse, and optionally multi-selected with space or ctrl+click.
+// A scrollbar bound to the scroll position is shown in the last column when the items overflow.
+//-------------------------------------------------------------------------------------------------
+pub struct ListMenu {
+    // Origin of the list menu.
+    origin: ICoord,
+    // Dimensions of the list menu.
+    dimensions: ICoord,
+    // Items of the list menu.
+    items: Vec<ListMenuItem>,
+    // Text typed to filter the items (lowercase).
+    filter: String,
+    // Indices of the items matching the filter.
+    filtered: Vec<usize>,
+    // Position of the cursor in the filtered items, if any.
+    cursor: Option<usize>,
+    // Position of the first visible filtered item.
+    scroll: usize,
+    // Whether items can be selected with space or ctrl+click.
+    multi_select: bool,
+    // Indices of the selected items.
+    selection: BTreeSet<i32>,
+    // Scrollbar shown when the filtered items overflow the visible rows.
+    scrollbar: Option<Scrollbar>,
+    // Whether the list menu must be redrawn.
+    dirty: bool,
+}
+
+impl ListMenu {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, dimensions: ICoord, multi_select: bool) -> Self {
+        Self {
+            origin,
+            dimensions,
+            items: Vec::new(),
+            filter: String::new(),
+            filtered: Vec::new(),
+            cursor: None,
+            scroll: 0,
+            multi_select,
+            selection: BTreeSet::new(),
+            scrollbar: None,
+            dirty: true,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the origin of the list menu. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+        self.sync_scrollbar();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.dimensions.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the height of the list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn height(&self) -> i32 {
+        self.dimensions.1
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the items of the list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn items(&self) -> &[ListMenuItem] {
+        &self.items
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes back a new item.
+    //---------------------------------------------------------------------------------------------
+    pub fn push<S>(&mut self, text: S, disabled: bool)
+    where
+        S: Into<String>,
+    {
+        self.items.push(ListMenuItem { text: text.into(), disabled });
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all items, the filter and the selection.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.filter.clear();
+        self.selection.clear();
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Disables or enables the item at an index, deselecting it when disabled.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_disabled(&mut self, index: i32, disabled: bool) {
+        if let Some(item) = self.items.get_mut(index as usize) {
+            item.disabled = disabled;
+
+            if disabled {
+                let _ = self.selection.remove(&index);
+            }
+
+            self.refresh_filter();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the text of the item at an index, keeping the cursor and selection.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_text<S>(&mut self, index: i32, text: S)
+    where
+        S: Into<String>,
+    {
+        if let Some(item) = self.items.get_mut(index as usize) {
+            item.text = text.into();
+
+            // The item may no longer match the filter (the scroll is kept when unfiltered).
+            if self.filter.is_empty() {
+                self.dirty = true;
+            } else {
+                self.refresh_filter();
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text typed to filter the items.
+    //---------------------------------------------------------------------------------------------
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the text to filter the items by (case insensitive).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.chars().flat_map(char::to_lowercase).collect();
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the indices of the items matching the filter.
+    //---------------------------------------------------------------------------------------------
+    pub fn filtered(&self) -> impl Iterator<Item = i32> + '_ {
+        self.filtered.iter().map(|i| *i as i32)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the item under the cursor, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn cursor_item(&self) -> Option<i32> {
+        self.cursor.map(|position| self.filtered[position] as i32)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether items can be selected with space or ctrl+click.
+    //---------------------------------------------------------------------------------------------
+    pub fn multi_select(&self) -> bool {
+        self.multi_select
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the indices of the selected items.
+    //---------------------------------------------------------------------------------------------
+    pub fn selection(&self) -> &BTreeSet<i32> {
+        &self.selection
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deselects all items.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to find the range of chars of a text matching a (lowercase) filter.
+    // The text is lowercased the same way as the filter, one char at a time, so that the range
+    // still refers to chars of the original text when a char lowercases to several.
+    //---------------------------------------------------------------------------------------------
+    fn match_range(text: &str, filter: &str) -> Option<(usize, usize)> {
+        let text: Vec<(usize, char)> = text
+            .chars()
+            .enumerate()
+            .flat_map(|(i, c)| c.to_lowercase().map(move |lower| (i, lower)))
+            .collect();
+        let filter: Vec<char> = filter.chars().collect();
+
+        if filter.is_empty() {
+            return Some((0, 0));
+        }
+
+        text.windows(filter.len())
+            .find(|window| window.iter().map(|(_, c)| *c).eq(filter.iter().copied()))
+            .map(|window| (window[0].0, window[filter.len() - 1].0 + 1))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to recalculate the filtered items, keeping the cursor on an enabled item.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_filter(&mut self) {
+        let cursor_item = self.cursor_item();
+
+        self.filtered = (0..self.items.len())
+            .filter(|i| Self::match_range(&self.items[*i].text, &self.filter).is_some())
+            .collect();
+
+        // Keep the cursor on the same item if it is still visible, otherwise the first enabled.
+        self.cursor = cursor_item
+            .and_then(|item| self.filtered.iter().position(|i| *i as i32 == item))
+            .filter(|position| !self.items[self.filtered[*position]].disabled)
+            .or_else(|| self.filtered.iter().position(|i| !self.items[*i].disabled));
+
+        self.scroll = 0;
+        self.scroll_to_cursor();
+        self.sync_scrollbar();
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the number of rows available for items.
+    //---------------------------------------------------------------------------------------------
+    fn item_rows(&self) -> usize {
+        let rows = if self.filter.is_empty() { self.height() } else { self.height() - 1 };
+        rows.max(0) as usize
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the number of columns available for item text.
+    //---------------------------------------------------------------------------------------------
+    fn text_width(&self) -> i32 {
+        if self.scrollbar.is_some() {
+            self.width() - 1
+        } else {
+            self.width()
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position of the first visible item in the filtered items.
+    //---------------------------------------------------------------------------------------------
+    pub fn scroll_position(&self) -> i32 {
+        self.scroll as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Scrolls so that a position in the filtered items is the first visible.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_scroll_position(&mut self, position: i32) {
+        let max_scroll = self.filtered.len().saturating_sub(self.item_rows());
+        self.scroll = (position.max(0) as usize).min(max_scroll);
+        self.sync_scrollbar();
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to show, hide or update the scrollbar to match the scroll position.
+    //---------------------------------------------------------------------------------------------
+    fn sync_scrollbar(&mut self) {
+        let rows = self.item_rows() as i32;
+        let content_height = self.filtered.len() as i32;
+
+        // The scrollbar requires room for its buttons and at least one track segment.
+        if content_height <= rows || rows <= 2 {
+            self.scrollbar = None;
+            return;
+        }
+
+        let origin = (self.origin.0 + self.width() - 1, self.origin.1);
+
+        match self.scrollbar.as_mut() {
+            Some(scrollbar) => {
+                if scrollbar.origin() != origin {
+                    scrollbar.set_origin(origin);
+                }
+                if scrollbar.height() != rows {
+                    scrollbar.set_height(rows);
+                }
+
+                scrollbar.sync(content_height, self.scroll as i32);
+            }
+            None => {
+                let mut scrollbar = Scrollbar::new(origin, rows, content_height);
+                scrollbar.set_current_line(self.scroll as i32);
+                self.scrollbar = Some(scrollbar);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to scroll the cursor into view.
+    //---------------------------------------------------------------------------------------------
+    fn scroll_to_cursor(&mut self) {
+        if let Some(position) = self.cursor {
+            let rows = self.item_rows().max(1);
+
+            if position < self.scroll {
+                self.scroll = position;
+            } else if position >= self.scroll + rows {
+                self.scroll = position + 1 - rows;
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to move the cursor up (negative) or down, skipping disabled items.
+    // Returns whether the cursor moved.
+    //---------------------------------------------------------------------------------------------
+    fn move_cursor(&mut self, delta: i32) -> bool {
+        let mut position = match self.cursor {
+            Some(position) => position as i32,
+            None if delta > 0 => -1,
+            None => self.filtered.len() as i32,
+        };
+
+        loop {
+            position += delta.signum();
+
+            if position < 0 || position >= self.filtered.len() as i32 {
+                return false;
+            }
+
+            if !self.items[self.filtered[position as usize]].disabled {
+                break;
+            }
+        }
+
+        self.cursor = Some(position as usize);
+        self.scroll_to_cursor();
+        self.dirty = true;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to toggle the selection of the item under the cursor.
+    // Returns whether the selection changed.
+    //---------------------------------------------------------------------------------------------
+    fn toggle_cursor_item(&mut self) -> bool {
+        let item = match self.cursor_item() {
+            Some(item) if self.multi_select => item,
+            _ => return false,
+        };
+
+        if !self.selection.remove(&item) {
+            let _ = self.selection.insert(item);
+        }
+
+        self.dirty = true;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the position in the filtered items of the row at a coord.
+    //---------------------------------------------------------------------------------------------
+    fn position_at(&self, (x, y): ICoord) -> Option<usize> {
+        if x < self.origin.0 || x >= self.origin.0 + self.text_width() || y < self.origin.1 {
+            return None;
+        }
+
+        let row = (y - self.origin.1) as usize;
+
+        if row >= self.item_rows() {
+            return None;
+        }
+
+        Some(self.scroll + row).filter(|position| *position < self.filtered.len())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the list menu, potentially redrawing if the state changes.
+    // Typing filters the items, up/down move the cursor, return triggers the item under the
+    // cursor and (with multi-select) space toggles its selection.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> ListMenuAction
+    where
+        M: Map2d<Tile>,
+    {
+        let mut action = ListMenuAction::Noop;
+
+        // Update the scrollbar, scrolling the items with it.
+        if let Some(scrollbar) = self.scrollbar.as_mut() {
+            match scrollbar.update(input, map) {
+                ScrollbarAction::Noop => {}
+                ScrollbarAction::ScrollUp(lines) => {
+                    self.scroll = self.scroll.saturating_sub(lines as usize);
+                    action = ListMenuAction::Interactable;
+                    self.dirty = true;
+                }
+                ScrollbarAction::ScrollDown(lines) => {
+                    self.scroll += lines as usize;
+                    action = ListMenuAction::Interactable;
+                    self.dirty = true;
+                }
+                _ => action = ListMenuAction::Interactable,
+            }
+        }
+
+        // Update the filter from typed keys.
+        let typed = input.just_pressed_chars();
+
+        if !typed.is_empty() && !input.modifier_pressed(&ModifierKey::Ctrl) {
+            self.filter.extend(typed.into_iter().flat_map(char::to_lowercase));
+            self.refresh_filter();
+            action = ListMenuAction::Interactable;
+        } else if !self.filter.is_empty()
+            && (input.key_just_pressed(InputKey::Backspace)
+                || input.key_just_pressed(InputKey::Escape))
+        {
+            if input.key_just_pressed(InputKey::Escape) {
+                self.filter.clear();
+            } else {
+                let _ = self.filter.pop();
+            }
+
+            self.refresh_filter();
+            action = ListMenuAction::Interactable;
+        }
+
+        // Move the cursor with the arrow keys.
+        if (input.key_just_pressed(InputKey::Up) && self.move_cursor(-1))
+            || (input.key_just_pressed(InputKey::Down) && self.move_cursor(1))
+        {
+            action = ListMenuAction::Interactable;
+        }
+
+        // Move the cursor with the mouse, triggering or toggling clicked items.
+        if let Some(position) = input.mouse_coord().and_then(|xy| self.position_at(xy)) {
+            if !self.items[self.filtered[position]].disabled {
+                if self.cursor != Some(position) {
+                    self.cursor = Some(position);
+                    self.dirty = true;
+                }
+
+                action = ListMenuAction::Interactable;
+
+                if input.mouse_clicked(InputMouse::Left) {
+                    if self.multi_select && input.modifier_pressed(&ModifierKey::Ctrl) {
+                        let _ = self.toggle_cursor_item();
+                        action = ListMenuAction::SelectionChanged;
+                    } else {
+                        action = ListMenuAction::Triggered(self.filtered[position] as i32);
+                    }
+                }
+            }
+
+            // Scroll with the mouse wheel.
+            let wheel = input.mouse_wheel();
+
+            if wheel != 0 {
+                let max_scroll = self.filtered.len().saturating_sub(self.item_rows());
+                self.scroll = (self.scroll as i32 - wheel).clamp(0, max_scroll as i32) as usize;
+                self.dirty = true;
+            }
+        }
+
+        // Handle the keyboard actions for the item under the cursor.
+        if input.key_just_pressed(InputKey::Space) && self.toggle_cursor_item() {
+            action = ListMenuAction::SelectionChanged;
+        } else if input.key_just_pressed(InputKey::Return) {
+            if let Some(item) = self.cursor_item() {
+                action = ListMenuAction::Triggered(item);
+            }
+        }
+
+        if self.dirty {
+            self.sync_scrollbar();
+            self.redraw(map);
+            self.dirty = false;
+        }
+
+        action
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to draw a row of text, padded or truncated to the width of the list menu.
+    //---------------------------------------------------------------------------------------------
+    fn draw_row<M>(&self, map: &mut M, row: i32, text: &str, settings: &RichTextFormatSettings)
+    where
+        M: Map2d<Tile>,
+    {
+        let width = self.text_width().max(0) as usize;
+        let text: String = text.chars().chain(std::iter::repeat(' ')).take(width).collect();
+
+        RichTextWriter::write_plain_with_settings(
+            map,
+            (self.origin.0, self.origin.1 + row),
+            &text,
+            settings,
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the list menu. Only necessary initially and when moving the list menu.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let rows = self.item_rows();
+
+        for row in 0..rows {
+            let position = self.scroll + row;
+
+            let index = match self.filtered.get(position) {
+                Some(index) => *index,
+                None => {
+                    self.draw_row(map, row as i32, "", &ITEM_SETTINGS);
+                    continue;
+                }
+            };
+
+            let item = &self.items[index];
+            let marker = match (self.multi_select, self.selection.contains(&(index as i32))) {
+                (false, _) => "",
+                (true, true) => SELECTED_MARKER,
+                (true, false) => UNSELECTED_MARKER,
+            };
+            let settings = if item.disabled {
+                &DISABLED_SETTINGS
+            } else if self.cursor == Some(position) {
+                &CURSOR_SETTINGS
+            } else {
+                &ITEM_SETTINGS
+            };
+
+            self.draw_row(map, row as i32, &format!("{}{}", marker, item.text), settings);
+
+            // Highlight the part of the item matching the filter.
+            if let Some((start, end)) = Self::match_range(&item.text, &self.filter) {
+                let offset = marker.chars().count() + start;
+                let available = (self.text_width().max(0) as usize).saturating_sub(offset);
+                let matched: String = item.text.chars().skip(start).take(end - start).collect();
+                let matched: String = matched.chars().take(available).collect();
+
+                if !matched.is_empty() && !item.disabled {
+                    RichTextWriter::write_plain_with_settings(
+                        map,
+                        (self.origin.0 + offset as i32, self.origin.1 + row as i32),
+                        &matched,
+                        &MATCH_SETTINGS,
+                    );
+                }
+            }
+        }
+
+        // Draw the scrollbar if the items overflow.
+        if let Some(scrollbar) = self.scrollbar.as_ref() {
+            scrollbar.redraw(map);
+        }
+
+        // Draw the search line while filtering.
+        if !self.filter.is_empty() && self.height() > 0 {
+            let text: String = format!("{}{}", SEARCH_PREFIX, self.filter)
+                .chars()
+                .chain(std::iter::repeat(' '))
+                .take(self.width().max(0) as usize)
+                .collect();
+
+            RichTextWriter::write_plain_with_settings(
+                map,
+                (self.origin.0, self.origin.1 + self.height() - 1),
+                &text,
+                &CURSOR_SETTINGS,
+            );
+        }
+    }
+}
@@ -1,23 +1,43 @@
+mod action_bar;
+mod banner;
 mod button;
 mod button_list;
 mod frame;
+mod keybinding_help;
 mod list_menu;
 mod modal;
+mod panel;
+mod progress_bar;
 mod rich_text_wrapper;
 mod rich_text_writer;
 mod scroll_log;
 mod scrollbar;
+mod tab_container;
+mod table;
+mod text_input;
 mod tree_list_menu;
+mod turn_order;
+mod widget_tree;
 
 pub mod prelude {
+    pub use crate::widgets::action_bar::*;
+    pub use crate::widgets::banner::*;
     pub use crate::widgets::button::*;
     pub use crate::widgets::button_list::*;
     pub use crate::widgets::frame::*;
+    pub use crate::widgets::keybinding_help::*;
     pub use crate::widgets::list_menu::*;
     pub use crate::widgets::modal::*;
+    pub use crate::widgets::panel::*;
+    pub use crate::widgets::progress_bar::*;
     pub use crate::widgets::rich_text_wrapper::*;
     pub use crate::widgets::rich_text_writer::*;
     pub use crate::widgets::scroll_log::*;
     pub use crate::widgets::scrollbar::*;
+    pub use crate::widgets::tab_container::*;
+    pub use crate::widgets::table::*;
+    pub use crate::widgets::text_input::*;
     pub use crate::widgets::tree_list_menu::*;
+    pub use crate::widgets::turn_order::*;
+    pub use crate::widgets::widget_tree::*;
 }
@@ -0,0 +1,196 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::widgets::frame::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Default offset of the drop shadow (down and to the right).
+const DEFAULT_SHADOW_OFFSET: ICoord = (1, 1);
+
+// Default opacity of the drop shadow.
+const DEFAULT_SHADOW_OPACITY: f32 = 0.6;
+
+// Default opacity of the interior fill.
+const DEFAULT_FILL_OPACITY: f32 = 0.9;
+
+//-------------------------------------------------------------------------------------------------
+// PanelShadow describes the drop shadow of a panel.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanelShadow {
+    // Offset of the shadow from the panel.
+    pub offset: ICoord,
+    // Opacity of the shadow (how much it darkens the tiles beneath it).
+    pub opacity: f32,
+}
+
+impl Default for PanelShadow {
+    fn default() -> Self {
+        Self { offset: DEFAULT_SHADOW_OFFSET, opacity: DEFAULT_SHADOW_OPACITY }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Panel draws a frame around a filled, optionally translucent interior with an optional drop
+// shadow, giving UI windows a consistent look. Draw a panel before the widgets it contains.
+//-------------------------------------------------------------------------------------------------
+pub struct Panel {
+    // Frame of the panel (nine-slice border and corner text).
+    pub frame: Frame,
+    // Color filling the panel (including beneath the border).
+    pub fill_color: TileColor,
+    // Opacity of the fill.
+    pub fill_opacity: f32,
+    // Drop shadow of the panel, if any.
+    pub shadow: Option<PanelShadow>,
+}
+
+impl Panel {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new panel with the default fill and shadow.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, inner_dimensions: ICoord, style: FrameStyle) -> Self {
+        Self::from_frame(Frame::new(origin, inner_dimensions, style))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new panel sized to snugly wrap content (e.g. the bounds of another widget).
+    //---------------------------------------------------------------------------------------------
+    pub fn around(content: &Rect, style: FrameStyle) -> Self {
+        Self::from_frame(Frame::around(content, style))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new panel from a frame with the default fill and shadow.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_frame(frame: Frame) -> Self {
+        Self {
+            frame,
+            fill_color: PaletteColor::Black.into(),
+            fill_opacity: DEFAULT_FILL_OPACITY,
+            shadow: Some(PanelShadow::default()),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the panel.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.frame.origin()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the area inside the panel.
+    //---------------------------------------------------------------------------------------------
+    pub fn inner_origin(&self) -> ICoord {
+        (self.frame.origin.0 + 1, self.frame.origin.1 + 1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the area inside the panel.
+    //---------------------------------------------------------------------------------------------
+    pub fn inner_dimensions(&self) -> ICoord {
+        self.frame.inner_dimensions()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area covered by the panel, excluding the shadow.
+    //---------------------------------------------------------------------------------------------
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.frame.origin(), self.frame.width(), self.frame.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area covered by the panel, including the shadow.
+    //---------------------------------------------------------------------------------------------
+    pub fn bounds(&self) -> Rect {
+        match self.shadow.as_ref() {
+            Some(shadow) => self.rect().containing(&self.shadow_rect(shadow)),
+            None => self.rect(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the panel's area offset by a shadow.
+    //---------------------------------------------------------------------------------------------
+    fn shadow_rect(&self, shadow: &PanelShadow) -> Rect {
+        let rect = self.rect();
+        Rect::new((rect.x + shadow.offset.0, rect.y + shadow.offset.1), rect.width, rect.height)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the panel.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.frame.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resizes the area inside the panel, returning whether all of the frame's text still fits.
+    //---------------------------------------------------------------------------------------------
+    pub fn resize(&mut self, inner_dimensions: ICoord) -> bool {
+        self.frame.resize(inner_dimensions)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to draw the shadow beneath the panel.
+    // Shadow tiles are set (not blended) so that redrawing does not darken them further.
+    //---------------------------------------------------------------------------------------------
+    fn draw_shadow<M>(&self, map: &mut M, shadow: &PanelShadow)
+    where
+        M: Map2d<Tile>,
+    {
+        for xy in self.shadow_rect(shadow).difference(&self.rect()) {
+            if !map.in_bounds(xy) {
+                continue;
+            }
+
+            let tile = map.get_xy_mut(xy);
+            tile.background_color = PaletteColor::Black.into();
+            tile.background_opacity = shadow.opacity;
+            tile.foreground_opacity = 1.0 - shadow.opacity;
+            tile.outline_opacity = 1.0 - shadow.opacity;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the panel onto a Map2d<Tile>, clearing the interior.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw<M>(&self, map: &mut M) -> Result<()>
+    where
+        M: Map2d<Tile>,
+    {
+        if let Some(shadow) = self.shadow.as_ref() {
+            self.draw_shadow(map, shadow);
+        }
+
+        self.frame.draw_clear(map)?;
+
+        // Fill beneath the border and interior.
+        for xy in self.rect().points() {
+            if !map.in_bounds(xy) {
+                continue;
+            }
+
+            let tile = map.get_xy_mut(xy);
+            tile.background_color = self.fill_color;
+            tile.background_opacity = self.fill_opacity;
+            tile.background_gradient = TileGradient::None;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants
+//-------------------------------------------------------------------------------------------------
+
+// Glyphs of a partially filled cell, by eighths filled (a fully filled cell is a full block).
+const PARTIAL_CHARS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const FILLED_CHAR: char = '█';
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+static FILLED_TILE: Tile = Tile {
+    glyph: FILLED_CHAR,
+    layout: TileLayout::Center,
+    style: TileStyle::Regular,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::DarkGrey.const_into(),
+    foreground_color: PaletteColor::Gold.const_into(),
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+static EMPTY_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Regular,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::DarkGrey.const_into(),
+    foreground_color: TileColor::TRANSPARENT,
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+
+//-------------------------------------------------------------------------------------------------
+// ProgressBar draws a horizontal bar filled in proportion to a progress in the range [0.0, 1.0].
+// Cells are filled in eighths, so that slow progress still visibly moves the bar.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ProgressBar {
+    // Origin of the bar.
+    origin: ICoord,
+    // Width of the bar in tiles.
+    width: i32,
+    // Progress of the bar, in the range [0.0, 1.0].
+    progress: f32,
+    // Color of the filled part of the bar.
+    color: TileColor,
+}
+
+impl ProgressBar {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty progress bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, width: i32) -> Self {
+        Self { origin, width: width.max(1), progress: 0.0, color: FILLED_TILE.foreground_color }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the origin of the bar. The bar must be redrawn afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the bar in tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the progress of the bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the progress of the bar (clamped to [0.0, 1.0]). Returns whether the drawn bar changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_progress(&mut self, progress: f32) -> bool {
+        let old_eighths = self.filled_eighths();
+        self.progress = progress.clamp(0.0, 1.0);
+        self.filled_eighths() != old_eighths
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color of the filled part of the bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn color(&self) -> TileColor {
+        self.color
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the color of the filled part of the bar. The bar must be redrawn afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_color(&mut self, color: TileColor) {
+        self.color = color;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the # of eighths of cells filled.
+    //---------------------------------------------------------------------------------------------
+    fn filled_eighths(&self) -> i32 {
+        (self.progress * (self.width * 8) as f32).floor() as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the bar.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let eighths = self.filled_eighths();
+
+        for i in 0..self.width {
+            let xy = (self.origin.0 + i, self.origin.1);
+
+            if !map.in_bounds(xy) {
+                continue;
+            }
+
+            let filled = (eighths - i * 8).clamp(0, 8);
+            let tile = map.get_xy_mut(xy);
+
+            match filled {
+                0 => *tile = EMPTY_TILE,
+                8 => {
+                    *tile = FILLED_TILE;
+                    tile.foreground_color = self.color;
+                }
+                _ => {
+                    *tile = FILLED_TILE;
+                    tile.glyph = PARTIAL_CHARS[filled as usize];
+                    tile.layout = TileLayout::Text;
+                    tile.foreground_color = self.color;
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //-------------------------------------------------------------------------------------------------
 use std::cell::{Ref, RefCell};
 use std::cmp;
+use std::collections::VecDeque;
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
@@ -26,6 +27,10 @@ use crate::widgets::rich_text_writer::*;
 const NEWLINE_CHARACTER: char = '\n';
 const SPACE_CHARACTER: char = ' ';
 
+// Prefix of the tags that make a line an anchor (e.g. "anchor:combat.12"), which widgets showing
+// the line can report interactions with.
+pub const ANCHOR_TAG_PREFIX: &str = "anchor:";
+
 //-------------------------------------------------------------------------------------------------
 // Helper struct for storing current format state.
 //-------------------------------------------------------------------------------------------------
@@ -49,6 +54,10 @@ struct FormatState {
     background_color: Option<String>,
     // Optional outline color tag value.
     outline_color: Option<String>,
+    // Optional outline thickness tag value.
+    outline_thickness: Option<String>,
+    // Optional outline glow tag value.
+    outline_glow: Option<String>,
 }
 
 impl FormatState {
@@ -63,6 +72,9 @@ impl FormatState {
         self.foreground_color = None;
         self.background_color = None;
         self.outline_color = None;
+        self.outline_thickness = None;
+        self.outline_glow = None;
+        self.updated = true;
     }
 
     //---------------------------------------------------------------------------------------------
@@ -77,6 +89,8 @@ impl FormatState {
             RichTextHintType::ForegroundColor => self.foreground_color = Some(value),
             RichTextHintType::BackgroundColor => self.background_color = Some(value),
             RichTextHintType::OutlineColor => self.outline_color = Some(value),
+            RichTextHintType::OutlineThickness => self.outline_thickness = Some(value),
+            RichTextHintType::OutlineGlow => self.outline_glow = Some(value),
         }
 
         self.updated = true;
@@ -116,6 +130,12 @@ impl FormatState {
             if let Some(ref outline_color) = self.outline_color {
                 *tag_string += &format!("<bc:{}>", outline_color);
             }
+            if let Some(ref outline_thickness) = self.outline_thickness {
+                *tag_string += &format!("<ot:{}>", outline_thickness);
+            }
+            if let Some(ref outline_glow) = self.outline_glow {
+                *tag_string += &format!("<og:{}>", outline_glow);
+            }
         }
 
         self.updated = false;
@@ -123,6 +143,57 @@ impl FormatState {
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// Identifies a line of a rich text wrapper (ids are never reused by the same wrapper).
+//-------------------------------------------------------------------------------------------------
+pub type RichTextLineId = u64;
+
+//-------------------------------------------------------------------------------------------------
+// RichTextLine describes a logical line of a rich text wrapper: the rich text appended to it
+// (which may wrap over several rows or contain newlines) along with metadata describing it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct RichTextLine {
+    // Id of the line.
+    id: RichTextLineId,
+    // Rich text appended to the line, one segment per append.
+    segments: Vec<String>,
+    // Index of the first wrapped row of the line.
+    first_row: usize,
+    // Tags describing the line (e.g. the channel or turn of a message).
+    pub tags: Vec<String>,
+}
+
+impl RichTextLine {
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the line.
+    //---------------------------------------------------------------------------------------------
+    pub fn id(&self) -> RichTextLineId {
+        self.id
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the rich text appended to the line, one segment per append.
+    //---------------------------------------------------------------------------------------------
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the line has a tag.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the key of the line's anchor (its first anchor tag without the prefix), if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn anchor(&self) -> Option<&str> {
+        self.tags.iter().find_map(|t| t.strip_prefix(ANCHOR_TAG_PREFIX))
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // RichTextWrapper parses and wraps and allows for drawing an expandable rich text string.
 //-------------------------------------------------------------------------------------------------
@@ -156,6 +227,10 @@ pub struct RichTextWrapper {
     visible_end: usize,
     // Index of the newline at the beginning of the current visible area.
     current_line: usize,
+    // Logical lines of the rich text, oldest first.
+    lines: VecDeque<RichTextLine>,
+    // Id of the next pushed line.
+    next_line_id: RichTextLineId,
 }
 
 impl RichTextWrapper {
@@ -246,6 +321,111 @@ impl RichTextWrapper {
         self.total_lines > self.height()
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns the logical lines of the rich text, oldest first.
+    //---------------------------------------------------------------------------------------------
+    pub fn lines(&self) -> impl Iterator<Item = &RichTextLine> + '_ {
+        self.lines.iter()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of logical lines of the rich text.
+    //---------------------------------------------------------------------------------------------
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a logical line by id.
+    //---------------------------------------------------------------------------------------------
+    pub fn line(&self, id: RichTextLineId) -> Option<&RichTextLine> {
+        self.lines.iter().find(|line| line.id == id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a logical line by id mutably (e.g. to update its tags).
+    //---------------------------------------------------------------------------------------------
+    pub fn line_mut(&mut self, id: RichTextLineId) -> Option<&mut RichTextLine> {
+        self.lines.iter_mut().find(|line| line.id == id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the range of wrapped rows covered by a logical line.
+    //---------------------------------------------------------------------------------------------
+    pub fn line_rows(&self, id: RichTextLineId) -> Option<(i32, i32)> {
+        let index = self.lines.iter().position(|line| line.id == id)?;
+        let end = self.lines.get(index + 1).map_or(self.newline_indices.len(), |l| l.first_row);
+
+        Some((self.lines[index].first_row as i32, end as i32))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the logical line covering a wrapped row, if any.
+    // (add lines_up() to find the line at a row of the visible area)
+    //---------------------------------------------------------------------------------------------
+    pub fn line_at_row(&self, row: i32) -> Option<RichTextLineId> {
+        if row < 0 || row >= self.newline_indices.len() as i32 {
+            return None;
+        }
+
+        let index = self.lines.partition_point(|line| line.first_row as i32 <= row);
+        index.checked_sub(1).map(|index| self.lines[index].id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the wrapped text: the width of the widest line (excluding trailing
+    // spaces) and the total lines. Words longer than the width overflow it.
+    //---------------------------------------------------------------------------------------------
+    pub fn content_dimensions(&self) -> Result<ICoord> {
+        let mut width = 0;
+
+        for line in self.wrapped_text.split(NEWLINE_CHARACTER) {
+            width = cmp::max(width, Self::line_width(line)?);
+        }
+
+        // Empty text has no dimensions, rather than a single empty line.
+        if width == 0 && self.total_lines <= 1 {
+            return Ok((0, 0));
+        }
+
+        Ok((width as i32, self.total_lines))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Computes the dimensions rich text would wrap to within a max width, without drawing it.
+    // (useful for sizing panels, tooltips and dialogs to their content)
+    //---------------------------------------------------------------------------------------------
+    pub fn measure(text: &str, max_width: i32) -> Result<ICoord> {
+        if text.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let mut wrapper = Self::new((0, 0), (cmp::max(max_width, 1), 1), i32::MAX);
+        wrapper.append(text)?;
+        wrapper.content_dimensions()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to find the width in cells of a line of wrapped text, excluding its tags
+    // and trailing spaces.
+    //---------------------------------------------------------------------------------------------
+    fn line_width(line: &str) -> Result<usize> {
+        if line.is_empty() {
+            return Ok(0);
+        }
+
+        let parsed = parse_rich_text(line).context("Failed to parse wrapped rich text line.")?;
+        let mut text = String::new();
+
+        for value in parsed.into_iter() {
+            if let RichTextValue::Text(t) = value {
+                text.push_str(&t);
+            }
+        }
+
+        Ok(RichTextWriter::display_width(text.trim_end()))
+    }
+
     //---------------------------------------------------------------------------------------------
     // When handling hints we want to...
     // 1. Create the inline tag string to append to the wrapped text.
@@ -345,7 +525,9 @@ impl RichTextWrapper {
         debug_assert!(!word.is_empty(), "Parsed an empty word.");
 
         // If there is not enough room to append the word on this line, break to the next line.
-        if self.last_line_length + word.chars().count() > self.width() as usize {
+        let word_width = RichTextWriter::display_width(word);
+
+        if self.last_line_length + word_width > self.width() as usize {
             // Do not append spaces that would cause a line break.
             if is_space {
                 return;
@@ -356,7 +538,7 @@ impl RichTextWrapper {
 
         // Append the word and update the last line length.
         self.wrapped_text.push_str(word);
-        self.last_line_length += word.chars().count();
+        self.last_line_length += word_width;
     }
 
     //---------------------------------------------------------------------------------------------
@@ -428,16 +610,22 @@ impl RichTextWrapper {
             }
 
             self.wrapped_text.truncate(size);
+
+            // Drop the logical lines that were truncated entirely and shift the rest.
+            while self.lines.len() > 1 && self.lines[1].first_row <= index {
+                let _ = self.lines.pop_front();
+            }
+
+            for line in self.lines.iter_mut() {
+                line.first_row = line.first_row.saturating_sub(index);
+            }
         }
     }
 
     //---------------------------------------------------------------------------------------------
-    // Append rich text to the rich text wrapper.
+    // Helper function to wrap parsed rich text onto the end of the wrapped text.
     //---------------------------------------------------------------------------------------------
-    pub fn append(&mut self, text: &str) -> Result<()> {
-        // Parse the rich text.
-        let parsed_values = parse_rich_text(text).context("Failed to parse rich text string.")?;
-
+    fn wrap_parsed(&mut self, parsed_values: Vec<RichTextValue>) {
         // Iterate over and handle each of the parsed values.
         for value in parsed_values.into_iter() {
             match value {
@@ -455,6 +643,90 @@ impl RichTextWrapper {
                 }
             }
         }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to create a new logical line starting at the current row.
+    //---------------------------------------------------------------------------------------------
+    fn new_line(&mut self, segment: &str, tags: Vec<String>) -> RichTextLine {
+        let id = self.next_line_id;
+        self.next_line_id += 1;
+
+        RichTextLine {
+            id,
+            segments: vec![segment.into()],
+            first_row: self.newline_indices.len() - 1,
+            tags,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to reset the wrapped text (but not the logical lines).
+    //---------------------------------------------------------------------------------------------
+    fn reset_wrapped_text(&mut self) {
+        self.wrapped_text.clear();
+        self.format_state.clear();
+        self.newline_indices.clear();
+        self.newline_indices.push(0);
+        self.prepend_space = false;
+        self.last_line_length = 0;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to rewrap all of the logical lines (after lines were inserted or removed),
+    // keeping the scroll position where possible.
+    //---------------------------------------------------------------------------------------------
+    fn rewrap(&mut self) -> Result<()> {
+        let lines = std::mem::take(&mut self.lines);
+        self.reset_wrapped_text();
+
+        for (i, mut line) in lines.into_iter().enumerate() {
+            // Each logical line begins on a new row.
+            if i > 0 {
+                self.handle_newline();
+                self.prepend_space = false;
+            }
+
+            line.first_row = self.newline_indices.len() - 1;
+
+            for segment in line.segments.iter() {
+                let parsed_values =
+                    parse_rich_text(segment).context("Failed to parse rich text string.")?;
+                self.wrap_parsed(parsed_values);
+            }
+
+            self.lines.push_back(line);
+        }
+
+        self.truncate_text();
+
+        // Keep the current line within the rewrapped text.
+        let max_line = cmp::max(self.newline_indices.len() as i32 - self.height(), 0);
+        self.current_line = cmp::min(self.current_line, max_line as usize);
+
+        self.refresh_visible_area_metrics();
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Append rich text to the rich text wrapper.
+    //---------------------------------------------------------------------------------------------
+    // (the text continues the last logical line, see push_line to begin a new one)
+    pub fn append(&mut self, text: &str) -> Result<()> {
+        // Parse the rich text.
+        let parsed_values = parse_rich_text(text).context("Failed to parse rich text string.")?;
+
+        // Record the text on the last logical line.
+        match self.lines.back_mut() {
+            Some(line) => line.segments.push(text.into()),
+            None => {
+                let line = self.new_line(text, Vec::new());
+                self.lines.push_back(line);
+            }
+        }
+
+        self.wrap_parsed(parsed_values);
 
         // Ensure the wrapped text is not longer than the max lines.
         self.truncate_text();
@@ -465,6 +737,79 @@ impl RichTextWrapper {
         Ok(())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Appends rich text as a new logical line beginning on a new row, returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_line(&mut self, text: &str, tags: Vec<String>) -> Result<RichTextLineId> {
+        // Parse the rich text.
+        let parsed_values = parse_rich_text(text).context("Failed to parse rich text string.")?;
+
+        if !self.lines.is_empty() {
+            self.handle_newline();
+            self.prepend_space = false;
+        }
+
+        let line = self.new_line(text, tags);
+        let id = line.id;
+        self.lines.push_back(line);
+        self.wrap_parsed(parsed_values);
+
+        // Ensure the wrapped text is not longer than the max lines.
+        self.truncate_text();
+
+        // Always update visible area metrics.
+        self.refresh_visible_area_metrics();
+
+        Ok(id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Inserts rich text as a new logical line before the line at an index, returning its id.
+    // (an index past the last line appends the line)
+    //---------------------------------------------------------------------------------------------
+    pub fn insert_line(
+        &mut self,
+        index: usize,
+        text: &str,
+        tags: Vec<String>,
+    ) -> Result<RichTextLineId> {
+        // Validate the rich text before inserting it.
+        let _ = parse_rich_text(text).context("Failed to parse rich text string.")?;
+
+        let line = self.new_line(text, tags);
+        let id = line.id;
+        self.lines.insert(cmp::min(index, self.lines.len()), line);
+        self.rewrap()?;
+
+        Ok(id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes a logical line by id, returning whether it was found.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove_line(&mut self, id: RichTextLineId) -> Result<bool> {
+        match self.lines.iter().position(|line| line.id == id) {
+            Some(index) => {
+                let _ = self.lines.remove(index);
+                self.rewrap()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes up to a # of the oldest logical lines.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove_oldest(&mut self, count: usize) -> Result<()> {
+        if count == 0 || self.lines.is_empty() {
+            return Ok(());
+        }
+
+        let _ = self.lines.drain(..cmp::min(count, self.lines.len()));
+        self.rewrap()
+    }
+
     //---------------------------------------------------------------------------------------------
     // Scrolls the visible area up by a # of lines.
     //---------------------------------------------------------------------------------------------
@@ -521,10 +866,8 @@ impl RichTextWrapper {
     //---------------------------------------------------------------------------------------------
     pub fn clear(&mut self) {
         self.current_line = 0;
-        self.wrapped_text.clear();
-        self.format_state.clear();
-        self.newline_indices.clear();
-        self.newline_indices.push(0);
+        self.lines.clear();
+        self.reset_wrapped_text();
     }
 
     //---------------------------------------------------------------------------------------------
@@ -2,6 +2,8 @@
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use anyhow::{anyhow, Context, Result};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
@@ -9,10 +11,25 @@ use anyhow::{anyhow, Context, Result};
 use fvr_engine_core::prelude::*;
 use fvr_engine_parser::prelude::*;
 
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::widgets::rich_text_wrapper::*;
+
 //-------------------------------------------------------------------------------------------------
 // Constants.
 //-------------------------------------------------------------------------------------------------
-const NEWLINE_CHAR: char = '\n';
+const NEWLINE_STR: &str = "\n";
+const CRLF_STR: &str = "\r\n";
+
+// Glyph written to the trailing cells of wide (double cell) characters.
+const WIDE_TRAILING_GLYPH: char = ' ';
+
+// Glyph written in place of wide characters too wide to fit on any line.
+const WIDE_REPLACEMENT_GLYPH: char = '?';
+
+// Appended to truncated text (the font atlases lack a single cell ellipsis).
+const ELLIPSIS: &str = "...";
 
 //-------------------------------------------------------------------------------------------------
 // Helper struct for holding rich text format settings.
@@ -31,6 +48,44 @@ pub struct RichTextFormatSettings {
     pub outline_opacity: Option<f32>,
 }
 
+impl RichTextFormatSettings {
+    //---------------------------------------------------------------------------------------------
+    // Applies the present settings to a tile.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, tile: &mut Tile) {
+        if let Some(layout) = self.layout {
+            tile.layout = layout;
+        }
+        if let Some(style) = self.style {
+            tile.style = style;
+        }
+        if let Some(size) = self.size {
+            tile.size = size;
+        }
+        if let Some(outlined) = self.outlined {
+            tile.outlined = outlined;
+        }
+        if let Some(background_color) = self.background_color {
+            tile.background_color = background_color;
+        }
+        if let Some(foreground_color) = self.foreground_color {
+            tile.foreground_color = foreground_color;
+        }
+        if let Some(outline_color) = self.outline_color {
+            tile.outline_color = outline_color;
+        }
+        if let Some(background_opacity) = self.background_opacity {
+            tile.background_opacity = background_opacity;
+        }
+        if let Some(foreground_opacity) = self.foreground_opacity {
+            tile.foreground_opacity = foreground_opacity;
+        }
+        if let Some(outline_opacity) = self.outline_opacity {
+            tile.outline_opacity = outline_opacity;
+        }
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // RichTextWriter exposes a static API for "writing" rich text into types that impl Map2D<Tile>.
 //-------------------------------------------------------------------------------------------------
@@ -38,7 +93,115 @@ pub struct RichTextWriter;
 
 impl RichTextWriter {
     //---------------------------------------------------------------------------------------------
-    // Find the len of a rich text string, excluding formatting tags.
+    // Returns the glyph and width in cells of a grapheme cluster, or None if it is not visible.
+    // (a tile holds a single char, so any combining marks are dropped)
+    //---------------------------------------------------------------------------------------------
+    pub fn grapheme_cell(grapheme: &str) -> Option<(char, i32)> {
+        let glyph = grapheme.chars().next()?;
+
+        match grapheme.width() {
+            0 => None,
+            1 => Some((glyph, 1)),
+            _ => Some((glyph, 2)),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Find the width in cells of plain text (wide characters count as two cells).
+    //---------------------------------------------------------------------------------------------
+    pub fn display_width(text: &str) -> usize {
+        text.graphemes(true).filter_map(Self::grapheme_cell).map(|(_, width)| width as usize).sum()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Truncates plain text to fit within a width in cells, ending with an ellipsis if truncated.
+    // (the ellipsis is dropped if the width is too narrow to fit it and any text)
+    //---------------------------------------------------------------------------------------------
+    pub fn truncate_plain(text: &str, width: usize) -> String {
+        if Self::display_width(text) <= width {
+            return text.into();
+        }
+
+        let ellipsis = if width > ELLIPSIS.len() { ELLIPSIS } else { "" };
+        let limit = width - ellipsis.len();
+        let mut truncated = String::new();
+        let mut used = 0;
+
+        for grapheme in text.graphemes(true) {
+            let grapheme_width =
+                Self::grapheme_cell(grapheme).map(|(_, width)| width as usize).unwrap_or(0);
+
+            if used + grapheme_width > limit {
+                break;
+            }
+
+            truncated.push_str(grapheme);
+            used += grapheme_width;
+        }
+
+        truncated.push_str(ellipsis);
+        truncated
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Writes the grapheme clusters of text, wrapping at the map2d's width and applying a format
+    // to each written tile. Wide characters are written across two cells.
+    // (wide characters that can't fit on a line are replaced, and text past the bottom is dropped)
+    //---------------------------------------------------------------------------------------------
+    fn write_graphemes<M, F>(
+        map: &mut M,
+        origin_x: i32,
+        cursor: &mut ICoord,
+        text: &str,
+        mut format: F,
+    ) where
+        M: Map2d<Tile>,
+        F: FnMut(&mut Tile),
+    {
+        for grapheme in text.graphemes(true) {
+            // Handle newlines.
+            if grapheme == NEWLINE_STR || grapheme == CRLF_STR {
+                *cursor = (origin_x, cursor.1 + 1);
+                continue;
+            }
+
+            // Skip graphemes that would not be visible.
+            let (mut glyph, mut width) = match Self::grapheme_cell(grapheme) {
+                Some(cell) => cell,
+                None => continue,
+            };
+
+            // Replace wide characters that would not fit even on a new line.
+            if origin_x + width > map.width() {
+                glyph = WIDE_REPLACEMENT_GLYPH;
+                width = 1;
+            }
+
+            // Move to the next line if necessary.
+            if cursor.0 + width > map.width() {
+                *cursor = (origin_x, cursor.1 + 1);
+            }
+
+            // Skip graphemes that fall outside the map2d.
+            if !map.in_bounds(*cursor) || !map.in_bounds((cursor.0 + width - 1, cursor.1)) {
+                cursor.0 += width;
+                continue;
+            }
+
+            // Update the tiles.
+            for i in 0..width {
+                let tile = map.get_xy_mut(*cursor);
+                tile.glyph = if i == 0 { glyph } else { WIDE_TRAILING_GLYPH };
+                format(tile);
+
+                // Increment the columns.
+                cursor.0 += 1;
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Find the len of a rich text string in cells, excluding formatting tags.
     //---------------------------------------------------------------------------------------------
     pub fn stripped_len(text: &str) -> Result<usize> {
         let mut len = 0;
@@ -51,13 +214,21 @@ impl RichTextWriter {
             match value {
                 RichTextValue::FormatHint { .. } => {}
                 RichTextValue::Newline => len += 1,
-                RichTextValue::Text(t) => len += t.chars().count(),
+                RichTextValue::Text(t) => len += Self::display_width(&t),
             }
         }
 
         Ok(len)
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Computes the dimensions rich text would wrap to within a max width, without writing tiles.
+    // (wraps on words like RichTextWrapper, see RichTextWrapper::measure)
+    //---------------------------------------------------------------------------------------------
+    pub fn measure(text: &str, max_width: i32) -> Result<ICoord> {
+        RichTextWrapper::measure(text, max_width)
+    }
+
     //---------------------------------------------------------------------------------------------
     // Write rich text, wrapping at the map2d's width.
     //---------------------------------------------------------------------------------------------
@@ -66,7 +237,7 @@ impl RichTextWriter {
         M: Map2d<Tile>,
     {
         // Declare mutable coords and options used to store current format state.
-        let (mut x, mut y) = xy;
+        let mut cursor = xy;
 
         let mut layout: Option<TileLayout> = None;
         let mut style: Option<TileStyle> = None;
@@ -75,6 +246,8 @@ impl RichTextWriter {
         let mut foreground_color: Option<TileColor> = None;
         let mut background_color: Option<TileColor> = None;
         let mut outline_color: Option<TileColor> = None;
+        let mut outline_thickness: Option<f32> = None;
+        let mut outline_glow: Option<bool> = None;
 
         // Parse the rich text.
         let parsed = parse_rich_text(text).context("Failed to parse rich text string.")?;
@@ -117,28 +290,30 @@ impl RichTextWriter {
                         let v = PaletteColor::from_format_hint(&value)?;
                         outline_color = Some(v.into());
                     }
+                    RichTextHintType::OutlineThickness => {
+                        let v = value
+                            .parse::<f32>()
+                            .context("Failed to parse outline thickness value.")?;
+                        outline_thickness = Some(v.clamp(0.0, MAX_OUTLINE_THICKNESS));
+                    }
+                    RichTextHintType::OutlineGlow => {
+                        let v = match value.as_str() {
+                            "t" => Ok(true),
+                            "f" => Ok(false),
+                            _ => Err(anyhow!("Failed to parse outline glow value.")),
+                        }?;
+                        outline_glow = Some(v);
+                    }
                 },
                 // For newlines, reset the x coord and move to the next line.
                 //---------------------------------------------------------------------------------
                 RichTextValue::Newline => {
-                    x = xy.0;
-                    y += 1;
+                    cursor = (xy.0, cursor.1 + 1);
                 }
-                // For text, iter the chars and update the tiles with the format state.
+                // For text, iter the graphemes and update the tiles with the format state.
                 //---------------------------------------------------------------------------------
                 RichTextValue::Text(text) => {
-                    for glyph in text.chars() {
-                        // Move to the next line if necessary.
-                        if x >= map.width() {
-                            x = xy.0;
-                            y += 1;
-                        }
-
-                        // Update the tile.
-                        let tile = map.get_xy_mut((x, y));
-
-                        tile.glyph = glyph;
-
+                    Self::write_graphemes(map, xy.0, &mut cursor, &text, |tile| {
                         if let Some(v) = layout {
                             tile.layout = v;
                         }
@@ -160,10 +335,13 @@ impl RichTextWriter {
                         if let Some(v) = outline_color {
                             tile.outline_color = v;
                         }
-
-                        // Increment the columns.
-                        x += 1;
-                    }
+                        if let Some(v) = outline_thickness {
+                            tile.outline_thickness = v;
+                        }
+                        if let Some(v) = outline_glow {
+                            tile.outline_glow = v;
+                        }
+                    });
                 }
             }
         }
@@ -178,31 +356,8 @@ impl RichTextWriter {
     where
         M: Map2d<Tile>,
     {
-        // Declare mutable coords and options used to store current format state.
-        let (mut x, mut y) = xy;
-
-        // Iterate and handle the glyphs.
-        for glyph in text.chars() {
-            // Handle newline characters.
-            if glyph == NEWLINE_CHAR {
-                x = xy.0;
-                y += 1;
-                continue;
-            }
-
-            // Move to the next line if necessary.
-            if x >= map.width() {
-                x = xy.0;
-                y += 1;
-            }
-
-            // Update the tile.
-            let tile = map.get_xy_mut((x, y));
-            tile.glyph = glyph;
-
-            // Increment the columns.
-            x += 1;
-        }
+        let mut cursor = xy;
+        Self::write_graphemes(map, xy.0, &mut cursor, text, |_| {});
     }
 
     //---------------------------------------------------------------------------------------------
@@ -226,29 +381,8 @@ impl RichTextWriter {
     ) where
         M: Map2d<Tile>,
     {
-        // Declare mutable coords and options used to store current format state.
-        let (mut x, mut y) = xy;
-
-        // Iterate and handle the glyphs.
-        for glyph in text.chars() {
-            // Handle newline characters.
-            if glyph == NEWLINE_CHAR {
-                x = xy.0;
-                y += 1;
-                continue;
-            }
-
-            // Move to the next line if necessary.
-            if x >= map.width() {
-                x = xy.0;
-                y += 1;
-            }
-
-            // Update the tile.
-            let tile = map.get_xy_mut((x, y));
-
-            tile.glyph = glyph;
-
+        let mut cursor = xy;
+        Self::write_graphemes(map, xy.0, &mut cursor, text, |tile| {
             if let Some(layout) = layout {
                 tile.layout = layout;
             }
@@ -279,10 +413,7 @@ impl RichTextWriter {
             if let Some(outline_opacity) = outline_opacity {
                 tile.outline_opacity = outline_opacity;
             }
-
-            // Increment the columns.
-            x += 1;
-        }
+        });
     }
 
     //---------------------------------------------------------------------------------------------
@@ -296,61 +427,35 @@ impl RichTextWriter {
     ) where
         M: Map2d<Tile>,
     {
-        // Declare mutable coords and options used to store current format state.
-        let (mut x, mut y) = xy;
-
-        // Iterate and handle the glyphs.
-        for glyph in text.chars() {
-            // Handle newline characters.
-            if glyph == NEWLINE_CHAR {
-                x = xy.0;
-                y += 1;
-                continue;
-            }
-
-            // Move to the next line if necessary.
-            if x >= map.width() {
-                x = xy.0;
-                y += 1;
-            }
-
-            // Update the tile.
-            let tile = map.get_xy_mut((x, y));
-            tile.glyph = glyph;
+        let mut cursor = xy;
+        Self::write_graphemes(map, xy.0, &mut cursor, text, |tile| settings.apply(tile));
+    }
+}
 
-            if let Some(layout) = settings.layout {
-                tile.layout = layout;
-            }
-            if let Some(style) = settings.style {
-                tile.style = style;
-            }
-            if let Some(size) = settings.size {
-                tile.size = size;
-            }
-            if let Some(outlined) = settings.outlined {
-                tile.outlined = outlined;
-            }
-            if let Some(background_color) = settings.background_color {
-                tile.background_color = background_color;
-            }
-            if let Some(foreground_color) = settings.foreground_color {
-                tile.foreground_color = foreground_color;
-            }
-            if let Some(outline_color) = settings.outline_color {
-                tile.outline_color = outline_color;
-            }
-            if let Some(background_opacity) = settings.background_opacity {
-                tile.background_opacity = background_opacity;
-            }
-            if let Some(foreground_opacity) = settings.foreground_opacity {
-                tile.foreground_opacity = foreground_opacity;
-            }
-            if let Some(outline_opacity) = settings.outline_opacity {
-                tile.outline_opacity = outline_opacity;
-            }
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
 
-            // Increment the columns.
-            x += 1;
-        }
-    }
+#[test]
+fn test_rich_text_writer_wide_graphemes() {
+    // Wide characters wrap to the next line instead of splitting across lines.
+    let mut map = GridMap::<Tile>::new((3, 2));
+    RichTextWriter::write_plain(&mut map, (0, 0), "a\u{4e16}\u{754c}");
+    assert_eq!(map.get_xy((0, 0)).glyph, 'a');
+    assert_eq!(map.get_xy((1, 0)).glyph, '\u{4e16}');
+    assert_eq!(map.get_xy((2, 0)).glyph, WIDE_TRAILING_GLYPH);
+    assert_eq!(map.get_xy((0, 1)).glyph, '\u{754c}');
+    assert_eq!(map.get_xy((1, 1)).glyph, WIDE_TRAILING_GLYPH);
+
+    // Wide characters are replaced on a map only one cell wide.
+    let mut map = GridMap::<Tile>::new((1, 2));
+    RichTextWriter::write_plain(&mut map, (0, 0), "\u{4e16}\u{754c}\u{4e16}");
+    assert_eq!(map.get_xy((0, 0)).glyph, WIDE_REPLACEMENT_GLYPH);
+    assert_eq!(map.get_xy((0, 1)).glyph, WIDE_REPLACEMENT_GLYPH);
+
+    // Wide characters are replaced when written at the last column.
+    let mut map = GridMap::<Tile>::new((3, 2));
+    RichTextWriter::write_plain(&mut map, (2, 0), "\u{4e16}\u{754c}");
+    assert_eq!(map.get_xy((2, 0)).glyph, WIDE_REPLACEMENT_GLYPH);
+    assert_eq!(map.get_xy((2, 1)).glyph, WIDE_REPLACEMENT_GLYPH);
 }
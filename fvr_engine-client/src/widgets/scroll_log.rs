@@ -27,6 +27,8 @@ pub enum ScrollLogAction {
     Focused,
     // The mouse is over an interactable area of the scroll log.
     Interactable,
+    // An anchored line was clicked (see ANCHOR_TAG_PREFIX).
+    AnchorClicked(RichTextLineId),
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -160,7 +162,7 @@ impl ScrollLog {
     //---------------------------------------------------------------------------------------------
     pub fn scroll_up(&mut self, lines: i32) {
         self.wrapper.scroll_up(lines);
-        self.scrollbar.set_current_line(self.wrapper.lines_up());
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
         self.dirty = true;
     }
 
@@ -169,7 +171,7 @@ impl ScrollLog {
     //---------------------------------------------------------------------------------------------
     pub fn scroll_down(&mut self, lines: i32) {
         self.wrapper.scroll_down(lines);
-        self.scrollbar.set_current_line(self.wrapper.lines_up());
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
         self.dirty = true;
     }
 
@@ -178,7 +180,7 @@ impl ScrollLog {
     //---------------------------------------------------------------------------------------------
     pub fn scroll_to_top(&mut self) {
         self.wrapper.scroll_to_top();
-        self.scrollbar.set_current_line(self.wrapper.lines_up());
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
         self.dirty = true;
     }
 
@@ -187,7 +189,7 @@ impl ScrollLog {
     //---------------------------------------------------------------------------------------------
     pub fn scroll_to_bottom(&mut self) {
         self.wrapper.scroll_to_bottom();
-        self.scrollbar.set_current_line(self.wrapper.lines_up());
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
         self.dirty = true;
     }
 
@@ -196,11 +198,62 @@ impl ScrollLog {
     //---------------------------------------------------------------------------------------------
     pub fn append(&mut self, text: &str) -> Result<()> {
         self.wrapper.append(text)?;
-        self.scrollbar.set_content_height(self.wrapper.total_lines());
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
         self.dirty = true;
         Ok(())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Append rich text to the scroll log as a new logical line, returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_line(&mut self, text: &str, tags: Vec<String>) -> Result<RichTextLineId> {
+        let id = self.wrapper.push_line(text, tags)?;
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
+        self.dirty = true;
+        Ok(id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes a logical line from the scroll log by id, returning whether it was found.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove_line(&mut self, id: RichTextLineId) -> Result<bool> {
+        let removed = self.wrapper.remove_line(id)?;
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
+        self.dirty = true;
+        Ok(removed)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes up to a # of the oldest logical lines from the scroll log.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove_oldest(&mut self, count: usize) -> Result<()> {
+        self.wrapper.remove_oldest(count)?;
+        self.scrollbar.sync_with_wrapper(&self.wrapper);
+        self.dirty = true;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the rich text wrapper of the scroll log (e.g. to inspect its logical lines).
+    //---------------------------------------------------------------------------------------------
+    pub fn wrapper(&self) -> &RichTextWrapper {
+        &self.wrapper
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the line shown at a coord, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn line_at(&self, xy: ICoord) -> Option<RichTextLineId> {
+        let origin = self.wrapper.origin();
+        let (width, height) = self.wrapper.dimensions();
+
+        if !Rect::new(origin, width, height).contains(xy) {
+            return None;
+        }
+
+        self.wrapper.line_at_row(xy.1 - origin.1 + self.wrapper.lines_up())
+    }
+
     //---------------------------------------------------------------------------------------------
     // Updates the scroll log, potentially redrawing if the state changes.
     //---------------------------------------------------------------------------------------------
@@ -220,19 +273,28 @@ impl ScrollLog {
         match scrollbar_action {
             ScrollbarAction::Focused => action = ScrollLogAction::Focused,
             ScrollbarAction::Interactable => action = ScrollLogAction::Interactable,
-            ScrollbarAction::ScrollUp(lines) => {
-                self.wrapper.scroll_up(lines);
-                action = ScrollLogAction::Interactable;
-                self.dirty = true;
-            }
-            ScrollbarAction::ScrollDown(lines) => {
-                self.wrapper.scroll_down(lines);
+            ScrollbarAction::ScrollUp(_) | ScrollbarAction::ScrollDown(_) => {
+                self.scrollbar.scroll_wrapper(scrollbar_action, &mut self.wrapper);
                 action = ScrollLogAction::Interactable;
                 self.dirty = true;
             }
             _ => {}
         }
 
+        // Report clicks on anchored lines.
+        let anchor = input
+            .mouse_coord()
+            .and_then(|xy| self.line_at(xy))
+            .filter(|id| self.wrapper.line(*id).and_then(|line| line.anchor()).is_some());
+
+        if let (Some(id), ScrollLogAction::Noop) = (anchor, action) {
+            action = if input.mouse_clicked(InputMouse::Left) {
+                ScrollLogAction::AnchorClicked(id)
+            } else {
+                ScrollLogAction::Interactable
+            };
+        }
+
         // Redraw the wrapped text if necessary.
         if self.dirty {
             if self.has_overflow() {
@@ -13,6 +13,7 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 use crate::input_manager::*;
 use crate::widgets::button::*;
+use crate::widgets::rich_text_wrapper::*;
 
 //-------------------------------------------------------------------------------------------------
 // Constants
@@ -35,6 +36,9 @@ static TRACK_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 static GRIP_TILE: Tile = Tile {
     glyph: ' ',
@@ -48,6 +52,25 @@ static GRIP_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+static GRIP_DRAGGED_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Bold,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::BrightGrey.const_into(),
+    foreground_color: TileColor::TRANSPARENT,
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 static LIMIT_TOP_TILE: Tile = Tile {
     glyph: TOP_CHAR,
@@ -61,6 +84,9 @@ static LIMIT_TOP_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 static LIMIT_BOTTOM_TILE: Tile = Tile {
     glyph: BOTTOM_CHAR,
@@ -74,6 +100,9 @@ static LIMIT_BOTTOM_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 //-------------------------------------------------------------------------------------------------
@@ -95,6 +124,8 @@ pub enum ScrollbarAction {
 
 //-------------------------------------------------------------------------------------------------
 // Scrollbar represents a scrollable, visual indicator of current position in vertical content.
+// The grip is sized by the ratio of visible to total content, clicking the track pages up/down
+// and dragging the grip scrolls continuously.
 //-------------------------------------------------------------------------------------------------
 pub struct Scrollbar {
     // Origin of the scrollbar.
@@ -105,10 +136,10 @@ pub struct Scrollbar {
     content_height: i32,
     // Index of the current line at the top of the visible area.
     current_line: i32,
-    // # of content lines represented by a segment of the track.
-    track_ratio: i32,
-    // Size of the grip.
+    // Size of the grip (0 if all content is visible).
     grip_size: i32,
+    // Offset of the mouse from the top of the grip while the grip is dragged.
+    drag_offset: Option<i32>,
     // Button at the top of the track.
     top_button: Button,
     // Button at the bottom of the track.
@@ -126,22 +157,22 @@ impl Scrollbar {
         self.top_button.origin = self.origin;
         self.bottom_button.origin = (self.origin.0, self.origin.1 + self.height - 1);
 
-        // Find how many lines the grip represents, taking into account the top/bottom buttons.
-        self.track_ratio = self.content_height / self.height;
+        // Keep the current line within the content.
+        self.current_line = self.current_line.clamp(0, self.max_line());
 
-        // If the content height is shorter than the height, don't draw the grip or buttons.
-        if self.track_ratio == 0 {
+        // If all content is visible, don't draw the grip.
+        if self.content_height <= self.height {
+            self.grip_size = 0;
+            self.drag_offset = None;
             return;
         }
 
-        // Set the grip size to a % of the track height equal to the ratio of content/height.
+        // Set the grip size to a % of the track height equal to the ratio of visible/content.
+        let track_height = self.track_height();
         self.grip_size =
-            ((self.height - 2) as f32 * (self.height as f32 / self.content_height as f32)) as i32;
-
-        // The grip should always have a length of at least 1.
-        if self.grip_size == 0 {
-            self.grip_size = 1;
-        }
+            ((track_height as f32 * (self.height as f32 / self.content_height as f32)).round()
+                as i32)
+                .clamp(1, track_height);
     }
 
     //---------------------------------------------------------------------------------------------
@@ -159,8 +190,8 @@ impl Scrollbar {
             height,
             content_height,
             current_line: 0,
-            track_ratio: 0,
             grip_size: 0,
+            drag_offset: None,
             top_button,
             bottom_button,
             dirty: true,
@@ -184,11 +215,33 @@ impl Scrollbar {
         self.height
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Returns the height of the content that the scrollbar represents.
+    //---------------------------------------------------------------------------------------------
+    pub fn content_height(&self) -> i32 {
+        self.content_height
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the current line at the top of the visible area.
+    //---------------------------------------------------------------------------------------------
+    pub fn current_line(&self) -> i32 {
+        self.current_line
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the grip is being dragged.
+    //---------------------------------------------------------------------------------------------
+    pub fn dragging(&self) -> bool {
+        self.drag_offset.is_some()
+    }
+
     //---------------------------------------------------------------------------------------------
     // Update the origin of the scrollbar.
     //---------------------------------------------------------------------------------------------
     pub fn set_origin(&mut self, origin: ICoord) {
         self.origin = origin;
+        self.refresh();
         self.dirty = true;
     }
 
@@ -215,11 +268,105 @@ impl Scrollbar {
     //---------------------------------------------------------------------------------------------
     // Update the current line of the scrollbar.
     //---------------------------------------------------------------------------------------------
-    pub fn set_current_line(&mut self, current_lint: i32) {
-        debug_assert!(current_lint < self.content_height - self.height);
+    pub fn set_current_line(&mut self, current_line: i32) {
+        debug_assert!(current_line <= self.max_line());
+
+        self.current_line = current_line.clamp(0, self.max_line());
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the content height and current line from the scrolled content, only marking the
+    // scrollbar dirty if either changed. Call whenever the content scrolls by other means.
+    //---------------------------------------------------------------------------------------------
+    pub fn sync(&mut self, content_height: i32, current_line: i32) {
+        if content_height != self.content_height {
+            self.set_content_height(content_height);
+        }
+
+        if current_line.clamp(0, self.max_line()) != self.current_line {
+            self.set_current_line(current_line.clamp(0, self.max_line()));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the content height and current line from a rich text wrapper.
+    //---------------------------------------------------------------------------------------------
+    pub fn sync_with_wrapper(&mut self, wrapper: &RichTextWrapper) {
+        self.sync(wrapper.total_lines(), wrapper.lines_up());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies a scroll action of the scrollbar to a rich text wrapper, then syncs with it.
+    //---------------------------------------------------------------------------------------------
+    pub fn scroll_wrapper(&mut self, action: ScrollbarAction, wrapper: &mut RichTextWrapper) {
+        match action {
+            ScrollbarAction::ScrollUp(lines) => wrapper.scroll_up(lines),
+            ScrollbarAction::ScrollDown(lines) => wrapper.scroll_down(lines),
+            _ => return,
+        }
+
+        self.sync_with_wrapper(wrapper);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the index of the last line that can be at the top.
+    //---------------------------------------------------------------------------------------------
+    fn max_line(&self) -> i32 {
+        cmp::max(self.content_height - self.height, 0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the height of the track between the buttons.
+    //---------------------------------------------------------------------------------------------
+    fn track_height(&self) -> i32 {
+        self.height - 2
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the offset of the grip from the top of the track.
+    //---------------------------------------------------------------------------------------------
+    fn grip_offset(&self) -> i32 {
+        let travel = self.track_height() - self.grip_size;
+
+        if self.max_line() == 0 || travel <= 0 {
+            return 0;
+        }
+
+        (travel as f32 * (self.current_line as f32 / self.max_line() as f32)).round() as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the line represented by an offset of the grip.
+    //---------------------------------------------------------------------------------------------
+    fn line_at_grip_offset(&self, offset: i32) -> i32 {
+        let travel = self.track_height() - self.grip_size;
+
+        if travel <= 0 {
+            return 0;
+        }
+
+        (self.max_line() as f32 * (offset.clamp(0, travel) as f32 / travel as f32)).round() as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to scroll to a line, returning the resulting action.
+    //---------------------------------------------------------------------------------------------
+    fn scroll_to(&mut self, line: i32) -> ScrollbarAction {
+        let lines = line.clamp(0, self.max_line()) - self.current_line;
+
+        if lines == 0 {
+            return ScrollbarAction::Noop;
+        }
 
-        self.current_line = current_lint;
+        self.current_line += lines;
         self.dirty = true;
+
+        if lines < 0 {
+            ScrollbarAction::ScrollUp(-lines)
+        } else {
+            ScrollbarAction::ScrollDown(lines)
+        }
     }
 
     //---------------------------------------------------------------------------------------------
@@ -235,35 +382,16 @@ impl Scrollbar {
         }
 
         // If all content is visible, do not draw the grip.
-        if self.track_ratio == 0 {
+        if self.grip_size == 0 {
             return;
         }
 
-        // Calculate the grip offset.
-        let mut grip_offset;
-        let track_height = self.height - 2;
+        // Draw the grip, highlighted while dragged.
+        let grip_tile = if self.dragging() { &GRIP_DRAGGED_TILE } else { &GRIP_TILE };
+        let grip_top = self.origin.1 + 1 + self.grip_offset();
 
-        if self.current_line + self.height == self.content_height {
-            // Ensure grip reaches the end of the track if the end of the content is visible.
-            grip_offset = track_height - self.grip_size;
-        } else {
-            // Set grip offset to a % of the track height equal to the current line/content ratio.
-            grip_offset = (track_height as f32
-                * (self.current_line as f32 / self.content_height as f32))
-                as i32;
-
-            // Ensure grip does not cover bottom button in instances where the content height is
-            // equal to a multiple of the height.
-            if self.grip_size + grip_offset > self.origin.1 + 1 + track_height {
-                grip_offset -= 1;
-            }
-        }
-
-        // Draw the grip.
-        for y in
-            (self.origin.1 + 1 + grip_offset)..(self.origin.1 + 1 + grip_offset + self.grip_size)
-        {
-            *map.get_xy_mut((self.origin.0, y)) = GRIP_TILE;
+        for y in grip_top..(grip_top + self.grip_size) {
+            *map.get_xy_mut((self.origin.0, y)) = *grip_tile;
         }
     }
 
@@ -316,6 +444,66 @@ impl Scrollbar {
         (top_action, bottom_action)
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Helper function for handling clicks on the track and dragging the grip.
+    //---------------------------------------------------------------------------------------------
+    fn update_track(&mut self, input: &InputManager) -> ScrollbarAction {
+        // Continue dragging the grip while the mouse is pressed, wherever the mouse is.
+        if let Some(drag_offset) = self.drag_offset {
+            if !input.mouse_pressed(InputMouse::Left) {
+                self.drag_offset = None;
+                self.dirty = true;
+                return ScrollbarAction::Focused;
+            }
+
+            return match input.mouse_coord() {
+                Some((_, y)) => {
+                    let grip_offset = y - self.origin.1 - 1 - drag_offset;
+
+                    match self.scroll_to(self.line_at_grip_offset(grip_offset)) {
+                        ScrollbarAction::Noop => ScrollbarAction::Focused,
+                        action => action,
+                    }
+                }
+                None => ScrollbarAction::Focused,
+            };
+        }
+
+        let coord = match input.mouse_coord() {
+            Some(coord) if self.contains(&coord) => coord,
+            _ => return ScrollbarAction::Noop,
+        };
+
+        // Ignore clicks on the buttons or when all content is visible.
+        let track_y = coord.1 - self.origin.1 - 1;
+
+        if !input.mouse_clicked(InputMouse::Left)
+            || self.grip_size == 0
+            || track_y < 0
+            || track_y >= self.track_height()
+        {
+            return ScrollbarAction::Focused;
+        }
+
+        // Page up/down when clicking the track above/below the grip, or begin dragging the grip.
+        let grip_offset = self.grip_offset();
+
+        let action = if track_y < grip_offset {
+            self.scroll_to(self.current_line - self.height)
+        } else if track_y >= grip_offset + self.grip_size {
+            self.scroll_to(self.current_line + self.height)
+        } else {
+            self.drag_offset = Some(track_y - grip_offset);
+            self.dirty = true;
+            ScrollbarAction::Noop
+        };
+
+        match action {
+            ScrollbarAction::Noop => ScrollbarAction::Focused,
+            action => action,
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Updates the scrollbar, potentially redrawing if the state changes.
     //---------------------------------------------------------------------------------------------
@@ -323,49 +511,30 @@ impl Scrollbar {
     where
         M: Map2d<Tile>,
     {
-        let mut action = ScrollbarAction::Noop;
-
         // Update the buttons and the action.
         let (top_action, bottom_action) = self.update_buttons(input, map);
 
         // Determine the response.
-        if top_action == ButtonAction::Triggered {
+        let action = if self.dragging() {
+            // If the grip is being dragged, the buttons are ignored.
+            self.update_track(input)
+        } else if top_action == ButtonAction::Triggered {
             // If the top button was triggered, scroll up the bar.
-            let lines = cmp::min(
-                // self.track_ratio,
-                // ^ This scrolls too quickly.
-                1,
-                self.current_line,
-            );
-            action = ScrollbarAction::ScrollUp(lines);
-            self.current_line -= lines;
-            self.dirty = true;
+            self.scroll_to(self.current_line - 1)
         } else if top_action == ButtonAction::Interactable {
             // Else if the top button was consumed, update action and break early.
-            action = ScrollbarAction::Interactable;
+            ScrollbarAction::Interactable
         } else if bottom_action == ButtonAction::Triggered {
             // Else if the bottom button was triggered, scroll down the bar, ensuring the content
             // is not overscrolled.
-            let lines = cmp::min(
-                // self.track_ratio,
-                // ^ This scrolls too quickly.
-                1,
-                (self.content_height - self.height) - self.current_line,
-            );
-            action = ScrollbarAction::ScrollDown(lines);
-            self.current_line += lines;
-            self.dirty = true;
+            self.scroll_to(self.current_line + 1)
         } else if bottom_action == ButtonAction::Interactable {
             // Else if the bottom button was consumed, update action.
-            action = ScrollbarAction::Interactable;
+            ScrollbarAction::Interactable
         } else {
-            // Else check if the scrollbar contains the mouse coord and update action.
-            if let Some(coord) = input.mouse_coord() {
-                if self.contains(&coord) {
-                    action = ScrollbarAction::Focused;
-                }
-            }
-        }
+            // Else handle clicking the track and dragging the grip.
+            self.update_track(input)
+        };
 
         // Draw the scrollbar if dirty.
         if self.dirty {
@@ -0,0 +1,317 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+use crate::widgets::rich_text_writer::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Rows taken by the tab headers and the rule beneath them.
+const HEADER_ROWS: i32 = 2;
+
+// Columns of space between the tab headers.
+const TAB_GAP: i32 = 1;
+
+// Glyph of the rule beneath the tab headers.
+const RULE_CHAR: char = '─';
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Format settings for the header of the current tab.
+static CURRENT_TAB_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Bold),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::DarkGrey.const_into()),
+    foreground_color: Some(PaletteColor::Gold.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the headers of the other tabs.
+static TAB_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::Black.const_into()),
+    foreground_color: Some(PaletteColor::BrightGrey.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the header of a hovered tab.
+static HOVERED_TAB_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::Black.const_into()),
+    foreground_color: Some(PaletteColor::Yellow.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the rule beneath the tab headers.
+static RULE_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Center),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::DarkGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the response codes when updating a tab container.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabContainerAction {
+    // The tab container was not interacted with.
+    Noop,
+    // A tab header is hovered.
+    Interactable,
+    // Index of the tab that was switched to (its content should be redrawn).
+    Changed(i32),
+}
+
+//-------------------------------------------------------------------------------------------------
+// TabContainer draws a row of tab headers above a content area, only one tab of which is current.
+// Tab and shift+tab cycle the tabs, and clicking a header switches to it. The container does not
+// own the tabs' content: the owner draws the current tab's content within the content area.
+//-------------------------------------------------------------------------------------------------
+pub struct TabContainer {
+    // Origin of the tab container.
+    origin: ICoord,
+    // Dimensions of the tab container (including the headers).
+    dimensions: ICoord,
+    // Titles of the tabs.
+    tabs: Vec<String>,
+    // Index of the current tab.
+    current: usize,
+    // Index of the tab whose header is under the mouse, if any.
+    hovered: Option<usize>,
+}
+
+impl TabContainer {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new tab container, with the first tab current.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, dimensions: ICoord, tabs: Vec<String>) -> Self {
+        Self { origin, dimensions, tabs, current: 0, hovered: None }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the tab container.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the origin of the tab container. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the tab container.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.dimensions.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the height of the tab container.
+    //---------------------------------------------------------------------------------------------
+    pub fn height(&self) -> i32 {
+        self.dimensions.1
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the content area beneath the tab headers.
+    //---------------------------------------------------------------------------------------------
+    pub fn content_origin(&self) -> ICoord {
+        (self.origin.0, self.origin.1 + HEADER_ROWS)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the content area beneath the tab headers.
+    //---------------------------------------------------------------------------------------------
+    pub fn content_dimensions(&self) -> ICoord {
+        (self.width(), (self.height() - HEADER_ROWS).max(0))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the titles of the tabs.
+    //---------------------------------------------------------------------------------------------
+    pub fn tabs(&self) -> &[String] {
+        &self.tabs
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the current tab.
+    //---------------------------------------------------------------------------------------------
+    pub fn current(&self) -> i32 {
+        self.current as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Switches to a tab (out of range indices are ignored). Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_current(&mut self, index: i32) {
+        if index >= 0 && (index as usize) < self.tabs.len() {
+            self.current = index as usize;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the header text of a tab.
+    //---------------------------------------------------------------------------------------------
+    fn header_text(title: &str) -> String {
+        format!(" {} ", title)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the column spans (start, end exclusive) of the tab headers.
+    //---------------------------------------------------------------------------------------------
+    fn header_spans(&self) -> Vec<(i32, i32)> {
+        let mut x = self.origin.0;
+
+        self.tabs
+            .iter()
+            .map(|title| {
+                let start = x;
+                x += RichTextWriter::display_width(&Self::header_text(title)) as i32 + TAB_GAP;
+                (start, x - TAB_GAP)
+            })
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the index of the tab whose header is at a coord.
+    //---------------------------------------------------------------------------------------------
+    fn tab_at(&self, (x, y): ICoord) -> Option<usize> {
+        if y != self.origin.1 || x >= self.origin.0 + self.width() {
+            return None;
+        }
+
+        self.header_spans().iter().position(|(start, end)| x >= *start && x < *end)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the tab container, redrawing the headers if the state changes.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> TabContainerAction
+    where
+        M: Map2d<Tile>,
+    {
+        if self.tabs.is_empty() {
+            return TabContainerAction::Noop;
+        }
+
+        let mut action = TabContainerAction::Noop;
+        let previous = (self.current, self.hovered);
+
+        // Cycle the tabs with tab and shift+tab.
+        if input.key_just_pressed(InputKey::Tab) {
+            let count = self.tabs.len();
+
+            self.current = if input.modifier_pressed(&ModifierKey::Shift) {
+                (self.current + count - 1) % count
+            } else {
+                (self.current + 1) % count
+            };
+        }
+
+        // Switch to clicked tabs.
+        self.hovered = input.mouse_coord().and_then(|xy| self.tab_at(xy));
+
+        if let Some(hovered) = self.hovered {
+            action = TabContainerAction::Interactable;
+
+            if input.mouse_clicked(InputMouse::Left) {
+                self.current = hovered;
+            }
+        }
+
+        if (self.current, self.hovered) != previous {
+            self.redraw(map);
+        }
+
+        if self.current != previous.0 {
+            action = TabContainerAction::Changed(self.current as i32);
+        }
+
+        action
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the tab headers and the rule beneath them (the content is drawn by the owner).
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let right = self.origin.0 + self.width();
+
+        // Clear the header row.
+        let blank: String = " ".repeat(self.width().max(0) as usize);
+        RichTextWriter::write_plain_with_settings(map, self.origin, &blank, &TAB_SETTINGS);
+
+        for (i, (title, (start, _))) in self.tabs.iter().zip(self.header_spans()).enumerate() {
+            if start >= right {
+                break;
+            }
+
+            let settings = if i == self.current {
+                &CURRENT_TAB_SETTINGS
+            } else if Some(i) == self.hovered {
+                &HOVERED_TAB_SETTINGS
+            } else {
+                &TAB_SETTINGS
+            };
+
+            let text = RichTextWriter::truncate_plain(
+                &Self::header_text(title),
+                (right - start).max(0) as usize,
+            );
+            RichTextWriter::write_plain_with_settings(
+                map,
+                (start, self.origin.1),
+                &text,
+                settings,
+            );
+        }
+
+        // Draw the rule beneath the headers.
+        let rule = RULE_CHAR.to_string().repeat(self.width().max(0) as usize);
+        RichTextWriter::write_plain_with_settings(
+            map,
+            (self.origin.0, self.origin.1 + 1),
+            &rule,
+            &RULE_SETTINGS,
+        );
+    }
+}
@@ -0,0 +1,559 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+use crate::widgets::rich_text_writer::*;
+use crate::widgets::scrollbar::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns of space between the cells of a row.
+const COLUMN_GAP: i32 = 1;
+
+// Prefix of the search line drawn while filtering.
+const SEARCH_PREFIX: &str = "/";
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Format settings for the column headers.
+static HEADER_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Bold),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::White.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for a heading row.
+static HEADING_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Bold),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::Gold.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for a cell.
+static CELL_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::BrightGrey.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+// Format settings for the search line.
+static SEARCH_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: None,
+    foreground_color: Some(PaletteColor::Yellow.const_into()),
+    outline_color: None,
+    background_opacity: None,
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the response codes when updating a table.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableAction {
+    // The table was not interacted with.
+    Noop,
+    // The table consumed user input (e.g. scrolled or changed the filter).
+    Interactable,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A column of a table.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableColumn {
+    // Text of the column header.
+    pub header: String,
+    // Width of the column (the last column takes the remaining width).
+    pub width: i32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A row of a table - either cells or a heading grouping the cell rows that follow it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableRow {
+    Cells(Vec<String>),
+    Heading(String),
+}
+
+//-------------------------------------------------------------------------------------------------
+// Table manages a scrolling grid of plain text cells below a row of column headers, optionally
+// grouped under headings. Typing filters the rows to those with a cell containing the text, and
+// headings are kept while any of their rows match.
+//-------------------------------------------------------------------------------------------------
+pub struct Table {
+    // Origin of the table.
+    origin: ICoord,
+    // Dimensions of the table.
+    dimensions: ICoord,
+    // Columns of the table.
+    columns: Vec<TableColumn>,
+    // Rows of the table.
+    rows: Vec<TableRow>,
+    // Text typed to filter the rows (lowercase).
+    filter: String,
+    // Indices of the rows matching the filter.
+    filtered: Vec<usize>,
+    // Position of the first visible filtered row.
+    scroll: usize,
+    // Scrollbar shown when the filtered rows overflow the visible rows.
+    scrollbar: Option<Scrollbar>,
+    // Whether the table must be redrawn.
+    dirty: bool,
+}
+
+impl Table {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty table.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, dimensions: ICoord, columns: Vec<TableColumn>) -> Self {
+        Self {
+            origin,
+            dimensions,
+            columns,
+            rows: Vec::new(),
+            filter: String::new(),
+            filtered: Vec::new(),
+            scroll: 0,
+            scrollbar: None,
+            dirty: true,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the origin of the table. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+        self.sync_scrollbar();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.dimensions.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the height of the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn height(&self) -> i32 {
+        self.dimensions.1
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the columns of the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn columns(&self) -> &[TableColumn] {
+        &self.columns
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the rows of the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn rows(&self) -> &[TableRow] {
+        &self.rows
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes back a new row of cells.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_row(&mut self, cells: Vec<String>) {
+        self.rows.push(TableRow::Cells(cells));
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes back a new heading, grouping the rows pushed after it.
+    //---------------------------------------------------------------------------------------------
+    pub fn push_heading<S>(&mut self, text: S)
+    where
+        S: Into<String>,
+    {
+        self.rows.push(TableRow::Heading(text.into()));
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all rows, keeping the filter.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text typed to filter the rows.
+    //---------------------------------------------------------------------------------------------
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the text to filter the rows by (case insensitive).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_lowercase();
+        self.refresh_filter();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the indices of the rows matching the filter (including their headings).
+    //---------------------------------------------------------------------------------------------
+    pub fn filtered(&self) -> impl Iterator<Item = i32> + '_ {
+        self.filtered.iter().map(|i| *i as i32)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to recalculate the filtered rows.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_filter(&mut self) {
+        self.filtered.clear();
+
+        // Headings without rows are still shown when not filtering.
+        if self.filter.is_empty() {
+            self.filtered.extend(0..self.rows.len());
+        } else {
+            let mut heading = None;
+
+            for (i, row) in self.rows.iter().enumerate() {
+                match row {
+                    TableRow::Heading(_) => heading = Some(i),
+                    TableRow::Cells(cells) => {
+                        if !cells.iter().any(|cell| cell.to_lowercase().contains(&self.filter)) {
+                            continue;
+                        }
+
+                        // Include the heading of the first matching row in its group.
+                        if let Some(heading) = heading.take() {
+                            self.filtered.push(heading);
+                        }
+
+                        self.filtered.push(i);
+                    }
+                }
+            }
+        }
+
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.sync_scrollbar();
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the number of rows available below the column headers.
+    //---------------------------------------------------------------------------------------------
+    fn body_rows(&self) -> usize {
+        let rows = if self.filter.is_empty() { self.height() - 1 } else { self.height() - 2 };
+        rows.max(0) as usize
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the number of columns available for cells.
+    //---------------------------------------------------------------------------------------------
+    fn text_width(&self) -> i32 {
+        if self.scrollbar.is_some() {
+            self.width() - 1
+        } else {
+            self.width()
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return the largest scroll position.
+    //---------------------------------------------------------------------------------------------
+    fn max_scroll(&self) -> usize {
+        self.filtered.len().saturating_sub(self.body_rows())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position of the first visible row in the filtered rows.
+    //---------------------------------------------------------------------------------------------
+    pub fn scroll_position(&self) -> i32 {
+        self.scroll as i32
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Scrolls so that a position in the filtered rows is the first visible.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_scroll_position(&mut self, position: i32) {
+        self.scroll = (position.max(0) as usize).min(self.max_scroll());
+        self.sync_scrollbar();
+        self.dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to show, hide or update the scrollbar to match the scroll position.
+    //---------------------------------------------------------------------------------------------
+    fn sync_scrollbar(&mut self) {
+        let rows = self.body_rows() as i32;
+        let content_height = self.filtered.len() as i32;
+
+        // The scrollbar requires room for its buttons and at least one track segment.
+        if content_height <= rows || rows <= 2 {
+            self.scrollbar = None;
+            return;
+        }
+
+        let origin = (self.origin.0 + self.width() - 1, self.origin.1 + 1);
+
+        match self.scrollbar.as_mut() {
+            Some(scrollbar) => {
+                if scrollbar.origin() != origin {
+                    scrollbar.set_origin(origin);
+                }
+                if scrollbar.height() != rows {
+                    scrollbar.set_height(rows);
+                }
+
+                scrollbar.sync(content_height, self.scroll as i32);
+            }
+            None => {
+                let mut scrollbar = Scrollbar::new(origin, rows, content_height);
+                scrollbar.set_current_line(self.scroll as i32);
+                self.scrollbar = Some(scrollbar);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to return whether a coord is within the table.
+    //---------------------------------------------------------------------------------------------
+    fn contains(&self, (x, y): ICoord) -> bool {
+        x >= self.origin.0
+            && x < self.origin.0 + self.width()
+            && y >= self.origin.1
+            && y < self.origin.1 + self.height()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the table, potentially redrawing if the state changes.
+    // Typing filters the rows, while up/down, page up/down and the mouse wheel scroll.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> TableAction
+    where
+        M: Map2d<Tile>,
+    {
+        let mut action = TableAction::Noop;
+        let scroll = self.scroll as i32;
+
+        // Update the scrollbar, scrolling the rows with it.
+        if let Some(scrollbar) = self.scrollbar.as_mut() {
+            match scrollbar.update(input, map) {
+                ScrollbarAction::Noop => {}
+                ScrollbarAction::ScrollUp(lines) => {
+                    self.set_scroll_position(scroll - lines);
+                    action = TableAction::Interactable;
+                }
+                ScrollbarAction::ScrollDown(lines) => {
+                    self.set_scroll_position(scroll + lines);
+                    action = TableAction::Interactable;
+                }
+                _ => action = TableAction::Interactable,
+            }
+        }
+
+        // Update the filter from typed keys.
+        let typed = input.just_pressed_chars();
+
+        if !typed.is_empty() && !input.modifier_pressed(&ModifierKey::Ctrl) {
+            self.filter.extend(typed);
+            self.scroll = 0;
+            self.refresh_filter();
+            action = TableAction::Interactable;
+        } else if !self.filter.is_empty()
+            && (input.key_just_pressed(InputKey::Backspace)
+                || input.key_just_pressed(InputKey::Escape))
+        {
+            if input.key_just_pressed(InputKey::Escape) {
+                self.filter.clear();
+            } else {
+                let _ = self.filter.pop();
+            }
+
+            self.scroll = 0;
+            self.refresh_filter();
+            action = TableAction::Interactable;
+        }
+
+        // Scroll with the keyboard.
+        let page = self.body_rows().max(1) as i32;
+        let delta = if input.key_just_pressed(InputKey::Up) {
+            -1
+        } else if input.key_just_pressed(InputKey::Down) {
+            1
+        } else if input.key_just_pressed(InputKey::PageUp) {
+            -page
+        } else if input.key_just_pressed(InputKey::PageDown) {
+            page
+        } else {
+            0
+        };
+
+        // Scroll with the mouse wheel while hovered.
+        let delta = match input.mouse_coord() {
+            Some(xy) if self.contains(xy) => {
+                action = TableAction::Interactable;
+                delta - input.mouse_wheel()
+            }
+            _ => delta,
+        };
+
+        if delta != 0 {
+            self.set_scroll_position(self.scroll as i32 + delta);
+            action = TableAction::Interactable;
+        }
+
+        if self.dirty {
+            self.redraw(map);
+            self.dirty = false;
+        }
+
+        action
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to draw text padded or truncated to a width.
+    //---------------------------------------------------------------------------------------------
+    fn draw_text<M>(
+        map: &mut M,
+        xy: ICoord,
+        width: i32,
+        text: &str,
+        settings: &RichTextFormatSettings,
+    ) where
+        M: Map2d<Tile>,
+    {
+        let width = width.max(0) as usize;
+        let text: String = RichTextWriter::truncate_plain(text, width)
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(width)
+            .collect();
+
+        RichTextWriter::write_plain_with_settings(map, xy, &text, settings);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to draw a row of cells, one per column.
+    //---------------------------------------------------------------------------------------------
+    fn draw_cells<M, S>(&self, map: &mut M, y: i32, cells: &[S], settings: &RichTextFormatSettings)
+    where
+        M: Map2d<Tile>,
+        S: AsRef<str>,
+    {
+        let right = self.origin.0 + self.text_width();
+        let mut x = self.origin.0;
+
+        for (i, column) in self.columns.iter().enumerate() {
+            if x >= right {
+                break;
+            }
+
+            // The last column takes the remaining width.
+            let width =
+                if i + 1 == self.columns.len() { right - x } else { column.width.min(right - x) };
+            let text = cells.get(i).map(|cell| cell.as_ref()).unwrap_or("");
+
+            Self::draw_text(map, (x, y), width, text, settings);
+
+            // Clear the gap between the columns.
+            let gap = COLUMN_GAP.min(right - x - width);
+            Self::draw_text(map, (x + width, y), gap, "", settings);
+
+            x += width + gap;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the table. Only necessary initially and when moving the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        if self.height() <= 0 {
+            return;
+        }
+
+        let headers: Vec<&str> = self.columns.iter().map(|c| c.header.as_str()).collect();
+        self.draw_cells(map, self.origin.1, &headers, &HEADER_SETTINGS);
+
+        for row in 0..self.body_rows() {
+            let y = self.origin.1 + 1 + row as i32;
+
+            match self.filtered.get(self.scroll + row).map(|i| &self.rows[*i]) {
+                Some(TableRow::Cells(cells)) => self.draw_cells(map, y, cells, &CELL_SETTINGS),
+                Some(TableRow::Heading(text)) => Self::draw_text(
+                    map,
+                    (self.origin.0, y),
+                    self.text_width(),
+                    text,
+                    &HEADING_SETTINGS,
+                ),
+                None => {
+                    Self::draw_text(map, (self.origin.0, y), self.text_width(), "", &CELL_SETTINGS)
+                }
+            }
+        }
+
+        // Draw the scrollbar if the rows overflow.
+        if let Some(scrollbar) = self.scrollbar.as_ref() {
+            scrollbar.redraw(map);
+        }
+
+        // Draw the search line while filtering.
+        if !self.filter.is_empty() && self.height() > 1 {
+            Self::draw_text(
+                map,
+                (self.origin.0, self.origin.1 + self.height() - 1),
+                self.width(),
+                &format!("{}{}", SEARCH_PREFIX, self.filter),
+                &SEARCH_SETTINGS,
+            );
+        }
+    }
+}
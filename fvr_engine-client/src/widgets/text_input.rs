@@ -0,0 +1,559 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::cmp;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::engine_event::*;
+use crate::input_manager::*;
+
+//-------------------------------------------------------------------------------------------------
+// Callback called with the text of a text input when it is submitted.
+//-------------------------------------------------------------------------------------------------
+pub type SubmitCallback = Box<dyn FnMut(&str)>;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Tile of the text.
+static TEXT_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Regular,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::Black.const_into(),
+    foreground_color: PaletteColor::BrightGrey.const_into(),
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+
+// Tile of the selected text.
+static SELECTED_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Regular,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::DarkGrey.const_into(),
+    foreground_color: PaletteColor::White.const_into(),
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+
+// Tile of the character under the cursor (while focused).
+static CURSOR_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Bold,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::Gold.const_into(),
+    foreground_color: PaletteColor::Black.const_into(),
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the response codes when updating a text input.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextInputAction {
+    // The text input was not interacted with.
+    Noop,
+    // The text input consumed user input (e.g. the text or cursor changed).
+    Interactable,
+    // The text was submitted (e.g. with enter).
+    Submitted,
+}
+
+//-------------------------------------------------------------------------------------------------
+// TextInput is a single line text field with a cursor, driven by the key and text events of the
+// input manager. It supports moving the cursor (by character, word or to either end), inserting
+// and deleting, selecting with shift, copying, cutting and pasting through the system clipboard,
+// a maximum length and a callback when the text is submitted.
+// The text scrolls horizontally to keep the cursor in view.
+// (scenes should ignore actions while a text input is focused, as typing also presses them)
+//-------------------------------------------------------------------------------------------------
+pub struct TextInput {
+    // Origin of the text input.
+    origin: ICoord,
+    // # of cells the text input covers.
+    width: i32,
+    // The text.
+    text: Vec<char>,
+    // Position of the cursor, between 0 and the length of the text.
+    cursor: usize,
+    // Position the selection extends from to the cursor, if any text is selected.
+    anchor: Option<usize>,
+    // Position of the first visible character.
+    scroll: usize,
+    // Maximum # of characters of the text, if limited.
+    max_length: Option<usize>,
+    // Whether the text input takes key and text events.
+    focused: bool,
+    // Called with the text when it is submitted.
+    on_submit: Option<SubmitCallback>,
+}
+
+impl TextInput {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new (unfocused and empty) text input.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, width: i32) -> Self {
+        Self {
+            origin,
+            width: cmp::max(width, 1),
+            text: Vec::new(),
+            cursor: 0,
+            anchor: None,
+            scroll: 0,
+            max_length: None,
+            focused: false,
+            on_submit: None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the text.
+    //---------------------------------------------------------------------------------------------
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the text (truncated to the maximum length), moving the cursor to its end.
+    // Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().filter(|c| !c.is_control()).collect();
+        self.text.truncate(self.max_length.unwrap_or(usize::MAX));
+        self.cursor = self.text.len();
+        self.anchor = None;
+        self.scroll_to_cursor();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes the text. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position of the cursor, in characters.
+    //---------------------------------------------------------------------------------------------
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the selected text, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection().map(|(start, end)| self.text[start..end].iter().collect())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the maximum # of characters of the text, if limited.
+    //---------------------------------------------------------------------------------------------
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Limits the # of characters of the text, truncating the current text. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+
+        if let Some(max_length) = max_length {
+            self.text.truncate(max_length);
+            self.cursor = cmp::min(self.cursor, self.text.len());
+            self.anchor = self.anchor.map(|anchor| cmp::min(anchor, self.text.len()));
+            self.scroll_to_cursor();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the text input takes key and text events.
+    //---------------------------------------------------------------------------------------------
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Focuses or unfocuses the text input (unfocusing clears the selection). Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+
+        if !focused {
+            self.anchor = None;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the callback called with the text when it is submitted.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_on_submit<F>(&mut self, on_submit: F)
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.on_submit = Some(Box::new(on_submit));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the text input. Requires a redraw.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area covered by the text input.
+    //---------------------------------------------------------------------------------------------
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.origin, self.width, 1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Inserts text at the cursor, replacing the selection. Control characters are skipped and the
+    // text is cut off at the maximum length. Returns whether the text changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn insert(&mut self, text: &str) -> bool {
+        let deleted = self.delete_selection();
+        let room = self.max_length.map_or(usize::MAX, |max| max.saturating_sub(self.text.len()));
+        let inserted = text.chars().filter(|c| !c.is_control()).take(room).collect::<Vec<_>>();
+
+        if inserted.is_empty() {
+            return deleted;
+        }
+
+        let count = inserted.len();
+        let _ = self.text.splice(self.cursor..self.cursor, inserted);
+        self.cursor += count;
+        self.scroll_to_cursor();
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deletes the selection, or the character (or word) before the cursor.
+    // Returns whether the text changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn delete_backward(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+
+        let start =
+            if word { self.previous_word(self.cursor) } else { self.cursor.saturating_sub(1) };
+        self.delete_range(start, self.cursor)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deletes the selection, or the character (or word) after the cursor.
+    // Returns whether the text changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn delete_forward(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+
+        let end = if word {
+            self.next_word(self.cursor)
+        } else {
+            cmp::min(self.cursor + 1, self.text.len())
+        };
+        self.delete_range(self.cursor, end)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the cursor to a position, extending the selection to it if requested (otherwise the
+    // selection is cleared).
+    //---------------------------------------------------------------------------------------------
+    pub fn move_cursor(&mut self, position: usize, extend_selection: bool) {
+        if extend_selection {
+            self.anchor = self.anchor.or(Some(self.cursor));
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = cmp::min(position, self.text.len());
+
+        if self.anchor == Some(self.cursor) {
+            self.anchor = None;
+        }
+
+        self.scroll_to_cursor();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Selects all of the text.
+    //---------------------------------------------------------------------------------------------
+    pub fn select_all(&mut self) {
+        self.move_cursor(0, false);
+        self.move_cursor(self.text.len(), true);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the start and end of the selection, if any text is selected.
+    //---------------------------------------------------------------------------------------------
+    fn selection(&self) -> Option<(usize, usize)> {
+        self.anchor.map(|anchor| (cmp::min(anchor, self.cursor), cmp::max(anchor, self.cursor)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that deletes the selected text, returning whether any was selected.
+    //---------------------------------------------------------------------------------------------
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => {
+                self.anchor = None;
+                self.delete_range(start, end)
+            }
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that deletes a range of the text, leaving the cursor at its start.
+    // Returns whether the range was not empty.
+    //---------------------------------------------------------------------------------------------
+    fn delete_range(&mut self, start: usize, end: usize) -> bool {
+        if start >= end {
+            return false;
+        }
+
+        let _ = self.text.drain(start..end);
+        self.cursor = start;
+        self.scroll_to_cursor();
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the start of the word before a position.
+    //---------------------------------------------------------------------------------------------
+    fn previous_word(&self, position: usize) -> usize {
+        let mut i = position;
+
+        while i > 0 && !self.text[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+
+        while i > 0 && self.text[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+
+        i
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the end of the word after a position.
+    //---------------------------------------------------------------------------------------------
+    fn next_word(&self, position: usize) -> usize {
+        let mut i = position;
+
+        while i < self.text.len() && !self.text[i].is_alphanumeric() {
+            i += 1;
+        }
+
+        while i < self.text.len() && self.text[i].is_alphanumeric() {
+            i += 1;
+        }
+
+        i
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that scrolls the text so that the cursor is visible.
+    //---------------------------------------------------------------------------------------------
+    fn scroll_to_cursor(&mut self) {
+        // The cursor takes a cell of its own past the end of the text.
+        let width = self.width as usize;
+
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + width {
+            self.scroll = self.cursor + 1 - width;
+        }
+
+        // Do not leave empty cells at the end while the text could fill them.
+        let max_scroll = (self.text.len() + 1).saturating_sub(width);
+        self.scroll = cmp::min(self.scroll, max_scroll);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that handles an editing key, returning whether it was consumed.
+    //---------------------------------------------------------------------------------------------
+    fn handle_key(
+        &mut self,
+        input: &InputManager,
+        key: InputKey,
+        modifiers: KeyModifiers,
+    ) -> bool {
+        let (shift, ctrl) = (modifiers.shift, modifiers.ctrl);
+
+        match key {
+            InputKey::Left if ctrl => self.move_cursor(self.previous_word(self.cursor), shift),
+            InputKey::Right if ctrl => self.move_cursor(self.next_word(self.cursor), shift),
+            // Without shift, collapse the selection to the side the cursor moves toward.
+            InputKey::Left => match self.selection() {
+                Some((start, _)) if !shift => self.move_cursor(start, false),
+                _ => self.move_cursor(self.cursor.saturating_sub(1), shift),
+            },
+            InputKey::Right => match self.selection() {
+                Some((_, end)) if !shift => self.move_cursor(end, false),
+                _ => self.move_cursor(self.cursor + 1, shift),
+            },
+            InputKey::Home => self.move_cursor(0, shift),
+            InputKey::End => self.move_cursor(self.text.len(), shift),
+            InputKey::Backspace => {
+                let _ = self.delete_backward(ctrl);
+            }
+            InputKey::Delete => {
+                let _ = self.delete_forward(ctrl);
+            }
+            InputKey::A if ctrl => self.select_all(),
+            InputKey::C | InputKey::X if ctrl => {
+                if let Some(selected) = self.selected_text() {
+                    if let Err(e) = input.set_clipboard_text(&selected) {
+                        eprintln!("[TextInput] {:#}", e);
+                    } else if key == InputKey::X {
+                        let _ = self.delete_selection();
+                    }
+                }
+            }
+            InputKey::V if ctrl => {
+                // Only the first line of the clipboard fits in the text input.
+                if let Some(text) = input.clipboard_text() {
+                    let _ = self.insert(text.lines().next().unwrap_or_default());
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the text input from the events since the last reset, potentially redrawing.
+    // Clicking the text input focuses it and places the cursor, and clicking elsewhere unfocuses
+    // it. Enter submits the text.
+    //---------------------------------------------------------------------------------------------
+    pub fn update<M>(&mut self, input: &InputManager, map: &mut M) -> TextInputAction
+    where
+        M: Map2d<Tile>,
+    {
+        let mut action = TextInputAction::Noop;
+
+        // Focus on click, placing the cursor under the mouse.
+        if input.mouse_clicked(InputMouse::Left) {
+            match input.mouse_coord() {
+                Some(xy) if self.bounds().contains(xy) => {
+                    self.focused = true;
+                    self.move_cursor(self.scroll + (xy.0 - self.origin.0) as usize, false);
+                    action = TextInputAction::Interactable;
+                }
+                _ if self.focused => {
+                    self.set_focused(false);
+                    action = TextInputAction::Interactable;
+                }
+                _ => {}
+            }
+        }
+
+        if !self.focused {
+            if action != TextInputAction::Noop {
+                self.redraw(map);
+            }
+
+            return action;
+        }
+
+        for event in input.events() {
+            match event {
+                // Typing with ctrl held is for shortcuts, not text.
+                EngineEvent::TextInput(text) if !input.modifier_pressed(&ModifierKey::Ctrl) => {
+                    let _ = self.insert(text);
+                    action = TextInputAction::Interactable;
+                }
+                EngineEvent::KeyDown { key: InputKey::Return, .. }
+                | EngineEvent::KeyDown { key: InputKey::KpEnter, .. } => {
+                    let text = self.text();
+
+                    if let Some(on_submit) = self.on_submit.as_mut() {
+                        on_submit(&text);
+                    }
+
+                    action = TextInputAction::Submitted;
+                }
+                EngineEvent::KeyDown { key, modifiers, .. } => {
+                    let handled = self.handle_key(input, *key, *modifiers);
+
+                    if handled && action == TextInputAction::Noop {
+                        action = TextInputAction::Interactable;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if action != TextInputAction::Noop {
+            self.redraw(map);
+        }
+
+        action
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the text input. Only necessary initially and when moving the text input.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let selection = self.selection();
+
+        for x in 0..self.width {
+            let i = self.scroll + x as usize;
+            let selected = matches!(selection, Some((start, end)) if i >= start && i < end);
+
+            let mut tile = if self.focused && i == self.cursor {
+                CURSOR_TILE
+            } else if selected {
+                SELECTED_TILE
+            } else {
+                TEXT_TILE
+            };
+            tile.glyph = self.text.get(i).copied().unwrap_or(' ');
+
+            *map.get_xy_mut((self.origin.0 + x, self.origin.1)) = tile;
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns taken by each entry (its glyph followed by a gap).
+const ENTRY_STRIDE: i32 = 2;
+
+// Glyph shown in the last cell when there are more entries than fit.
+const OVERFLOW_CHAR: char = '+';
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+static BLANK_TILE: Tile = Tile {
+    glyph: ' ',
+    layout: TileLayout::Center,
+    style: TileStyle::Regular,
+    size: TileSize::Normal,
+    outlined: false,
+    background_color: PaletteColor::Black.const_into(),
+    foreground_color: PaletteColor::DarkGrey.const_into(),
+    outline_color: TileColor::TRANSPARENT,
+    background_opacity: 1.0,
+    foreground_opacity: 1.0,
+    outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
+};
+
+//-------------------------------------------------------------------------------------------------
+// An actor shown in a turn order.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TurnOrderEntry {
+    // Glyph of the actor.
+    pub glyph: char,
+    // Color of the actor's glyph.
+    pub color: TileColor,
+    // Whether the actor is the player (highlighted).
+    pub player: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// TurnOrder draws the glyphs of the next actors to take their turns in a row, in order, with the
+// player highlighted. Entries that do not fit are summarized by a trailing overflow glyph.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct TurnOrder {
+    // Origin of the row.
+    origin: ICoord,
+    // Max # of entries shown.
+    capacity: usize,
+    // Entries in the order the actors take their turns.
+    entries: Vec<TurnOrderEntry>,
+}
+
+impl TurnOrder {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty turn order showing at most capacity entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(origin: ICoord, capacity: usize) -> Self {
+        Self { origin, capacity: capacity.max(1), entries: Vec::new() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the origin of the row.
+    //---------------------------------------------------------------------------------------------
+    pub fn origin(&self) -> ICoord {
+        self.origin
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the origin of the row. The turn order must be redrawn afterwards.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_origin(&mut self, origin: ICoord) {
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the max # of entries shown.
+    //---------------------------------------------------------------------------------------------
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the width of the row in tiles.
+    //---------------------------------------------------------------------------------------------
+    pub fn width(&self) -> i32 {
+        self.capacity as i32 * ENTRY_STRIDE - 1
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the entries in the order the actors take their turns.
+    //---------------------------------------------------------------------------------------------
+    pub fn entries(&self) -> &[TurnOrderEntry] {
+        &self.entries
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the entries. Returns whether they changed (and so should be redrawn).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_entries<I>(&mut self, entries: I) -> bool
+    where
+        I: IntoIterator<Item = TurnOrderEntry>,
+    {
+        let entries: Vec<TurnOrderEntry> = entries.into_iter().collect();
+
+        if entries == self.entries {
+            return false;
+        }
+
+        self.entries = entries;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the turn order.
+    //---------------------------------------------------------------------------------------------
+    pub fn redraw<M>(&self, map: &mut M)
+    where
+        M: Map2d<Tile>,
+    {
+        let overflow = self.entries.len() > self.capacity;
+
+        for i in 0..self.width() {
+            let xy = (self.origin.0 + i, self.origin.1);
+
+            if !map.in_bounds(xy) {
+                continue;
+            }
+
+            let tile = map.get_xy_mut(xy);
+            *tile = BLANK_TILE;
+
+            if i % ENTRY_STRIDE != 0 {
+                continue;
+            }
+
+            let index = (i / ENTRY_STRIDE) as usize;
+
+            if overflow && index == self.capacity - 1 {
+                tile.glyph = OVERFLOW_CHAR;
+                continue;
+            }
+
+            if let Some(entry) = self.entries.get(index) {
+                tile.glyph = entry.glyph;
+                tile.foreground_color = entry.color;
+
+                if entry.player {
+                    tile.background_color = PaletteColor::Gold.const_into();
+                    tile.background_opacity = 0.35;
+                }
+            }
+        }
+    }
+}
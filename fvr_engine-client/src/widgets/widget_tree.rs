@@ -0,0 +1,1259 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::any::Any;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{Context, Result};
+use fnv::FnvHashMap;
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::input_manager::*;
+use crate::interaction_map::*;
+use crate::terminal::*;
+use crate::widgets::button::*;
+use crate::widgets::button_list::*;
+use crate::widgets::frame::*;
+use crate::widgets::list_menu::*;
+use crate::widgets::panel::*;
+use crate::widgets::scroll_log::*;
+use crate::widgets::scrollbar::*;
+use crate::widgets::table::*;
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+// Ids of widgets in a widget tree are stable for the lifetime of the tree and never reused.
+//-------------------------------------------------------------------------------------------------
+pub type WidgetId = u32;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the response codes when updating a widget through a widget tree.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WidgetAction {
+    // The widget was not interacted with.
+    Noop,
+    // The widget consumed user input, but was not triggered.
+    Interactable,
+    // The widget was triggered, with the index of the triggered item (0 for single item widgets).
+    Triggered(i32),
+}
+
+//-------------------------------------------------------------------------------------------------
+// An action of a widget in a widget tree, collected during the tree's update.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WidgetEvent {
+    // Id of the widget.
+    pub id: WidgetId,
+    // Action of the widget.
+    pub action: WidgetAction,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Widget is implemented by widgets that can be owned by a widget tree.
+//-------------------------------------------------------------------------------------------------
+pub trait Widget: Any {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget (for layout serialization).
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the widget receives input and can take focus.
+    //---------------------------------------------------------------------------------------------
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget with input routed by the tree, potentially redrawing.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction>;
+
+    //---------------------------------------------------------------------------------------------
+    // Activates the widget from the keyboard while it has focus.
+    //---------------------------------------------------------------------------------------------
+    fn activate(&mut self) -> WidgetAction {
+        WidgetAction::Noop
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets the widget to the default state (e.g. when losing focus).
+    //---------------------------------------------------------------------------------------------
+    fn reset(&mut self) {}
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()>;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any (for downcasting to the concrete widget).
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably (for downcasting to the concrete widget).
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for Button.
+//-------------------------------------------------------------------------------------------------
+impl Widget for Button {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "button"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin, self.text.chars().count() as i32, 1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match Button::update(self, input, terminal) {
+            ButtonAction::Noop => WidgetAction::Noop,
+            ButtonAction::Interactable => WidgetAction::Interactable,
+            ButtonAction::Triggered => WidgetAction::Triggered(0),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Activates the widget from the keyboard.
+    //---------------------------------------------------------------------------------------------
+    fn activate(&mut self) -> WidgetAction {
+        if self.disabled() {
+            WidgetAction::Noop
+        } else {
+            WidgetAction::Triggered(0)
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets the widget to the default state.
+    //---------------------------------------------------------------------------------------------
+    fn reset(&mut self) {
+        Button::reset(self);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        Button::redraw(self, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for ButtonList.
+//-------------------------------------------------------------------------------------------------
+impl Widget for ButtonList {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "button_list"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), self.width(), self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match ButtonList::update(self, input, terminal) {
+            ButtonListAction::Noop => WidgetAction::Noop,
+            ButtonListAction::Interactable | ButtonListAction::SelectionChanged => {
+                WidgetAction::Interactable
+            }
+            ButtonListAction::Triggered(index) => WidgetAction::Triggered(index),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets the widget to the default state.
+    //---------------------------------------------------------------------------------------------
+    fn reset(&mut self) {
+        ButtonList::reset(self);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        ButtonList::redraw(self, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for Frame (a static container for other widgets).
+//-------------------------------------------------------------------------------------------------
+impl Widget for Frame {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "frame"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), self.width(), self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the widget receives input and can take focus.
+    //---------------------------------------------------------------------------------------------
+    fn interactive(&self) -> bool {
+        false
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, _: &InputManager, _: &mut Terminal) -> Result<WidgetAction> {
+        Ok(WidgetAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        self.draw_clear(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for ListMenu.
+//-------------------------------------------------------------------------------------------------
+impl Widget for ListMenu {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "list_menu"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), self.width(), self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match ListMenu::update(self, input, terminal) {
+            ListMenuAction::Noop => WidgetAction::Noop,
+            ListMenuAction::Interactable | ListMenuAction::SelectionChanged => {
+                WidgetAction::Interactable
+            }
+            ListMenuAction::Triggered(index) => WidgetAction::Triggered(index),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Activates the widget from the keyboard.
+    //---------------------------------------------------------------------------------------------
+    fn activate(&mut self) -> WidgetAction {
+        match self.cursor_item() {
+            Some(index) => WidgetAction::Triggered(index),
+            None => WidgetAction::Noop,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        ListMenu::redraw(self, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for Panel (a static container for other widgets).
+//-------------------------------------------------------------------------------------------------
+impl Widget for Panel {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "panel"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Panel::bounds(self)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the widget receives input and can take focus.
+    //---------------------------------------------------------------------------------------------
+    fn interactive(&self) -> bool {
+        false
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, _: &InputManager, _: &mut Terminal) -> Result<WidgetAction> {
+        Ok(WidgetAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        self.draw(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for ScrollLog.
+//-------------------------------------------------------------------------------------------------
+impl Widget for ScrollLog {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "scroll_log"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), self.width(), self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match ScrollLog::update(self, input, terminal)? {
+            ScrollLogAction::Noop => WidgetAction::Noop,
+            // Line ids do not fit an item index, so anchor clicks are read from the log itself.
+            ScrollLogAction::Focused
+            | ScrollLogAction::Interactable
+            | ScrollLogAction::AnchorClicked(_) => WidgetAction::Interactable,
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        ScrollLog::redraw(self, terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for Scrollbar.
+//-------------------------------------------------------------------------------------------------
+impl Widget for Scrollbar {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "scrollbar"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), 1, self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match Scrollbar::update(self, input, terminal) {
+            ScrollbarAction::Noop => WidgetAction::Noop,
+            _ => WidgetAction::Interactable,
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        Scrollbar::redraw(self, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Widget for Table.
+//-------------------------------------------------------------------------------------------------
+impl Widget for Table {
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the kind of widget.
+    //---------------------------------------------------------------------------------------------
+    fn kind(&self) -> &'static str {
+        "table"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the area of the terminal covered by the widget.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect {
+        Rect::new(self.origin(), self.width(), self.height())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widget, converting its action.
+    //---------------------------------------------------------------------------------------------
+    fn update(&mut self, input: &InputManager, terminal: &mut Terminal) -> Result<WidgetAction> {
+        Ok(match Table::update(self, input, terminal) {
+            TableAction::Noop => WidgetAction::Noop,
+            TableAction::Interactable => WidgetAction::Interactable,
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the widget.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        Table::redraw(self, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any.
+    //---------------------------------------------------------------------------------------------
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget as Any mutably.
+    //---------------------------------------------------------------------------------------------
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// WidgetLayout is a serializable description of a widget and its children (for tooling, e.g.
+// inspecting or diffing the layout of a scene).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct WidgetLayout {
+    // Id of the widget.
+    pub id: WidgetId,
+    // Kind of the widget.
+    pub kind: String,
+    // Origin of the widget's bounds.
+    pub origin: ICoord,
+    // Dimensions of the widget's bounds.
+    pub dimensions: ICoord,
+    // Whether the widget is visible.
+    pub visible: bool,
+    // Layouts of the widget's children, in draw order.
+    pub children: Vec<WidgetLayout>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A widget owned by a widget tree and its place in the hierarchy.
+//-------------------------------------------------------------------------------------------------
+struct WidgetNode {
+    // The widget.
+    widget: Box<dyn Widget>,
+    // Id of the parent widget, if any.
+    parent: Option<WidgetId>,
+    // Ids of the child widgets, in draw order.
+    children: Vec<WidgetId>,
+    // Whether the widget (and its children) are visible.
+    visible: bool,
+    // Whether the widget must be redrawn.
+    dirty: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// WidgetTree is an optional retained-mode layer that owns widgets in a hierarchy, routes input to
+// them through an interaction map and keyboard focus, and only redraws widgets marked dirty.
+// Children are drawn after (and receive input in priority over) their parents.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct WidgetTree {
+    // Widgets by id.
+    nodes: FnvHashMap<WidgetId, WidgetNode>,
+    // Ids of the widgets without a parent, in draw order.
+    roots: Vec<WidgetId>,
+    // Id of the next inserted widget.
+    next_id: WidgetId,
+    // Id of the widget with keyboard focus, if any.
+    focused: Option<WidgetId>,
+    // Id of the widget that consumed input last update, if any (e.g. dragging a scrollbar).
+    captured: Option<WidgetId>,
+    // Hot regions of the visible, interactive widgets.
+    interactions: InteractionMap,
+    // Whether the hot regions must be registered again.
+    interactions_dirty: bool,
+    // Areas of hidden or removed widgets that must be cleared on the next draw.
+    cleared: Vec<Rect>,
+    // Events collected during the last update.
+    events: Vec<WidgetEvent>,
+}
+
+impl WidgetTree {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty widget tree.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Inserts a widget as the last child of a parent (or as a root), returning its id.
+    //---------------------------------------------------------------------------------------------
+    pub fn insert<W>(&mut self, widget: W, parent: Option<WidgetId>) -> Result<WidgetId>
+    where
+        W: Widget,
+    {
+        let id = self.next_id;
+
+        match parent {
+            Some(parent_id) => self
+                .nodes
+                .get_mut(&parent_id)
+                .context("Parent widget does not exist.")?
+                .children
+                .push(id),
+            None => self.roots.push(id),
+        }
+
+        let node = WidgetNode {
+            widget: Box::new(widget),
+            parent,
+            children: Vec::new(),
+            visible: true,
+            dirty: true,
+        };
+        let _ = self.nodes.insert(id, node);
+        self.next_id += 1;
+        self.interactions_dirty = true;
+
+        Ok(id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes a widget and its children, clearing their area on the next draw.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove(&mut self, id: WidgetId) -> bool {
+        let parent = match self.nodes.get(&id) {
+            Some(node) => node.parent,
+            None => return false,
+        };
+
+        match parent.and_then(|parent_id| self.nodes.get_mut(&parent_id)) {
+            Some(parent_node) => parent_node.children.retain(|child| *child != id),
+            None => self.roots.retain(|root| *root != id),
+        }
+
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.remove(&id) {
+                if node.visible {
+                    self.cleared.push(node.widget.bounds());
+                }
+
+                if self.focused == Some(id) {
+                    self.focused = None;
+                }
+                if self.captured == Some(id) {
+                    self.captured = None;
+                }
+
+                stack.extend(node.children);
+            }
+        }
+
+        self.mark_parent_dirty(parent);
+        self.interactions_dirty = true;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all widgets. Ids are not reused.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        for root in self.roots.clone() {
+            let _ = self.remove(root);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a widget exists.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, id: WidgetId) -> bool {
+        self.nodes.contains_key(&id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of widgets.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no widgets.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the parent of a widget, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn parent(&self, id: WidgetId) -> Option<WidgetId> {
+        self.nodes.get(&id).and_then(|node| node.parent)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the children of a widget, in draw order.
+    //---------------------------------------------------------------------------------------------
+    pub fn children(&self, id: WidgetId) -> &[WidgetId] {
+        self.nodes.get(&id).map(|node| node.children.as_slice()).unwrap_or(&[])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a widget as its concrete type, if it exists and is of that type.
+    //---------------------------------------------------------------------------------------------
+    pub fn get<W>(&self, id: WidgetId) -> Option<&W>
+    where
+        W: Widget,
+    {
+        self.nodes.get(&id).and_then(|node| node.widget.as_any().downcast_ref::<W>())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a widget mutably as its concrete type, if it exists and is of that type.
+    // The widget is marked dirty, since it may be changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn get_mut<W>(&mut self, id: WidgetId) -> Option<&mut W>
+    where
+        W: Widget,
+    {
+        let node = self.nodes.get_mut(&id)?;
+        let widget = node.widget.as_any_mut().downcast_mut::<W>()?;
+
+        // Bounds may change, so the widget's area is cleared and the regions registered again.
+        if node.visible {
+            self.cleared.push(widget.bounds());
+        }
+        node.dirty = true;
+        self.interactions_dirty = true;
+
+        Some(widget)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Marks a widget (and therefore its children) to be redrawn on the next draw.
+    //---------------------------------------------------------------------------------------------
+    pub fn mark_dirty(&mut self, id: WidgetId) {
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.dirty = true;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Marks all widgets to be redrawn on the next draw (e.g. after the terminal was cleared).
+    //---------------------------------------------------------------------------------------------
+    pub fn mark_all_dirty(&mut self) {
+        for node in self.nodes.values_mut() {
+            node.dirty = true;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to mark the parent of a changed widget dirty (if any).
+    //---------------------------------------------------------------------------------------------
+    fn mark_parent_dirty(&mut self, parent: Option<WidgetId>) {
+        if let Some(parent_id) = parent {
+            self.mark_dirty(parent_id);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a widget and all of its ancestors are visible.
+    //---------------------------------------------------------------------------------------------
+    pub fn visible(&self, id: WidgetId) -> bool {
+        let mut current = Some(id);
+
+        while let Some(id) = current {
+            match self.nodes.get(&id) {
+                Some(node) if node.visible => current = node.parent,
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Shows or hides a widget and its children, clearing the area of hidden widgets.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_visible(&mut self, id: WidgetId, visible: bool) {
+        let was_visible = self.visible(id);
+
+        let parent = match self.nodes.get_mut(&id) {
+            Some(node) if node.visible != visible => {
+                node.visible = visible;
+                node.dirty = true;
+                node.parent
+            }
+            _ => return,
+        };
+
+        if was_visible && !visible {
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.widget.reset();
+                self.cleared.push(node.widget.bounds());
+            }
+            self.mark_parent_dirty(parent);
+
+            // Move focus out of the hidden subtree.
+            if self.focused.is_some_and(|focused| !self.visible(focused)) {
+                self.set_focus(None);
+            }
+        }
+
+        self.interactions_dirty = true;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the widget with keyboard focus, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focused
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Gives keyboard focus to a widget (or none), resetting the widget losing focus.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_focus(&mut self, id: Option<WidgetId>) {
+        if id == self.focused {
+            return;
+        }
+
+        if let Some(node) = self.focused.and_then(|focused| self.nodes.get_mut(&focused)) {
+            node.widget.reset();
+            node.dirty = true;
+        }
+
+        self.focused = id.filter(|id| {
+            self.nodes.get(id).is_some_and(|node| node.widget.interactive()) && self.visible(*id)
+        });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves keyboard focus to the next visible, interactive widget in draw order (wrapping).
+    //---------------------------------------------------------------------------------------------
+    pub fn focus_next(&mut self) {
+        let order = self.focus_order();
+        let next = match self.focused.and_then(|id| order.iter().position(|other| *other == id)) {
+            Some(index) => order.get((index + 1) % order.len()).copied(),
+            None => order.first().copied(),
+        };
+
+        self.set_focus(next);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves keyboard focus to the previous visible, interactive widget in draw order (wrapping).
+    //---------------------------------------------------------------------------------------------
+    pub fn focus_previous(&mut self) {
+        let order = self.focus_order();
+        let previous =
+            match self.focused.and_then(|id| order.iter().position(|other| *other == id)) {
+                Some(index) => order.get((index + order.len() - 1) % order.len()).copied(),
+                None => order.last().copied(),
+            };
+
+        self.set_focus(previous);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to collect the visible widgets in draw order (depth-first, parents first).
+    //---------------------------------------------------------------------------------------------
+    fn draw_order(&self) -> Vec<WidgetId> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<WidgetId> = self.roots.iter().rev().copied().collect();
+
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.get(&id) {
+                if node.visible {
+                    order.push(id);
+                    stack.extend(node.children.iter().rev());
+                }
+            }
+        }
+
+        order
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to collect the widgets that can take focus, in draw order.
+    //---------------------------------------------------------------------------------------------
+    fn focus_order(&self) -> Vec<WidgetId> {
+        self.draw_order().into_iter().filter(|id| self.nodes[id].widget.interactive()).collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function to register the hot regions of the visible, interactive widgets.
+    //---------------------------------------------------------------------------------------------
+    fn sync_interactions(&mut self) {
+        if !self.interactions_dirty {
+            return;
+        }
+
+        self.interactions.clear();
+
+        for id in self.focus_order() {
+            self.interactions.register_rect(id, self.nodes[&id].widget.bounds());
+        }
+
+        self.interactions_dirty = false;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Updates the widgets, routing input to those that are hovered, focused or consumed input last
+    // update, and returns the resulting events.
+    // Tab and shift+tab move keyboard focus, and return activates the focused widget.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(
+        &mut self,
+        input: &InputManager,
+        terminal: &mut Terminal,
+    ) -> Result<&[WidgetEvent]> {
+        self.events.clear();
+        self.sync_interactions();
+
+        // Resolve the mouse against the hot regions.
+        let mut interaction_events = Vec::new();
+        self.interactions.resolve(
+            input.mouse_coord(),
+            input.mouse_clicked_state(),
+            &mut interaction_events,
+        );
+
+        // Collect the widgets to route input to, giving focus to clicked widgets.
+        let mut targets = Vec::new();
+
+        for event in interaction_events.iter() {
+            match *event {
+                InteractionEvent::HoverEnter(id) | InteractionEvent::HoverLeave(id) => {
+                    targets.push(id)
+                }
+                InteractionEvent::Click(id, _) => self.set_focus(Some(id)),
+            }
+        }
+
+        targets.extend(self.interactions.hovered());
+        targets.extend(self.captured);
+
+        // Handle keyboard focus.
+        if input.key_just_pressed(InputKey::Tab) {
+            if input.modifier_pressed(&ModifierKey::Shift) {
+                self.focus_previous();
+            } else {
+                self.focus_next();
+            }
+        }
+
+        // The focused widget receives input wherever the mouse is (e.g. typed keys).
+        targets.extend(self.focused);
+
+        // Route input to the collected widgets.
+        for (i, id) in targets.iter().enumerate() {
+            if targets[..i].contains(id) {
+                continue;
+            }
+
+            let node = match self.nodes.get_mut(id) {
+                Some(node) => node,
+                None => continue,
+            };
+            let action = node.widget.update(input, terminal)?;
+
+            if action != WidgetAction::Noop {
+                self.events.push(WidgetEvent { id: *id, action });
+                self.captured = Some(*id);
+            } else if self.captured == Some(*id) {
+                self.captured = None;
+            }
+        }
+
+        // Activate the focused widget, unless it already handled the key itself.
+        if input.key_just_pressed(InputKey::Return) {
+            if let Some(id) = self.focused.filter(|id| self.action(*id).is_none()) {
+                let node = self.nodes.get_mut(&id).expect("Focused widget does not exist.");
+                let action = node.widget.activate();
+
+                if action != WidgetAction::Noop {
+                    node.dirty = true;
+                    self.events.push(WidgetEvent { id, action });
+                }
+            }
+        }
+
+        Ok(&self.events)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the events collected during the last update.
+    //---------------------------------------------------------------------------------------------
+    pub fn events(&self) -> &[WidgetEvent] {
+        &self.events
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the action of a widget from the last update, if it was interacted with.
+    //---------------------------------------------------------------------------------------------
+    pub fn action(&self, id: WidgetId) -> Option<WidgetAction> {
+        self.events.iter().rev().find(|event| event.id == id).map(|event| event.action)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Redraws the dirty widgets (and their children) after clearing the area of hidden widgets,
+    // returning whether anything was drawn.
+    //---------------------------------------------------------------------------------------------
+    pub fn draw(&mut self, terminal: &mut Terminal) -> Result<bool> {
+        let mut drawn = false;
+
+        // Clear the areas of hidden and removed widgets, redrawing any widgets they overlapped.
+        for rect in self.cleared.drain(..) {
+            for xy in rect.points() {
+                if terminal.in_bounds(xy) {
+                    *terminal.get_xy_mut(xy) = BLANK_TILE;
+                }
+            }
+
+            for node in self.nodes.values_mut() {
+                if node.widget.bounds().intersects(&rect) {
+                    node.dirty = true;
+                }
+            }
+
+            drawn = true;
+        }
+
+        // Draw in draw order, redrawing the children of dirty widgets as well.
+        let mut stack: Vec<(WidgetId, bool)> =
+            self.roots.iter().rev().map(|root| (*root, false)).collect();
+
+        while let Some((id, parent_drawn)) = stack.pop() {
+            let node = match self.nodes.get_mut(&id) {
+                Some(node) if node.visible => node,
+                _ => continue,
+            };
+
+            let redraw = parent_drawn || node.dirty;
+
+            if redraw {
+                node.widget.redraw(terminal)?;
+                drawn = true;
+            }
+
+            node.dirty = false;
+            stack.extend(node.children.iter().rev().map(|child| (*child, redraw)));
+        }
+
+        Ok(drawn)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the layouts of the root widgets and their children.
+    //---------------------------------------------------------------------------------------------
+    pub fn layout(&self) -> Vec<WidgetLayout> {
+        self.roots.iter().filter_map(|root| self.widget_layout(*root)).collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the layout of a widget and its children, if it exists.
+    //---------------------------------------------------------------------------------------------
+    pub fn widget_layout(&self, id: WidgetId) -> Option<WidgetLayout> {
+        let node = self.nodes.get(&id)?;
+        let bounds = node.widget.bounds();
+
+        Some(WidgetLayout {
+            id,
+            kind: node.widget.kind().into(),
+            origin: bounds.origin(),
+            dimensions: bounds.dimensions(),
+            visible: node.visible,
+            children: node
+                .children
+                .iter()
+                .filter_map(|child| self.widget_layout(*child))
+                .collect(),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Serializes the layout of the tree into pretty-printed JSON.
+    //---------------------------------------------------------------------------------------------
+    pub fn layout_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.layout())?)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+struct ProbeWidget {
+    bounds: Rect,
+    interactive: bool,
+    updates: u32,
+}
+
+#[cfg(test)]
+impl ProbeWidget {
+    fn new(origin: ICoord, interactive: bool) -> Self {
+        Self { bounds: Rect::new(origin, 4, 1), interactive, updates: 0 }
+    }
+}
+
+#[cfg(test)]
+impl Widget for ProbeWidget {
+    fn kind(&self) -> &'static str {
+        "probe"
+    }
+
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    fn update(&mut self, _: &InputManager, _: &mut Terminal) -> Result<WidgetAction> {
+        self.updates += 1;
+        Ok(WidgetAction::Noop)
+    }
+
+    fn redraw(&self, _: &mut Terminal) -> Result<()> {
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[test]
+fn test_widget_tree_focus_order() {
+    let mut tree = WidgetTree::new();
+    let panel = tree.insert(ProbeWidget::new((0, 0), false), None).unwrap();
+    let first = tree.insert(ProbeWidget::new((0, 1), true), Some(panel)).unwrap();
+    let second = tree.insert(ProbeWidget::new((0, 2), true), Some(panel)).unwrap();
+    let third = tree.insert(ProbeWidget::new((0, 3), true), None).unwrap();
+
+    // Focus moves through the interactive widgets in draw order, wrapping around.
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(first));
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(second));
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(third));
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(first));
+    tree.focus_previous();
+    assert_eq!(tree.focused(), Some(third));
+
+    // Non-interactive widgets never take focus.
+    tree.set_focus(Some(panel));
+    assert_eq!(tree.focused(), None);
+    tree.focus_previous();
+    assert_eq!(tree.focused(), Some(third));
+
+    // Hidden subtrees are skipped and lose focus.
+    tree.set_focus(Some(second));
+    tree.set_visible(panel, false);
+    assert_eq!(tree.focused(), None);
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(third));
+    tree.focus_next();
+    assert_eq!(tree.focused(), Some(third));
+
+    // Removed widgets lose focus.
+    assert!(tree.remove(third));
+    assert_eq!(tree.focused(), None);
+}
+
+#[test]
+fn test_widget_tree_routing() {
+    // The input manager is sized by the config, so load the shipped one.
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../config/fvr_engine.json");
+    let _ = override_config(ConfigOverrides { path: Some(path), ..Default::default() });
+
+    let input = InputManager::default();
+    let mut terminal = Terminal::new((8, 4));
+    let mut tree = WidgetTree::new();
+    let first = tree.insert(ProbeWidget::new((0, 0), true), None).unwrap();
+    let second = tree.insert(ProbeWidget::new((0, 1), true), None).unwrap();
+    let updates = |tree: &WidgetTree, id| tree.get::<ProbeWidget>(id).unwrap().updates;
+
+    // Widgets that are neither hovered nor focused receive no input.
+    let _ = tree.update(&input, &mut terminal).unwrap();
+    assert_eq!((updates(&tree, first), updates(&tree, second)), (0, 0));
+
+    // The focused widget receives input while the mouse is elsewhere.
+    tree.set_focus(Some(second));
+    let _ = tree.update(&input, &mut terminal).unwrap();
+    assert_eq!((updates(&tree, first), updates(&tree, second)), (0, 1));
+
+    tree.focus_next();
+    let _ = tree.update(&input, &mut terminal).unwrap();
+    assert_eq!((updates(&tree, first), updates(&tree, second)), (1, 1));
+    assert!(tree.events().is_empty());
+}
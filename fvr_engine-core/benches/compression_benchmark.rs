@@ -0,0 +1,66 @@
+use std::io::{Read, Write};
+
+use criterion::*;
+
+use fvr_engine_core::prelude::*;
+
+// Dimensions of the zone-like payload.
+const ZONE_WIDTH: usize = 255;
+const ZONE_HEIGHT: usize = 255;
+
+// Builds a payload resembling a serialized zone: mostly repeated floor tiles with some walls.
+fn zone_payload() -> Vec<u8> {
+    let mut payload = Vec::with_capacity(ZONE_WIDTH * ZONE_HEIGHT * 8);
+
+    for y in 0..ZONE_HEIGHT {
+        for x in 0..ZONE_WIDTH {
+            let tile: &[u8] =
+                if (x * 31 + y * 17) % 11 == 0 { b"{\"g\":\"#\"}" } else { b"{\"g\":\".\"}" };
+            payload.extend_from_slice(tile);
+        }
+    }
+
+    payload
+}
+
+pub fn benchmark(c: &mut Criterion) {
+    let payload = zone_payload();
+    let options = [
+        ("none", CompressionOptions::none()),
+        ("deflate level 1", CompressionOptions::new(CompressionCodec::Deflate, 1)),
+        ("deflate level 6", CompressionOptions::new(CompressionCodec::Deflate, 6)),
+        ("deflate level 9", CompressionOptions::new(CompressionCodec::Deflate, 9)),
+        ("lz4", CompressionOptions::new(CompressionCodec::Lz4, 0)),
+    ];
+
+    for (name, options) in options.iter() {
+        // Benchmark saving the payload.
+        c.bench_with_input(BenchmarkId::new("compressed_save", name), &payload, |b, payload| {
+            b.iter(|| {
+                let mut writer = CompressedWriter::new(Vec::new(), *options).unwrap();
+                writer.write_all(payload).unwrap();
+                let _compressed = writer.finish().unwrap();
+            })
+        });
+
+        let mut writer = CompressedWriter::new(Vec::new(), *options).unwrap();
+        writer.write_all(&payload).unwrap();
+        let compressed = writer.finish().unwrap();
+
+        // Benchmark loading the payload.
+        c.bench_with_input(
+            BenchmarkId::new("compressed_load", name),
+            &compressed,
+            |b, compressed| {
+                b.iter(|| {
+                    let mut reader = CompressedReader::new(compressed.as_slice()).unwrap();
+                    let mut decompressed = Vec::with_capacity(payload.len());
+                    reader.read_to_end(&mut decompressed).unwrap();
+                })
+            },
+        );
+    }
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+use criterion::*;
+use fnv::FnvHashMap;
+
+use fvr_engine_core::prelude::*;
+
+// Builds a metric for a codepoint, distinct per codepoint so that lookups can be checked.
+fn metric(codepoint: i32) -> GlyphMetric {
+    GlyphMetric {
+        codepoint,
+        x: codepoint,
+        y: 0,
+        width: 1,
+        height: 1,
+        x_offset: 0,
+        y_offset: 0,
+        x_advance: 2,
+    }
+}
+
+pub fn benchmark(c: &mut Criterion) {
+    let codepoints: Vec<i32> = CP437_CHARS.iter().map(|&c| c as i32).collect();
+    let table = GlyphMetricTable::from_metrics(codepoints.iter().map(|&c| metric(c)));
+    let map: FnvHashMap<i32, GlyphMetric> = codepoints.iter().map(|&c| (c, metric(c))).collect();
+
+    let mut group = c.benchmark_group("glyph_metric_lookup");
+
+    // Benchmark looking up every cp437 glyph in a hash map.
+    group.bench_with_input("fnv_hash_map", &codepoints, |b, codepoints| {
+        b.iter(|| codepoints.iter().map(|c| map.get(black_box(c)).unwrap().x as i64).sum::<i64>())
+    });
+
+    // Benchmark looking up every cp437 glyph in the table.
+    group.bench_with_input("glyph_metric_table", &codepoints, |b, codepoints| {
+        b.iter(|| {
+            codepoints.iter().map(|&c| table.get(black_box(c)).unwrap().x as i64).sum::<i64>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
@@ -194,17 +194,41 @@ impl AStar {
         M: Map2d<T>,
         T: Map2dType + Into<Passability>,
     {
-        // If the start and end coords are equal, or either are not passable, return.
-        if start == end
-            // TODO: Should we always assume the starting coord is passable?
-            // || *states.get_xy(start) == Passability::Blocked
-            || states.get_xy(end).clone().into() == Passability::Blocked
-        {
+        let cost = |xy| {
+            if states.get_xy(xy).clone().into() == Passability::Blocked {
+                None
+            } else {
+                Some(weights.map_or(1.0, |weights| *weights.get_xy(xy)))
+            }
+        };
+
+        self.push_path_with_cost(start, end, states.dimensions(), cost, points);
+    }
+
+    //-------------------------------------------------------------------------------------------------
+    // Calculates the shortest path between two points and pushes it into a vec, using a cost
+    // function instead of a passability map (e.g. for per-actor costs). The cost function returns
+    // the multiplier of the cost of stepping into a coord, or None if the coord is impassable.
+    //-------------------------------------------------------------------------------------------------
+    pub fn push_path_with_cost<F>(
+        &mut self,
+        start: ICoord,
+        end: ICoord,
+        dimensions: ICoord,
+        cost: F,
+        points: &mut Vec<ICoord>,
+    ) where
+        F: Fn(ICoord) -> Option<f32>,
+    {
+        let in_bounds = |(x, y): ICoord| x >= 0 && y >= 0 && x < dimensions.0 && y < dimensions.1;
+
+        // If the start and end coords are equal, or the end is not passable, return.
+        // TODO: Should we always assume the starting coord is passable?
+        if start == end || !in_bounds(end) || cost(end).is_none() {
             return;
         }
 
         // Check if dimensions of states are different than the previous and refresh if necessary.
-        let dimensions = states.dimensions();
         let adjacency = self.distance.adjacency();
 
         // Always clear the queue.
@@ -276,14 +300,15 @@ impl AStar {
             // Process the node.
             for xy in adjacency.neighbors(node.0) {
                 // Continue if the neighbor is not valid.
-                if !states.in_bounds(xy) {
+                if !in_bounds(xy) {
                     continue;
                 }
 
                 // Continue if the neighbor is not passable.
-                if states.get_xy(xy).clone().into() == Passability::Blocked {
-                    continue;
-                }
+                let multiplier = match cost(xy) {
+                    Some(multiplier) => multiplier,
+                    None => continue,
+                };
 
                 let is_visited = self.nodes.get_xy(xy).is_some();
 
@@ -293,12 +318,7 @@ impl AStar {
                 }
 
                 // Calculate new depth value.
-                let mut depth = self.distance.calculate(node.0, xy);
-
-                if weights.is_some() {
-                    depth *= *weights.as_ref().unwrap().get_xy(xy);
-                }
-
+                let depth = self.distance.calculate(node.0, xy) * multiplier;
                 let depth =
                     OrderedFloat(depth) + self.nodes.get_xy(node.0).as_ref().unwrap().depth;
 
@@ -354,3 +374,37 @@ impl AStar {
         points
     }
 }
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_a_star_push_path_with_cost() {
+    // A wall along x = 2 with a costly gap at (2, 0) and a free gap at (2, 4).
+    let cost = |door_cost: Option<f32>| {
+        move |(x, y): ICoord| match (x, y) {
+            (2, 0) => door_cost,
+            (2, 4) => Some(1.0),
+            (2, _) => None,
+            _ => Some(1.0),
+        }
+    };
+
+    let mut a_star = AStar::new(Distance::Manhattan);
+    let mut path = Vec::new();
+
+    // A cheap gap is the shortest route.
+    a_star.push_path_with_cost((0, 0), (4, 0), (5, 5), cost(Some(1.0)), &mut path);
+    assert!(path.contains(&(2, 0)));
+
+    // A costly gap is avoided in favor of the longer route.
+    path.clear();
+    a_star.push_path_with_cost((0, 0), (4, 0), (5, 5), cost(Some(100.0)), &mut path);
+    assert!(path.contains(&(2, 4)));
+
+    // An impassable end produces no path.
+    path.clear();
+    a_star.push_path_with_cost((0, 0), (2, 1), (5, 5), cost(None), &mut path);
+    assert!(path.is_empty());
+}
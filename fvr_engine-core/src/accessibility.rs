@@ -0,0 +1,132 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::sync::RwLock;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::config::*;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+static ACCESSIBILITY: Lazy<RwLock<AccessibilityOptions>> =
+    Lazy::new(|| RwLock::new(CONFIG.accessibility.clone()));
+
+//-------------------------------------------------------------------------------------------------
+// Accessibility options for reducing motion and avoiding photosensitivity triggers.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AccessibilityOptions {
+    // Whether to disable screen shake and other camera motion.
+    pub reduce_motion: bool,
+    // Whether to disable flashing and blinking effects.
+    pub disable_flashing: bool,
+    // Max speed multiplier for animations (1.0 is normal speed).
+    pub max_animation_speed: f32,
+}
+
+impl Default for AccessibilityOptions {
+    //---------------------------------------------------------------------------------------------
+    // Returns options with every effect enabled at normal speed.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self { reduce_motion: false, disable_flashing: false, max_animation_speed: 1.0 }
+    }
+}
+
+impl AccessibilityOptions {
+    //---------------------------------------------------------------------------------------------
+    // Returns an animation speed multiplier capped by the max animation speed.
+    //---------------------------------------------------------------------------------------------
+    pub fn animation_speed(&self, speed: f32) -> f32 {
+        speed.min(self.max_animation_speed.max(0.0))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a frame delta time scaled for animations running at normal speed.
+    //---------------------------------------------------------------------------------------------
+    pub fn animation_dt(&self, dt: Duration) -> Duration {
+        dt.mul_f32(self.animation_speed(1.0))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Accessibility exposes a static API and is the single query point for accessibility options.
+// Animations, particles, weather and text effects should consult it rather than the config.
+//-------------------------------------------------------------------------------------------------
+pub struct Accessibility;
+
+impl Accessibility {
+    //---------------------------------------------------------------------------------------------
+    // Returns a copy of the current options.
+    //---------------------------------------------------------------------------------------------
+    pub fn options() -> AccessibilityOptions {
+        ACCESSIBILITY.read().expect("Failed to read accessibility options.").clone()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the current options (initially loaded from the config).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_options(options: AccessibilityOptions) {
+        *ACCESSIBILITY.write().expect("Failed to write accessibility options.") = options;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether screen shake and other camera motion should be applied.
+    //---------------------------------------------------------------------------------------------
+    pub fn motion_enabled() -> bool {
+        !ACCESSIBILITY.read().expect("Failed to read accessibility options.").reduce_motion
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether flashing and blinking effects should be applied.
+    //---------------------------------------------------------------------------------------------
+    pub fn flashing_enabled() -> bool {
+        !ACCESSIBILITY.read().expect("Failed to read accessibility options.").disable_flashing
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an animation speed multiplier capped by the max animation speed.
+    //---------------------------------------------------------------------------------------------
+    pub fn animation_speed(speed: f32) -> f32 {
+        ACCESSIBILITY.read().expect("Failed to read accessibility options.").animation_speed(speed)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a frame delta time scaled for animations running at normal speed.
+    //---------------------------------------------------------------------------------------------
+    pub fn animation_dt(dt: Duration) -> Duration {
+        ACCESSIBILITY.read().expect("Failed to read accessibility options.").animation_dt(dt)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_accessibility_options() {
+    let mut options = AccessibilityOptions::default();
+    assert_eq!(options.animation_speed(2.0), 1.0);
+    assert_eq!(options.animation_dt(Duration::from_millis(10)), Duration::from_millis(10));
+
+    options.max_animation_speed = 0.5;
+    assert_eq!(options.animation_speed(0.25), 0.25);
+    assert_eq!(options.animation_dt(Duration::from_millis(10)), Duration::from_millis(5));
+
+    // Missing fields take their defaults.
+    let options: AccessibilityOptions =
+        serde_json::from_str(r#"{"reduce_motion": true}"#).unwrap();
+    assert!(options.reduce_motion);
+    assert!(!options.disable_flashing);
+}
@@ -0,0 +1,127 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::palette_color::*;
+use crate::tile::*;
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type AmbientAnimationId = u16;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Ids of the ambient animations.
+pub const TORCH_ANIMATION: AmbientAnimationId = 0;
+pub const PORTAL_ANIMATION: AmbientAnimationId = 1;
+pub const WATER_ANIMATION: AmbientAnimationId = 2;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Ambient animations, indexed by id.
+static AMBIENT_ANIMATIONS: [AmbientAnimation; 3] = [
+    AmbientAnimation {
+        frames: &[],
+        frame_duration: Duration::from_millis(0),
+        oscillation: Some(ColorOscillation {
+            color: PaletteColor::Yellow.const_into(),
+            period: Duration::from_millis(370),
+            amount: 0.8,
+        }),
+    },
+    AmbientAnimation {
+        frames: &['○', '☼', '◙', '☼'],
+        frame_duration: Duration::from_millis(150),
+        oscillation: Some(ColorOscillation {
+            color: PaletteColor::BrightMagenta.const_into(),
+            period: Duration::from_millis(1100),
+            amount: 0.6,
+        }),
+    },
+    AmbientAnimation {
+        frames: &['≈', '~'],
+        frame_duration: Duration::from_millis(700),
+        oscillation: Some(ColorOscillation {
+            color: PaletteColor::BrightCyan.const_into(),
+            period: Duration::from_millis(2400),
+            amount: 0.4,
+        }),
+    },
+];
+
+//-------------------------------------------------------------------------------------------------
+// Oscillation of a foreground color towards another color and back.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct ColorOscillation {
+    // The color oscillated towards.
+    pub color: TileColor,
+    // Duration of a full oscillation.
+    pub period: Duration,
+    // How far towards the color the foreground gets (in the range [0.0, 1.0]).
+    pub amount: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// An idle animation of a thing's tile: cycling glyph frames and/or an oscillating color.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct AmbientAnimation {
+    // Glyph frames cycled through (the tile's own glyph is kept if empty).
+    pub frames: &'static [char],
+    // Duration of each glyph frame.
+    pub frame_duration: Duration,
+    // Oscillation of the foreground color, if any.
+    pub oscillation: Option<ColorOscillation>,
+}
+
+impl AmbientAnimation {
+    //---------------------------------------------------------------------------------------------
+    // Returns the animation with an id, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(id: AmbientAnimationId) -> Option<&'static AmbientAnimation> {
+        AMBIENT_ANIMATIONS.get(id as usize)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of ambient animations.
+    //---------------------------------------------------------------------------------------------
+    pub fn count() -> usize {
+        AMBIENT_ANIMATIONS.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the glyph and foreground color of the animation at a time, given the base ones.
+    //---------------------------------------------------------------------------------------------
+    pub fn sample(&self, elapsed: Duration, glyph: char, color: TileColor) -> (char, TileColor) {
+        let glyph = if self.frames.is_empty() || self.frame_duration.is_zero() {
+            glyph
+        } else {
+            let frame = elapsed.as_millis() / self.frame_duration.as_millis();
+            self.frames[frame as usize % self.frames.len()]
+        };
+
+        let color = match self.oscillation {
+            Some(oscillation) if !oscillation.period.is_zero() => {
+                let phase = elapsed.as_secs_f32() / oscillation.period.as_secs_f32();
+                let t = (1.0 - (phase * TAU).cos()) * 0.5 * oscillation.amount.clamp(0.0, 1.0);
+                let (from, to) = (color.0, oscillation.color.0);
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                TileColor::rgba(mix(from.r, to.r), mix(from.g, to.g), mix(from.b, to.b), from.a)
+            }
+            _ => color,
+        };
+
+        (glyph, color)
+    }
+}
@@ -0,0 +1,320 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fs;
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{Context, Result};
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::adjacency::*;
+use crate::map2d::*;
+use crate::misc::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Bits of the neighbor masks, in the order of the eight way adjacencies.
+pub const AUTOTILE_NORTH: u8 = 1 << 0;
+pub const AUTOTILE_NORTHEAST: u8 = 1 << 1;
+pub const AUTOTILE_EAST: u8 = 1 << 2;
+pub const AUTOTILE_SOUTHEAST: u8 = 1 << 3;
+pub const AUTOTILE_SOUTH: u8 = 1 << 4;
+pub const AUTOTILE_SOUTHWEST: u8 = 1 << 5;
+pub const AUTOTILE_WEST: u8 = 1 << 6;
+pub const AUTOTILE_NORTHWEST: u8 = 1 << 7;
+
+// Masks of the cardinal neighbors and all neighbors.
+pub const AUTOTILE_CARDINALS: u8 = AUTOTILE_NORTH | AUTOTILE_EAST | AUTOTILE_SOUTH | AUTOTILE_WEST;
+pub const AUTOTILE_ALL: u8 = 0xFF;
+
+// Glyphs of the box drawing presets, indexed by the 4-bit mask of the cardinal neighbors
+// (north = 1, east = 2, south = 4, west = 8).
+const SINGLE_LINE_GLYPHS: [char; 16] =
+    ['■', '│', '─', '└', '│', '│', '┌', '├', '─', '┘', '─', '┴', '┐', '┤', '┬', '┼'];
+const DOUBLE_LINE_GLYPHS: [char; 16] =
+    ['■', '║', '═', '╚', '║', '║', '╔', '╠', '═', '╝', '═', '╩', '╗', '╣', '╦', '╬'];
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the neighbors considered when computing a mask.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AutotileNeighbors {
+    // Only the cardinal neighbors (a 4-bit mask).
+    Four,
+    // The cardinal and diagonal neighbors (an 8-bit mask). Diagonal neighbors only count when
+    // both of the cardinal neighbors beside them are also set, so that lone diagonal walls do not
+    // multiply the # of variants a ruleset has to handle.
+    Eight,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A rule mapping neighbor masks to a glyph. A rule matches when the bits of a mask it cares about
+// are equal to the bits of its own mask.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AutotileRule {
+    // The required state of the bits cared about.
+    pub mask: u8,
+    // The bits cared about.
+    pub care: u8,
+    // The glyph of a matching cell.
+    pub glyph: char,
+}
+
+impl AutotileRule {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new rule.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(mask: u8, care: u8, glyph: char) -> Self {
+        Self { mask: mask & care, care, glyph }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the rule matches a mask.
+    //---------------------------------------------------------------------------------------------
+    pub fn matches(&self, mask: u8) -> bool {
+        mask & self.care == self.mask
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// AutotileRuleset picks the glyph variant of a wall from which of its neighbors are also walls.
+// Rules are matched in order, so more specific rules should come first.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutotileRuleset {
+    // The neighbors considered.
+    pub neighbors: AutotileNeighbors,
+    // Whether coords outside of the map count as walls.
+    #[serde(default)]
+    pub edges_connect: bool,
+    // The rules, matched in order.
+    pub rules: Vec<AutotileRule>,
+    // The glyph of cells no rule matches.
+    pub fallback: char,
+}
+
+impl Default for AutotileRuleset {
+    fn default() -> Self {
+        Self::single_line()
+    }
+}
+
+impl AutotileRuleset {
+    //---------------------------------------------------------------------------------------------
+    // Helper function that creates a cardinal ruleset from a table of 16 glyphs.
+    //---------------------------------------------------------------------------------------------
+    fn from_cardinal_glyphs(glyphs: &[char; 16]) -> Self {
+        let rules = glyphs
+            .iter()
+            .enumerate()
+            .map(|(i, glyph)| {
+                let mut mask = 0;
+
+                for (bit, cardinal) in
+                    [AUTOTILE_NORTH, AUTOTILE_EAST, AUTOTILE_SOUTH, AUTOTILE_WEST]
+                        .iter()
+                        .enumerate()
+                {
+                    if i & (1 << bit) != 0 {
+                        mask |= cardinal;
+                    }
+                }
+
+                AutotileRule::new(mask, AUTOTILE_CARDINALS, *glyph)
+            })
+            .collect();
+
+        Self {
+            neighbors: AutotileNeighbors::Four,
+            edges_connect: false,
+            rules,
+            fallback: glyphs[0],
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a ruleset of single line box drawing glyphs.
+    //---------------------------------------------------------------------------------------------
+    pub fn single_line() -> Self {
+        Self::from_cardinal_glyphs(&SINGLE_LINE_GLYPHS)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a ruleset of double line box drawing glyphs.
+    //---------------------------------------------------------------------------------------------
+    pub fn double_line() -> Self {
+        Self::from_cardinal_glyphs(&DOUBLE_LINE_GLYPHS)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads a ruleset from a json file.
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read autotile ruleset {}.", path.display()))?;
+
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse autotile ruleset {}.", path.display()))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Computes the neighbor mask of a coord, given whether each coord is a wall.
+    //---------------------------------------------------------------------------------------------
+    pub fn mask<F>(&self, xy: ICoord, mut is_wall: F) -> u8
+    where
+        F: FnMut(ICoord) -> bool,
+    {
+        let adjacency = match self.neighbors {
+            AutotileNeighbors::Four => Adjacency::Cardinals,
+            AutotileNeighbors::Eight => Adjacency::EightWay,
+        };
+
+        let mut mask = 0;
+
+        for direction in adjacency.iter() {
+            if is_wall((xy.0 + direction.dx(), xy.1 + direction.dy())) {
+                mask |= 1 << direction.orientation() as u8;
+            }
+        }
+
+        // Drop the diagonals not flanked by two set cardinals.
+        let flanks = [
+            (AUTOTILE_NORTHEAST, AUTOTILE_NORTH | AUTOTILE_EAST),
+            (AUTOTILE_SOUTHEAST, AUTOTILE_SOUTH | AUTOTILE_EAST),
+            (AUTOTILE_SOUTHWEST, AUTOTILE_SOUTH | AUTOTILE_WEST),
+            (AUTOTILE_NORTHWEST, AUTOTILE_NORTH | AUTOTILE_WEST),
+        ];
+
+        for (diagonal, cardinals) in flanks.iter() {
+            if mask & cardinals != *cardinals {
+                mask &= !diagonal;
+            }
+        }
+
+        mask
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Computes the neighbor mask of a coord in a boolean wall map.
+    //---------------------------------------------------------------------------------------------
+    pub fn mask_in<M>(&self, walls: &M, xy: ICoord) -> u8
+    where
+        M: Map2d<bool>,
+    {
+        self.mask(
+            xy,
+            |xy| if walls.in_bounds(xy) { *walls.get_xy(xy) } else { self.edges_connect },
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the glyph of a neighbor mask.
+    //---------------------------------------------------------------------------------------------
+    pub fn glyph(&self, mask: u8) -> char {
+        self.rules.iter().find(|rule| rule.matches(mask)).map_or(self.fallback, |rule| rule.glyph)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the glyph of a coord in a boolean wall map.
+    //---------------------------------------------------------------------------------------------
+    pub fn glyph_in<M>(&self, walls: &M, xy: ICoord) -> char
+    where
+        M: Map2d<bool>,
+    {
+        self.glyph(self.mask_in(walls, xy))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the glyph of every wall in a boolean wall map to its variant in a map of glyphs.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply<M, G>(&self, walls: &M, glyphs: &mut G)
+    where
+        M: Map2d<bool>,
+        G: Map2d<char>,
+    {
+        for y in 0..walls.height().min(glyphs.height()) {
+            for x in 0..walls.width().min(glyphs.width()) {
+                if *walls.get_xy((x, y)) {
+                    *glyphs.get_xy_mut((x, y)) = self.glyph_in(walls, (x, y));
+                }
+            }
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_autotile() {
+    use crate::grid_map::*;
+
+    // A 5x4 room with a dividing wall:
+    // #####
+    // #.#.#
+    // #####
+    // ....#
+    let layout = ["#####", "#.#.#", "#####", "....#"];
+    let mut walls = GridMap::new((5, 4));
+
+    for (y, row) in layout.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            *walls.get_xy_mut((x as i32, y as i32)) = c == '#';
+        }
+    }
+
+    let ruleset = AutotileRuleset::single_line();
+    let mut glyphs: GridMap<char> = GridMap::new((5, 4));
+    ruleset.apply(&walls, &mut glyphs);
+
+    let expected = ["┌─┬─┐", "│ │ │", "└─┴─┤", "    │"];
+
+    for (y, row) in expected.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let glyph = *glyphs.get_xy((x as i32, y as i32));
+            assert_eq!(if c == ' ' { '\0' } else { c }, glyph);
+        }
+    }
+
+    // Isolated walls fall back to a pillar, and edges can connect.
+    let mut pillar = GridMap::new((1, 1));
+    *pillar.get_xy_mut((0, 0)) = true;
+    assert_eq!(ruleset.glyph_in(&pillar, (0, 0)), '■');
+
+    let connected = AutotileRuleset { edges_connect: true, ..AutotileRuleset::double_line() };
+    assert_eq!(connected.glyph_in(&pillar, (0, 0)), '╬');
+
+    // Eight way masks drop diagonals not flanked by two cardinals, and specific rules win.
+    let solid = AutotileRuleset {
+        neighbors: AutotileNeighbors::Eight,
+        edges_connect: false,
+        rules: vec![
+            AutotileRule::new(AUTOTILE_ALL, AUTOTILE_ALL, '█'),
+            AutotileRule::new(0, 0, '#'),
+        ],
+        fallback: '?',
+    };
+
+    let mut block = GridMap::new((3, 3));
+    block.data_mut().fill(true);
+    assert_eq!(solid.glyph_in(&block, (1, 1)), '█');
+    assert_eq!(solid.glyph_in(&block, (0, 0)), '#');
+    assert_eq!(solid.mask_in(&block, (0, 0)), AUTOTILE_EAST | AUTOTILE_SOUTHEAST | AUTOTILE_SOUTH);
+
+    *block.get_xy_mut((1, 0)) = false;
+    assert_eq!(solid.mask_in(&block, (0, 0)), AUTOTILE_SOUTH);
+}
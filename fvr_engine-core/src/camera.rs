@@ -0,0 +1,335 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::accessibility::*;
+use crate::map2d::*;
+use crate::misc::*;
+use crate::rect::*;
+use crate::sub_map::*;
+
+//-------------------------------------------------------------------------------------------------
+// Camera tracks the window of a larger map (e.g. a zone) that is visible in the terminal.
+// It follows a target once the target leaves a dead zone around the center of the view, easing
+// towards it (unless motion is reduced), and keeps the view within the bounds of the map.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Camera {
+    // Dimensions (in tiles) of the view.
+    dimensions: ICoord,
+    // Bounds the view is kept within, if any.
+    bounds: Option<Rect>,
+    // Precise center of the view, in tiles (the center of tile (x, y) is (x + 0.5, y + 0.5)).
+    center: (f32, f32),
+    // Coord followed by the view, if any.
+    target: Option<ICoord>,
+    // Max distance of the target from the center on each axis before the view follows.
+    dead_zone: ICoord,
+    // Rate (per second) at which the view closes the distance to the target, or 0 to snap.
+    smoothing: f32,
+}
+
+impl Camera {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new camera with a view of some dimensions, centered on the origin.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord) -> Self {
+        Self {
+            dimensions,
+            bounds: None,
+            center: (0.5, 0.5),
+            target: None,
+            dead_zone: (0, 0),
+            smoothing: 0.0,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the camera with its view kept within bounds.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.set_bounds(Some(bounds));
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the camera with a dead zone the target can move within without the view following.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_dead_zone(mut self, dead_zone: ICoord) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the camera easing towards its target at a rate per second.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.max(0.0);
+        self
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the view.
+    //---------------------------------------------------------------------------------------------
+    pub fn dimensions(&self) -> ICoord {
+        self.dimensions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the dimensions of the view (e.g. after the terminal is resized).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_dimensions(&mut self, dimensions: ICoord) {
+        self.dimensions = dimensions;
+        self.center = self.clamp_center(self.center);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the bounds the view is kept within, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the bounds the view is kept within (e.g. when entering a zone of another size).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_bounds(&mut self, bounds: Option<Rect>) {
+        self.bounds = bounds;
+        self.center = self.clamp_center(self.center);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord followed by the view, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn target(&self) -> Option<ICoord> {
+        self.target
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the coord followed by the view (should be called whenever the target moves).
+    //---------------------------------------------------------------------------------------------
+    pub fn follow(&mut self, target: ICoord) {
+        self.target = Some(target);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops following the target, leaving the view where it is.
+    //---------------------------------------------------------------------------------------------
+    pub fn unfollow(&mut self) {
+        self.target = None;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Centers the view on a coord immediately (e.g. after teleporting or entering a zone).
+    //---------------------------------------------------------------------------------------------
+    pub fn snap_to(&mut self, xy: ICoord) {
+        self.center = self.clamp_center(Self::tile_center(xy));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the precise center of a tile.
+    //---------------------------------------------------------------------------------------------
+    fn tile_center(xy: ICoord) -> (f32, f32) {
+        (xy.0 as f32 + 0.5, xy.1 as f32 + 0.5)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that clamps a center on one axis so that the view stays within the bounds
+    // (or centers the view on the bounds if they are smaller than the view).
+    //---------------------------------------------------------------------------------------------
+    fn clamp_axis(center: f32, view: i32, start: i32, len: i32) -> f32 {
+        let half = view as f32 / 2.0;
+        let (min, max) = (start as f32 + half, (start + len) as f32 - half);
+
+        if min >= max {
+            start as f32 + len as f32 / 2.0
+        } else {
+            center.clamp(min, max)
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that clamps a center so that the view stays within the bounds.
+    //---------------------------------------------------------------------------------------------
+    fn clamp_center(&self, center: (f32, f32)) -> (f32, f32) {
+        match self.bounds {
+            Some(bounds) => (
+                Self::clamp_axis(center.0, self.dimensions.0, bounds.x, bounds.width),
+                Self::clamp_axis(center.1, self.dimensions.1, bounds.y, bounds.height),
+            ),
+            None => center,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the view towards the target by a frame delta time. The view snaps to the target when
+    // smoothing is disabled or motion is reduced.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) {
+        let target = match self.target {
+            Some(target) => Self::tile_center(target),
+            None => return,
+        };
+
+        // Only follow the target as far as needed to bring it back within the dead zone.
+        let follow_axis = |center: f32, target: f32, dead_zone: i32| {
+            let dead_zone = dead_zone as f32;
+
+            if target - center > dead_zone {
+                target - dead_zone
+            } else if center - target > dead_zone {
+                target + dead_zone
+            } else {
+                center
+            }
+        };
+        let desired = self.clamp_center((
+            follow_axis(self.center.0, target.0, self.dead_zone.0),
+            follow_axis(self.center.1, target.1, self.dead_zone.1),
+        ));
+
+        if self.smoothing <= 0.0 || !Accessibility::motion_enabled() {
+            self.center = desired;
+            return;
+        }
+
+        let t = 1.0 - (-self.smoothing * dt.as_secs_f32()).exp();
+        self.center = (
+            self.center.0 + (desired.0 - self.center.0) * t,
+            self.center.1 + (desired.1 - self.center.1) * t,
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the region of the map that is visible.
+    //---------------------------------------------------------------------------------------------
+    pub fn view(&self) -> Rect {
+        let origin = (
+            (self.center.0 - self.dimensions.0 as f32 / 2.0).round() as i32,
+            (self.center.1 - self.dimensions.1 as f32 / 2.0).round() as i32,
+        );
+
+        Rect::new(origin, self.dimensions.0, self.dimensions.1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the map coord at the origin of the view (what scenes subtract to draw map coords).
+    //---------------------------------------------------------------------------------------------
+    pub fn offset(&self) -> ICoord {
+        self.view().origin()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Converts a map coord into a view coord, if it is visible.
+    //---------------------------------------------------------------------------------------------
+    pub fn map_to_view(&self, xy: ICoord) -> Option<ICoord> {
+        self.view().extract_xy(xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Converts a view coord (e.g. under the mouse) into a map coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn view_to_map(&self, xy: ICoord) -> ICoord {
+        self.view().insert_xy(xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies the visible window of a map into a region of another map (e.g. the terminal) at an
+    // origin. Coords of the view outside the source map are filled with a value.
+    //---------------------------------------------------------------------------------------------
+    pub fn blit<S, M, T>(&self, source: &S, target: &mut M, origin: ICoord, fill: &T)
+    where
+        S: Map2dView<Type = T>,
+        M: Map2dView<Type = T> + Map2dViewMut<Type = T>,
+        T: Map2dType,
+    {
+        let view = self.view();
+        let region = Rect::new(origin, view.width, view.height);
+
+        // Only copy the part of the view that fits in the target.
+        let region = match region.intersection(&Rect::new((0, 0), target.width(), target.height()))
+        {
+            Some(region) => region,
+            None => return,
+        };
+        let skipped = (region.x - origin.0, region.y - origin.1);
+        let mut sub_map = SubMap::new(target, region);
+
+        for y in 0..region.height {
+            for x in 0..region.width {
+                let xy = view.insert_xy((x + skipped.0, y + skipped.1));
+                let value = if source.in_bounds(xy) { source.get_xy(xy) } else { fill };
+
+                *sub_map.get_xy_mut((x, y)) = value.clone();
+            }
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_camera_dead_zone() {
+    let mut camera = Camera::new((11, 9)).with_dead_zone((2, 1));
+    camera.snap_to((20, 20));
+    camera.follow((22, 21));
+    camera.update(Duration::from_millis(16));
+    assert_eq!(camera.offset(), (15, 16));
+
+    // Leaving the dead zone moves the view only as far as needed.
+    camera.follow((24, 23));
+    camera.update(Duration::from_millis(16));
+    assert_eq!(camera.view_to_map((5, 4)), (22, 22));
+    assert_eq!(camera.map_to_view((24, 23)), Some((7, 5)));
+    assert_eq!(camera.map_to_view((0, 0)), None);
+}
+
+#[test]
+fn test_camera_bounds() {
+    let bounds = Rect::new((0, 0), 40, 30);
+    let mut camera = Camera::new((10, 10)).with_bounds(bounds);
+
+    camera.snap_to((1, 1));
+    assert_eq!(camera.offset(), (0, 0));
+
+    camera.follow((39, 29));
+    camera.update(Duration::from_millis(16));
+    assert_eq!(camera.view(), Rect::new((30, 20), 10, 10));
+
+    // Bounds smaller than the view center the view on them.
+    camera.set_bounds(Some(Rect::new((0, 0), 6, 20)));
+    assert_eq!(camera.offset(), (-2, 10));
+}
+
+#[test]
+fn test_camera_blit() {
+    use crate::grid_map::*;
+
+    let mut source = GridMap::<i32>::new((8, 8));
+
+    for (i, value) in source.data_mut().iter_mut().enumerate() {
+        *value = i as i32;
+    }
+
+    let mut camera = Camera::new((4, 3)).with_bounds(Rect::new((0, 0), 8, 8));
+    camera.snap_to((7, 7));
+
+    // The view's bottom right corner of the source, drawn at the edge of the target.
+    let mut target = GridMap::<i32>::new((6, 6));
+    camera.blit(&source, &mut target, (3, 2), &-1);
+    assert_eq!(*target.get_xy((3, 2)), 44);
+    assert_eq!(*target.get_xy((5, 4)), 62);
+    assert_eq!(*target.get_xy((2, 2)), 0);
+
+    // Coords outside the source are filled.
+    let camera = Camera::new((4, 3));
+    camera.blit(&source, &mut target, (0, 0), &-1);
+    assert_eq!(*target.get_xy((0, 0)), -1);
+    assert_eq!(*target.get_xy((3, 2)), 9);
+}
@@ -0,0 +1,150 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Charges tracks a regenerating pool of uses (e.g. wand charges or ability cooldowns).
+// A cooldown is a pool with a single charge that regenerates over a number of turns.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Charges {
+    // Maximum number of charges.
+    pub max: u32,
+    // Current number of charges.
+    pub current: u32,
+    // Charges regenerated per turn (fractional values take multiple turns per charge).
+    pub regen_per_turn: f32,
+    // Charges consumed per use.
+    pub cost: u32,
+    // Progress towards the next charge.
+    #[serde(default)]
+    progress: f32,
+}
+
+impl Charges {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, full pool of charges.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(max: u32, regen_per_turn: f32, cost: u32) -> Self {
+        Self { max, current: max, regen_per_turn, cost, progress: 0.0 }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, ready cooldown that takes a number of turns to recover after each use.
+    //---------------------------------------------------------------------------------------------
+    pub fn cooldown(turns: u32) -> Self {
+        Self::new(1, 1.0 / turns.max(1) as f32, 1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are enough charges for a use.
+    //---------------------------------------------------------------------------------------------
+    pub fn can_use(&self) -> bool {
+        self.current >= self.cost
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Consumes the charges for a use, returning whether there were enough.
+    //---------------------------------------------------------------------------------------------
+    pub fn try_use(&mut self) -> bool {
+        if !self.can_use() {
+            return false;
+        }
+
+        self.current -= self.cost;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the pool is full.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_full(&self) -> bool {
+        self.current >= self.max
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Regenerates the charges for a turn, returning the number of charges regained.
+    //---------------------------------------------------------------------------------------------
+    pub fn tick(&mut self) -> u32 {
+        if self.is_full() {
+            self.progress = 0.0;
+            return 0;
+        }
+
+        self.progress += self.regen_per_turn.max(0.0);
+
+        // Guard against rounding leaving the progress just short of a whole charge.
+        let regained =
+            ((self.progress + f32::EPSILON).floor() as u32).min(self.max - self.current);
+        self.current += regained;
+        self.progress =
+            if self.is_full() { 0.0 } else { (self.progress - regained as f32).max(0.0) };
+
+        regained
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the fill of the pool in [0, 1] including progress towards the next charge.
+    // (useful for drawing charge and cooldown indicators)
+    //---------------------------------------------------------------------------------------------
+    pub fn fill(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+
+        ((self.current as f32 + self.progress) / self.max as f32).clamp(0.0, 1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of turns until a use is available (0 if available now, or none if the
+    // charges never regenerate enough for a use).
+    //---------------------------------------------------------------------------------------------
+    pub fn turns_until_ready(&self) -> Option<u32> {
+        if self.can_use() {
+            return Some(0);
+        }
+
+        if self.regen_per_turn <= 0.0 || self.cost > self.max {
+            return None;
+        }
+
+        let needed = self.cost as f32 - self.current as f32 - self.progress;
+        Some((needed / self.regen_per_turn - f32::EPSILON).ceil().max(1.0) as u32)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_charges_use_and_regen() {
+    let mut charges = Charges::new(3, 0.5, 2);
+    assert!(charges.try_use());
+    assert_eq!(charges.current, 1);
+    assert!(!charges.try_use());
+    assert_eq!(charges.turns_until_ready(), Some(2));
+
+    assert_eq!(charges.tick(), 0);
+    assert_eq!(charges.tick(), 1);
+    assert!(charges.can_use());
+    assert_eq!(charges.tick(), 0);
+    assert_eq!(charges.tick(), 1);
+    assert!(charges.is_full());
+    assert_eq!(charges.tick(), 0);
+    assert_eq!(charges.fill(), 1.0);
+}
+
+#[test]
+fn test_charges_cooldown() {
+    let mut cooldown = Charges::cooldown(3);
+    assert!(cooldown.try_use());
+    assert_eq!(cooldown.turns_until_ready(), Some(3));
+
+    assert_eq!(cooldown.tick(), 0);
+    assert_eq!(cooldown.tick(), 0);
+    assert_eq!(cooldown.turns_until_ready(), Some(1));
+    assert_eq!(cooldown.tick(), 1);
+    assert!(cooldown.can_use());
+}
@@ -0,0 +1,125 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::tile::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Weights of the RGB channels for calculating luma (matching the shaders).
+const LUMA_WEIGHTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+//-------------------------------------------------------------------------------------------------
+// ColorGrade describes the grading applied to the entire rendered frame, so that zones and scenes
+// can set a mood (e.g. a cold dungeon or a desaturated flashback) without touching tile data.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ColorGrade {
+    // Color multiplied with the frame (white for no tint).
+    pub tint: TileColor,
+    // Multiplier of the frame's brightness (1.0 for unchanged).
+    pub brightness: f32,
+    // Multiplier of the frame's saturation (0.0 for greyscale, 1.0 for unchanged).
+    pub saturation: f32,
+}
+
+impl ColorGrade {
+    //---------------------------------------------------------------------------------------------
+    // Constants.
+    //---------------------------------------------------------------------------------------------
+    pub const NEUTRAL: ColorGrade =
+        ColorGrade { tint: TileColor::WHITE, brightness: 1.0, saturation: 1.0 };
+    pub const COLD: ColorGrade = ColorGrade {
+        tint: TileColor(SdlColor { r: 200, g: 220, b: 255, a: 255 }),
+        brightness: 0.9,
+        saturation: 0.8,
+    };
+    pub const WARM: ColorGrade = ColorGrade {
+        tint: TileColor(SdlColor { r: 255, g: 215, b: 190, a: 255 }),
+        brightness: 1.0,
+        saturation: 1.1,
+    };
+    pub const DESATURATED: ColorGrade =
+        ColorGrade { tint: TileColor::WHITE, brightness: 0.9, saturation: 0.15 };
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the grade leaves colors unchanged.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::NEUTRAL
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the tint as normalized [R, G, B] floats.
+    //---------------------------------------------------------------------------------------------
+    pub fn tint_rgb(&self) -> [f32; 3] {
+        [self.tint.0.r as f32 / 255.0, self.tint.0.g as f32 / 255.0, self.tint.0.b as f32 / 255.0]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the grade interpolated towards another grade (e.g. for fading between zones).
+    //---------------------------------------------------------------------------------------------
+    pub fn lerp(&self, other: &ColorGrade, t: f32) -> ColorGrade {
+        let t = t.clamp(0.0, 1.0);
+        let lerp_u8 = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        ColorGrade {
+            tint: TileColor::rgb(
+                lerp_u8(self.tint.0.r, other.tint.0.r),
+                lerp_u8(self.tint.0.g, other.tint.0.g),
+                lerp_u8(self.tint.0.b, other.tint.0.b),
+            ),
+            brightness: self.brightness + (other.brightness - self.brightness) * t,
+            saturation: self.saturation + (other.saturation - self.saturation) * t,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies the grade to a color, as the shaders do (e.g. for the clear color).
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, color: &TileColor) -> TileColor {
+        let rgb = [color.0.r as f32, color.0.g as f32, color.0.b as f32];
+        let luma: f32 = rgb.iter().zip(LUMA_WEIGHTS.iter()).map(|(c, w)| c * w).sum();
+        let tint = self.tint_rgb();
+
+        let grade = |i: usize| {
+            let saturated = luma + (rgb[i] - luma) * self.saturation;
+            (saturated * tint[i] * self.brightness).round().clamp(0.0, 255.0) as u8
+        };
+
+        TileColor::rgba(grade(0), grade(1), grade(2), color.0.a)
+    }
+}
+
+impl Default for ColorGrade {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_color_grade() {
+    let color = TileColor::rgba(200, 100, 50, 128);
+    assert_eq!(ColorGrade::NEUTRAL.apply(&color), color);
+
+    // Fully desaturated colors are grey.
+    let grey = ColorGrade { saturation: 0.0, ..ColorGrade::NEUTRAL }.apply(&color);
+    assert!(grey.0.r == grey.0.g && grey.0.g == grey.0.b);
+    assert_eq!(grey.0.a, 128);
+
+    let half = ColorGrade::NEUTRAL.lerp(&ColorGrade::DESATURATED, 0.5);
+    assert!((half.saturation - 0.575).abs() < 0.001);
+    assert_eq!(ColorGrade::NEUTRAL.lerp(&ColorGrade::COLD, 1.0), ColorGrade::COLD);
+    assert!(ColorGrade::default().is_neutral());
+}
@@ -0,0 +1,226 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+
+//-------------------------------------------------------------------------------------------------
+// Command describes a reversible change to a target.
+//-------------------------------------------------------------------------------------------------
+pub trait Command<T> {
+    //---------------------------------------------------------------------------------------------
+    // Applies the change to the target.
+    //---------------------------------------------------------------------------------------------
+    fn execute(&mut self, target: &mut T);
+
+    //---------------------------------------------------------------------------------------------
+    // Reverts the change to the target.
+    //---------------------------------------------------------------------------------------------
+    fn undo(&mut self, target: &mut T);
+}
+
+//-------------------------------------------------------------------------------------------------
+// A group of commands that are undone and redone together.
+//-------------------------------------------------------------------------------------------------
+type CommandGroup<T> = Vec<Box<dyn Command<T>>>;
+
+//-------------------------------------------------------------------------------------------------
+// CommandStack manages undo/redo history for commands that operate on a target.
+// Commands executed between begin_group and end_group are undone and redone as a single step.
+// Once the capacity is reached the oldest steps are discarded.
+//-------------------------------------------------------------------------------------------------
+pub struct CommandStack<T> {
+    // Steps that can be undone (oldest at the front).
+    undo_stack: VecDeque<CommandGroup<T>>,
+    // Steps that can be redone (most recently undone at the back).
+    redo_stack: Vec<CommandGroup<T>>,
+    // The group currently being recorded, if any.
+    open_group: Option<CommandGroup<T>>,
+    // Maximum number of steps kept in the undo history.
+    capacity: usize,
+}
+
+impl<T> CommandStack<T> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new command stack that keeps at most capacity undo steps.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            open_group: None,
+            capacity: capacity.max(1),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pushes a finished step onto the undo stack, discarding the oldest steps if necessary.
+    //---------------------------------------------------------------------------------------------
+    fn push_step(&mut self, step: CommandGroup<T>) {
+        if step.is_empty() {
+            return;
+        }
+
+        self.undo_stack.push_back(step);
+
+        while self.undo_stack.len() > self.capacity {
+            let _ = self.undo_stack.pop_front();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Executes a command and records it, clearing the redo history.
+    //---------------------------------------------------------------------------------------------
+    pub fn execute(&mut self, mut command: Box<dyn Command<T>>, target: &mut T) {
+        command.execute(target);
+        self.redo_stack.clear();
+
+        match self.open_group.as_mut() {
+            Some(group) => group.push(command),
+            None => self.push_step(vec![command]),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Begins recording a group of commands as a single step.
+    // (does nothing if a group is already open)
+    //---------------------------------------------------------------------------------------------
+    pub fn begin_group(&mut self) {
+        if self.open_group.is_none() {
+            self.open_group = Some(Vec::new());
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Finishes recording the open group of commands.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_group(&mut self) {
+        if let Some(group) = self.open_group.take() {
+            self.push_step(group);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a group of commands is being recorded.
+    //---------------------------------------------------------------------------------------------
+    pub fn in_group(&self) -> bool {
+        self.open_group.is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Undoes the most recent step, returning whether anything was undone.
+    // (closes any open group first)
+    //---------------------------------------------------------------------------------------------
+    pub fn undo(&mut self, target: &mut T) -> bool {
+        self.end_group();
+
+        match self.undo_stack.pop_back() {
+            Some(mut step) => {
+                for command in step.iter_mut().rev() {
+                    command.undo(target);
+                }
+
+                self.redo_stack.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Redoes the most recently undone step, returning whether anything was redone.
+    //---------------------------------------------------------------------------------------------
+    pub fn redo(&mut self, target: &mut T) -> bool {
+        self.end_group();
+
+        match self.redo_stack.pop() {
+            Some(mut step) => {
+                for command in step.iter_mut() {
+                    command.execute(target);
+                }
+
+                self.undo_stack.push_back(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there is a step that can be undone.
+    //---------------------------------------------------------------------------------------------
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty() || self.open_group.as_ref().is_some_and(|g| !g.is_empty())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there is a step that can be redone.
+    //---------------------------------------------------------------------------------------------
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the capacity of the undo history.
+    //---------------------------------------------------------------------------------------------
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Clears all history.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.open_group = None;
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+struct AddCommand(i32);
+
+#[cfg(test)]
+impl Command<i32> for AddCommand {
+    fn execute(&mut self, target: &mut i32) {
+        *target += self.0;
+    }
+
+    fn undo(&mut self, target: &mut i32) {
+        *target -= self.0;
+    }
+}
+
+#[test]
+fn test_command_stack() {
+    let mut value = 0;
+    let mut stack = CommandStack::new(2);
+
+    // Test execute/undo/redo.
+    stack.execute(Box::new(AddCommand(1)), &mut value);
+    stack.execute(Box::new(AddCommand(2)), &mut value);
+    assert_eq!(value, 3);
+    assert!(stack.undo(&mut value));
+    assert_eq!(value, 1);
+    assert!(stack.redo(&mut value));
+    assert_eq!(value, 3);
+
+    // Test grouping.
+    stack.begin_group();
+    stack.execute(Box::new(AddCommand(10)), &mut value);
+    stack.execute(Box::new(AddCommand(20)), &mut value);
+    stack.end_group();
+    assert_eq!(value, 33);
+    assert!(stack.undo(&mut value));
+    assert_eq!(value, 3);
+
+    // Test capacity (only two steps are kept).
+    assert!(stack.redo(&mut value));
+    assert!(stack.undo(&mut value));
+    assert!(stack.undo(&mut value));
+    assert!(!stack.undo(&mut value));
+    assert_eq!(value, 1);
+}
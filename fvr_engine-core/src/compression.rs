@@ -0,0 +1,333 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Chain, Cursor, Read, Write};
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{bail, Context, Result};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Magic bytes beginning every file written through a compressed writer.
+pub const COMPRESSION_MAGIC: [u8; 4] = *b"FVRC";
+
+// Version of the header following the magic bytes.
+const COMPRESSION_HEADER_VERSION: u8 = 1;
+
+// Length of the header: magic bytes, version, codec id and level.
+const COMPRESSION_HEADER_LEN: usize = COMPRESSION_MAGIC.len() + 3;
+
+// Default and maximum compression levels.
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+pub const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the codecs saves and zone files can be compressed with.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CompressionCodec {
+    // Stored as is (still headed, so that the codec can be changed without breaking old files).
+    None,
+    // Deflate (as used by the .xp files).
+    Deflate,
+    // LZ4 frames (faster than deflate but larger, and the level is ignored).
+    Lz4,
+}
+
+impl CompressionCodec {
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the codec written to the header.
+    //---------------------------------------------------------------------------------------------
+    pub fn id(&self) -> u8 {
+        match self {
+            CompressionCodec::None => 0,
+            CompressionCodec::Deflate => 1,
+            CompressionCodec::Lz4 => 2,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the codec of an id read from a header.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0 => Ok(CompressionCodec::None),
+            1 => Ok(CompressionCodec::Deflate),
+            2 => Ok(CompressionCodec::Lz4),
+            _ => bail!("Unknown compression codec {}.", id),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Options for compressing saves and zone files.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompressionOptions {
+    // The codec to compress with.
+    pub codec: CompressionCodec,
+    // The level to compress at, from 0 (fastest) to 9 (smallest).
+    pub level: u32,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self { codec: CompressionCodec::Deflate, level: DEFAULT_COMPRESSION_LEVEL }
+    }
+}
+
+impl CompressionOptions {
+    //---------------------------------------------------------------------------------------------
+    // Creates options with a codec and level (clamped to the maximum level).
+    //---------------------------------------------------------------------------------------------
+    pub fn new(codec: CompressionCodec, level: u32) -> Self {
+        Self { codec, level: level.min(MAX_COMPRESSION_LEVEL) }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates options for storing without compression.
+    //---------------------------------------------------------------------------------------------
+    pub fn none() -> Self {
+        Self::new(CompressionCodec::None, 0)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper enum holding the stream a compressed writer writes through.
+//-------------------------------------------------------------------------------------------------
+enum Encoder<W: Write> {
+    None(W),
+    Deflate(DeflateEncoder<W>),
+    Lz4(FrameEncoder<W>),
+}
+
+//-------------------------------------------------------------------------------------------------
+// CompressedWriter writes a header identifying the codec and level, then compresses everything
+// written through it as it is written (so that large files are never held in memory).
+//-------------------------------------------------------------------------------------------------
+pub struct CompressedWriter<W: Write> {
+    // The stream written through.
+    encoder: Encoder<W>,
+}
+
+impl<W: Write> CompressedWriter<W> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new compressed writer, writing the header.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(mut writer: W, options: CompressionOptions) -> Result<Self> {
+        let level = options.level.min(MAX_COMPRESSION_LEVEL);
+
+        writer.write_all(&COMPRESSION_MAGIC)?;
+        writer.write_all(&[COMPRESSION_HEADER_VERSION, options.codec.id(), level as u8])?;
+
+        let encoder = match options.codec {
+            CompressionCodec::None => Encoder::None(writer),
+            CompressionCodec::Deflate => {
+                Encoder::Deflate(DeflateEncoder::new(writer, Compression::new(level)))
+            }
+            CompressionCodec::Lz4 => Encoder::Lz4(FrameEncoder::new(writer)),
+        };
+
+        Ok(Self { encoder })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Finishes compressing, returning the underlying writer.
+    //---------------------------------------------------------------------------------------------
+    pub fn finish(self) -> Result<W> {
+        match self.encoder {
+            Encoder::None(writer) => Ok(writer),
+            Encoder::Deflate(encoder) => encoder.finish().context("Failed to finish compression."),
+            Encoder::Lz4(encoder) => encoder.finish().context("Failed to finish compression."),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    //---------------------------------------------------------------------------------------------
+    // Compresses and writes a buffer.
+    //---------------------------------------------------------------------------------------------
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.encoder {
+            Encoder::None(writer) => writer.write(buf),
+            Encoder::Deflate(encoder) => encoder.write(buf),
+            Encoder::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Flushes the compressed data written so far.
+    //---------------------------------------------------------------------------------------------
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.encoder {
+            Encoder::None(writer) => writer.flush(),
+            Encoder::Deflate(encoder) => encoder.flush(),
+            Encoder::Lz4(encoder) => encoder.flush(),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper enum holding the stream a compressed reader reads through.
+//-------------------------------------------------------------------------------------------------
+enum Decoder<R: Read> {
+    // A stream without a header, read as is (the bytes read looking for a header come first).
+    Raw(Chain<Cursor<Vec<u8>>, R>),
+    None(R),
+    Deflate(DeflateDecoder<R>),
+    Lz4(FrameDecoder<R>),
+}
+
+//-------------------------------------------------------------------------------------------------
+// CompressedReader reads the header written by a compressed writer, then decompresses everything
+// read through it as it is read. Streams without a header are read as is, so that files written
+// before compression was introduced can still be loaded.
+//-------------------------------------------------------------------------------------------------
+pub struct CompressedReader<R: Read> {
+    // The stream read through.
+    decoder: Decoder<R>,
+    // The codec of the stream (none if it has no header).
+    codec: Option<CompressionCodec>,
+}
+
+impl<R: Read> CompressedReader<R> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new compressed reader, reading the header (if any).
+    //---------------------------------------------------------------------------------------------
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut header = Vec::with_capacity(COMPRESSION_HEADER_LEN);
+        (&mut reader).take(COMPRESSION_HEADER_LEN as u64).read_to_end(&mut header)?;
+
+        if header.len() < COMPRESSION_HEADER_LEN || header[..4] != COMPRESSION_MAGIC {
+            let decoder = Decoder::Raw(Cursor::new(header).chain(reader));
+            return Ok(Self { decoder, codec: None });
+        }
+
+        if header[4] != COMPRESSION_HEADER_VERSION {
+            bail!("Unsupported compression header version {}.", header[4]);
+        }
+
+        let codec = CompressionCodec::from_id(header[5])?;
+
+        let decoder = match codec {
+            CompressionCodec::None => Decoder::None(reader),
+            CompressionCodec::Deflate => Decoder::Deflate(DeflateDecoder::new(reader)),
+            CompressionCodec::Lz4 => Decoder::Lz4(FrameDecoder::new(reader)),
+        };
+
+        Ok(Self { decoder, codec: Some(codec) })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the codec of the stream (none if it has no header).
+    //---------------------------------------------------------------------------------------------
+    pub fn codec(&self) -> Option<CompressionCodec> {
+        self.codec
+    }
+}
+
+impl<R: Read> Read for CompressedReader<R> {
+    //---------------------------------------------------------------------------------------------
+    // Reads and decompresses into a buffer.
+    //---------------------------------------------------------------------------------------------
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.decoder {
+            Decoder::Raw(reader) => reader.read(buf),
+            Decoder::None(reader) => reader.read(buf),
+            Decoder::Deflate(decoder) => decoder.read(buf),
+            Decoder::Lz4(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Saves a compressed file, streaming what the write function writes through the compressor.
+//-------------------------------------------------------------------------------------------------
+pub fn save_compressed<P, F>(path: P, options: CompressionOptions, write: F) -> Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut CompressedWriter<BufWriter<File>>) -> Result<()>,
+{
+    let path = path.as_ref();
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create compressed file {}.", path.display()))?;
+
+    let mut writer = CompressedWriter::new(BufWriter::new(file), options)?;
+    write(&mut writer)?;
+    writer.finish()?.flush()?;
+
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------
+// Opens a (possibly) compressed file for streaming decompression.
+//-------------------------------------------------------------------------------------------------
+pub fn load_compressed<P>(path: P) -> Result<CompressedReader<BufReader<File>>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open compressed file {}.", path.display()))?;
+
+    CompressedReader::new(BufReader::new(file))
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_compression_round_trip() {
+    let data: Vec<u8> = (0..64 * 1024).map(|i| (i % 7) as u8).collect();
+
+    for options in [
+        CompressionOptions::none(),
+        CompressionOptions::new(CompressionCodec::Deflate, 1),
+        CompressionOptions::default(),
+        CompressionOptions::new(CompressionCodec::Lz4, 0),
+    ]
+    .iter()
+    {
+        let mut writer = CompressedWriter::new(Vec::new(), *options).unwrap();
+        writer.write_all(&data).unwrap();
+        let compressed = writer.finish().unwrap();
+
+        assert_eq!(compressed[..4], COMPRESSION_MAGIC);
+
+        if options.codec != CompressionCodec::None {
+            assert!(compressed.len() < data.len() / 10);
+        }
+
+        let mut reader = CompressedReader::new(compressed.as_slice()).unwrap();
+        let mut decompressed = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+
+        assert_eq!(reader.codec(), Some(options.codec));
+        assert_eq!(decompressed, data);
+    }
+
+    // Streams without a header are read as is.
+    let mut reader = CompressedReader::new(&b"FVR"[..]).unwrap();
+    let mut raw = Vec::new();
+    reader.read_to_end(&mut raw).unwrap();
+
+    assert_eq!(reader.codec(), None);
+    assert_eq!(raw, b"FVR");
+
+    // Unknown codecs are rejected.
+    let mut unknown = COMPRESSION_MAGIC.to_vec();
+    unknown.extend_from_slice(&[COMPRESSION_HEADER_VERSION, 255, 0]);
+    assert!(CompressedReader::new(unknown.as_slice()).is_err());
+}
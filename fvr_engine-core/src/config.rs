@@ -1,25 +1,31 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
+use std::path::PathBuf;
 use std::time::Duration;
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
-use once_cell::sync::Lazy;
+use anyhow::{anyhow, Result};
+use once_cell::sync::{Lazy, OnceCell};
 use serde_derive::{Deserialize, Serialize};
 
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::accessibility::*;
+use crate::compression::*;
 use crate::misc::*;
+use crate::platform_dirs::*;
+use crate::settings::*;
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
 //-------------------------------------------------------------------------------------------------
 
-// Path to the config file.
-const CONFIG_FILE_PATH: &str = "./config/fvr_engine.json";
+// Name of the config file.
+pub const CONFIG_FILE_NAME: &str = "fvr_engine.json";
 
 // Interval at which to log fps.
 pub const CONFIG_FPS_LOG_INTERVAL: Duration = Duration::from_secs(5);
@@ -27,24 +33,58 @@ pub const CONFIG_FPS_LOG_INTERVAL: Duration = Duration::from_secs(5);
 // Title of the game window.
 pub const CONFIG_WINDOW_TITLE: &str = "FVR_ENGINE";
 
-// Path to default keybindings. These never change.
-pub const CONFIG_DEFAULT_KEYBINDINGS_PATH: &str = "./config/default_keybindings.json";
+// Name of the default keybindings file. These never change.
+pub const CONFIG_DEFAULT_KEYBINDINGS_FILE: &str = "default_keybindings.json";
 
-// Relative path to the fonts directory.
-pub const CONFIG_FONTS_DIR: &str = "./assets/fonts/";
+// Path to the fonts directory, relative to the assets directory.
+pub const CONFIG_FONTS_DIR: &str = "fonts";
 
-// Path to current serialized keybindings. These can change.
-pub const CONFIG_KEYBINDINGS_PATH: &str = "./config/keybindings.json";
+// Path to the shaders directory, relative to the assets directory.
+pub const CONFIG_SHADERS_DIR: &str = "shaders";
+
+// Path to the audio directory, relative to the assets directory.
+pub const CONFIG_AUDIO_DIR: &str = "audio";
+
+// Path to the scripts directory, relative to the assets directory.
+pub const CONFIG_SCRIPTS_DIR: &str = "scripts";
+
+// Interval at which to render frames while the window does not have focus (unless configured).
+const DEFAULT_BACKGROUND_RENDER_INTERVAL: Duration = Duration::from_millis(100);
+
+// Path to the screenshots directory, relative to the save directory.
+pub const CONFIG_SCREENSHOTS_DIR: &str = "screenshots";
+
+// Name of the current serialized keybindings file. These can change.
+pub const CONFIG_KEYBINDINGS_FILE: &str = "keybindings.json";
 
 //-------------------------------------------------------------------------------------------------
 // Statics.
 //-------------------------------------------------------------------------------------------------
+
+// Overrides of the config file (e.g. from command-line args), fixed once the config is loaded.
+static CONFIG_OVERRIDES: OnceCell<ConfigOverrides> = OnceCell::new();
+
 pub static CONFIG: Lazy<Config> = Lazy::new(|| {
-    let config_json =
-        std::fs::read_to_string(CONFIG_FILE_PATH).expect("Failed to load config file.");
-    serde_json::from_str(&config_json).expect("Failed to parse config json.")
+    let overrides = CONFIG_OVERRIDES.get_or_init(ConfigOverrides::default);
+    let path = match &overrides.path {
+        Some(path) => path.clone(),
+        None => PLATFORM_DIRS.config_file(CONFIG_FILE_NAME),
+    };
+    let config_json = std::fs::read_to_string(path).expect("Failed to load config file.");
+    let mut config = serde_json::from_str(&config_json).expect("Failed to parse config json.");
+    overrides.apply(&mut config);
+    config
 });
 
+//-------------------------------------------------------------------------------------------------
+// Sets the overrides of the config file. Must be called before the config is first accessed.
+//-------------------------------------------------------------------------------------------------
+pub fn override_config(overrides: ConfigOverrides) -> Result<()> {
+    CONFIG_OVERRIDES
+        .set(overrides)
+        .map_err(|_| anyhow!("Config overrides must be set before the config is loaded."))
+}
+
 //-------------------------------------------------------------------------------------------------
 // Enumerates the types of game windows.
 //-------------------------------------------------------------------------------------------------
@@ -58,25 +98,76 @@ pub enum WindowType {
     WindowedFullscreen,
 }
 
+//-------------------------------------------------------------------------------------------------
+// Options for how the game behaves while its window does not have focus.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct BackgroundOptions {
+    // Interval at which to render frames, or none to stop rendering.
+    pub render_interval: Option<Duration>,
+    // Whether to stop updating the game state.
+    pub pause_updates: bool,
+    // Whether to silence all sound effects and music.
+    pub mute_audio: bool,
+}
+
+impl Default for BackgroundOptions {
+    fn default() -> Self {
+        Self {
+            render_interval: Some(DEFAULT_BACKGROUND_RENDER_INTERVAL),
+            pause_updates: true,
+            mute_audio: true,
+        }
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Config holds the global config.
 //-------------------------------------------------------------------------------------------------
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
+    // Accessibility options (query these through Accessibility rather than the config).
+    #[serde(default)]
+    pub accessibility: AccessibilityOptions,
+    // Volumes of the audio channels.
+    #[serde(default)]
+    pub audio: AudioSettings,
+    // How the game behaves while its window does not have focus.
+    #[serde(default)]
+    pub background: BackgroundOptions,
     // Whether to render the full frame vignette.
     pub enable_vignette: bool,
     // Whether the window should be created fullscreen.
     pub window_type: WindowType,
     // Name of the font to use.
     pub font_name: String,
+    // Seed to generate the world from, or none for a random seed.
+    #[serde(default)]
+    pub seed: Option<u64>,
     // Minimum size of the game window.
     pub minimum_window_dimensions: ICoord,
+    // Codec and level to compress saves and zone files with.
+    #[serde(default)]
+    pub save_compression: CompressionOptions,
     // Interval at which to render frames, or none for as fast as possible.
     pub render_interval: Option<Duration>,
     // Interval to sleep when the render interval has not been met.
     pub sleep_interval: Duration,
     // Whether to display current fps.
     pub show_fps: bool,
+    // Whether the mouse cursor is drawn as a glyph in place of the hardware cursor.
+    #[serde(default)]
+    pub software_cursor: bool,
+    // When the health bars of actors are drawn over the map.
+    #[serde(default)]
+    pub actor_bars: ActorBarMode,
+    // Which items the player picks up automatically.
+    #[serde(default)]
+    pub auto_pickup: AutoPickupSettings,
+    // Whether resizing the window resizes the terminal (in whole tiles) instead of scaling it.
+    // The terminal dimensions are then the minimum dimensions of the terminal.
+    #[serde(default)]
+    pub resize_terminal: bool,
     // Dimensions (in tiles) of the terminal.
     pub terminal_dimensions: ICoord,
     // Dimensions (in pixels) of each tile.
@@ -87,6 +178,50 @@ pub struct Config {
     pub use_alternating_vbos: bool,
     // Whether to use signed distance field font rendering.
     pub use_sdf_fonts: bool,
+    // Whether to build vertex data on a worker thread while the previous frame renders.
+    // (reclaims main thread time for large terminals, at the cost of a frame of latency)
+    #[serde(default)]
+    pub use_threaded_sync: bool,
+    // Whether to wait for vertical sync when presenting frames.
+    #[serde(default)]
+    pub vsync: bool,
     // Dimensions (in pixels) of the game window.
     pub window_dimensions: ICoord,
 }
+
+//-------------------------------------------------------------------------------------------------
+// ConfigOverrides holds values that replace those of the config file when it is loaded.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    // Path of the config file to load in place of the default.
+    pub path: Option<PathBuf>,
+    // Seed to generate the world from.
+    pub seed: Option<u64>,
+    // Name of the font to use.
+    pub font_name: Option<String>,
+    // Dimensions (in pixels) of the game window.
+    pub window_dimensions: Option<ICoord>,
+    // Type of the game window.
+    pub window_type: Option<WindowType>,
+}
+
+impl ConfigOverrides {
+    //---------------------------------------------------------------------------------------------
+    // Replaces the values of a config with the overrides that are set.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(seed) = self.seed {
+            config.seed = Some(seed);
+        }
+        if let Some(font_name) = &self.font_name {
+            config.font_name = font_name.clone();
+        }
+        if let Some(window_dimensions) = self.window_dimensions {
+            config.window_dimensions = window_dimensions;
+        }
+        if let Some(window_type) = self.window_type {
+            config.window_type = window_type;
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::a_star::*;
+use crate::adjacency::*;
+use crate::grid_map::*;
+use crate::map2d::*;
+use crate::misc::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Label given to blocked coords in a regions map.
+pub const REGION_NONE: i32 = -1;
+
+//-------------------------------------------------------------------------------------------------
+// Regions labels every connected area of passable coords in a map.
+//-------------------------------------------------------------------------------------------------
+pub struct Regions {
+    // Region label of every coord (REGION_NONE for blocked coords).
+    labels: GridMap<i32>,
+    // Number of coords in each region, indexed by label.
+    sizes: Vec<usize>,
+}
+
+impl Regions {
+    //---------------------------------------------------------------------------------------------
+    // Labels the connected regions of a passability map.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(states: &GridMap<Passability>, adjacency: Adjacency) -> Self {
+        let mut labels = GridMap::new(states.dimensions());
+        labels.data_mut().iter_mut().for_each(|label| *label = REGION_NONE);
+
+        let mut sizes = Vec::new();
+        let mut frontier = VecDeque::new();
+
+        for index in 0..states.data().len() {
+            if !states.get(index).passable() || *labels.get(index) != REGION_NONE {
+                continue;
+            }
+
+            // Flood fill a new region from the unlabeled coord.
+            let label = sizes.len() as i32;
+            let mut size = 0;
+            *labels.get_mut(index) = label;
+            frontier.push_back(Misc::reverse_index_2d(index, states.width()));
+
+            while let Some(xy) = frontier.pop_front() {
+                size += 1;
+
+                for direction in adjacency.iter() {
+                    let neighbor = (xy.0 + direction.dx(), xy.1 + direction.dy());
+
+                    if !states.in_bounds(neighbor)
+                        || !states.get_xy(neighbor).passable()
+                        || *labels.get_xy(neighbor) != REGION_NONE
+                    {
+                        continue;
+                    }
+
+                    *labels.get_xy_mut(neighbor) = label;
+                    frontier.push_back(neighbor);
+                }
+            }
+
+            sizes.push(size);
+        }
+
+        Self { labels, sizes }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the region label of a coord, if it is passable.
+    //---------------------------------------------------------------------------------------------
+    pub fn region(&self, xy: ICoord) -> Option<i32> {
+        if !self.labels.in_bounds(xy) {
+            return None;
+        }
+
+        match *self.labels.get_xy(xy) {
+            REGION_NONE => None,
+            label => Some(label),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of regions.
+    //---------------------------------------------------------------------------------------------
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of coords in a region.
+    //---------------------------------------------------------------------------------------------
+    pub fn size(&self, label: i32) -> usize {
+        self.sizes[label as usize]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns ref to the underlying labels map.
+    //---------------------------------------------------------------------------------------------
+    pub fn labels(&self) -> &GridMap<i32> {
+        &self.labels
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Metrics describing the layout of a passability map, useful for tuning generators.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConnectivityMetrics {
+    // Number of connected regions.
+    pub regions: usize,
+    // Number of passable coords.
+    pub passable: usize,
+    // Number of passable coords with exactly two opposing cardinal passable neighbors.
+    pub corridors: usize,
+    // Number of passable coords with exactly one cardinal passable neighbor.
+    pub dead_ends: usize,
+    // Ratio of corridor coords to passable coords.
+    pub corridor_ratio: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Result of a connectivity repair pass.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct ConnectivityRepair {
+    // Coords that were carved from blocked to passable.
+    pub carved: Vec<ICoord>,
+    // Carved coords at the ends of each new corridor, suitable for placing doors.
+    pub doors: Vec<ICoord>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Connectivity provides a static API for validating and repairing map connectivity.
+//-------------------------------------------------------------------------------------------------
+pub struct Connectivity;
+
+impl Connectivity {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether all important coords are passable and mutually reachable.
+    //---------------------------------------------------------------------------------------------
+    pub fn validate(
+        states: &GridMap<Passability>,
+        important: &[ICoord],
+        adjacency: Adjacency,
+    ) -> bool {
+        let regions = Regions::new(states, adjacency);
+        let mut first = None;
+
+        for &xy in important {
+            match (regions.region(xy), first) {
+                (None, _) => return false,
+                (Some(label), None) => first = Some(label),
+                (Some(label), Some(first)) if label != first => return false,
+                _ => {}
+            }
+        }
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Carves minimal corridors until all important coords are mutually reachable.
+    // (corridors only use cardinal steps so they are walkable for any adjacency)
+    //---------------------------------------------------------------------------------------------
+    pub fn repair(
+        states: &mut GridMap<Passability>,
+        important: &[ICoord],
+        adjacency: Adjacency,
+    ) -> ConnectivityRepair {
+        let mut repair = ConnectivityRepair::default();
+
+        // Important coords must be passable before anything else.
+        for &xy in important {
+            if states.in_bounds(xy) && !states.get_xy(xy).passable() {
+                *states.get_xy_mut(xy) = Passability::Passable;
+                repair.carved.push(xy);
+            }
+        }
+
+        // Each iteration joins the root region with one disconnected region.
+        loop {
+            let regions = Regions::new(states, adjacency);
+            let mut labels = important.iter().filter_map(|&xy| regions.region(xy));
+
+            let root = match labels.next() {
+                Some(label) => label,
+                None => break,
+            };
+
+            let target = match labels.find(|&label| label != root) {
+                Some(label) => label,
+                None => break,
+            };
+
+            let corridor = Self::find_corridor(states, &regions, root, target);
+
+            if corridor.is_empty() {
+                break;
+            }
+
+            for &xy in corridor.iter() {
+                *states.get_xy_mut(xy) = Passability::Passable;
+            }
+
+            repair.doors.push(corridor[0]);
+
+            if corridor.len() > 1 {
+                repair.doors.push(corridor[corridor.len() - 1]);
+            }
+
+            repair.carved.extend(corridor);
+        }
+
+        repair
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Carves minimal corridors until every passable coord is reachable from the largest region.
+    // (for generators without important coords, e.g. to join pockets enclosed by trees)
+    //---------------------------------------------------------------------------------------------
+    pub fn connect_all(
+        states: &mut GridMap<Passability>,
+        adjacency: Adjacency,
+    ) -> ConnectivityRepair {
+        let regions = Regions::new(states, adjacency);
+        let mut seeds = vec![INVALID_ICOORD; regions.count()];
+
+        for (index, &label) in regions.labels().data().iter().enumerate() {
+            if label != REGION_NONE && seeds[label as usize] == INVALID_ICOORD {
+                seeds[label as usize] = Misc::reverse_index_2d(index, states.width());
+            }
+        }
+
+        // The first important coord is the root, so the largest region goes first.
+        let mut labels: Vec<i32> = (0..regions.count() as i32).collect();
+        labels.sort_by_key(|&label| std::cmp::Reverse(regions.size(label)));
+        let important: Vec<ICoord> = labels.iter().map(|&label| seeds[label as usize]).collect();
+
+        Self::repair(states, &important, adjacency)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Finds the blocked coords that must be carved to join two regions with the fewest changes.
+    // (0-1 BFS where passable coords are free and blocked coords cost one)
+    //---------------------------------------------------------------------------------------------
+    fn find_corridor(
+        states: &GridMap<Passability>,
+        regions: &Regions,
+        root: i32,
+        target: i32,
+    ) -> Vec<ICoord> {
+        let mut costs = GridMap::new(states.dimensions());
+        costs.data_mut().iter_mut().for_each(|cost| *cost = usize::MAX);
+
+        let mut parents = GridMap::new(states.dimensions());
+        parents.data_mut().iter_mut().for_each(|parent| *parent = INVALID_ICOORD);
+
+        // Seed the search with every coord of the root region.
+        let mut frontier = VecDeque::new();
+
+        for (index, label) in regions.labels().data().iter().enumerate() {
+            if *label == root {
+                *costs.get_mut(index) = 0;
+                frontier.push_back(Misc::reverse_index_2d(index, states.width()));
+            }
+        }
+
+        let mut found = None;
+
+        while let Some(xy) = frontier.pop_front() {
+            if regions.region(xy) == Some(target) {
+                found = Some(xy);
+                break;
+            }
+
+            let cost = *costs.get_xy(xy);
+
+            for direction in Adjacency::Cardinals.iter() {
+                let neighbor = (xy.0 + direction.dx(), xy.1 + direction.dy());
+
+                if !states.in_bounds(neighbor) {
+                    continue;
+                }
+
+                let step = if states.get_xy(neighbor).passable() { 0 } else { 1 };
+
+                if cost + step >= *costs.get_xy(neighbor) {
+                    continue;
+                }
+
+                *costs.get_xy_mut(neighbor) = cost + step;
+                *parents.get_xy_mut(neighbor) = xy;
+
+                if step == 0 {
+                    frontier.push_front(neighbor);
+                } else {
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        // Walk back from the target region, collecting the blocked coords.
+        let mut corridor = Vec::new();
+        let mut current = match found {
+            Some(xy) => xy,
+            None => return corridor,
+        };
+
+        while current != INVALID_ICOORD {
+            if !states.get_xy(current).passable() {
+                corridor.push(current);
+            }
+
+            current = *parents.get_xy(current);
+        }
+
+        corridor.reverse();
+        corridor
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Calculates layout metrics for a passability map.
+    //---------------------------------------------------------------------------------------------
+    pub fn metrics(states: &GridMap<Passability>, adjacency: Adjacency) -> ConnectivityMetrics {
+        let mut metrics = ConnectivityMetrics {
+            regions: Regions::new(states, adjacency).count(),
+            ..Default::default()
+        };
+
+        let passable = |xy: ICoord| states.in_bounds(xy) && states.get_xy(xy).passable();
+
+        for index in 0..states.data().len() {
+            if !states.get(index).passable() {
+                continue;
+            }
+
+            metrics.passable += 1;
+
+            let (x, y) = Misc::reverse_index_2d(index, states.width());
+            let north = passable((x, y - 1));
+            let south = passable((x, y + 1));
+            let east = passable((x + 1, y));
+            let west = passable((x - 1, y));
+            let neighbors = [north, south, east, west].iter().filter(|&&p| p).count();
+
+            if neighbors == 1 {
+                metrics.dead_ends += 1;
+            } else if neighbors == 2 && ((north && south) || (east && west)) {
+                metrics.corridors += 1;
+            }
+        }
+
+        if metrics.passable > 0 {
+            metrics.corridor_ratio = metrics.corridors as f32 / metrics.passable as f32;
+        }
+
+        metrics
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_connectivity_repair() {
+    // Two rooms separated by a three cell thick wall.
+    let mut states = GridMap::new((11, 5));
+
+    for y in 0..5 {
+        for x in 4..7 {
+            *states.get_xy_mut((x, y)) = Passability::Blocked;
+        }
+    }
+
+    let important = [(1, 2), (9, 2)];
+    assert_eq!(Regions::new(&states, Adjacency::EightWay).count(), 2);
+    assert!(!Connectivity::validate(&states, &important, Adjacency::EightWay));
+
+    let repair = Connectivity::repair(&mut states, &important, Adjacency::EightWay);
+    assert_eq!(repair.carved.len(), 3);
+    assert_eq!(repair.doors.len(), 2);
+    assert!(Connectivity::validate(&states, &important, Adjacency::EightWay));
+
+    let metrics = Connectivity::metrics(&states, Adjacency::EightWay);
+    assert_eq!(metrics.regions, 1);
+    assert_eq!(metrics.passable, 11 * 5 - 12);
+    assert_eq!(metrics.corridors, 3);
+}
+
+#[test]
+fn test_connectivity_connect_all() {
+    // A large room, a small room and a single enclosed coord.
+    let mut states = GridMap::new((12, 5));
+    states.data_mut().iter_mut().for_each(|state| *state = Passability::Blocked);
+
+    for y in 0..5 {
+        for x in 0..6 {
+            *states.get_xy_mut((x, y)) = Passability::Passable;
+        }
+    }
+
+    *states.get_xy_mut((8, 2)) = Passability::Passable;
+    *states.get_xy_mut((10, 0)) = Passability::Passable;
+    *states.get_xy_mut((11, 0)) = Passability::Passable;
+    assert_eq!(Regions::new(&states, Adjacency::Cardinals).count(), 3);
+
+    let repair = Connectivity::connect_all(&mut states, Adjacency::Cardinals);
+    assert_eq!(Regions::new(&states, Adjacency::Cardinals).count(), 1);
+    assert!(!repair.carved.is_empty());
+    assert!(repair.carved.iter().all(|&xy| xy.0 >= 6));
+}
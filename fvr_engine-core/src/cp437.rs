@@ -1,12 +1,22 @@
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
-use fnv::FnvHashSet;
+use anyhow::{bail, Result};
+use fnv::{FnvHashMap, FnvHashSet};
 use once_cell::sync::Lazy;
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
 //-------------------------------------------------------------------------------------------------
+
+// Chars of CP437 codes 0 (null) and 255 (non-breaking space), which are drawn blank.
+pub const CP437_NULL_CHAR: char = '\0';
+pub const CP437_NBSP_CHAR: char = '\u{a0}';
+
+// Substitute for chars without a CP437 equivalent or near substitution.
+pub const CP437_UNKNOWN_CHAR: char = '?';
+
+// Chars of CP437 codes 1 through 254 (the visible glyphs).
 pub const CP437_CHARS: [char; 254] = [
     '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕', '‼',
     '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼', ' ', '!', '"', '#', '$', '%', '&',
@@ -27,6 +37,8 @@ pub const CP437_CHARS: [char; 254] = [
 //-------------------------------------------------------------------------------------------------
 // Statics.
 //-------------------------------------------------------------------------------------------------
+
+// Set of the codepoints of the visible CP437 glyphs.
 pub static CP437_SET: Lazy<FnvHashSet<i32>> = Lazy::new(|| {
     let mut cp437_set = FnvHashSet::default();
 
@@ -36,3 +48,218 @@ pub static CP437_SET: Lazy<FnvHashSet<i32>> = Lazy::new(|| {
 
     cp437_set
 });
+
+// Table of every CP437 code to its char.
+pub static CP437_TO_CHAR: Lazy<[char; 256]> = Lazy::new(|| {
+    let mut table = [CP437_NULL_CHAR; 256];
+    table[1..255].copy_from_slice(&CP437_CHARS);
+    table[255] = CP437_NBSP_CHAR;
+    table
+});
+
+// Map of every char with a CP437 code to its code.
+pub static CHAR_TO_CP437: Lazy<FnvHashMap<char, u8>> =
+    Lazy::new(|| CP437_TO_CHAR.iter().enumerate().map(|(code, c)| (*c, code as u8)).collect());
+
+//-------------------------------------------------------------------------------------------------
+// Cp437Category enumerates groups of the visible CP437 glyphs.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Cp437Category {
+    // Single and double line box drawing glyphs.
+    BoxDrawing,
+    // Shades, full, half and square blocks.
+    Blocks,
+    // Latin and Greek letters (including accented letters).
+    Letters,
+    // The digits 0 through 9.
+    Digits,
+    // ASCII punctuation and the space.
+    Punctuation,
+    // Every other visible glyph (e.g. arrows, suits and math symbols).
+    Symbols,
+}
+
+impl Cp437Category {
+    //---------------------------------------------------------------------------------------------
+    // Returns the category of a CP437 code (or none for the blank codes 0 and 255).
+    //---------------------------------------------------------------------------------------------
+    pub fn of_code(code: u8) -> Option<Self> {
+        let c = cp437_to_char(code);
+
+        match code {
+            0 | 255 => None,
+            0xb3..=0xda => Some(Cp437Category::BoxDrawing),
+            0xb0..=0xb2 | 0xdb..=0xdf | 0xfe => Some(Cp437Category::Blocks),
+            _ if c.is_ascii_digit() => Some(Cp437Category::Digits),
+            _ if c.is_alphabetic() => Some(Cp437Category::Letters),
+            _ if c == ' ' || c.is_ascii_punctuation() => Some(Cp437Category::Punctuation),
+            _ => Some(Cp437Category::Symbols),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the category of a char (or none if it is not a visible CP437 glyph).
+    //---------------------------------------------------------------------------------------------
+    pub fn of_char(c: char) -> Option<Self> {
+        char_to_cp437(c).and_then(Self::of_code)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the codes in the category, in order.
+    //---------------------------------------------------------------------------------------------
+    pub fn codes(self) -> impl Iterator<Item = u8> {
+        (0..=255u8).filter(move |code| Self::of_code(*code) == Some(self))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the chars in the category, in code order.
+    //---------------------------------------------------------------------------------------------
+    pub fn chars(self) -> impl Iterator<Item = char> {
+        self.codes().map(cp437_to_char)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns the char of a CP437 code.
+//-------------------------------------------------------------------------------------------------
+pub fn cp437_to_char(code: u8) -> char {
+    CP437_TO_CHAR[code as usize]
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns the CP437 code of a char, if it has one.
+//-------------------------------------------------------------------------------------------------
+pub fn char_to_cp437(c: char) -> Option<u8> {
+    CHAR_TO_CP437.get(&c).copied()
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns whether a char has a CP437 code.
+//-------------------------------------------------------------------------------------------------
+pub fn is_cp437(c: char) -> bool {
+    CHAR_TO_CP437.contains_key(&c)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that returns a visually similar CP437 char for common unmappable chars.
+//-------------------------------------------------------------------------------------------------
+fn cp437_substitution(c: char) -> Option<char> {
+    let substitute = match c {
+        // Typographic punctuation.
+        '‘' | '’' | '‚' | '′' | '´' => '\'',
+        '“' | '”' | '„' | '″' => '"',
+        '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => '-',
+        '…' => '.',
+        '‹' => '<',
+        '›' => '>',
+        '\t' | '\u{2002}'..='\u{200a}' => ' ',
+        // Latin letters without a CP437 glyph.
+        'À' | 'Á' | 'Â' | 'Ã' => 'A',
+        'È' | 'Ê' | 'Ë' => 'E',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ø' => 'O',
+        'Ù' | 'Ú' | 'Û' => 'U',
+        'Ý' | 'Ÿ' => 'Y',
+        'ã' => 'a',
+        'õ' | 'ø' => 'o',
+        'ý' => 'y',
+        'Œ' => 'O',
+        'œ' => 'o',
+        // Symbols.
+        '×' => 'x',
+        '✓' | '✔' => '√',
+        '◦' | '∘' => '°',
+        '●' | '⬤' => '•',
+        '□' | '▪' | '◼' => '■',
+        '▶' => '►',
+        '◀' => '◄',
+        '≠' => '≡',
+        '€' => 'E',
+        // Box drawing variants.
+        '━' => '─',
+        '┃' => '│',
+        '╭' | '┏' => '┌',
+        '╮' | '┓' => '┐',
+        '╰' | '┗' => '└',
+        '╯' | '┛' => '┘',
+        _ => return None,
+    };
+
+    Some(substitute)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns the char if it has a CP437 code, otherwise a visually similar CP437 char (or '?').
+//-------------------------------------------------------------------------------------------------
+pub fn nearest_cp437(c: char) -> char {
+    if is_cp437(c) {
+        return c;
+    }
+
+    cp437_substitution(c).unwrap_or(CP437_UNKNOWN_CHAR)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns text with every char replaced by its nearest CP437 char (newlines are preserved).
+//-------------------------------------------------------------------------------------------------
+pub fn sanitize_cp437(text: &str) -> String {
+    text.chars().map(|c| if c == '\n' { c } else { nearest_cp437(c) }).collect()
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns an error naming the first char in text (excluding newlines) without a CP437 code.
+//-------------------------------------------------------------------------------------------------
+pub fn validate_cp437(text: &str) -> Result<()> {
+    if let Some((i, c)) = text.chars().enumerate().find(|(_, c)| *c != '\n' && !is_cp437(*c)) {
+        bail!("Char '{}' (U+{:04X}) at index {} has no CP437 equivalent.", c, c as u32, i);
+    }
+
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_cp437_tables() {
+    for code in 0..=255u8 {
+        assert_eq!(char_to_cp437(cp437_to_char(code)), Some(code));
+    }
+
+    assert_eq!(cp437_to_char(b'A'), 'A');
+    assert_eq!(cp437_to_char(0xdb), '█');
+    assert_eq!(char_to_cp437('☺'), Some(1));
+    assert_eq!(char_to_cp437('■'), Some(0xfe));
+    assert!(is_cp437('╬'));
+    assert!(!is_cp437('…'));
+}
+
+#[test]
+fn test_cp437_substitution() {
+    assert_eq!(nearest_cp437('é'), 'é');
+    assert_eq!(nearest_cp437('’'), '\'');
+    assert_eq!(nearest_cp437('╭'), '┌');
+    assert_eq!(nearest_cp437('😀'), CP437_UNKNOWN_CHAR);
+    assert_eq!(sanitize_cp437("“Hi”—\nbye…"), "\"Hi\"-\nbye.");
+    assert!(validate_cp437("Hello,\nworld!").is_ok());
+    assert!(validate_cp437("naïve…").is_err());
+
+    // Every substitute is itself a CP437 char.
+    for c in ['‘', '“', '–', 'À', 'Œ', '×', '✓', '▶', '━', '╯'].iter() {
+        assert!(is_cp437(nearest_cp437(*c)));
+    }
+}
+
+#[test]
+fn test_cp437_categories() {
+    assert_eq!(Cp437Category::BoxDrawing.codes().count(), 0xda - 0xb3 + 1);
+    assert_eq!(Cp437Category::Digits.chars().collect::<String>(), "0123456789");
+    assert!(Cp437Category::Blocks.chars().any(|c| c == '▓'));
+    assert!(Cp437Category::Letters.chars().any(|c| c == 'ß'));
+    assert_eq!(Cp437Category::of_char('♥'), Some(Cp437Category::Symbols));
+    assert_eq!(Cp437Category::of_char(' '), Some(Cp437Category::Punctuation));
+    assert_eq!(Cp437Category::of_char(CP437_NULL_CHAR), None);
+    assert_eq!(Cp437Category::of_char('…'), None);
+}
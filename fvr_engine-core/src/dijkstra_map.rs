@@ -94,7 +94,17 @@ pub struct DijkstraMap {
 // Implementation for both recalculate and recalculate_thin methods below to avoid duplication.
 //-------------------------------------------------------------------------------------------------
 macro_rules! recalculate_impl {
+    // The default cost: coords are passable at a cost of 1.0 if available in the states.
     ($self:ident, $states:ident) => {
+        recalculate_impl!($self, $states, |xy: ICoord| {
+            if Into::<DijkstraState>::into($states.get_xy(xy).clone()).passable() {
+                Some(1.0)
+            } else {
+                None
+            }
+        })
+    };
+    ($self:ident, $states:ident, $cost:expr) => {
         // Find the adjacency method and max weight value.
         let adjacency = $self.distance.adjacency();
         let start_weight = ($states.width() * $states.height()) as f32;
@@ -108,16 +118,15 @@ macro_rules! recalculate_impl {
             let icoord = (coord.0 as i32, coord.1 as i32);
 
             match $states.get_xy(icoord).clone().into() {
-                DijkstraState::Available => {
-                    // Set all passable coords to the max weight.
-                    *$self.weights.get_xy_mut(icoord) = Some(start_weight);
-                }
                 DijkstraState::Goal(weight) => {
                     // Set all goal coords to their weight and add them as edges.
                     *$self.weights.get_xy_mut(icoord) = Some(weight as f32);
                     $self.edges.insert(*coord);
                 }
-                _ => {}
+                _ => {
+                    // Set all passable coords to the max weight.
+                    *$self.weights.get_xy_mut(icoord) = Some(start_weight);
+                }
             }
         }
 
@@ -139,18 +148,21 @@ macro_rules! recalculate_impl {
 
                 // Iterate all neighboring coords around the edge.
                 for neighbor in adjacency.neighbors(iedge) {
-                    // If neighbor is out of bounds, has been processed or is blocked, continue.
-                    if !$states.in_bounds(neighbor)
-                        || *$self.processed.get_xy(neighbor)
-                        || !Into::<DijkstraState>::into($states.get_xy(neighbor).clone())
-                            .passable()
-                    {
+                    // If neighbor is out of bounds or has been processed, continue.
+                    if !$states.in_bounds(neighbor) || *$self.processed.get_xy(neighbor) {
                         continue;
                     }
 
+                    // If the neighbor is blocked, continue.
+                    let multiplier = match ($cost)(neighbor) {
+                        Some(multiplier) => multiplier,
+                        None => continue,
+                    };
+
                     // Calculate the new weight for the neighbor (which will always be Some).
                     let neighbor_weight = $self.weights.get_xy(neighbor).unwrap();
-                    let new_weight = current_weight + $self.distance.calculate(iedge, neighbor);
+                    let new_weight =
+                        current_weight + $self.distance.calculate(iedge, neighbor) * multiplier;
 
                     // If the new weight is less (closer) than the previous weight, update and
                     // add the neighbor to the queue of edges to process.
@@ -506,6 +518,34 @@ impl DijkstraMap {
         self.recalculate_thin(states);
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Calculates the output weights given a grid map of dijkstra state (for the goals) and a cost
+    // function (e.g. for per-actor costs), which returns the multiplier of the cost of stepping
+    // into a coord or None if the coord is impassable. Intended for usage with thin dijkstra maps.
+    //---------------------------------------------------------------------------------------------
+    pub fn calculate_thin_with_cost<M, T, F>(&mut self, states: &M, cost: F)
+    where
+        M: Map2d<T>,
+        T: Map2dType + Into<DijkstraState>,
+        F: Fn(ICoord) -> Option<f32>,
+    {
+        // Recreate the set of walkable coords.
+        self.walkable.clear();
+
+        map2d_iter_index!(states, x, y, state, {
+            let goal = matches!(state.clone().into(), DijkstraState::Goal(_));
+
+            if goal || cost((x, y)).is_some() {
+                self.walkable.insert((x as u8, y as u8));
+            } else {
+                *self.weights.get_xy_mut((x, y)) = None;
+            }
+        });
+
+        // See macro above for details.
+        recalculate_impl!(self, states, cost);
+    }
+
     //---------------------------------------------------------------------------------------------
     // Recalculates the output weights faster, but if only the passable/goals states change.
     // NOTE: Panics if called on a thin dijkstra map.
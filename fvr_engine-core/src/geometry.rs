@@ -0,0 +1,363 @@
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::fov::*;
+use crate::map2d::*;
+use crate::misc::*;
+use crate::rect::*;
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type ShapeWithin<S> = std::iter::Filter<S, Box<dyn FnMut(&ICoord) -> bool>>;
+
+//-------------------------------------------------------------------------------------------------
+// Shape describes an iterator over the coords of a shape, adding helpers for using the shape with
+// rects and maps.
+//-------------------------------------------------------------------------------------------------
+pub trait Shape: Iterator<Item = ICoord> + Sized {
+    //---------------------------------------------------------------------------------------------
+    // Returns the smallest rect containing every coord of the shape.
+    //---------------------------------------------------------------------------------------------
+    fn bounds(&self) -> Rect;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the coords of the shape that are contained in a rect.
+    //---------------------------------------------------------------------------------------------
+    fn within(self, rect: Rect) -> ShapeWithin<Self> {
+        self.filter(Box::new(move |xy| rect.contains(*xy)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the coords of the shape that are in bounds of a map to a value.
+    //---------------------------------------------------------------------------------------------
+    fn draw_into<M, T>(self, map: &mut M, value: T)
+    where
+        M: Map2d<T>,
+        T: Map2dType,
+    {
+        for xy in self {
+            if map.in_bounds(xy) {
+                *map.get_xy_mut(xy) = value.clone();
+            }
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Line iterates the coords of a line between two points using Bresenham's algorithm.
+// Unlike Lines::bresenham the coords are always yielded from the start to the end.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Line {
+    // The first coord of the line.
+    start: ICoord,
+    // The next coord of the line.
+    xy: ICoord,
+    // The last coord of the line.
+    end: ICoord,
+    // Absolute (positive) x and negated (negative) y deltas of the line.
+    delta: ICoord,
+    // Direction of the steps along each axis.
+    step: ICoord,
+    // Accumulated error term.
+    error: i32,
+    // Whether the end has been yielded.
+    done: bool,
+}
+
+impl Line {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new line between two points (inclusive).
+    //---------------------------------------------------------------------------------------------
+    pub fn new(start: ICoord, end: ICoord) -> Self {
+        let delta = ((end.0 - start.0).abs(), -(end.1 - start.1).abs());
+        let step = ((end.0 - start.0).signum(), (end.1 - start.1).signum());
+
+        Self { start, xy: start, end, delta, step, error: delta.0 + delta.1, done: false }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there is a line of sight between two points, i.e. whether every coord of
+    // the line between them (exclusive) is in bounds of a map and transparent.
+    //---------------------------------------------------------------------------------------------
+    pub fn line_of_sight<M, T>(start: ICoord, end: ICoord, states: &M) -> bool
+    where
+        M: Map2dView<Type = T>,
+        T: Map2dType + Into<Transparency>,
+    {
+        Self::new(start, end).skip(1).take_while(|xy| *xy != end).all(|xy| {
+            states.in_bounds(xy) && states.get_xy(xy).clone().into() == Transparency::Transparent
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coords of the line up to and including the first coord (after the start) that
+    // is out of bounds of a map or opaque, e.g. the path of a projectile.
+    //---------------------------------------------------------------------------------------------
+    pub fn until_blocked<M, T>(self, states: &M) -> Vec<ICoord>
+    where
+        M: Map2dView<Type = T>,
+        T: Map2dType + Into<Transparency>,
+    {
+        let mut path = Vec::new();
+
+        for xy in self {
+            let blocked = !path.is_empty()
+                && (!states.in_bounds(xy)
+                    || states.get_xy(xy).clone().into() == Transparency::Opaque);
+
+            path.push(xy);
+
+            if blocked {
+                break;
+            }
+        }
+
+        path
+    }
+}
+
+impl Iterator for Line {
+    type Item = ICoord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let xy = self.xy;
+
+        if xy == self.end {
+            self.done = true;
+            return Some(xy);
+        }
+
+        let error = self.error * 2;
+
+        if error >= self.delta.1 {
+            self.error += self.delta.1;
+            self.xy.0 += self.step.0;
+        }
+
+        if error <= self.delta.0 {
+            self.error += self.delta.0;
+            self.xy.1 += self.step.1;
+        }
+
+        Some(xy)
+    }
+}
+
+impl Shape for Line {
+    fn bounds(&self) -> Rect {
+        let origin = (self.start.0.min(self.end.0), self.start.1.min(self.end.1));
+        Rect::new(origin, self.delta.0 + 1, 1 - self.delta.1)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Ellipse iterates the coords of an axis-aligned ellipse, either its outline or every coord it
+// contains, in rows from the top left of its bounds.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Ellipse {
+    // Center of the ellipse.
+    center: ICoord,
+    // Horizontal and vertical radii of the ellipse.
+    radii: ICoord,
+    // Whether every contained coord is yielded instead of only the outline.
+    filled: bool,
+    // The next coord of the bounds to test.
+    cursor: ICoord,
+}
+
+impl Ellipse {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new ellipse outline.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(center: ICoord, radii: ICoord) -> Self {
+        let radii = (radii.0.max(0), radii.1.max(0));
+        Self { center, radii, filled: false, cursor: (center.0 - radii.0, center.1 - radii.1) }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new filled ellipse.
+    //---------------------------------------------------------------------------------------------
+    pub fn filled(center: ICoord, radii: ICoord) -> Self {
+        Self { filled: true, ..Self::new(center, radii) }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an offset from the center is contained in the ellipse.
+    // (the radii are extended by half a cell so that the ellipse is not pinched at its extents)
+    //---------------------------------------------------------------------------------------------
+    fn contains_offset(&self, (dx, dy): ICoord) -> bool {
+        let rx = self.radii.0 as f32 + 0.5;
+        let ry = self.radii.1 as f32 + 0.5;
+        let (dx, dy) = (dx as f32, dy as f32);
+
+        (dx * dx) / (rx * rx) + (dy * dy) / (ry * ry) <= 1.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a coord is contained in the ellipse.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, xy: ICoord) -> bool {
+        self.contains_offset((xy.0 - self.center.0, xy.1 - self.center.1))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns whether a contained offset is on the outline, i.e. whether an
+    // orthogonal neighbor is not contained.
+    //---------------------------------------------------------------------------------------------
+    fn is_outline_offset(&self, (dx, dy): ICoord) -> bool {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .any(|(ox, oy)| !self.contains_offset((dx + ox, dy + oy)))
+    }
+}
+
+impl Iterator for Ellipse {
+    type Item = ICoord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x_max, y_max) = (self.center.0 + self.radii.0, self.center.1 + self.radii.1);
+
+        while self.cursor.1 <= y_max {
+            let xy = self.cursor;
+
+            if self.cursor.0 < x_max {
+                self.cursor.0 += 1;
+            } else {
+                self.cursor = (self.center.0 - self.radii.0, self.cursor.1 + 1);
+            }
+
+            let offset = (xy.0 - self.center.0, xy.1 - self.center.1);
+
+            if self.contains_offset(offset) && (self.filled || self.is_outline_offset(offset)) {
+                return Some(xy);
+            }
+        }
+
+        None
+    }
+}
+
+impl Shape for Ellipse {
+    fn bounds(&self) -> Rect {
+        Rect::with_center(self.center, self.radii.0 * 2 + 1, self.radii.1 * 2 + 1)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Circle iterates the coords of a circle, either its outline or every coord it contains.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Circle(Ellipse);
+
+impl Circle {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new circle outline.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(center: ICoord, radius: i32) -> Self {
+        Self(Ellipse::new(center, (radius, radius)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new filled circle.
+    //---------------------------------------------------------------------------------------------
+    pub fn filled(center: ICoord, radius: i32) -> Self {
+        Self(Ellipse::filled(center, (radius, radius)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a coord is contained in the circle.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, xy: ICoord) -> bool {
+        self.0.contains(xy)
+    }
+}
+
+impl Iterator for Circle {
+    type Item = ICoord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl Shape for Circle {
+    fn bounds(&self) -> Rect {
+        self.0.bounds()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_geometry_line() {
+    let line: Vec<_> = Line::new((0, 0), (4, 2)).collect();
+    assert_eq!(line, vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+
+    let line: Vec<_> = Line::new((4, 2), (0, 0)).collect();
+    assert_eq!(line.first(), Some(&(4, 2)));
+    assert_eq!(line.last(), Some(&(0, 0)));
+    assert_eq!(line.len(), 5);
+
+    let line: Vec<_> = Line::new((3, 3), (3, 3)).collect();
+    assert_eq!(line, vec![(3, 3)]);
+
+    assert_eq!(Line::new((2, 5), (-1, 1)).bounds(), Rect::new((-1, 1), 4, 5));
+
+    // The bounds do not change as the line is iterated.
+    let mut line = Line::new((2, 5), (-1, 1));
+    let _ = line.nth(2);
+    assert_eq!(line.bounds(), Rect::new((-1, 1), 4, 5));
+}
+
+#[test]
+fn test_geometry_circle() {
+    let filled: Vec<_> = Circle::filled((5, 5), 1).collect();
+    assert_eq!(filled.len(), 9);
+
+    let outline: Vec<_> = Circle::new((5, 5), 2).collect();
+    assert!(!outline.contains(&(5, 5)));
+    assert!(outline.contains(&(3, 5)));
+    assert!(outline.contains(&(5, 7)));
+    assert!(outline.iter().all(|xy| Circle::new((5, 5), 2).contains(*xy)));
+
+    let clipped: Vec<_> = Circle::filled((0, 0), 2).within(Rect::new((0, 0), 10, 10)).collect();
+    assert!(clipped.iter().all(|(x, y)| *x >= 0 && *y >= 0));
+    assert!(clipped.contains(&(2, 0)));
+}
+
+#[test]
+fn test_geometry_ellipse() {
+    let ellipse = Ellipse::filled((10, 10), (4, 1));
+    assert_eq!(ellipse.bounds(), Rect::new((6, 9), 9, 3));
+
+    let points: Vec<_> = ellipse.collect();
+    assert!(points.contains(&(6, 10)));
+    assert!(points.contains(&(14, 10)));
+    assert!(points.contains(&(10, 9)));
+    assert!(!points.contains(&(6, 9)));
+}
+
+#[test]
+fn test_geometry_draw_and_line_of_sight() {
+    use crate::grid_map::*;
+
+    let mut states = GridMap::<Transparency>::new((10, 10));
+    Line::new((5, 0), (5, 9)).draw_into(&mut states, Transparency::Opaque);
+
+    assert!(!Line::line_of_sight((0, 4), (9, 4), &states));
+    assert!(Line::line_of_sight((0, 4), (4, 4), &states));
+    assert!(Line::line_of_sight((0, 4), (5, 4), &states));
+
+    let path = Line::new((0, 4), (9, 4)).until_blocked(&states);
+    assert_eq!(path.last(), Some(&(5, 4)));
+    assert_eq!(path.len(), 6);
+}
@@ -0,0 +1,215 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use fnv::FnvHashMap;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::serialized_metrics::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Codepoints below this limit are stored densely (covers every CP437 glyph).
+const DENSE_CODEPOINT_LIMIT: i32 = 0x2700;
+
+// Slot value marking a codepoint without a metric.
+const EMPTY_SLOT: u16 = u16::MAX;
+
+//-------------------------------------------------------------------------------------------------
+// GlyphMetricTable maps codepoints to glyph metrics for a single font texture.
+// Common codepoints are resolved through a dense codepoint -> slot table built at load, avoiding a
+// hash lookup per quad. Rare codepoints fall back to a hash map.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct GlyphMetricTable {
+    // Slot in the dense metrics for each codepoint below the dense limit.
+    slots: Vec<u16>,
+    // Densely stored metrics for codepoints below the dense limit.
+    dense: Vec<GlyphMetric>,
+    // Metrics for codepoints at or above the dense limit.
+    sparse: FnvHashMap<i32, GlyphMetric>,
+    // Kerning amounts for pairs of codepoints.
+    kernings: FnvHashMap<(i32, i32), i32>,
+}
+
+impl GlyphMetricTable {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty glyph metric table.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new glyph metric table from an iterator of metrics.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_metrics<I>(metrics: I) -> Self
+    where
+        I: IntoIterator<Item = GlyphMetric>,
+    {
+        let mut table = Self::new();
+
+        for metric in metrics {
+            table.insert(metric);
+        }
+
+        table
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new glyph metric table from deserialized font metrics, including kernings.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_font_metrics(font_metrics: FontMetricsV2) -> Self {
+        let mut table = Self::from_metrics(font_metrics.metrics);
+
+        for kerning in font_metrics.kernings {
+            table.kernings.insert((kerning.first, kerning.second), kerning.amount);
+        }
+
+        table
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Inserts a metric, replacing any existing metric for the same codepoint.
+    //---------------------------------------------------------------------------------------------
+    pub fn insert(&mut self, metric: GlyphMetric) {
+        let codepoint = metric.codepoint;
+
+        // Store rare codepoints (or any once the dense slots are exhausted) in the hash map.
+        if !(0..DENSE_CODEPOINT_LIMIT).contains(&codepoint)
+            || self.dense.len() >= EMPTY_SLOT as usize
+        {
+            self.sparse.insert(codepoint, metric);
+            return;
+        }
+
+        // Grow the slot table to fit the codepoint.
+        let index = codepoint as usize;
+
+        if index >= self.slots.len() {
+            self.slots.resize(index + 1, EMPTY_SLOT);
+        }
+
+        match self.slots[index] {
+            EMPTY_SLOT => {
+                self.slots[index] = self.dense.len() as u16;
+                self.dense.push(metric);
+            }
+            slot => self.dense[slot as usize] = metric,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the metric for a codepoint, if there is one.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, codepoint: i32) -> Option<&GlyphMetric> {
+        if (0..DENSE_CODEPOINT_LIMIT).contains(&codepoint) {
+            match self.slots.get(codepoint as usize) {
+                Some(&slot) if slot != EMPTY_SLOT => return Some(&self.dense[slot as usize]),
+                _ => {}
+            }
+        }
+
+        self.sparse.get(&codepoint)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the horizontal advance for a codepoint, if there is a metric for it.
+    // (metrics without an advance fall back to the extent of the glyph)
+    //---------------------------------------------------------------------------------------------
+    pub fn advance(&self, codepoint: i32) -> Option<i32> {
+        self.get(codepoint).map(GlyphMetric::advance)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the kerning adjustment between a pair of codepoints (0 if there is none).
+    //---------------------------------------------------------------------------------------------
+    pub fn kerning(&self, first: i32, second: i32) -> i32 {
+        if self.kernings.is_empty() {
+            return 0;
+        }
+
+        self.kernings.get(&(first, second)).copied().unwrap_or(0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there is a metric for a codepoint.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, codepoint: i32) -> bool {
+        self.get(codepoint).is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of metrics in the table.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.dense.len() + self.sparse.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the table is empty.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+fn test_metric(codepoint: i32) -> GlyphMetric {
+    GlyphMetric {
+        codepoint,
+        x: codepoint,
+        y: 0,
+        width: 1,
+        height: 1,
+        x_offset: 0,
+        y_offset: 0,
+        x_advance: 2,
+    }
+}
+
+#[test]
+fn test_glyph_metric_table() {
+    let mut table = GlyphMetricTable::from_metrics(
+        crate::cp437::CP437_CHARS.iter().map(|&c| test_metric(c as i32)),
+    );
+    table.insert(test_metric(0x1F600));
+    table.insert(test_metric(-1));
+    assert_eq!(table.len(), crate::cp437::CP437_CHARS.len() + 2);
+
+    // Dense, sparse and missing lookups.
+    assert_eq!(table.get('☺' as i32).unwrap().x, '☺' as i32);
+    assert_eq!(table.get(0x1F600).unwrap().x, 0x1F600);
+    assert_eq!(table.get(-1).unwrap().x, -1);
+    assert!(!table.contains(0x2699));
+    assert!(!table.contains(0x10FFFF));
+
+    // Replacing a metric does not grow the table.
+    let mut replacement = test_metric('a' as i32);
+    replacement.width = 7;
+    table.insert(replacement);
+    assert_eq!(table.len(), crate::cp437::CP437_CHARS.len() + 2);
+    assert_eq!(table.get('a' as i32).unwrap().width, 7);
+
+    // Advances and kernings.
+    let table = GlyphMetricTable::from_font_metrics(FontMetricsV2 {
+        metrics: vec![
+            test_metric('A' as i32),
+            GlyphMetric { x_advance: 0, ..test_metric('V' as i32) },
+        ],
+        kernings: vec![GlyphKerning { first: 'A' as i32, second: 'V' as i32, amount: -1 }],
+        substitutions: Vec::new(),
+    });
+    assert_eq!(table.advance('A' as i32), Some(2));
+    assert_eq!(table.advance('V' as i32), Some(1));
+    assert_eq!(table.advance('W' as i32), None);
+    assert_eq!(table.kerning('A' as i32, 'V' as i32), -1);
+    assert_eq!(table.kerning('V' as i32, 'A' as i32), 0);
+}
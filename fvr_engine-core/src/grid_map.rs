@@ -7,6 +7,7 @@ use crate::misc::*;
 //-------------------------------------------------------------------------------------------------
 // GridMap describes a 2D grid represented internally by a 1D array.
 //-------------------------------------------------------------------------------------------------
+#[derive(Clone)]
 pub struct GridMap<T>
 where
     T: Map2dType,
@@ -1,40 +1,76 @@
 mod a_star;
+mod accessibility;
 mod adjacency;
+mod ambient_animation;
+mod autotile;
+mod camera;
+mod charges;
+mod color_grade;
+mod command_stack;
+mod compression;
 mod config;
+mod connectivity;
 mod cp437;
 mod dijkstra_map;
 mod direction;
 mod distance;
 mod fov;
+mod geometry;
+mod glyph_metric_table;
 mod grid_map;
 mod lines;
 mod map2d;
 mod misc;
+mod mods;
+mod noise;
 mod palette_color;
+mod platform_dirs;
+mod pool;
 mod radius;
 mod rect;
+mod rex_paint;
+mod rng;
 mod serialized_metrics;
+mod settings;
 mod sub_map;
 mod tile;
 mod timer;
 
 pub mod prelude {
     pub use crate::a_star::*;
+    pub use crate::accessibility::*;
     pub use crate::adjacency::*;
+    pub use crate::ambient_animation::*;
+    pub use crate::autotile::*;
+    pub use crate::camera::*;
+    pub use crate::charges::*;
+    pub use crate::color_grade::*;
+    pub use crate::command_stack::*;
+    pub use crate::compression::*;
     pub use crate::config::*;
+    pub use crate::connectivity::*;
     pub use crate::cp437::*;
     pub use crate::dijkstra_map::*;
     pub use crate::direction::*;
     pub use crate::distance::*;
     pub use crate::fov::*;
+    pub use crate::geometry::*;
+    pub use crate::glyph_metric_table::*;
     pub use crate::grid_map::*;
     pub use crate::lines::*;
     pub use crate::map2d::*;
     pub use crate::misc::*;
+    pub use crate::mods::*;
+    pub use crate::noise::*;
     pub use crate::palette_color::*;
+    pub use crate::platform_dirs::*;
+    pub use crate::pool::*;
     pub use crate::radius::*;
     pub use crate::rect::*;
+    pub use crate::rex_paint::*;
+    pub use crate::rng::*;
     pub use crate::serialized_metrics::*;
+    pub use crate::settings::*;
     pub use crate::sub_map::*;
     pub use crate::tile::*;
     pub use crate::timer::*;
@@ -0,0 +1,376 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::platform_dirs::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Name of the config file listing the mods to load, in order.
+pub const MOD_MANIFEST_FILE: &str = "mods.json";
+
+// Id of the layer of the base game's content.
+pub const BASE_LAYER_ID: &str = "base";
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+pub static CONTENT_LAYERS: Lazy<ContentLayers> = Lazy::new(|| {
+    let base = PLATFORM_DIRS.assets_dir();
+
+    let layers =
+        ModManifest::load(PLATFORM_DIRS.config_file(MOD_MANIFEST_FILE)).and_then(|manifest| {
+            ContentLayers::from_manifest(base, PLATFORM_DIRS.mods_dir(), &manifest)
+        });
+
+    // Fall back to the base game's content if the mods cannot be loaded.
+    let layers = layers.unwrap_or_else(|e| {
+        eprintln!("[Mods] Failed to load mods: {:#}", e);
+        ContentLayers::new(base)
+    });
+
+    match layers.file_conflicts() {
+        Ok(conflicts) => {
+            for conflict in conflicts.iter() {
+                eprintln!("[Mods] Conflict: {}", conflict);
+            }
+        }
+        Err(e) => eprintln!("[Mods] Failed to check for conflicts: {:#}", e),
+    }
+
+    layers
+});
+
+//-------------------------------------------------------------------------------------------------
+// ModManifest lists the mods to load after the base game, in load order (later mods override
+// earlier ones).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ModManifest {
+    // Ids (directory names) of the mods to load, in order.
+    #[serde(default)]
+    pub load_order: Vec<String>,
+    // Ids of the mods in the load order to skip.
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl ModManifest {
+    //---------------------------------------------------------------------------------------------
+    // Loads a manifest from a json file (an absent file lists no mods).
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read mod manifest {}.", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse mod manifest {}.", path.display()))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the ids of the enabled mods, in load order.
+    //---------------------------------------------------------------------------------------------
+    pub fn enabled(&self) -> impl Iterator<Item = &str> {
+        self.load_order.iter().filter(move |id| !self.disabled.contains(id)).map(String::as_str)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A directory of content: the base game's assets or a mod.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentLayer {
+    // Id of the layer (BASE_LAYER_ID or the mod's directory name).
+    pub id: String,
+    // Root directory of the layer.
+    pub root: PathBuf,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes content provided by more than one mod, where the last mod in load order wins.
+// (overriding the base game's content is intended, and is not a conflict)
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModConflict {
+    // Relative path of the file.
+    pub content: String,
+    // Ids of the mods providing the content, in load order.
+    pub mods: Vec<String>,
+}
+
+impl fmt::Display for ModConflict {
+    //---------------------------------------------------------------------------------------------
+    // Formats the conflict as "<content> is provided by <mods> (<last mod> wins)".
+    //---------------------------------------------------------------------------------------------
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is provided by {} ({} wins)",
+            self.content,
+            self.mods.join(", "),
+            self.mods.last().map(String::as_str).unwrap_or(BASE_LAYER_ID)
+        )
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ContentLayers resolves content across the base game's content directory and the content
+// directories of the enabled mods, in load order. Files in later layers override files with the
+// same relative path in earlier layers.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentLayers {
+    // The layers, in load order (the base game's first).
+    layers: Vec<ContentLayer>,
+}
+
+impl ContentLayers {
+    //---------------------------------------------------------------------------------------------
+    // Creates content layers of only the base game's content.
+    //---------------------------------------------------------------------------------------------
+    pub fn new<P>(base: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let base = ContentLayer { id: BASE_LAYER_ID.into(), root: base.as_ref().into() };
+        Self { layers: vec![base] }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates content layers of the base game's content and the enabled mods of a manifest.
+    // Every missing or duplicated mod is reported at once.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_manifest<B, M>(base: B, mods_dir: M, manifest: &ModManifest) -> Result<Self>
+    where
+        B: AsRef<Path>,
+        M: AsRef<Path>,
+    {
+        let mut layers = Self::new(base);
+        let mut errors = Vec::new();
+
+        for id in manifest.enabled() {
+            let root = mods_dir.as_ref().join(id);
+
+            if id == BASE_LAYER_ID || layers.layers.iter().any(|layer| layer.id == id) {
+                errors.push(format!("Mod '{}' is listed more than once.", id));
+            } else if !root.is_dir() {
+                errors.push(format!("Mod '{}' was not found at {}.", id, root.display()));
+            } else {
+                layers.push(id, root);
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!("Found {} mod errors:\n  {}", errors.len(), errors.join("\n  "));
+        }
+
+        Ok(layers)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds a layer, which overrides the existing layers.
+    //---------------------------------------------------------------------------------------------
+    pub fn push<P>(&mut self, id: &str, root: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.layers.push(ContentLayer { id: id.into(), root: root.as_ref().into() });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the layers, in load order.
+    //---------------------------------------------------------------------------------------------
+    pub fn layers(&self) -> &[ContentLayer] {
+        &self.layers
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the path of a file from the last layer providing it.
+    // (the base game's path if no layer provides it, so that errors name the expected path)
+    //---------------------------------------------------------------------------------------------
+    pub fn resolve<P>(&self, path: P) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        self.layers
+            .iter()
+            .rev()
+            .map(|layer| layer.root.join(path))
+            .find(|candidate| candidate.exists())
+            .unwrap_or_else(|| self.layers[0].root.join(path))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the ids of the layers providing a file and the paths of their copies, in load order
+    // (e.g. for content that extends rather than overrides).
+    //---------------------------------------------------------------------------------------------
+    pub fn find_all<P>(&self, path: P) -> Vec<(&str, PathBuf)>
+    where
+        P: AsRef<Path>,
+    {
+        self.layers
+            .iter()
+            .map(|layer| (layer.id.as_str(), layer.root.join(path.as_ref())))
+            .filter(|(_, candidate)| candidate.exists())
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the files of a directory merged across the layers by file name, ordered by name.
+    //---------------------------------------------------------------------------------------------
+    pub fn merged_dir<P>(&self, dir: P) -> Result<BTreeMap<String, PathBuf>>
+    where
+        P: AsRef<Path>,
+    {
+        let mut files = BTreeMap::new();
+
+        for layer in self.layers.iter() {
+            for path in Self::files_in(&layer.root.join(dir.as_ref()))? {
+                let name = path.file_name().expect("Unreachable.").to_string_lossy().into_owned();
+                let _ = files.insert(name, path);
+            }
+        }
+
+        Ok(files)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the files provided by more than one mod, ordered by relative path.
+    //---------------------------------------------------------------------------------------------
+    pub fn file_conflicts(&self) -> Result<Vec<ModConflict>> {
+        let mut providers: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+
+        for layer in self.layers.iter().filter(|layer| layer.id != BASE_LAYER_ID) {
+            let mut files = BTreeSet::new();
+            Self::collect_files(&layer.root, &layer.root, &mut files)?;
+
+            for file in files {
+                providers.entry(file).or_default().push(layer.id.clone());
+            }
+        }
+
+        Ok(providers
+            .into_iter()
+            .filter(|(_, mods)| mods.len() > 1)
+            .map(|(path, mods)| ModConflict { content: path.display().to_string(), mods })
+            .collect())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the paths of the files in a directory, ordered by name.
+    // (an absent directory has no files)
+    //---------------------------------------------------------------------------------------------
+    fn files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(dir)
+            .with_context(|| format!("Failed to read content directory {}.", dir.display()))?
+        {
+            let entry = entry?;
+
+            if entry.file_type()?.is_file() {
+                files.push(entry.path());
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that collects the paths of the files under a directory, relative to a root.
+    //---------------------------------------------------------------------------------------------
+    fn collect_files(root: &Path, dir: &Path, files: &mut BTreeSet<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)
+            .with_context(|| format!("Failed to read content directory {}.", dir.display()))?
+        {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                Self::collect_files(root, &entry.path(), files)?;
+            } else {
+                let _ = files.insert(entry.path().strip_prefix(root)?.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_content_layers() {
+    let root = std::env::temp_dir().join(format!("fvr_engine_mods_{}", std::process::id()));
+    let base = root.join("assets");
+    let mods_dir = root.join("mods");
+
+    let write = |path: PathBuf, contents: &str| {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    };
+
+    write(base.join("fonts/a.json"), "base");
+    write(base.join("fonts/b.json"), "base");
+    write(mods_dir.join("one/fonts/a.json"), "one");
+    write(mods_dir.join("two/fonts/a.json"), "two");
+    write(mods_dir.join("three/fonts/a.json"), "three");
+
+    // Disabled mods are skipped, and missing mods are reported.
+    let manifest = ModManifest {
+        load_order: vec!["one".into(), "two".into(), "three".into()],
+        disabled: vec!["three".into()],
+    };
+    let layers = ContentLayers::from_manifest(&base, &mods_dir, &manifest).unwrap();
+    let ids: Vec<&str> = layers.layers().iter().map(|layer| layer.id.as_str()).collect();
+    assert_eq!(ids, vec![BASE_LAYER_ID, "one", "two"]);
+
+    let missing = ModManifest { load_order: vec!["one".into(), "four".into()], disabled: vec![] };
+    assert!(ContentLayers::from_manifest(&base, &mods_dir, &missing).is_err());
+
+    // Later layers override files.
+    assert_eq!(fs::read_to_string(layers.resolve("fonts/a.json")).unwrap(), "two");
+    assert_eq!(fs::read_to_string(layers.resolve("fonts/b.json")).unwrap(), "base");
+    assert_eq!(layers.resolve("fonts/c.json"), base.join("fonts/c.json"));
+    assert_eq!(layers.find_all("fonts/a.json").len(), 3);
+    assert_eq!(layers.merged_dir("fonts").unwrap().len(), 2);
+
+    // Files provided by more than one mod are conflicts.
+    let conflicts = layers.file_conflicts().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].content, Path::new("fonts").join("a.json").display().to_string());
+
+    fs::remove_dir_all(&root).unwrap();
+}
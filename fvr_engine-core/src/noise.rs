@@ -0,0 +1,380 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use rand::seq::SliceRandom;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::grid_map::*;
+use crate::map2d::*;
+use crate::map2d_iter_index_mut;
+use crate::rng::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Skew and unskew factors for 2D simplex noise.
+const SIMPLEX_F2: f32 = 0.366_025_42;
+const SIMPLEX_G2: f32 = 0.211_324_87;
+
+// Scale to bring 2D simplex noise into the range [-1.0..1.0].
+const SIMPLEX_SCALE: f32 = 70.0;
+
+// Scale to bring 2D perlin noise into the range [-1.0..1.0].
+const PERLIN_SCALE: f32 = std::f32::consts::SQRT_2;
+
+// Offsets applied to the second warp sample so the x and y displacements are not correlated.
+const WARP_OFFSET_X: (f32, f32) = (0.0, 0.0);
+const WARP_OFFSET_Y: (f32, f32) = (5.2, 1.3);
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the available base noise functions.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoiseType {
+    // Gradient noise sampled on a square lattice.
+    Perlin,
+    // Gradient noise sampled on a triangular lattice.
+    #[default]
+    Simplex,
+    // Interpolated random values sampled on a square lattice.
+    Value,
+}
+
+//-------------------------------------------------------------------------------------------------
+// NoiseSettings describes how the octaves of a fractal noise function are combined.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct NoiseSettings {
+    // The base noise function to sample.
+    pub noise_type: NoiseType,
+    // Frequency of the first octave (in cycles per cell).
+    pub frequency: f32,
+    // Number of octaves to sum.
+    pub octaves: u32,
+    // Frequency multiplier applied for each successive octave.
+    pub lacunarity: f32,
+    // Amplitude multiplier applied for each successive octave.
+    pub gain: f32,
+}
+
+impl Default for NoiseSettings {
+    fn default() -> Self {
+        Self {
+            noise_type: Default::default(),
+            frequency: 0.05,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Noise provides seedable 2D coherent noise functions.
+// Single samples are in the range [-1.0..1.0] unless stated otherwise.
+// Adapted from Ken Perlin's improved noise and Stefan Gustavson's simplex noise paper.
+//-------------------------------------------------------------------------------------------------
+pub struct Noise {
+    // The seed used to generate the permutation table.
+    seed: u64,
+    // Shuffled permutation table, repeated twice to avoid wrapping indices.
+    permutations: Vec<u8>,
+}
+
+impl Noise {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new noise generator from a seed.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut rng);
+
+        let mut permutations = Vec::with_capacity(512);
+        permutations.extend_from_slice(&table);
+        permutations.extend_from_slice(&table);
+
+        Self { seed, permutations }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the seed of the noise generator.
+    //---------------------------------------------------------------------------------------------
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Hashes a lattice coord into the range [0..255].
+    //---------------------------------------------------------------------------------------------
+    fn hash(&self, x: i32, y: i32) -> u8 {
+        let xi = (x & 255) as usize;
+        let yi = (y & 255) as usize;
+        self.permutations[self.permutations[xi] as usize + yi]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Quintic fade curve used for smooth interpolation.
+    //---------------------------------------------------------------------------------------------
+    fn fade(t: f32) -> f32 {
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Linear interpolation between two values.
+    //---------------------------------------------------------------------------------------------
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + t * (b - a)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dot product of an offset with one of eight gradient vectors picked by hash.
+    //---------------------------------------------------------------------------------------------
+    fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+        match hash & 7 {
+            0 => x + y,
+            1 => -x + y,
+            2 => x - y,
+            3 => -x - y,
+            4 => x,
+            5 => -x,
+            6 => y,
+            _ => -y,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples value noise at a point.
+    //---------------------------------------------------------------------------------------------
+    pub fn value(&self, x: f32, y: f32) -> f32 {
+        let x0 = x.floor() as i32;
+        let y0 = y.floor() as i32;
+        let u = Self::fade(x - x0 as f32);
+        let v = Self::fade(y - y0 as f32);
+
+        // Convert the lattice hashes into the range [-1.0..1.0].
+        let corner = |cx, cy| (self.hash(cx, cy) as f32 / 127.5) - 1.0;
+
+        let top = Self::lerp(corner(x0, y0), corner(x0 + 1, y0), u);
+        let bottom = Self::lerp(corner(x0, y0 + 1), corner(x0 + 1, y0 + 1), u);
+        Self::lerp(top, bottom, v)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples perlin noise at a point.
+    //---------------------------------------------------------------------------------------------
+    pub fn perlin(&self, x: f32, y: f32) -> f32 {
+        let x0 = x.floor() as i32;
+        let y0 = y.floor() as i32;
+        let fx = x - x0 as f32;
+        let fy = y - y0 as f32;
+        let u = Self::fade(fx);
+        let v = Self::fade(fy);
+
+        let top = Self::lerp(
+            Self::gradient(self.hash(x0, y0), fx, fy),
+            Self::gradient(self.hash(x0 + 1, y0), fx - 1.0, fy),
+            u,
+        );
+        let bottom = Self::lerp(
+            Self::gradient(self.hash(x0, y0 + 1), fx, fy - 1.0),
+            Self::gradient(self.hash(x0 + 1, y0 + 1), fx - 1.0, fy - 1.0),
+            u,
+        );
+
+        (Self::lerp(top, bottom, v) * PERLIN_SCALE).clamp(-1.0, 1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples simplex noise at a point.
+    //---------------------------------------------------------------------------------------------
+    pub fn simplex(&self, x: f32, y: f32) -> f32 {
+        // Skew the input space to find the containing simplex cell.
+        let s = (x + y) * SIMPLEX_F2;
+        let i = (x + s).floor() as i32;
+        let j = (y + s).floor() as i32;
+
+        // Unskew the cell origin back to input space.
+        let t = (i + j) as f32 * SIMPLEX_G2;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+
+        // Determine which of the two triangles in the cell contains the point.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        // Offsets for the middle and last corners.
+        let x1 = x0 - i1 as f32 + SIMPLEX_G2;
+        let y1 = y0 - j1 as f32 + SIMPLEX_G2;
+        let x2 = x0 - 1.0 + 2.0 * SIMPLEX_G2;
+        let y2 = y0 - 1.0 + 2.0 * SIMPLEX_G2;
+
+        // Sum the contribution of each corner.
+        let contribution = |hash, cx: f32, cy: f32| {
+            let t = 0.5 - cx * cx - cy * cy;
+            if t < 0.0 {
+                0.0
+            } else {
+                let t2 = t * t;
+                t2 * t2 * Self::gradient(hash, cx, cy)
+            }
+        };
+
+        let n0 = contribution(self.hash(i, j), x0, y0);
+        let n1 = contribution(self.hash(i + i1, j + j1), x1, y1);
+        let n2 = contribution(self.hash(i + 1, j + 1), x2, y2);
+
+        ((n0 + n1 + n2) * SIMPLEX_SCALE).clamp(-1.0, 1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples a base noise function at a point.
+    //---------------------------------------------------------------------------------------------
+    pub fn sample(&self, noise_type: NoiseType, x: f32, y: f32) -> f32 {
+        match noise_type {
+            NoiseType::Perlin => self.perlin(x, y),
+            NoiseType::Simplex => self.simplex(x, y),
+            NoiseType::Value => self.value(x, y),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples fractal brownian motion (summed octaves) at a point.
+    //---------------------------------------------------------------------------------------------
+    pub fn fbm(&self, x: f32, y: f32, settings: &NoiseSettings) -> f32 {
+        let mut frequency = settings.frequency;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut max_total = 0.0;
+
+        for _ in 0..settings.octaves.max(1) {
+            total += self.sample(settings.noise_type, x * frequency, y * frequency) * amplitude;
+            max_total += amplitude;
+            frequency *= settings.lacunarity;
+            amplitude *= settings.gain;
+        }
+
+        total / max_total
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples ridged multifractal noise at a point.
+    // (result is in the range [0.0..1.0], with ridges approaching 1.0)
+    //---------------------------------------------------------------------------------------------
+    pub fn ridged(&self, x: f32, y: f32, settings: &NoiseSettings) -> f32 {
+        let mut frequency = settings.frequency;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut max_total = 0.0;
+
+        for _ in 0..settings.octaves.max(1) {
+            let ridge = 1.0 - self.sample(settings.noise_type, x * frequency, y * frequency).abs();
+            total += ridge * ridge * amplitude;
+            max_total += amplitude;
+            frequency *= settings.lacunarity;
+            amplitude *= settings.gain;
+        }
+
+        total / max_total
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Samples domain warped fbm at a point.
+    // (the point is displaced by two fbm samples scaled by strength before the final sample)
+    //---------------------------------------------------------------------------------------------
+    pub fn warped(&self, x: f32, y: f32, settings: &NoiseSettings, strength: f32) -> f32 {
+        let warp_x = self.fbm(x + WARP_OFFSET_X.0, y + WARP_OFFSET_X.1, settings);
+        let warp_y = self.fbm(x + WARP_OFFSET_Y.0, y + WARP_OFFSET_Y.1, settings);
+        self.fbm(x + warp_x * strength, y + warp_y * strength, settings)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a grid map by calling a sampling function for every coord.
+    //---------------------------------------------------------------------------------------------
+    fn fill_with<F>(map: &mut GridMap<f32>, mut f: F)
+    where
+        F: FnMut(f32, f32) -> f32,
+    {
+        map2d_iter_index_mut!(map, x, y, value, {
+            *value = f(x as f32, y as f32);
+        });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a grid map with fbm noise.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_fbm(&self, map: &mut GridMap<f32>, settings: &NoiseSettings) {
+        Self::fill_with(map, |x, y| self.fbm(x, y, settings));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a grid map with ridged noise.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_ridged(&self, map: &mut GridMap<f32>, settings: &NoiseSettings) {
+        Self::fill_with(map, |x, y| self.ridged(x, y, settings));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fills a grid map with domain warped fbm noise.
+    //---------------------------------------------------------------------------------------------
+    pub fn fill_warped(&self, map: &mut GridMap<f32>, settings: &NoiseSettings, strength: f32) {
+        Self::fill_with(map, |x, y| self.warped(x, y, settings, strength));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Rescales the values of a grid map to the range [0.0..1.0].
+    //---------------------------------------------------------------------------------------------
+    pub fn normalize(map: &mut GridMap<f32>) {
+        let min = map.data().iter().cloned().fold(f32::MAX, f32::min);
+        let max = map.data().iter().cloned().fold(f32::MIN, f32::max);
+        let range = max - min;
+
+        for value in map.data_mut().iter_mut() {
+            *value = if range > 0.0 { (*value - min) / range } else { 0.0 };
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_noise_deterministic() {
+    let settings = NoiseSettings::default();
+    let a = Noise::new(1337);
+    let b = Noise::new(1337);
+    let c = Noise::new(7331);
+
+    let mut map_a = GridMap::new((16, 16));
+    let mut map_b = GridMap::new((16, 16));
+    let mut map_c = GridMap::new((16, 16));
+    a.fill_fbm(&mut map_a, &settings);
+    b.fill_fbm(&mut map_b, &settings);
+    c.fill_fbm(&mut map_c, &settings);
+
+    assert_eq!(map_a.data(), map_b.data());
+    assert_ne!(map_a.data(), map_c.data());
+}
+
+#[test]
+fn test_noise_range() {
+    let noise = Noise::new(42);
+
+    for noise_type in [NoiseType::Perlin, NoiseType::Simplex, NoiseType::Value] {
+        let settings = NoiseSettings { noise_type, ..Default::default() };
+        let mut map = GridMap::new((32, 32));
+
+        noise.fill_fbm(&mut map, &settings);
+        assert!(map.data().iter().all(|v| (-1.0..=1.0).contains(v)));
+
+        noise.fill_ridged(&mut map, &settings);
+        assert!(map.data().iter().all(|v| (0.0..=1.0).contains(v)));
+
+        noise.fill_warped(&mut map, &settings, 4.0);
+        Noise::normalize(&mut map);
+        assert!(map.data().iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+}
@@ -1,3 +1,8 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
@@ -7,8 +12,35 @@ use serde_derive::{Deserialize, Serialize};
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::accessibility::*;
 use crate::tile::*;
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Number of palette cycling groups.
+pub const PALETTE_CYCLE_COUNT: usize = 2;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Colors of the palette cycling groups. These are reserved for cycling (distinct from the palette
+// colors) so that only tiles colored for cycling are animated.
+static WATER_CYCLE_COLORS: [TileColor; 4] = [
+    TileColor(SdlColor { r: 23, g: 62, b: 148, a: 255 }),
+    TileColor(SdlColor { r: 30, g: 86, b: 178, a: 255 }),
+    TileColor(SdlColor { r: 44, g: 116, b: 204, a: 255 }),
+    TileColor(SdlColor { r: 70, g: 150, b: 224, a: 255 }),
+];
+static FIRE_CYCLE_COLORS: [TileColor; 4] = [
+    TileColor(SdlColor { r: 156, g: 32, b: 20, a: 255 }),
+    TileColor(SdlColor { r: 214, g: 66, b: 18, a: 255 }),
+    TileColor(SdlColor { r: 245, g: 124, b: 24, a: 255 }),
+    TileColor(SdlColor { r: 252, g: 186, b: 48, a: 255 }),
+];
+
 //-------------------------------------------------------------------------------------------------
 // Enumerates the different color palettes.
 //-------------------------------------------------------------------------------------------------
@@ -148,3 +180,185 @@ impl From<PaletteColor> for TileColor {
         palette_color.const_into()
     }
 }
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the palette cycling groups. Tiles colored with the colors of a group are animated by
+// rotating the group's colors over time (the classic flowing water and flickering fire effects).
+//-------------------------------------------------------------------------------------------------
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash, Serialize)]
+pub enum PaletteCycle {
+    Water,
+    Fire,
+}
+
+impl PaletteCycle {
+    //---------------------------------------------------------------------------------------------
+    // Constants.
+    //---------------------------------------------------------------------------------------------
+    pub const ALL: [PaletteCycle; PALETTE_CYCLE_COUNT] = [PaletteCycle::Water, PaletteCycle::Fire];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the colors of the group, in cycling order.
+    //---------------------------------------------------------------------------------------------
+    pub fn colors(&self) -> &'static [TileColor] {
+        match self {
+            PaletteCycle::Water => &WATER_CYCLE_COLORS,
+            PaletteCycle::Fire => &FIRE_CYCLE_COLORS,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the duration between each rotation of the group's colors.
+    //---------------------------------------------------------------------------------------------
+    pub const fn step(&self) -> Duration {
+        match self {
+            PaletteCycle::Water => Duration::from_millis(250),
+            PaletteCycle::Fire => Duration::from_millis(100),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color to write into tiles for an entry of the group (wrapping).
+    //---------------------------------------------------------------------------------------------
+    pub fn color(&self, entry: usize) -> TileColor {
+        let colors = self.colors();
+        colors[entry % colors.len()]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the group and entry of a color, if it is a palette cycling color.
+    //---------------------------------------------------------------------------------------------
+    pub fn find(color: &TileColor) -> Option<(PaletteCycle, usize)> {
+        PaletteCycle::ALL.iter().find_map(|cycle| {
+            cycle.colors().iter().position(|c| c == color).map(|entry| (*cycle, entry))
+        })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// PaletteCycler rotates the mapping of the palette cycling groups' colors over time. Tiles keep
+// their cycling colors and are remapped when rendered, so cycling needs no per-tile state.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct PaletteCycler {
+    // Time accumulated towards the next rotation of each group.
+    elapsed: [Duration; PALETTE_CYCLE_COUNT],
+    // Current rotation of each group.
+    offsets: [usize; PALETTE_CYCLE_COUNT],
+}
+
+impl PaletteCycler {
+    //---------------------------------------------------------------------------------------------
+    // Advances the rotations by a frame delta time, scaled and paused per the accessibility
+    // options, returning whether the mapping changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self, dt: Duration) -> bool {
+        if !Accessibility::motion_enabled() {
+            return false;
+        }
+
+        self.advance(Accessibility::animation_dt(dt))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Advances the rotations by a duration, returning whether the mapping changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn advance(&mut self, dt: Duration) -> bool {
+        let mut changed = false;
+
+        for cycle in PaletteCycle::ALL.iter() {
+            let i = *cycle as usize;
+            let step = cycle.step();
+            self.elapsed[i] += dt;
+
+            while self.elapsed[i] >= step {
+                self.elapsed[i] -= step;
+                self.offsets[i] = (self.offsets[i] + 1) % cycle.colors().len();
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the current rotation of each group (e.g. for detecting changes).
+    //---------------------------------------------------------------------------------------------
+    pub fn offsets(&self) -> [usize; PALETTE_CYCLE_COUNT] {
+        self.offsets
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets the rotations so that colors map to themselves.
+    //---------------------------------------------------------------------------------------------
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color a palette cycling color currently maps to, if it is one.
+    //---------------------------------------------------------------------------------------------
+    pub fn remap(&self, color: &TileColor) -> Option<TileColor> {
+        let (cycle, entry) = PaletteCycle::find(color)?;
+        Some(cycle.color(entry + self.offsets[cycle as usize]))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a copy of a tile with its palette cycling colors remapped, if it has any.
+    //---------------------------------------------------------------------------------------------
+    pub fn cycle_tile(&self, tile: &Tile) -> Option<Tile> {
+        let background_color = self.remap(&tile.background_color);
+        let foreground_color = self.remap(&tile.foreground_color);
+        let outline_color = self.remap(&tile.outline_color);
+        let gradient_color = tile.background_gradient.end_color().and_then(|c| self.remap(&c));
+
+        if background_color.is_none()
+            && foreground_color.is_none()
+            && outline_color.is_none()
+            && gradient_color.is_none()
+        {
+            return None;
+        }
+
+        let background_gradient = match (tile.background_gradient, gradient_color) {
+            (TileGradient::Vertical(_), Some(color)) => TileGradient::Vertical(color),
+            (TileGradient::Horizontal(_), Some(color)) => TileGradient::Horizontal(color),
+            (gradient, _) => gradient,
+        };
+
+        Some(Tile {
+            background_color: background_color.unwrap_or(tile.background_color),
+            foreground_color: foreground_color.unwrap_or(tile.foreground_color),
+            outline_color: outline_color.unwrap_or(tile.outline_color),
+            background_gradient,
+            ..*tile
+        })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_palette_cycler() {
+    let mut cycler = PaletteCycler::default();
+    let water = PaletteCycle::Water.color(1);
+    assert_eq!(PaletteCycle::find(&water), Some((PaletteCycle::Water, 1)));
+    assert_eq!(PaletteCycle::find(&PaletteColor::DarkBlue.const_into()), None);
+    assert_eq!(cycler.remap(&water), Some(water));
+
+    assert!(!cycler.advance(Duration::from_millis(50)));
+    assert!(cycler.advance(Duration::from_millis(200)));
+    assert_eq!(cycler.offsets(), [1, 2]);
+    assert_eq!(cycler.remap(&water), Some(PaletteCycle::Water.color(2)));
+
+    let tile = Tile { foreground_color: water, ..BLANK_TILE };
+    assert_eq!(cycler.cycle_tile(&tile).unwrap().foreground_color, PaletteCycle::Water.color(2));
+    assert!(cycler.cycle_tile(&BLANK_TILE).is_none());
+
+    // Rotations wrap around the group.
+    assert!(cycler.advance(Duration::from_millis(750)));
+    assert_eq!(cycler.remap(&water), Some(water));
+}
@@ -0,0 +1,299 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Name of the per-user project directories.
+const PROJECT_NAME: &str = "fvr_engine";
+
+// Env var overriding the per-user directories (config, saves and cache become subdirectories).
+pub const PLATFORM_DIRS_HOME_VAR: &str = "FVR_ENGINE_HOME";
+
+// Env var overriding the directory containing the shipped config defaults and assets.
+pub const PLATFORM_DIRS_RESOURCES_VAR: &str = "FVR_ENGINE_RESOURCES";
+
+// Names of the shipped config defaults and assets directories within the resources directory.
+const DEFAULTS_DIR_NAME: &str = "config";
+const ASSETS_DIR_NAME: &str = "assets";
+
+// Name of the per-user mods directory.
+const MODS_DIR_NAME: &str = "mods";
+
+// Prefix of shipped config files that are always read from the defaults directory.
+const DEFAULTS_ONLY_PREFIX: &str = "default_";
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+pub static PLATFORM_DIRS: Lazy<PlatformDirs> = Lazy::new(|| {
+    let dirs = PlatformDirs::resolve();
+
+    // Copy the shipped defaults into the config directory on the first run.
+    if let Err(e) = dirs.migrate_defaults() {
+        eprintln!("[PlatformDirs] Failed to migrate default config: {:#}", e);
+    }
+
+    dirs
+});
+
+//-------------------------------------------------------------------------------------------------
+// PlatformDirs resolves where config, saves and cache are kept per OS, and where the shipped
+// config defaults and assets are found:
+// - Linux: $XDG_CONFIG_HOME, $XDG_DATA_HOME and $XDG_CACHE_HOME (or their ~/. defaults).
+// - Windows: %APPDATA% for config and saves, %LOCALAPPDATA% for the cache.
+// - macOS: ~/Library/Application Support for config and saves, ~/Library/Caches for the cache.
+// The shipped resources are looked for in the working directory, then beside the executable.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct PlatformDirs {
+    // Per-user config files.
+    config_dir: PathBuf,
+    // Per-user save files.
+    save_dir: PathBuf,
+    // Per-user cache files.
+    cache_dir: PathBuf,
+    // Per-user mods (each a directory of content layered over the shipped assets).
+    mods_dir: PathBuf,
+    // Shipped config defaults.
+    defaults_dir: PathBuf,
+    // Shipped assets.
+    assets_dir: PathBuf,
+}
+
+impl PlatformDirs {
+    //---------------------------------------------------------------------------------------------
+    // Creates platform dirs with every per-user directory within a single home directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_home<P, R>(home: P, resources: R) -> Self
+    where
+        P: AsRef<Path>,
+        R: AsRef<Path>,
+    {
+        let home = home.as_ref();
+        let resources = resources.as_ref();
+
+        Self {
+            config_dir: home.join("config"),
+            save_dir: home.join("saves"),
+            cache_dir: home.join("cache"),
+            mods_dir: home.join(MODS_DIR_NAME),
+            defaults_dir: resources.join(DEFAULTS_DIR_NAME),
+            assets_dir: resources.join(ASSETS_DIR_NAME),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resolves the platform dirs from the environment.
+    //---------------------------------------------------------------------------------------------
+    pub fn resolve() -> Self {
+        let resources = Self::resources_dir();
+
+        // The override var places everything in one directory.
+        if let Some(home) = env::var_os(PLATFORM_DIRS_HOME_VAR) {
+            return Self::with_home(PathBuf::from(home), resources);
+        }
+
+        match Self::user_dirs() {
+            Some((config_dir, save_dir, cache_dir)) => Self {
+                config_dir: config_dir.join(PROJECT_NAME),
+                save_dir: save_dir.join(PROJECT_NAME),
+                cache_dir: cache_dir.join(PROJECT_NAME),
+                mods_dir: save_dir.join(PROJECT_NAME).join(MODS_DIR_NAME),
+                defaults_dir: resources.join(DEFAULTS_DIR_NAME),
+                assets_dir: resources.join(ASSETS_DIR_NAME),
+            },
+            // Fall back to keeping everything beside the resources.
+            None => Self::with_home(resources.join("user"), resources),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the directory containing the shipped defaults and assets.
+    //---------------------------------------------------------------------------------------------
+    fn resources_dir() -> PathBuf {
+        if let Some(resources) = env::var_os(PLATFORM_DIRS_RESOURCES_VAR) {
+            return PathBuf::from(resources);
+        }
+
+        let mut candidates = vec![PathBuf::from(".")];
+
+        if let Some(exe_dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(Into::into))
+        {
+            let exe_dir: PathBuf = exe_dir;
+            candidates.push(exe_dir.join("..").join("share").join(PROJECT_NAME));
+            candidates.push(exe_dir);
+        }
+
+        candidates
+            .into_iter()
+            .find(|candidate| candidate.join(DEFAULTS_DIR_NAME).is_dir())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the OS config, data and cache base directories.
+    //---------------------------------------------------------------------------------------------
+    fn user_dirs() -> Option<(PathBuf, PathBuf, PathBuf)> {
+        let var = |name: &str| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+        if cfg!(target_os = "windows") {
+            let roaming = var("APPDATA")?;
+            let local = var("LOCALAPPDATA").unwrap_or_else(|| roaming.clone());
+            Some((roaming.clone(), roaming, local))
+        } else if cfg!(target_os = "macos") {
+            let library = var("HOME")?.join("Library");
+            let support = library.join("Application Support");
+            Some((support.clone(), support, library.join("Caches")))
+        } else {
+            let home = var("HOME");
+            let xdg = |name: &str, default: &str| {
+                var(name).or_else(|| home.as_ref().map(|home| home.join(default)))
+            };
+            Some((
+                xdg("XDG_CONFIG_HOME", ".config")?,
+                xdg("XDG_DATA_HOME", ".local/share")?,
+                xdg("XDG_CACHE_HOME", ".cache")?,
+            ))
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies shipped config defaults missing from the config directory into it.
+    // Files that are always read from the defaults directory are not copied.
+    //---------------------------------------------------------------------------------------------
+    pub fn migrate_defaults(&self) -> Result<()> {
+        if !self.defaults_dir.is_dir() || self.defaults_dir == self.config_dir {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.config_dir).with_context(|| {
+            format!("Failed to create config directory {}.", self.config_dir.display())
+        })?;
+
+        for entry in fs::read_dir(&self.defaults_dir)? {
+            let entry = entry?;
+
+            if !entry.file_type()?.is_file()
+                || entry.file_name().to_string_lossy().starts_with(DEFAULTS_ONLY_PREFIX)
+            {
+                continue;
+            }
+
+            let target = self.config_dir.join(entry.file_name());
+
+            if !target.exists() {
+                fs::copy(entry.path(), &target).with_context(|| {
+                    format!("Failed to copy default config to {}.", target.display())
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the path of a config file, preferring the user's copy over the shipped default.
+    //---------------------------------------------------------------------------------------------
+    pub fn config_file(&self, name: &str) -> PathBuf {
+        let path = self.config_dir.join(name);
+
+        if path.exists() {
+            path
+        } else {
+            self.default_config_file(name)
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the path of a shipped default config file.
+    //---------------------------------------------------------------------------------------------
+    pub fn default_config_file(&self, name: &str) -> PathBuf {
+        self.defaults_dir.join(name)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the path of an asset relative to the assets directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn asset_path<P>(&self, path: P) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        self.assets_dir.join(path)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the per-user config directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the per-user save directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn save_dir(&self) -> &Path {
+        &self.save_dir
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the per-user cache directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the per-user mods directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn mods_dir(&self) -> &Path {
+        &self.mods_dir
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the shipped assets directory.
+    //---------------------------------------------------------------------------------------------
+    pub fn assets_dir(&self) -> &Path {
+        &self.assets_dir
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_platform_dirs_migrate_defaults() {
+    let root = env::temp_dir().join(format!("fvr_engine_platform_dirs_{}", std::process::id()));
+    let resources = root.join("resources");
+    fs::create_dir_all(resources.join(DEFAULTS_DIR_NAME)).unwrap();
+    fs::write(resources.join(DEFAULTS_DIR_NAME).join("a.json"), "default").unwrap();
+    fs::write(resources.join(DEFAULTS_DIR_NAME).join("b.json"), "default").unwrap();
+    fs::write(resources.join(DEFAULTS_DIR_NAME).join("default_c.json"), "default").unwrap();
+
+    // Existing user files are kept.
+    let dirs = PlatformDirs::with_home(root.join("home"), &resources);
+    fs::create_dir_all(dirs.config_dir()).unwrap();
+    fs::write(dirs.config_dir().join("a.json"), "user").unwrap();
+    dirs.migrate_defaults().unwrap();
+
+    assert_eq!(fs::read_to_string(dirs.config_file("a.json")).unwrap(), "user");
+    assert_eq!(fs::read_to_string(dirs.config_file("b.json")).unwrap(), "default");
+    assert!(dirs.config_dir().join("b.json").exists());
+    assert!(!dirs.config_dir().join("default_c.json").exists());
+    assert_eq!(dirs.config_file("missing.json"), dirs.default_config_file("missing.json"));
+    assert_eq!(dirs.asset_path("fonts"), resources.join(ASSETS_DIR_NAME).join("fonts"));
+
+    fs::remove_dir_all(&root).unwrap();
+}
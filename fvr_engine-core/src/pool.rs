@@ -0,0 +1,377 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
+
+//-------------------------------------------------------------------------------------------------
+// Poolable describes a type that can be reset and reused without reallocating.
+//-------------------------------------------------------------------------------------------------
+pub trait Poolable: Default {
+    //---------------------------------------------------------------------------------------------
+    // Resets the value to an empty state, keeping any allocated capacity.
+    //---------------------------------------------------------------------------------------------
+    fn reset(&mut self);
+}
+
+impl<T> Poolable for Vec<T> {
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Poolable for VecDeque<T> {
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, S> Poolable for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, V, S> Poolable for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Counters describing how a pool has been used.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    // Number of values that had to be newly allocated.
+    pub allocations: usize,
+    // Number of values that were reused from the pool.
+    pub reuses: usize,
+}
+
+impl PoolStats {
+    //---------------------------------------------------------------------------------------------
+    // Returns the total number of values taken from the pool.
+    //---------------------------------------------------------------------------------------------
+    pub fn takes(&self) -> usize {
+        self.allocations + self.reuses
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Pool keeps reset values around so that temporaries used every turn can be reused rather than
+// reallocated. Values are taken from the pool, used, and given back once no longer needed.
+// Counters are tracked per turn (reset by end_turn) and in total.
+//-------------------------------------------------------------------------------------------------
+pub struct Pool<T>
+where
+    T: Poolable,
+{
+    // Values available for reuse.
+    free: Vec<T>,
+    // Counters for the current turn.
+    turn_stats: PoolStats,
+    // Counters since the pool was created.
+    total_stats: PoolStats,
+}
+
+impl<T> Default for Pool<T>
+where
+    T: Poolable,
+{
+    //---------------------------------------------------------------------------------------------
+    // Default impl.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Pool<T>
+where
+    T: Poolable,
+{
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty pool.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self {
+            free: Vec::new(),
+            turn_stats: PoolStats::default(),
+            total_stats: PoolStats::default(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Takes a value from the pool, creating a new one if none are available.
+    //---------------------------------------------------------------------------------------------
+    pub fn take(&mut self) -> T {
+        match self.free.pop() {
+            Some(value) => {
+                self.turn_stats.reuses += 1;
+                self.total_stats.reuses += 1;
+                value
+            }
+            None => {
+                self.turn_stats.allocations += 1;
+                self.total_stats.allocations += 1;
+                T::default()
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Resets a value and returns it to the pool.
+    //---------------------------------------------------------------------------------------------
+    pub fn give(&mut self, mut value: T) {
+        value.reset();
+        self.free.push(value);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of values available for reuse.
+    //---------------------------------------------------------------------------------------------
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters for the current turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn turn_stats(&self) -> PoolStats {
+        self.turn_stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters since the pool was created.
+    //---------------------------------------------------------------------------------------------
+    pub fn total_stats(&self) -> PoolStats {
+        self.total_stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Ends the current turn, returning and resetting the counters for the turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_turn(&mut self) -> PoolStats {
+        std::mem::take(&mut self.turn_stats)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Drops all values available for reuse, freeing their memory.
+    //---------------------------------------------------------------------------------------------
+    pub fn shrink(&mut self) {
+        self.free.clear();
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Refers to a list of values allocated in a frame arena during a turn.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArenaSlice {
+    // Index of the first value of the list in the arena.
+    start: usize,
+    // # of values in the list.
+    len: usize,
+    // Turn of the arena the list was allocated in.
+    turn: u64,
+}
+
+impl ArenaSlice {
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of values in the list.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the list has no values.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// FrameArena allocates the short-lived lists of a turn (e.g. the facts each actor retells)
+// back to back in a single buffer, which is emptied when the turn ends but keeps its capacity.
+// Once the buffer has grown to fit a turn, allocating a list no longer allocates memory.
+// Lists are referred to by slices, which must not be used after the turn they were allocated in.
+// Allocations that had to grow the buffer are counted as allocations, the others as reuses.
+//-------------------------------------------------------------------------------------------------
+pub struct FrameArena<T> {
+    // The values of every list allocated this turn.
+    values: Vec<T>,
+    // # of turns ended (to detect slices from earlier turns).
+    turn: u64,
+    // Counters for the current turn.
+    turn_stats: PoolStats,
+    // Counters since the arena was created.
+    total_stats: PoolStats,
+}
+
+impl<T> Default for FrameArena<T> {
+    //---------------------------------------------------------------------------------------------
+    // Default impl.
+    //---------------------------------------------------------------------------------------------
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FrameArena<T> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty arena.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            turn: 0,
+            turn_stats: PoolStats::default(),
+            total_stats: PoolStats::default(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Allocates a list of values in the arena, returning the slice referring to it.
+    //---------------------------------------------------------------------------------------------
+    pub fn alloc<I>(&mut self, values: I) -> ArenaSlice
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let start = self.values.len();
+        let capacity = self.values.capacity();
+        self.values.extend(values);
+
+        if self.values.capacity() != capacity {
+            self.turn_stats.allocations += 1;
+            self.total_stats.allocations += 1;
+        } else {
+            self.turn_stats.reuses += 1;
+            self.total_stats.reuses += 1;
+        }
+
+        ArenaSlice { start, len: self.values.len() - start, turn: self.turn }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the values of a list allocated this turn.
+    // Panics if the list was allocated in an earlier turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, slice: ArenaSlice) -> &[T] {
+        assert_eq!(slice.turn, self.turn, "Arena slice used after its turn ended.");
+        &self.values[slice.start..slice.start + slice.len]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of values allocated this turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no values have been allocated this turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of values the arena can hold without growing.
+    //---------------------------------------------------------------------------------------------
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters for the current turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn turn_stats(&self) -> PoolStats {
+        self.turn_stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the counters since the arena was created.
+    //---------------------------------------------------------------------------------------------
+    pub fn total_stats(&self) -> PoolStats {
+        self.total_stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Ends the current turn, dropping the values allocated in it (keeping the capacity) and
+    // returning and resetting the counters for the turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_turn(&mut self) -> PoolStats {
+        self.values.clear();
+        self.turn += 1;
+        std::mem::take(&mut self.turn_stats)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Frees the memory of the arena (which must be empty, i.e. called between turns).
+    //---------------------------------------------------------------------------------------------
+    pub fn shrink(&mut self) {
+        self.values.clear();
+        self.values.shrink_to_fit();
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_pool() {
+    let mut pool: Pool<Vec<i32>> = Pool::new();
+
+    // The first take allocates.
+    let mut values = pool.take();
+    values.extend_from_slice(&[1, 2, 3]);
+    let capacity = values.capacity();
+    pool.give(values);
+    assert_eq!(pool.end_turn(), PoolStats { allocations: 1, reuses: 0 });
+
+    // Later takes reuse the cleared value and its capacity.
+    let values = pool.take();
+    assert!(values.is_empty());
+    assert_eq!(values.capacity(), capacity);
+    pool.give(values);
+    assert_eq!(pool.end_turn(), PoolStats { allocations: 0, reuses: 1 });
+    assert_eq!(pool.total_stats().takes(), 2);
+}
+
+#[test]
+fn test_frame_arena() {
+    let mut arena: FrameArena<i32> = FrameArena::new();
+
+    // Lists are allocated back to back, and the first allocation grows the buffer.
+    let a = arena.alloc(vec![1, 2, 3]);
+    let empty = arena.alloc(None);
+    assert_eq!(arena.get(a), &[1, 2, 3]);
+    assert!(empty.is_empty() && arena.get(empty).is_empty());
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.turn_stats().allocations, 1);
+
+    let capacity = arena.capacity();
+    assert_eq!(arena.end_turn().takes(), 2);
+    assert!(arena.is_empty());
+
+    // Later turns reuse the capacity.
+    let b = arena.alloc(vec![4]);
+    let c = arena.alloc(5..=6);
+    assert_eq!(arena.get(b), &[4]);
+    assert_eq!(arena.get(c), &[5, 6]);
+    assert_eq!(arena.capacity(), capacity);
+    assert_eq!(arena.end_turn(), PoolStats { allocations: 0, reuses: 2 });
+    assert_eq!(arena.total_stats().takes(), 4);
+}
@@ -0,0 +1,233 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::cp437::*;
+use crate::grid_map::*;
+use crate::map2d::*;
+use crate::tile::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Version number written to exported .xp files.
+const REX_PAINT_VERSION: i32 = -1;
+
+// Background color REXPaint uses to mark a cell as transparent.
+const REX_PAINT_TRANSPARENT: [u8; 3] = [255, 0, 255];
+
+// CP437 code written for glyphs that have no CP437 equivalent ('?').
+const REX_PAINT_UNKNOWN_CODE: u32 = 63;
+
+//-------------------------------------------------------------------------------------------------
+// RexPaint provides a static API for reading and writing REXPaint .xp files.
+// Each layer of an .xp file maps to a GridMap<Tile> using the glyph, foreground color, and
+// background color of each cell. Cells with REXPaint's magenta key color are transparent.
+//-------------------------------------------------------------------------------------------------
+pub struct RexPaint;
+
+impl RexPaint {
+    //---------------------------------------------------------------------------------------------
+    // Converts a CP437 code into a char.
+    //---------------------------------------------------------------------------------------------
+    fn code_to_glyph(code: u32) -> char {
+        match code {
+            1..=254 => cp437_to_char(code as u8),
+            _ => ' ',
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Converts a char into a CP437 code, substituting a similar glyph where possible.
+    //---------------------------------------------------------------------------------------------
+    fn glyph_to_code(glyph: char) -> u32 {
+        char_to_cp437(nearest_cp437(glyph)).map(u32::from).unwrap_or(REX_PAINT_UNKNOWN_CODE)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reads a little endian i32.
+    //---------------------------------------------------------------------------------------------
+    fn read_i32<R: Read>(reader: &mut R) -> Result<i32> {
+        let mut bytes = [0; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(i32::from_le_bytes(bytes))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reads the layers of an uncompressed .xp stream.
+    //---------------------------------------------------------------------------------------------
+    fn read_layers<R: Read>(reader: &mut R) -> Result<Vec<GridMap<Tile>>> {
+        // The version is not used but must be present.
+        let _ = Self::read_i32(reader).context("Failed to read .xp version.")?;
+        let layer_count = Self::read_i32(reader).context("Failed to read .xp layer count.")?;
+
+        if layer_count < 0 {
+            return Err(anyhow!(format!("Invalid .xp layer count {}.", layer_count)));
+        }
+
+        let mut layers = Vec::with_capacity(layer_count as usize);
+
+        for _ in 0..layer_count {
+            let width = Self::read_i32(reader).context("Failed to read .xp layer width.")?;
+            let height = Self::read_i32(reader).context("Failed to read .xp layer height.")?;
+
+            if width < 0 || height < 0 {
+                return Err(anyhow!(format!("Invalid .xp layer size {}x{}.", width, height)));
+            }
+
+            let mut layer = GridMap::new((width, height));
+
+            // Cells are stored in column-major order.
+            for x in 0..width {
+                for y in 0..height {
+                    let mut cell = [0; 10];
+                    reader.read_exact(&mut cell).context("Failed to read .xp cell.")?;
+
+                    let code = u32::from_le_bytes([cell[0], cell[1], cell[2], cell[3]]);
+                    let tile = layer.get_xy_mut((x, y));
+                    *tile = BLANK_TILE;
+                    tile.glyph = Self::code_to_glyph(code);
+                    tile.foreground_color = TileColor::rgb(cell[4], cell[5], cell[6]);
+
+                    if cell[7..10] == REX_PAINT_TRANSPARENT {
+                        tile.foreground_color = TileColor::TRANSPARENT;
+                        tile.background_color = TileColor::TRANSPARENT;
+                    } else {
+                        tile.background_color = TileColor::rgb(cell[7], cell[8], cell[9]);
+                    }
+                }
+            }
+
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reads the layers of a gzip compressed .xp stream.
+    //---------------------------------------------------------------------------------------------
+    pub fn read<R: Read>(reader: R) -> Result<Vec<GridMap<Tile>>> {
+        let mut decoder = GzDecoder::new(reader);
+        Self::read_layers(&mut decoder)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads the layers of an .xp file.
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<GridMap<Tile>>> {
+        let file = File::open(&path)
+            .with_context(|| format!("Failed to open .xp file {}.", path.as_ref().display()))?;
+        Self::read(BufReader::new(file))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Writes layers as a gzip compressed .xp stream.
+    // (tiles with a fully transparent background are written as transparent cells)
+    //---------------------------------------------------------------------------------------------
+    pub fn write<W: Write>(writer: W, layers: &[&GridMap<Tile>]) -> Result<()> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+
+        encoder.write_all(&REX_PAINT_VERSION.to_le_bytes())?;
+        encoder.write_all(&(layers.len() as i32).to_le_bytes())?;
+
+        for layer in layers.iter() {
+            encoder.write_all(&layer.width().to_le_bytes())?;
+            encoder.write_all(&layer.height().to_le_bytes())?;
+
+            for x in 0..layer.width() {
+                for y in 0..layer.height() {
+                    let tile = layer.get_xy((x, y));
+                    let fg = tile.foreground_color.0;
+                    let bg = if tile.background_color.0.a == 0 {
+                        REX_PAINT_TRANSPARENT
+                    } else {
+                        let bg = tile.background_color.0;
+                        [bg.r, bg.g, bg.b]
+                    };
+
+                    encoder.write_all(&Self::glyph_to_code(tile.glyph).to_le_bytes())?;
+                    encoder.write_all(&[fg.r, fg.g, fg.b])?;
+                    encoder.write_all(&bg)?;
+                }
+            }
+        }
+
+        encoder.finish().context("Failed to finish .xp compression.")?;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves layers to an .xp file.
+    //---------------------------------------------------------------------------------------------
+    pub fn save<P: AsRef<Path>>(path: P, layers: &[&GridMap<Tile>]) -> Result<()> {
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create .xp file {}.", path.as_ref().display()))?;
+        Self::write(BufWriter::new(file), layers)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Flattens layers into a single grid map, with later layers drawn over earlier ones.
+    // (transparent cells in later layers leave the cells beneath them untouched)
+    //---------------------------------------------------------------------------------------------
+    pub fn flatten(layers: &[GridMap<Tile>]) -> GridMap<Tile> {
+        let dimensions = layers.first().map(|l| l.dimensions()).unwrap_or((0, 0));
+        let mut flattened = GridMap::new(dimensions);
+        flattened.data_mut().fill(BLANK_TILE);
+
+        for layer in layers.iter() {
+            for x in 0..layer.width().min(dimensions.0) {
+                for y in 0..layer.height().min(dimensions.1) {
+                    let tile = layer.get_xy((x, y));
+
+                    if tile.background_color.0.a != 0 {
+                        *flattened.get_xy_mut((x, y)) = *tile;
+                    }
+                }
+            }
+        }
+
+        flattened
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_rex_paint_round_trip() {
+    let mut layer = GridMap::new((3, 2));
+    layer.data_mut().fill(BLANK_TILE);
+
+    let tile = layer.get_xy_mut((2, 1));
+    tile.glyph = '☺';
+    tile.foreground_color = TileColor::rgb(1, 2, 3);
+    tile.background_color = TileColor::rgb(4, 5, 6);
+
+    let mut bytes = Vec::new();
+    RexPaint::write(&mut bytes, &[&layer]).unwrap();
+    let layers = RexPaint::read(bytes.as_slice()).unwrap();
+
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].dimensions(), (3, 2));
+    assert_eq!(layers[0].get_xy((2, 1)).glyph, '☺');
+    assert_eq!(layers[0].get_xy((2, 1)).foreground_color, TileColor::rgb(1, 2, 3));
+    assert_eq!(layers[0].get_xy((2, 1)).background_color, TileColor::rgb(4, 5, 6));
+    assert_eq!(layers[0].get_xy((0, 0)).background_color, TileColor::TRANSPARENT);
+}
@@ -0,0 +1,165 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use rand::{Error, RngCore, SeedableRng};
+use serde_derive::{Deserialize, Serialize};
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Increment of the SplitMix64 generator used to expand seeds (the golden ratio).
+const SPLITMIX64_INCREMENT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// FNV-1a offset basis and prime, for hashing stream names.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+//-------------------------------------------------------------------------------------------------
+// Rng is the engine's source of randomness: a xoshiro256** generator that produces the same
+// sequence for the same seed on every platform and rand version, and whose state can be saved.
+// Independent named streams (e.g. "worldgen" and "combat") are forked from a seed, so that drawing
+// more numbers from one stream does not change the numbers drawn from another.
+// Rng implements rand's RngCore, so the rand::Rng methods (gen_range etc.) are available.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Rng {
+    // Seed the rng was created from, which its streams are forked from.
+    seed: u64,
+    // State of the generator.
+    state: [u64; 4],
+}
+
+impl Rng {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new rng from a seed.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(seed: u64) -> Self {
+        let mut splitmix = seed;
+        let state = [
+            Self::splitmix64(&mut splitmix),
+            Self::splitmix64(&mut splitmix),
+            Self::splitmix64(&mut splitmix),
+            Self::splitmix64(&mut splitmix),
+        ];
+
+        Self { seed, state }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the seed the rng was created from.
+    //---------------------------------------------------------------------------------------------
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a new rng for a named stream of the rng's seed.
+    // (forks depend only on the seed and name, not on how much has been drawn from the rng)
+    //---------------------------------------------------------------------------------------------
+    pub fn fork(&self, stream: &str) -> Self {
+        let hash = stream
+            .bytes()
+            .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME));
+        let mut splitmix = self.seed ^ hash;
+
+        Self::new(Self::splitmix64(&mut splitmix))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that advances a SplitMix64 state and returns its next value.
+    //---------------------------------------------------------------------------------------------
+    fn splitmix64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(SPLITMIX64_INCREMENT);
+
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Rng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_rng_deterministic() {
+    let mut a = Rng::new(1234);
+    let mut b = Rng::new(1234);
+    let mut c = Rng::new(4321);
+
+    let a_values: Vec<_> = (0..16).map(|_| a.next_u64()).collect();
+    let b_values: Vec<_> = (0..16).map(|_| b.next_u64()).collect();
+    let c_values: Vec<_> = (0..16).map(|_| c.next_u64()).collect();
+
+    assert_eq!(a_values, b_values);
+    assert_ne!(a_values, c_values);
+}
+
+#[test]
+fn test_rng_fork() {
+    let mut root = Rng::new(99);
+    let worldgen = root.fork("worldgen");
+
+    // Forks do not depend on what has been drawn from the rng.
+    root.next_u64();
+    assert_eq!(root.fork("worldgen"), worldgen);
+    assert_ne!(root.fork("combat"), worldgen);
+    assert_ne!(Rng::new(100).fork("worldgen"), worldgen);
+}
+
+#[test]
+fn test_rng_serialize() {
+    let mut rng = Rng::new(7);
+    rng.next_u64();
+
+    let json = serde_json::to_string(&rng).unwrap();
+    let mut restored: Rng = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.next_u64(), rng.next_u64());
+}
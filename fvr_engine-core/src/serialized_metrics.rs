@@ -1,8 +1,17 @@
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
+use anyhow::{bail, Context, Result};
 use serde_derive::{Deserialize, Serialize};
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Fields of a glyph in legacy font metrics, in the order of the glyph metric fields.
+const LEGACY_FIELDS: [&str; 7] =
+    ["codepoint", "x", "y", "width", "height", "x_offset", "y_offset"];
+
 //-------------------------------------------------------------------------------------------------
 // Describes the location of a glyph within a font atlas, as well as positioning info.
 //-------------------------------------------------------------------------------------------------
@@ -15,6 +24,45 @@ pub struct GlyphMetric {
     pub height: i32,
     pub x_offset: i32,
     pub y_offset: i32,
+    // Horizontal distance to the next glyph (0 if missing from older metrics files).
+    #[serde(default)]
+    pub x_advance: i32,
+}
+
+impl GlyphMetric {
+    //---------------------------------------------------------------------------------------------
+    // Returns the horizontal advance of the glyph.
+    // (metrics without an advance fall back to the extent of the glyph)
+    //---------------------------------------------------------------------------------------------
+    pub fn advance(&self) -> i32 {
+        match self.x_advance {
+            0 => self.x_offset + self.width,
+            advance => advance,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes an adjustment to the advance between a pair of glyphs.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct GlyphKerning {
+    pub first: i32,
+    pub second: i32,
+    pub amount: i32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Records a glyph that was not taken from the font itself when generating its atlas.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GlyphSubstitution {
+    // Codepoint the glyph is rendered for.
+    pub codepoint: i32,
+    // Codepoint whose glyph is rendered instead (the same codepoint unless remapped).
+    pub source: i32,
+    // Whether the glyph was taken from the default font.
+    pub default_font: bool,
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -23,4 +71,156 @@ pub struct GlyphMetric {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct FontMetricsV2 {
     pub metrics: Vec<GlyphMetric>,
+    #[serde(default)]
+    pub kernings: Vec<GlyphKerning>,
+    // Substituted glyphs, recorded for debugging (not used when rendering).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub substitutions: Vec<GlyphSubstitution>,
+}
+
+impl FontMetricsV2 {
+    //---------------------------------------------------------------------------------------------
+    // Parses font metrics in either layout: JSON for V2 metrics or lines for legacy metrics.
+    //---------------------------------------------------------------------------------------------
+    pub fn parse(text: &str) -> Result<Self> {
+        if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).context("Failed to parse font metrics.")
+        } else {
+            Ok(FontMetrics::parse(text).context("Failed to parse legacy font metrics.")?.into())
+        }
+    }
+}
+
+impl From<FontMetrics> for FontMetricsV2 {
+    //---------------------------------------------------------------------------------------------
+    // Upgrades legacy font metrics. Advances are taken from the extent of the glyphs.
+    //---------------------------------------------------------------------------------------------
+    fn from(legacy: FontMetrics) -> Self {
+        let metrics = legacy
+            .metrics
+            .into_iter()
+            .map(|metric| GlyphMetric { x_advance: metric.x_offset + metric.width, ..metric })
+            .collect();
+
+        Self { metrics, kernings: Vec::new(), substitutions: Vec::new() }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Array of glyph metrics for a font, in the legacy layout of one "key = value" line per field and
+// a "[[metrics]]" line before each glyph. Legacy metrics have no advances or kernings.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct FontMetrics {
+    pub metrics: Vec<GlyphMetric>,
+}
+
+impl FontMetrics {
+    //---------------------------------------------------------------------------------------------
+    // Parses legacy font metrics line by line. Blank lines and "#" comments are skipped.
+    //---------------------------------------------------------------------------------------------
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut metrics = Vec::new();
+        // Fields of the glyph being parsed, in the order of LEGACY_FIELDS.
+        let mut fields: Option<[Option<i32>; 7]> = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line == "[[metrics]]" {
+                if let Some(fields) = fields.take() {
+                    metrics.push(Self::metric(&fields)?);
+                }
+
+                fields = Some([None; 7]);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .with_context(|| format!("Expected a key and value on line {}.", i + 1))?;
+            let key = key.trim();
+            let index = LEGACY_FIELDS
+                .iter()
+                .position(|&field| field == key)
+                .with_context(|| format!("Unknown field <{}> on line {}.", key, i + 1))?;
+            let value = value
+                .trim()
+                .parse::<i32>()
+                .with_context(|| format!("Failed to parse <{}> on line {}.", key, i + 1))?;
+
+            match fields.as_mut() {
+                Some(fields) => fields[index] = Some(value),
+                None => bail!("Field <{}> outside of a glyph on line {}.", key, i + 1),
+            }
+        }
+
+        if let Some(fields) = fields {
+            metrics.push(Self::metric(&fields)?);
+        }
+
+        Ok(Self { metrics })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Builds a metric from parsed fields, all of which must be present.
+    //---------------------------------------------------------------------------------------------
+    fn metric(fields: &[Option<i32>; 7]) -> Result<GlyphMetric> {
+        let mut values = [0; 7];
+
+        for ((value, field), name) in values.iter_mut().zip(fields.iter()).zip(LEGACY_FIELDS) {
+            *value = field.with_context(|| format!("Glyph is missing field <{}>.", name))?;
+        }
+
+        Ok(GlyphMetric {
+            codepoint: values[0],
+            x: values[1],
+            y: values[2],
+            width: values[3],
+            height: values[4],
+            x_offset: values[5],
+            y_offset: values[6],
+            x_advance: 0,
+        })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_legacy_font_metrics() {
+    let text =
+        "[[metrics]]\ncodepoint = 65\nx = 2\ny = 4\nwidth = 10\nheight = 12\nx_offset = 1\n\
+                y_offset = -3\n\n# Comment.\n[[metrics]]\ncodepoint = 66\nx = 14\ny = 4\n\
+                width = 9\nheight = 12\nx_offset = 2\ny_offset = -3\n";
+
+    let legacy = FontMetrics::parse(text).unwrap();
+    assert_eq!(legacy.metrics.len(), 2);
+    assert_eq!(legacy.metrics[1].codepoint, 66);
+    assert_eq!(legacy.metrics[0].y_offset, -3);
+    assert_eq!(legacy.metrics[0].x_advance, 0);
+
+    // Upgraded metrics advance by the extent of the glyphs.
+    let upgraded = FontMetricsV2::parse(text).unwrap();
+    assert_eq!(upgraded.metrics[0].x_advance, 11);
+    assert_eq!(upgraded.metrics[1].x_advance, 11);
+    assert!(upgraded.kernings.is_empty());
+
+    // V2 metrics are parsed as JSON, keeping their advances.
+    let json = serde_json::to_string(&upgraded).unwrap();
+    let parsed = FontMetricsV2::parse(&json).unwrap();
+    assert_eq!(parsed.metrics.len(), 2);
+    assert_eq!(parsed.metrics[1].x_advance, 11);
+
+    // Incomplete glyphs, unknown fields and stray fields are rejected.
+    assert!(FontMetrics::parse("[[metrics]]\ncodepoint = 65\n").is_err());
+    assert!(FontMetrics::parse("[[metrics]]\nadvance = 1\n").is_err());
+    assert!(FontMetrics::parse("codepoint = 65\n").is_err());
+    assert!(FontMetrics::parse("[[metrics]]\ncodepoint = A\n").is_err());
 }
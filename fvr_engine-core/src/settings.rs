@@ -0,0 +1,365 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::accessibility::*;
+use crate::config::*;
+use crate::mods::*;
+use crate::platform_dirs::*;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+static SETTINGS: Lazy<RwLock<Settings>> =
+    Lazy::new(|| RwLock::new(Settings::from_config(&CONFIG)));
+
+// Incremented whenever the settings are applied, so that watchers can tell they changed.
+static SETTINGS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+//-------------------------------------------------------------------------------------------------
+// Volumes of the audio channels, each in the range [0.0, 1.0].
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    // Volume scaling every channel.
+    pub master_volume: f32,
+    // Volume of the music.
+    pub music_volume: f32,
+    // Volume of the sound effects.
+    pub effects_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { master_volume: 1.0, music_volume: 0.8, effects_volume: 1.0 }
+    }
+}
+
+impl AudioSettings {
+    //---------------------------------------------------------------------------------------------
+    // Returns the gain music should be played at.
+    //---------------------------------------------------------------------------------------------
+    pub fn music_gain(&self) -> f32 {
+        (self.master_volume * self.music_volume).clamp(0.0, 1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the gain sound effects should be played at.
+    //---------------------------------------------------------------------------------------------
+    pub fn effects_gain(&self) -> f32 {
+        (self.master_volume * self.effects_volume).clamp(0.0, 1.0)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Which items the player picks up automatically when entering a cell, by category. Items named in
+// the exclusion list are never picked up automatically.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AutoPickupSettings {
+    // Whether items are picked up automatically at all.
+    pub enabled: bool,
+    // Whether consumable items (e.g. draughts) are picked up.
+    pub consumables: bool,
+    // Whether thrown items (e.g. knives and flasks) are picked up.
+    pub throwables: bool,
+    // Names of the items never picked up automatically.
+    pub excluded: Vec<String>,
+}
+
+impl Default for AutoPickupSettings {
+    fn default() -> Self {
+        Self { enabled: true, consumables: true, throwables: true, excluded: Vec::new() }
+    }
+}
+
+impl AutoPickupSettings {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an item is excluded by name (ignoring case).
+    //---------------------------------------------------------------------------------------------
+    pub fn excludes(&self, name: &str) -> bool {
+        self.excluded.iter().any(|excluded| excluded.eq_ignore_ascii_case(name))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates when the health bars of actors are drawn over the map.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum ActorBarMode {
+    // Drawn for every visible actor.
+    Always,
+    // Drawn for visible actors that have taken damage.
+    #[default]
+    OnDamage,
+    // Never drawn.
+    Never,
+}
+
+impl ActorBarMode {
+    //---------------------------------------------------------------------------------------------
+    // Returns the mode one step forward (positive) or back, wrapping around at either end.
+    //---------------------------------------------------------------------------------------------
+    pub fn step(self, direction: i32) -> Self {
+        const MODES: [ActorBarMode; 3] =
+            [ActorBarMode::Always, ActorBarMode::OnDamage, ActorBarMode::Never];
+
+        let index = MODES.iter().position(|mode| *mode == self).unwrap_or(0) as i32;
+        MODES[(index + direction).rem_euclid(MODES.len() as i32) as usize]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the bar of an actor is drawn, given whether it has taken damage.
+    //---------------------------------------------------------------------------------------------
+    pub fn shows(self, damaged: bool) -> bool {
+        match self {
+            ActorBarMode::Always => true,
+            ActorBarMode::OnDamage => damaged,
+            ActorBarMode::Never => false,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Settings holds the parts of the config the player can change while the game is running. The
+// config is loaded once, so the current settings are queried here instead and changes are picked
+// up by polling a SettingsWatcher.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    // Name of the font to use.
+    pub font_name: String,
+    // Whether to wait for vertical sync when presenting frames.
+    pub vsync: bool,
+    // Whether to render the full frame vignette.
+    pub enable_vignette: bool,
+    // Whether to log the current fps.
+    pub show_fps: bool,
+    // Volumes of the audio channels.
+    pub audio: AudioSettings,
+    // Whether the mouse cursor is drawn as a glyph in place of the hardware cursor.
+    pub software_cursor: bool,
+    // When the health bars of actors are drawn over the map.
+    pub actor_bars: ActorBarMode,
+    // Which items are picked up automatically.
+    pub auto_pickup: AutoPickupSettings,
+    // Accessibility options.
+    pub accessibility: AccessibilityOptions,
+}
+
+impl Settings {
+    //---------------------------------------------------------------------------------------------
+    // Creates settings from the values of a config.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            font_name: config.font_name.clone(),
+            vsync: config.vsync,
+            enable_vignette: config.enable_vignette,
+            show_fps: config.show_fps,
+            audio: config.audio.clone(),
+            software_cursor: config.software_cursor,
+            actor_bars: config.actor_bars,
+            auto_pickup: config.auto_pickup.clone(),
+            accessibility: config.accessibility.clone(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a copy of the current settings.
+    //---------------------------------------------------------------------------------------------
+    pub fn current() -> Self {
+        SETTINGS.read().expect("Failed to read settings.").clone()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the current settings, notifying the watchers (nothing is saved).
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(settings: Settings) {
+        Accessibility::set_options(settings.accessibility.clone());
+        *SETTINGS.write().expect("Failed to write settings.") = settings;
+        SETTINGS_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the names of the fonts that can be chosen, merged across the content layers.
+    //---------------------------------------------------------------------------------------------
+    pub fn available_fonts() -> Result<Vec<String>> {
+        let mut fonts = BTreeSet::new();
+
+        for layer in CONTENT_LAYERS.layers() {
+            let dir = layer.root.join(CONFIG_FONTS_DIR);
+
+            if !dir.is_dir() {
+                continue;
+            }
+
+            for entry in fs::read_dir(&dir)
+                .with_context(|| format!("Failed to read fonts directory {}.", dir.display()))?
+            {
+                let entry = entry?;
+
+                if entry.file_type()?.is_dir() {
+                    let _ = fonts.insert(entry.file_name().to_string_lossy().into_owned());
+                }
+            }
+        }
+
+        Ok(fonts.into_iter().collect())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that writes the settings over their entries in a parsed config file,
+    // leaving every other entry untouched.
+    //---------------------------------------------------------------------------------------------
+    fn patch_config_json(&self, json: &mut Value) -> Result<()> {
+        let object = json.as_object_mut().ok_or_else(|| anyhow!("Config is not an object."))?;
+
+        let _ = object.insert("font_name".into(), serde_json::to_value(&self.font_name)?);
+        let _ = object.insert("vsync".into(), serde_json::to_value(self.vsync)?);
+        let _ =
+            object.insert("enable_vignette".into(), serde_json::to_value(self.enable_vignette)?);
+        let _ = object.insert("show_fps".into(), serde_json::to_value(self.show_fps)?);
+        let _ = object.insert("audio".into(), serde_json::to_value(&self.audio)?);
+        let _ =
+            object.insert("software_cursor".into(), serde_json::to_value(self.software_cursor)?);
+        let _ = object.insert("actor_bars".into(), serde_json::to_value(self.actor_bars)?);
+        let _ = object.insert("auto_pickup".into(), serde_json::to_value(&self.auto_pickup)?);
+        let _ = object.insert("accessibility".into(), serde_json::to_value(&self.accessibility)?);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves the settings to the user's config file (copying the shipped default if necessary).
+    //---------------------------------------------------------------------------------------------
+    pub fn save(&self) -> Result<()> {
+        let source = PLATFORM_DIRS.config_file(CONFIG_FILE_NAME);
+        let json = fs::read_to_string(&source)
+            .with_context(|| format!("Failed to read config file {}.", source.display()))?;
+        let mut json: Value =
+            serde_json::from_str(&json).context("Failed to parse config json.")?;
+
+        self.patch_config_json(&mut json)?;
+
+        fs::create_dir_all(PLATFORM_DIRS.config_dir()).with_context(|| {
+            format!("Failed to create config directory {}.", PLATFORM_DIRS.config_dir().display())
+        })?;
+
+        let target = PLATFORM_DIRS.config_dir().join(CONFIG_FILE_NAME);
+        fs::write(&target, serde_json::to_string_pretty(&json)?)
+            .with_context(|| format!("Failed to write config file {}.", target.display()))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// SettingsWatcher tells its owner whether the settings were applied since it last checked.
+// Each subsystem that applies settings (renderer, input, audio) holds its own watcher.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct SettingsWatcher {
+    // Generation of the settings when last checked.
+    generation: u64,
+}
+
+impl Default for SettingsWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SettingsWatcher {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new watcher, treating the current settings as seen.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self { generation: SETTINGS_GENERATION.load(Ordering::SeqCst) }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the settings were applied since the last check.
+    //---------------------------------------------------------------------------------------------
+    pub fn changed(&mut self) -> bool {
+        let generation = SETTINGS_GENERATION.load(Ordering::SeqCst);
+        let changed = generation != self.generation;
+        self.generation = generation;
+        changed
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_settings() {
+    let audio = AudioSettings { master_volume: 0.5, music_volume: 0.5, effects_volume: 2.0 };
+    assert_eq!(audio.music_gain(), 0.25);
+    assert_eq!(audio.effects_gain(), 1.0);
+
+    // Saving only touches the entries of the settings.
+    let settings = Settings {
+        font_name: String::from("deja_vu_sans_mono"),
+        vsync: true,
+        enable_vignette: false,
+        show_fps: true,
+        audio,
+        software_cursor: true,
+        actor_bars: ActorBarMode::Never,
+        auto_pickup: AutoPickupSettings {
+            throwables: false,
+            excluded: vec![String::from("fire flask")],
+            ..Default::default()
+        },
+        accessibility: AccessibilityOptions { reduce_motion: true, ..Default::default() },
+    };
+
+    let mut json = serde_json::json!({ "font_name": "fantasque_sans_mono", "show_fps": false,
+        "update_interval": { "secs": 0, "nanos": 1 } });
+    settings.patch_config_json(&mut json).unwrap();
+
+    assert_eq!(json["font_name"], "deja_vu_sans_mono");
+    assert_eq!(json["vsync"], true);
+    assert_eq!(json["show_fps"], true);
+    assert_eq!(json["audio"]["master_volume"], 0.5);
+    assert_eq!(json["actor_bars"], "Never");
+    assert_eq!(json["auto_pickup"]["throwables"], false);
+    assert_eq!(json["auto_pickup"]["excluded"][0], "fire flask");
+    assert_eq!(json["accessibility"]["reduce_motion"], true);
+    assert_eq!(json["update_interval"]["nanos"], 1);
+    assert!(settings.patch_config_json(&mut serde_json::json!([])).is_err());
+
+    // Exclusions ignore case.
+    assert!(settings.auto_pickup.excludes("Fire Flask"));
+    assert!(!settings.auto_pickup.excludes("throwing knife"));
+
+    // Bar modes step through each mode and wrap around.
+    assert_eq!(ActorBarMode::Always.step(1), ActorBarMode::OnDamage);
+    assert_eq!(ActorBarMode::Always.step(-1), ActorBarMode::Never);
+    assert!(ActorBarMode::OnDamage.shows(true));
+    assert!(!ActorBarMode::OnDamage.shows(false));
+
+    // Watchers see each change once.
+    let mut watcher = SettingsWatcher::new();
+    assert!(!watcher.changed());
+    SETTINGS_GENERATION.fetch_add(1, Ordering::SeqCst);
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+}
@@ -20,6 +20,11 @@ pub const TILE_STYLE_COUNT: usize = 4;
 pub const TILE_STYLE_NAMES: &[&str] = &["regular", "bold", "italic", "bold_italic"];
 pub const TILE_SIZE_COUNT: usize = 4;
 
+// Default and max thickness of a tile's glyph's outline, relative to the font's outline.
+// (without SDF fonts, the shader caps how far thick outlines spread to the glyph padding)
+pub const DEFAULT_OUTLINE_THICKNESS: f32 = 1.0;
+pub const MAX_OUTLINE_THICKNESS: f32 = 4.0;
+
 //-------------------------------------------------------------------------------------------------
 // Statics
 //-------------------------------------------------------------------------------------------------
@@ -37,6 +42,9 @@ pub static BLANK_TILE: Tile = Tile {
     background_opacity: 1.0,
     foreground_opacity: 1.0,
     outline_opacity: 1.0,
+    background_gradient: TileGradient::None,
+    outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+    outline_glow: false,
 };
 
 //-------------------------------------------------------------------------------------------------
@@ -81,6 +89,21 @@ impl TileColor {
     pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self(SdlColor { r, g, b, a })
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color interpolated towards another color (including the alpha).
+    //---------------------------------------------------------------------------------------------
+    pub fn lerp(&self, other: &TileColor, t: f32) -> TileColor {
+        let t = t.clamp(0.0, 1.0);
+        let lerp_u8 = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        TileColor::rgba(
+            lerp_u8(self.0.r, other.0.r),
+            lerp_u8(self.0.g, other.0.g),
+            lerp_u8(self.0.b, other.0.b),
+            lerp_u8(self.0.a, other.0.a),
+        )
+    }
 }
 
 impl Distribution<TileColor> for Standard {
@@ -93,7 +116,7 @@ impl Distribution<TileColor> for Standard {
 // TileStyle describes the style of the glyph within the tile when rendered.
 //-------------------------------------------------------------------------------------------------
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum TileStyle {
     // The glyph has the default appearance.
     Regular = 0,
@@ -257,6 +280,33 @@ impl Default for TileLayout {
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// TileGradient describes an optional gradient across the tile's background quad, from the
+// background color to an end color (at the bottom or right edge of the tile).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum TileGradient {
+    // The background is a solid color.
+    #[default]
+    None,
+    // The background is lerped from the top edge to the bottom edge's color.
+    Vertical(TileColor),
+    // The background is lerped from the left edge to the right edge's color.
+    Horizontal(TileColor),
+}
+
+impl TileGradient {
+    //---------------------------------------------------------------------------------------------
+    // Returns the end color of the gradient, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_color(&self) -> Option<TileColor> {
+        match self {
+            TileGradient::None => None,
+            TileGradient::Vertical(color) | TileGradient::Horizontal(color) => Some(*color),
+        }
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Tile describes a visual tile that can be rendered.
 //-------------------------------------------------------------------------------------------------
@@ -284,6 +334,22 @@ pub struct Tile {
     pub foreground_opacity: f32,
     // The opacity of the tile's glyph's outline.
     pub outline_opacity: f32,
+    // The gradient of the tile's background quad.
+    #[serde(default)]
+    pub background_gradient: TileGradient,
+    // The thickness of the tile's glyph's outline, relative to the font's outline.
+    #[serde(default = "default_outline_thickness")]
+    pub outline_thickness: f32,
+    // Whether the tile's glyph's outline is rendered as a soft glow.
+    #[serde(default)]
+    pub outline_glow: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that returns the default outline thickness for serde.
+//-------------------------------------------------------------------------------------------------
+fn default_outline_thickness() -> f32 {
+    DEFAULT_OUTLINE_THICKNESS
 }
 
 impl Default for Tile {
@@ -300,6 +366,9 @@ impl Default for Tile {
             background_opacity: 1.0,
             foreground_opacity: 1.0,
             outline_opacity: 1.0,
+            background_gradient: TileGradient::None,
+            outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+            outline_glow: false,
         }
     }
 }
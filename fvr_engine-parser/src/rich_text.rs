@@ -1,9 +1,9 @@
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
-use anyhow::Result;
+use anyhow::{bail, Result};
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take_till1};
+use nom::bytes::complete::{tag, take_till1, take_while1};
 use nom::multi::many1;
 use nom::sequence::tuple;
 use nom::IResult;
@@ -33,6 +33,8 @@ const OUTLINED_KEY_TAG: &str = "o";
 const FOREGROUND_COLOR_KEY_TAG: &str = "fc";
 const BACKGROUND_COLOR_KEY_TAG: &str = "bc";
 const OUTLINE_COLOR_KEY_TAG: &str = "oc";
+const OUTLINE_THICKNESS_KEY_TAG: &str = "ot";
+const OUTLINE_GLOW_KEY_TAG: &str = "og";
 
 // Tags for the possible layout values.
 const CENTER_LAYOUT_VALUE_TAG: &str = "c";
@@ -91,6 +93,8 @@ pub enum RichTextHintType {
     ForegroundColor,
     BackgroundColor,
     OutlineColor,
+    OutlineThickness,
+    OutlineGlow,
 }
 
 impl RichTextHintType {
@@ -103,6 +107,8 @@ impl RichTextHintType {
             RichTextHintType::ForegroundColor => FOREGROUND_COLOR_KEY_TAG,
             RichTextHintType::BackgroundColor => BACKGROUND_COLOR_KEY_TAG,
             RichTextHintType::OutlineColor => OUTLINE_COLOR_KEY_TAG,
+            RichTextHintType::OutlineThickness => OUTLINE_THICKNESS_KEY_TAG,
+            RichTextHintType::OutlineGlow => OUTLINE_GLOW_KEY_TAG,
         }
     }
 }
@@ -279,6 +285,52 @@ fn outlined_hint_parser(input: &str) -> IResult<&str, RichTextValue> {
     ))
 }
 
+//-------------------------------------------------------------------------------------------------
+// Parser for the value of an outline thickness format hint (a decimal number).
+//-------------------------------------------------------------------------------------------------
+fn outline_thickness_value_parser(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_ascii_digit() || c == '.')(input)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Parser that matches an outline thickness format hint.
+//-------------------------------------------------------------------------------------------------
+fn outline_thickness_hint_parser(input: &str) -> IResult<&str, RichTextValue> {
+    let (remainder, result) = tuple((
+        format_hint_begin_parser,
+        tag(OUTLINE_THICKNESS_KEY_TAG),
+        format_hint_separator_parser,
+        outline_thickness_value_parser,
+        format_hint_end_parser,
+    ))(input)?;
+
+    Ok((
+        remainder,
+        RichTextValue::FormatHint {
+            key: RichTextHintType::OutlineThickness,
+            value: result.3.into(),
+        },
+    ))
+}
+
+//-------------------------------------------------------------------------------------------------
+// Parser that matches an outline glow format hint.
+//-------------------------------------------------------------------------------------------------
+fn outline_glow_hint_parser(input: &str) -> IResult<&str, RichTextValue> {
+    let (remainder, result) = tuple((
+        format_hint_begin_parser,
+        tag(OUTLINE_GLOW_KEY_TAG),
+        format_hint_separator_parser,
+        outlined_value_parser,
+        format_hint_end_parser,
+    ))(input)?;
+
+    Ok((
+        remainder,
+        RichTextValue::FormatHint { key: RichTextHintType::OutlineGlow, value: result.3.into() },
+    ))
+}
+
 //-------------------------------------------------------------------------------------------------
 // Parser for the value of any of the color format hints.
 //-------------------------------------------------------------------------------------------------
@@ -376,21 +428,48 @@ fn format_hint_parser(input: &str) -> IResult<&str, RichTextValue> {
         foreground_color_hint_parser,
         background_color_hint_parser,
         outline_color_hint_parser,
+        outline_thickness_hint_parser,
+        outline_glow_hint_parser,
     ))(input)
 }
 
+//-------------------------------------------------------------------------------------------------
+// Parser that matches a sequence of text, newlines, escaped chevrons and format hints.
+//-------------------------------------------------------------------------------------------------
+fn rich_text_parser(input: &str) -> IResult<&str, Vec<RichTextValue>> {
+    many1(alt((text_parser, newline_parser, escaped_chevron_parser, format_hint_parser)))(input)
+}
+
 //-------------------------------------------------------------------------------------------------
 // The main parse function.
 //-------------------------------------------------------------------------------------------------
 pub fn parse_rich_text<S: AsRef<str>>(input: S) -> Result<Vec<RichTextValue>> {
-    let result =
-        many1(alt((text_parser, newline_parser, escaped_chevron_parser, format_hint_parser)))(
-            input.as_ref(),
-        );
+    let result = rich_text_parser(input.as_ref());
 
     Ok(result.map_err(|e| anyhow::format_err!(e.to_string()))?.1)
 }
 
+//-------------------------------------------------------------------------------------------------
+// Checks that the whole of the input is valid rich text, e.g. for validating content at startup.
+// (parse_rich_text stops at the first invalid format hint, dropping the rest of the input)
+//-------------------------------------------------------------------------------------------------
+pub fn validate_rich_text<S: AsRef<str>>(input: S) -> Result<()> {
+    let input = input.as_ref();
+
+    if input.is_empty() {
+        return Ok(());
+    }
+
+    let (remainder, _) =
+        rich_text_parser(input).map_err(|e| anyhow::format_err!(e.to_string()))?;
+
+    if !remainder.is_empty() {
+        bail!("Invalid rich text at '{}'.", remainder);
+    }
+
+    Ok(())
+}
+
 //-------------------------------------------------------------------------------------------------
 // Tests.
 //-------------------------------------------------------------------------------------------------
@@ -561,6 +640,30 @@ fn test_outlined_hint_parser() {
     assert_eq!(outlined_hint_parser("<l:c>"), Err(error));
 }
 
+#[test]
+fn test_outline_thickness_hint_parser() {
+    let format_hint =
+        RichTextValue::FormatHint { key: RichTextHintType::OutlineThickness, value: "2.5".into() };
+    assert_eq!(outline_thickness_hint_parser("<ot:2.5>Hi"), Ok(("Hi", format_hint)));
+
+    let error = nom::Err::Error(nom::error::Error {
+        input: "x>",
+        code: nom::error::ErrorKind::TakeWhile1,
+    });
+    assert_eq!(outline_thickness_hint_parser("<ot:x>"), Err(error));
+}
+
+#[test]
+fn test_outline_glow_hint_parser() {
+    let format_hint =
+        RichTextValue::FormatHint { key: RichTextHintType::OutlineGlow, value: "t".into() };
+    assert_eq!(format_hint_parser("<og:t>"), Ok(("", format_hint)));
+
+    let format_hint =
+        RichTextValue::FormatHint { key: RichTextHintType::OutlineThickness, value: "3".into() };
+    assert_eq!(format_hint_parser("<ot:3>"), Ok(("", format_hint)));
+}
+
 #[test]
 fn test_parse_rich_text() {
     const TEST_STR: &str =
@@ -602,3 +705,13 @@ fn test_parse_rich_text() {
         ]
     );
 }
+
+#[test]
+fn test_validate_rich_text() {
+    assert!(validate_rich_text("").is_ok());
+    assert!(validate_rich_text("<fc:y>You cannot go that way.").is_ok());
+    assert!(validate_rich_text("<<escaped<fc:Y>\nnewline").is_ok());
+    assert!(validate_rich_text("<zz:y>Unknown hint.").is_err());
+    assert!(validate_rich_text("Unknown <fc:y trailing hint.").is_err());
+    assert!(validate_rich_text("Unknown <l:q>layout.").is_err());
+}
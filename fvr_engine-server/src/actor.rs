@@ -2,7 +2,6 @@
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use rand::distributions::{Distribution, Standard};
-use rand::prelude::*;
 use specs::prelude::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -13,6 +12,8 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::blackboard::*;
+use crate::knowledge::*;
 use crate::thing::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -47,7 +48,7 @@ pub struct ActorStats {
 }
 
 impl Distribution<ActorStats> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ActorStats {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> ActorStats {
         ActorStats {
             STR: rng.gen_range(0..=18),
             DEX: rng.gen_range(0..=18),
@@ -59,25 +60,85 @@ impl Distribution<ActorStats> for Standard {
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// Subset of actor struct containing the actor's hit points.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActorHealth {
+    // Current hit points.
+    pub current: i32,
+    // Maximum hit points.
+    pub max: i32,
+}
+
+impl ActorHealth {
+    //---------------------------------------------------------------------------------------------
+    // Creates full health for an actor's stats.
+    //---------------------------------------------------------------------------------------------
+    pub fn for_stats(stats: &ActorStats) -> Self {
+        let max = 10 + stats.CON as i32 / 2;
+        Self { current: max, max }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the fraction of hit points remaining, in the range [0.0, 1.0].
+    //---------------------------------------------------------------------------------------------
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0 {
+            return 0.0;
+        }
+
+        (self.current as f32 / self.max as f32).clamp(0.0, 1.0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the actor has taken damage.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_damaged(&self) -> bool {
+        self.current < self.max
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Subset of actor struct containing what the actor is capable of, used for per-actor path costs.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ActorCapabilities {
+    // Whether the actor can open doors (doors are impassable to it otherwise).
+    pub open_doors: bool,
+}
+
 //-------------------------------------------------------------------------------------------------
 // Actor describes a dynamic game entity with a position, appearance, and AI.
 //-------------------------------------------------------------------------------------------------
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Actor {
     // The SPECS entity of the actor.
     pub entity: Entity,
     // The actor's thing.
     pub thing: Thing,
+    // Name of the actor's kind (e.g. as recorded in the bestiary).
+    pub kind: &'static str,
     // Current position.
     pub xy: ICoord,
     // Additional navigation values.
     pub navigation: ActorNavigation,
     // The actor's base stats.
     pub stats: ActorStats,
+    // The actor's hit points.
+    pub health: ActorHealth,
+    // What the actor is capable of.
+    pub capabilities: ActorCapabilities,
     // Index of the actor's behavior.
     pub behavior: usize,
     // Index of the actor's intention.
     pub intention: usize,
+    // Data shared between the actor's intention, goals, behaviors and scripts.
+    pub blackboard: Blackboard,
+    // The faction the actor belongs to (and gossips with).
+    pub faction: Faction,
+    // What the actor has witnessed or heard.
+    pub knowledge: Knowledge,
 }
 
 impl Actor {}
@@ -0,0 +1,241 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the types of values held by a blackboard.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlackboardValue {
+    // A flag (e.g. "alerted").
+    Bool(bool),
+    // A count (e.g. turns spent searching).
+    Int(i32),
+    // A measure (e.g. the navigation weight of the previous cell).
+    Float(f32),
+    // A position (e.g. where the player was last seen).
+    Coord(ICoord),
+    // The id of an entity (e.g. the current target).
+    Entity(u32),
+    // Free-form text (e.g. set by scripts).
+    Text(String),
+}
+
+//-------------------------------------------------------------------------------------------------
+// BlackboardType is implemented by the types that can be read from and written to a blackboard.
+//-------------------------------------------------------------------------------------------------
+pub trait BlackboardType: Sized {
+    //---------------------------------------------------------------------------------------------
+    // Returns the value as the type, if the value holds the type.
+    //---------------------------------------------------------------------------------------------
+    fn from_value(value: &BlackboardValue) -> Option<Self>;
+
+    //---------------------------------------------------------------------------------------------
+    // Wraps the type in a value.
+    //---------------------------------------------------------------------------------------------
+    fn into_value(self) -> BlackboardValue;
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper macro that implements BlackboardType for a type held by a copyable value variant.
+//-------------------------------------------------------------------------------------------------
+macro_rules! impl_blackboard_type {
+    ($type:ty, $variant:ident) => {
+        impl BlackboardType for $type {
+            //-------------------------------------------------------------------------------------
+            // Returns the value as the type, if the value holds the type.
+            //-------------------------------------------------------------------------------------
+            fn from_value(value: &BlackboardValue) -> Option<Self> {
+                match value {
+                    BlackboardValue::$variant(inner) => Some(*inner),
+                    _ => None,
+                }
+            }
+
+            //-------------------------------------------------------------------------------------
+            // Wraps the type in a value.
+            //-------------------------------------------------------------------------------------
+            fn into_value(self) -> BlackboardValue {
+                BlackboardValue::$variant(self)
+            }
+        }
+    };
+}
+
+impl_blackboard_type!(bool, Bool);
+impl_blackboard_type!(i32, Int);
+impl_blackboard_type!(f32, Float);
+impl_blackboard_type!(ICoord, Coord);
+impl_blackboard_type!(u32, Entity);
+
+impl BlackboardType for String {
+    //---------------------------------------------------------------------------------------------
+    // Returns the value as the type, if the value holds the type.
+    //---------------------------------------------------------------------------------------------
+    fn from_value(value: &BlackboardValue) -> Option<Self> {
+        match value {
+            BlackboardValue::Text(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Wraps the type in a value.
+    //---------------------------------------------------------------------------------------------
+    fn into_value(self) -> BlackboardValue {
+        BlackboardValue::Text(self)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// An entry of a blackboard.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlackboardEntry {
+    // The value of the entry.
+    pub value: BlackboardValue,
+    // Turns left before the entry expires, if it expires.
+    pub ttl: Option<u32>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Blackboard is a per-actor typed key-value store shared between the actor's intention, goals,
+// behaviors and scripts, so that AI stages can communicate without dedicated fields.
+// Entries can be given a time to live in turns, after which they are removed by tick.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Blackboard {
+    // Entries by key (ordered, so that iteration is deterministic).
+    entries: BTreeMap<String, BlackboardEntry>,
+}
+
+impl Blackboard {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty blackboard.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Writes a value that never expires, replacing any entry with the same key.
+    //---------------------------------------------------------------------------------------------
+    pub fn set<T>(&mut self, key: &str, value: T)
+    where
+        T: BlackboardType,
+    {
+        let entry = BlackboardEntry { value: value.into_value(), ttl: None };
+        let _ = self.entries.insert(key.into(), entry);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Writes a value of any type that never expires, replacing any entry with the same key.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_value(&mut self, key: &str, value: BlackboardValue) {
+        let entry = BlackboardEntry { value, ttl: None };
+        let _ = self.entries.insert(key.into(), entry);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Writes a value that expires after a # of turns, replacing any entry with the same key.
+    // (a ttl of 0 expires at the next tick)
+    //---------------------------------------------------------------------------------------------
+    pub fn set_with_ttl<T>(&mut self, key: &str, value: T, turns: u32)
+    where
+        T: BlackboardType,
+    {
+        let entry = BlackboardEntry { value: value.into_value(), ttl: Some(turns) };
+        let _ = self.entries.insert(key.into(), entry);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reads a value, if the key is present and holds the type.
+    //---------------------------------------------------------------------------------------------
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: BlackboardType,
+    {
+        self.entries.get(key).and_then(|entry| T::from_value(&entry.value))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Reads a value of any type, if the key is present.
+    //---------------------------------------------------------------------------------------------
+    pub fn value(&self, key: &str) -> Option<&BlackboardValue> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the turns left before an entry expires (None if absent or never expiring).
+    //---------------------------------------------------------------------------------------------
+    pub fn ttl(&self, key: &str) -> Option<u32> {
+        self.entries.get(key).and_then(|entry| entry.ttl)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a key is present.
+    //---------------------------------------------------------------------------------------------
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes an entry, returning its value.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove(&mut self, key: &str) -> Option<BlackboardValue> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the keys and entries, ordered by key.
+    //---------------------------------------------------------------------------------------------
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BlackboardEntry)> {
+        self.entries.iter().map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Counts down the expiring entries by a turn, removing those that expire.
+    // Returns the # of removed entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn tick(&mut self) -> usize {
+        let len = self.entries.len();
+
+        self.entries.retain(|_, entry| match entry.ttl.as_mut() {
+            Some(0) => false,
+            Some(ttl) => {
+                *ttl -= 1;
+                true
+            }
+            None => true,
+        });
+
+        len - self.entries.len()
+    }
+}
@@ -0,0 +1,143 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::knowledge::*;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates how the player relates to an actor they bump into.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BumpRelation {
+    // Monsters and the factions the player has wronged.
+    Hostile,
+    // Actors with no opinion of the player (e.g. critters and strangers).
+    Neutral,
+    // Factions the player has helped.
+    Friendly,
+}
+
+impl BumpRelation {
+    //---------------------------------------------------------------------------------------------
+    // Returns the relation with a member of a faction, given the faction's standing.
+    //---------------------------------------------------------------------------------------------
+    pub fn of(faction: Faction, standing: i32) -> Self {
+        if faction == Faction::Wild || standing < 0 {
+            BumpRelation::Hostile
+        } else if standing > 0 {
+            BumpRelation::Friendly
+        } else {
+            BumpRelation::Neutral
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the actions the player can take when bumping into an actor, most likely first.
+    //---------------------------------------------------------------------------------------------
+    pub fn actions(&self) -> &'static [BumpAction] {
+        match self {
+            BumpRelation::Hostile => &[BumpAction::Attack],
+            BumpRelation::Neutral => &[BumpAction::Talk, BumpAction::Swap, BumpAction::Attack],
+            BumpRelation::Friendly => &[BumpAction::Swap, BumpAction::Talk, BumpAction::Attack],
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates what the player can do by bumping into an actor.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BumpAction {
+    // Attack the actor (takes a turn).
+    Attack,
+    // Trade places with the actor (takes a turn).
+    Swap,
+    // Talk to the actor (no turn passes).
+    Talk,
+}
+
+impl BumpAction {
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable name of the action.
+    //---------------------------------------------------------------------------------------------
+    pub fn name(&self) -> &'static str {
+        match self {
+            BumpAction::Attack => "Attack",
+            BumpAction::Swap => "Swap places",
+            BumpAction::Talk => "Talk",
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates how a bump into an actor is resolved.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BumpPolicy {
+    // Ask the player which action to take (unless only one is available).
+    Ask,
+    // Reject the move, as if the actor were a wall.
+    Block,
+    // Always take an action.
+    Always(BumpAction),
+}
+
+//-------------------------------------------------------------------------------------------------
+// The policies for resolving the player's bumps into actors, by relation.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BumpPolicies {
+    // Policy for bumping into hostile actors.
+    pub hostile: BumpPolicy,
+    // Policy for bumping into neutral actors.
+    pub neutral: BumpPolicy,
+    // Policy for bumping into friendly actors.
+    pub friendly: BumpPolicy,
+}
+
+impl BumpPolicies {
+    //---------------------------------------------------------------------------------------------
+    // Returns the policy for a relation.
+    //---------------------------------------------------------------------------------------------
+    pub fn policy(&self, relation: BumpRelation) -> BumpPolicy {
+        match relation {
+            BumpRelation::Hostile => self.hostile,
+            BumpRelation::Neutral => self.neutral,
+            BumpRelation::Friendly => self.friendly,
+        }
+    }
+}
+
+impl Default for BumpPolicies {
+    fn default() -> Self {
+        Self {
+            hostile: BumpPolicy::Always(BumpAction::Attack),
+            neutral: BumpPolicy::Ask,
+            friendly: BumpPolicy::Always(BumpAction::Swap),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A bump into an actor the player must disambiguate. The client answers it by passing one of the
+// actions back to the server.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BumpRequest {
+    // Id of the actor bumped into.
+    pub target: u32,
+    // Name of the actor's kind.
+    pub kind: &'static str,
+    // Coord of the actor.
+    pub xy: ICoord,
+    // Direction the player moved in.
+    pub direction: Direction,
+    // How the player relates to the actor.
+    pub relation: BumpRelation,
+    // The actions to choose from, most likely first.
+    pub actions: Vec<BumpAction>,
+}
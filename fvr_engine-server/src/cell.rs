@@ -6,6 +6,7 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::door::*;
 use crate::thing::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -39,4 +40,38 @@ impl Cell {
             Transparency::Opaque
         }
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the state of the door in the cell, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn door(&self) -> Option<DoorState> {
+        self.things.iter().find_map(|thing| thing.door)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Opens or closes the door in the cell. Returns whether the door changed state.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_door(&mut self, state: DoorState) -> bool {
+        match self.things.iter_mut().find(|thing| thing.door.is_some()) {
+            Some(thing) if thing.door != Some(state) => {
+                *thing = state.thing();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns the passability of every cell of a map, for connectivity checks and repairs.
+// (closed doors count as passable, as they can be opened)
+//-------------------------------------------------------------------------------------------------
+pub fn passability_map(cell_map: &GridMap<Cell>) -> GridMap<Passability> {
+    let mut states = GridMap::new(cell_map.dimensions());
+
+    for (state, cell) in states.data_mut().iter_mut().zip(cell_map.data().iter()) {
+        *state = if cell.door().is_some() { Passability::Passable } else { cell.passability() };
+    }
+
+    states
 }
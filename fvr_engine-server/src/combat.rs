@@ -0,0 +1,148 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use rand::{Rng as _, RngCore};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Sides of the die rolled to hit in melee.
+const MELEE_ATTACK_DIE: i32 = 20;
+
+// Sides of the die rolled for melee damage.
+const MELEE_DAMAGE_DIE: i32 = 6;
+
+// Defense of an actor before modifiers.
+const BASE_DEFENSE: i32 = 10;
+
+//-------------------------------------------------------------------------------------------------
+// Combat events produced during a tick, collected by the server after the systems run.
+//-------------------------------------------------------------------------------------------------
+pub type CombatEvents = Vec<CombatEvent>;
+
+//-------------------------------------------------------------------------------------------------
+// A named bonus or penalty applied to a roll (e.g. "strength +2", "prone -4").
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RollModifier {
+    // What the modifier comes from.
+    pub source: String,
+    // Value added to the roll (negative for penalties).
+    pub value: i32,
+}
+
+impl RollModifier {
+    //---------------------------------------------------------------------------------------------
+    // Returns the modifier a stat gives to rolls (e.g. +2 for 14 or -1 for 8), if not zero.
+    //---------------------------------------------------------------------------------------------
+    pub fn from_stat(source: &str, stat: u8) -> Option<Self> {
+        let value = (stat as i32 - 10).div_euclid(2);
+        (value != 0).then(|| Self { source: source.into(), value })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The full math of an attack, so that the client can show the player how it was resolved.
+// An attack hits when its total meets the defense, and deals its damage total less mitigation.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RollBreakdown {
+    // Natural value of the attack roll.
+    pub attack_roll: i32,
+    // Modifiers added to the attack roll.
+    pub attack_modifiers: Vec<RollModifier>,
+    // Defense the attack total must meet to hit.
+    pub defense: i32,
+    // Natural value of the damage roll.
+    pub damage_roll: i32,
+    // Modifiers added to the damage roll.
+    pub damage_modifiers: Vec<RollModifier>,
+    // Damage subtracted by the defender's armor and resistances.
+    pub mitigation: i32,
+}
+
+impl RollBreakdown {
+    //---------------------------------------------------------------------------------------------
+    // Rolls a melee attack: a d20 plus strength against 10 plus the defender's dexterity, for a
+    // d6 plus strength of damage. Both dice are always rolled, so that the rng advances the same
+    // amount whether or not the attack hits.
+    //---------------------------------------------------------------------------------------------
+    pub fn melee<R>(rng: &mut R, attacker: &ActorStats, defender: &ActorStats) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let strength = RollModifier::from_stat("strength", attacker.STR);
+        let dexterity = RollModifier::from_stat("dexterity", defender.DEX);
+
+        Self {
+            attack_roll: rng.gen_range(1..=MELEE_ATTACK_DIE),
+            attack_modifiers: strength.iter().cloned().collect(),
+            defense: BASE_DEFENSE + dexterity.map_or(0, |m| m.value),
+            damage_roll: rng.gen_range(1..=MELEE_DAMAGE_DIE),
+            damage_modifiers: strength.into_iter().collect(),
+            mitigation: 0,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the attack roll with its modifiers applied.
+    //---------------------------------------------------------------------------------------------
+    pub fn attack_total(&self) -> i32 {
+        self.attack_roll + self.attack_modifiers.iter().map(|m| m.value).sum::<i32>()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the attack hit.
+    //---------------------------------------------------------------------------------------------
+    pub fn hit(&self) -> bool {
+        self.attack_total() >= self.defense
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the damage roll with its modifiers applied (before mitigation).
+    //---------------------------------------------------------------------------------------------
+    pub fn damage_total(&self) -> i32 {
+        self.damage_roll + self.damage_modifiers.iter().map(|m| m.value).sum::<i32>()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the damage dealt: none on a miss, otherwise the damage total less mitigation.
+    //---------------------------------------------------------------------------------------------
+    pub fn damage(&self) -> i32 {
+        if self.hit() {
+            (self.damage_total() - self.mitigation).max(0)
+        } else {
+            0
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Event emitted when an attack is resolved, for the combat log and other UI feedback.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombatEvent {
+    // Id of the attacking entity.
+    pub attacker: u32,
+    // Name of the attacker, as shown to the player.
+    pub attacker_name: String,
+    // Id of the defending entity.
+    pub defender: u32,
+    // Name of the defender, as shown to the player.
+    pub defender_name: String,
+    // Coord of the defender in the zone.
+    pub xy: ICoord,
+    // How the attack was resolved.
+    pub breakdown: RollBreakdown,
+}
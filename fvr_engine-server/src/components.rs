@@ -1,6 +1,7 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
 //-------------------------------------------------------------------------------------------------
@@ -17,12 +18,18 @@ use fvr_engine_core::prelude::*;
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::actor::*;
+use crate::crowd::*;
+use crate::decal::*;
 use crate::goals::*;
+use crate::scent::*;
 
 //-------------------------------------------------------------------------------------------------
 // Aliases for convenience.
 //-------------------------------------------------------------------------------------------------
+pub type ChargeEvents = Vec<ChargeEvent>;
+pub type DecalEvents = Vec<DecalEvent>;
 pub type GoalStack = Vec<Box<dyn Goal + Send + Sync>>;
+pub type MoveRequests = Vec<(Entity, WantsToMove)>;
 pub type SharedActor = Arc<Mutex<Actor>>;
 
 //-------------------------------------------------------------------------------------------------
@@ -38,8 +45,10 @@ pub struct IsActor(pub SharedActor);
 #[derive(Component, Default)]
 #[storage(VecStorage)]
 pub struct HasGoals {
-    // Stack of goals.
+    // Stack of goals (the top goal is the one pursued last turn).
     pub goals: GoalStack,
+    // The goal chosen by the arbitration step of the last turn, for debug inspection.
+    pub choice: Option<GoalChoice>,
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -54,4 +63,44 @@ pub struct WantsToMove {
     pub weight: f32,
     // The priority.
     pub priority: u8,
+    // The navigation map followed, for nudging the actor sideways when its way is contested.
+    pub gradient: MoveGradient,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Component for actors that leave a trail of scent wherever they are each turn.
+//-------------------------------------------------------------------------------------------------
+#[derive(Component, Debug)]
+#[storage(DenseVecStorage)]
+pub struct EmitsScent {
+    // The kind of scent left.
+    pub kind: ScentKind,
+    // Strength of the scent deposited each turn.
+    pub strength: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Component holding named pools of charges (e.g. ability cooldowns or wand charges).
+// The charges system regenerates every pool once per turn.
+//-------------------------------------------------------------------------------------------------
+#[derive(Component, Debug, Default)]
+#[storage(DenseVecStorage)]
+pub struct HasCharges {
+    // Pools of charges by name (ordered, so that the pools tick and report in a stable order).
+    pub pools: BTreeMap<String, Charges>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Event emitted when a pool of charges regains charges, for updating UI indicators.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChargeEvent {
+    // Id of the entity holding the pool.
+    pub entity: u32,
+    // Name of the pool.
+    pub name: String,
+    // Number of charges in the pool after regenerating.
+    pub charges: u32,
+    // Whether the pool is now full.
+    pub full: bool,
 }
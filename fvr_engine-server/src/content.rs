@@ -0,0 +1,211 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fmt;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{bail, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+use fvr_engine_parser::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::door::*;
+use crate::thing::*;
+
+//-------------------------------------------------------------------------------------------------
+// An error found while validating content.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentError {
+    // The content the error was found in (e.g. "thing 'tree'").
+    pub source: String,
+    // Description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ContentError {
+    //---------------------------------------------------------------------------------------------
+    // Formats the error as "<source>: <message>".
+    //---------------------------------------------------------------------------------------------
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.message)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ContentReport aggregates the errors found while validating all of the loaded content, so that
+// every error can be reported at once rather than failing on the first.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct ContentReport {
+    // The errors found.
+    errors: Vec<ContentError>,
+    // # of pieces of content checked.
+    checked: usize,
+}
+
+impl ContentReport {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty report.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds an error to the report.
+    //---------------------------------------------------------------------------------------------
+    pub fn error<S, M>(&mut self, source: S, message: M)
+    where
+        S: Into<String>,
+        M: Into<String>,
+    {
+        self.errors.push(ContentError { source: source.into(), message: message.into() });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the errors found.
+    //---------------------------------------------------------------------------------------------
+    pub fn errors(&self) -> &[ContentError] {
+        &self.errors
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of pieces of content checked.
+    //---------------------------------------------------------------------------------------------
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no errors were found.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks a thing: its glyph and animation frames must be in the atlas, its opacities in range,
+    // and its door state (if any) consistent with its passability and transparency.
+    //---------------------------------------------------------------------------------------------
+    pub fn check_thing(&mut self, name: &str, thing: &Thing) {
+        self.checked += 1;
+        let source = format!("thing '{}'", name);
+
+        if !is_cp437(thing.tile.glyph) {
+            self.error(
+                &source,
+                format!("Glyph {:?} is missing from the atlas.", thing.tile.glyph),
+            );
+        }
+
+        let opacities = [
+            ("background", thing.tile.background_opacity),
+            ("foreground", thing.tile.foreground_opacity),
+            ("outline", thing.tile.outline_opacity),
+        ];
+
+        for (layer, opacity) in opacities.iter() {
+            if !(0.0..=1.0).contains(opacity) {
+                self.error(&source, format!("The {} opacity {} is not in 0..=1.", layer, opacity));
+            }
+        }
+
+        if let Some(id) = thing.animation {
+            match AmbientAnimation::get(id) {
+                Some(animation) => {
+                    for glyph in animation.frames.iter().filter(|glyph| !is_cp437(**glyph)) {
+                        self.error(
+                            &source,
+                            format!("Animation frame {:?} is missing from the atlas.", glyph),
+                        );
+                    }
+                }
+                None => self.error(
+                    &source,
+                    format!(
+                        "Dangling animation id {} (only {} exist).",
+                        id,
+                        AmbientAnimation::count()
+                    ),
+                ),
+            }
+        }
+
+        let expected = match thing.door {
+            Some(DoorState::Open) => Some((Passability::Passable, Transparency::Transparent)),
+            Some(DoorState::Closed) => Some((Passability::Blocked, Transparency::Opaque)),
+            None => None,
+        };
+
+        if let Some((passability, transparency)) = expected {
+            if thing.passability != passability || thing.transparency != transparency {
+                self.error(
+                    &source,
+                    format!(
+                        "A {:?} door must be {:?} and {:?}.",
+                        thing.door.expect("Unreachable."),
+                        passability,
+                        transparency
+                    ),
+                );
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks that every glyph an autotile ruleset can pick is in the atlas.
+    //---------------------------------------------------------------------------------------------
+    pub fn check_autotile_ruleset(&mut self, name: &str, ruleset: &AutotileRuleset) {
+        self.checked += 1;
+        let glyphs = ruleset.rules.iter().map(|rule| rule.glyph);
+
+        for glyph in glyphs.chain(std::iter::once(ruleset.fallback)) {
+            if !is_cp437(glyph) {
+                self.error(name, format!("Glyph {:?} is missing from the atlas.", glyph));
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks that a string is valid rich text.
+    //---------------------------------------------------------------------------------------------
+    pub fn check_rich_text(&mut self, source: &str, text: &str) {
+        self.checked += 1;
+
+        if let Err(e) = validate_rich_text(text) {
+            self.error(source, format!("{:#}", e));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Checks that an index refers to one of a # of pieces of content (e.g. an actor's intention).
+    //---------------------------------------------------------------------------------------------
+    pub fn check_index(&mut self, source: &str, what: &str, index: usize, len: usize) {
+        self.checked += 1;
+
+        if index >= len {
+            self.error(source, format!("Dangling {} index {} (only {} exist).", what, index, len));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Consumes the report, returning an error listing every error found, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn into_result(self) -> Result<()> {
+        if self.is_ok() {
+            return Ok(());
+        }
+
+        let errors: Vec<String> = self.errors.iter().map(|e| format!("  {}", e)).collect();
+        bail!("Found {} content errors:\n{}", errors.len(), errors.join("\n"));
+    }
+}
@@ -0,0 +1,342 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::rejection::*;
+use crate::zone::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Rotations (clockwise, in eighths) tried in order when nudging a blocked actor sideways.
+const SIDESTEP_ROTATIONS: [i32; 4] = [1, 7, 2, 6];
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the navigation maps a move can follow, used to nudge blocked actors sideways.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveGradient {
+    // The move does not follow a navigation map (e.g. the player's moves).
+    None,
+    // The move follows the avoid map.
+    Avoid,
+    // The move follows the chase map.
+    Chase,
+    // The move follows the chase map through closed doors.
+    DoorChase,
+}
+
+impl MoveGradient {
+    //---------------------------------------------------------------------------------------------
+    // Returns the navigation map of the gradient in a zone, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn map<'a>(&self, zone: &'a Zone) -> Option<&'a DijkstraMap> {
+        match self {
+            MoveGradient::None => None,
+            MoveGradient::Avoid => Some(&zone.avoid_map),
+            MoveGradient::Chase => Some(&zone.chase_map),
+            MoveGradient::DoorChase => Some(&zone.door_chase_map),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the weight of a coord in the navigation map of the gradient, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn weight(&self, zone: &Zone, xy: ICoord) -> Option<f32> {
+        self.map(zone).filter(|map| map.in_bounds(xy)).and_then(|map| *map.get_xy(xy))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes an actor's intention to move this turn.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MoveIntent {
+    // Current position of the actor.
+    pub from: ICoord,
+    // Direction the actor wants to move.
+    pub direction: Direction,
+    // Navigation map the move follows.
+    pub gradient: MoveGradient,
+    // Priority of the move (higher priority moves claim contested cells first).
+    pub priority: u8,
+    // Whether the actor is the player (the player's moves are resolved first and never nudged).
+    pub player: bool,
+}
+
+impl MoveIntent {
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord the actor wants to move to.
+    //---------------------------------------------------------------------------------------------
+    pub fn target(&self) -> ICoord {
+        (self.from.0 + self.direction.dx(), self.from.1 + self.direction.dy())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes how a move intention was resolved.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveOutcome {
+    // The actor moves to the coord it wanted.
+    Moved(ICoord),
+    // The actor trades places with an actor that wanted its coord.
+    Swapped(ICoord),
+    // The actor was nudged sideways to a coord that is no further from its goal.
+    Sidestepped(ICoord),
+    // The actor stays put.
+    Blocked(RejectionReason),
+}
+
+impl MoveOutcome {
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord the actor moves to, if it moves.
+    //---------------------------------------------------------------------------------------------
+    pub fn destination(&self) -> Option<ICoord> {
+        match self {
+            MoveOutcome::Moved(xy) | MoveOutcome::Swapped(xy) | MoveOutcome::Sidestepped(xy) => {
+                Some(*xy)
+            }
+            MoveOutcome::Blocked(_) => None,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Temporaries used to resolve the moves of a turn, taken from the turn pools so that resolving
+// does not reallocate them every turn.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct MoveResolution {
+    // Indices of the intentions in the order they are resolved.
+    order: Vec<usize>,
+    // Index of the intention of the actor at each coord.
+    movers: HashMap<ICoord, usize>,
+    // Coords claimed by the moves resolved so far.
+    claimed: HashSet<ICoord>,
+    // Outcome of each intention, once resolved.
+    pending: Vec<Option<MoveOutcome>>,
+    // Outcome of each intention, in the same order as the intentions.
+    outcomes: Vec<MoveOutcome>,
+}
+
+impl Poolable for MoveResolution {
+    fn reset(&mut self) {
+        self.order.clear();
+        self.movers.clear();
+        self.claimed.clear();
+        self.pending.clear();
+        self.outcomes.clear();
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Returns the key by which actors take their turns (in ascending order): the player first, then
+// by descending priority, then by the order the actors are processed in.
+//-------------------------------------------------------------------------------------------------
+pub fn turn_order_key(player: bool, priority: u8, order: usize) -> (bool, Reverse<u8>, usize) {
+    (!player, Reverse(priority), order)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Resolves the move intentions of a turn together, so that conflicts do not depend on the order
+// in which the actors are processed. Returns the outcome of each intention, in the same order.
+// The temporaries (and the outcomes) are kept in a resolution, which is reset first.
+//
+// Rules:
+// - The player's moves are resolved first, then moves by descending priority, then by order.
+// - A move into a coord vacated by another move this turn succeeds (actors can follow each other).
+// - Two (non-player) actors that want each other's coords swap places.
+// - A (non-player) actor whose coord is contested or occupied is nudged sideways to the free
+//   neighbor closest to its goal, as long as that does not take it further from its goal.
+//-------------------------------------------------------------------------------------------------
+pub fn resolve_moves<'a>(
+    zone: &Zone,
+    intents: &[MoveIntent],
+    resolution: &'a mut MoveResolution,
+) -> &'a [MoveOutcome] {
+    resolution.reset();
+
+    let MoveResolution { order, movers, claimed, pending: outcomes, .. } = resolution;
+
+    order.extend(0..intents.len());
+    order.sort_by_key(|&i| turn_order_key(intents[i].player, intents[i].priority, i));
+
+    movers.extend(intents.iter().enumerate().map(|(i, intent)| (intent.from, i)));
+    outcomes.resize(intents.len(), None);
+
+    // Returns whether a coord is free of (remaining) actors.
+    let vacated = |outcomes: &[Option<MoveOutcome>], xy: ICoord| {
+        if zone.actor_map.get_xy(xy).is_none() {
+            return true;
+        }
+
+        match movers.get(&xy) {
+            Some(&j) => outcomes[j].is_some_and(|outcome| outcome.destination().is_some()),
+            None => false,
+        }
+    };
+
+    // Resolve the moves into free or vacated coords until no more can be resolved, deferring the
+    // moves into coords whose occupants have not been resolved yet.
+    let mut progress = true;
+
+    while progress {
+        progress = false;
+
+        for &i in order.iter() {
+            if outcomes[i].is_some() {
+                continue;
+            }
+
+            let target = intents[i].target();
+
+            if let Some(reason) = zone.terrain_blocked_reason(target) {
+                outcomes[i] = Some(MoveOutcome::Blocked(reason));
+                progress = true;
+                continue;
+            }
+
+            if claimed.contains(&target) {
+                continue;
+            }
+
+            let occupant_pending =
+                movers.get(&target).is_some_and(|&j| j != i && outcomes[j].is_none());
+
+            if occupant_pending || !vacated(outcomes, target) {
+                continue;
+            }
+
+            let _ = claimed.insert(target);
+            outcomes[i] = Some(MoveOutcome::Moved(target));
+            progress = true;
+        }
+    }
+
+    // Swap actors that want each other's coords.
+    for &i in order.iter() {
+        if outcomes[i].is_some() || intents[i].player {
+            continue;
+        }
+
+        let target = intents[i].target();
+
+        let j = match movers.get(&target) {
+            Some(&j) if j != i && outcomes[j].is_none() && !intents[j].player => j,
+            _ => continue,
+        };
+
+        if intents[j].target() != intents[i].from
+            || claimed.contains(&target)
+            || claimed.contains(&intents[i].from)
+        {
+            continue;
+        }
+
+        let _ = claimed.insert(target);
+        let _ = claimed.insert(intents[i].from);
+        outcomes[i] = Some(MoveOutcome::Swapped(target));
+        outcomes[j] = Some(MoveOutcome::Swapped(intents[i].from));
+    }
+
+    // Nudge the remaining actors sideways, or block them.
+    for &i in order.iter() {
+        if outcomes[i].is_some() {
+            continue;
+        }
+
+        let intent = &intents[i];
+        let mut best: Option<(ICoord, f32)> = None;
+
+        if let Some(current) = intent.gradient.weight(zone, intent.from).filter(|_| !intent.player)
+        {
+            for rotation in SIDESTEP_ROTATIONS.iter() {
+                let direction = intent.direction.clockwise(*rotation);
+                let xy = (intent.from.0 + direction.dx(), intent.from.1 + direction.dy());
+
+                if zone.terrain_blocked_reason(xy).is_some()
+                    || claimed.contains(&xy)
+                    || !vacated(outcomes, xy)
+                {
+                    continue;
+                }
+
+                let weight = match intent.gradient.weight(zone, xy) {
+                    Some(weight) if weight <= current => weight,
+                    _ => continue,
+                };
+
+                if best.is_none_or(|(_, best_weight)| weight < best_weight) {
+                    best = Some((xy, weight));
+                }
+            }
+        }
+
+        outcomes[i] = Some(match best {
+            Some((xy, _)) => {
+                let _ = claimed.insert(xy);
+                MoveOutcome::Sidestepped(xy)
+            }
+            None => MoveOutcome::Blocked(RejectionReason::Occupied),
+        });
+    }
+
+    resolution
+        .outcomes
+        .extend(resolution.pending.iter().map(|outcome| outcome.expect("Unresolved move.")));
+
+    &resolution.outcomes
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_resolve_moves_reuses_resolution() {
+    use specs::prelude::*;
+
+    use crate::components::*;
+
+    let mut world = World::new();
+    world.register::<IsActor>();
+    world.register::<HasGoals>();
+    world.register::<EmitsScent>();
+
+    let zone =
+        Zone::from_cells(GridMap::new((16, 16)), &mut world, &mut Rng::new(0xC20D)).unwrap();
+    let intents = [MoveIntent {
+        from: zone.player_xy,
+        direction: EAST_DIRECTION,
+        gradient: MoveGradient::None,
+        priority: 0,
+        player: true,
+    }];
+    let moved = MoveOutcome::Moved((zone.player_xy.0 + 1, zone.player_xy.1));
+    let mut pool: Pool<MoveResolution> = Pool::new();
+
+    // The first turn allocates the temporaries.
+    let mut resolution = pool.take();
+    assert_eq!(resolve_moves(&zone, &intents, &mut resolution), &[moved]);
+    pool.give(resolution);
+    assert_eq!(pool.end_turn(), PoolStats { allocations: 1, reuses: 0 });
+
+    // Later turns reuse them, resolving the same way.
+    let mut resolution = pool.take();
+    assert_eq!(resolve_moves(&zone, &intents, &mut resolution), &[moved]);
+    assert!(resolution.movers.capacity() > 0 && resolution.claimed.capacity() > 0);
+    pool.give(resolution);
+    assert_eq!(pool.end_turn(), PoolStats { allocations: 0, reuses: 1 });
+}
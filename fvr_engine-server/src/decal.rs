@@ -0,0 +1,416 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::io::{Read, Write};
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Strength below which a decal is considered gone.
+pub const MIN_DECAL_STRENGTH: f32 = 0.05;
+
+// Opacity of the background tint of a decal at full strength.
+pub const MAX_DECAL_OPACITY: f32 = 0.6;
+
+// Strength of blood an actor must step in to start leaving footprints.
+pub const FOOTPRINT_BLOOD_STRENGTH: f32 = 0.3;
+
+// # of footprints an actor leaves after stepping in blood.
+pub const FOOTPRINT_STEPS: u8 = 6;
+
+// Strength of the first footprint (later footprints fade with the remaining steps).
+pub const FOOTPRINT_STRENGTH: f32 = 0.8;
+
+// Strength of the blood spilled by a wound, and the strength added per point of damage.
+pub const WOUND_BLOOD_STRENGTH: f32 = 0.3;
+pub const WOUND_BLOOD_PER_DAMAGE: f32 = 0.05;
+
+// Damage at which a wound splatters blood over the neighboring cells as well.
+pub const WOUND_SPLATTER_DAMAGE: i32 = 6;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the kinds of decals.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DecalKind {
+    // Spilled by wounded actors.
+    Blood,
+    // Left by fire and explosions.
+    Scorch,
+    // Left by actors that stepped in blood.
+    Footprints,
+}
+
+impl DecalKind {
+    // All of the decal kinds.
+    pub const ALL: [DecalKind; 3] = [DecalKind::Blood, DecalKind::Scorch, DecalKind::Footprints];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the fraction of the kind's strength kept each turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn decay(&self) -> f32 {
+        match self {
+            DecalKind::Blood => 0.998,
+            DecalKind::Scorch => 0.999,
+            DecalKind::Footprints => 0.99,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color the kind tints the background (or draws its overlay glyph) with.
+    //---------------------------------------------------------------------------------------------
+    pub fn color(&self) -> PaletteColor {
+        match self {
+            DecalKind::Blood => PaletteColor::DarkRed,
+            DecalKind::Scorch => PaletteColor::Black,
+            DecalKind::Footprints => PaletteColor::DarkRed,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the glyph the kind is drawn over floors with, if it is not only a background tint.
+    //---------------------------------------------------------------------------------------------
+    pub fn overlay_glyph(&self) -> Option<char> {
+        match self {
+            DecalKind::Footprints => Some('·'),
+            _ => None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether water washes the kind away.
+    //---------------------------------------------------------------------------------------------
+    pub fn washable(&self) -> bool {
+        !matches!(self, DecalKind::Scorch)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A purely cosmetic mark left on a cell.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decal {
+    // The kind of the decal.
+    pub kind: DecalKind,
+    // Strength of the decal (in the range [MIN_DECAL_STRENGTH, 1.0]).
+    pub strength: f32,
+}
+
+impl Decal {
+    //---------------------------------------------------------------------------------------------
+    // Draws the decal over the tile of a cell. Overlay glyphs are only drawn over passable cells.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, tile: &mut Tile, passable: bool) {
+        let color: TileColor = self.kind.color().into();
+
+        match self.kind.overlay_glyph() {
+            Some(glyph) if passable => {
+                tile.glyph = glyph;
+                tile.foreground_color = color;
+                tile.foreground_opacity *= self.strength.max(MAX_DECAL_OPACITY);
+            }
+            Some(_) => {}
+            None => {
+                let t = self.strength * MAX_DECAL_OPACITY;
+                let (old, new) = (tile.background_color.0, color.0);
+                let mix = |a: u8, b: u8| (a as f32 * (1.0 - t) + b as f32 * t).round() as u8;
+
+                // A transparent background takes the decal's color outright.
+                let (r, g, b) = if old.a == 0 {
+                    (new.r, new.g, new.b)
+                } else {
+                    (mix(old.r, new.r), mix(old.g, new.g), mix(old.b, new.b))
+                };
+
+                let a = old.a.max((t * 255.0).round() as u8);
+                tile.background_color = TileColor::rgba(r, g, b, a);
+            }
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A request for a decal, written by combat and hazards and applied by the decal system.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DecalEvent {
+    // Center of the decal.
+    pub xy: ICoord,
+    // The kind of the decal.
+    pub kind: DecalKind,
+    // Strength of the decal at its center.
+    pub strength: f32,
+    // Radius of the splatter (the strength falls off towards its edge).
+    pub radius: i32,
+}
+
+impl DecalEvent {
+    //---------------------------------------------------------------------------------------------
+    // Returns the blood spilled by a wound of some damage at a coord, if any was dealt.
+    // Heavier wounds spill more blood, further.
+    //---------------------------------------------------------------------------------------------
+    pub fn wound(xy: ICoord, damage: i32) -> Option<Self> {
+        if damage <= 0 {
+            return None;
+        }
+
+        Some(Self {
+            xy,
+            kind: DecalKind::Blood,
+            strength: (WOUND_BLOOD_STRENGTH + damage as f32 * WOUND_BLOOD_PER_DAMAGE).min(1.0),
+            radius: if damage >= WOUND_SPLATTER_DAMAGE { 1 } else { 0 },
+        })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// DecalLayer holds at most one decal per cell of a zone, fading them over time.
+//-------------------------------------------------------------------------------------------------
+pub struct DecalLayer {
+    // The decal of each coord, if any.
+    decals: GridMap<Option<Decal>>,
+}
+
+impl DecalLayer {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, clean layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord) -> Self {
+        Self { decals: GridMap::new(dimensions) }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the decal at a coord, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, xy: ICoord) -> Option<Decal> {
+        if self.decals.in_bounds(xy) {
+            *self.decals.get_xy(xy)
+        } else {
+            None
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Leaves a decal at a coord. Decals of the same kind accumulate, otherwise the stronger wins.
+    //---------------------------------------------------------------------------------------------
+    pub fn add(&mut self, xy: ICoord, kind: DecalKind, strength: f32) {
+        if !self.decals.in_bounds(xy) || strength < MIN_DECAL_STRENGTH {
+            return;
+        }
+
+        let existing = self.decals.get_xy_mut(xy);
+
+        *existing = match *existing {
+            Some(decal) if decal.kind == kind => {
+                Some(Decal { kind, strength: (decal.strength + strength).min(1.0) })
+            }
+            Some(decal) if decal.strength >= strength => Some(decal),
+            _ => Some(Decal { kind, strength: strength.min(1.0) }),
+        };
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies a decal event, splattering its decal over the coords within its radius.
+    //---------------------------------------------------------------------------------------------
+    pub fn splatter(&mut self, event: &DecalEvent) {
+        let radius = event.radius.max(0);
+
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+
+                if distance > radius as f32 + 0.5 {
+                    continue;
+                }
+
+                let falloff = 1.0 - distance / (radius as f32 + 1.0);
+                self.add((event.xy.0 + dx, event.xy.1 + dy), event.kind, event.strength * falloff);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Washes away the washable decals within a radius of a coord. Returns the # washed away.
+    //---------------------------------------------------------------------------------------------
+    pub fn wash(&mut self, xy: ICoord, radius: i32) -> usize {
+        let mut washed = 0;
+
+        for y in (xy.1 - radius)..=(xy.1 + radius) {
+            for x in (xy.0 - radius)..=(xy.0 + radius) {
+                if !self.decals.in_bounds((x, y)) {
+                    continue;
+                }
+
+                let decal = self.decals.get_xy_mut((x, y));
+
+                if decal.is_some_and(|decal| decal.kind.washable()) {
+                    *decal = None;
+                    washed += 1;
+                }
+            }
+        }
+
+        washed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fades the decals by one turn, removing those that have faded away.
+    //---------------------------------------------------------------------------------------------
+    pub fn age(&mut self) {
+        for decal in self.decals.data_mut().iter_mut() {
+            if let Some(d) = decal {
+                d.strength *= d.kind.decay();
+
+                if d.strength < MIN_DECAL_STRENGTH {
+                    *decal = None;
+                }
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all decals from the layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.decals.data_mut().fill(None);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of decals in the layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.decals.data().iter().flatten().count()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the layer has no decals.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.decals.data().iter().all(Option::is_none)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Serializes the decals, one "x,y:kind:strength" entry per decal in row order.
+    //---------------------------------------------------------------------------------------------
+    pub fn serialize(&self) -> String {
+        let mut entries = Vec::new();
+
+        for (index, decal) in self.decals.data().iter().enumerate() {
+            if let Some(decal) = decal {
+                let (x, y) = Misc::reverse_index_2d(index, self.decals.width());
+                entries.push(format!("{},{}:{:?}:{:.3}", x, y, decal.kind, decal.strength));
+            }
+        }
+
+        entries.join(";")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deserializes a layer of some dimensions from text created by serialize (e.g. to restore the
+    // decals of a zone that is entered again).
+    //---------------------------------------------------------------------------------------------
+    pub fn deserialize(dimensions: ICoord, text: &str) -> Result<Self> {
+        let mut layer = Self::new(dimensions);
+
+        for entry in text.split(';').filter(|entry| !entry.is_empty()) {
+            let invalid = || anyhow!("Invalid decal entry {:?}.", entry);
+            let mut fields = entry.split(':');
+            let (xy, kind, strength) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(xy), Some(kind), Some(strength)) => (xy, kind, strength),
+                _ => return Err(invalid()),
+            };
+            let xy = xy
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .filter(|xy| layer.decals.in_bounds(*xy))
+                .ok_or_else(invalid)?;
+            let kind = DecalKind::ALL
+                .iter()
+                .copied()
+                .find(|k| format!("{:?}", k) == kind)
+                .ok_or_else(invalid)?;
+            let strength = strength.parse::<f32>().map_err(|_| invalid())?;
+
+            *layer.decals.get_xy_mut(xy) = Some(Decal { kind, strength });
+        }
+
+        Ok(layer)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves the decals to a (compressed) zone file.
+    //---------------------------------------------------------------------------------------------
+    pub fn save<P>(&self, path: P, options: CompressionOptions) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        save_compressed(path, options, |writer| {
+            writer.write_all(self.serialize().as_bytes()).context("Failed to write decals.")
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads a layer of some dimensions from a (compressed) zone file.
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P>(dimensions: ICoord, path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut text = String::new();
+        load_compressed(path)?.read_to_string(&mut text).context("Failed to read decals.")?;
+        Self::deserialize(dimensions, &text)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_decal_layer_round_trip() {
+    let mut layer = DecalLayer::new((8, 8));
+    layer.splatter(&DecalEvent::wound((3, 3), 10).unwrap());
+    layer.add((7, 0), DecalKind::Scorch, 0.5);
+    layer.add((0, 7), DecalKind::Footprints, 0.25);
+
+    let text = layer.serialize();
+    let restored = DecalLayer::deserialize((8, 8), &text).unwrap();
+    assert_eq!(restored.serialize(), text);
+    assert_eq!(restored.len(), layer.len());
+    assert_eq!(restored.get((7, 0)).map(|decal| decal.kind), Some(DecalKind::Scorch));
+
+    assert!(DecalLayer::deserialize((8, 8), "").unwrap().is_empty());
+    assert!(DecalLayer::deserialize((8, 8), "9,0:Blood:0.500").is_err());
+    assert!(DecalLayer::deserialize((8, 8), "1,1:Mud:0.500").is_err());
+
+    // Zone files round trip with every codec.
+    let path = std::env::temp_dir().join(format!("fvr_engine_decals_{}", std::process::id()));
+
+    for codec in [CompressionCodec::None, CompressionCodec::Deflate, CompressionCodec::Lz4].iter()
+    {
+        layer.save(&path, CompressionOptions::new(*codec, DEFAULT_COMPRESSION_LEVEL)).unwrap();
+        assert_eq!(DecalLayer::load((8, 8), &path).unwrap().serialize(), text);
+    }
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_decal_event_wound() {
+    assert_eq!(DecalEvent::wound((0, 0), 0), None);
+    assert_eq!(DecalEvent::wound((0, 0), 2).unwrap().radius, 0);
+    assert_eq!(DecalEvent::wound((0, 0), WOUND_SPLATTER_DAMAGE).unwrap().radius, 1);
+    assert_eq!(DecalEvent::wound((0, 0), 100).unwrap().strength, 1.0);
+}
@@ -0,0 +1,255 @@
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use rand::Rng as _;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::cell::*;
+use crate::thing::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Offsets sampling the dampness noise away from the floor noise.
+const DAMPNESS_OFFSET: (f32, f32) = (173.0, 59.0);
+
+// Settings of the floor variation and dampness noise.
+const DECORATION_NOISE: NoiseSettings = NoiseSettings {
+    noise_type: NoiseType::Simplex,
+    frequency: 0.08,
+    octaves: 3,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+pub static RUBBLE_THING: Thing = decoration_thing('∙', PaletteColor::BrightGrey);
+pub static BONES_THING: Thing = decoration_thing('%', PaletteColor::White);
+pub static MUSHROOM_THING: Thing = decoration_thing('♠', PaletteColor::BrightRed);
+
+static FOREST_THEME: DecorationTheme = DecorationTheme {
+    floor_variants: &[
+        FloorVariant { max_noise: -0.35, glyph: ',', color: PaletteColor::DarkGreen },
+        FloorVariant { max_noise: 0.2, glyph: '.', color: PaletteColor::DarkGreen },
+        FloorVariant { max_noise: 0.45, glyph: '\'', color: PaletteColor::BrightGreen },
+        FloorVariant { max_noise: 1.0, glyph: '"', color: PaletteColor::BrightGreen },
+    ],
+    moss_dampness: 0.5,
+    moss_color: PaletteColor::DarkCyan,
+    scatter: &[
+        Scatter { thing: &MUSHROOM_THING, density: 0.004, min_dampness: 0.2, blocked_bias: 2.0 },
+        Scatter { thing: &BONES_THING, density: 0.001, min_dampness: -1.0, blocked_bias: 0.0 },
+        Scatter { thing: &RUBBLE_THING, density: 0.001, min_dampness: -1.0, blocked_bias: 4.0 },
+    ],
+};
+
+static RUINS_THEME: DecorationTheme = DecorationTheme {
+    floor_variants: &[
+        FloorVariant { max_noise: -0.3, glyph: '∙', color: PaletteColor::DarkGrey },
+        FloorVariant { max_noise: 0.4, glyph: '.', color: PaletteColor::BrightGrey },
+        FloorVariant { max_noise: 1.0, glyph: ',', color: PaletteColor::Brown },
+    ],
+    moss_dampness: 0.3,
+    moss_color: PaletteColor::DarkGreen,
+    scatter: &[
+        Scatter { thing: &RUBBLE_THING, density: 0.02, min_dampness: -1.0, blocked_bias: 3.0 },
+        Scatter { thing: &BONES_THING, density: 0.004, min_dampness: -1.0, blocked_bias: 1.0 },
+        Scatter { thing: &MUSHROOM_THING, density: 0.006, min_dampness: 0.4, blocked_bias: 1.0 },
+    ],
+};
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that creates a non-blocking decorative thing.
+//-------------------------------------------------------------------------------------------------
+const fn decoration_thing(glyph: char, color: PaletteColor) -> Thing {
+    Thing {
+        tile: Tile {
+            glyph,
+            layout: TileLayout::Center,
+            style: TileStyle::Regular,
+            size: TileSize::Normal,
+            outlined: false,
+            background_color: TileColor::TRANSPARENT,
+            foreground_color: color.const_into(),
+            outline_color: TileColor::TRANSPARENT,
+            background_opacity: 1.0,
+            foreground_opacity: 1.0,
+            outline_opacity: 1.0,
+            background_gradient: TileGradient::None,
+            outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+            outline_glow: false,
+        },
+        passability: Passability::Passable,
+        transparency: Transparency::Transparent,
+        door: None,
+        autotile: false,
+        animation: None,
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A variant of the floor glyph, used where the floor noise is at most a value.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct FloorVariant {
+    // The maximum floor noise (in the range [-1.0..1.0]) the variant is used at.
+    pub max_noise: f32,
+    // The glyph of the variant.
+    pub glyph: char,
+    // The foreground color of the variant.
+    pub color: PaletteColor,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A decorative thing scattered over the floor.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Scatter {
+    // The thing scattered (must not block).
+    pub thing: &'static Thing,
+    // Chance of the thing being scattered on a floor cell.
+    pub density: f32,
+    // The minimum dampness noise (in the range [-1.0..1.0]) the thing is scattered at.
+    pub min_dampness: f32,
+    // Increase of the density for each blocking neighbor (e.g. rubble gathers beside walls).
+    pub blocked_bias: f32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// DecorationTheme describes how the floors of a biome are decorated. Themes are plain data, so
+// that the look of generated zones can be changed without touching the decoration pass.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct DecorationTheme {
+    // Variants of the floor glyph, ordered by their maximum floor noise.
+    pub floor_variants: &'static [FloorVariant],
+    // The minimum dampness noise moss grows at.
+    pub moss_dampness: f32,
+    // The foreground color of mossy floors and walls.
+    pub moss_color: PaletteColor,
+    // The decorative things scattered over the floor, tried in order (one per cell at most).
+    pub scatter: &'static [Scatter],
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the biomes zones are decorated for.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Biome {
+    // Grassy and mushroom-strewn.
+    Forest,
+    // Stony and rubble-strewn.
+    Ruins,
+}
+
+impl Biome {
+    // All of the biomes.
+    pub const ALL: [Biome; 2] = [Biome::Forest, Biome::Ruins];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the decoration theme of the biome.
+    //---------------------------------------------------------------------------------------------
+    pub fn theme(&self) -> &'static DecorationTheme {
+        match self {
+            Biome::Forest => &FOREST_THEME,
+            Biome::Ruins => &RUINS_THEME,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that returns whether a thing is a floor the decoration pass may vary.
+//-------------------------------------------------------------------------------------------------
+fn is_floor(thing: &Thing) -> bool {
+    thing.passability == Passability::Passable && thing.door.is_none() && !thing.autotile
+}
+
+//-------------------------------------------------------------------------------------------------
+// Runs the decoration pass over generated cells: varies the floor glyphs and colors with noise,
+// grows moss on damp floors and walls, and scatters decorative things over the floor.
+//-------------------------------------------------------------------------------------------------
+pub fn decorate(cell_map: &mut GridMap<Cell>, theme: &DecorationTheme, seed: u64) {
+    let noise = Noise::new(seed);
+    let mut rng = Rng::new(seed);
+    let dimensions = cell_map.dimensions();
+
+    // Count the blocking neighbors of every cell before any are changed.
+    let mut blocked_neighbors = GridMap::<u8>::new(dimensions);
+
+    for y in 0..dimensions.1 {
+        for x in 0..dimensions.0 {
+            *blocked_neighbors.get_xy_mut((x, y)) = Adjacency::EightWay
+                .iter()
+                .map(|direction| (x + direction.dx(), y + direction.dy()))
+                .filter(|xy| {
+                    cell_map.in_bounds(*xy)
+                        && cell_map.get_xy(*xy).passability() == Passability::Blocked
+                })
+                .count() as u8;
+        }
+    }
+
+    for y in 0..dimensions.1 {
+        for x in 0..dimensions.0 {
+            let (fx, fy) = (x as f32, y as f32);
+            let floor_noise = noise.fbm(fx, fy, &DECORATION_NOISE);
+            let dampness =
+                noise.fbm(fx + DAMPNESS_OFFSET.0, fy + DAMPNESS_OFFSET.1, &DECORATION_NOISE);
+            let mossy = dampness >= theme.moss_dampness;
+            let cell = cell_map.get_xy_mut((x, y));
+
+            let thing = match cell.things.first_mut() {
+                Some(thing) => thing,
+                None => continue,
+            };
+
+            // Walls only grow moss.
+            if !is_floor(thing) {
+                if mossy && thing.autotile {
+                    thing.tile.foreground_color = theme.moss_color.into();
+                }
+
+                continue;
+            }
+
+            // Vary the floor.
+            if let Some(variant) =
+                theme.floor_variants.iter().find(|variant| floor_noise <= variant.max_noise)
+            {
+                thing.tile.glyph = variant.glyph;
+                thing.tile.foreground_color = variant.color.into();
+            }
+
+            if mossy {
+                thing.tile.foreground_color = theme.moss_color.into();
+            }
+
+            // Scatter decorations on otherwise empty floors.
+            if cell.things.len() != 1 {
+                continue;
+            }
+
+            let blocked = *blocked_neighbors.get_xy((x, y)) as f32;
+
+            for scatter in theme.scatter.iter() {
+                let chance = scatter.density * (1.0 + scatter.blocked_bias * blocked);
+
+                if dampness >= scatter.min_dampness && rng.gen::<f32>() < chance {
+                    cell.things.push(*scatter.thing);
+                    break;
+                }
+            }
+        }
+    }
+}
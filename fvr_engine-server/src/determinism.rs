@@ -0,0 +1,385 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{bail, Context, Error, Result};
+use rand::Rng as _;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::server::*;
+
+//-------------------------------------------------------------------------------------------------
+// ServerState is the simulation state of a server serialized into comparable entries, keyed by
+// component and entity (e.g. "actor.12").
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ServerState {
+    // Serialized state entries by key.
+    entries: BTreeMap<String, String>,
+}
+
+impl ServerState {
+    //---------------------------------------------------------------------------------------------
+    // Inserts a serialized entry, replacing any entry with the same key.
+    //---------------------------------------------------------------------------------------------
+    pub fn insert(&mut self, key: &str, value: String) {
+        let _ = self.entries.insert(key.into(), value);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the serialized entry for a key, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|value| value.as_str())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no entries.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the entries that differ from another state, in key order.
+    //---------------------------------------------------------------------------------------------
+    pub fn diff(&self, other: &ServerState) -> Vec<StateDifference> {
+        let mut differences = Vec::new();
+
+        for (key, value) in self.entries.iter() {
+            match other.entries.get(key) {
+                Some(other_value) if other_value == value => {}
+                other_value => differences.push(StateDifference {
+                    key: key.clone(),
+                    left: Some(value.clone()),
+                    right: other_value.cloned(),
+                }),
+            }
+        }
+
+        for (key, value) in other.entries.iter() {
+            if !self.entries.contains_key(key) {
+                differences.push(StateDifference {
+                    key: key.clone(),
+                    left: None,
+                    right: Some(value.clone()),
+                });
+            }
+        }
+
+        differences.sort_by(|a, b| a.key.cmp(&b.key));
+        differences
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// An entry that differs between two server states (none if the entry is missing).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateDifference {
+    // Key of the entry.
+    pub key: String,
+    // Entry of the left state.
+    pub left: Option<String>,
+    // Entry of the right state.
+    pub right: Option<String>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the inputs that can be recorded on a tape and replayed against a server.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum TapeInput {
+    // Pass a turn.
+    Wait,
+    // Move the player in a direction.
+    Move(Direction),
+    // Begin traveling the player to a coord.
+    TravelTo(ICoord),
+    // Move the traveling player one step.
+    TravelStep,
+    // Use a named pool of the player's charges.
+    UseCharges(String),
+}
+
+impl TapeInput {
+    //---------------------------------------------------------------------------------------------
+    // Applies the input to a server.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, server: &mut Server) -> Result<()> {
+        match self {
+            TapeInput::Wait => server.tick(),
+            TapeInput::Move(direction) => {
+                let _ = server.move_player(*direction)?;
+            }
+            TapeInput::TravelTo(xy) => {
+                let _ = server.travel_to(*xy);
+            }
+            TapeInput::TravelStep => {
+                let _ = server.travel_step()?;
+            }
+            TapeInput::UseCharges(name) => {
+                let _ = server.use_player_charges(name);
+            }
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Generates a tape of random waits and moves from a seed.
+    //---------------------------------------------------------------------------------------------
+    pub fn random_tape(seed: u64, len: usize) -> Vec<TapeInput> {
+        let mut rng = Rng::new(seed);
+
+        (0..len)
+            .map(|_| match rng.gen_range(0..=DIRECTIONS.len()) {
+                0 => TapeInput::Wait,
+                i => TapeInput::Move(DIRECTIONS[i - 1]),
+            })
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads a tape from a file of one input per line, skipping blank lines and # comments.
+    //---------------------------------------------------------------------------------------------
+    pub fn load_tape<P: AsRef<Path>>(path: P) -> Result<Vec<TapeInput>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tape file {:?}.", path))?;
+
+        contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| {
+                line.parse()
+                    .with_context(|| format!("Invalid input on line {} of {:?}.", i + 1, path))
+            })
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that parses a direction from its name (e.g. "north" or "ne").
+    //---------------------------------------------------------------------------------------------
+    fn parse_direction(name: &str) -> Result<Direction> {
+        let direction = match name {
+            "n" | "north" => NORTH_DIRECTION,
+            "ne" | "northeast" => NORTHEAST_DIRECTION,
+            "e" | "east" => EAST_DIRECTION,
+            "se" | "southeast" => SOUTHEAST_DIRECTION,
+            "s" | "south" => SOUTH_DIRECTION,
+            "sw" | "southwest" => SOUTHWEST_DIRECTION,
+            "w" | "west" => WEST_DIRECTION,
+            "nw" | "northwest" => NORTHWEST_DIRECTION,
+            _ => bail!("Unknown direction '{}'.", name),
+        };
+
+        Ok(direction)
+    }
+}
+
+impl FromStr for TapeInput {
+    type Err = Error;
+
+    //---------------------------------------------------------------------------------------------
+    // Parses a tape input, e.g. "wait", "move north", "travel_to 10 4", "travel_step" or
+    // "use_charges dash".
+    //---------------------------------------------------------------------------------------------
+    fn from_str(s: &str) -> Result<Self> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let coord = |word: &str| {
+            word.parse::<i32>().with_context(|| format!("Invalid coordinate '{}'.", word))
+        };
+
+        let input = match words.as_slice() {
+            [] => bail!("Empty tape input."),
+            ["wait"] => TapeInput::Wait,
+            ["move", direction] => TapeInput::Move(Self::parse_direction(direction)?),
+            ["travel_to", x, y] => TapeInput::TravelTo((coord(x)?, coord(y)?)),
+            ["travel_step"] => TapeInput::TravelStep,
+            ["use_charges", name] => TapeInput::UseCharges((*name).into()),
+            _ => bail!("Unknown tape input '{}'.", s.trim()),
+        };
+
+        Ok(input)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Divergence reports the first turn on which two servers in lockstep had different states.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    // Turn on which the states diverged (0 for the initial state).
+    pub turn: usize,
+    // Input applied on the turn, if any.
+    pub input: Option<TapeInput>,
+    // Entries that differed.
+    pub differences: Vec<StateDifference>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Display for Divergence, listing each differing entry of both states.
+//-------------------------------------------------------------------------------------------------
+impl fmt::Display for Divergence {
+    //---------------------------------------------------------------------------------------------
+    // Formats the divergence report.
+    //---------------------------------------------------------------------------------------------
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.input {
+            Some(input) => {
+                writeln!(f, "Servers diverged on turn {} after {:?}:", self.turn, input)?
+            }
+            None => writeln!(f, "Servers diverged before the first turn:")?,
+        }
+
+        for difference in self.differences.iter() {
+            writeln!(f, "  {}:", difference.key)?;
+            writeln!(f, "    left:  {}", difference.left.as_deref().unwrap_or("<missing>"))?;
+            writeln!(f, "    right: {}", difference.right.as_deref().unwrap_or("<missing>"))?;
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// LockstepHarness runs two servers created with the same seed through the same input tape,
+// comparing their states after every turn to find sources of nondeterminism (e.g. unordered
+// iteration or float differences) before they break saves and replays.
+//-------------------------------------------------------------------------------------------------
+pub struct LockstepHarness {
+    // The servers run in lockstep.
+    servers: (Server, Server),
+    // Number of turns run.
+    turn: usize,
+}
+
+impl LockstepHarness {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new lockstep harness with two servers created from a seed.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(seed: u64) -> Result<Self> {
+        Ok(Self { servers: (Server::with_seed(seed)?, Server::with_seed(seed)?), turn: 0 })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the servers run in lockstep, e.g. for setting up identical state in both.
+    //---------------------------------------------------------------------------------------------
+    pub fn servers_mut(&mut self) -> (&mut Server, &mut Server) {
+        (&mut self.servers.0, &mut self.servers.1)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of turns run.
+    //---------------------------------------------------------------------------------------------
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Compares the states of the servers, returning the divergence if they differ.
+    //---------------------------------------------------------------------------------------------
+    pub fn compare(&self, input: Option<&TapeInput>) -> Option<Divergence> {
+        let differences = self.servers.0.state().diff(&self.servers.1.state());
+
+        if differences.is_empty() {
+            None
+        } else {
+            Some(Divergence { turn: self.turn, input: input.cloned(), differences })
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies an input to both servers and compares their states.
+    //---------------------------------------------------------------------------------------------
+    pub fn step(&mut self, input: &TapeInput) -> Result<Option<Divergence>> {
+        input.apply(&mut self.servers.0)?;
+        input.apply(&mut self.servers.1)?;
+        self.turn += 1;
+
+        Ok(self.compare(Some(input)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs a tape of inputs, returning the first divergence (if any).
+    //---------------------------------------------------------------------------------------------
+    pub fn run(&mut self, tape: &[TapeInput]) -> Result<Option<Divergence>> {
+        if let Some(divergence) = self.compare(None) {
+            return Ok(Some(divergence));
+        }
+
+        for input in tape.iter() {
+            if let Some(divergence) = self.step(input)? {
+                return Ok(Some(divergence));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_lockstep_determinism() {
+    let mut harness = LockstepHarness::new(0xF7E).unwrap();
+
+    // Exercise the charges system alongside movement.
+    let (left, right) = harness.servers_mut();
+    left.set_player_charges("dash", Charges::cooldown(3)).unwrap();
+    right.set_player_charges("dash", Charges::cooldown(3)).unwrap();
+
+    // Exercise the decal system with blood the player can leave footprints through.
+    let xy = left.snapshot().player_xy();
+    left.splatter(xy, crate::decal::DecalKind::Blood, 1.0, 2);
+    right.splatter(xy, crate::decal::DecalKind::Blood, 1.0, 2);
+
+    let mut tape = TapeInput::random_tape(0xF7E, 24);
+    tape.insert(4, TapeInput::UseCharges("dash".into()));
+
+    if let Some(divergence) = harness.run(&tape).unwrap() {
+        panic!("{}", divergence);
+    }
+
+    assert_eq!(harness.turn(), tape.len());
+}
+
+#[test]
+fn test_parse_tape_input() {
+    assert_eq!("wait".parse::<TapeInput>().unwrap(), TapeInput::Wait);
+    assert_eq!("move ne".parse::<TapeInput>().unwrap(), TapeInput::Move(NORTHEAST_DIRECTION));
+    assert_eq!("travel_to 10 -4".parse::<TapeInput>().unwrap(), TapeInput::TravelTo((10, -4)));
+    assert_eq!(
+        " use_charges dash ".parse::<TapeInput>().unwrap(),
+        TapeInput::UseCharges("dash".into())
+    );
+    assert!("move up".parse::<TapeInput>().is_err());
+    assert!("travel_to 10".parse::<TapeInput>().is_err());
+    assert!("".parse::<TapeInput>().is_err());
+}
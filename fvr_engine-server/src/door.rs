@@ -0,0 +1,105 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::thing::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Multiplier of the cost of pathing through a closed door (opening it takes a turn).
+pub const DOOR_PATH_COST: f32 = 3.0;
+
+// Blackboard key of the door an actor opened (so that it can close the door behind itself).
+pub const OPENED_DOOR_KEY: &str = "door.opened";
+
+// Blackboard key of the door an actor is passing through.
+pub const PASSING_DOOR_KEY: &str = "door.passing";
+
+// Turns an actor remembers a door it opened but did not pass through.
+pub const OPENED_DOOR_MEMORY: u32 = 5;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// A closed door, blocking movement and sight.
+pub static CLOSED_DOOR_THING: Thing = Thing {
+    tile: Tile {
+        glyph: '+',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::Brown.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Blocked,
+    transparency: Transparency::Opaque,
+    door: Some(DoorState::Closed),
+    autotile: false,
+    animation: None,
+};
+
+// An open door.
+pub static OPEN_DOOR_THING: Thing = Thing {
+    tile: Tile {
+        glyph: '\'',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::Brown.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Passable,
+    transparency: Transparency::Transparent,
+    door: Some(DoorState::Open),
+    autotile: false,
+    animation: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the states of a door.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DoorState {
+    // The door can be walked and seen through.
+    Open,
+    // The door must be opened (by an actor capable of it) before it can be walked through.
+    Closed,
+}
+
+impl DoorState {
+    // All of the door states.
+    pub const ALL: [DoorState; 2] = [DoorState::Open, DoorState::Closed];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the thing representing a door in the state.
+    //---------------------------------------------------------------------------------------------
+    pub fn thing(&self) -> Thing {
+        match self {
+            DoorState::Open => OPEN_DOOR_THING,
+            DoorState::Closed => CLOSED_DOOR_THING,
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Energy cost of an unencumbered action. A turn passes for each action energy spent.
+pub const ACTION_ENERGY: u32 = 100;
+
+// Weight anyone can carry unencumbered, and the weight added per point of strength.
+const BASE_CARRYING_CAPACITY: u32 = 10;
+const CARRYING_CAPACITY_PER_STR: u32 = 2;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the markers of an actor's status, shown beside the actor's stats.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusMarker {
+    // Carrying more than the actor's capacity.
+    Burdened,
+    // Carrying more than one and a half times the actor's capacity.
+    Stressed,
+    // Carrying more than twice the actor's capacity.
+    Overloaded,
+}
+
+impl StatusMarker {
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable name of the marker.
+    //---------------------------------------------------------------------------------------------
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusMarker::Burdened => "Burdened",
+            StatusMarker::Stressed => "Stressed",
+            StatusMarker::Overloaded => "Overloaded",
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color the marker is shown in.
+    //---------------------------------------------------------------------------------------------
+    pub fn color(&self) -> PaletteColor {
+        match self {
+            StatusMarker::Burdened => PaletteColor::Gold,
+            StatusMarker::Stressed => PaletteColor::BrightOrange,
+            StatusMarker::Overloaded => PaletteColor::BrightRed,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the tiers of encumbrance, by the weight carried relative to the carrying capacity.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EncumbranceTier {
+    #[default]
+    Unburdened,
+    Burdened,
+    Stressed,
+    Overloaded,
+}
+
+impl EncumbranceTier {
+    //---------------------------------------------------------------------------------------------
+    // Returns the tier of carrying a weight with a carrying capacity.
+    //---------------------------------------------------------------------------------------------
+    pub fn of(weight: u32, capacity: u32) -> Self {
+        // Compare in halves of the capacity to avoid rounding.
+        let halves = weight * 2;
+
+        if halves <= capacity * 2 {
+            EncumbranceTier::Unburdened
+        } else if halves <= capacity * 3 {
+            EncumbranceTier::Burdened
+        } else if halves <= capacity * 4 {
+            EncumbranceTier::Stressed
+        } else {
+            EncumbranceTier::Overloaded
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the energy cost of an action taken in the tier.
+    //---------------------------------------------------------------------------------------------
+    pub fn energy_cost(&self) -> u32 {
+        match self {
+            EncumbranceTier::Unburdened => ACTION_ENERGY,
+            EncumbranceTier::Burdened => ACTION_ENERGY * 5 / 4,
+            EncumbranceTier::Stressed => ACTION_ENERGY * 3 / 2,
+            EncumbranceTier::Overloaded => ACTION_ENERGY * 2,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the status marker applied in the tier, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn marker(&self) -> Option<StatusMarker> {
+        match self {
+            EncumbranceTier::Unburdened => None,
+            EncumbranceTier::Burdened => Some(StatusMarker::Burdened),
+            EncumbranceTier::Stressed => Some(StatusMarker::Stressed),
+            EncumbranceTier::Overloaded => Some(StatusMarker::Overloaded),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The weight an actor carries, the weight it can carry and the resulting tier of encumbrance.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Encumbrance {
+    // Total weight carried.
+    pub weight: u32,
+    // Weight that can be carried unencumbered.
+    pub capacity: u32,
+    // Tier of encumbrance.
+    pub tier: EncumbranceTier,
+}
+
+impl Encumbrance {
+    //---------------------------------------------------------------------------------------------
+    // Returns the encumbrance of an actor with stats carrying a weight.
+    //---------------------------------------------------------------------------------------------
+    pub fn of(weight: u32, stats: &ActorStats) -> Self {
+        let capacity = BASE_CARRYING_CAPACITY + CARRYING_CAPACITY_PER_STR * stats.STR as u32;
+        Self { weight, capacity, tier: EncumbranceTier::of(weight, capacity) }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// EnergyDebt accumulates the energy an actor owes for actions costing more than a turn, so that
+// the cost of actions taken while encumbered is paid in whole extra turns.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnergyDebt {
+    // Energy owed beyond the turns already taken (always less than a turn's worth).
+    owed: u32,
+}
+
+impl EnergyDebt {
+    //---------------------------------------------------------------------------------------------
+    // Returns the energy owed.
+    //---------------------------------------------------------------------------------------------
+    pub fn owed(&self) -> u32 {
+        self.owed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Charges the energy cost of an action taken in a tier, which already took one turn. Returns
+    // the # of further turns that pass, one for each turn's worth of energy owed.
+    //---------------------------------------------------------------------------------------------
+    pub fn spend(&mut self, tier: EncumbranceTier) -> u32 {
+        self.owed += tier.energy_cost() - ACTION_ENERGY;
+
+        let turns = self.owed / ACTION_ENERGY;
+        self.owed %= ACTION_ENERGY;
+        turns
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_encumbrance_tiers() {
+    let tier = |weight| EncumbranceTier::of(weight, 10);
+
+    // Each tier includes its upper boundary.
+    assert_eq!(tier(0), EncumbranceTier::Unburdened);
+    assert_eq!(tier(10), EncumbranceTier::Unburdened);
+    assert_eq!(tier(11), EncumbranceTier::Burdened);
+    assert_eq!(tier(15), EncumbranceTier::Burdened);
+    assert_eq!(tier(16), EncumbranceTier::Stressed);
+    assert_eq!(tier(20), EncumbranceTier::Stressed);
+    assert_eq!(tier(21), EncumbranceTier::Overloaded);
+
+    // Half capacities are compared without rounding.
+    assert_eq!(EncumbranceTier::of(10, 7), EncumbranceTier::Burdened);
+    assert_eq!(EncumbranceTier::of(11, 7), EncumbranceTier::Stressed);
+
+    // The capacity grows with strength.
+    let stats = ActorStats { STR: 5, ..Default::default() };
+    let encumbrance = Encumbrance::of(20, &stats);
+    assert_eq!(encumbrance.capacity, 20);
+    assert_eq!(encumbrance.tier, EncumbranceTier::Unburdened);
+    assert_eq!(Encumbrance::of(21, &stats).tier, EncumbranceTier::Burdened);
+}
+
+#[test]
+fn test_energy_debt() {
+    let mut debt = EnergyDebt::default();
+
+    // Unburdened actions never owe energy.
+    assert_eq!(debt.spend(EncumbranceTier::Unburdened), 0);
+    assert_eq!(debt.owed(), 0);
+
+    // Burdened actions owe a quarter turn each, so every fourth takes an extra turn.
+    let extra: Vec<u32> = (0..8).map(|_| debt.spend(EncumbranceTier::Burdened)).collect();
+    assert_eq!(extra, vec![0, 0, 0, 1, 0, 0, 0, 1]);
+    assert_eq!(debt.owed(), 0);
+
+    // Owed energy carries over between tiers.
+    assert_eq!(debt.spend(EncumbranceTier::Stressed), 0);
+    assert_eq!(debt.spend(EncumbranceTier::Burdened), 0);
+    assert_eq!(debt.owed(), 75);
+    assert_eq!(debt.spend(EncumbranceTier::Overloaded), 1);
+    assert_eq!(debt.owed(), 75);
+}
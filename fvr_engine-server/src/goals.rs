@@ -1,8 +1,3 @@
-//-------------------------------------------------------------------------------------------------
-// Extern crate includes.
-//-------------------------------------------------------------------------------------------------
-use specs::prelude::*;
-
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
@@ -13,6 +8,8 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 use crate::actor::*;
 use crate::components::*;
+use crate::crowd::*;
+use crate::scent::*;
 use crate::zone::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -28,6 +25,51 @@ pub enum GoalState {
     InProgress,
 }
 
+//-------------------------------------------------------------------------------------------------
+// Describes how urgent a goal is. Higher priority goals preempt lower priority goals.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GoalPriority {
+    // Filler goals for when there is nothing else to do (e.g. idling).
+    Idle,
+    // Goals that are pursued at leisure (e.g. patrolling).
+    Low,
+    // Goals that are the actor's main concern (e.g. chasing).
+    Normal,
+    // Goals that override the actor's main concern (e.g. fleeing).
+    High,
+    // Goals that must be acted upon this turn (e.g. an attack of opportunity).
+    Urgent,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes what happens to a goal when a higher priority goal preempts it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GoalInterruption {
+    // The goal is kept and resumed once the preempting goals are done.
+    Suspend,
+    // The goal is dropped (e.g. a path that will be stale once the actor has moved elsewhere).
+    Abandon,
+    // The goal cannot be preempted while it is applicable.
+    Uninterruptible,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes the goal chosen by the arbitration step of a turn, for debug inspection.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GoalChoice {
+    // Name of the chosen goal.
+    pub name: &'static str,
+    // Priority of the chosen goal.
+    pub priority: GoalPriority,
+    // Name of the goal that was preempted by the chosen goal this turn, if any.
+    pub preempted: Option<&'static str>,
+    // Whether the preempted goal was dropped rather than suspended.
+    pub abandoned: bool,
+}
+
 //-------------------------------------------------------------------------------------------------
 // Goal describes a failable objective and is responsible for generating related tasks.
 //-------------------------------------------------------------------------------------------------
@@ -39,8 +81,109 @@ pub trait Goal {
         &mut self,
         actor: &mut Actor,
         zone: &mut Zone,
-        updater: &Read<LazyUpdate>,
+        moves: &mut MoveRequests,
     ) -> GoalState;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str;
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the priority of the goal.
+    //---------------------------------------------------------------------------------------------
+    fn priority(&self) -> GoalPriority {
+        GoalPriority::Normal
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns what happens to the goal when it is preempted.
+    //---------------------------------------------------------------------------------------------
+    fn interruption(&self) -> GoalInterruption {
+        GoalInterruption::Suspend
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the goal can be pursued this turn. Inapplicable goals are skipped by the
+    // arbitration step but kept (e.g. an attack of opportunity while the player is not adjacent).
+    //---------------------------------------------------------------------------------------------
+    fn is_applicable(&self, _actor: &Actor, _zone: &Zone) -> bool {
+        true
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Chooses the goal to pursue this turn, moving it to the top of the stack and returning a
+// description of the choice. The top of the stack is the goal pursued last turn.
+//
+// Rules:
+// - Only applicable goals are considered.
+// - The highest priority goal is chosen, with ties going to the goal highest in the stack (so
+//   that the current goal is kept, and the most recently pushed goal is preferred otherwise).
+// - An applicable uninterruptible current goal is kept regardless of priority, unless the chosen
+//   goal is urgent (e.g. an attack of opportunity interrupts even a committed path).
+// - A preempted current goal is either left in the stack to be resumed later or removed,
+//   depending on its interruption policy.
+//-------------------------------------------------------------------------------------------------
+pub fn arbitrate_goals(goals: &mut GoalStack, actor: &Actor, zone: &Zone) -> Option<GoalChoice> {
+    // Find the highest, and then top-most, applicable goal.
+    let mut best: Option<(usize, GoalPriority)> = None;
+
+    for (i, goal) in goals.iter().enumerate() {
+        if !goal.is_applicable(actor, zone) {
+            continue;
+        }
+
+        let priority = goal.priority();
+
+        if best.is_none_or(|(_, best_priority)| priority >= best_priority) {
+            best = Some((i, priority));
+        }
+    }
+
+    let (index, priority) = best?;
+    let top = goals.len() - 1;
+    let mut preempted = None;
+    let mut abandoned = false;
+
+    if index != top {
+        let current = &goals[top];
+
+        // Keep an uninterruptible current goal, unless the chosen goal is urgent.
+        if current.interruption() == GoalInterruption::Uninterruptible
+            && current.is_applicable(actor, zone)
+            && priority < GoalPriority::Urgent
+        {
+            return Some(choice(current.as_ref(), None, false));
+        }
+
+        // Only report a preemption when the current goal was applicable (as opposed to waiting).
+        if current.is_applicable(actor, zone) {
+            preempted = Some(current.name());
+        }
+
+        if current.interruption() == GoalInterruption::Abandon {
+            let _ = goals.pop();
+            abandoned = true;
+        }
+
+        // Move the chosen goal to the top of the stack.
+        let goal = goals.remove(index);
+        goals.push(goal);
+    }
+
+    goals.last().map(|goal| choice(goal.as_ref(), preempted, abandoned))
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that describes a chosen goal.
+//-------------------------------------------------------------------------------------------------
+fn choice(
+    goal: &(dyn Goal + Send + Sync),
+    preempted: Option<&'static str>,
+    abandoned: bool,
+) -> GoalChoice {
+    GoalChoice { name: goal.name(), priority: goal.priority(), preempted, abandoned }
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -56,7 +199,7 @@ impl Goal for AvoidPlayerGoal {
         &mut self,
         actor: &mut Actor,
         zone: &mut Zone,
-        updater: &Read<LazyUpdate>,
+        moves: &mut MoveRequests,
     ) -> GoalState {
         // Complete if the actor has been stationary for more than one turn.
         // if actor.navigation.stationary > 1 {
@@ -83,11 +226,85 @@ impl Goal for AvoidPlayerGoal {
         // }
 
         // Flag the actor for moving.
-        let component = WantsToMove { direction: dir, weight, priority: actor.stats.DEX };
-        updater.insert(actor.entity, component);
+        let component = WantsToMove {
+            direction: dir,
+            weight,
+            priority: actor.stats.DEX,
+            gradient: MoveGradient::Avoid,
+        };
+        moves.push((actor.entity, component));
 
         GoalState::InProgress
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "avoid player"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Fleeing preempts the actor's other concerns.
+    //---------------------------------------------------------------------------------------------
+    fn priority(&self) -> GoalPriority {
+        GoalPriority::High
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Keep away from the player while they are in sight (e.g. townsfolk who heard of their deeds).
+//-------------------------------------------------------------------------------------------------
+pub struct ShunPlayerGoal;
+
+impl Goal for ShunPlayerGoal {
+    //---------------------------------------------------------------------------------------------
+    // Updates the goal, returning the new state.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        actor: &mut Actor,
+        zone: &mut Zone,
+        moves: &mut MoveRequests,
+    ) -> GoalState {
+        // Complete once the player is out of sight.
+        if !zone.can_see_player(actor.xy) {
+            return GoalState::Complete;
+        }
+
+        // Otherwise, back away along the avoid map.
+        let (dir, weight) = match zone.avoid_map.best_direction(actor.xy) {
+            Some(best) => best,
+            None => {
+                actor.navigation.stationary += 1;
+                return GoalState::InProgress;
+            }
+        };
+
+        let component = WantsToMove {
+            direction: dir,
+            weight,
+            priority: actor.stats.DEX,
+            gradient: MoveGradient::Avoid,
+        };
+        moves.push((actor.entity, component));
+
+        GoalState::InProgress
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "shun player"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Shunning preempts the actor's other concerns.
+    //---------------------------------------------------------------------------------------------
+    fn priority(&self) -> GoalPriority {
+        GoalPriority::High
+    }
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -103,15 +320,20 @@ impl Goal for ChasePlayerGoal {
         &mut self,
         actor: &mut Actor,
         zone: &mut Zone,
-        updater: &Read<LazyUpdate>,
+        moves: &mut MoveRequests,
     ) -> GoalState {
         // Complete if the actor occupies a neighboring coord to the player.
         // if Adjacency::is_neighbor(actor.xy, zone.player_xy) {
         //     return GoalState::Complete;
         // }
 
-        // Get the best chase direction.
-        let best_dir = zone.chase_map.best_direction(actor.xy);
+        // Get the best chase direction (through doors, if the actor can open them).
+        let (map, gradient) = if actor.capabilities.open_doors {
+            (&zone.door_chase_map, MoveGradient::DoorChase)
+        } else {
+            (&zone.chase_map, MoveGradient::Chase)
+        };
+        let best_dir = map.best_direction(actor.xy);
 
         if best_dir.is_none() {
             actor.navigation.stationary += 1;
@@ -129,11 +351,135 @@ impl Goal for ChasePlayerGoal {
         // }
 
         // Flag the actor for moving.
-        let component = WantsToMove { direction: dir, weight, priority: actor.stats.DEX };
-        updater.insert(actor.entity, component);
+        let component =
+            WantsToMove { direction: dir, weight, priority: actor.stats.DEX, gradient };
+        moves.push((actor.entity, component));
+
+        GoalState::InProgress
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "chase player"
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Chase the player while in sight, and follow their scent trail once sight is lost. Fails when
+// the trail goes cold.
+//-------------------------------------------------------------------------------------------------
+pub struct TrackScentGoal {
+    // The kind of scent followed.
+    pub kind: ScentKind,
+}
+
+impl Goal for TrackScentGoal {
+    //---------------------------------------------------------------------------------------------
+    // Updates the goal, returning the new state.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        actor: &mut Actor,
+        zone: &mut Zone,
+        moves: &mut MoveRequests,
+    ) -> GoalState {
+        // Chase directly while the player is in sight.
+        let (map, gradient) = if actor.capabilities.open_doors {
+            (&zone.door_chase_map, MoveGradient::DoorChase)
+        } else {
+            (&zone.chase_map, MoveGradient::Chase)
+        };
+
+        let (direction, weight, gradient) = if zone.can_see_player(actor.xy) {
+            match map.best_direction(actor.xy) {
+                Some((dir, weight)) => (dir, weight, gradient),
+                None => {
+                    actor.navigation.stationary += 1;
+                    return GoalState::InProgress;
+                }
+            }
+        } else {
+            // Otherwise, follow the scent uphill.
+            let passable = |xy| zone.passable_for(xy, &actor.capabilities);
+
+            match zone.scent.layer(self.kind).strongest_direction(actor.xy, passable) {
+                Some((dir, strength)) => (dir, -strength, MoveGradient::None),
+                None => return GoalState::Failed,
+            }
+        };
+
+        // Flag the actor for moving.
+        let component = WantsToMove { direction, weight, priority: actor.stats.DEX, gradient };
+        moves.push((actor.entity, component));
 
         GoalState::InProgress
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "track scent"
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Strike at the player once as they pass by, interrupting whatever the actor was pursuing.
+// Monsters do not resolve melee yet, so the strike is a lunge into the player's cell.
+//-------------------------------------------------------------------------------------------------
+pub struct AttackOfOpportunityGoal;
+
+impl Goal for AttackOfOpportunityGoal {
+    //---------------------------------------------------------------------------------------------
+    // Updates the goal, returning the new state.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        actor: &mut Actor,
+        zone: &mut Zone,
+        moves: &mut MoveRequests,
+    ) -> GoalState {
+        // Lunge at the player, completing right away so that the strike is only taken once.
+        let component = WantsToMove {
+            direction: Direction::closest_direction(actor.xy, zone.player_xy),
+            weight: 0.0,
+            priority: actor.stats.DEX,
+            gradient: MoveGradient::None,
+        };
+        moves.push((actor.entity, component));
+
+        GoalState::Complete
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "attack of opportunity"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // The opening is gone by next turn, so the strike must be taken now.
+    //---------------------------------------------------------------------------------------------
+    fn priority(&self) -> GoalPriority {
+        GoalPriority::Urgent
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // A strike is stale once the player has moved on.
+    //---------------------------------------------------------------------------------------------
+    fn interruption(&self) -> GoalInterruption {
+        GoalInterruption::Abandon
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // The strike can only be taken while the player is adjacent.
+    //---------------------------------------------------------------------------------------------
+    fn is_applicable(&self, actor: &Actor, zone: &Zone) -> bool {
+        actor.xy != zone.player_xy && Adjacency::is_neighbor(actor.xy, zone.player_xy)
+    }
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -161,7 +507,7 @@ impl Goal for IdleGoal {
         &mut self,
         _actor: &mut Actor,
         _zone: &mut Zone,
-        _updater: &Read<LazyUpdate>,
+        _moves: &mut MoveRequests,
     ) -> GoalState {
         // Check if the goal is complete.
         if self.turns == 0 {
@@ -172,6 +518,75 @@ impl Goal for IdleGoal {
         self.turns -= 1;
         GoalState::InProgress
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "idle"
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Idling gives way to anything else.
+    //---------------------------------------------------------------------------------------------
+    fn priority(&self) -> GoalPriority {
+        GoalPriority::Idle
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Take a single step in a direction (e.g. as chosen by a script).
+//-------------------------------------------------------------------------------------------------
+pub struct StepGoal {
+    // The direction to step in.
+    direction: Direction,
+    // Whether the step has been requested.
+    stepped: bool,
+}
+
+impl StepGoal {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new step goal.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(direction: Direction) -> Self {
+        Self { direction, stepped: false }
+    }
+}
+
+impl Goal for StepGoal {
+    //---------------------------------------------------------------------------------------------
+    // Updates the goal, returning the new state.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        actor: &mut Actor,
+        _zone: &mut Zone,
+        moves: &mut MoveRequests,
+    ) -> GoalState {
+        // Complete once the step has been taken (or contested) last turn.
+        if self.stepped {
+            return GoalState::Complete;
+        }
+
+        // Flag the actor for moving.
+        let component = WantsToMove {
+            direction: self.direction,
+            weight: 0.0,
+            priority: actor.stats.DEX,
+            gradient: MoveGradient::None,
+        };
+        moves.push((actor.entity, component));
+        self.stepped = true;
+
+        GoalState::InProgress
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the name of the goal, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &'static str {
+        "step"
+    }
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -193,3 +608,145 @@ pub struct RoamGoal {
     // Boundary radius.
     pub radius: i32,
 }
+
+//-------------------------------------------------------------------------------------------------
+// Goal with fixed properties, for testing the arbitration step.
+//-------------------------------------------------------------------------------------------------
+#[cfg(test)]
+struct ProbeGoal {
+    name: &'static str,
+    priority: GoalPriority,
+    interruption: GoalInterruption,
+    applicable: bool,
+}
+
+#[cfg(test)]
+impl Goal for ProbeGoal {
+    fn update(&mut self, _: &mut Actor, _: &mut Zone, _: &mut MoveRequests) -> GoalState {
+        GoalState::InProgress
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn priority(&self) -> GoalPriority {
+        self.priority
+    }
+
+    fn interruption(&self) -> GoalInterruption {
+        self.interruption
+    }
+
+    fn is_applicable(&self, _: &Actor, _: &Zone) -> bool {
+        self.applicable
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that boxes a probe goal.
+//-------------------------------------------------------------------------------------------------
+#[cfg(test)]
+fn probe(
+    name: &'static str,
+    priority: GoalPriority,
+    interruption: GoalInterruption,
+    applicable: bool,
+) -> Box<dyn Goal + Send + Sync> {
+    Box::new(ProbeGoal { name, priority, interruption, applicable })
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that creates an open zone and a copy of its player actor.
+//-------------------------------------------------------------------------------------------------
+#[cfg(test)]
+fn probe_zone() -> (Zone, Actor) {
+    use specs::prelude::*;
+
+    let mut world = World::new();
+    world.register::<IsActor>();
+    world.register::<HasGoals>();
+    world.register::<EmitsScent>();
+
+    let zone =
+        Zone::from_cells(GridMap::new((16, 16)), &mut world, &mut Rng::new(0x60A1)).unwrap();
+    let actor = zone.actor_map.get_xy(zone.player_xy).as_ref().unwrap().lock().unwrap().clone();
+
+    (zone, actor)
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_arbitrate_goals_order() {
+    use GoalInterruption::*;
+    use GoalPriority::*;
+
+    let (zone, actor) = probe_zone();
+    let names = |goals: &GoalStack| goals.iter().map(|goal| goal.name()).collect::<Vec<_>>();
+
+    // No applicable goals means waiting.
+    let mut goals = vec![probe("wait", Normal, Suspend, false)];
+    assert_eq!(arbitrate_goals(&mut goals, &actor, &zone), None);
+
+    // The highest priority goal wins, suspending the current goal.
+    let mut goals = vec![probe("flee", High, Suspend, true), probe("patrol", Low, Suspend, true)];
+    let choice = arbitrate_goals(&mut goals, &actor, &zone).unwrap();
+    assert_eq!((choice.name, choice.preempted, choice.abandoned), ("flee", Some("patrol"), false));
+    assert_eq!(names(&goals), ["patrol", "flee"]);
+
+    // Ties go to the goal highest in the stack.
+    let mut goals = vec![probe("old", Normal, Suspend, true), probe("new", Normal, Suspend, true)];
+    assert_eq!(arbitrate_goals(&mut goals, &actor, &zone).unwrap().name, "new");
+
+    // Inapplicable goals are skipped but kept.
+    let mut goals =
+        vec![probe("chase", Normal, Suspend, true), probe("flee", High, Suspend, false)];
+    let choice = arbitrate_goals(&mut goals, &actor, &zone).unwrap();
+    assert_eq!((choice.name, choice.preempted), ("chase", None));
+    assert_eq!(names(&goals), ["flee", "chase"]);
+
+    // An uninterruptible current goal is kept against a higher priority goal...
+    let mut goals =
+        vec![probe("flee", High, Suspend, true), probe("path", Low, Uninterruptible, true)];
+    assert_eq!(arbitrate_goals(&mut goals, &actor, &zone).unwrap().name, "path");
+    assert_eq!(names(&goals), ["flee", "path"]);
+
+    // ...but not against an urgent one.
+    let mut goals =
+        vec![probe("strike", Urgent, Suspend, true), probe("path", Low, Uninterruptible, true)];
+    let choice = arbitrate_goals(&mut goals, &actor, &zone).unwrap();
+    assert_eq!((choice.name, choice.preempted), ("strike", Some("path")));
+    assert_eq!(names(&goals), ["path", "strike"]);
+
+    // A preempted goal that abandons is dropped.
+    let mut goals = vec![probe("flee", High, Suspend, true), probe("path", Normal, Abandon, true)];
+    let choice = arbitrate_goals(&mut goals, &actor, &zone).unwrap();
+    assert_eq!((choice.name, choice.preempted, choice.abandoned), ("flee", Some("path"), true));
+    assert_eq!(names(&goals), ["flee"]);
+}
+
+#[test]
+fn test_attack_of_opportunity() {
+    let (zone, mut actor) = probe_zone();
+    let attack = AttackOfOpportunityGoal;
+
+    // Only applicable while the player is adjacent.
+    assert!(!attack.is_applicable(&actor, &zone));
+    actor.xy = (zone.player_xy.0 + 3, zone.player_xy.1);
+    assert!(!attack.is_applicable(&actor, &zone));
+    actor.xy = (zone.player_xy.0 + 1, zone.player_xy.1 + 1);
+    assert!(attack.is_applicable(&actor, &zone));
+
+    // The strike interrupts a path the actor was committed to.
+    let mut goals = vec![
+        Box::new(TrackScentGoal { kind: ScentKind::Player }) as Box<dyn Goal + Send + Sync>,
+        probe("path", GoalPriority::Normal, GoalInterruption::Uninterruptible, true),
+        Box::new(attack),
+    ];
+    let choice = arbitrate_goals(&mut goals, &actor, &zone).unwrap();
+    assert_eq!(choice.name, "attack of opportunity");
+    assert_eq!(choice.priority, GoalPriority::Urgent);
+}
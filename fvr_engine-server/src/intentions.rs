@@ -1,9 +1,15 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::actor::*;
 use crate::components::*;
 use crate::goals::*;
+use crate::scent::*;
 use crate::zone::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -19,6 +25,27 @@ pub trait Intention {
     // Called when when the actor has no goals.
     //---------------------------------------------------------------------------------------------
     fn bored(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack);
+
+    //---------------------------------------------------------------------------------------------
+    // Called every turn before the goals are arbitrated, so that the intention can push goals in
+    // reaction to the zone (e.g. a flee goal when the player comes near).
+    //---------------------------------------------------------------------------------------------
+    fn react(&self, _actor: &mut Actor, _zone: &Zone, _goals: &mut GoalStack) {}
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper function that pushes an attack of opportunity while the player is adjacent (unless one is
+// already pending).
+//-------------------------------------------------------------------------------------------------
+fn push_attack_of_opportunity(actor: &Actor, zone: &Zone, goals: &mut GoalStack) {
+    let attack = AttackOfOpportunityGoal;
+
+    if !attack.is_applicable(actor, zone) || goals.iter().any(|goal| goal.name() == attack.name())
+    {
+        return;
+    }
+
+    goals.push(Box::new(attack));
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -53,4 +80,79 @@ impl Intention for BasicChasePlayerIntention {
         // actor.navigation.stationary = 0;
         goals.push(Box::new(ChasePlayerGoal {}));
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Strikes at the player when they come adjacent.
+    //---------------------------------------------------------------------------------------------
+    fn react(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        push_attack_of_opportunity(actor, zone, goals);
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A generic intention that tracks the player by sight and scent, waiting when the trail is cold.
+//-------------------------------------------------------------------------------------------------
+pub struct BasicTrackPlayerIntention;
+
+impl Intention for BasicTrackPlayerIntention {
+    //---------------------------------------------------------------------------------------------
+    // Called when when the actor has no goals.
+    //---------------------------------------------------------------------------------------------
+    fn bored(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        const WAIT_TURNS: i32 = 3;
+
+        // Reset the actor state and push a goal.
+        actor.navigation.weight = None;
+
+        let scented = zone.scent.layer(ScentKind::Player).strength(actor.xy) > 0.0
+            || Adjacency::EightWay
+                .neighbors(actor.xy)
+                .any(|xy| zone.scent.layer(ScentKind::Player).strength(xy) > 0.0);
+
+        if scented || zone.can_see_player(actor.xy) {
+            goals.push(Box::new(TrackScentGoal { kind: ScentKind::Player }));
+        } else {
+            goals.push(Box::new(IdleGoal::new(WAIT_TURNS)));
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Strikes at the player when they come adjacent.
+    //---------------------------------------------------------------------------------------------
+    fn react(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        push_attack_of_opportunity(actor, zone, goals);
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A generic intention for townsfolk, who idle about and shun the player once they resent them
+// (having witnessed or heard of their misdeeds).
+//-------------------------------------------------------------------------------------------------
+pub struct BasicVillagerIntention;
+
+impl Intention for BasicVillagerIntention {
+    //---------------------------------------------------------------------------------------------
+    // Called when when the actor has no goals.
+    //---------------------------------------------------------------------------------------------
+    fn bored(&self, actor: &mut Actor, _zone: &Zone, goals: &mut GoalStack) {
+        const WAIT_TURNS: i32 = 5;
+
+        // Reset the actor state and push a goal.
+        actor.navigation.weight = None;
+        goals.push(Box::new(IdleGoal::new(WAIT_TURNS)));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Shuns the player while in sight, if the actor resents them.
+    //---------------------------------------------------------------------------------------------
+    fn react(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        if !actor.knowledge.resents_player(actor.faction)
+            || !zone.can_see_player(actor.xy)
+            || goals.iter().any(|goal| goal.name() == "shun player")
+        {
+            return;
+        }
+
+        goals.push(Box::new(ShunPlayerGoal {}));
+    }
 }
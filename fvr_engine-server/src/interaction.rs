@@ -0,0 +1,176 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+use crate::door::*;
+use crate::item::*;
+use crate::rejection::*;
+use crate::thing::*;
+use crate::zone::*;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates what an actor can do with the things around them.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionVerb {
+    // Open a closed door (by moving into it).
+    OpenDoor,
+    // Close an open door.
+    CloseDoor,
+    // Pick up the items lying in the actor's cell.
+    PickUp,
+}
+
+impl InteractionVerb {
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable name of the verb.
+    //---------------------------------------------------------------------------------------------
+    pub fn name(&self) -> &'static str {
+        match self {
+            InteractionVerb::OpenDoor => "Open door",
+            InteractionVerb::CloseDoor => "Close door",
+            InteractionVerb::PickUp => "Pick up",
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates what an interaction acts upon.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub enum InteractionTarget {
+    // The topmost thing of a cell (e.g. a door).
+    Thing(Thing),
+    // The pile of items lying in a cell, by its # of items.
+    Items(u32),
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates whether an interaction can currently be carried out.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionValidity {
+    // The interaction can be carried out.
+    Valid,
+    // The interaction would be rejected, for a reason.
+    Invalid(RejectionReason),
+}
+
+impl InteractionValidity {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the interaction can be carried out.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_valid(&self) -> bool {
+        *self == InteractionValidity::Valid
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the intentions that carry out interactions, i.e. what the server is asked to do.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionIntention {
+    // Move (or bump) in a direction.
+    Move(Direction),
+    // Close the door at a coord.
+    CloseDoor(ICoord),
+    // Pick up a quantity of the items in the actor's cell that pass a filter.
+    PickUp(PickupFilter, ItemQuantity),
+}
+
+//-------------------------------------------------------------------------------------------------
+// Something an actor can do at or next to their position, and how to do it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Interaction {
+    // What the actor can do.
+    pub verb: InteractionVerb,
+    // Coord of the target of the interaction.
+    pub xy: ICoord,
+    // Direction of the target from the actor (none for the actor's own cell).
+    pub direction: Option<Direction>,
+    // What the interaction acts upon.
+    pub target: InteractionTarget,
+    // Whether the interaction can currently be carried out.
+    pub validity: InteractionValidity,
+    // The intention that carries out the interaction.
+    pub intention: InteractionIntention,
+}
+
+impl Interaction {
+    //---------------------------------------------------------------------------------------------
+    // Returns the interactions of an actor with its own cell and then the adjacent cells (in the
+    // order of DIRECTIONS), including those that are currently invalid.
+    //---------------------------------------------------------------------------------------------
+    pub fn around(actor: &Actor, zone: &Zone) -> Vec<Self> {
+        let mut interactions = Vec::new();
+        let cells = std::iter::once(None).chain(DIRECTIONS.iter().copied().map(Some));
+
+        for direction in cells {
+            let delta = direction.map_or((0, 0), |d| (d.dx(), d.dy()));
+            let xy = (actor.xy.0 + delta.0, actor.xy.1 + delta.1);
+
+            if !zone.cell_map.in_bounds(xy) {
+                continue;
+            }
+
+            // Items are only picked up from the actor's own cell.
+            let pile = zone.ground_items.get(xy);
+
+            if direction.is_none() && !pile.is_empty() {
+                interactions.push(Self {
+                    verb: InteractionVerb::PickUp,
+                    xy,
+                    direction,
+                    target: InteractionTarget::Items(pile.iter().map(|stack| stack.count).sum()),
+                    validity: InteractionValidity::Valid,
+                    intention: InteractionIntention::PickUp(PickupFilter::All, ItemQuantity::All),
+                });
+            }
+
+            let cell = zone.cell_map.get_xy(xy);
+            let thing = match cell.things.last() {
+                Some(thing) => *thing,
+                None => continue,
+            };
+
+            let (verb, validity, intention) = match (cell.door(), direction) {
+                // Closed doors are opened by moving into them.
+                (Some(DoorState::Closed), Some(direction)) => {
+                    let validity = if actor.capabilities.open_doors {
+                        InteractionValidity::Valid
+                    } else {
+                        InteractionValidity::Invalid(RejectionReason::Blocked)
+                    };
+                    (InteractionVerb::OpenDoor, validity, InteractionIntention::Move(direction))
+                }
+                // Doors cannot be closed on an actor (including the actor standing in one).
+                (Some(DoorState::Open), _) => {
+                    let validity = if zone.actor_map.get_xy(xy).is_some() {
+                        InteractionValidity::Invalid(RejectionReason::Occupied)
+                    } else {
+                        InteractionValidity::Valid
+                    };
+                    (InteractionVerb::CloseDoor, validity, InteractionIntention::CloseDoor(xy))
+                }
+                _ => continue,
+            };
+
+            interactions.push(Self {
+                verb,
+                xy,
+                direction,
+                target: InteractionTarget::Thing(thing),
+                validity,
+                intention,
+            });
+        }
+
+        interactions
+    }
+}
@@ -0,0 +1,771 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
+use std::mem;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+use crate::scripting::*;
+
+//-------------------------------------------------------------------------------------------------
+// Item events produced by the player's use of items, collected by the server after each tick.
+//-------------------------------------------------------------------------------------------------
+pub type ItemEvents = Vec<ItemEvent>;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+static HEALING_DRAUGHT: ItemDefinition = ItemDefinition {
+    name: "healing draught",
+    stack_key: "healing_draught",
+    weight: 1,
+    max_stack: 5,
+    category: ItemCategory::Consumable,
+    glyph: '!',
+    color: PaletteColor::BrightRed,
+    usage: ItemUsage::Consume,
+    range: 0,
+    radius: 0,
+    effect: ItemEffect::Heal(8),
+};
+
+static THROWING_KNIFE: ItemDefinition = ItemDefinition {
+    name: "throwing knife",
+    stack_key: "throwing_knife",
+    weight: 2,
+    max_stack: 10,
+    category: ItemCategory::Throwable,
+    glyph: '/',
+    color: PaletteColor::BrightGrey,
+    usage: ItemUsage::Throw,
+    range: 6,
+    radius: 0,
+    effect: ItemEffect::Damage(3),
+};
+
+static FIRE_FLASK: ItemDefinition = ItemDefinition {
+    name: "fire flask",
+    stack_key: "fire_flask",
+    weight: 3,
+    max_stack: 3,
+    category: ItemCategory::Throwable,
+    glyph: '!',
+    color: PaletteColor::BrightOrange,
+    usage: ItemUsage::Throw,
+    range: 5,
+    radius: 1,
+    effect: ItemEffect::Damage(4),
+};
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the categories of items, e.g. for the player's auto-pickup preferences.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    // Items used up on the user (e.g. draughts).
+    Consumable,
+    // Items thrown at a target (e.g. knives and flasks).
+    Throwable,
+}
+
+impl ItemCategory {
+    // All of the categories, in the order they are listed.
+    pub const ALL: [ItemCategory; 2] = [ItemCategory::Consumable, ItemCategory::Throwable];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the human readable (plural) name of the category.
+    //---------------------------------------------------------------------------------------------
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemCategory::Consumable => "Consumables",
+            ItemCategory::Throwable => "Throwables",
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether items of the category are picked up automatically under the settings.
+    //---------------------------------------------------------------------------------------------
+    pub fn auto_pickup(&self, settings: &AutoPickupSettings) -> bool {
+        settings.enabled
+            && match self {
+                ItemCategory::Consumable => settings.consumables,
+                ItemCategory::Throwable => settings.throwables,
+            }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates how an item is used.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemUsage {
+    // The item is used up on the user.
+    Consume,
+    // The item is thrown at a target coord.
+    Throw,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the effects of using an item, applied to each actor in its area.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemEffect {
+    // Restores hit points.
+    Heal(i32),
+    // Removes hit points.
+    Damage(i32),
+    // Runs the affect hook of the named script.
+    Script(&'static str),
+}
+
+impl ItemEffect {
+    //---------------------------------------------------------------------------------------------
+    // Applies the effect to an actor. Returns the change in hit points.
+    //---------------------------------------------------------------------------------------------
+    pub fn apply(&self, actor: &mut Actor) -> i32 {
+        let before = actor.health.current;
+
+        actor.health.current = match self {
+            ItemEffect::Heal(amount) => (actor.health.current + amount).min(actor.health.max),
+            ItemEffect::Damage(amount) => (actor.health.current - amount).max(0),
+            ItemEffect::Script(script) => return SCRIPTS.run_item_effect(script, actor),
+        };
+
+        actor.health.current - before
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Describes a kind of item: how it looks, how it is used and what it does.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct ItemDefinition {
+    // Name of the item, as shown to the player.
+    pub name: &'static str,
+    // Items whose kinds share a stack key merge into the same stacks.
+    pub stack_key: &'static str,
+    // Max # of items in a stack (1 for items that do not stack).
+    pub max_stack: u32,
+    // Weight of a single item, for encumbrance.
+    pub weight: u32,
+    // Category of the item.
+    pub category: ItemCategory,
+    // Glyph of the item (e.g. in the inventory and when thrown).
+    pub glyph: char,
+    // Color of the item's glyph.
+    pub color: PaletteColor,
+    // How the item is used.
+    pub usage: ItemUsage,
+    // Max distance of the target from the user (0 for items used on the user).
+    pub range: i32,
+    // Radius of the area affected around the target (0 for the target alone).
+    pub radius: i32,
+    // Effect on each actor in the area.
+    pub effect: ItemEffect,
+}
+
+impl ItemDefinition {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the item is used at a chosen target (rather than on the user).
+    //---------------------------------------------------------------------------------------------
+    pub fn targeted(&self) -> bool {
+        self.range > 0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a target coord is within range of the user's coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn in_range(&self, from: ICoord, to: ICoord) -> bool {
+        (to.0 - from.0).abs().max((to.1 - from.1).abs()) <= self.range
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coords of the area affected around a target coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn area(&self, target: ICoord) -> impl Iterator<Item = ICoord> {
+        let radius = self.radius;
+
+        (-radius..=radius)
+            .flat_map(move |dy| (-radius..=radius).map(move |dx| (target.0 + dx, target.1 + dy)))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the kinds of items.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    HealingDraught,
+    ThrowingKnife,
+    FireFlask,
+}
+
+impl ItemKind {
+    // All of the kinds, e.g. for validating their definitions.
+    pub const ALL: [ItemKind; 3] =
+        [ItemKind::HealingDraught, ItemKind::ThrowingKnife, ItemKind::FireFlask];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the definition of the kind.
+    //---------------------------------------------------------------------------------------------
+    pub fn definition(&self) -> &'static ItemDefinition {
+        match self {
+            ItemKind::HealingDraught => &HEALING_DRAUGHT,
+            ItemKind::ThrowingKnife => &THROWING_KNIFE,
+            ItemKind::FireFlask => &FIRE_FLASK,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the kind is picked up automatically under the settings.
+    //---------------------------------------------------------------------------------------------
+    pub fn auto_pickup(&self, settings: &AutoPickupSettings) -> bool {
+        let definition = self.definition();
+        definition.category.auto_pickup(settings) && !settings.excludes(definition.name)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates which of the items in a pile the player picks up.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupFilter {
+    // Every item.
+    All,
+    // The items of a category.
+    Category(ItemCategory),
+    // The items of a kind.
+    Kind(ItemKind),
+}
+
+impl PickupFilter {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether an item passes the filter.
+    //---------------------------------------------------------------------------------------------
+    pub fn matches(&self, item: ItemKind) -> bool {
+        match self {
+            PickupFilter::All => true,
+            PickupFilter::Category(category) => item.definition().category == *category,
+            PickupFilter::Kind(kind) => item == *kind,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates how many of the items of a stack (or of several) an intention applies to.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemQuantity {
+    // Every item.
+    All,
+    // At most a # of items.
+    Count(u32),
+}
+
+impl ItemQuantity {
+    //---------------------------------------------------------------------------------------------
+    // Returns how many of a # of available items the quantity applies to.
+    //---------------------------------------------------------------------------------------------
+    pub fn of(&self, available: u32) -> u32 {
+        match self {
+            ItemQuantity::All => available,
+            ItemQuantity::Count(count) => (*count).min(available),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A # of items of the same kind.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ItemStack {
+    // Kind of the items.
+    pub kind: ItemKind,
+    // # of items (at least 1, at most the kind's max stack).
+    pub count: u32,
+}
+
+impl ItemStack {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new stack of a single item.
+    //---------------------------------------------------------------------------------------------
+    pub fn one(kind: ItemKind) -> Self {
+        Self { kind, count: 1 }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the total weight of the stack's items.
+    //---------------------------------------------------------------------------------------------
+    pub fn weight(&self) -> u32 {
+        self.kind.definition().weight * self.count
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether items of a kind merge into the stack.
+    //---------------------------------------------------------------------------------------------
+    pub fn stacks_with(&self, kind: ItemKind) -> bool {
+        self.kind.definition().stack_key == kind.definition().stack_key
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ItemStacks holds items as stacks, merging the items added into the stacks they stack with (up
+// to the max stack of their kinds) and splitting the stacks items are taken from. Every holder of
+// items (the inventory, ground piles) stores its items through ItemStacks, so that items merge and
+// split the same way wherever they are moved.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemStacks {
+    // The stacks, in the order they were first added.
+    stacks: Vec<ItemStack>,
+}
+
+impl ItemStacks {
+    //---------------------------------------------------------------------------------------------
+    // Creates new, empty stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn stacks(&self) -> &[ItemStack] {
+        &self.stacks
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the stack at an index, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, index: usize) -> Option<ItemStack> {
+        self.stacks.get(index).copied()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no items.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the total # of items of a kind.
+    //---------------------------------------------------------------------------------------------
+    pub fn count_of(&self, kind: ItemKind) -> u32 {
+        self.stacks.iter().filter(|stack| stack.kind == kind).map(|stack| stack.count).sum()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds a stack of items, topping up the stacks they stack with before starting new stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn add(&mut self, stack: ItemStack) {
+        let max_stack = stack.kind.definition().max_stack.max(1);
+        let mut remaining = stack.count;
+
+        for existing in self.stacks.iter_mut().filter(|existing| existing.stacks_with(stack.kind))
+        {
+            let moved = remaining.min(max_stack.saturating_sub(existing.count));
+            existing.count += moved;
+            remaining -= moved;
+        }
+
+        while remaining > 0 {
+            let count = remaining.min(max_stack);
+            self.stacks.push(ItemStack { kind: stack.kind, count });
+            remaining -= count;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds several stacks of items (see add).
+    //---------------------------------------------------------------------------------------------
+    pub fn add_all<I>(&mut self, stacks: I)
+    where
+        I: IntoIterator<Item = ItemStack>,
+    {
+        for stack in stacks {
+            self.add(stack);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes and returns a quantity of the items of the stack at an index, splitting the stack if
+    // only some are taken. Returns none if there is no stack at the index or the quantity is 0.
+    //---------------------------------------------------------------------------------------------
+    pub fn take(&mut self, index: usize, quantity: ItemQuantity) -> Option<ItemStack> {
+        let stack = self.stacks.get_mut(index)?;
+        let count = quantity.of(stack.count);
+
+        if count == 0 {
+            return None;
+        }
+
+        stack.count -= count;
+        let taken = ItemStack { kind: stack.kind, count };
+
+        if stack.count == 0 {
+            let _ = self.stacks.remove(index);
+        }
+
+        Some(taken)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes and returns up to a quantity of the items whose kinds satisfy a predicate, taken
+    // from the stacks in order.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_where<F>(&mut self, mut predicate: F, quantity: ItemQuantity) -> Vec<ItemStack>
+    where
+        F: FnMut(ItemKind) -> bool,
+    {
+        let mut taken = Vec::new();
+        let mut remaining = quantity;
+        let mut index = 0;
+
+        while index < self.stacks.len() && remaining != ItemQuantity::Count(0) {
+            if !predicate(self.stacks[index].kind) {
+                index += 1;
+                continue;
+            }
+
+            let before = self.stacks.len();
+            let stack = self.take(index, remaining).expect("Item stack missing.");
+
+            if let ItemQuantity::Count(count) = &mut remaining {
+                *count -= stack.count;
+            }
+
+            // Only step past stacks that were split rather than emptied.
+            if self.stacks.len() == before {
+                index += 1;
+            }
+
+            taken.push(stack);
+        }
+
+        taken
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the changes to an inventory, e.g. for recomputing what depends on the items carried.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InventoryEvent {
+    // Items were added.
+    Added(ItemStack),
+    // Items were removed.
+    Removed(ItemStack),
+}
+
+//-------------------------------------------------------------------------------------------------
+// The items the player carries. Every change is recorded as an event, taken by the server to
+// recompute what depends on the items carried (e.g. encumbrance).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Inventory {
+    // The carried items.
+    stacks: ItemStacks,
+    // Changes since the events were last taken.
+    events: Vec<InventoryEvent>,
+}
+
+impl Inventory {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty inventory.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates the inventory the player starts with.
+    //---------------------------------------------------------------------------------------------
+    pub fn starting_kit() -> Self {
+        let mut inventory = Self::new();
+        inventory.add(ItemStack::one(ItemKind::HealingDraught));
+        inventory.add(ItemStack { kind: ItemKind::ThrowingKnife, count: 2 });
+        inventory.add(ItemStack::one(ItemKind::FireFlask));
+        inventory
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the carried stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn items(&self) -> &[ItemStack] {
+        self.stacks.stacks()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the stack at an index, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, index: usize) -> Option<ItemStack> {
+        self.stacks.get(index)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of carried stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no items are carried.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the total weight of the carried items.
+    //---------------------------------------------------------------------------------------------
+    pub fn weight(&self) -> u32 {
+        self.stacks.stacks().iter().map(|stack| stack.weight()).sum()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds a stack of items, merging it into the carried stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn add(&mut self, stack: ItemStack) {
+        self.stacks.add(stack);
+        self.events.push(InventoryEvent::Added(stack));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes and returns a quantity of the items of the stack at an index, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn remove(&mut self, index: usize, quantity: ItemQuantity) -> Option<ItemStack> {
+        let taken = self.stacks.take(index, quantity)?;
+        self.events.push(InventoryEvent::Removed(taken));
+        Some(taken)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the changes since the events were last taken.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_events(&mut self) -> Vec<InventoryEvent> {
+        mem::take(&mut self.events)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// GroundItems holds the piles of items lying in the cells of a zone.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroundItems {
+    // The pile of each coord with items.
+    piles: BTreeMap<ICoord, ItemStacks>,
+}
+
+impl GroundItems {
+    //---------------------------------------------------------------------------------------------
+    // Creates new, empty ground items.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the pile at a coord (empty if there are no items).
+    //---------------------------------------------------------------------------------------------
+    pub fn get(&self, xy: ICoord) -> &[ItemStack] {
+        self.piles.get(&xy).map_or(&[], |pile| pile.stacks())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the coords with items and their piles.
+    //---------------------------------------------------------------------------------------------
+    pub fn iter(&self) -> impl Iterator<Item = (ICoord, &[ItemStack])> {
+        self.piles.iter().map(|(xy, pile)| (*xy, pile.stacks()))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Drops a stack of items onto the pile at a coord, merging it into the pile's stacks.
+    //---------------------------------------------------------------------------------------------
+    pub fn add(&mut self, xy: ICoord, stack: ItemStack) {
+        self.piles.entry(xy).or_default().add(stack);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes and returns up to a quantity of the items of the pile at a coord whose kinds satisfy
+    // a predicate.
+    //---------------------------------------------------------------------------------------------
+    pub fn take<F>(&mut self, xy: ICoord, predicate: F, quantity: ItemQuantity) -> Vec<ItemStack>
+    where
+        F: FnMut(ItemKind) -> bool,
+    {
+        let pile = match self.piles.get_mut(&xy) {
+            Some(pile) => pile,
+            None => return Vec::new(),
+        };
+
+        let taken = pile.take_where(predicate, quantity);
+
+        if pile.is_empty() {
+            let _ = self.piles.remove(&xy);
+        }
+
+        taken
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Serializes the piles into a comparable string.
+    //---------------------------------------------------------------------------------------------
+    pub fn serialize(&self) -> String {
+        self.piles
+            .iter()
+            .map(|((x, y), pile)| format!("{},{}:{:?}", x, y, pile.stacks()))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// An actor affected by the use of an item.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemHit {
+    // Id of the affected entity.
+    pub entity: u32,
+    // Coord of the affected entity.
+    pub xy: ICoord,
+    // Change in the entity's hit points.
+    pub change: i32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Event emitted when the player uses an item, for the log and the client's animations.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemEvent {
+    // The item used.
+    pub item: ItemKind,
+    // Coord the item was used from.
+    pub from: ICoord,
+    // Coord the item was used at (the same as from for consumed items).
+    pub to: ICoord,
+    // The actors affected.
+    pub hits: Vec<ItemHit>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Event emitted when the player picks up items (automatically or not), summarizing the items
+// picked up and those left behind.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PickupEvent {
+    // Coord of the pile.
+    pub xy: ICoord,
+    // Whether the items were picked up automatically on entering the coord.
+    pub automatic: bool,
+    // The stacks picked up.
+    pub picked_up: Vec<ItemStack>,
+    // The stacks left in the pile.
+    pub left: Vec<ItemStack>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_item_stacks_add() {
+    let mut stacks = ItemStacks::new();
+
+    // Items of the same kind merge into one stack.
+    stacks.add(ItemStack { kind: ItemKind::HealingDraught, count: 2 });
+    stacks.add(ItemStack { kind: ItemKind::ThrowingKnife, count: 4 });
+    stacks.add(ItemStack { kind: ItemKind::HealingDraught, count: 2 });
+    assert_eq!(
+        stacks.stacks(),
+        &[
+            ItemStack { kind: ItemKind::HealingDraught, count: 4 },
+            ItemStack { kind: ItemKind::ThrowingKnife, count: 4 },
+        ]
+    );
+
+    // Items past the max stack top up the existing stack, then overflow into new full stacks.
+    stacks.add(ItemStack { kind: ItemKind::HealingDraught, count: 12 });
+    assert_eq!(stacks.len(), 5);
+    assert_eq!(stacks.get(0), Some(ItemStack { kind: ItemKind::HealingDraught, count: 5 }));
+    assert_eq!(stacks.get(2), Some(ItemStack { kind: ItemKind::HealingDraught, count: 5 }));
+    assert_eq!(stacks.get(3), Some(ItemStack { kind: ItemKind::HealingDraught, count: 5 }));
+    assert_eq!(stacks.get(4), Some(ItemStack { kind: ItemKind::HealingDraught, count: 1 }));
+    assert_eq!(stacks.count_of(ItemKind::HealingDraught), 16);
+}
+
+#[test]
+fn test_item_stacks_take() {
+    let mut stacks = ItemStacks::new();
+    stacks.add(ItemStack { kind: ItemKind::ThrowingKnife, count: 6 });
+    stacks.add(ItemStack::one(ItemKind::FireFlask));
+
+    // Taking some of a stack splits it.
+    assert_eq!(
+        stacks.take(0, ItemQuantity::Count(4)),
+        Some(ItemStack { kind: ItemKind::ThrowingKnife, count: 4 })
+    );
+    assert_eq!(stacks.get(0), Some(ItemStack { kind: ItemKind::ThrowingKnife, count: 2 }));
+
+    // Taking more than a stack holds takes the whole stack and removes it.
+    assert_eq!(
+        stacks.take(0, ItemQuantity::Count(9)),
+        Some(ItemStack { kind: ItemKind::ThrowingKnife, count: 2 })
+    );
+    assert_eq!(stacks.stacks(), &[ItemStack::one(ItemKind::FireFlask)]);
+
+    // Nothing is taken for a quantity of 0 or a missing stack.
+    assert_eq!(stacks.take(0, ItemQuantity::Count(0)), None);
+    assert_eq!(stacks.take(1, ItemQuantity::All), None);
+    assert_eq!(stacks.take(0, ItemQuantity::All), Some(ItemStack::one(ItemKind::FireFlask)));
+    assert!(stacks.is_empty());
+}
+
+#[test]
+fn test_item_stacks_take_where() {
+    let mut stacks = ItemStacks::new();
+    stacks.add(ItemStack { kind: ItemKind::HealingDraught, count: 7 });
+    stacks.add(ItemStack { kind: ItemKind::FireFlask, count: 2 });
+
+    // A quantity is taken across stacks in order, splitting the last stack taken from.
+    let taken = stacks.take_where(|kind| kind == ItemKind::HealingDraught, ItemQuantity::Count(6));
+    assert_eq!(
+        taken,
+        vec![
+            ItemStack { kind: ItemKind::HealingDraught, count: 5 },
+            ItemStack { kind: ItemKind::HealingDraught, count: 1 },
+        ]
+    );
+    assert_eq!(
+        stacks.stacks(),
+        &[
+            ItemStack { kind: ItemKind::HealingDraught, count: 1 },
+            ItemStack { kind: ItemKind::FireFlask, count: 2 },
+        ]
+    );
+
+    // Stacks not matching the predicate are skipped.
+    let taken = stacks.take_where(
+        |kind| kind.definition().category == ItemCategory::Throwable,
+        ItemQuantity::All,
+    );
+    assert_eq!(taken, vec![ItemStack { kind: ItemKind::FireFlask, count: 2 }]);
+    assert_eq!(stacks.stacks(), &[ItemStack::one(ItemKind::HealingDraught)]);
+}
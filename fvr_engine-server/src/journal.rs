@@ -0,0 +1,343 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::io::{Read, Write};
+use std::path::Path;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, bail, Context, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// First line of a serialized journal, identifying the format version.
+const JOURNAL_HEADER: &str = "journal 1";
+
+// Separates the fields of a serialized entry.
+const FIELD_SEPARATOR: &str = "\t";
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the states of a quest.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuestStatus {
+    // The quest has been started.
+    Active,
+    // The quest has been completed.
+    Completed,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A quest recorded in the journal.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuestEntry {
+    // Unique id of the quest.
+    pub id: String,
+    // Title of the quest (rich text).
+    pub title: String,
+    // Description of the quest (rich text).
+    pub description: String,
+    // Whether the quest is active or completed.
+    pub status: QuestStatus,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A kind of monster recorded in the journal's bestiary.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestiaryEntry {
+    // Name of the monster kind.
+    pub kind: String,
+    // Glyph of the monster kind.
+    pub glyph: char,
+    // # of monsters of the kind killed by the player.
+    pub kills: u32,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A lore text recorded in the journal.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoreEntry {
+    // Unique id of the lore.
+    pub id: String,
+    // Title of the lore (rich text).
+    pub title: String,
+    // Text of the lore (rich text).
+    pub text: String,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Journal records what the player has learned over a game: the quests started and completed, the
+// monster kinds seen (the bestiary) and the lore discovered, in the order they were recorded.
+// It outlives zones, so it is held by the server rather than the world.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Journal {
+    // Quests started.
+    quests: Vec<QuestEntry>,
+    // Monster kinds seen.
+    bestiary: Vec<BestiaryEntry>,
+    // Lore discovered.
+    lore: Vec<LoreEntry>,
+}
+
+impl Journal {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty journal.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the quests, in the order they were started.
+    //---------------------------------------------------------------------------------------------
+    pub fn quests(&self) -> &[QuestEntry] {
+        &self.quests
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the quests with a status, in the order they were started.
+    //---------------------------------------------------------------------------------------------
+    pub fn quests_with_status(&self, status: QuestStatus) -> impl Iterator<Item = &QuestEntry> {
+        self.quests.iter().filter(move |quest| quest.status == status)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Starts a quest. Returns false if a quest with the id was already started.
+    //---------------------------------------------------------------------------------------------
+    pub fn start_quest(&mut self, id: &str, title: &str, description: &str) -> bool {
+        if self.quests.iter().any(|quest| quest.id == id) {
+            return false;
+        }
+
+        self.quests.push(QuestEntry {
+            id: id.into(),
+            title: title.into(),
+            description: description.into(),
+            status: QuestStatus::Active,
+        });
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Completes an active quest. Returns whether the quest was active.
+    //---------------------------------------------------------------------------------------------
+    pub fn complete_quest(&mut self, id: &str) -> bool {
+        match self.quests.iter_mut().find(|quest| quest.id == id) {
+            Some(quest) if quest.status == QuestStatus::Active => {
+                quest.status = QuestStatus::Completed;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the monster kinds seen, in the order they were first seen.
+    //---------------------------------------------------------------------------------------------
+    pub fn bestiary(&self) -> &[BestiaryEntry] {
+        &self.bestiary
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the bestiary entry of a monster kind, adding it if unseen.
+    //---------------------------------------------------------------------------------------------
+    fn bestiary_entry(&mut self, kind: &str, glyph: char) -> (&mut BestiaryEntry, bool) {
+        match self.bestiary.iter().position(|entry| entry.kind == kind) {
+            Some(index) => (&mut self.bestiary[index], false),
+            None => {
+                self.bestiary.push(BestiaryEntry { kind: kind.into(), glyph, kills: 0 });
+                (self.bestiary.last_mut().expect("Unreachable."), true)
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records that a monster kind was seen. Returns whether it was seen for the first time.
+    //---------------------------------------------------------------------------------------------
+    pub fn see_monster(&mut self, kind: &str, glyph: char) -> bool {
+        self.bestiary_entry(kind, glyph).1
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records that the player killed a monster (its kind is seen, if it was not already).
+    //---------------------------------------------------------------------------------------------
+    pub fn record_kill(&mut self, kind: &str, glyph: char) {
+        self.bestiary_entry(kind, glyph).0.kills += 1;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the lore discovered, in the order it was discovered.
+    //---------------------------------------------------------------------------------------------
+    pub fn lore(&self) -> &[LoreEntry] {
+        &self.lore
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records discovered lore. Returns false if lore with the id was already discovered.
+    //---------------------------------------------------------------------------------------------
+    pub fn discover_lore(&mut self, id: &str, title: &str, text: &str) -> bool {
+        if self.lore.iter().any(|lore| lore.id == id) {
+            return false;
+        }
+
+        self.lore.push(LoreEntry { id: id.into(), title: title.into(), text: text.into() });
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that escapes a field, so that it contains no separators or newlines.
+    //---------------------------------------------------------------------------------------------
+    fn escape(field: &str) -> String {
+        field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that reverses escape.
+    //---------------------------------------------------------------------------------------------
+    fn unescape(field: &str) -> String {
+        let mut unescaped = String::with_capacity(field.len());
+        let mut chars = field.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('t') => unescaped.push('\t'),
+                Some('n') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            }
+        }
+
+        unescaped
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Serializes the journal to text, one entry per line.
+    //---------------------------------------------------------------------------------------------
+    pub fn serialize(&self) -> String {
+        let mut lines = vec![String::from(JOURNAL_HEADER)];
+        let join = |fields: &[&str]| {
+            fields
+                .iter()
+                .map(|field| Self::escape(field))
+                .collect::<Vec<_>>()
+                .join(FIELD_SEPARATOR)
+        };
+
+        for quest in self.quests.iter() {
+            let status = match quest.status {
+                QuestStatus::Active => "active",
+                QuestStatus::Completed => "completed",
+            };
+            lines.push(join(&["quest", &quest.id, status, &quest.title, &quest.description]));
+        }
+
+        for entry in self.bestiary.iter() {
+            let glyph = (entry.glyph as u32).to_string();
+            let kills = entry.kills.to_string();
+            lines.push(join(&["monster", &entry.kind, &glyph, &kills]));
+        }
+
+        for lore in self.lore.iter() {
+            lines.push(join(&["lore", &lore.id, &lore.title, &lore.text]));
+        }
+
+        lines.join("\n")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deserializes a journal from text created by serialize.
+    //---------------------------------------------------------------------------------------------
+    pub fn deserialize(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+
+        if lines.next() != Some(JOURNAL_HEADER) {
+            bail!("Missing or unsupported journal header.");
+        }
+
+        let mut journal = Self::new();
+
+        for (i, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+            let fields: Vec<String> = line.split(FIELD_SEPARATOR).map(Self::unescape).collect();
+            let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+
+            match fields.as_slice() {
+                ["quest", id, status, title, description] => {
+                    let status = match *status {
+                        "active" => QuestStatus::Active,
+                        "completed" => QuestStatus::Completed,
+                        _ => bail!("Invalid quest status {:?} on line {}.", status, i + 2),
+                    };
+                    journal.quests.push(QuestEntry {
+                        id: (*id).into(),
+                        title: (*title).into(),
+                        description: (*description).into(),
+                        status,
+                    });
+                }
+                ["monster", kind, glyph, kills] => {
+                    let glyph = glyph
+                        .parse::<u32>()
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| anyhow!("Invalid glyph on line {}.", i + 2))?;
+                    let kills = kills
+                        .parse::<u32>()
+                        .with_context(|| format!("Invalid kill count on line {}.", i + 2))?;
+                    journal.bestiary.push(BestiaryEntry { kind: (*kind).into(), glyph, kills });
+                }
+                ["lore", id, title, text] => {
+                    journal.lore.push(LoreEntry {
+                        id: (*id).into(),
+                        title: (*title).into(),
+                        text: (*text).into(),
+                    });
+                }
+                _ => bail!("Invalid journal entry on line {}.", i + 2),
+            }
+        }
+
+        Ok(journal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves the journal to a (compressed) file.
+    //---------------------------------------------------------------------------------------------
+    pub fn save<P>(&self, path: P, options: CompressionOptions) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        save_compressed(path, options, |writer| {
+            writer.write_all(self.serialize().as_bytes()).context("Failed to write journal.")
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads a journal from a (compressed) file.
+    //---------------------------------------------------------------------------------------------
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut text = String::new();
+        load_compressed(path)?.read_to_string(&mut text).context("Failed to read journal.")?;
+        Self::deserialize(&text)
+    }
+}
@@ -0,0 +1,378 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::{BTreeMap, HashSet};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Chebyshev distance within which friendly actors exchange what they know each turn.
+pub const GOSSIP_RANGE: i32 = 2;
+
+// Chebyshev distance within which actors witness deeds and sight enemies.
+pub const WITNESS_RANGE: i32 = 8;
+
+// Fraction of a fact's confidence kept each time it is retold.
+pub const GOSSIP_TRUST: f32 = 0.8;
+
+// Fraction of a fact's confidence kept each turn.
+pub const FACT_DECAY: f32 = 0.995;
+
+// Confidence below which a fact is forgotten (or not worth retelling).
+pub const MIN_FACT_CONFIDENCE: f32 = 0.1;
+
+// Bounds of a faction's standing with the player.
+pub const MIN_STANDING: i32 = -100;
+pub const MAX_STANDING: i32 = 100;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the factions actors belong to. Actors only gossip with members of their faction.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Faction {
+    // Unaffiliated actors (e.g. critters), which neither gossip nor hold a standing.
+    None,
+    // Townsfolk.
+    Town,
+    // Monsters of the wilds.
+    Wild,
+}
+
+impl Faction {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether members of the faction share what they know with members of another.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_friendly(&self, other: Faction) -> bool {
+        *self != Faction::None && *self == other
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether members of the faction consider members of another enemies.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_hostile(&self, other: Faction) -> bool {
+        matches!((self, other), (Faction::Town, Faction::Wild) | (Faction::Wild, Faction::Town))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the topics of facts, for querying knowledge regardless of the details.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FactTopic {
+    // The player stole something.
+    PlayerStole,
+    // The player attacked someone.
+    PlayerAttacked,
+    // The player helped someone.
+    PlayerHelped,
+    // An enemy was sighted somewhere.
+    EnemySighted,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the things that can be known.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum FactKind {
+    // The player stole an item (by name).
+    PlayerStole(String),
+    // The player attacked a member of a faction.
+    PlayerAttacked(Faction),
+    // The player helped a member of a faction.
+    PlayerHelped(Faction),
+    // An enemy (by entity id) was sighted.
+    EnemySighted(u32),
+}
+
+impl FactKind {
+    //---------------------------------------------------------------------------------------------
+    // Returns the topic of the fact.
+    //---------------------------------------------------------------------------------------------
+    pub fn topic(&self) -> FactTopic {
+        match self {
+            FactKind::PlayerStole(_) => FactTopic::PlayerStole,
+            FactKind::PlayerAttacked(_) => FactTopic::PlayerAttacked,
+            FactKind::PlayerHelped(_) => FactTopic::PlayerHelped,
+            FactKind::EnemySighted(_) => FactTopic::EnemySighted,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the change to a faction's standing with the player when the faction hears the fact.
+    //---------------------------------------------------------------------------------------------
+    pub fn standing_change(&self, faction: Faction) -> i32 {
+        match self {
+            FactKind::PlayerStole(_) => -10,
+            FactKind::PlayerAttacked(victim) if *victim == faction => -25,
+            FactKind::PlayerHelped(helped) if *helped == faction => 10,
+            _ => 0,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether only the latest version of the fact is worth knowing (e.g. where an enemy
+    // was last sighted), rather than each occurrence.
+    //---------------------------------------------------------------------------------------------
+    pub fn latest_only(&self) -> bool {
+        matches!(self, FactKind::EnemySighted(_))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A fact known by an actor.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fact {
+    // Unique id of the occurrence (retold facts keep the id, so that they are not heard twice).
+    pub id: u64,
+    // What is known.
+    pub kind: FactKind,
+    // Turn the fact occurred.
+    pub turn: u64,
+    // Where the fact occurred.
+    pub xy: ICoord,
+    // How much the fact is believed (1.0 when witnessed, lowered by retelling and time).
+    pub confidence: f32,
+    // # of times the fact was retold before reaching the knower (0 when witnessed).
+    pub hops: u8,
+}
+
+impl Fact {
+    //---------------------------------------------------------------------------------------------
+    // Returns the fact as retold by its knower, if it is still worth retelling.
+    //---------------------------------------------------------------------------------------------
+    pub fn retold(&self) -> Option<Fact> {
+        let confidence = self.confidence * GOSSIP_TRUST;
+
+        if confidence < MIN_FACT_CONFIDENCE {
+            return None;
+        }
+
+        Some(Fact { confidence, hops: self.hops.saturating_add(1), ..self.clone() })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Knowledge holds the facts an actor knows, whether witnessed or heard through gossip.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Knowledge {
+    // Known facts, in the order they were learned.
+    facts: Vec<Fact>,
+}
+
+impl Knowledge {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty knowledge.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Learns a fact. Returns whether the fact is news (not already known).
+    // A fact already known is believed as much as the more confident of its versions, and a
+    // latest-only fact replaces an older version of itself.
+    //---------------------------------------------------------------------------------------------
+    pub fn learn(&mut self, fact: Fact) -> bool {
+        if let Some(known) = self.facts.iter_mut().find(|known| known.id == fact.id) {
+            if fact.confidence > known.confidence {
+                known.confidence = fact.confidence;
+                known.hops = fact.hops;
+            }
+
+            return false;
+        }
+
+        if fact.kind.latest_only() {
+            if let Some(known) = self.facts.iter_mut().find(|known| known.kind == fact.kind) {
+                if fact.turn <= known.turn {
+                    return false;
+                }
+
+                *known = fact;
+                return true;
+            }
+        }
+
+        self.facts.push(fact);
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Lowers the confidence of every fact by a turn, forgetting those no longer believed.
+    // Returns the # of forgotten facts.
+    //---------------------------------------------------------------------------------------------
+    pub fn decay(&mut self) -> usize {
+        let len = self.facts.len();
+
+        for fact in self.facts.iter_mut() {
+            fact.confidence *= FACT_DECAY;
+        }
+
+        self.facts.retain(|fact| fact.confidence >= MIN_FACT_CONFIDENCE);
+        len - self.facts.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether any fact of a topic is known.
+    //---------------------------------------------------------------------------------------------
+    pub fn knows(&self, topic: FactTopic) -> bool {
+        self.facts.iter().any(|fact| fact.kind.topic() == topic)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the most recent fact of a topic, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn latest(&self, topic: FactTopic) -> Option<&Fact> {
+        self.facts.iter().filter(|fact| fact.kind.topic() == topic).max_by_key(|fact| fact.turn)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether any known fact would lower a faction's standing with the player.
+    //---------------------------------------------------------------------------------------------
+    pub fn resents_player(&self, faction: Faction) -> bool {
+        self.facts.iter().any(|fact| fact.kind.standing_change(faction) < 0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the known facts, in the order they were learned.
+    //---------------------------------------------------------------------------------------------
+    pub fn facts(&self) -> &[Fact] {
+        &self.facts
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of known facts.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.facts.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether no facts are known.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.facts.is_empty()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// FactionStandings holds each faction's standing with the player, which changes as the faction's
+// members hear of the player's deeds. Inserted into the world as a resource.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct FactionStandings {
+    // Standing by faction (0 when absent).
+    standings: BTreeMap<Faction, i32>,
+    // Facts (by id) each faction has heard of, so that a deed only counts once per faction.
+    heard: HashSet<(Faction, u64)>,
+    // Id of the next recorded fact.
+    next_fact_id: u64,
+}
+
+impl FactionStandings {
+    //---------------------------------------------------------------------------------------------
+    // Creates new, neutral standings.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records a new, witnessed fact.
+    //---------------------------------------------------------------------------------------------
+    pub fn record(&mut self, kind: FactKind, turn: u64, xy: ICoord) -> Fact {
+        let id = self.next_fact_id;
+        self.next_fact_id += 1;
+
+        Fact { id, kind, turn, xy, confidence: 1.0, hops: 0 }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a faction's standing with the player.
+    //---------------------------------------------------------------------------------------------
+    pub fn standing(&self, faction: Faction) -> i32 {
+        self.standings.get(&faction).copied().unwrap_or(0)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Lets a faction hear of a fact, changing its standing with the player the first time.
+    // Returns whether the standing changed.
+    //---------------------------------------------------------------------------------------------
+    pub fn hear(&mut self, faction: Faction, fact: &Fact) -> bool {
+        let change = fact.kind.standing_change(faction);
+
+        if faction == Faction::None || change == 0 || !self.heard.insert((faction, fact.id)) {
+            return false;
+        }
+
+        let standing = self.standings.entry(faction).or_insert(0);
+        *standing = (*standing + change).clamp(MIN_STANDING, MAX_STANDING);
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the factions and their standings, ordered by faction.
+    //---------------------------------------------------------------------------------------------
+    pub fn iter(&self) -> impl Iterator<Item = (Faction, i32)> + '_ {
+        self.standings.iter().map(|(faction, standing)| (*faction, *standing))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the conditions dialogue can be gated behind, evaluated for the speaking actor.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueCondition {
+    // Always holds.
+    Always,
+    // The speaker knows a fact of the topic.
+    Knows(FactTopic),
+    // The speaker's faction's standing with the player is at least the value.
+    StandingAtLeast(i32),
+    // The speaker's faction's standing with the player is below the value.
+    StandingBelow(i32),
+    // All of the conditions hold.
+    All(Vec<DialogueCondition>),
+    // Any of the conditions hold.
+    Any(Vec<DialogueCondition>),
+    // The condition does not hold.
+    Not(Box<DialogueCondition>),
+}
+
+impl DialogueCondition {
+    //---------------------------------------------------------------------------------------------
+    // Evaluates the condition for a speaking actor.
+    //---------------------------------------------------------------------------------------------
+    pub fn evaluate(&self, speaker: &Actor, standings: &FactionStandings) -> bool {
+        match self {
+            DialogueCondition::Always => true,
+            DialogueCondition::Knows(topic) => speaker.knowledge.knows(*topic),
+            DialogueCondition::StandingAtLeast(value) => {
+                standings.standing(speaker.faction) >= *value
+            }
+            DialogueCondition::StandingBelow(value) => {
+                standings.standing(speaker.faction) < *value
+            }
+            DialogueCondition::All(conditions) => {
+                conditions.iter().all(|condition| condition.evaluate(speaker, standings))
+            }
+            DialogueCondition::Any(conditions) => {
+                conditions.iter().any(|condition| condition.evaluate(speaker, standings))
+            }
+            DialogueCondition::Not(condition) => !condition.evaluate(speaker, standings),
+        }
+    }
+}
@@ -1,23 +1,69 @@
 mod actor;
 mod behavior;
+mod blackboard;
+mod bump;
 mod cell;
+mod combat;
 mod components;
+mod content;
+mod crowd;
+mod decal;
+mod decoration;
+mod determinism;
+mod door;
+mod encumbrance;
 mod goals;
 mod intentions;
+mod interaction;
+mod item;
+mod journal;
+mod knowledge;
+mod rejection;
+mod scent;
+mod scripting;
 mod server;
+mod server_stats;
+mod simulation;
+mod snapshot;
 mod systems;
 mod thing;
+mod travel;
 mod zone;
+mod zone_gen;
+mod zone_graph;
 
 pub mod prelude {
     pub use crate::actor::*;
     pub use crate::behavior::*;
+    pub use crate::blackboard::*;
+    pub use crate::bump::*;
     pub use crate::cell::*;
+    pub use crate::combat::*;
     pub use crate::components::*;
+    pub use crate::content::*;
+    pub use crate::crowd::*;
+    pub use crate::decal::*;
+    pub use crate::decoration::*;
+    pub use crate::determinism::*;
+    pub use crate::door::*;
+    pub use crate::encumbrance::*;
     pub use crate::goals::*;
     pub use crate::intentions::*;
+    pub use crate::interaction::*;
+    pub use crate::item::*;
+    pub use crate::journal::*;
+    pub use crate::knowledge::*;
+    pub use crate::rejection::*;
+    pub use crate::scent::*;
+    pub use crate::scripting::*;
     pub use crate::server::*;
+    pub use crate::server_stats::*;
+    pub use crate::simulation::*;
+    pub use crate::snapshot::*;
     pub use crate::systems::*;
     pub use crate::thing::*;
+    pub use crate::travel::*;
     pub use crate::zone::*;
+    pub use crate::zone_gen::*;
+    pub use crate::zone_graph::*;
 }
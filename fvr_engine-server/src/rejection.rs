@@ -0,0 +1,82 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Alias for convenience.
+// Rejections of the player's intentions during resolution, inserted into the world as a resource.
+//-------------------------------------------------------------------------------------------------
+pub type Rejections = Vec<Rejection>;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the reasons an intention of the player can be rejected.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectionReason {
+    // The target coord is outside of the zone.
+    OutOfBounds,
+    // The target coord is impassable terrain.
+    Blocked,
+    // The target coord is occupied by an actor.
+    Occupied,
+    // The target is beyond the range of the action.
+    OutOfRange,
+    // The action does not have enough charges (e.g. it is cooling down).
+    Recharging,
+    // The world is paused (e.g. by a debug command).
+    Paused,
+    // There is nothing at the target coord to act on.
+    NoTarget,
+}
+
+impl RejectionReason {
+    // All of the reasons, e.g. for validating their messages.
+    pub const ALL: [RejectionReason; 7] = [
+        RejectionReason::OutOfBounds,
+        RejectionReason::Blocked,
+        RejectionReason::Occupied,
+        RejectionReason::OutOfRange,
+        RejectionReason::Recharging,
+        RejectionReason::Paused,
+        RejectionReason::NoTarget,
+    ];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the rich text message describing the reason to the player.
+    //---------------------------------------------------------------------------------------------
+    pub fn message(&self) -> &'static str {
+        match self {
+            RejectionReason::OutOfBounds => "<fc:y>You cannot go that way.",
+            RejectionReason::Blocked => "<fc:y>Something blocks your way.",
+            RejectionReason::Occupied => "<fc:y>Someone is in your way.",
+            RejectionReason::OutOfRange => "<fc:y>That is out of range.",
+            RejectionReason::Recharging => "<fc:y>That is not ready yet.",
+            RejectionReason::Paused => "<fc:y>The world is paused.",
+            RejectionReason::NoTarget => "<fc:y>There is nothing to do there.",
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Rejection describes why an intention of the player was not carried out, so that the client can
+// give feedback (e.g. flash the log or play a bump animation).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection {
+    // Why the intention was rejected.
+    pub reason: RejectionReason,
+    // Coord the intention targeted.
+    pub xy: ICoord,
+    // Rich text message describing the rejection to the player.
+    pub message: String,
+}
+
+impl Rejection {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new rejection with the default message for the reason.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(reason: RejectionReason, xy: ICoord) -> Self {
+        Self { reason, xy, message: reason.message().into() }
+    }
+}
@@ -0,0 +1,238 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::{prelude::*, xy_tuple_iter};
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Strength below which scent is considered gone.
+pub const MIN_SCENT_STRENGTH: f32 = 0.01;
+
+// Strength of the scent the player leaves each turn.
+pub const PLAYER_SCENT_STRENGTH: f32 = 1.0;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the kinds of scent, each stored in its own layer.
+//-------------------------------------------------------------------------------------------------
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScentKind {
+    // Left by the player wherever they go.
+    Player,
+    // Left by wounded actors.
+    Blood,
+}
+
+impl ScentKind {
+    // All of the scent kinds, in layer order.
+    pub const ALL: [ScentKind; 2] = [ScentKind::Player, ScentKind::Blood];
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the kind's layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the fraction of the kind's strength kept each turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn decay(&self) -> f32 {
+        match self {
+            ScentKind::Player => 0.9,
+            ScentKind::Blood => 0.97,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the fraction of the kind's strength spread to the neighboring coords each turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn diffusion(&self) -> f32 {
+        match self {
+            ScentKind::Player => 0.2,
+            ScentKind::Blood => 0.05,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ScentLayer is a decaying, diffusing map of the strength of one kind of scent.
+//-------------------------------------------------------------------------------------------------
+pub struct ScentLayer {
+    // Strength of the scent at each coord.
+    strengths: GridMap<f32>,
+    // Strengths of the previous turn, used while spreading.
+    previous: GridMap<f32>,
+    // Fraction of the strength kept each turn.
+    decay: f32,
+    // Fraction of the strength spread to the neighboring coords each turn.
+    diffusion: f32,
+}
+
+impl ScentLayer {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, scentless layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord, decay: f32, diffusion: f32) -> Self {
+        Self {
+            strengths: GridMap::new(dimensions),
+            previous: GridMap::new(dimensions),
+            decay: decay.clamp(0.0, 1.0),
+            diffusion: diffusion.clamp(0.0, 1.0),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the strengths of the layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn strengths(&self) -> &GridMap<f32> {
+        &self.strengths
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the strength of the scent at a coord (0.0 out of bounds).
+    //---------------------------------------------------------------------------------------------
+    pub fn strength(&self, xy: ICoord) -> f32 {
+        if self.strengths.in_bounds(xy) {
+            *self.strengths.get_xy(xy)
+        } else {
+            0.0
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deposits scent at a coord, keeping the stronger of the existing and deposited strengths.
+    //---------------------------------------------------------------------------------------------
+    pub fn deposit(&mut self, xy: ICoord, strength: f32) {
+        if self.strengths.in_bounds(xy) {
+            let existing = self.strengths.get_xy_mut(xy);
+            *existing = existing.max(strength);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Removes all scent from the layer.
+    //---------------------------------------------------------------------------------------------
+    pub fn clear(&mut self) {
+        self.strengths.data_mut().fill(0.0);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Spreads and decays the scent by one turn. Scent only spreads between passable coords.
+    //---------------------------------------------------------------------------------------------
+    pub fn spread<F>(&mut self, passable: F)
+    where
+        F: Fn(ICoord) -> bool,
+    {
+        std::mem::swap(&mut self.strengths, &mut self.previous);
+        let dimensions = self.previous.dimensions();
+
+        xy_tuple_iter!(x, y, dimensions, {
+            let strength = *self.previous.get_xy((x, y));
+
+            // Average the scent of the passable neighbors.
+            let mut total = 0.0;
+            let mut count = 0;
+
+            if passable((x, y)) {
+                for dir in Adjacency::EightWay.iter() {
+                    let xy = (x + dir.dx(), y + dir.dy());
+
+                    if self.previous.in_bounds(xy) && passable(xy) {
+                        total += *self.previous.get_xy(xy);
+                        count += 1;
+                    }
+                }
+            }
+
+            let spread = if count > 0 {
+                strength * (1.0 - self.diffusion) + self.diffusion * total / count as f32
+            } else {
+                strength
+            };
+            let decayed = spread * self.decay;
+
+            *self.strengths.get_xy_mut((x, y)) =
+                if decayed < MIN_SCENT_STRENGTH { 0.0 } else { decayed };
+        });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the direction of the strongest scent around a coord, if stronger than at the coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn strongest_direction<F>(&self, xy: ICoord, passable: F) -> Option<(Direction, f32)>
+    where
+        F: Fn(ICoord) -> bool,
+    {
+        let mut best: Option<(Direction, f32)> = None;
+        let mut best_strength = self.strength(xy).max(MIN_SCENT_STRENGTH);
+
+        for dir in Adjacency::EightWay.iter() {
+            let neighbor = (xy.0 + dir.dx(), xy.1 + dir.dy());
+            let strength = self.strength(neighbor);
+
+            if strength > best_strength && passable(neighbor) {
+                best_strength = strength;
+                best = Some((*dir, strength));
+            }
+        }
+
+        best
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ScentMaps holds a scent layer for each kind of scent in a zone.
+//-------------------------------------------------------------------------------------------------
+pub struct ScentMaps {
+    // Layers by kind index.
+    layers: Vec<ScentLayer>,
+}
+
+impl ScentMaps {
+    //---------------------------------------------------------------------------------------------
+    // Creates new, scentless layers for each kind.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(dimensions: ICoord) -> Self {
+        let layers = ScentKind::ALL
+            .iter()
+            .map(|kind| ScentLayer::new(dimensions, kind.decay(), kind.diffusion()))
+            .collect();
+
+        Self { layers }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the layer of a kind of scent.
+    //---------------------------------------------------------------------------------------------
+    pub fn layer(&self, kind: ScentKind) -> &ScentLayer {
+        &self.layers[kind.index()]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the mutable layer of a kind of scent.
+    //---------------------------------------------------------------------------------------------
+    pub fn layer_mut(&mut self, kind: ScentKind) -> &mut ScentLayer {
+        &mut self.layers[kind.index()]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Deposits a kind of scent at a coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn deposit(&mut self, kind: ScentKind, xy: ICoord, strength: f32) {
+        self.layer_mut(kind).deposit(xy, strength);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Spreads and decays every layer by one turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn spread<F>(&mut self, passable: F)
+    where
+        F: Fn(ICoord) -> bool,
+    {
+        for layer in self.layers.iter_mut() {
+            layer.spread(&passable);
+        }
+    }
+}
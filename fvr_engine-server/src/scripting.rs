@@ -0,0 +1,836 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, ImmutableString, Scope, AST, FLOAT, INT};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+use crate::blackboard::*;
+use crate::components::*;
+use crate::door::*;
+use crate::goals::*;
+use crate::intentions::*;
+use crate::scent::*;
+use crate::zone::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Extension of the script files.
+const SCRIPT_EXTENSION: &str = "rhai";
+
+// Min time between checks of the script files for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+// Hooks a script can define:
+// - fn bored(actor, zone) / fn react(actor, zone): the scripted intention of the same name.
+// - fn affect(actor): an item effect, applied to each actor in the item's area.
+// - fn on_turn(zone): a zone event, run at the end of every turn.
+pub const BORED_HOOK: &str = "bored";
+pub const REACT_HOOK: &str = "react";
+pub const AFFECT_HOOK: &str = "affect";
+pub const ON_TURN_HOOK: &str = "on_turn";
+
+// Limits of the engine, so that a runaway script cannot stall or exhaust the server.
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 4096;
+const MAX_ARRAY_SIZE: usize = 1024;
+const MAX_MAP_SIZE: usize = 256;
+
+// Chebyshev radius of the zone around an actor visible to its intention scripts.
+const SCRIPT_VIEW_RADIUS: i32 = 12;
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+pub static SCRIPTS: Lazy<Scripts> = Lazy::new(Scripts::load);
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the goals a script can push onto an actor's goal stack.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptGoal {
+    // Avoid the player.
+    AvoidPlayer,
+    // Chase the player.
+    ChasePlayer,
+    // Track the player by sight and scent.
+    TrackPlayer,
+    // Keep away from the player while in sight.
+    ShunPlayer,
+    // Idle for a # of turns.
+    Idle(i32),
+    // Take a single step in a direction.
+    Step(Direction),
+}
+
+impl ScriptGoal {
+    //---------------------------------------------------------------------------------------------
+    // Creates the goal.
+    //---------------------------------------------------------------------------------------------
+    fn create(self) -> Box<dyn Goal + Send + Sync> {
+        match self {
+            ScriptGoal::AvoidPlayer => Box::new(AvoidPlayerGoal {}),
+            ScriptGoal::ChasePlayer => Box::new(ChasePlayerGoal {}),
+            ScriptGoal::TrackPlayer => Box::new(TrackScentGoal { kind: ScentKind::Player }),
+            ScriptGoal::ShunPlayer => Box::new(ShunPlayerGoal {}),
+            ScriptGoal::Idle(turns) => Box::new(IdleGoal::new(turns)),
+            ScriptGoal::Step(direction) => Box::new(StepGoal::new(direction)),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the commands returned by script hooks. Scripts only ever see copies of the game
+// state, and the server applies the commands they return, so that scripts cannot break the
+// invariants of the zone or its actors.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptCommand {
+    // Pushes a goal onto the actor's goal stack.
+    Goal(ScriptGoal),
+    // Writes a value to the actor's blackboard.
+    Remember(String, BlackboardValue),
+    // Removes an entry from the actor's blackboard.
+    Forget(String),
+    // Restores hit points of the actor.
+    Heal(i32),
+    // Removes hit points of the actor.
+    Damage(i32),
+    // Places a wall at an unoccupied coord.
+    PlaceWall(ICoord),
+    // Places a closed door at an unoccupied coord.
+    PlaceDoor(ICoord),
+    // Opens or closes the door at a coord.
+    SetDoor(ICoord, DoorState),
+}
+
+impl ScriptCommand {
+    //---------------------------------------------------------------------------------------------
+    // Applies the command to an actor and its goal stack (if the hook can change goals).
+    // Returns an error for commands that modify the zone.
+    //---------------------------------------------------------------------------------------------
+    fn apply_to_actor(&self, actor: &mut Actor, goals: Option<&mut GoalStack>) -> Result<()> {
+        match self {
+            ScriptCommand::Goal(goal) => match goals {
+                Some(goals) => goals.push(goal.create()),
+                None => return Err(anyhow!("Goals can only be pushed by intention hooks.")),
+            },
+            ScriptCommand::Remember(key, value) => actor.blackboard.set_value(key, value.clone()),
+            ScriptCommand::Forget(key) => {
+                let _ = actor.blackboard.remove(key);
+            }
+            ScriptCommand::Heal(amount) => {
+                actor.health.current =
+                    (actor.health.current + (*amount).max(0)).min(actor.health.max)
+            }
+            ScriptCommand::Damage(amount) => {
+                actor.health.current = (actor.health.current - (*amount).max(0)).max(0)
+            }
+            _ => return Err(anyhow!("{:?} can only be applied by zone hooks.", self)),
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies the command to a zone. Returns an error for commands that target an actor.
+    //---------------------------------------------------------------------------------------------
+    fn apply_to_zone(&self, zone: &mut Zone) -> Result<()> {
+        let unoccupied =
+            |zone: &Zone, xy| zone.cell_map.in_bounds(xy) && zone.actor_map.get_xy(xy).is_none();
+
+        match self {
+            ScriptCommand::PlaceWall(xy) if unoccupied(zone, *xy) => zone.place_wall(*xy),
+            ScriptCommand::PlaceDoor(xy) if unoccupied(zone, *xy) => zone.place_door(*xy),
+            ScriptCommand::SetDoor(xy, state) => {
+                let _ = zone.set_door(*xy, *state);
+            }
+            ScriptCommand::PlaceWall(_) | ScriptCommand::PlaceDoor(_) => {}
+            _ => return Err(anyhow!("{:?} can only be applied by actor hooks.", self)),
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ScriptActor is the read-only copy of an actor passed to scripts.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ScriptActor {
+    // Id of the actor's entity.
+    pub id: INT,
+    // Name of the actor's kind.
+    pub kind: String,
+    // Name of the actor's faction.
+    pub faction: String,
+    // Position of the actor.
+    pub xy: ICoord,
+    // Current and max hit points.
+    pub health: ActorHealth,
+    // Copy of the actor's blackboard.
+    pub blackboard: Blackboard,
+}
+
+impl ScriptActor {
+    //---------------------------------------------------------------------------------------------
+    // Copies an actor.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(actor: &Actor) -> Self {
+        Self {
+            id: actor.entity.id() as INT,
+            kind: actor.kind.to_string(),
+            faction: format!("{:?}", actor.faction).to_lowercase(),
+            xy: actor.xy,
+            health: actor.health,
+            blackboard: actor.blackboard.clone(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a blackboard value of the actor as a script value (unit if absent).
+    //---------------------------------------------------------------------------------------------
+    fn get(&self, key: &str) -> Dynamic {
+        match self.blackboard.value(key) {
+            Some(BlackboardValue::Bool(value)) => Dynamic::from(*value),
+            Some(BlackboardValue::Int(value)) => Dynamic::from(*value as INT),
+            Some(BlackboardValue::Float(value)) => Dynamic::from(*value as FLOAT),
+            Some(BlackboardValue::Coord((x, y))) => {
+                Dynamic::from(vec![Dynamic::from(*x as INT), Dynamic::from(*y as INT)])
+            }
+            Some(BlackboardValue::Entity(value)) => Dynamic::from(*value as INT),
+            Some(BlackboardValue::Text(value)) => Dynamic::from(value.clone()),
+            None => Dynamic::UNIT,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ScriptZone is the read-only copy of (a window of) a zone passed to scripts.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ScriptZone {
+    // Dimensions of the zone.
+    pub dimensions: ICoord,
+    // Position of the player.
+    pub player_xy: ICoord,
+    // The zone's turn.
+    pub turn: u64,
+    // Top left coord and dimensions of the copied window.
+    origin: ICoord,
+    size: ICoord,
+    // Whether each coord of the window is blocked (by terrain or an actor).
+    blocked: Vec<bool>,
+    // Whether each coord of the window is in sight of the player.
+    visible: Vec<bool>,
+    // Copies of the actors in the window (other than any actor being updated).
+    actors: Vec<ScriptActor>,
+}
+
+impl ScriptZone {
+    //---------------------------------------------------------------------------------------------
+    // Copies the window of a zone within a chebyshev radius of a coord.
+    // (actors locked by the caller are skipped)
+    //---------------------------------------------------------------------------------------------
+    pub fn new(zone: &Zone, center: ICoord, radius: i32) -> Self {
+        let origin = ((center.0 - radius).max(0), (center.1 - radius).max(0));
+        let end = (
+            (center.0 + radius + 1).min(zone.dimensions.0),
+            (center.1 + radius + 1).min(zone.dimensions.1),
+        );
+        let size = ((end.0 - origin.0).max(0), (end.1 - origin.1).max(0));
+        let capacity = (size.0 * size.1) as usize;
+
+        let mut view = Self {
+            dimensions: zone.dimensions,
+            player_xy: zone.player_xy,
+            turn: zone.turn,
+            origin,
+            size,
+            blocked: Vec::with_capacity(capacity),
+            visible: Vec::with_capacity(capacity),
+            actors: Vec::new(),
+        };
+
+        for y in origin.1..end.1 {
+            for x in origin.0..end.0 {
+                view.blocked.push(zone.is_blocked((x, y)));
+                view.visible.push(zone.can_see_player((x, y)));
+
+                if let Some(shared) = zone.actor_map.get_xy((x, y)) {
+                    if let Ok(actor) = shared.try_lock() {
+                        view.actors.push(ScriptActor::new(&actor));
+                    }
+                }
+            }
+        }
+
+        view
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies the whole of a zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn whole(zone: &Zone) -> Self {
+        let radius = zone.dimensions.0.max(zone.dimensions.1);
+        Self::new(zone, (0, 0), radius)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the index of a coord in the window, if it is in the window.
+    //---------------------------------------------------------------------------------------------
+    fn index(&self, (x, y): ICoord) -> Option<usize> {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+
+        if dx < 0 || dy < 0 || dx >= self.size.0 || dy >= self.size.1 {
+            return None;
+        }
+
+        Some((dy * self.size.0 + dx) as usize)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a coord is blocked (coords outside of the window are).
+    //---------------------------------------------------------------------------------------------
+    pub fn is_blocked(&self, xy: ICoord) -> bool {
+        self.index(xy).is_none_or(|i| self.blocked[i])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the player is in sight from a coord (never from outside of the window).
+    //---------------------------------------------------------------------------------------------
+    pub fn can_see_player(&self, xy: ICoord) -> bool {
+        self.index(xy).is_some_and(|i| self.visible[i])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns copies of the actors within a chebyshev distance of a coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn actors_near(&self, (x, y): ICoord, distance: i32) -> Vec<ScriptActor> {
+        self.actors
+            .iter()
+            .filter(|actor| (actor.xy.0 - x).abs().max((actor.xy.1 - y).abs()) <= distance)
+            .cloned()
+            .collect()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A compiled script and the hooks it defines.
+//-------------------------------------------------------------------------------------------------
+struct Script {
+    // Path the script was loaded from.
+    path: PathBuf,
+    // Modification time of the file when it was loaded.
+    modified: Option<SystemTime>,
+    // The compiled script.
+    ast: AST,
+    // Names of the hooks the script defines.
+    hooks: Vec<String>,
+    // Whether a call of the script has failed since it was loaded (so that errors that repeat
+    // every turn are only logged once).
+    failed: AtomicBool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Scripts loads the script files of the scripts dir (across every content layer) and runs their
+// hooks in a sandboxed engine: scripts cannot access the filesystem, are limited in the work they
+// can do per call, and only see copies of the game state. In debug builds, changed script files
+// are reloaded while the game is running.
+//-------------------------------------------------------------------------------------------------
+pub struct Scripts {
+    // The engine, with the script API registered.
+    engine: Engine,
+    // Compiled scripts by name (the file stem).
+    scripts: RwLock<BTreeMap<String, Script>>,
+    // Errors found while loading the scripts, by script name.
+    errors: Vec<(String, String)>,
+    // Files that failed to compile and their modification times, so that they are only retried
+    // once they change again.
+    failed: Mutex<BTreeMap<PathBuf, Option<SystemTime>>>,
+    // When the script files were last checked for changes.
+    last_reload: Mutex<Option<Instant>>,
+}
+
+impl Scripts {
+    //---------------------------------------------------------------------------------------------
+    // Creates the engine and compiles the scripts of the scripts dir.
+    //---------------------------------------------------------------------------------------------
+    pub fn load() -> Self {
+        let mut scripts = BTreeMap::new();
+        let mut errors = Vec::new();
+        let mut failed = BTreeMap::new();
+        let engine = Self::create_engine();
+
+        match Self::script_files() {
+            Ok(files) => {
+                for (name, path) in files {
+                    match Self::compile(&engine, &path) {
+                        Ok(script) => {
+                            let _ = scripts.insert(name, script);
+                        }
+                        Err(e) => {
+                            errors.push((name, format!("{:#}", e)));
+                            let _ = failed.insert(path.clone(), Self::modified(&path));
+                        }
+                    }
+                }
+            }
+            Err(e) => errors.push((CONFIG_SCRIPTS_DIR.to_string(), format!("{:#}", e))),
+        }
+
+        Self {
+            engine,
+            scripts: RwLock::new(scripts),
+            errors,
+            failed: Mutex::new(failed),
+            last_reload: Mutex::new(None),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that creates a sandboxed engine with the script API registered.
+    //---------------------------------------------------------------------------------------------
+    fn create_engine() -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_ARRAY_SIZE)
+            .set_max_map_size(MAX_MAP_SIZE);
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.on_print(|text| println!("[Scripts] {}", text));
+
+        // Actors.
+        engine
+            .register_type_with_name::<ScriptActor>("Actor")
+            .register_get("id", |actor: &mut ScriptActor| actor.id)
+            .register_get("kind", |actor: &mut ScriptActor| actor.kind.clone())
+            .register_get("faction", |actor: &mut ScriptActor| actor.faction.clone())
+            .register_get("x", |actor: &mut ScriptActor| actor.xy.0 as INT)
+            .register_get("y", |actor: &mut ScriptActor| actor.xy.1 as INT)
+            .register_get("health", |actor: &mut ScriptActor| actor.health.current as INT)
+            .register_get("max_health", |actor: &mut ScriptActor| actor.health.max as INT)
+            .register_fn("get", |actor: &mut ScriptActor, key: ImmutableString| {
+                actor.get(key.as_str())
+            })
+            .register_fn("has", |actor: &mut ScriptActor, key: ImmutableString| {
+                actor.blackboard.contains(key.as_str())
+            });
+
+        // Zones.
+        engine
+            .register_type_with_name::<ScriptZone>("Zone")
+            .register_get("width", |zone: &mut ScriptZone| zone.dimensions.0 as INT)
+            .register_get("height", |zone: &mut ScriptZone| zone.dimensions.1 as INT)
+            .register_get("player_x", |zone: &mut ScriptZone| zone.player_xy.0 as INT)
+            .register_get("player_y", |zone: &mut ScriptZone| zone.player_xy.1 as INT)
+            .register_get("turn", |zone: &mut ScriptZone| zone.turn as INT)
+            .register_fn("is_blocked", |zone: &mut ScriptZone, x: INT, y: INT| {
+                zone.is_blocked((x as i32, y as i32))
+            })
+            .register_fn("can_see_player", |zone: &mut ScriptZone, x: INT, y: INT| {
+                zone.can_see_player((x as i32, y as i32))
+            })
+            .register_fn("actors_near", |zone: &mut ScriptZone, x: INT, y: INT, distance: INT| {
+                zone.actors_near((x as i32, y as i32), distance as i32)
+                    .into_iter()
+                    .map(Dynamic::from)
+                    .collect::<Array>()
+            });
+
+        // Commands.
+        use ScriptCommand::Goal;
+        engine
+            .register_type_with_name::<ScriptCommand>("Command")
+            .register_fn("avoid_player", || Goal(ScriptGoal::AvoidPlayer))
+            .register_fn("chase_player", || Goal(ScriptGoal::ChasePlayer))
+            .register_fn("track_player", || Goal(ScriptGoal::TrackPlayer))
+            .register_fn("shun_player", || Goal(ScriptGoal::ShunPlayer))
+            .register_fn("idle", |turns: INT| Goal(ScriptGoal::Idle(turns.max(0) as i32)))
+            .register_fn("step", |dx: INT, dy: INT| {
+                let delta = (dx.signum() as i32, dy.signum() as i32);
+                let direction = DIRECTIONS.iter().find(|dir| dir.delta() == delta);
+                Goal(ScriptGoal::Step(*direction.unwrap_or(&NULL_DIRECTION)))
+            })
+            .register_fn("remember", |key: ImmutableString, value: bool| {
+                ScriptCommand::Remember(key.to_string(), BlackboardValue::Bool(value))
+            })
+            .register_fn("remember", |key: ImmutableString, value: INT| {
+                ScriptCommand::Remember(key.to_string(), BlackboardValue::Int(value as i32))
+            })
+            .register_fn("remember", |key: ImmutableString, value: FLOAT| {
+                ScriptCommand::Remember(key.to_string(), BlackboardValue::Float(value as f32))
+            })
+            .register_fn("remember", |key: ImmutableString, value: ImmutableString| {
+                ScriptCommand::Remember(key.to_string(), BlackboardValue::Text(value.to_string()))
+            })
+            .register_fn("forget", |key: ImmutableString| ScriptCommand::Forget(key.to_string()))
+            .register_fn("heal", |amount: INT| ScriptCommand::Heal(amount as i32))
+            .register_fn("damage", |amount: INT| ScriptCommand::Damage(amount as i32))
+            .register_fn("place_wall", |x: INT, y: INT| {
+                ScriptCommand::PlaceWall((x as i32, y as i32))
+            })
+            .register_fn("place_door", |x: INT, y: INT| {
+                ScriptCommand::PlaceDoor((x as i32, y as i32))
+            })
+            .register_fn("open_door", |x: INT, y: INT| {
+                ScriptCommand::SetDoor((x as i32, y as i32), DoorState::Open)
+            })
+            .register_fn("close_door", |x: INT, y: INT| {
+                ScriptCommand::SetDoor((x as i32, y as i32), DoorState::Closed)
+            });
+
+        engine
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the script files merged across the content layers, by name.
+    //---------------------------------------------------------------------------------------------
+    fn script_files() -> Result<Vec<(String, PathBuf)>> {
+        let files = CONTENT_LAYERS.merged_dir(CONFIG_SCRIPTS_DIR)?;
+
+        Ok(files
+            .into_iter()
+            .filter(|(_, path)| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .filter_map(|(_, path)| {
+                let name = path.file_stem()?.to_string_lossy().into_owned();
+                Some((name, path))
+            })
+            .collect())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the modification time of a file, if available.
+    //---------------------------------------------------------------------------------------------
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that compiles a script file.
+    //---------------------------------------------------------------------------------------------
+    fn compile(engine: &Engine, path: &Path) -> Result<Script> {
+        let modified = Self::modified(path);
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}.", path.display()))?;
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| anyhow!("Failed to compile script {}: {}", path.display(), e))?;
+        let hooks = ast.iter_functions().map(|function| function.name.to_string()).collect();
+
+        Ok(Script {
+            path: path.to_path_buf(),
+            modified,
+            ast,
+            hooks,
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the errors found while loading the scripts, as (script name, error) pairs.
+    //---------------------------------------------------------------------------------------------
+    pub fn errors(&self) -> &[(String, String)] {
+        &self.errors
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the names of the scripts that define a hook, ordered by name.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_hook(&self, hook: &str) -> Vec<String> {
+        let scripts = self.scripts.read().expect("Failed to lock scripts.");
+
+        scripts
+            .iter()
+            .filter(|(_, script)| script.hooks.iter().any(|name| name == hook))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a script defines a hook.
+    //---------------------------------------------------------------------------------------------
+    pub fn has_hook(&self, name: &str, hook: &str) -> bool {
+        let scripts = self.scripts.read().expect("Failed to lock scripts.");
+        scripts.get(name).is_some_and(|script| script.hooks.iter().any(|h| h == hook))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Recompiles the scripts whose files changed since they were loaded, and loads new scripts.
+    // The files are checked at most once per reload interval, and are compiled outside of the
+    // lock so that hooks only wait for the scripts to be swapped in. Files that fail to compile
+    // keep the previous version of their script and are not retried until they change again.
+    // Returns the names of the scripts that were (re)loaded.
+    //---------------------------------------------------------------------------------------------
+    pub fn reload_changed(&self) -> Vec<String> {
+        {
+            let mut last_reload = self.last_reload.lock().expect("Failed to lock reload time.");
+
+            if last_reload.is_some_and(|last| last.elapsed() < RELOAD_INTERVAL) {
+                return Vec::new();
+            }
+
+            *last_reload = Some(Instant::now());
+        }
+
+        let files = match Self::script_files() {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("[Scripts] Failed to list scripts: {:#}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut failed = self.failed.lock().expect("Failed to lock failed scripts.");
+        let changed: Vec<(String, PathBuf, Option<SystemTime>)> = {
+            let scripts = self.scripts.read().expect("Failed to lock scripts.");
+
+            files
+                .into_iter()
+                .filter_map(|(name, path)| {
+                    let modified = Self::modified(&path);
+                    let loaded = scripts
+                        .get(&name)
+                        .is_some_and(|script| script.path == path && script.modified == modified);
+                    let broken = failed.get(&path).is_some_and(|failed| *failed == modified);
+
+                    if loaded || broken {
+                        None
+                    } else {
+                        Some((name, path, modified))
+                    }
+                })
+                .collect()
+        };
+
+        let mut reloaded = Vec::new();
+
+        for (name, path, modified) in changed {
+            match Self::compile(&self.engine, &path) {
+                Ok(script) => {
+                    println!("[Scripts] Reloaded {}.", path.display());
+                    let _ = failed.remove(&path);
+                    let _ = self
+                        .scripts
+                        .write()
+                        .expect("Failed to lock scripts.")
+                        .insert(name.clone(), script);
+                    reloaded.push(name);
+                }
+                Err(e) => {
+                    eprintln!("[Scripts] {:#}", e);
+                    let _ = failed.insert(path, modified);
+                }
+            }
+        }
+
+        reloaded
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that calls a hook of a script, returning the commands it returned (none if
+    // the script does not define the hook). Errors are logged once per load of the script.
+    //---------------------------------------------------------------------------------------------
+    fn call<A>(&self, name: &str, hook: &str, args: A) -> Vec<ScriptCommand>
+    where
+        A: rhai::FuncArgs,
+    {
+        let scripts = self.scripts.read().expect("Failed to lock scripts.");
+
+        let script = match scripts.get(name) {
+            Some(script) if script.hooks.iter().any(|h| h == hook) => script,
+            _ => return Vec::new(),
+        };
+
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &script.ast, hook, args)
+            .map_err(|e| anyhow!("{}", e))
+            .and_then(Self::commands);
+
+        match result {
+            Ok(commands) => commands,
+            Err(e) => {
+                Self::report(script, hook, e);
+                Vec::new()
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that converts the value returned by a hook (a command, an array of commands
+    // or nothing) into commands.
+    //---------------------------------------------------------------------------------------------
+    fn commands(value: Dynamic) -> Result<Vec<ScriptCommand>> {
+        if value.is::<()>() {
+            return Ok(Vec::new());
+        }
+
+        if value.is::<ScriptCommand>() {
+            return Ok(vec![value.cast::<ScriptCommand>()]);
+        }
+
+        let type_name = value.type_name();
+        let values = match value.try_cast::<Array>() {
+            Some(values) => values,
+            None => return Err(anyhow!("Hooks must return commands, not {}.", type_name)),
+        };
+
+        values
+            .into_iter()
+            .map(|value| {
+                let type_name = value.type_name();
+                value
+                    .try_cast::<ScriptCommand>()
+                    .ok_or_else(|| anyhow!("Hooks must return commands, not {}.", type_name))
+            })
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that logs an error of a script, once per load of the script.
+    //---------------------------------------------------------------------------------------------
+    fn report(script: &Script, hook: &str, error: anyhow::Error) {
+        if !script.failed.swap(true, Ordering::Relaxed) {
+            eprintln!("[Scripts] {} ({}): {:#}", script.path.display(), hook, error);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that applies the commands of a hook to an actor, reporting errors.
+    //---------------------------------------------------------------------------------------------
+    fn apply_to_actor(
+        &self,
+        name: &str,
+        hook: &str,
+        commands: Vec<ScriptCommand>,
+        actor: &mut Actor,
+        mut goals: Option<&mut GoalStack>,
+    ) {
+        for command in commands {
+            if let Err(e) = command.apply_to_actor(actor, goals.as_deref_mut()) {
+                let scripts = self.scripts.read().expect("Failed to lock scripts.");
+
+                if let Some(script) = scripts.get(name) {
+                    Self::report(script, hook, e);
+                }
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs an intention hook of a script for an actor, pushing the goals it returns.
+    //---------------------------------------------------------------------------------------------
+    pub fn run_intention(
+        &self,
+        name: &str,
+        hook: &str,
+        actor: &mut Actor,
+        zone: &Zone,
+        goals: &mut GoalStack,
+    ) {
+        if !self.has_hook(name, hook) {
+            return;
+        }
+
+        let args = (ScriptActor::new(actor), ScriptZone::new(zone, actor.xy, SCRIPT_VIEW_RADIUS));
+        let commands = self.call(name, hook, args);
+        self.apply_to_actor(name, hook, commands, actor, Some(goals));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs the item effect hook of a script on an actor. Returns the change in hit points.
+    //---------------------------------------------------------------------------------------------
+    pub fn run_item_effect(&self, name: &str, actor: &mut Actor) -> i32 {
+        let before = actor.health.current;
+        let commands = self.call(name, AFFECT_HOOK, (ScriptActor::new(actor),));
+        self.apply_to_actor(name, AFFECT_HOOK, commands, actor, None);
+
+        actor.health.current - before
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs the zone event hook of every script that defines it, in name order. Returns the # of
+    // commands applied.
+    //---------------------------------------------------------------------------------------------
+    pub fn run_zone_events(&self, zone: &mut Zone) -> usize {
+        let names = self.with_hook(ON_TURN_HOOK);
+
+        if names.is_empty() {
+            return 0;
+        }
+
+        let view = ScriptZone::whole(zone);
+        let mut applied = 0;
+
+        for name in names {
+            for command in self.call(&name, ON_TURN_HOOK, (view.clone(),)) {
+                match command.apply_to_zone(zone) {
+                    Ok(()) => applied += 1,
+                    Err(e) => {
+                        let scripts = self.scripts.read().expect("Failed to lock scripts.");
+
+                        if let Some(script) = scripts.get(&name) {
+                            Self::report(script, ON_TURN_HOOK, e);
+                        }
+                    }
+                }
+            }
+        }
+
+        applied
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// An intention whose hooks are defined by a script.
+//-------------------------------------------------------------------------------------------------
+pub struct ScriptedIntention {
+    // Name of the script.
+    pub script: String,
+}
+
+impl Intention for ScriptedIntention {
+    //---------------------------------------------------------------------------------------------
+    // Called when when the actor has no goals.
+    //---------------------------------------------------------------------------------------------
+    fn bored(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        // Reset the actor state and let the script push goals.
+        actor.navigation.weight = None;
+        SCRIPTS.run_intention(&self.script, BORED_HOOK, actor, zone, goals);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called every turn before the goals are arbitrated.
+    //---------------------------------------------------------------------------------------------
+    fn react(&self, actor: &mut Actor, zone: &Zone, goals: &mut GoalStack) {
+        SCRIPTS.run_intention(&self.script, REACT_HOOK, actor, zone, goals);
+    }
+}
@@ -1,23 +1,55 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use anyhow::Result;
+use rand::{thread_rng, Rng as _};
 use specs::prelude::*;
-use specs::shred::Fetch;
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
-use fvr_engine_core::{prelude::*, xy_iter};
+use fvr_engine_core::prelude::*;
 
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::behavior::*;
+use crate::bump::*;
+use crate::cell::*;
+use crate::combat::*;
 use crate::components::*;
+use crate::content::*;
+use crate::crowd::*;
+use crate::decal::*;
+use crate::decoration::*;
+use crate::determinism::*;
+use crate::door::*;
+use crate::encumbrance::*;
+use crate::goals::*;
 use crate::intentions::*;
+use crate::interaction::*;
+use crate::item::*;
+use crate::journal::*;
+use crate::knowledge::*;
+use crate::rejection::*;
+use crate::scent::*;
+use crate::scripting::*;
+use crate::server_stats::*;
+use crate::simulation::*;
+use crate::snapshot::*;
 use crate::systems::*;
+use crate::travel::*;
 use crate::zone::*;
+use crate::zone_gen::*;
+use crate::zone_graph::*;
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
@@ -26,6 +58,38 @@ use crate::zone::*;
 // TODO: Remove or find a way to populate dynamically.
 pub const BASIC_AVOID_PLAYER_INDEX: usize = 0;
 pub const BASIC_CHASE_PLAYER_INDEX: usize = 1;
+pub const BASIC_TRACK_PLAYER_INDEX: usize = 2;
+pub const BASIC_VILLAGER_INDEX: usize = 3;
+
+// TODO: Remove or find a way to populate dynamically.
+const ZONE_DIMENSIONS: ICoord = (255, 255);
+
+// Number of threads used for generating zones.
+const ZONE_WORKER_COUNT: usize = 2;
+
+// Extension of the files the decals of the zones left are saved to.
+const ZONE_FILE_EXTENSION: &str = "zone";
+
+// Names of the streams of the server's rng.
+const WORLDGEN_STREAM: &str = "worldgen";
+const POPULATION_STREAM: &str = "population";
+const COMBAT_STREAM: &str = "combat";
+const STRESS_STREAM: &str = "stress";
+
+// Name of the player's pool of charges for dashing.
+pub const DASH_CHARGES: &str = "dash";
+
+// # of turns the player's dash takes to recover.
+const DASH_COOLDOWN_TURNS: u32 = 8;
+
+// Max # of charge events kept between polls (the oldest are dropped, e.g. when running headless).
+const MAX_PENDING_CHARGE_EVENTS: usize = 256;
+
+// Max # of combat events kept between polls (the oldest are dropped, e.g. when running headless).
+const MAX_PENDING_COMBAT_EVENTS: usize = 256;
+
+// Max # of item events kept between polls (the oldest are dropped, e.g. when running headless).
+const MAX_PENDING_ITEM_EVENTS: usize = 256;
 
 //-------------------------------------------------------------------------------------------------
 // Enumerates the possible results returned from server actions.
@@ -35,6 +99,10 @@ pub enum ServerResult {
     Fail,
     // The request succeeded.
     Success,
+    // The request was an invalid intention of the player (and no turn passed).
+    Rejected(Rejection),
+    // The request was a bump into an actor the player must disambiguate (and no turn passed).
+    Ambiguous(BumpRequest),
 }
 
 //-------------------------------------------------------------------------------------------------
@@ -45,204 +113,1702 @@ pub struct Server {
     world: World,
     // System for managing actor goals.
     goals_system: GoalsSystem,
+    // System for opening and closing doors.
+    door_system: DoorSystem,
     // System for managing actor movement.
     move_system: MoveSystem,
+    // System for fading and leaving decals.
+    decal_system: DecalSystem,
+    // System for spreading and depositing scent.
+    scent_system: ScentSystem,
+    // System for spreading what actors know.
+    gossip_system: GossipSystem,
+    // System for regenerating charges.
+    charges_system: ChargesSystem,
+    // System for running the zone events of the scripts.
+    zone_script_system: ZoneScriptSystem,
+    // What the player perceived as of the last tick.
+    snapshot: WorldSnapshot,
+    // Pool of threads for generating requested zones.
+    zone_workers: ZoneWorkers,
+    // Cells of requested zones that have finished generating.
+    ready_zones: HashMap<ZoneId, GridMap<Cell>>,
+    // Serialized decals of the zones left, restored when they are entered again.
+    // (only used if no zone directory is set)
+    zone_decals: HashMap<ZoneId, String>,
+    // Directory the decals of the zones left are saved to, and the compression to save them with.
+    zone_dir: Option<(PathBuf, CompressionOptions)>,
+    // Timing and counters for the last tick.
+    stats: ServerStats,
+    // Charge events produced since the last poll.
+    charge_events: Vec<ChargeEvent>,
+    // Combat events produced since the last poll.
+    combat_events: Vec<CombatEvent>,
+    // Item events produced since the last poll.
+    item_events: Vec<ItemEvent>,
+    // Pickup events produced since the last poll.
+    pickup_events: Vec<PickupEvent>,
+    // Pathfinder used for player travel.
+    travel_a_star: AStar,
+    // The path the player is currently traveling, if any.
+    travel: Option<Travel>,
+    // Whether the world is paused, the turns queued to pass and the update speed.
+    clock: SimulationClock,
+    // What the player has learned over the game (kept across zones).
+    journal: Journal,
+    // What the player carries (kept across zones).
+    inventory: Inventory,
+    // Zones the player has discovered and the connections taken between them.
+    zone_graph: ZoneGraph,
+    // Policies for resolving the player's bumps into actors.
+    bump_policies: BumpPolicies,
+    // Which items the player picks up on entering a cell.
+    auto_pickup: AutoPickupSettings,
+    // Rng every stream of the game's randomness is forked from, seeded with the server's seed.
+    rng: Rng,
+    // Stream of the rng for the player's attacks.
+    combat_rng: Rng,
+    // Energy the player owes for their actions beyond the turns the actions took.
+    energy_debt: EnergyDebt,
 }
 
 impl Server {
     //---------------------------------------------------------------------------------------------
-    // Creates a new server. There should only ever be one.
+    // Creates the behaviors, indexed by the actors' behavior indexes.
     //---------------------------------------------------------------------------------------------
-    pub fn new() -> Result<Self> {
-        // TODO: Remove - generate a dummy zone and insert it as a resource.
+    fn behaviors() -> Behaviors {
+        vec![Box::new(BasicBehavior {})]
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates the intentions, indexed by the actors' intention indexes.
+    //---------------------------------------------------------------------------------------------
+    fn intentions() -> Intentions {
+        #[rustfmt::skip]
+        let mut intentions: Intentions = vec![
+            Box::new(BasicAvoidPlayerIntention {}),
+            Box::new(BasicChasePlayerIntention {}),
+            Box::new(BasicTrackPlayerIntention {}),
+            Box::new(BasicVillagerIntention {})
+        ];
+
+        // The scripted intentions follow the basic intentions, ordered by script name.
+        for script in SCRIPTS.with_hook(BORED_HOOK) {
+            intentions.push(Box::new(ScriptedIntention { script }));
+        }
+
+        intentions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Cross-checks all of the loaded content (things, messages and the references between them),
+    // returning a report of every error found.
+    //---------------------------------------------------------------------------------------------
+    pub fn validate_content() -> ContentReport {
+        let mut report = ContentReport::new();
+
+        for (name, thing) in ZONE_THINGS.iter() {
+            report.check_thing(name, thing);
+        }
+
+        for state in DoorState::ALL.iter() {
+            let name = format!("{:?} door", state).to_lowercase();
+            report.check_thing(&name, &state.thing());
+
+            if state.thing().door != Some(*state) {
+                report.error(format!("thing '{}'", name), "The door state does not match.");
+            }
+        }
+
+        for biome in Biome::ALL.iter() {
+            let theme = biome.theme();
+
+            for scatter in theme.scatter.iter() {
+                let name = format!("{:?} decoration '{}'", biome, scatter.thing.tile.glyph);
+                report.check_thing(&name, scatter.thing);
+
+                if scatter.thing.passability != Passability::Passable {
+                    report.error(format!("thing '{}'", name), "Decorations must not block.");
+                }
+            }
+
+            for variant in theme.floor_variants.iter() {
+                if !is_cp437(variant.glyph) {
+                    report.error(
+                        format!("{:?} floor variant", biome),
+                        format!("Glyph {:?} is missing from the atlas.", variant.glyph),
+                    );
+                }
+            }
+        }
+
+        for reason in RejectionReason::ALL.iter() {
+            report.check_rich_text(&format!("rejection message {:?}", reason), reason.message());
+        }
+
+        let intention_count = Self::intentions().len();
+        let intention_indexes = [
+            ("BASIC_AVOID_PLAYER_INDEX", BASIC_AVOID_PLAYER_INDEX),
+            ("BASIC_CHASE_PLAYER_INDEX", BASIC_CHASE_PLAYER_INDEX),
+            ("BASIC_TRACK_PLAYER_INDEX", BASIC_TRACK_PLAYER_INDEX),
+            ("BASIC_VILLAGER_INDEX", BASIC_VILLAGER_INDEX),
+        ];
+
+        for (name, index) in intention_indexes.iter() {
+            report.check_index(name, "intention", *index, intention_count);
+        }
+
+        for (script, error) in SCRIPTS.errors().iter() {
+            report.error(format!("script '{}'", script), error.as_str());
+        }
+
+        report.check_autotile_ruleset("default wall ruleset", &AutotileRuleset::default());
+
+        for kind in DecalKind::ALL.iter() {
+            if let Some(glyph) = kind.overlay_glyph().filter(|glyph| !is_cp437(*glyph)) {
+                report.error(
+                    format!("{:?} decal", kind),
+                    format!("Glyph {:?} is missing from the atlas.", glyph),
+                );
+            }
+        }
+
+        for (i, kind) in ItemKind::ALL.iter().enumerate() {
+            let definition = kind.definition();
+
+            if !is_cp437(definition.glyph) {
+                report.error(
+                    format!("{:?} item", kind),
+                    format!("Glyph {:?} is missing from the atlas.", definition.glyph),
+                );
+            }
+
+            if definition.max_stack == 0 {
+                report.error(format!("{:?} item", kind), "Max stack must be at least 1.");
+            }
+
+            // Items only carry their kind, so stacks of different kinds must never merge.
+            for other in ItemKind::ALL.iter().skip(i + 1) {
+                if other.definition().stack_key == definition.stack_key {
+                    report.error(
+                        format!("{:?} item", kind),
+                        format!(
+                            "Stack key {:?} is shared with {:?}.",
+                            definition.stack_key, other
+                        ),
+                    );
+                }
+            }
+        }
+
+        // The (dummy) mobs all use the first behavior.
+        report.check_index("dummy mobs", "behavior", 0, Self::behaviors().len());
+
+        report
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the stream of an rng for generating or populating a zone (none for the starting zone).
+    //---------------------------------------------------------------------------------------------
+    fn zone_rng(rng: &Rng, stream: &str, id: Option<ZoneId>) -> Rng {
+        let stream = rng.fork(stream);
+
+        match id {
+            Some(id) => stream.fork(&id.to_string()),
+            None => stream,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a specs world containing a zone created from generated cells.
+    //---------------------------------------------------------------------------------------------
+    fn create_world(cell_map: GridMap<Cell>, rng: &mut Rng) -> Result<(World, WorldSnapshot)> {
         let mut world = World::new();
         world.register::<IsActor>();
         world.register::<HasGoals>();
         world.register::<WantsToMove>();
+        world.register::<HasCharges>();
+        world.register::<EmitsScent>();
+        world.insert(TurnPools::default());
+        world.insert(SystemCounters::default());
+        world.insert(Rejections::new());
+        world.insert(ChargeEvents::new());
+        world.insert(CombatEvents::new());
+        world.insert(ItemEvents::new());
+        world.insert(DecalEvents::new());
+        world.insert(FactionStandings::new());
 
-        let zone = Zone::dummy((255, 255), &mut world)?;
+        let zone = Zone::from_cells(cell_map, &mut world, rng)?;
+        let snapshot = WorldSnapshot::new(&zone);
         world.insert(zone);
 
         // Populate behaviors and intention vecs and insert them as resources.
-        let behaviors: Behaviors = vec![Box::new(BasicBehavior {})];
+        world.insert(Self::behaviors());
+        world.insert(Self::intentions());
 
-        #[rustfmt::skip]
-        let intentions: Intentions = vec![
-            Box::new(BasicAvoidPlayerIntention {}),
-            Box::new(BasicChasePlayerIntention {})
-        ];
+        Ok((world, snapshot))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new server. There should only ever be one.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Result<Self> {
+        Self::with_seed(thread_rng().gen())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new server whose starting zone is generated from a seed.
+    // Servers created with the same seed and given the same inputs produce the same state.
+    //---------------------------------------------------------------------------------------------
+    pub fn with_seed(seed: u64) -> Result<Self> {
+        // Report every content error at once, rather than failing later on the first.
+        Self::validate_content().into_result()?;
+
+        // TODO: Remove - generate a dummy zone on the main thread.
+        let rng = Rng::new(seed);
+        let worldgen_seed = Self::zone_rng(&rng, WORLDGEN_STREAM, None).seed();
+        let cell_map =
+            ForestGenerator::new(worldgen_seed).generate(ZONE_DIMENSIONS, &mut |_| {})?;
+        let (world, snapshot) =
+            Self::create_world(cell_map, &mut Self::zone_rng(&rng, POPULATION_STREAM, None))?;
 
-        world.insert(behaviors);
-        world.insert(intentions);
+        let mut server = Self {
+            world,
+            goals_system: GoalsSystem {},
+            door_system: DoorSystem::default(),
+            move_system: MoveSystem,
+            scent_system: ScentSystem {},
+            gossip_system: GossipSystem::default(),
+            decal_system: DecalSystem::default(),
+            charges_system: ChargesSystem {},
+            zone_script_system: ZoneScriptSystem {},
+            snapshot,
+            zone_workers: ZoneWorkers::new(ZONE_WORKER_COUNT),
+            ready_zones: HashMap::new(),
+            zone_decals: HashMap::new(),
+            zone_dir: None,
+            stats: ServerStats::default(),
+            charge_events: Vec::new(),
+            combat_events: Vec::new(),
+            item_events: Vec::new(),
+            pickup_events: Vec::new(),
+            travel_a_star: AStar::new(TRAVEL_DISTANCE),
+            travel: None,
+            clock: SimulationClock::default(),
+            journal: Journal::new(),
+            inventory: Inventory::starting_kit(),
+            zone_graph: ZoneGraph::new(),
+            bump_policies: BumpPolicies::default(),
+            auto_pickup: AutoPickupSettings::default(),
+            combat_rng: rng.fork(COMBAT_STREAM),
+            rng,
+            energy_debt: EnergyDebt::default(),
+        };
 
-        Ok(Self { world, goals_system: GoalsSystem {}, move_system: MoveSystem::default() })
+        server.refresh_encumbrance();
+        server.set_player_charges(DASH_CHARGES, Charges::cooldown(DASH_COOLDOWN_TURNS))?;
+        Ok(server)
     }
 
     //---------------------------------------------------------------------------------------------
-    // Returns a ref to the current zone.
+    // Requests a zone be generated off of the main thread with a particular generator.
+    // Progress and completion are reported through poll_zone_events.
     //---------------------------------------------------------------------------------------------
-    pub fn zone(&self) -> Fetch<Zone> {
-        self.world.fetch::<Zone>()
+    pub fn request_zone_with(
+        &mut self,
+        id: ZoneId,
+        generator: Box<dyn ZoneGenerator>,
+    ) -> ZoneHandle {
+        self.ready_zones.remove(&id);
+        self.zone_workers.request(id, ZONE_DIMENSIONS, generator)
     }
 
     //---------------------------------------------------------------------------------------------
-    // Copies a section the visual state of current zone into a map2d.
-    // Returns the offset from the origin of the zone of the blit.
+    // Requests a zone be generated off of the main thread, seeded by its id and the server's seed.
+    // TODO: Choose the generator from zone data once zones are defined by content.
     //---------------------------------------------------------------------------------------------
-    pub fn blit<M>(
-        &self,
-        terminal: &mut M,
-        src: &Rect,
-        dest_origin: ICoord,
-        show_fov: bool,
-    ) -> ICoord
-    where
-        M: Map2d<Tile>,
-    {
-        let zone = self.world.fetch::<Zone>();
+    pub fn request_zone(&mut self, id: ZoneId) -> ZoneHandle {
+        let seed = Self::zone_rng(&self.rng, WORLDGEN_STREAM, Some(id)).seed();
+        self.request_zone_with(id, Box::new(ForestGenerator::new(seed)))
+    }
 
-        // Iterate through each of the visible tiles, updating them from the zone.
-        xy_iter!(x, y, src.width, src.height, {
-            // Calculate the adjusted coord.
-            let src_xy = (src.x + x, src.y + y);
-            let dst_xy = (dest_origin.0 + x, dest_origin.1 + y);
-
-            // Get the tile to be updated.
-            let tile = terminal.get_xy_mut(dst_xy);
-
-            // Update the tile either with an actor, a thing, or a default tile.
-            if let Some(actor) = zone.actor_map.get_xy(src_xy) {
-                let actor = actor.as_ref().lock().unwrap();
-                *tile = actor.thing.tile;
-            } else if let Some(thing) = zone.cell_map.get_xy(src_xy).things.last() {
-                *tile = thing.tile;
-            } else {
-                *tile = Tile::default();
+    //---------------------------------------------------------------------------------------------
+    // Returns the events produced by zone generation since the last poll.
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_zone_events(&mut self) -> Vec<ZoneEvent> {
+        let mut events = Vec::new();
+
+        while let Some(message) = self.zone_workers.try_recv() {
+            match message {
+                ZoneWorkerMessage::Progress(id, progress) => {
+                    events.push(ZoneEvent::Progress { id, progress });
+                }
+                ZoneWorkerMessage::Finished(id, Ok(cell_map)) => {
+                    self.ready_zones.insert(id, cell_map);
+                    events.push(ZoneEvent::Complete { id });
+                }
+                ZoneWorkerMessage::Finished(id, Err(e)) => {
+                    events.push(ZoneEvent::Failed { id, error: format!("{:#}", e) });
+                }
             }
+        }
+
+        events
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a requested zone has finished generating and can be entered.
+    //---------------------------------------------------------------------------------------------
+    pub fn zone_ready(&self, id: ZoneId) -> bool {
+        self.ready_zones.contains_key(&id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the current zone with a requested zone that has finished generating.
+    //---------------------------------------------------------------------------------------------
+    pub fn enter_zone(&mut self, id: ZoneId) -> Result<ServerResult> {
+        let cell_map = match self.ready_zones.remove(&id) {
+            Some(cell_map) => cell_map,
+            None => return Ok(ServerResult::Fail),
+        };
+
+        // Keep the decals of the zone being left, in case it is entered again.
+        if let Some(from) = self.zone_graph.current() {
+            let zone = self.world.fetch::<Zone>();
+
+            match &self.zone_dir {
+                Some((dir, options)) => zone.decals.save(Self::zone_file(dir, from), *options)?,
+                None => {
+                    let _ = self.zone_decals.insert(from, zone.decals.serialize());
+                }
+            }
+        }
+
+        let mut rng = Self::zone_rng(&self.rng, POPULATION_STREAM, Some(id));
+        let (world, snapshot) = Self::create_world(cell_map, &mut rng)?;
+        self.world = world;
+        self.snapshot = snapshot;
+
+        // Restore the decals of a zone entered before.
+        {
+            let mut zone = self.world.fetch_mut::<Zone>();
+            let saved = self
+                .zone_dir
+                .as_ref()
+                .map(|(dir, _)| Self::zone_file(dir, id))
+                .filter(|path| path.exists());
+            let decals = match (saved, self.zone_decals.get(&id)) {
+                (Some(path), _) => Some(DecalLayer::load(zone.dimensions, path)?),
+                (None, Some(decals)) => Some(DecalLayer::deserialize(zone.dimensions, decals)?),
+                (None, None) => None,
+            };
 
-            // Optionally adjust for Fov.
-            if show_fov {
-                tile.foreground_opacity = *zone.player_fov.get_xy(src_xy);
-                tile.outline_opacity = tile.foreground_opacity;
+            if let Some(decals) = decals {
+                zone.decals = decals;
+                self.snapshot.refresh(&zone);
             }
-        });
+        }
+        self.travel = None;
+        self.refresh_encumbrance();
+
+        // TODO: Name zones and decide which allow fast travel from zone data once zones are
+        // defined by content.
+        let name = format!("Forest {}", self.zone_graph.nodes().len() + 1);
+        let _ = self.zone_graph.enter(id, &name, true);
 
-        // Return the visible offset from the origin of the zone.
-        (src.x, src.y)
+        Ok(ServerResult::Success)
     }
 
     //---------------------------------------------------------------------------------------------
-    // Copies a section the visual state of current zone, centered on a coord, into a map2d.
-    // Returns the offset from the origin of the zone of the blit.
+    // Replaces the current zone with a requested zone reached through stairs or a portal,
+    // recording the connection in the zone graph.
     //---------------------------------------------------------------------------------------------
-    pub fn blit_centered<M>(
-        &self,
-        terminal: &mut M,
-        center: ICoord,
-        dimensions: ICoord,
-        dest_origin: ICoord,
-        show_fov: bool,
-    ) -> ICoord
-    where
-        M: Map2d<Tile>,
-    {
+    pub fn enter_zone_via(&mut self, id: ZoneId, kind: ZoneLinkKind) -> Result<ServerResult> {
+        let from = self.zone_graph.current();
+        let result = self.enter_zone(id)?;
+
+        if let (ServerResult::Success, Some(from)) = (&result, from) {
+            let _ = self.zone_graph.connect(from, id, kind);
+        }
+
+        Ok(result)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a ref to the snapshot of what the player perceives, updated every tick.
+    //---------------------------------------------------------------------------------------------
+    pub fn snapshot(&self) -> &WorldSnapshot {
+        &self.snapshot
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the color grading of the current zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn zone_color_grade(&self) -> ColorGrade {
+        self.world.fetch::<Zone>().color_grade
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the ruleset picking the glyphs of the current zone's walls, if its glyphs are valid.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_wall_ruleset(&mut self, ruleset: AutotileRuleset) -> Result<()> {
+        let mut report = ContentReport::new();
+        report.check_autotile_ruleset("wall ruleset", &ruleset);
+        report.into_result()?;
+
+        self.world.fetch_mut::<Zone>().wall_ruleset = ruleset;
+        self.snapshot.refresh(&self.world.fetch::<Zone>());
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Adds a # of mobs chasing the player to the current zone, for stress testing the systems.
+    // Returns the # of mobs added.
+    //---------------------------------------------------------------------------------------------
+    pub fn spawn_stress_mobs(&mut self, count: usize) -> Result<usize> {
+        let mut rng = self.rng.fork(STRESS_STREAM);
+
+        // Take the zone out of the world while its mobs are inserted into the world.
+        let mut zone = self.world.remove::<Zone>().expect("Zone resource missing.");
+        let created = zone.generate_stress_mobs(&mut self.world, &mut rng, count);
+        self.world.insert(zone);
+
+        self.snapshot.refresh(&self.world.fetch::<Zone>());
+        created
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the allocation counters of the per-turn pools for the last tick.
+    //---------------------------------------------------------------------------------------------
+    pub fn pool_stats(&self) -> PoolStats {
+        self.stats.pool_stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the timing and counters of the systems for the last tick.
+    //---------------------------------------------------------------------------------------------
+    pub fn stats(&self) -> &ServerStats {
+        &self.stats
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the goal chosen by an entity's last arbitration step, for debug inspection.
+    //---------------------------------------------------------------------------------------------
+    pub fn chosen_goal(&self, entity: u32) -> Option<GoalChoice> {
+        let entity = self.world.entities().entity(entity);
+        self.world.read_storage::<HasGoals>().get(entity).and_then(|h| h.choice)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the strength of a kind of scent at a coord, for debug visualization.
+    //---------------------------------------------------------------------------------------------
+    pub fn scent_strength(&self, kind: ScentKind, xy: ICoord) -> f32 {
+        self.world.fetch::<Zone>().scent.layer(kind).strength(xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records a deed of the player, which the actors that can see the player witness (and later
+    // gossip about). Returns the # of witnesses.
+    //---------------------------------------------------------------------------------------------
+    pub fn record_player_deed(&mut self, kind: FactKind) -> usize {
         let zone = self.world.fetch::<Zone>();
+        let mut standings = self.world.write_resource::<FactionStandings>();
+        let fact = standings.record(kind, zone.turn, zone.player_xy);
+        let mut witnesses = 0;
 
-        // Calculate the view rect.
-        let mut rect = Rect::with_center(center, dimensions.0, dimensions.1);
-        rect.fit_boundary(&Rect::new((0, 0), zone.dimensions.0, zone.dimensions.1));
-
-        // Iterate through each of the visible tiles, updating them from the zone.
-        xy_iter!(x, y, rect.width, rect.height, {
-            // Calculate the adjusted coord.
-            let src_xy = (rect.x + x, rect.y + y);
-            let dst_xy = (dest_origin.0 + x, dest_origin.1 + y);
-
-            // Get the tile to be updated.
-            let tile = terminal.get_xy_mut(dst_xy);
-
-            // Update the tile either with an actor, a thing, or a default tile.
-            if let Some(actor) = zone.actor_map.get_xy(src_xy) {
-                let actor = actor.as_ref().lock().unwrap();
-                *tile = actor.thing.tile;
-            } else if let Some(thing) = zone.cell_map.get_xy(src_xy).things.last() {
-                *tile = thing.tile;
-            } else {
-                *tile = Tile::default();
+        for (xy, shared) in zone.actors_near(zone.player_xy, WITNESS_RANGE) {
+            if xy == zone.player_xy || !zone.can_see_player(xy) {
+                continue;
             }
 
-            // Optionally adjust for Fov.
-            if show_fov {
-                tile.foreground_opacity = *zone.player_fov.get_xy(src_xy);
-                tile.outline_opacity = tile.foreground_opacity;
+            let mut actor = shared.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            if actor.faction != Faction::None && actor.knowledge.learn(fact.clone()) {
+                let _ = standings.hear(actor.faction, &fact);
+                witnesses += 1;
             }
-        });
+        }
 
-        // Return the visible offset from the origin of the zone.
-        (rect.x, rect.y)
+        witnesses
     }
 
     //---------------------------------------------------------------------------------------------
-    // Copies a section the visual state of current zone, centered on the player, into a map2d.
-    // Returns the offset from the origin of the zone of the blit.
+    // Returns a faction's standing with the player.
     //---------------------------------------------------------------------------------------------
-    pub fn blit_centered_on_player<M>(
-        &self,
-        terminal: &mut M,
-        dimensions: ICoord,
-        dest_origin: ICoord,
-        show_fov: bool,
-    ) -> ICoord
-    where
-        M: Map2d<Tile>,
-    {
-        let player_xy = self.world.fetch::<Zone>().player_xy;
-        self.blit_centered(terminal, player_xy, dimensions, dest_origin, show_fov)
+    pub fn faction_standing(&self, faction: Faction) -> i32 {
+        self.world.read_resource::<FactionStandings>().standing(faction)
     }
 
     //---------------------------------------------------------------------------------------------
-    // Tries to move the player in a direction. Returns the result.
+    // Returns the facts an entity knows, for debug inspection.
     //---------------------------------------------------------------------------------------------
-    fn try_move_player(&mut self, dir: Direction) -> Result<ServerResult> {
-        // Calculate the tentative new player position.
+    pub fn known_facts(&self, entity: u32) -> Vec<Fact> {
+        let entity = self.world.entities().entity(entity);
+        self.world.read_storage::<IsActor>().get(entity).map_or_else(Vec::new, |a| {
+            a.0.as_ref().lock().expect("Failed to lock actor mutex.").knowledge.facts().to_vec()
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Evaluates a dialogue condition for an entity (false if the entity is not an actor).
+    //---------------------------------------------------------------------------------------------
+    pub fn dialogue_condition(&self, entity: u32, condition: &DialogueCondition) -> bool {
+        let entity = self.world.entities().entity(entity);
+        let standings = self.world.read_resource::<FactionStandings>();
+
+        self.world.read_storage::<IsActor>().get(entity).is_some_and(|a| {
+            let actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+            condition.evaluate(&actor, &standings)
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the simulation state in a comparable form, e.g. for checking determinism.
+    //---------------------------------------------------------------------------------------------
+    pub fn state(&self) -> ServerState {
+        let mut state = ServerState::default();
         let zone = self.world.fetch::<Zone>();
-        let new_xy = (zone.player_xy.0 + dir.dx(), zone.player_xy.1 + dir.dy());
+        state.insert("zone.player_xy", format!("{:?}", zone.player_xy));
+        state.insert("zone.turn", format!("{}", zone.turn));
+        state.insert("zone.decals", zone.decals.serialize());
+        state.insert("zone.ground_items", zone.ground_items.serialize());
+        state.insert("inventory", format!("{:?}", self.inventory.items()));
+        state.insert("player.encumbrance", format!("{:?}", self.snapshot.encumbrance()));
+        state.insert("player.energy_owed", format!("{}", self.energy_debt.owed()));
+        state.insert("journal", self.journal.serialize());
 
-        // Is the new position in bounds?
-        if zone.is_blocked(new_xy) {
-            return Ok(ServerResult::Fail);
+        for (faction, standing) in self.world.read_resource::<FactionStandings>().iter() {
+            state.insert(&format!("standing.{:?}", faction), format!("{}", standing));
         }
 
-        // Otherwise, flag the player for moving and dispatch.
-        let player_dex =
-            zone.actor_map.get_xy(zone.player_xy).as_ref().unwrap().lock().unwrap().stats.DEX;
-        let component = WantsToMove { direction: dir, weight: f32::MAX, priority: player_dex };
-        self.world.write_component::<WantsToMove>().insert(zone.player_entity, component)?;
+        let entities = self.world.entities();
+        let is_actor = self.world.read_storage::<IsActor>();
+        let has_goals = self.world.read_storage::<HasGoals>();
+        let has_charges = self.world.read_storage::<HasCharges>();
 
-        Ok(ServerResult::Success)
+        for (entity, a) in (&entities, &is_actor).join() {
+            let actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+            state.insert(
+                &format!("actor.{}", entity.id()),
+                format!(
+                    "xy: {:?}, navigation: {:?}, stats: {:?}, behavior: {}, intention: {}, blackboard: {:?}, knowledge: {:?}",
+                    actor.xy,
+                    actor.navigation,
+                    actor.stats,
+                    actor.behavior,
+                    actor.intention,
+                    actor.blackboard,
+                    actor.knowledge
+                ),
+            );
+        }
+
+        for (entity, h) in (&entities, &has_goals).join() {
+            state.insert(
+                &format!("goals.{}", entity.id()),
+                format!("{} goals, chosen: {:?}", h.goals.len(), h.choice),
+            );
+        }
+
+        for (entity, h) in (&entities, &has_charges).join() {
+            for (name, charges) in h.pools.iter() {
+                state.insert(
+                    &format!("charges.{}.{}", entity.id(), name),
+                    format!("{:?}", charges),
+                );
+            }
+        }
+
+        state
     }
 
     //---------------------------------------------------------------------------------------------
-    // Tries to move the player to a particular coord. Returns the result.
+    // Returns a ref to the journal of what the player has learned.
     //---------------------------------------------------------------------------------------------
-    pub fn move_player(&mut self, dir: Direction) -> Result<ServerResult> {
-        let result = self.try_move_player(dir);
-        self.tick();
-        result
+    pub fn journal(&self) -> &Journal {
+        &self.journal
     }
 
     //---------------------------------------------------------------------------------------------
-    // Allow one "tick", or turn, to pass in the server.
+    // Returns a mut ref to the journal (e.g. for starting quests or recording discovered lore).
     //---------------------------------------------------------------------------------------------
-    pub fn tick(&mut self) {
-        // Run the systems.
-        self.goals_system.run_now(&self.world);
-        self.world.maintain();
+    pub fn journal_mut(&mut self) -> &mut Journal {
+        &mut self.journal
+    }
 
-        self.move_system.run_now(&self.world);
-        self.world.maintain();
+    //---------------------------------------------------------------------------------------------
+    // Saves the decals of the zones left to files in a directory from now on, rather than keeping
+    // them in memory.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_zone_dir(&mut self, dir: PathBuf, options: CompressionOptions) {
+        self.zone_dir = Some((dir, options));
+    }
 
-        // Refresh zone navigation maps and fov.
-        self.world.fetch_mut::<Zone>().refresh();
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the path of the file the decals of a zone are saved to.
+    //---------------------------------------------------------------------------------------------
+    fn zone_file(dir: &Path, id: ZoneId) -> PathBuf {
+        dir.join(format!("{}.{}", id, ZONE_FILE_EXTENSION))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Replaces the journal (e.g. with one loaded from a save).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_journal(&mut self, journal: Journal) {
+        self.journal = journal;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the items lying at a coord of the zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn ground_items(&self, xy: ICoord) -> Vec<ItemStack> {
+        self.world.fetch::<Zone>().ground_items.get(xy).to_vec()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the coord and topmost item of each pile the player can see.
+    //---------------------------------------------------------------------------------------------
+    pub fn visible_ground_items(&self) -> Vec<(ICoord, ItemKind)> {
+        self.world
+            .fetch::<Zone>()
+            .ground_items
+            .iter()
+            .filter(|(xy, _)| self.snapshot.is_visible(*xy))
+            .filter_map(|(xy, pile)| pile.last().map(|stack| (xy, stack.kind)))
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the settings for which items the player picks up on entering a cell.
+    //---------------------------------------------------------------------------------------------
+    pub fn auto_pickup(&self) -> &AutoPickupSettings {
+        &self.auto_pickup
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the settings for which items the player picks up on entering a cell.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_auto_pickup(&mut self, settings: AutoPickupSettings) {
+        self.auto_pickup = settings;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the items the player carries.
+    //---------------------------------------------------------------------------------------------
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a mut ref to the items the player carries (e.g. for giving the player items).
+    //---------------------------------------------------------------------------------------------
+    pub fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a ref to the graph of the zones the player has discovered.
+    //---------------------------------------------------------------------------------------------
+    pub fn zone_graph(&self) -> &ZoneGraph {
+        &self.zone_graph
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a mut ref to the zone graph (e.g. for choosing a fast travel destination).
+    //---------------------------------------------------------------------------------------------
+    pub fn zone_graph_mut(&mut self) -> &mut ZoneGraph {
+        &mut self.zone_graph
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that records the kinds of the perceived monsters in the bestiary.
+    // (townsfolk are not monsters)
+    //---------------------------------------------------------------------------------------------
+    fn record_sightings(&mut self) {
+        let zone = self.world.fetch::<Zone>();
+
+        for entity in self.snapshot.entities().iter().filter(|e| e.xy != zone.player_xy) {
+            if let Some(actor) = zone.actor_map.get_xy(entity.xy) {
+                let actor = actor.as_ref().lock().expect("Failed to lock actor mutex.");
+
+                if actor.faction != Faction::Town {
+                    let _ = self.journal.see_monster(actor.kind, actor.thing.tile.glyph);
+                }
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Requests a decal splatter (e.g. blood from a wound or a scorch mark from a fire), applied
+    // when the next turn passes.
+    //---------------------------------------------------------------------------------------------
+    pub fn splatter(&mut self, xy: ICoord, kind: DecalKind, strength: f32, radius: i32) {
+        self.world.write_resource::<DecalEvents>().push(DecalEvent { xy, kind, strength, radius });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Washes the washable decals within a radius of a coord away with water.
+    // Returns the # of decals washed away.
+    //---------------------------------------------------------------------------------------------
+    pub fn wash_decals(&mut self, xy: ICoord, radius: i32) -> usize {
+        let washed = self.world.fetch_mut::<Zone>().decals.wash(xy, radius);
+
+        if washed > 0 {
+            self.snapshot.refresh(&self.world.fetch::<Zone>());
+        }
+
+        washed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the decal at a coord of the current zone, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn decal(&self, xy: ICoord) -> Option<Decal> {
+        self.world.fetch::<Zone>().decals.get(xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the charge events produced since the last poll.
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_charge_events(&mut self) -> Vec<ChargeEvent> {
+        mem::take(&mut self.charge_events)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the combat events (with their roll breakdowns) produced since the last poll.
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_combat_events(&mut self) -> Vec<CombatEvent> {
+        mem::take(&mut self.combat_events)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the item events (with the actors affected) produced since the last poll.
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_item_events(&mut self) -> Vec<ItemEvent> {
+        mem::take(&mut self.item_events)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the pickup events produced since the last poll.
+    //---------------------------------------------------------------------------------------------
+    pub fn poll_pickup_events(&mut self) -> Vec<PickupEvent> {
+        mem::take(&mut self.pickup_events)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a copy of a named pool of the player's charges, if the player has it.
+    //---------------------------------------------------------------------------------------------
+    pub fn player_charges(&self, name: &str) -> Option<Charges> {
+        let player_entity = self.world.fetch::<Zone>().player_entity;
+        let has_charges = self.world.read_storage::<HasCharges>();
+        has_charges.get(player_entity).and_then(|h| h.pools.get(name).copied())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Gives the player a named pool of charges, replacing any pool with the same name.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_player_charges(&mut self, name: &str, charges: Charges) -> Result<()> {
+        let player_entity = self.world.fetch::<Zone>().player_entity;
+        let mut has_charges = self.world.write_storage::<HasCharges>();
+
+        if !has_charges.contains(player_entity) {
+            let _ = has_charges.insert(player_entity, HasCharges::default())?;
+        }
+
+        let _ = has_charges
+            .get_mut(player_entity)
+            .expect("Player charges missing.")
+            .pools
+            .insert(name.into(), charges);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Consumes a use of a named pool of the player's charges. Returns the result.
+    // (the use is rejected if the pool does not have enough charges, and fails if it is missing)
+    //---------------------------------------------------------------------------------------------
+    pub fn use_player_charges(&mut self, name: &str) -> ServerResult {
+        let zone = self.world.fetch::<Zone>();
+        let mut has_charges = self.world.write_storage::<HasCharges>();
+
+        let charges =
+            match has_charges.get_mut(zone.player_entity).and_then(|h| h.pools.get_mut(name)) {
+                Some(charges) => charges,
+                None => return ServerResult::Fail,
+            };
+
+        if charges.try_use() {
+            ServerResult::Success
+        } else {
+            ServerResult::Rejected(Rejection::new(RejectionReason::Recharging, zone.player_xy))
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Dashes the player two cells in a direction in the time of one move, using the player's dash
+    // charges. Returns the result. The dash is rejected without a turn passing if the first cell is
+    // blocked or the dash is recharging, and stops after one cell if the second cell is blocked.
+    //---------------------------------------------------------------------------------------------
+    pub fn dash_player(&mut self, dir: Direction) -> Result<ServerResult> {
+        let (from, first, second) = {
+            let zone = self.world.fetch::<Zone>();
+
+            // The player cannot act while the world is frozen.
+            if self.clock.paused() {
+                return Ok(ServerResult::Rejected(Rejection::new(
+                    RejectionReason::Paused,
+                    zone.player_xy,
+                )));
+            }
+
+            let from = zone.player_xy;
+            let first = (from.0 + dir.dx(), from.1 + dir.dy());
+
+            // Dashing does not open doors or bump into actors.
+            if let Some(reason) = zone.blocked_reason(first) {
+                return Ok(ServerResult::Rejected(Rejection::new(reason, first)));
+            }
+
+            (from, first, (first.0 + dir.dx(), first.1 + dir.dy()))
+        };
+
+        if let result @ (ServerResult::Fail | ServerResult::Rejected(_)) =
+            self.use_player_charges(DASH_CHARGES)
+        {
+            return Ok(result);
+        }
+
+        // Take the first step outside of the turn.
+        let blocked = {
+            let mut zone = self.world.fetch_mut::<Zone>();
+            let player = zone.actor_map.get_xy_mut(from).take();
+
+            if let Some(player) = &player {
+                player.lock().expect("Failed to lock actor mutex.").xy = first;
+            }
+
+            *zone.actor_map.get_xy_mut(first) = player;
+            zone.player_xy = first;
+            zone.blocked_reason(second).is_some()
+        };
+
+        // Then take the second step as a move, or stop short (which still takes the turn).
+        if blocked {
+            self.tick();
+            self.spend_action_energy();
+            Ok(ServerResult::Success)
+        } else {
+            match self.walk_player(dir)? {
+                ServerResult::Rejected(_) => Ok(ServerResult::Success),
+                result => Ok(result),
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Tries to move the player in a direction. Returns the result.
+    //---------------------------------------------------------------------------------------------
+    fn try_move_player(&mut self, dir: Direction) -> Result<ServerResult> {
+        // Calculate the tentative new player position.
+        let zone = self.world.fetch::<Zone>();
+
+        // The player cannot act while the world is frozen.
+        if self.clock.paused() {
+            return Ok(ServerResult::Rejected(Rejection::new(
+                RejectionReason::Paused,
+                zone.player_xy,
+            )));
+        }
+
+        let new_xy = (zone.player_xy.0 + dir.dx(), zone.player_xy.1 + dir.dy());
+
+        // Is the new position passable? (moving into a closed door opens it)
+        if let Some(reason) = zone.blocked_reason(new_xy) {
+            if !zone.closed_door_at(new_xy) {
+                return Ok(ServerResult::Rejected(Rejection::new(reason, new_xy)));
+            }
+        }
+
+        // Otherwise, flag the player for moving and dispatch.
+        let player_dex =
+            zone.actor_map.get_xy(zone.player_xy).as_ref().unwrap().lock().unwrap().stats.DEX;
+        let component = WantsToMove {
+            direction: dir,
+            weight: f32::MAX,
+            priority: player_dex,
+            gradient: MoveGradient::None,
+        };
+        self.world.write_component::<WantsToMove>().insert(zone.player_entity, component)?;
+
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Tries to move the player in a direction. Returns the result.
+    // Bumping into an actor is resolved by the bump policy for the actor, which may ask the player
+    // to disambiguate. Otherwise, see walk_player.
+    //---------------------------------------------------------------------------------------------
+    pub fn move_player(&mut self, dir: Direction) -> Result<ServerResult> {
+        let request = match self.bump_request(dir) {
+            Some(request) => request,
+            None => return self.walk_player(dir),
+        };
+
+        let action = match self.bump_policies.policy(request.relation) {
+            BumpPolicy::Ask if request.actions.len() > 1 => {
+                return Ok(ServerResult::Ambiguous(request));
+            }
+            BumpPolicy::Ask => request.actions[0],
+            BumpPolicy::Always(action) => action,
+            BumpPolicy::Block => return self.walk_player(dir),
+        };
+
+        self.resolve_bump(&request, action)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Tries to move the player to a particular coord. Returns the result.
+    // The move is rejected without a turn passing if the coord is not passable, or after the turn
+    // if it became blocked before the player's move was resolved.
+    //---------------------------------------------------------------------------------------------
+    fn walk_player(&mut self, dir: Direction) -> Result<ServerResult> {
+        let result = self.try_move_player(dir)?;
+
+        if let ServerResult::Rejected(_) = result {
+            return Ok(result);
+        }
+
+        let from = self.world.fetch::<Zone>().player_xy;
+        self.tick();
+
+        if let Some(rejection) = self.world.write_resource::<Rejections>().pop() {
+            return Ok(ServerResult::Rejected(rejection));
+        }
+
+        // Entering a cell with items picks up those the player's settings allow.
+        if self.world.fetch::<Zone>().player_xy != from {
+            let settings = self.auto_pickup.clone();
+            self.take_ground_items(true, |item| item.auto_pickup(&settings), ItemQuantity::All);
+        }
+
+        self.spend_action_energy();
+        Ok(result)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Picks up a quantity of the items in the player's cell that pass a filter, which takes the
+    // player's turn. The pick up is rejected without a turn passing if no items pass. Returns the
+    // result.
+    //---------------------------------------------------------------------------------------------
+    pub fn pick_up(
+        &mut self,
+        filter: PickupFilter,
+        quantity: ItemQuantity,
+    ) -> Result<ServerResult> {
+        let player_xy = self.snapshot.player_xy();
+
+        if self.clock.paused() {
+            return Ok(ServerResult::Rejected(Rejection::new(RejectionReason::Paused, player_xy)));
+        }
+
+        if !self.take_ground_items(false, |item| filter.matches(item), quantity) {
+            return Ok(ServerResult::Rejected(Rejection::new(
+                RejectionReason::NoTarget,
+                player_xy,
+            )));
+        }
+
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Drops a quantity of the items of the stack at an index of the player's inventory onto the
+    // player's cell, which takes the player's turn (e.g. dropping 5 of a stack of 20). The drop is
+    // rejected without a turn passing if there is no stack at the index or the quantity is 0.
+    // Returns the result.
+    //---------------------------------------------------------------------------------------------
+    pub fn drop_item(&mut self, index: usize, quantity: ItemQuantity) -> Result<ServerResult> {
+        let player_xy = self.snapshot.player_xy();
+
+        if self.clock.paused() {
+            return Ok(ServerResult::Rejected(Rejection::new(RejectionReason::Paused, player_xy)));
+        }
+
+        let stack = match self.inventory.remove(index, quantity) {
+            Some(stack) => stack,
+            None => {
+                return Ok(ServerResult::Rejected(Rejection::new(
+                    RejectionReason::NoTarget,
+                    player_xy,
+                )));
+            }
+        };
+
+        self.world.fetch_mut::<Zone>().ground_items.add(player_xy, stack);
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that moves up to a quantity of the items in the player's cell that satisfy a
+    // predicate into the inventory, summarizing them in a pickup event. Automatic pickups are
+    // summarized whenever the cell has items (so that the player is told what was left). Returns
+    // whether any items were picked up.
+    //---------------------------------------------------------------------------------------------
+    fn take_ground_items<F>(
+        &mut self,
+        automatic: bool,
+        predicate: F,
+        quantity: ItemQuantity,
+    ) -> bool
+    where
+        F: FnMut(ItemKind) -> bool,
+    {
+        let (xy, picked_up, left) = {
+            let mut zone = self.world.fetch_mut::<Zone>();
+            let xy = zone.player_xy;
+            let picked_up = zone.ground_items.take(xy, predicate, quantity);
+            (xy, picked_up, zone.ground_items.get(xy).to_vec())
+        };
+
+        if picked_up.is_empty() && (!automatic || left.is_empty()) {
+            return false;
+        }
+
+        for stack in picked_up.iter() {
+            self.inventory.add(*stack);
+        }
+
+        let taken = !picked_up.is_empty();
+        self.pickup_events.push(PickupEvent { xy, automatic, picked_up, left });
+        self.handle_inventory_events();
+        taken
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that takes the inventory's events, recomputing the player's encumbrance if
+    // the inventory changed.
+    //---------------------------------------------------------------------------------------------
+    fn handle_inventory_events(&mut self) {
+        if !self.inventory.take_events().is_empty() {
+            self.refresh_encumbrance();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that recomputes the player's encumbrance from the weight of the inventory
+    // and the player's strength, updating the snapshot.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_encumbrance(&mut self) {
+        let stats = {
+            let zone = self.world.fetch::<Zone>();
+            let player = zone.actor_map.get_xy(zone.player_xy).as_ref().expect("Player missing.");
+            let stats = player.lock().expect("Failed to lock actor mutex.").stats;
+            stats
+        };
+
+        self.snapshot.set_encumbrance(Encumbrance::of(self.inventory.weight(), &stats));
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that charges the energy cost of the player's action, which already took one
+    // turn. The energy owed beyond the turn accumulates (e.g. while encumbered), and a further turn
+    // passes whenever a turn's worth is owed.
+    //---------------------------------------------------------------------------------------------
+    fn spend_action_energy(&mut self) {
+        let turns = self.energy_debt.spend(self.snapshot.encumbrance().tier);
+
+        for _ in 0..turns {
+            self.tick();
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the player's entity.
+    //---------------------------------------------------------------------------------------------
+    pub fn player_id(&self) -> u32 {
+        self.world.fetch::<Zone>().player_entity.id()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns what an actor can do in its cell and the adjacent cells, including the options that
+    // are currently invalid (and why), or nothing if the entity is not an actor.
+    //---------------------------------------------------------------------------------------------
+    pub fn available_interactions(&self, entity: u32) -> Vec<Interaction> {
+        let entity = self.world.entities().entity(entity);
+        let zone = self.world.fetch::<Zone>();
+
+        self.world.read_storage::<IsActor>().get(entity).map_or_else(Vec::new, |a| {
+            let actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+            Interaction::around(&actor, &zone)
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Carries out an interaction of the player by submitting its intention. Invalid interactions
+    // are rejected without a turn passing. Returns the result.
+    //---------------------------------------------------------------------------------------------
+    pub fn interact(&mut self, interaction: &Interaction) -> Result<ServerResult> {
+        if let InteractionValidity::Invalid(reason) = interaction.validity {
+            return Ok(ServerResult::Rejected(Rejection::new(reason, interaction.xy)));
+        }
+
+        match interaction.intention {
+            InteractionIntention::Move(direction) => self.move_player(direction),
+            InteractionIntention::CloseDoor(xy) => self.close_door(xy),
+            InteractionIntention::PickUp(filter, quantity) => self.pick_up(filter, quantity),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Uses the item at an index of the player's inventory at a target coord, which takes the
+    // player's turn. Consumed items ignore the target and affect the player. Thrown items must
+    // target a visible coord within their range and affect the actors in their area. Returns the
+    // result.
+    //---------------------------------------------------------------------------------------------
+    pub fn use_item(&mut self, index: usize, target: ICoord) -> Result<ServerResult> {
+        let player_xy = self.snapshot.player_xy();
+
+        let reason = match self.inventory.get(index).map(|stack| stack.kind.definition()) {
+            _ if self.clock.paused() => Some(RejectionReason::Paused),
+            None => Some(RejectionReason::NoTarget),
+            Some(definition) if !definition.targeted() => None,
+            Some(definition) if !definition.in_range(player_xy, target) => {
+                Some(RejectionReason::OutOfRange)
+            }
+            Some(_) if !self.snapshot.is_visible(target) => Some(RejectionReason::Blocked),
+            Some(_) => None,
+        };
+
+        if let Some(reason) = reason {
+            return Ok(ServerResult::Rejected(Rejection::new(reason, target)));
+        }
+
+        let item = self
+            .inventory
+            .remove(index, ItemQuantity::Count(1))
+            .expect("Item missing from inventory.")
+            .kind;
+        let definition = item.definition();
+        let target = if definition.targeted() { target } else { player_xy };
+        let mut factions = Vec::new();
+
+        {
+            let zone = self.world.fetch::<Zone>();
+            let mut hits = Vec::new();
+
+            for xy in definition.area(target) {
+                if !zone.actor_map.in_bounds(xy) {
+                    continue;
+                }
+
+                if let Some(shared) = zone.actor_map.get_xy(xy) {
+                    let mut actor = shared.lock().expect("Failed to lock actor mutex.");
+                    let change = definition.effect.apply(&mut actor);
+
+                    if change < 0 && actor.faction != Faction::None && xy != player_xy {
+                        factions.push(actor.faction);
+                    }
+
+                    hits.push(ItemHit { entity: actor.entity.id(), xy, change });
+                }
+            }
+
+            self.world.write_resource::<ItemEvents>().push(ItemEvent {
+                item,
+                from: player_xy,
+                to: target,
+                hits,
+            });
+        }
+
+        // Each faction hurt witnesses the attack once.
+        factions.sort();
+        factions.dedup();
+
+        for faction in factions {
+            let _ = self.record_player_deed(FactKind::PlayerAttacked(faction));
+        }
+
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the policies for resolving the player's bumps into actors.
+    //---------------------------------------------------------------------------------------------
+    pub fn bump_policies(&self) -> BumpPolicies {
+        self.bump_policies
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the policies for resolving the player's bumps into actors.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_bump_policies(&mut self, policies: BumpPolicies) {
+        self.bump_policies = policies;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that describes the bump into the actor in a direction from the player, if
+    // there is one (and the player can act).
+    //---------------------------------------------------------------------------------------------
+    fn bump_request(&self, dir: Direction) -> Option<BumpRequest> {
+        if self.clock.paused() {
+            return None;
+        }
+
+        let zone = self.world.fetch::<Zone>();
+        let xy = (zone.player_xy.0 + dir.dx(), zone.player_xy.1 + dir.dy());
+
+        if !zone.actor_map.in_bounds(xy) {
+            return None;
+        }
+
+        let actor =
+            zone.actor_map.get_xy(xy).as_ref()?.lock().expect("Failed to lock actor mutex.");
+        let standing = self.world.read_resource::<FactionStandings>().standing(actor.faction);
+        let relation = BumpRelation::of(actor.faction, standing);
+
+        Some(BumpRequest {
+            target: actor.entity.id(),
+            kind: actor.kind,
+            xy,
+            direction: dir,
+            relation,
+            actions: relation.actions().to_vec(),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Carries out an action of a bump into an actor, e.g. one chosen to answer an ambiguous bump.
+    // The action is rejected without a turn passing if the actor is no longer where it was bumped
+    // into. Returns the result.
+    //---------------------------------------------------------------------------------------------
+    pub fn resolve_bump(
+        &mut self,
+        request: &BumpRequest,
+        action: BumpAction,
+    ) -> Result<ServerResult> {
+        if self.clock.paused() {
+            return Ok(ServerResult::Rejected(Rejection::new(
+                RejectionReason::Paused,
+                request.xy,
+            )));
+        }
+
+        // The bump may have been answered after the world changed.
+        if self.bump_request(request.direction).map(|current| current.target)
+            != Some(request.target)
+            || !request.actions.contains(&action)
+        {
+            return Ok(ServerResult::Rejected(Rejection::new(
+                RejectionReason::NoTarget,
+                request.xy,
+            )));
+        }
+
+        match action {
+            BumpAction::Attack => self.player_attack(request.xy),
+            BumpAction::Swap => self.player_swap(request.xy),
+            // TODO: Start a conversation once actors have something to say.
+            BumpAction::Talk => Ok(ServerResult::Success),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that has the player attack the actor at a coord, which takes the player's
+    // turn. Attacking a member of a faction is witnessed as a deed.
+    //---------------------------------------------------------------------------------------------
+    fn player_attack(&mut self, xy: ICoord) -> Result<ServerResult> {
+        let faction = {
+            let zone = self.world.fetch::<Zone>();
+            let attacker = zone.actor_map.get_xy(zone.player_xy).as_ref().unwrap().clone();
+            let defender = zone.actor_map.get_xy(xy).as_ref().unwrap().clone();
+            let attacker = attacker.lock().expect("Failed to lock actor mutex.");
+            let mut defender = defender.lock().expect("Failed to lock actor mutex.");
+
+            let breakdown =
+                RollBreakdown::melee(&mut self.combat_rng, &attacker.stats, &defender.stats);
+
+            // TODO: Remove actors that run out of hit points.
+            defender.health.current = (defender.health.current - breakdown.damage()).max(0);
+
+            self.world.write_resource::<CombatEvents>().push(CombatEvent {
+                attacker: attacker.entity.id(),
+                attacker_name: "You".into(),
+                defender: defender.entity.id(),
+                defender_name: format!("the {}", defender.kind),
+                xy,
+                breakdown,
+            });
+
+            defender.faction
+        };
+
+        if faction != Faction::None {
+            let _ = self.record_player_deed(FactKind::PlayerAttacked(faction));
+        }
+
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that has the player trade places with the actor at a coord, which takes the
+    // player's turn.
+    //---------------------------------------------------------------------------------------------
+    fn player_swap(&mut self, xy: ICoord) -> Result<ServerResult> {
+        {
+            let mut zone = self.world.fetch_mut::<Zone>();
+            let player_xy = zone.player_xy;
+            let player = zone.actor_map.get_xy_mut(player_xy).take();
+            let other = zone.actor_map.get_xy_mut(xy).take();
+
+            if let Some(player) = &player {
+                player.lock().expect("Failed to lock actor mutex.").xy = xy;
+            }
+
+            if let Some(other) = &other {
+                other.lock().expect("Failed to lock actor mutex.").xy = player_xy;
+            }
+
+            *zone.actor_map.get_xy_mut(xy) = player;
+            *zone.actor_map.get_xy_mut(player_xy) = other;
+            zone.player_xy = xy;
+        }
+
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Closes the open door at a coord next to the player, which takes the player's turn.
+    // Returns the result.
+    //---------------------------------------------------------------------------------------------
+    pub fn close_door(&mut self, xy: ICoord) -> Result<ServerResult> {
+        {
+            let mut zone = self.world.fetch_mut::<Zone>();
+            let delta = (xy.0 - zone.player_xy.0, xy.1 - zone.player_xy.1);
+
+            let reason = if self.clock.paused() {
+                Some(RejectionReason::Paused)
+            } else if delta == (0, 0) || delta.0.abs() > 1 || delta.1.abs() > 1 {
+                Some(RejectionReason::OutOfRange)
+            } else if !zone.cell_map.in_bounds(xy)
+                || zone.cell_map.get_xy(xy).door() != Some(DoorState::Open)
+            {
+                Some(RejectionReason::NoTarget)
+            } else if zone.actor_map.get_xy(xy).is_some() {
+                Some(RejectionReason::Occupied)
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                return Ok(ServerResult::Rejected(Rejection::new(reason, xy)));
+            }
+
+            let _ = zone.set_door(xy, DoorState::Closed);
+        }
+
+        self.tick();
+        self.spend_action_energy();
+        Ok(ServerResult::Success)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns a preview of the path the player would travel to reach a coord, if reachable.
+    //---------------------------------------------------------------------------------------------
+    pub fn preview_travel(&mut self, xy: ICoord) -> Option<TravelPreview> {
+        self.snapshot.travel_preview(&mut self.travel_a_star, xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Begins traveling the player to a coord, one step per call to travel_step.
+    //---------------------------------------------------------------------------------------------
+    pub fn travel_to(&mut self, xy: ICoord) -> ServerResult {
+        match self.preview_travel(xy) {
+            Some(preview) => {
+                self.travel = Some(Travel::new(preview, &self.snapshot));
+                ServerResult::Success
+            }
+            None => {
+                self.travel = None;
+                ServerResult::Fail
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the remaining path the player is traveling, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn travel_path(&self) -> Option<&VecDeque<ICoord>> {
+        self.travel.as_ref().map(|travel| travel.path())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the player is traveling.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_traveling(&self) -> bool {
+        self.travel.is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Stops the player traveling. Returns the resulting status.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel_travel(&mut self) -> TravelStatus {
+        match self.travel.take() {
+            Some(_) => TravelStatus::Interrupted(TravelInterruption::Cancelled),
+            None => TravelStatus::Idle,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Moves the traveling player one step along their path, ticking the server.
+    //---------------------------------------------------------------------------------------------
+    pub fn travel_step(&mut self) -> Result<TravelStatus> {
+        let travel = match self.travel.as_mut() {
+            Some(travel) => travel,
+            None => return Ok(TravelStatus::Idle),
+        };
+
+        // Travel waits while the world is paused.
+        if self.clock.paused() {
+            return Ok(TravelStatus::Traveling);
+        }
+
+        let direction = match travel.next_step(&self.snapshot) {
+            Ok(Some(direction)) => direction,
+            Ok(None) => {
+                self.travel = None;
+                return Ok(TravelStatus::Arrived);
+            }
+            Err(interruption) => {
+                self.travel = None;
+                return Ok(TravelStatus::Interrupted(interruption));
+            }
+        };
+
+        if let ServerResult::Fail | ServerResult::Rejected(_) = self.walk_player(direction)? {
+            self.travel = None;
+            return Ok(TravelStatus::Interrupted(TravelInterruption::Blocked));
+        }
+
+        // Report arrival on the final step rather than waiting for another call.
+        if self.travel_path().map(|path| path.len()).unwrap_or(0) == 0 {
+            self.travel = None;
+            return Ok(TravelStatus::Arrived);
+        }
+
+        Ok(TravelStatus::Traveling)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the simulation clock (whether the world is paused, queued turns and speed).
+    //---------------------------------------------------------------------------------------------
+    pub fn clock(&self) -> &SimulationClock {
+        &self.clock
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Freezes the world: the player's actions are rejected until resumed, but turns can still be
+    // passed with step (e.g. to watch the AI turn by turn).
+    //---------------------------------------------------------------------------------------------
+    pub fn pause(&mut self) {
+        self.clock.set_paused(true);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Unfreezes the world.
+    //---------------------------------------------------------------------------------------------
+    pub fn resume(&mut self) {
+        self.clock.set_paused(false);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the world is paused.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_paused(&self) -> bool {
+        self.clock.paused()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Queues a # of turns to pass, one per call to update (paused or not).
+    //---------------------------------------------------------------------------------------------
+    pub fn step(&mut self, n_turns: u32) {
+        self.clock.queue_steps(n_turns);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the multiplier of the main loop's update rate.
+    //---------------------------------------------------------------------------------------------
+    pub fn speed(&self) -> f32 {
+        self.clock.speed()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the multiplier of the main loop's update rate (e.g. to fast-forward queued turns).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_speed(&mut self, speed: f32) {
+        self.clock.set_speed(speed);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the main loop's update interval scaled by the speed.
+    //---------------------------------------------------------------------------------------------
+    pub fn update_interval(&self, interval: Duration) -> Duration {
+        self.clock.scale_interval(interval)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Passes one queued turn, if any. Returns whether a turn passed.
+    // (should be called once per update, so that the speed controls how fast queued turns pass)
+    //---------------------------------------------------------------------------------------------
+    pub fn update(&mut self) -> bool {
+        if !self.clock.take_step() {
+            return false;
+        }
+
+        self.tick();
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Runs a debug command, returning a description of the resulting simulation clock.
+    //---------------------------------------------------------------------------------------------
+    pub fn run_debug_command(&mut self, command: DebugCommand) -> String {
+        match command {
+            DebugCommand::Pause => self.pause(),
+            DebugCommand::Resume => {
+                self.clock.cancel_steps();
+                self.resume();
+            }
+            DebugCommand::TogglePause => {
+                let paused = self.is_paused();
+                self.clock.set_paused(!paused);
+            }
+            DebugCommand::Step(turns) => self.step(turns),
+            DebugCommand::Speed(speed) => self.set_speed(speed),
+            DebugCommand::ScaleSpeed(scale) => self.set_speed(self.speed() * scale),
+        }
+
+        format!(
+            "{} at {}x speed ({} turns queued).",
+            if self.is_paused() { "Paused" } else { "Running" },
+            self.speed(),
+            self.clock.pending_steps()
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Allow one "tick", or turn, to pass in the server.
+    //---------------------------------------------------------------------------------------------
+    pub fn tick(&mut self) {
+        let tick_start = Instant::now();
+        self.stats.begin_tick();
+
+        // Pick up edits to the scripts while the game is running.
+        #[cfg(debug_assertions)]
+        let _ = SCRIPTS.reload_changed();
+
+        // Drop rejections left over from previous turns.
+        // (they are only reported for the turn the player's move was resolved in)
+        self.world.write_resource::<Rejections>().clear();
+        self.world.fetch_mut::<Zone>().turn += 1;
+
+        // Run the systems.
+        Self::run_system(&mut self.world, &mut self.goals_system, "goals", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.door_system, "doors", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.move_system, "move", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.decal_system, "decals", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.scent_system, "scent", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.gossip_system, "gossip", &mut self.stats);
+        Self::run_system(&mut self.world, &mut self.charges_system, "charges", &mut self.stats);
+        Self::run_system(
+            &mut self.world,
+            &mut self.zone_script_system,
+            "scripts",
+            &mut self.stats,
+        );
+        Self::append_events(
+            &mut self.charge_events,
+            &mut self.world.write_resource::<ChargeEvents>(),
+            MAX_PENDING_CHARGE_EVENTS,
+        );
+        Self::append_events(
+            &mut self.combat_events,
+            &mut self.world.write_resource::<CombatEvents>(),
+            MAX_PENDING_COMBAT_EVENTS,
+        );
+        Self::append_events(
+            &mut self.item_events,
+            &mut self.world.write_resource::<ItemEvents>(),
+            MAX_PENDING_ITEM_EVENTS,
+        );
+
+        // Recompute what depends on the inventory if it changed (e.g. through inventory_mut).
+        self.handle_inventory_events();
+
+        // Refresh zone navigation maps and fov.
+        let start = Instant::now();
+        self.world.fetch_mut::<Zone>().refresh();
+        self.stats.record("zone refresh", start.elapsed(), SystemCounters::default());
+
+        // Refresh the snapshot of what the player perceives.
+        let start = Instant::now();
+        self.snapshot.refresh(&self.world.fetch::<Zone>());
+        self.stats.record("snapshot", start.elapsed(), SystemCounters::default());
+
+        // Record the monster kinds the player now sees.
+        self.record_sightings();
+
+        // Reset the per-turn pools.
+        self.stats.pool_stats = self.world.write_resource::<TurnPools>().end_turn();
+
+        // Count the entities remaining at the end of the tick.
+        self.stats.entities = self.world.entities().join().count();
+        self.stats.actors = self.world.read_storage::<IsActor>().join().count();
+        self.stats.duration = tick_start.elapsed();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that runs a system, recording its timing and counters.
+    //---------------------------------------------------------------------------------------------
+    fn run_system<S>(
+        world: &mut World,
+        system: &mut S,
+        name: &'static str,
+        stats: &mut ServerStats,
+    ) where
+        S: for<'a> RunNow<'a>,
+    {
+        *world.write_resource::<SystemCounters>() = SystemCounters::default();
+
+        let start = Instant::now();
+        system.run_now(world);
+        world.maintain();
+
+        let counters = *world.read_resource::<SystemCounters>();
+        stats.record(name, start.elapsed(), counters);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that moves the events produced by a tick into those pending a poll, dropping
+    // the oldest pending events beyond a max.
+    //---------------------------------------------------------------------------------------------
+    fn append_events<T>(pending: &mut Vec<T>, events: &mut Vec<T>, max: usize) {
+        pending.append(events);
+
+        if pending.len() > max {
+            let excess = pending.len() - max;
+            let _ = pending.drain(..excess);
+        }
     }
 }
@@ -0,0 +1,119 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fmt;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Counters that a system increments while running, inserted into the world as a resource.
+// The system runner resets them before each system and collects them after.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemCounters {
+    // Number of entities the system processed.
+    pub entities: usize,
+    // Number of events (e.g. components inserted for other systems) the system produced.
+    pub events: usize,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Timing and counters for one system (or other stage of a tick).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemStats {
+    // Name of the system.
+    pub name: &'static str,
+    // Time the system took to run.
+    pub duration: Duration,
+    // Counters collected from the system.
+    pub counters: SystemCounters,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ServerStats describes the last tick of the server, for the debug gui and profiling.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct ServerStats {
+    // Number of ticks that have passed.
+    pub ticks: u64,
+    // Time the last tick took.
+    pub duration: Duration,
+    // Stats for each stage of the last tick, in the order they ran.
+    pub systems: Vec<SystemStats>,
+    // Number of live entities at the end of the last tick.
+    pub entities: usize,
+    // Number of actors at the end of the last tick.
+    pub actors: usize,
+    // Allocation counters of the per-turn pools for the last tick.
+    pub pool_stats: PoolStats,
+}
+
+impl ServerStats {
+    //---------------------------------------------------------------------------------------------
+    // Clears the per-tick stats before a tick is run.
+    //---------------------------------------------------------------------------------------------
+    pub fn begin_tick(&mut self) {
+        self.ticks += 1;
+        self.duration = Duration::default();
+        self.systems.clear();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records the stats of a stage of the current tick.
+    //---------------------------------------------------------------------------------------------
+    pub fn record(&mut self, name: &'static str, duration: Duration, counters: SystemCounters) {
+        self.systems.push(SystemStats { name, duration, counters });
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the stats of a stage of the last tick by name.
+    //---------------------------------------------------------------------------------------------
+    pub fn system(&self, name: &str) -> Option<&SystemStats> {
+        self.systems.iter().find(|system| system.name == name)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the total number of events produced in the last tick.
+    //---------------------------------------------------------------------------------------------
+    pub fn events(&self) -> usize {
+        self.systems.iter().map(|system| system.counters.events).sum()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Impl Display for ServerStats, one line per stage.
+//-------------------------------------------------------------------------------------------------
+impl fmt::Display for ServerStats {
+    //---------------------------------------------------------------------------------------------
+    // Formats the server stats.
+    //---------------------------------------------------------------------------------------------
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "tick {}: {:.3}ms, {} entities, {} actors, {} pool allocations",
+            self.ticks,
+            self.duration.as_secs_f64() * 1000.0,
+            self.entities,
+            self.actors,
+            self.pool_stats.allocations,
+        )?;
+
+        for system in self.systems.iter() {
+            writeln!(
+                f,
+                "  {}: {:.3}ms, {} entities, {} events",
+                system.name,
+                system.duration.as_secs_f64() * 1000.0,
+                system.counters.entities,
+                system.counters.events,
+            )?;
+        }
+
+        Ok(())
+    }
+}
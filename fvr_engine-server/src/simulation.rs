@@ -0,0 +1,220 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::str::FromStr;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, bail, Context, Error, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Bounds of the simulation speed multiplier.
+pub const MIN_SIMULATION_SPEED: f32 = 0.125;
+pub const MAX_SIMULATION_SPEED: f32 = 16.0;
+
+//-------------------------------------------------------------------------------------------------
+// SimulationClock describes how the server's world advances: whether it is paused, how many
+// turns have been queued to step through, and how fast the main loop should update it.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimulationClock {
+    // Whether the world is frozen (the player's actions are rejected).
+    paused: bool,
+    // Turns queued to pass, one per update.
+    pending_steps: u32,
+    // Multiplier of the main loop's update rate.
+    speed: f32,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self { paused: false, pending_steps: 0, speed: 1.0 }
+    }
+}
+
+impl SimulationClock {
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the world is paused.
+    //---------------------------------------------------------------------------------------------
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Pauses or resumes the world.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of turns queued to pass.
+    //---------------------------------------------------------------------------------------------
+    pub fn pending_steps(&self) -> u32 {
+        self.pending_steps
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Queues a # of turns to pass, one per update.
+    //---------------------------------------------------------------------------------------------
+    pub fn queue_steps(&mut self, turns: u32) {
+        self.pending_steps = self.pending_steps.saturating_add(turns);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Dequeues a turn, returning whether one was queued.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_step(&mut self) -> bool {
+        if self.pending_steps == 0 {
+            return false;
+        }
+
+        self.pending_steps -= 1;
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Drops any queued turns.
+    //---------------------------------------------------------------------------------------------
+    pub fn cancel_steps(&mut self) {
+        self.pending_steps = 0;
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the multiplier of the main loop's update rate.
+    //---------------------------------------------------------------------------------------------
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the multiplier of the main loop's update rate (clamped to the supported range).
+    //---------------------------------------------------------------------------------------------
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = if speed.is_finite() {
+            speed.clamp(MIN_SIMULATION_SPEED, MAX_SIMULATION_SPEED)
+        } else {
+            1.0
+        };
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an update interval scaled by the speed (e.g. half the interval at double speed).
+    //---------------------------------------------------------------------------------------------
+    pub fn scale_interval(&self, interval: Duration) -> Duration {
+        interval.div_f32(self.speed)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the debug commands controlling the simulation clock.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DebugCommand {
+    // Freeze the world.
+    Pause,
+    // Unfreeze the world.
+    Resume,
+    // Freeze or unfreeze the world.
+    TogglePause,
+    // Queue a # of turns to pass.
+    Step(u32),
+    // Set the multiplier of the update rate.
+    Speed(f32),
+    // Multiply the current multiplier of the update rate.
+    ScaleSpeed(f32),
+}
+
+impl FromStr for DebugCommand {
+    type Err = Error;
+
+    //---------------------------------------------------------------------------------------------
+    // Parses a debug command, e.g. "pause", "resume", "toggle_pause", "step 10", "speed 2.0" or
+    // "scale_speed 0.5".
+    //---------------------------------------------------------------------------------------------
+    fn from_str(s: &str) -> Result<Self> {
+        let mut words = s.split_whitespace();
+        let name = words.next().ok_or_else(|| anyhow!("Empty debug command."))?;
+        let argument = words.next();
+
+        if words.next().is_some() {
+            bail!("Too many arguments for debug command '{}'.", name);
+        }
+
+        let command = match (name, argument) {
+            ("pause", None) => DebugCommand::Pause,
+            ("resume", None) => DebugCommand::Resume,
+            ("toggle_pause", None) => DebugCommand::TogglePause,
+            ("step", None) => DebugCommand::Step(1),
+            ("step", Some(turns)) => DebugCommand::Step(
+                turns.parse().with_context(|| format!("Invalid # of turns '{}'.", turns))?,
+            ),
+            ("speed", Some(speed)) => DebugCommand::Speed(
+                speed.parse().with_context(|| format!("Invalid speed '{}'.", speed))?,
+            ),
+            ("scale_speed", Some(scale)) => DebugCommand::ScaleSpeed(
+                scale.parse().with_context(|| format!("Invalid speed scale '{}'.", scale))?,
+            ),
+            _ => bail!("Unknown debug command '{}'.", s.trim()),
+        };
+
+        Ok(command)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_simulation_clock() {
+    let mut clock = SimulationClock::default();
+    assert!(!clock.paused());
+    assert!(!clock.take_step());
+
+    // Queued turns are taken one at a time.
+    clock.queue_steps(2);
+    assert!(clock.take_step());
+    assert_eq!(clock.pending_steps(), 1);
+    clock.queue_steps(u32::MAX);
+    assert_eq!(clock.pending_steps(), u32::MAX);
+    clock.cancel_steps();
+    assert!(!clock.take_step());
+
+    // The speed is clamped to the supported range, and scales the update interval.
+    clock.set_speed(2.0);
+    assert_eq!(clock.scale_interval(Duration::from_secs(1)), Duration::from_millis(500));
+    clock.set_speed(1000.0);
+    assert_eq!(clock.speed(), MAX_SIMULATION_SPEED);
+    clock.set_speed(0.0);
+    assert_eq!(clock.speed(), MIN_SIMULATION_SPEED);
+    clock.set_speed(f32::NAN);
+    assert_eq!(clock.speed(), 1.0);
+}
+
+#[test]
+fn test_debug_command_parse() {
+    let parse = |s: &str| s.parse::<DebugCommand>().ok();
+
+    assert_eq!(parse("pause"), Some(DebugCommand::Pause));
+    assert_eq!(parse(" resume "), Some(DebugCommand::Resume));
+    assert_eq!(parse("toggle_pause"), Some(DebugCommand::TogglePause));
+    assert_eq!(parse("step"), Some(DebugCommand::Step(1)));
+    assert_eq!(parse("step 10"), Some(DebugCommand::Step(10)));
+    assert_eq!(parse("speed 2.5"), Some(DebugCommand::Speed(2.5)));
+    assert_eq!(parse("scale_speed 0.5"), Some(DebugCommand::ScaleSpeed(0.5)));
+
+    // Malformed commands are rejected.
+    assert_eq!(parse(""), None);
+    assert_eq!(parse("pause now"), None);
+    assert_eq!(parse("step -1"), None);
+    assert_eq!(parse("speed"), None);
+    assert_eq!(parse("scale_speed fast"), None);
+    assert_eq!(parse("step 1 2"), None);
+    assert_eq!(parse("rewind"), None);
+}
@@ -0,0 +1,414 @@
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::{prelude::*, xy_iter, xy_tuple_iter};
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::actor::*;
+use crate::crowd::*;
+use crate::door::*;
+use crate::encumbrance::*;
+use crate::travel::*;
+use crate::zone::*;
+
+//-------------------------------------------------------------------------------------------------
+// An entity perceived by the player.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct SnapshotEntity {
+    // Stable id of the entity (unique while the entity is alive).
+    pub id: u32,
+    // Position of the entity in the zone.
+    pub xy: ICoord,
+    // Visual tile of the entity.
+    pub tile: Tile,
+    // Hit points of the entity.
+    pub health: ActorHealth,
+    // Priority of the entity's turns (e.g. the order in which its moves are resolved).
+    pub priority: u8,
+}
+
+//-------------------------------------------------------------------------------------------------
+// WorldSnapshot is the view of the world the server produces for the client each update.
+// It only contains what the player can currently perceive: cells outside of the player's fov are
+// blank and unseen entities are omitted, so the client never has access to hidden state.
+//-------------------------------------------------------------------------------------------------
+pub struct WorldSnapshot {
+    // Dimensions of the zone.
+    dimensions: ICoord,
+    // Position of the player in the zone.
+    player_xy: ICoord,
+    // Visual tiles of the perceived cells.
+    tiles: GridMap<Tile>,
+    // Visibility of each cell (0.0 is not visible).
+    visibility: GridMap<f32>,
+    // Whether each perceived cell blocks movement.
+    passability: GridMap<Passability>,
+    // Passability used for travel paths (perceived entities other than the player are blocked).
+    travel_passability: GridMap<Passability>,
+    // Whether each perceived cell holds a closed door.
+    closed_doors: GridMap<bool>,
+    // Perceived cells with an ambient animation, and the ids of their animations.
+    animated: Vec<(ICoord, AmbientAnimationId)>,
+    // Perceived entities.
+    entities: Vec<SnapshotEntity>,
+    // Index into the entities vec for each cell.
+    entity_map: GridMap<Option<usize>>,
+    // Indices into the entities vec, in the order the entities take their turns.
+    turn_order: Vec<usize>,
+    // What the player carries relative to what they can carry.
+    encumbrance: Encumbrance,
+    // Markers of the player's status.
+    status_markers: Vec<StatusMarker>,
+}
+
+impl WorldSnapshot {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new snapshot of a zone.
+    //---------------------------------------------------------------------------------------------
+    pub(crate) fn new(zone: &Zone) -> Self {
+        let mut snapshot = Self {
+            dimensions: zone.dimensions,
+            player_xy: zone.player_xy,
+            tiles: GridMap::new(zone.dimensions),
+            visibility: GridMap::new(zone.dimensions),
+            passability: GridMap::new(zone.dimensions),
+            travel_passability: GridMap::new(zone.dimensions),
+            closed_doors: GridMap::new(zone.dimensions),
+            animated: Vec::new(),
+            entities: Vec::new(),
+            entity_map: GridMap::new(zone.dimensions),
+            turn_order: Vec::new(),
+            encumbrance: Encumbrance::default(),
+            status_markers: Vec::new(),
+        };
+
+        snapshot.refresh(zone);
+        snapshot
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Refreshes the snapshot from the current state of a zone, reusing the existing allocations.
+    //---------------------------------------------------------------------------------------------
+    pub(crate) fn refresh(&mut self, zone: &Zone) {
+        // Resize the maps if the zone has changed.
+        if self.dimensions != zone.dimensions {
+            self.dimensions = zone.dimensions;
+            self.tiles.resize(zone.dimensions);
+            self.visibility.resize(zone.dimensions);
+            self.passability.resize(zone.dimensions);
+            self.travel_passability.resize(zone.dimensions);
+            self.closed_doors.resize(zone.dimensions);
+            self.entity_map.resize(zone.dimensions);
+            self.entity_map.data_mut().fill(None);
+            self.entities.clear();
+        }
+
+        self.player_xy = zone.player_xy;
+        self.animated.clear();
+
+        // Copy the perceived cells.
+        xy_tuple_iter!(x, y, self.dimensions, {
+            let visibility = *zone.player_fov.get_xy((x, y));
+            *self.visibility.get_xy_mut((x, y)) = visibility;
+
+            if visibility > 0.0 {
+                let cell = zone.cell_map.get_xy((x, y));
+                let tile = self.tiles.get_xy_mut((x, y));
+                *tile = cell.things.last().map(|thing| thing.tile).unwrap_or_default();
+
+                if let Some(glyph) = zone.autotile_glyph((x, y)) {
+                    tile.glyph = glyph;
+                }
+
+                if let Some(id) = cell.things.last().and_then(|thing| thing.animation) {
+                    self.animated.push(((x, y), id));
+                }
+
+                if let Some(decal) = zone.decals.get((x, y)) {
+                    decal.apply(tile, cell.passability() == Passability::Passable);
+                }
+                *self.passability.get_xy_mut((x, y)) = cell.passability();
+                *self.closed_doors.get_xy_mut((x, y)) = cell.door() == Some(DoorState::Closed);
+            } else {
+                *self.tiles.get_xy_mut((x, y)) = Tile::default();
+                *self.passability.get_xy_mut((x, y)) = Passability::Passable;
+                *self.closed_doors.get_xy_mut((x, y)) = false;
+            }
+        });
+
+        // Clear the previously perceived entities.
+        for entity in self.entities.drain(..) {
+            if self.entity_map.in_bounds(entity.xy) {
+                *self.entity_map.get_xy_mut(entity.xy) = None;
+            }
+        }
+
+        // Copy the perceived entities.
+        for actor in zone.actor_map.data().iter().flatten() {
+            let actor = actor.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            if *self.visibility.get_xy(actor.xy) <= 0.0 {
+                continue;
+            }
+
+            *self.entity_map.get_xy_mut(actor.xy) = Some(self.entities.len());
+            self.entities.push(SnapshotEntity {
+                id: actor.entity.id(),
+                xy: actor.xy,
+                tile: actor.thing.tile,
+                health: actor.health,
+                priority: actor.stats.DEX,
+            });
+        }
+
+        // Order the perceived entities as the systems process them (by id) to take their turns.
+        let player_xy = self.player_xy;
+        let entities = &self.entities;
+        self.turn_order.clear();
+        self.turn_order.extend(0..entities.len());
+        self.turn_order.sort_by_key(|&i| {
+            let entity = &entities[i];
+            turn_order_key(entity.xy == player_xy, entity.priority, entity.id as usize)
+        });
+
+        // Entities are drawn over cells, so cells under them are not animated.
+        let entity_map = &self.entity_map;
+        self.animated.retain(|(xy, _)| entity_map.get_xy(*xy).is_none());
+
+        // Refresh the travel passability, blocking perceived entities other than the player.
+        self.travel_passability.data_mut().copy_from_slice(self.passability.data());
+
+        for entity in self.entities.iter() {
+            if entity.xy != self.player_xy {
+                *self.travel_passability.get_xy_mut(entity.xy) = Passability::Blocked;
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the dimensions of the zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn dimensions(&self) -> ICoord {
+        self.dimensions
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the position of the player in the zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn player_xy(&self) -> ICoord {
+        self.player_xy
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the visibility of a coord (0.0 if not visible or out of bounds).
+    //---------------------------------------------------------------------------------------------
+    pub fn visibility(&self, xy: ICoord) -> f32 {
+        if self.visibility.in_bounds(xy) {
+            *self.visibility.get_xy(xy)
+        } else {
+            0.0
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a coord is visible to the player.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_visible(&self, xy: ICoord) -> bool {
+        self.visibility(xy) > 0.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the perceived entities.
+    //---------------------------------------------------------------------------------------------
+    pub fn entities(&self) -> &[SnapshotEntity] {
+        &self.entities
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns what the player carries relative to what they can carry.
+    //---------------------------------------------------------------------------------------------
+    pub fn encumbrance(&self) -> Encumbrance {
+        self.encumbrance
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the markers of the player's status.
+    //---------------------------------------------------------------------------------------------
+    pub fn status_markers(&self) -> &[StatusMarker] {
+        &self.status_markers
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Sets the player's encumbrance, along with the status markers applied by it.
+    //---------------------------------------------------------------------------------------------
+    pub(crate) fn set_encumbrance(&mut self, encumbrance: Encumbrance) {
+        self.encumbrance = encumbrance;
+        self.status_markers.clear();
+        self.status_markers.extend(encumbrance.tier.marker());
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the perceived entities in the order they take their turns (the player first).
+    //---------------------------------------------------------------------------------------------
+    pub fn turn_order(&self) -> impl Iterator<Item = &SnapshotEntity> + '_ {
+        self.turn_order.iter().map(move |i| &self.entities[*i])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the perceived cells with an ambient animation, and the ids of their animations.
+    //---------------------------------------------------------------------------------------------
+    pub fn animated_cells(&self) -> &[(ICoord, AmbientAnimationId)] {
+        &self.animated
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the perceived entity at a coord, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn entity_at(&self, xy: ICoord) -> Option<&SnapshotEntity> {
+        if !self.entity_map.in_bounds(xy) {
+            return None;
+        }
+
+        self.entity_map.get_xy(xy).map(|index| &self.entities[index])
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the perceived tile at a coord (entities are drawn over cells).
+    //---------------------------------------------------------------------------------------------
+    pub fn tile(&self, xy: ICoord) -> Tile {
+        match self.entity_at(xy) {
+            Some(entity) => entity.tile,
+            None if self.tiles.in_bounds(xy) => *self.tiles.get_xy(xy),
+            None => Tile::default(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Determines whether a coord is known to be blocked.
+    // (unseen coords in bounds are assumed to be open)
+    //---------------------------------------------------------------------------------------------
+    pub fn is_blocked(&self, xy: ICoord) -> bool {
+        if !self.passability.in_bounds(xy) {
+            return true;
+        }
+
+        !self.passability.get_xy(xy).passable() || self.entity_at(xy).is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a coord is known to hold a closed door.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_closed_door(&self, xy: ICoord) -> bool {
+        self.closed_doors.in_bounds(xy) && *self.closed_doors.get_xy(xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Calculates a path from the player to a coord through cells not known to be blocked.
+    // Perceived entities are routed around, and closed doors are opened along the way.
+    //---------------------------------------------------------------------------------------------
+    pub fn travel_preview(&self, a_star: &mut AStar, xy: ICoord) -> Option<TravelPreview> {
+        if xy == self.player_xy || !self.travel_passability.in_bounds(xy) {
+            return None;
+        }
+
+        let cost = |xy| {
+            if self.travel_passability.get_xy(xy).passable() {
+                Some(1.0)
+            } else if self.is_closed_door(xy) && self.entity_at(xy).is_none() {
+                Some(DOOR_PATH_COST)
+            } else {
+                None
+            }
+        };
+
+        let mut path = Vec::new();
+        a_star.push_path_with_cost(self.player_xy, xy, self.dimensions, cost, &mut path);
+
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(TravelPreview::from_a_star_path(path))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies a section of the snapshot into a map2d.
+    // Returns the offset from the origin of the zone of the blit.
+    //---------------------------------------------------------------------------------------------
+    pub fn blit<M>(
+        &self,
+        terminal: &mut M,
+        src: &Rect,
+        dest_origin: ICoord,
+        show_fov: bool,
+    ) -> ICoord
+    where
+        M: Map2d<Tile>,
+    {
+        // Iterate through each of the visible tiles, updating them from the snapshot.
+        xy_iter!(x, y, src.width, src.height, {
+            // Calculate the adjusted coord.
+            let src_xy = (src.x + x, src.y + y);
+            let dst_xy = (dest_origin.0 + x, dest_origin.1 + y);
+
+            // Update the tile.
+            let tile = terminal.get_xy_mut(dst_xy);
+            *tile = self.tile(src_xy);
+
+            // Optionally adjust for Fov.
+            if show_fov {
+                tile.foreground_opacity = self.visibility(src_xy);
+                tile.outline_opacity = tile.foreground_opacity;
+            }
+        });
+
+        // Return the visible offset from the origin of the zone.
+        (src.x, src.y)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Copies the section of the snapshot in the view of a camera into a map2d at an origin.
+    //---------------------------------------------------------------------------------------------
+    pub fn blit_camera<M>(
+        &self,
+        camera: &Camera,
+        terminal: &mut M,
+        dest_origin: ICoord,
+        show_fov: bool,
+    ) where
+        M: Map2d<Tile>,
+    {
+        // Copy the perceived cells, then draw the perceived entities over them.
+        camera.blit(&self.tiles, terminal, dest_origin, &Tile::default());
+
+        for entity in self.entities.iter() {
+            if let Some(view_xy) = camera.map_to_view(entity.xy) {
+                let dst_xy = (dest_origin.0 + view_xy.0, dest_origin.1 + view_xy.1);
+
+                if terminal.in_bounds(dst_xy) {
+                    *terminal.get_xy_mut(dst_xy) = entity.tile;
+                }
+            }
+        }
+
+        // Optionally adjust for Fov.
+        if !show_fov {
+            return;
+        }
+
+        let view = camera.view();
+
+        xy_iter!(x, y, view.width, view.height, {
+            let dst_xy = (dest_origin.0 + x, dest_origin.1 + y);
+
+            if terminal.in_bounds(dst_xy) {
+                let tile = terminal.get_xy_mut(dst_xy);
+                tile.foreground_opacity = self.visibility(view.insert_xy((x, y)));
+                tile.outline_opacity = tile.foreground_opacity;
+            }
+        });
+    }
+}
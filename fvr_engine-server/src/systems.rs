@@ -1,7 +1,7 @@
 //-------------------------------------------------------------------------------------------------
-// STD Includes.
+// STD includes.
 //-------------------------------------------------------------------------------------------------
-use std::mem::transmute;
+use std::collections::HashMap;
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
@@ -16,11 +16,61 @@ use fvr_engine_core::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+use crate::combat::*;
 use crate::components::*;
+use crate::crowd::*;
+use crate::decal::*;
+use crate::door::*;
 use crate::goals::*;
 use crate::intentions::*;
+use crate::item::*;
+use crate::knowledge::*;
+use crate::rejection::*;
+use crate::scent::*;
+use crate::scripting::*;
+use crate::server_stats::*;
 use crate::zone::*;
 
+//-------------------------------------------------------------------------------------------------
+// Pools and arenas of temporaries used by the systems each turn, inserted into the world as a
+// resource.
+// NOTE: The zone's dijkstra maps are not pooled here, as each map already keeps its edge sets
+//       between calculations (they only allocate when a zone is created).
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct TurnPools {
+    // Pool of vecs for collecting move requests.
+    pub moves: Pool<MoveRequests>,
+    // Pool of vecs for collecting the moving actors.
+    pub movers: Pool<Vec<(SharedActor, f32, MoveGradient)>>,
+    // Pool of vecs for collecting the actors' move intentions.
+    pub intents: Pool<Vec<MoveIntent>>,
+    // Pool of temporaries for resolving the move intentions.
+    pub resolutions: Pool<MoveResolution>,
+    // Arena of the facts each actor retells this turn.
+    pub retold_facts: FrameArena<Fact>,
+}
+
+impl TurnPools {
+    //---------------------------------------------------------------------------------------------
+    // Ends the turn for each of the pools and arenas, returning the combined counters of the turn.
+    //---------------------------------------------------------------------------------------------
+    pub fn end_turn(&mut self) -> PoolStats {
+        [
+            self.moves.end_turn(),
+            self.movers.end_turn(),
+            self.intents.end_turn(),
+            self.resolutions.end_turn(),
+            self.retold_facts.end_turn(),
+        ]
+        .iter()
+        .fold(PoolStats::default(), |total, stats| PoolStats {
+            allocations: total.allocations + stats.allocations,
+            reuses: total.reuses + stats.reuses,
+        })
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // The goals system maintains the goals stack for an actor based on their intention.
 //-------------------------------------------------------------------------------------------------
@@ -30,33 +80,61 @@ impl<'a> System<'a> for GoalsSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         WriteExpect<'a, Zone>,
-        Read<'a, LazyUpdate>,
+        Write<'a, TurnPools>,
+        Write<'a, SystemCounters>,
         ReadExpect<'a, Intentions>,
         WriteStorage<'a, IsActor>,
         WriteStorage<'a, HasGoals>,
+        WriteStorage<'a, WantsToMove>,
     );
 
     //---------------------------------------------------------------------------------------------
     // Specs system run impl.
-    // Ensures the actor's goals vec is populated from their intention, and cleans and goals that
-    // are complete or failed.
+    // Ensures the actor's goals vec is populated from their intention, arbitrates which goal to
+    // pursue, and cleans and goals that are complete or failed.
     //---------------------------------------------------------------------------------------------
     fn run(
         &mut self,
-        (mut zone, updater, intentions, mut is_actor, mut has_goals): Self::SystemData,
+        (
+            mut zone,
+            mut pools,
+            mut counters,
+            intentions,
+            mut is_actor,
+            mut has_goals,
+            mut wants_to_move,
+        ): Self::SystemData,
     ) {
+        let mut moves = pools.moves.take();
+
         for (a, h) in (&mut is_actor, &mut has_goals).join() {
+            counters.entities += 1;
+
             // Aquire a mutable ref to the actor.
             let mut actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
 
             // If there are no current goals, populate some from the actor's intention.
+            let intention = intentions[actor.intention].as_ref();
+
+            // Expire the blackboard entries that have outlived their ttl.
+            let _ = actor.blackboard.tick();
+
             if h.goals.is_empty() {
-                intentions[actor.intention].as_ref().bored(&mut actor, &zone, &mut h.goals);
+                intention.bored(&mut actor, &zone, &mut h.goals);
+            }
+
+            // Let the intention react to the zone, then choose the goal to pursue.
+            intention.react(&mut actor, &zone, &mut h.goals);
+            h.choice = arbitrate_goals(&mut h.goals, &actor, &zone);
+
+            // Wait if none of the goals are applicable.
+            if h.choice.is_none() {
+                continue;
             }
 
-            // Update the current goal.
+            // Update the chosen goal.
             let state = match h.goals.last_mut() {
-                Some(goal) => goal.update(&mut actor, &mut zone, &updater),
+                Some(goal) => goal.update(&mut actor, &mut zone, &mut moves),
                 None => panic!("Goal vec empty!"),
             };
 
@@ -65,76 +143,438 @@ impl<'a> System<'a> for GoalsSystem {
                 let _ = h.goals.pop();
             }
         }
+
+        // Flag the actors for moving and return the vec to the pool.
+        counters.events += moves.len();
+
+        for (entity, component) in moves.drain(..) {
+            let _ = wants_to_move.insert(entity, component);
+        }
+
+        pools.moves.give(moves);
     }
 }
 
 //-------------------------------------------------------------------------------------------------
-// The move system handles actor movement within the zone.
+// The door system turns moves into closed doors into opening them (which takes the turn) for
+// actors capable of it, and has actors close the doors they opened once they have passed through.
 //-------------------------------------------------------------------------------------------------
-pub struct MoveSystem {
-    // Vec for use in sorting movement by priority.
-    cache: Vec<(&'static mut IsActor, &'static mut WantsToMove)>,
+#[derive(Default)]
+pub struct DoorSystem {
+    // Vec for collecting the entities that opened a door this turn.
+    opened: Vec<Entity>,
 }
 
-impl Default for MoveSystem {
+impl<'a> System<'a> for DoorSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Zone>,
+        Write<'a, SystemCounters>,
+        ReadStorage<'a, IsActor>,
+        WriteStorage<'a, WantsToMove>,
+    );
+
     //---------------------------------------------------------------------------------------------
-    // Default impl.
+    // Specs system run impl.
+    // Closes the doors passed through, then opens the doors actors want to move into.
     //---------------------------------------------------------------------------------------------
-    fn default() -> Self {
-        Self { cache: Vec::new() }
+    fn run(
+        &mut self,
+        (entities, mut zone, mut counters, is_actor, mut wants_to_move): Self::SystemData,
+    ) {
+        // Close the doors behind the actors (other than the player) that opened them.
+        for (entity, a) in (&entities, &is_actor).join() {
+            if entity == zone.player_entity {
+                continue;
+            }
+
+            counters.entities += 1;
+            let mut actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            if let Some(door) = actor.blackboard.get::<ICoord>(OPENED_DOOR_KEY) {
+                if actor.xy == door {
+                    actor.blackboard.set(PASSING_DOOR_KEY, door);
+                    let _ = actor.blackboard.remove(OPENED_DOOR_KEY);
+                }
+            }
+
+            if let Some(door) = actor.blackboard.get::<ICoord>(PASSING_DOOR_KEY) {
+                if actor.xy != door {
+                    let _ = actor.blackboard.remove(PASSING_DOOR_KEY);
+
+                    if zone.set_door(door, DoorState::Closed) {
+                        counters.events += 1;
+                    }
+                }
+            }
+        }
+
+        // Open the doors the actors want to move into.
+        for (entity, a, m) in (&entities, &is_actor, &wants_to_move).join() {
+            let mut actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+            let target = (actor.xy.0 + m.direction.dx(), actor.xy.1 + m.direction.dy());
+
+            if !actor.capabilities.open_doors || !zone.closed_door_at(target) {
+                continue;
+            }
+
+            if zone.set_door(target, DoorState::Open) {
+                actor.blackboard.set_with_ttl(OPENED_DOOR_KEY, target, OPENED_DOOR_MEMORY);
+                self.opened.push(entity);
+                counters.events += 1;
+            }
+        }
+
+        // Opening a door takes the turn.
+        for entity in self.opened.drain(..) {
+            let _ = wants_to_move.remove(entity);
+        }
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// The move system handles actor movement within the zone.
+//-------------------------------------------------------------------------------------------------
+pub struct MoveSystem;
+
 impl<'a> System<'a> for MoveSystem {
     #[allow(clippy::type_complexity)]
-    type SystemData =
-        (WriteExpect<'a, Zone>, WriteStorage<'a, IsActor>, WriteStorage<'a, WantsToMove>);
+    type SystemData = (
+        WriteExpect<'a, Zone>,
+        Write<'a, TurnPools>,
+        Write<'a, SystemCounters>,
+        Write<'a, Rejections>,
+        ReadStorage<'a, IsActor>,
+        WriteStorage<'a, WantsToMove>,
+    );
 
     //---------------------------------------------------------------------------------------------
     // Specs system run impl.
-    // Ensures the actor's goals vec is populated from their intention, and cleans and goals that
-    // are complete or failed.
+    // Collects the movement intentions of the turn, resolves conflicts between them and then moves
+    // the actors.
     //---------------------------------------------------------------------------------------------
-    fn run(&mut self, (mut zone, mut is_actor, mut wants_to_move): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut zone, mut pools, mut counters, mut rejections, is_actor, mut wants_to_move): Self::SystemData,
+    ) {
+        let mut actors = pools.movers.take();
+        let mut intents = pools.intents.take();
+        let mut resolution = pools.resolutions.take();
+
+        // Collect the movement intentions.
+        for (a, m) in (&is_actor, &wants_to_move).join() {
+            let actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            intents.push(MoveIntent {
+                from: actor.xy,
+                direction: m.direction,
+                gradient: m.gradient,
+                priority: m.priority,
+                player: actor.entity == zone.player_entity,
+            });
+            actors.push((a.0.clone(), m.weight, m.gradient));
+        }
+
+        counters.entities += intents.len();
+
+        // Resolve the conflicts between the intentions.
+        let outcomes = resolve_moves(&zone, &intents, &mut resolution);
+
+        // Take the moving actors out of the actor map before placing them, so that actors can
+        // follow and swap with each other.
+        for (intent, outcome) in intents.iter().zip(outcomes.iter()) {
+            if outcome.destination().is_some() {
+                let _ = zone.actor_map.get_xy_mut(intent.from).take();
+            }
+        }
+
+        for ((intent, outcome), (shared, weight, gradient)) in
+            intents.iter().zip(outcomes.iter()).zip(actors.iter())
         {
-            // Evil transmute to bypass annoying borrow checker.
-            // This is safe since we're always clearing the vec of refs.
-            let cache_ref: &mut Vec<(&mut IsActor, &mut WantsToMove)> =
-                unsafe { transmute(&mut self.cache) };
-            cache_ref.extend((&mut is_actor, &mut wants_to_move).join());
-            cache_ref.sort_by(|a, b| a.1.priority.cmp(&b.1.priority));
-
-            for (a, m) in cache_ref.iter() {
-                // Aquire a mutable ref to the actor.
-                let mut actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
-
-                // Calculate the new xy.
-                let new_xy = (actor.xy.0 + m.direction.dx(), actor.xy.1 + m.direction.dy());
-
-                // Return if the position is blocked.
-                if zone.is_blocked(new_xy) {
+            let mut actor = shared.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            let new_xy = match outcome {
+                MoveOutcome::Blocked(reason) => {
+                    // Reject the move if it was the player's.
+                    if intent.player {
+                        rejections.push(Rejection::new(*reason, intent.target()));
+                    }
+
                     actor.navigation.stationary += 1;
                     continue;
                 }
+                MoveOutcome::Sidestepped(xy) => {
+                    actor.navigation.weight = gradient.weight(&zone, *xy).or(Some(*weight));
+                    *xy
+                }
+                MoveOutcome::Moved(xy) | MoveOutcome::Swapped(xy) => {
+                    actor.navigation.weight = Some(*weight);
+                    *xy
+                }
+            };
+
+            // The new position is available - update the actor and the actor map.
+            *zone.actor_map.get_xy_mut(new_xy) = Some(shared.clone());
+            actor.navigation.stationary = 0;
+            actor.xy = new_xy;
+            counters.events += 1;
+
+            // If the entity is the player, also update the player xy.
+            if intent.player {
+                zone.player_xy = new_xy;
+            }
+        }
+
+        // Return the temporaries to the pools and clear all components.
+        pools.movers.give(actors);
+        pools.intents.give(intents);
+        pools.resolutions.give(resolution);
+        wants_to_move.clear();
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The scent system spreads and decays the scent trails and lets actors leave fresh scent.
+//-------------------------------------------------------------------------------------------------
+pub struct ScentSystem;
+
+impl<'a> System<'a> for ScentSystem {
+    type SystemData = (
+        WriteExpect<'a, Zone>,
+        Write<'a, SystemCounters>,
+        ReadStorage<'a, IsActor>,
+        ReadStorage<'a, EmitsScent>,
+    );
+
+    //---------------------------------------------------------------------------------------------
+    // Specs system run impl.
+    // Ages the existing scent by a turn, then deposits the scent of each emitting actor.
+    //---------------------------------------------------------------------------------------------
+    fn run(&mut self, (mut zone, mut counters, is_actor, emits_scent): Self::SystemData) {
+        zone.spread_scent();
+
+        for (a, e) in (&is_actor, &emits_scent).join() {
+            counters.entities += 1;
+
+            let xy = a.0.as_ref().lock().expect("Failed to lock actor mutex.").xy;
+            zone.scent.deposit(e.kind, xy, e.strength);
+            counters.events += 1;
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The decal system fades the decals, splatters the decals requested by combat and hazards, and
+// lets actors that stepped in blood leave a trail of footprints.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct DecalSystem {
+    // Last position and remaining footprints of each actor, by entity.
+    tracks: HashMap<Entity, (ICoord, u8)>,
+}
+
+impl<'a> System<'a> for DecalSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Zone>,
+        Write<'a, SystemCounters>,
+        Write<'a, DecalEvents>,
+        Read<'a, CombatEvents>,
+        Read<'a, ItemEvents>,
+        ReadStorage<'a, IsActor>,
+    );
+
+    //---------------------------------------------------------------------------------------------
+    // Specs system run impl.
+    // Wounds dealt by attacks and items this turn spill blood. Blood splatters also leave blood
+    // scent, so that wounds can be tracked.
+    //---------------------------------------------------------------------------------------------
+    fn run(
+        &mut self,
+        (entities, mut zone, mut counters, mut events, combat, items, is_actor): Self::SystemData,
+    ) {
+        zone.decals.age();
+
+        let wounds = combat.iter().map(|event| (event.xy, event.breakdown.damage())).chain(
+            items.iter().flat_map(|event| event.hits.iter().map(|hit| (hit.xy, -hit.change))),
+        );
+        events.extend(wounds.filter_map(|(xy, damage)| DecalEvent::wound(xy, damage)));
+
+        for event in events.drain(..) {
+            counters.events += 1;
+            zone.decals.splatter(&event);
+
+            if event.kind == DecalKind::Blood {
+                zone.scent.deposit(ScentKind::Blood, event.xy, event.strength);
+            }
+        }
+
+        for (entity, a) in (&entities, &is_actor).join() {
+            counters.entities += 1;
+
+            let xy = a.0.as_ref().lock().expect("Failed to lock actor mutex.").xy;
+            let (last_xy, steps) = self.tracks.entry(entity).or_insert((xy, 0));
+
+            // Leave a fading footprint after each step out of blood.
+            if *last_xy != xy && *steps > 0 {
+                let strength = FOOTPRINT_STRENGTH * *steps as f32 / FOOTPRINT_STEPS as f32;
+                zone.decals.add(xy, DecalKind::Footprints, strength);
+                *steps -= 1;
+                counters.events += 1;
+            }
+
+            if zone.decals.get(xy).is_some_and(|decal| {
+                decal.kind == DecalKind::Blood && decal.strength >= FOOTPRINT_BLOOD_STRENGTH
+            }) {
+                *steps = FOOTPRINT_STEPS;
+            }
+
+            *last_xy = xy;
+        }
+
+        // Forget the tracks of actors that are gone.
+        self.tracks.retain(|entity, _| entities.is_alive(*entity));
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The gossip system ages what actors know, lets them sight enemies, and spreads what they know
+// between nearby actors of the same faction, changing the factions' standings with the player
+// as they hear of the player's deeds.
+//-------------------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct GossipSystem {
+    // Position, faction and retold facts (in the turn pools' arena) of each actor with something
+    // to tell this turn.
+    tellers: Vec<(ICoord, Faction, ArenaSlice)>,
+}
+
+impl<'a> System<'a> for GossipSystem {
+    type SystemData = (
+        ReadExpect<'a, Zone>,
+        Write<'a, FactionStandings>,
+        Write<'a, TurnPools>,
+        Write<'a, SystemCounters>,
+        ReadStorage<'a, IsActor>,
+    );
+
+    //---------------------------------------------------------------------------------------------
+    // Specs system run impl.
+    // Collects what each actor knew at the start of the turn before spreading it, so that facts
+    // spread at most one step per turn regardless of the order of the actors.
+    //---------------------------------------------------------------------------------------------
+    fn run(&mut self, (zone, mut standings, mut pools, mut counters, is_actor): Self::SystemData) {
+        for a in is_actor.join() {
+            let mut actor = a.0.as_ref().lock().expect("Failed to lock actor mutex.");
+
+            if actor.faction == Faction::None {
+                continue;
+            }
+
+            counters.entities += 1;
+            let _ = actor.knowledge.decay();
+
+            // Sight the enemies nearby.
+            for (xy, other) in zone.actors_near(actor.xy, WITNESS_RANGE) {
+                if xy == actor.xy {
+                    continue;
+                }
+
+                let other = other.as_ref().lock().expect("Failed to lock actor mutex.");
+
+                if !actor.faction.is_hostile(other.faction) {
+                    continue;
+                }
+
+                let kind = FactKind::EnemySighted(other.entity.id());
+                let fact = standings.record(kind, zone.turn, xy);
+                let _ = actor.knowledge.learn(fact);
+            }
+
+            // Collect what the actor has to tell.
+            let facts =
+                pools.retold_facts.alloc(actor.knowledge.facts().iter().filter_map(Fact::retold));
+
+            if !facts.is_empty() {
+                self.tellers.push((actor.xy, actor.faction, facts));
+            }
+        }
+
+        // Tell the nearby actors of the same faction.
+        for (xy, faction, facts) in self.tellers.drain(..) {
+            for (listener_xy, listener) in zone.actors_near(xy, GOSSIP_RANGE) {
+                if listener_xy == xy {
+                    continue;
+                }
 
-                // The new position is available - update the actor and the actor map.
-                *zone.actor_map.get_xy_mut(new_xy) = zone.actor_map.get_xy_mut(actor.xy).take();
+                let mut listener = listener.as_ref().lock().expect("Failed to lock actor mutex.");
 
-                actor.navigation.weight = Some(m.weight);
-                actor.navigation.stationary = 0;
-                actor.xy = new_xy;
+                if !faction.is_friendly(listener.faction) {
+                    continue;
+                }
 
-                // If the entity is the player, also update the player xy.
-                if actor.entity == zone.player_entity {
-                    zone.player_xy = new_xy;
+                for fact in pools.retold_facts.get(facts) {
+                    if listener.knowledge.learn(fact.clone()) {
+                        let _ = standings.hear(listener.faction, fact);
+                        counters.events += 1;
+                    }
                 }
             }
+        }
+    }
+}
 
-            // IMPORTANT - clear the vec of refs.
-            cache_ref.clear();
+//-------------------------------------------------------------------------------------------------
+// The charges system regenerates the pools of charges held by entities once per turn.
+//-------------------------------------------------------------------------------------------------
+pub struct ChargesSystem;
+
+impl<'a> System<'a> for ChargesSystem {
+    type SystemData = (
+        Entities<'a>,
+        Write<'a, SystemCounters>,
+        Write<'a, ChargeEvents>,
+        WriteStorage<'a, HasCharges>,
+    );
+
+    //---------------------------------------------------------------------------------------------
+    // Specs system run impl.
+    // Ticks each pool of charges, emitting an event for each pool that regained charges.
+    //---------------------------------------------------------------------------------------------
+    fn run(&mut self, (entities, mut counters, mut events, mut has_charges): Self::SystemData) {
+        for (entity, h) in (&entities, &mut has_charges).join() {
+            counters.entities += 1;
+
+            for (name, charges) in h.pools.iter_mut() {
+                if charges.tick() == 0 {
+                    continue;
+                }
+
+                events.push(ChargeEvent {
+                    entity: entity.id(),
+                    name: name.clone(),
+                    charges: charges.current,
+                    full: charges.is_full(),
+                });
+                counters.events += 1;
+            }
         }
+    }
+}
 
-        // Clear all components.
-        wants_to_move.clear();
+//-------------------------------------------------------------------------------------------------
+// The zone script system runs the zone event hooks of the scripts once per turn.
+//-------------------------------------------------------------------------------------------------
+pub struct ZoneScriptSystem;
+
+impl<'a> System<'a> for ZoneScriptSystem {
+    type SystemData = (WriteExpect<'a, Zone>, Write<'a, SystemCounters>);
+
+    //---------------------------------------------------------------------------------------------
+    // Specs system run impl.
+    // Runs the zone event hooks, applying the commands they return to the zone.
+    //---------------------------------------------------------------------------------------------
+    fn run(&mut self, (mut zone, mut counters): Self::SystemData) {
+        counters.events += SCRIPTS.run_zone_events(&mut zone);
     }
 }
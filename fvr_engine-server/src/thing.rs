@@ -3,6 +3,11 @@
 //-------------------------------------------------------------------------------------------------
 use fvr_engine_core::prelude::*;
 
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::door::*;
+
 //-------------------------------------------------------------------------------------------------
 // Thing describes a thing in the game world.
 //-------------------------------------------------------------------------------------------------
@@ -14,4 +19,10 @@ pub struct Thing {
     pub transparency: Transparency,
     // Visual tile of the thing.
     pub tile: Tile,
+    // State of the thing if it is a door.
+    pub door: Option<DoorState>,
+    // Whether the glyph of the thing is picked from its neighbors by the zone's autotiler.
+    pub autotile: bool,
+    // Id of the thing's ambient animation, if any (animated by the client).
+    pub animation: Option<AmbientAnimationId>,
 }
@@ -0,0 +1,163 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::{HashSet, VecDeque};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::snapshot::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Distance used when calculating the cost of a travel path.
+pub const TRAVEL_DISTANCE: Distance = Distance::Euclidean;
+
+//-------------------------------------------------------------------------------------------------
+// A previewed path for the player to travel along.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TravelPreview {
+    // Coords to step through in order (excluding the player's coord).
+    pub path: Vec<ICoord>,
+    // Total movement cost of the path.
+    pub cost: f32,
+}
+
+impl TravelPreview {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new preview from a path as returned by AStar (from the end back to the start).
+    //---------------------------------------------------------------------------------------------
+    pub fn from_a_star_path(mut path: Vec<ICoord>) -> Self {
+        path.reverse();
+
+        let cost = path.windows(2).map(|step| TRAVEL_DISTANCE.calculate(step[0], step[1])).sum();
+
+        // Skip the start coord.
+        if !path.is_empty() {
+            path.remove(0);
+        }
+
+        Self { path, cost }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the number of turns needed to travel the path.
+    //---------------------------------------------------------------------------------------------
+    pub fn turns(&self) -> usize {
+        self.path.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the destination of the path, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn destination(&self) -> Option<ICoord> {
+        self.path.last().copied()
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the reasons travel stops before reaching the destination.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TravelInterruption {
+    // The next step of the path is blocked.
+    Blocked,
+    // An actor that was not visible when travel began came into view.
+    ActorSighted,
+    // The player is no longer on the path (e.g. a move failed).
+    OffPath,
+    // Travel was cancelled by the client.
+    Cancelled,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the states returned when advancing travel.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TravelStatus {
+    // The player is not traveling.
+    Idle,
+    // The player took a step and is still traveling.
+    Traveling,
+    // The player reached the destination.
+    Arrived,
+    // Travel stopped early.
+    Interrupted(TravelInterruption),
+}
+
+//-------------------------------------------------------------------------------------------------
+// Travel tracks the player walking a path one step per turn.
+// Travel is interrupted when the next step is blocked or a new actor is perceived.
+//-------------------------------------------------------------------------------------------------
+pub struct Travel {
+    // Remaining coords to step through.
+    path: VecDeque<ICoord>,
+    // Ids of the entities perceived when travel began.
+    known_entities: HashSet<u32>,
+}
+
+impl Travel {
+    //---------------------------------------------------------------------------------------------
+    // Begins traveling along a previewed path.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(preview: TravelPreview, snapshot: &WorldSnapshot) -> Self {
+        Self {
+            path: preview.path.into(),
+            known_entities: snapshot.entities().iter().map(|entity| entity.id).collect(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the remaining coords to step through.
+    //---------------------------------------------------------------------------------------------
+    pub fn path(&self) -> &VecDeque<ICoord> {
+        &self.path
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the direction of the next step, or why travel should stop.
+    // Returns None once the destination has been reached.
+    //---------------------------------------------------------------------------------------------
+    pub fn next_step(
+        &mut self,
+        snapshot: &WorldSnapshot,
+    ) -> Result<Option<Direction>, TravelInterruption> {
+        let next_xy = match self.path.front() {
+            Some(xy) => *xy,
+            None => return Ok(None),
+        };
+
+        // Stop when a new actor comes into view.
+        if snapshot.entities().iter().any(|entity| !self.known_entities.contains(&entity.id)) {
+            return Err(TravelInterruption::ActorSighted);
+        }
+
+        // Stop if the player was moved off of the path.
+        let player_xy = snapshot.player_xy();
+
+        if (next_xy.0 - player_xy.0).abs() > 1 || (next_xy.1 - player_xy.1).abs() > 1 {
+            return Err(TravelInterruption::OffPath);
+        }
+
+        // Open a closed door in the way (which takes the turn) before stepping through it.
+        if snapshot.is_closed_door(next_xy) && snapshot.entity_at(next_xy).is_none() {
+            return Ok(Some(Direction::closest_direction(player_xy, next_xy)));
+        }
+
+        // Stop if the next step has become blocked.
+        if snapshot.is_blocked(next_xy) {
+            return Err(TravelInterruption::Blocked);
+        }
+
+        self.path.pop_front();
+        Ok(Some(Direction::closest_direction(player_xy, next_xy)))
+    }
+}
@@ -7,20 +7,27 @@ use std::sync::{Arc, Mutex};
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
 use anyhow::Result;
-use rand::prelude::*;
+use rand::Rng as _;
 use specs::prelude::*;
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
-use fvr_engine_core::{map2d_iter_index_mut, prelude::*, xy_tuple_iter};
+use fvr_engine_core::{prelude::*, xy_tuple_iter};
 
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::actor::*;
+use crate::blackboard::*;
 use crate::cell::*;
 use crate::components::*;
+use crate::decal::*;
+use crate::door::*;
+use crate::item::*;
+use crate::knowledge::*;
+use crate::rejection::*;
+use crate::scent::*;
 use crate::server::*;
 use crate::thing::*;
 
@@ -29,7 +36,7 @@ use crate::thing::*;
 //-------------------------------------------------------------------------------------------------
 
 // TODO: Remove.
-static TREE_THING: Thing = Thing {
+pub(crate) static TREE_THING: Thing = Thing {
     tile: Tile {
         glyph: '♣',
         layout: TileLayout::Center,
@@ -42,13 +49,19 @@ static TREE_THING: Thing = Thing {
         background_opacity: 1.0,
         foreground_opacity: 1.0,
         outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
     },
     passability: Passability::Blocked,
     transparency: Transparency::Opaque,
+    door: None,
+    autotile: false,
+    animation: None,
 };
 
 // TODO: Remove.
-static GRASS_THING: Thing = Thing {
+pub(crate) static GRASS_THING: Thing = Thing {
     tile: Tile {
         glyph: '.',
         layout: TileLayout::Center,
@@ -61,9 +74,65 @@ static GRASS_THING: Thing = Thing {
         background_opacity: 1.0,
         foreground_opacity: 1.0,
         outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
     },
     passability: Passability::Passable,
     transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
+};
+
+// TODO: Remove.
+pub(crate) static WALL_THING: Thing = Thing {
+    tile: Tile {
+        glyph: '#',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::BrightGrey.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Blocked,
+    transparency: Transparency::Opaque,
+    door: None,
+    autotile: true,
+    animation: None,
+};
+
+// TODO: Remove.
+pub(crate) static TORCH_THING: Thing = Thing {
+    tile: Tile {
+        glyph: '¡',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::BrightOrange.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Blocked,
+    transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: Some(TORCH_ANIMATION),
 };
 
 // TODO: Remove.
@@ -80,9 +149,15 @@ static AVOID_MOB_THING: Thing = Thing {
         background_opacity: 1.0,
         foreground_opacity: 1.0,
         outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
     },
     passability: Passability::Blocked,
     transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
 };
 
 // TODO: Remove.
@@ -99,9 +174,65 @@ static CHASE_MOB_THING: Thing = Thing {
         background_opacity: 1.0,
         foreground_opacity: 1.0,
         outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
     },
     passability: Passability::Blocked,
     transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
+};
+
+// TODO: Remove.
+static TRACK_MOB_THING: Thing = Thing {
+    tile: Tile {
+        glyph: 'h',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::Yellow.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Blocked,
+    transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
+};
+
+// TODO: Remove.
+static VILLAGER_THING: Thing = Thing {
+    tile: Tile {
+        glyph: 'p',
+        layout: TileLayout::Center,
+        style: TileStyle::Regular,
+        size: TileSize::Normal,
+        outlined: false,
+        background_color: TileColor::TRANSPARENT,
+        foreground_color: PaletteColor::Brown.const_into(),
+        outline_color: TileColor::TRANSPARENT,
+        background_opacity: 1.0,
+        foreground_opacity: 1.0,
+        outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
+    },
+    passability: Passability::Blocked,
+    transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
 };
 
 // TODO: Remove.
@@ -118,11 +249,31 @@ static PLAYER_THING: Thing = Thing {
         background_opacity: 1.0,
         foreground_opacity: 1.0,
         outline_opacity: 1.0,
+        background_gradient: TileGradient::None,
+        outline_thickness: DEFAULT_OUTLINE_THICKNESS,
+        outline_glow: false,
     },
     passability: Passability::Blocked,
     transparency: Transparency::Transparent,
+    door: None,
+    autotile: false,
+    animation: None,
 };
 
+// Things of the (dummy) zone content by name, for validation.
+// TODO: Remove once things are defined by content.
+pub(crate) static ZONE_THINGS: [(&str, &Thing); 9] = [
+    ("tree", &TREE_THING),
+    ("grass", &GRASS_THING),
+    ("wall", &WALL_THING),
+    ("torch", &TORCH_THING),
+    ("avoid mob", &AVOID_MOB_THING),
+    ("chase mob", &CHASE_MOB_THING),
+    ("track mob", &TRACK_MOB_THING),
+    ("villager", &VILLAGER_THING),
+    ("player", &PLAYER_THING),
+];
+
 //-------------------------------------------------------------------------------------------------
 // Helper struct to store pathing related state for a cell.
 //-------------------------------------------------------------------------------------------------
@@ -178,38 +329,36 @@ pub struct Zone {
     pub avoid_map: DijkstraMap,
     // Navigation map pointing towards the player.
     pub chase_map: DijkstraMap,
+    // Navigation map pointing towards the player through closed doors (at a cost), for actors
+    // capable of opening doors.
+    pub door_chase_map: DijkstraMap,
     // Shared pathing propertie.
     pub pathing: GridMap<PathingProperties>,
+    // Color grading applied while the zone is displayed (e.g. cold for dungeons).
+    pub color_grade: ColorGrade,
+    // Scent trails left by actors, for tracking.
+    pub scent: ScentMaps,
+    // # of turns passed in the zone.
+    pub turn: u64,
+    // Cosmetic marks (blood, scorch marks, footprints) left on the cells.
+    pub decals: DecalLayer,
+    // Ruleset picking the glyphs of autotiled things (walls).
+    pub wall_ruleset: AutotileRuleset,
+    // Piles of items lying in the cells.
+    pub ground_items: GroundItems,
 }
 
 impl Zone {
     //---------------------------------------------------------------------------------------------
     // TODO: Remove.
     //---------------------------------------------------------------------------------------------
-    pub fn generate_dummy_map(&mut self) {
-        let mut rng = thread_rng();
-        const TREE_CHANCE: u8 = 15;
-
-        // Iterate over the map, setting each cell to either grass or a tree.
-        map2d_iter_index_mut!(self.cell_map, x, y, item, {
-            if rng.gen::<u8>() % TREE_CHANCE == 0 {
-                *item = Cell { things: vec![TREE_THING] };
-            } else {
-                *item = Cell { things: vec![GRASS_THING] };
-            }
-        });
-
-        // Ensure the player's cell is passable.
-        *self.cell_map.get_xy_mut(self.player_xy) = Cell { things: vec![GRASS_THING] };
-    }
-
-    //---------------------------------------------------------------------------------------------
-    // TODO: Remove.
-    //---------------------------------------------------------------------------------------------
-    pub fn generate_dummy_mobs(&mut self, world: &mut World) -> Result<()> {
-        let mut rng = thread_rng();
+    pub fn generate_dummy_mobs(&mut self, world: &mut World, rng: &mut Rng) -> Result<()> {
         const AVOID_MOB_COUNT: u8 = 50;
         const CHASE_MOB_COUNT: u8 = 20;
+        const TRACK_MOB_COUNT: u8 = 5;
+        const VILLAGER_COUNT: u8 = 12;
+        const VILLAGE_RADIUS: i32 = 6;
+        const HUT_HALF_EXTENTS: ICoord = (3, 2);
 
         // Populate map randomly with actors that avoid the player.
         for _ in 0..AVOID_MOB_COUNT {
@@ -226,14 +375,21 @@ impl Zone {
 
             // Create the avoid mob and insert it into the world and the actor map.
             let entity = world.create_entity().build();
+            let stats: ActorStats = rng.gen();
             let actor = Arc::new(Mutex::new(Actor {
                 entity,
                 thing: AVOID_MOB_THING,
+                kind: "skulker",
                 xy,
                 navigation: ActorNavigation::default(),
-                stats: rng.gen(),
+                stats,
+                health: ActorHealth::for_stats(&stats),
+                capabilities: ActorCapabilities::default(),
                 behavior: 0,
                 intention: BASIC_AVOID_PLAYER_INDEX,
+                blackboard: Blackboard::new(),
+                faction: Faction::None,
+                knowledge: Knowledge::new(),
             }));
 
             world.write_component::<IsActor>().insert(entity, IsActor(actor.clone()))?;
@@ -256,14 +412,138 @@ impl Zone {
 
             // Create the chase mob and insert it into the world and the actor map.
             let entity = world.create_entity().build();
+            let stats: ActorStats = rng.gen();
             let actor = Arc::new(Mutex::new(Actor {
                 entity,
                 thing: CHASE_MOB_THING,
+                kind: "stalker",
                 xy,
                 navigation: ActorNavigation::default(),
-                stats: rng.gen(),
+                stats,
+                health: ActorHealth::for_stats(&stats),
+                capabilities: ActorCapabilities { open_doors: true },
                 behavior: 0,
                 intention: BASIC_CHASE_PLAYER_INDEX,
+                blackboard: Blackboard::new(),
+                faction: Faction::Wild,
+                knowledge: Knowledge::new(),
+            }));
+
+            world.write_component::<IsActor>().insert(entity, IsActor(actor.clone()))?;
+            world.write_component::<HasGoals>().insert(entity, HasGoals::default())?;
+            *self.actor_map.get_xy_mut(xy) = Some(actor);
+        }
+
+        // Populate map randomly with actors that track the player by scent.
+        for _ in 0..TRACK_MOB_COUNT {
+            // Find a random coord.
+            let xy = (rng.gen_range(0..self.dimensions.0), rng.gen_range(0..self.dimensions.1));
+
+            // Check if it is available.
+            if xy == self.player_xy
+                || self.actor_map.get_xy(xy).is_some()
+                || !self.pathing.get_xy(xy).passable()
+            {
+                continue;
+            }
+
+            // Create the tracking mob and insert it into the world and the actor map.
+            let entity = world.create_entity().build();
+            let stats: ActorStats = rng.gen();
+            let actor = Arc::new(Mutex::new(Actor {
+                entity,
+                thing: TRACK_MOB_THING,
+                kind: "tracker",
+                xy,
+                navigation: ActorNavigation::default(),
+                stats,
+                health: ActorHealth::for_stats(&stats),
+                capabilities: ActorCapabilities::default(),
+                behavior: 0,
+                intention: BASIC_TRACK_PLAYER_INDEX,
+                blackboard: Blackboard::new(),
+                faction: Faction::Wild,
+                knowledge: Knowledge::new(),
+            }));
+
+            world.write_component::<IsActor>().insert(entity, IsActor(actor.clone()))?;
+            world.write_component::<HasGoals>().insert(entity, HasGoals::default())?;
+            *self.actor_map.get_xy_mut(xy) = Some(actor);
+        }
+
+        // Populate a village of gossiping townsfolk around a random coord.
+        let center = (rng.gen_range(0..self.dimensions.0), rng.gen_range(0..self.dimensions.1));
+
+        // Build a walled hut at the center of the village, with a door on its south side.
+        for y in -HUT_HALF_EXTENTS.1..=HUT_HALF_EXTENTS.1 {
+            for x in -HUT_HALF_EXTENTS.0..=HUT_HALF_EXTENTS.0 {
+                let xy = (center.0 + x, center.1 + y);
+
+                if x.abs() != HUT_HALF_EXTENTS.0 && y.abs() != HUT_HALF_EXTENTS.1 {
+                    continue;
+                }
+
+                if xy == self.player_xy
+                    || !self.actor_map.in_bounds(xy)
+                    || self.actor_map.get_xy(xy).is_some()
+                {
+                    continue;
+                }
+
+                if (x, y) == (0, HUT_HALF_EXTENTS.1) {
+                    self.place_door(xy);
+                } else {
+                    self.place_wall(xy);
+                }
+            }
+        }
+
+        // Light the way to the door with a pair of torches.
+        for x in [-2, 2].iter() {
+            let xy = (center.0 + x, center.1 + HUT_HALF_EXTENTS.1 + 1);
+
+            if xy != self.player_xy
+                && self.actor_map.in_bounds(xy)
+                && self.actor_map.get_xy(xy).is_none()
+            {
+                *self.cell_map.get_xy_mut(xy) = Cell { things: vec![GRASS_THING, TORCH_THING] };
+                self.refresh_pathing_xy(xy);
+            }
+        }
+
+        for _ in 0..VILLAGER_COUNT {
+            // Find a random coord near the center of the village.
+            let xy = (
+                center.0 + rng.gen_range(-VILLAGE_RADIUS..=VILLAGE_RADIUS),
+                center.1 + rng.gen_range(-VILLAGE_RADIUS..=VILLAGE_RADIUS),
+            );
+
+            // Check if it is available.
+            if xy == self.player_xy
+                || !self.actor_map.in_bounds(xy)
+                || self.actor_map.get_xy(xy).is_some()
+                || !self.pathing.get_xy(xy).passable()
+            {
+                continue;
+            }
+
+            // Create the villager and insert it into the world and the actor map.
+            let entity = world.create_entity().build();
+            let stats: ActorStats = rng.gen();
+            let actor = Arc::new(Mutex::new(Actor {
+                entity,
+                thing: VILLAGER_THING,
+                kind: "villager",
+                xy,
+                navigation: ActorNavigation::default(),
+                stats,
+                health: ActorHealth::for_stats(&stats),
+                capabilities: ActorCapabilities { open_doors: true },
+                behavior: 0,
+                intention: BASIC_VILLAGER_INDEX,
+                blackboard: Blackboard::new(),
+                faction: Faction::Town,
+                knowledge: Knowledge::new(),
             }));
 
             world.write_component::<IsActor>().insert(entity, IsActor(actor.clone()))?;
@@ -274,6 +554,86 @@ impl Zone {
         Ok(())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Populates the zone with extra mobs that chase the player (e.g. for stress testing).
+    // Returns the # of mobs created, which is fewer than requested if free coords are scarce.
+    //---------------------------------------------------------------------------------------------
+    pub fn generate_stress_mobs(
+        &mut self,
+        world: &mut World,
+        rng: &mut Rng,
+        count: usize,
+    ) -> Result<usize> {
+        // Give up after a bounded # of attempts rather than searching a full zone forever.
+        const ATTEMPTS_PER_MOB: usize = 8;
+
+        let mut created = 0;
+
+        for _ in 0..count * ATTEMPTS_PER_MOB {
+            if created == count {
+                break;
+            }
+
+            // Find a random coord.
+            let xy = (rng.gen_range(0..self.dimensions.0), rng.gen_range(0..self.dimensions.1));
+
+            // Check if it is available.
+            if xy == self.player_xy
+                || self.actor_map.get_xy(xy).is_some()
+                || !self.pathing.get_xy(xy).passable()
+            {
+                continue;
+            }
+
+            // Create the chase mob and insert it into the world and the actor map.
+            let entity = world.create_entity().build();
+            let stats: ActorStats = rng.gen();
+            let actor = Arc::new(Mutex::new(Actor {
+                entity,
+                thing: CHASE_MOB_THING,
+                kind: "stalker",
+                xy,
+                navigation: ActorNavigation::default(),
+                stats,
+                health: ActorHealth::for_stats(&stats),
+                capabilities: ActorCapabilities { open_doors: true },
+                behavior: 0,
+                intention: BASIC_CHASE_PLAYER_INDEX,
+                blackboard: Blackboard::new(),
+                faction: Faction::Wild,
+                knowledge: Knowledge::new(),
+            }));
+
+            world.write_component::<IsActor>().insert(entity, IsActor(actor.clone()))?;
+            world.write_component::<HasGoals>().insert(entity, HasGoals::default())?;
+            *self.actor_map.get_xy_mut(xy) = Some(actor);
+            created += 1;
+        }
+
+        Ok(created)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // TODO: Remove.
+    //---------------------------------------------------------------------------------------------
+    pub fn generate_dummy_items(&mut self, rng: &mut Rng) {
+        const ITEM_COUNT: u8 = 16;
+        const ITEM_RADIUS: i32 = 12;
+
+        // Scatter items around the player.
+        for _ in 0..ITEM_COUNT {
+            let xy = (
+                self.player_xy.0 + rng.gen_range(-ITEM_RADIUS..=ITEM_RADIUS),
+                self.player_xy.1 + rng.gen_range(-ITEM_RADIUS..=ITEM_RADIUS),
+            );
+            let item = ItemKind::ALL[rng.gen_range(0..ItemKind::ALL.len())];
+
+            if xy != self.player_xy && self.terrain_blocked_reason(xy).is_none() {
+                self.ground_items.add(xy, ItemStack::one(item));
+            }
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Refreshes the state of the navigation related maps.
     //---------------------------------------------------------------------------------------------
@@ -320,6 +680,23 @@ impl Zone {
         // Caluclate the chase map.
         self.chase_map.calculate_thin(&self.pathing);
 
+        // Calculate the chase map through closed doors.
+        let pathing = &self.pathing;
+        let cell_map = &self.cell_map;
+        let actor_map = &self.actor_map;
+
+        self.door_chase_map.calculate_thin_with_cost(pathing, |xy| {
+            if Passability::from(*pathing.get_xy(xy)).passable() {
+                Some(1.0)
+            } else if cell_map.get_xy(xy).door() == Some(DoorState::Closed)
+                && actor_map.get_xy(xy).is_none()
+            {
+                Some(DOOR_PATH_COST)
+            } else {
+                None
+            }
+        });
+
         // Calculate the avoid map using the max xy of the chase map.
         let highest_xy = self.chase_map.highest_xy();
 
@@ -367,42 +744,69 @@ impl Zone {
     }
 
     //---------------------------------------------------------------------------------------------
-    // TODO: Remove.
+    // Creates a zone from generated cells, populating it with the player and (dummy) mobs.
+    // The placement and stats of the actors are drawn from an rng so that the zone is reproducible.
     //---------------------------------------------------------------------------------------------
-    pub fn dummy(dimensions: ICoord, world: &mut World) -> Result<Self> {
+    pub fn from_cells(
+        mut cell_map: GridMap<Cell>,
+        world: &mut World,
+        rng: &mut Rng,
+    ) -> Result<Self> {
+        let dimensions = cell_map.dimensions();
         let mut actor_map = GridMap::new(dimensions);
 
         // Create and insert the player entity.
-        let mut rng = thread_rng();
         let player_xy = (rng.gen_range(0..dimensions.0), rng.gen_range(0..dimensions.1));
         let player_entity = world.create_entity().build();
+        let stats: ActorStats = rng.gen();
         let player_actor = Arc::new(Mutex::new(Actor {
             entity: player_entity,
             thing: PLAYER_THING,
+            kind: "player",
             xy: player_xy,
             navigation: ActorNavigation::default(),
-            stats: rng.gen(),
+            stats,
+            health: ActorHealth::for_stats(&stats),
+            capabilities: ActorCapabilities { open_doors: true },
             behavior: usize::MAX,
             intention: usize::MAX,
+            blackboard: Blackboard::new(),
+            faction: Faction::None,
+            knowledge: Knowledge::new(),
         }));
         world.write_component::<IsActor>().insert(player_entity, IsActor(player_actor.clone()))?;
+        world.write_component::<EmitsScent>().insert(
+            player_entity,
+            EmitsScent { kind: ScentKind::Player, strength: PLAYER_SCENT_STRENGTH },
+        )?;
         *actor_map.get_xy_mut(player_xy) = Some(player_actor);
 
-        // Generate dummy data for the zone.
+        // Ensure the player's cell is passable.
+        *cell_map.get_xy_mut(player_xy) = Cell { things: vec![GRASS_THING] };
+
+        // Create the zone and populate it with (dummy) mobs.
         let mut zone = Self {
             dimensions,
             player_xy,
             player_entity,
             player_fov: Fov::new_thin(dimensions, Distance::Euclidean),
-            cell_map: GridMap::new(dimensions),
+            cell_map,
             actor_map,
             avoid_map: DijkstraMap::new_thin(dimensions, Distance::Euclidean),
             chase_map: DijkstraMap::new_thin(dimensions, Distance::Euclidean),
+            door_chase_map: DijkstraMap::new_thin(dimensions, Distance::Euclidean),
             pathing: GridMap::new(dimensions),
+            color_grade: ColorGrade::default(),
+            scent: ScentMaps::new(dimensions),
+            turn: 0,
+            decals: DecalLayer::new(dimensions),
+            wall_ruleset: AutotileRuleset::default(),
+            ground_items: GroundItems::new(),
         };
 
-        zone.generate_dummy_map();
-        zone.generate_dummy_mobs(world)?;
+        zone.generate_dummy_mobs(world, rng)?;
+        zone.connect_regions();
+        zone.generate_dummy_items(rng);
         zone.refresh();
         Ok(zone)
     }
@@ -419,21 +823,181 @@ impl Zone {
     // Determins whether a coord in the zone is passable.
     //---------------------------------------------------------------------------------------------
     pub fn is_blocked(&self, xy: ICoord) -> bool {
+        self.blocked_reason(xy).is_some()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns why a coord in the zone is not passable, if it is not.
+    //---------------------------------------------------------------------------------------------
+    pub fn blocked_reason(&self, xy: ICoord) -> Option<RejectionReason> {
+        if let Some(reason) = self.terrain_blocked_reason(xy) {
+            return Some(reason);
+        }
+
+        // Is the position occupied by an actor?
+        if self.actor_map.get_xy(xy).is_some() {
+            return Some(RejectionReason::Occupied);
+        }
+
+        None
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there is a closed door at a coord.
+    //---------------------------------------------------------------------------------------------
+    pub fn closed_door_at(&self, xy: ICoord) -> bool {
+        self.cell_map.in_bounds(xy) && self.cell_map.get_xy(xy).door() == Some(DoorState::Closed)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the passability of a coord regardless of actors for an actor with capabilities.
+    // Closed doors are passable to actors capable of opening them.
+    //---------------------------------------------------------------------------------------------
+    pub fn passable_for(&self, xy: ICoord, capabilities: &ActorCapabilities) -> bool {
+        self.terrain_blocked_reason(xy).is_none()
+            || (capabilities.open_doors && self.closed_door_at(xy))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Places a closed door at a coord, replacing the things in the cell.
+    //---------------------------------------------------------------------------------------------
+    pub fn place_door(&mut self, xy: ICoord) {
+        if self.cell_map.in_bounds(xy) {
+            *self.cell_map.get_xy_mut(xy) = Cell { things: vec![CLOSED_DOOR_THING] };
+            self.refresh_pathing_xy(xy);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Carves the fewest blocked cells needed for every passable cell to be reachable from every
+    // other (e.g. after walls were placed). Closed doors count as passable, and corridors through
+    // walls get doors at their ends while the other carved cells become grass.
+    //---------------------------------------------------------------------------------------------
+    pub fn connect_regions(&mut self) {
+        let mut states = passability_map(&self.cell_map);
+        let repair = Connectivity::connect_all(&mut states, Adjacency::EightWay);
+
+        for &xy in repair.carved.iter() {
+            let walled = self.cell_map.get_xy(xy).things.iter().any(|thing| thing.autotile);
+
+            if walled && repair.doors.contains(&xy) {
+                self.place_door(xy);
+            } else {
+                *self.cell_map.get_xy_mut(xy) = Cell { things: vec![GRASS_THING] };
+                self.refresh_pathing_xy(xy);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Places a wall at a coord, replacing the things in the cell.
+    //---------------------------------------------------------------------------------------------
+    pub fn place_wall(&mut self, xy: ICoord) {
+        if self.cell_map.in_bounds(xy) {
+            *self.cell_map.get_xy_mut(xy) = Cell { things: vec![WALL_THING] };
+            self.refresh_pathing_xy(xy);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the glyph the wall ruleset picks for a coord, if its visible thing is autotiled.
+    // Autotiled things connect to each other and to doors.
+    //---------------------------------------------------------------------------------------------
+    pub fn autotile_glyph(&self, xy: ICoord) -> Option<char> {
+        let connects = |xy: ICoord| {
+            self.cell_map.in_bounds(xy)
+                && self
+                    .cell_map
+                    .get_xy(xy)
+                    .things
+                    .last()
+                    .is_some_and(|t| t.autotile || t.door.is_some())
+        };
+
+        if !self.cell_map.in_bounds(xy)
+            || !self.cell_map.get_xy(xy).things.last().is_some_and(|thing| thing.autotile)
+        {
+            return None;
+        }
+
+        Some(self.wall_ruleset.glyph(self.wall_ruleset.mask(xy, connects)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Opens or closes the door at a coord. Returns whether the door changed state.
+    // Doors cannot be closed on an actor.
+    //---------------------------------------------------------------------------------------------
+    pub fn set_door(&mut self, xy: ICoord, state: DoorState) -> bool {
+        if !self.cell_map.in_bounds(xy)
+            || (state == DoorState::Closed && self.actor_map.get_xy(xy).is_some())
+        {
+            return false;
+        }
+
+        if !self.cell_map.get_xy_mut(xy).set_door(state) {
+            return false;
+        }
+
+        self.refresh_pathing_xy(xy);
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that refreshes the pathing properties of an unoccupied coord whose cell has
+    // changed, so that the change applies before the navigation maps are next refreshed.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_pathing_xy(&mut self, xy: ICoord) {
+        let cell = self.cell_map.get_xy(xy);
+        let pathing = self.pathing.get_xy_mut(xy);
+        pathing.dijkstra_state = cell.passability().into();
+        pathing.transparency = cell.transparency();
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the player is in sight from a coord.
+    // (the player's fov is used, assuming that sight is symmetric)
+    //---------------------------------------------------------------------------------------------
+    pub fn can_see_player(&self, xy: ICoord) -> bool {
+        self.player_fov.in_bounds(xy) && *self.player_fov.get_xy(xy) > 0.0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns an iterator over the actors within a chebyshev distance of a coord (including any
+    // actor at the coord), with their coords.
+    //---------------------------------------------------------------------------------------------
+    pub fn actors_near(
+        &self,
+        xy: ICoord,
+        distance: i32,
+    ) -> impl Iterator<Item = (ICoord, &SharedActor)> + '_ {
+        (-distance..=distance)
+            .flat_map(move |dy| (-distance..=distance).map(move |dx| (xy.0 + dx, xy.1 + dy)))
+            .filter(move |xy| self.actor_map.in_bounds(*xy))
+            .filter_map(move |xy| self.actor_map.get_xy(xy).as_ref().map(|actor| (xy, actor)))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Spreads and decays the scent trails by one turn. Scent does not spread through obstacles.
+    //---------------------------------------------------------------------------------------------
+    pub fn spread_scent(&mut self) {
+        let cell_map = &self.cell_map;
+        self.scent.spread(|xy| cell_map.get_xy(xy).passability() == Passability::Passable);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns why a coord in the zone is not passable regardless of actors, if it is not.
+    //---------------------------------------------------------------------------------------------
+    pub fn terrain_blocked_reason(&self, xy: ICoord) -> Option<RejectionReason> {
         // Is the position in bounds?
         if !self.cell_map.in_bounds(xy) {
-            return true;
+            return Some(RejectionReason::OutOfBounds);
         }
 
         // Is the position passable?
         if !self.pathing.get_xy(xy).passable() {
-            return true;
-        }
-
-        // Is the position occupied by an actor?
-        if self.actor_map.get_xy(xy).is_some() {
-            return true;
+            return Some(RejectionReason::Blocked);
         }
 
-        false
+        None
     }
 }
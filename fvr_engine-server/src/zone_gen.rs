@@ -0,0 +1,287 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+use rand::Rng as _;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::cell::*;
+use crate::decoration::*;
+use crate::thing::*;
+use crate::zone::*;
+
+//-------------------------------------------------------------------------------------------------
+// Aliases for convenience.
+//-------------------------------------------------------------------------------------------------
+pub type ZoneId = u32;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the events produced while generating requested zones.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum ZoneEvent {
+    // Generation of a zone has progressed (in the range [0.0, 1.0]).
+    Progress { id: ZoneId, progress: f32 },
+    // A zone has finished generating and can be entered.
+    Complete { id: ZoneId },
+    // A zone failed to generate.
+    Failed { id: ZoneId, error: String },
+}
+
+//-------------------------------------------------------------------------------------------------
+// ZoneGenerator generates the cells of a zone. Generators are sent to worker threads, so they
+// must not hold references to the specs world or any other main thread state.
+//-------------------------------------------------------------------------------------------------
+pub trait ZoneGenerator: Send {
+    //---------------------------------------------------------------------------------------------
+    // Generates the cells of a zone, periodically reporting progress in the range [0.0, 1.0].
+    //---------------------------------------------------------------------------------------------
+    fn generate(
+        &mut self,
+        dimensions: ICoord,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<GridMap<Cell>>;
+}
+
+//-------------------------------------------------------------------------------------------------
+// Generates a forest of grass and randomly placed trees from a seed, then decorates it.
+//-------------------------------------------------------------------------------------------------
+pub struct ForestGenerator {
+    // Seed for the rng.
+    pub seed: u64,
+}
+
+impl ForestGenerator {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new forest generator.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl ZoneGenerator for ForestGenerator {
+    //---------------------------------------------------------------------------------------------
+    // Generates the cells of a forest zone, reporting progress after each row and decorating.
+    //---------------------------------------------------------------------------------------------
+    fn generate(
+        &mut self,
+        dimensions: ICoord,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<GridMap<Cell>> {
+        const TREE_CHANCE: u8 = 15;
+        const DECORATION_PROGRESS: f32 = 0.1;
+
+        let mut rng = Rng::new(self.seed);
+        let mut cell_map = GridMap::new(dimensions);
+
+        // Set each cell to either grass or a tree.
+        for y in 0..dimensions.1 {
+            for x in 0..dimensions.0 {
+                let thing =
+                    if rng.gen::<u8>() % TREE_CHANCE == 0 { TREE_THING } else { GRASS_THING };
+                *cell_map.get_xy_mut((x, y)) = Cell { things: vec![thing] };
+            }
+
+            progress((1.0 - DECORATION_PROGRESS) * (y + 1) as f32 / dimensions.1 as f32);
+        }
+
+        // Clear trees to join any pockets of grass they enclose.
+        repair_connectivity(&mut cell_map, &GRASS_THING);
+
+        // Vary the floors and scatter decorations.
+        decorate(&mut cell_map, Biome::Forest.theme(), self.seed);
+        progress(1.0);
+
+        Ok(cell_map)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Carves the blocked cells of a generated map until every passable cell is reachable from every
+// other (closed doors count as passable), replacing the carved cells with a floor thing.
+// Returns the repair for placing doors.
+//-------------------------------------------------------------------------------------------------
+pub fn repair_connectivity(cell_map: &mut GridMap<Cell>, floor: &Thing) -> ConnectivityRepair {
+    let mut states = passability_map(cell_map);
+    let repair = Connectivity::connect_all(&mut states, Adjacency::EightWay);
+
+    for &xy in repair.carved.iter() {
+        *cell_map.get_xy_mut(xy) = Cell { things: vec![*floor] };
+    }
+
+    repair
+}
+
+//-------------------------------------------------------------------------------------------------
+// ZoneHandle refers to a requested zone and allows for polling its generation progress.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub struct ZoneHandle {
+    // Id of the requested zone.
+    id: ZoneId,
+    // Progress of the generation, stored as f32 bits.
+    progress: Arc<AtomicU32>,
+}
+
+impl ZoneHandle {
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the requested zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn id(&self) -> ZoneId {
+        self.id
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the latest generation progress in the range [0.0, 1.0].
+    //---------------------------------------------------------------------------------------------
+    pub fn progress(&self) -> f32 {
+        f32::from_bits(self.progress.load(Ordering::Relaxed))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Helper struct for holding a zone generation task sent to the workers.
+//-------------------------------------------------------------------------------------------------
+struct ZoneTask {
+    // Id of the requested zone.
+    id: ZoneId,
+    // Dimensions of the requested zone.
+    dimensions: ICoord,
+    // Generator for the zone.
+    generator: Box<dyn ZoneGenerator>,
+    // Progress shared with the zone's handle.
+    progress: Arc<AtomicU32>,
+}
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the messages sent from the workers.
+//-------------------------------------------------------------------------------------------------
+pub(crate) enum ZoneWorkerMessage {
+    // Generation of a zone has progressed.
+    Progress(ZoneId, f32),
+    // Generation of a zone has finished.
+    Finished(ZoneId, Result<GridMap<Cell>>),
+}
+
+//-------------------------------------------------------------------------------------------------
+// ZoneWorkers is a pool of threads that run zone generators off of the main thread.
+//-------------------------------------------------------------------------------------------------
+pub struct ZoneWorkers {
+    // Sender for queueing tasks (None once the pool is shutting down).
+    task_sender: Option<Sender<ZoneTask>>,
+    // Receiver for messages from the workers.
+    message_receiver: Receiver<ZoneWorkerMessage>,
+    // The worker threads.
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ZoneWorkers {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new pool of zone workers.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(thread_count: usize) -> Self {
+        let (task_sender, task_receiver) = channel::<ZoneTask>();
+        let (message_sender, message_receiver) = channel();
+        let task_receiver = Arc::new(Mutex::new(task_receiver));
+
+        let threads = (0..thread_count.max(1))
+            .map(|_| {
+                let tasks = task_receiver.clone();
+                let messages = message_sender.clone();
+
+                thread::spawn(move || loop {
+                    // Wait for the next task, stopping once the pool has been dropped.
+                    let task = tasks.lock().expect("Failed to lock zone task receiver.").recv();
+                    let mut task = match task {
+                        Ok(task) => task,
+                        Err(_) => break,
+                    };
+
+                    // Run the generator, forwarding progress to the handle and the main thread.
+                    let (id, shared_progress) = (task.id, task.progress.clone());
+                    let mut report = |progress: f32| {
+                        shared_progress.store(progress.to_bits(), Ordering::Relaxed);
+                        let _ = messages.send(ZoneWorkerMessage::Progress(id, progress));
+                    };
+                    let result = task.generator.generate(task.dimensions, &mut report);
+                    task.progress.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+                    if messages.send(ZoneWorkerMessage::Finished(id, result)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        Self { task_sender: Some(task_sender), message_receiver, threads }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Queues a zone to be generated, returning a handle to it.
+    //---------------------------------------------------------------------------------------------
+    pub fn request(
+        &mut self,
+        id: ZoneId,
+        dimensions: ICoord,
+        generator: Box<dyn ZoneGenerator>,
+    ) -> ZoneHandle {
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let task = ZoneTask { id, dimensions, generator, progress: progress.clone() };
+
+        if let Some(sender) = self.task_sender.as_ref() {
+            // The workers only stop once the sender is dropped, so this cannot fail.
+            let _ = sender.send(task);
+        }
+
+        ZoneHandle { id, progress }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the next pending message from the workers, if any.
+    //---------------------------------------------------------------------------------------------
+    pub(crate) fn try_recv(&self) -> Option<ZoneWorkerMessage> {
+        self.message_receiver.try_recv().ok()
+    }
+}
+
+impl Drop for ZoneWorkers {
+    //---------------------------------------------------------------------------------------------
+    // Stops the workers, waiting for any in progress generation to finish.
+    //---------------------------------------------------------------------------------------------
+    fn drop(&mut self) {
+        self.task_sender = None;
+
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_forest_generator_connected() {
+    for seed in 0..4 {
+        let cell_map = ForestGenerator::new(seed).generate((48, 24), &mut |_| {}).unwrap();
+        let states = passability_map(&cell_map);
+        assert_eq!(Regions::new(&states, Adjacency::EightWay).count(), 1);
+    }
+}
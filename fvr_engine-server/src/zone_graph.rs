@@ -0,0 +1,176 @@
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::zone_gen::*;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the kinds of connections between zones.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZoneLinkKind {
+    // Stairs leading up or down.
+    Stairs,
+    // A portal.
+    Portal,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A zone the player has discovered.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZoneNode {
+    // Id of the zone.
+    pub id: ZoneId,
+    // Name of the zone.
+    pub name: String,
+    // Whether the zone can be fast traveled to and from.
+    pub fast_travel: bool,
+}
+
+//-------------------------------------------------------------------------------------------------
+// A connection between two discovered zones (undirected).
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZoneLink {
+    // Id of the zone the connection was first taken from.
+    pub from: ZoneId,
+    // Id of the zone the connection leads to.
+    pub to: ZoneId,
+    // Kind of the connection.
+    pub kind: ZoneLinkKind,
+}
+
+//-------------------------------------------------------------------------------------------------
+// ZoneGraph records the zones the player has discovered, the connections taken between them and
+// the zone the player is in. It also holds the destination chosen for fast travel until the
+// client starts the travel, as generating the destination is the client's job.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ZoneGraph {
+    // Zones discovered, in the order they were discovered.
+    nodes: Vec<ZoneNode>,
+    // Connections taken between the zones.
+    links: Vec<ZoneLink>,
+    // Id of the zone the player is in, if any.
+    current: Option<ZoneId>,
+    // Id of the zone chosen for fast travel, if any.
+    destination: Option<ZoneId>,
+}
+
+impl ZoneGraph {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new, empty zone graph.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the zones discovered, in the order they were discovered.
+    //---------------------------------------------------------------------------------------------
+    pub fn nodes(&self) -> &[ZoneNode] {
+        &self.nodes
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the connections taken between the zones.
+    //---------------------------------------------------------------------------------------------
+    pub fn links(&self) -> &[ZoneLink] {
+        &self.links
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the discovered zone with an id, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn node(&self, id: ZoneId) -> Option<&ZoneNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the id of the zone the player is in, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn current(&self) -> Option<ZoneId> {
+        self.current
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records that the player entered a zone, discovering it if necessary.
+    // Returns whether the zone was discovered for the first time.
+    //---------------------------------------------------------------------------------------------
+    pub fn enter(&mut self, id: ZoneId, name: &str, fast_travel: bool) -> bool {
+        let discovered = self.node(id).is_none();
+
+        if discovered {
+            self.nodes.push(ZoneNode { id, name: name.into(), fast_travel });
+        }
+
+        self.current = Some(id);
+        discovered
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Records a connection between two discovered zones. Returns false if either zone is
+    // undiscovered or the zones are already connected.
+    //---------------------------------------------------------------------------------------------
+    pub fn connect(&mut self, from: ZoneId, to: ZoneId, kind: ZoneLinkKind) -> bool {
+        if from == to || self.node(from).is_none() || self.node(to).is_none() {
+            return false;
+        }
+
+        if self.neighbors(from).any(|id| id == to) {
+            return false;
+        }
+
+        self.links.push(ZoneLink { from, to, kind });
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the ids of the zones connected to a zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn neighbors(&self, id: ZoneId) -> impl Iterator<Item = ZoneId> + '_ {
+        self.links.iter().filter_map(move |link| {
+            if link.from == id {
+                Some(link.to)
+            } else if link.to == id {
+                Some(link.from)
+            } else {
+                None
+            }
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether the player can fast travel to a zone: both it and the current zone must
+    // allow fast travel, and it must not be the current zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn can_fast_travel(&self, id: ZoneId) -> bool {
+        let current = match self.current.and_then(|current| self.node(current)) {
+            Some(current) => current,
+            None => return false,
+        };
+
+        current.id != id
+            && current.fast_travel
+            && self.node(id).map(|node| node.fast_travel).unwrap_or(false)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Chooses a zone as the destination of fast travel. Returns false if it is not allowed.
+    //---------------------------------------------------------------------------------------------
+    pub fn choose_destination(&mut self, id: ZoneId) -> bool {
+        if !self.can_fast_travel(id) {
+            return false;
+        }
+
+        self.destination = Some(id);
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Takes the destination chosen for fast travel, if any.
+    //---------------------------------------------------------------------------------------------
+    pub fn take_destination(&mut self) -> Option<ZoneId> {
+        self.destination.take()
+    }
+}
@@ -0,0 +1,243 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::path::PathBuf;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{crate_version, App, Arg, ArgMatches};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_core::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Default # of turns to run when stress testing.
+const DEFAULT_STRESS_TURNS: u32 = 1000;
+
+// Default # of extra actors to spawn when stress testing.
+const DEFAULT_STRESS_ACTORS: usize = 500;
+
+//-------------------------------------------------------------------------------------------------
+// Cli holds the parsed command-line args of the game.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct Cli {
+    // Overrides of the config file (must be applied before the config is first accessed).
+    pub overrides: ConfigOverrides,
+    // Path of a tape of inputs to replay against the server before the game starts.
+    pub replay: Option<PathBuf>,
+    // Whether to run the server without a window.
+    pub headless: bool,
+    // Whether to stress test the server (and the renderer, unless headless).
+    pub stress: bool,
+    // # of extra actors to spawn when stress testing.
+    pub actors: usize,
+    // # of turns to run the server for when headless or stress testing (after the replay).
+    pub turns: u32,
+}
+
+impl Cli {
+    //---------------------------------------------------------------------------------------------
+    // Helper function that builds the description of the accepted args.
+    //---------------------------------------------------------------------------------------------
+    fn app() -> App<'static, 'static> {
+        App::new("fvr_engine")
+            .version(crate_version!())
+            .about("Runs the game, optionally headless, stress testing or replaying inputs.")
+            .arg(
+                Arg::with_name("config")
+                    .long("config")
+                    .value_name("PATH")
+                    .help("Loads the config from a file in place of the default"),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .long("seed")
+                    .value_name("SEED")
+                    .help("Generates the world from a seed"),
+            )
+            .arg(
+                Arg::with_name("font")
+                    .long("font")
+                    .value_name("NAME")
+                    .help("Uses a font from the fonts directory"),
+            )
+            .arg(
+                Arg::with_name("window")
+                    .long("window")
+                    .value_name("WxH")
+                    .help("Sets the dimensions (in pixels) of the window, e.g. 1280x720"),
+            )
+            .arg(
+                Arg::with_name("fullscreen")
+                    .long("fullscreen")
+                    .help("Creates the window fullscreen"),
+            )
+            .arg(
+                Arg::with_name("replay")
+                    .long("replay")
+                    .value_name("FILE")
+                    .help("Replays a tape of inputs (one per line) before the game starts"),
+            )
+            .arg(
+                Arg::with_name("headless")
+                    .long("headless")
+                    .conflicts_with_all(&["font", "window", "fullscreen"])
+                    .help("Runs the server without a window, then exits"),
+            )
+            .arg(
+                Arg::with_name("stress")
+                    .long("stress")
+                    .help("Runs a zone packed with actors and reports turn (and frame) times"),
+            )
+            .arg(
+                Arg::with_name("actors")
+                    .long("actors")
+                    .value_name("N")
+                    .requires("stress")
+                    .help("# of extra actors to spawn when stress testing"),
+            )
+            .arg(
+                Arg::with_name("turns")
+                    .long("turns")
+                    .value_name("N")
+                    .help("# of turns to run when headless or stress testing"),
+            )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Parses the command-line args, exiting with a usage message if they are malformed.
+    //---------------------------------------------------------------------------------------------
+    pub fn parse() -> Result<Self> {
+        Self::from_matches(&Self::app().get_matches())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that converts matched args into the cli.
+    //---------------------------------------------------------------------------------------------
+    fn from_matches(matches: &ArgMatches) -> Result<Self> {
+        let seed = matches
+            .value_of("seed")
+            .map(|seed| seed.parse().with_context(|| format!("Invalid seed '{}'.", seed)))
+            .transpose()?;
+        let window_dimensions =
+            matches.value_of("window").map(Self::parse_window_dimensions).transpose()?;
+        let turns = matches
+            .value_of("turns")
+            .map(|turns| turns.parse().with_context(|| format!("Invalid # of turns '{}'.", turns)))
+            .transpose()?;
+        let actors = matches
+            .value_of("actors")
+            .map(|actors| {
+                actors.parse().with_context(|| format!("Invalid # of actors '{}'.", actors))
+            })
+            .transpose()?;
+        let stress = matches.is_present("stress");
+        let headless = matches.is_present("headless");
+
+        if turns.is_some() && !headless && !stress {
+            bail!("The # of turns is only used when headless or stress testing.");
+        }
+
+        Ok(Self {
+            overrides: ConfigOverrides {
+                path: matches.value_of("config").map(PathBuf::from),
+                seed,
+                font_name: matches.value_of("font").map(String::from),
+                window_dimensions,
+                window_type: if matches.is_present("fullscreen") {
+                    Some(WindowType::Fullscreen)
+                } else {
+                    None
+                },
+            },
+            replay: matches.value_of("replay").map(PathBuf::from),
+            headless,
+            stress,
+            actors: actors.unwrap_or(DEFAULT_STRESS_ACTORS),
+            turns: turns.unwrap_or(if stress { DEFAULT_STRESS_TURNS } else { 0 }),
+        })
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that parses window dimensions in the form "WxH".
+    //---------------------------------------------------------------------------------------------
+    fn parse_window_dimensions(s: &str) -> Result<ICoord> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| anyhow!("Invalid window dimensions '{}', expected WxH.", s))?;
+        let dimension = |d: &str| {
+            d.trim()
+                .parse::<i32>()
+                .ok()
+                .filter(|d| *d > 0)
+                .ok_or_else(|| anyhow!("Invalid window dimension '{}'.", d))
+        };
+
+        Ok((dimension(width)?, dimension(height)?))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_parse() {
+    let parse = |args: &[&str]| -> Result<Cli> {
+        let args = std::iter::once("fvr_engine").chain(args.iter().copied());
+        Cli::from_matches(&Cli::app().get_matches_from_safe(args)?)
+    };
+
+    // No args run the game with the config file.
+    let cli = parse(&[]).unwrap();
+    assert!(!cli.stress && !cli.headless);
+    assert_eq!(cli.turns, 0);
+    assert!(cli.overrides.path.is_none() && cli.overrides.seed.is_none());
+
+    // Stress testing defaults the # of turns and actors.
+    let cli = parse(&["--stress"]).unwrap();
+    assert!(cli.stress && !cli.headless);
+    assert_eq!(cli.turns, DEFAULT_STRESS_TURNS);
+    assert_eq!(cli.actors, DEFAULT_STRESS_ACTORS);
+
+    let cli = parse(&[
+        "--stress",
+        "--headless",
+        "--actors",
+        "50",
+        "--turns",
+        "20",
+        "--seed",
+        "1234",
+        "--config",
+        "stress.json",
+    ])
+    .unwrap();
+    assert!(cli.stress && cli.headless);
+    assert_eq!((cli.actors, cli.turns), (50, 20));
+    assert_eq!(cli.overrides.seed, Some(1234));
+    assert_eq!(cli.overrides.path, Some(PathBuf::from("stress.json")));
+
+    let cli =
+        parse(&["--window", "1280x720", "--fullscreen", "--font", "deja_vu_sans_mono"]).unwrap();
+    assert_eq!(cli.overrides.window_dimensions, Some((1280, 720)));
+    assert!(matches!(cli.overrides.window_type, Some(WindowType::Fullscreen)));
+    assert_eq!(cli.overrides.font_name.as_deref(), Some("deja_vu_sans_mono"));
+
+    // Malformed or misplaced args are rejected.
+    assert!(parse(&["--turns", "20"]).is_err());
+    assert!(parse(&["--actors", "50"]).is_err());
+    assert!(parse(&["--stress", "--turns", "many"]).is_err());
+    assert!(parse(&["--seed", "-1"]).is_err());
+    assert!(parse(&["--window", "1280"]).is_err());
+    assert!(parse(&["--window", "0x720"]).is_err());
+    assert!(parse(&["--headless", "--fullscreen"]).is_err());
+}
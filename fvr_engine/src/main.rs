@@ -1,12 +1,13 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
-use std::time::Duration;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 //-------------------------------------------------------------------------------------------------
 // Extern crate includes.
 //-------------------------------------------------------------------------------------------------
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 //-------------------------------------------------------------------------------------------------
 // Workspace includes.
@@ -18,59 +19,221 @@ use fvr_engine_server::prelude::*;
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
+mod cli;
+use cli::*;
+
 mod scene_stack;
 use scene_stack::*;
 
 mod scenes;
 use scenes::Initial;
 
+mod stress;
+use stress::*;
+
+//-------------------------------------------------------------------------------------------------
+// Handles an engine-level hotkey.
+//-------------------------------------------------------------------------------------------------
+fn run_hotkey(hotkey: Hotkey, server: &mut Server, client: &mut Client) {
+    match hotkey {
+        Hotkey::Screenshot => client.request_screenshot(),
+        Hotkey::ToggleFullscreen => match client.toggle_fullscreen() {
+            Ok(fullscreen) => println!("[Client] Fullscreen: {}.", fullscreen),
+            Err(e) => eprintln!("[Client] {:#}", e),
+        },
+        Hotkey::ToggleConsole => {
+            let _ = client.toggle_console();
+        }
+        Hotkey::ToggleDebugGui => client.toggle_debug(),
+        // Debug commands controlling the simulation clock.
+        Hotkey::TogglePause => {
+            println!("[Server] {}", server.run_debug_command(DebugCommand::TogglePause));
+        }
+        Hotkey::StepTurn => {
+            println!("[Server] {}", server.run_debug_command(DebugCommand::Step(1)));
+        }
+        Hotkey::SlowDown => {
+            println!("[Server] {}", server.run_debug_command(DebugCommand::ScaleSpeed(0.5)));
+        }
+        Hotkey::SpeedUp => {
+            println!("[Server] {}", server.run_debug_command(DebugCommand::ScaleSpeed(2.0)));
+        }
+        // Reload the shaders after editing them.
+        Hotkey::ReloadShaders => match client.reload_shaders() {
+            Ok(()) => println!("[Client] Reloaded shaders."),
+            Err(e) => eprintln!("[Client] {:#}", e),
+        },
+        // Show how many times each pixel is drawn per frame.
+        Hotkey::ToggleOverdraw => match client.toggle_overdraw_view() {
+            Ok(shown) => println!("[Client] Overdraw view shown: {}.", shown),
+            Err(e) => eprintln!("[Client] {:#}", e),
+        },
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Replays a tape of inputs against the server.
+//-------------------------------------------------------------------------------------------------
+fn run_replay(path: &Path, server: &mut Server) -> Result<()> {
+    let tape = TapeInput::load_tape(path)?;
+
+    for (i, input) in tape.iter().enumerate() {
+        input.apply(server).with_context(|| format!("Failed to replay input {}.", i + 1))?;
+    }
+
+    println!("[Server] Replayed {} inputs from {:?}.", tape.len(), path);
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------
+// Runs the server for a # of turns without a window, then reports where it ended up.
+//-------------------------------------------------------------------------------------------------
+fn run_headless(turns: u32, server: &mut Server) {
+    let start = Instant::now();
+
+    for _ in 0..turns {
+        server.tick();
+    }
+
+    println!(
+        "[Server] Ran {} turns in {:.2?}. Player at {:?}.",
+        turns,
+        start.elapsed(),
+        server.snapshot().player_xy()
+    );
+
+    if turns > 0 {
+        print!("[Server] {}", server.stats());
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Main.
 //-------------------------------------------------------------------------------------------------
 fn main() -> Result<()> {
-    // Initialize everything.
+    // Apply the command-line args before anything reads the config.
+    let cli = Cli::parse()?;
+    override_config(cli.overrides.clone())?;
+
+    // Create the server, bringing it up to date with the replay (if any).
+    let seed = CONFIG.seed.unwrap_or_else(rand::random);
+    let mut server = Server::with_seed(seed)?;
+
+    if let Some(path) = &cli.replay {
+        run_replay(path, &mut server)?;
+    }
+
+    if cli.stress {
+        let actors = server.spawn_stress_mobs(cli.actors)?;
+        println!("[Stress] Seed: {}. Spawned {} actors.", seed, actors);
+    }
+
+    match (cli.headless, cli.stress) {
+        (true, true) => {
+            println!("[Stress] Turn times: {}.", stress_server(&mut server, cli.turns));
+            return Ok(());
+        }
+        (true, false) => {
+            run_headless(cli.turns, &mut server);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Initialize everything else.
     let mut render_dt;
     let mut update_dt = Duration::from_secs(0);
     let mut update_timer = Timer::new(CONFIG.update_interval);
-    let mut server = Server::new()?;
     let mut client = Client::new()?;
     let mut terminal = Terminal::default();
-    let mut input = InputManager::with_default_bindings()?;
+    let mut input = InputManager::with_keybindings()?;
+
+    if cli.stress {
+        return stress_client(
+            &mut server,
+            &mut client,
+            &mut terminal,
+            &mut input,
+            cli.turns,
+            seed,
+        );
+    }
+
     let mut scene_stack = SceneStack::new();
-    scene_stack.push(Box::new(Initial::new()), &mut server, &mut terminal, &input)?;
+    scene_stack.push(Box::new(Initial::new()), &mut server, &mut terminal, &mut input)?;
 
     // Begin the game loop.
     'main: loop {
         while let Some(event) = client.poll_event() {
-            match event {
-                // Break immediately if quit event is received.
-                InputEvent::Quit { .. } => break 'main,
-                // Toggle the debug gui on space.
-                // TODO: Change this, obviously.
-                InputEvent::KeyDown { keycode: Some(InputKey::Space), .. } => {
-                    client.toggle_debug();
-                }
-                _ => {}
+            // Hotkeys are handled before the scenes see any input.
+            if let Some(hotkey) = input.handle_hotkey(&event) {
+                run_hotkey(hotkey, &mut server, &mut client);
+                continue;
             }
+
+            // Break immediately if quit event is received.
+            if let EngineEvent::Quit = event {
+                break 'main;
+            }
+
+            input.handle_event(&event);
+
+            // Let the current scene know when the window loses or regains focus.
+            if let EngineEvent::FocusLost | EngineEvent::FocusGained = event {
+                scene_stack.focus_changed(
+                    input.has_focus(),
+                    &mut server,
+                    &mut terminal,
+                    &input,
+                )?;
+            }
+        }
+
+        // Run the commands entered into the debug console.
+        for command in client.take_console_commands() {
+            let response = match command.parse::<DebugCommand>() {
+                Ok(command) => server.run_debug_command(command),
+                Err(e) => format!("{:#}", e),
+            };
+
+            client.console_print(response);
+        }
+
+        // Fit the terminal to the window (if enabled) and let the scenes reflow.
+        if let Some(dimensions) = client.resize_terminal(&mut terminal)? {
+            input.virtual_cursor_mut().set_dimensions(dimensions);
+            scene_stack.resize(&mut server, &mut terminal, &input)?;
         }
 
+        // Apply settings changed since the last loop (e.g. by the options menu).
+        client.sync_settings(&mut input)?;
+
         // Update the frame time counters.
         render_dt = client.update_input(&mut input);
         update_dt += render_dt;
 
-        // If enough time has passed, update the game state.
-        if update_timer.update(&render_dt) {
-            if !scene_stack.update(&mut server, &mut terminal, &input, &update_dt)? {
+        // Scale the update interval by the simulation speed.
+        update_timer.interval = server.update_interval(CONFIG.update_interval);
+
+        // Stop updating the game state while the window does not have focus (if configured),
+        // dropping the time and input that pass meanwhile so that it resumes cleanly.
+        // Otherwise, if enough time has passed, update the game state.
+        if CONFIG.background.pause_updates && !client.has_focus() {
+            update_timer.reset();
+            update_dt = Duration::from_secs(0);
+            input.reset();
+        } else if update_timer.update(&render_dt) {
+            if !scene_stack.update(&mut server, &mut terminal, &mut input, &update_dt)? {
                 break 'main;
             }
 
             input.reset();
-            update_dt -= CONFIG.update_interval;
+            update_dt = update_dt.saturating_sub(update_timer.interval);
         }
 
         // Always render the frame.
         scene_stack.render(&mut terminal, &render_dt)?;
-        let _ = client.render_frame(&terminal)?;
+        let _ = client.render_frame(&mut terminal)?;
     }
 
     Ok(())
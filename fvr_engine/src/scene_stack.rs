@@ -27,6 +27,9 @@ pub enum SceneAction {
     Pop,
     // The scene stack should swap the current scene with a new scene.
     Swap(Box<dyn Scene>),
+    // The scene stack should push a new scene over the current scene, restoring the terminal to
+    // how the current scene left it when the new scene is popped.
+    Overlay(Box<dyn Scene>),
 }
 
 impl Display for SceneAction {
@@ -36,18 +39,28 @@ impl Display for SceneAction {
             SceneAction::Push(_) => write!(f, "SceneAction::Push"),
             SceneAction::Pop => write!(f, "SceneAction::Pop"),
             SceneAction::Swap(_) => write!(f, "SceneAction::Swap"),
+            SceneAction::Overlay(_) => write!(f, "SceneAction::Overlay"),
         }
     }
 }
 
 //-------------------------------------------------------------------------------------------------
 // Describes the interface for anything that is handled by the scene stack.
+//
+// The lifecycle hooks are always called by the scene stack in the same order:
+// - Push:    on_suspend (current) -> on_enter (new)
+// - Overlay: on_suspend (current) -> on_enter (new), with the terminal stashed in between
+// - Pop:     on_exit (current) -> on_resume (previous), with the terminal restored in between if
+//            the popped scene was an overlay
+// - Swap:    on_exit (current) -> on_enter (new)
+// A scene should redraw the entire terminal in on_enter and on_resume rather than rely on update
+// having been called (unless it was resumed from under an overlay).
 //-------------------------------------------------------------------------------------------------
 pub trait Scene {
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is added to the stack.
+    // Called when the scene is added to the stack (e.g. to load its resources and draw).
     //---------------------------------------------------------------------------------------------
-    fn load(
+    fn on_enter(
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
@@ -55,34 +68,41 @@ pub trait Scene {
     ) -> Result<()>;
 
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is removed from the stack.
+    // Called when the scene is removed from the stack (e.g. to release its resources).
     //---------------------------------------------------------------------------------------------
-    fn unload(
+    fn on_exit(
         &mut self,
-        server: &mut Server,
-        terminal: &mut Terminal,
-        input: &InputManager,
-    ) -> Result<()>;
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is made current again (e.g. a the next scene was popped).
+    // Called when the scene is made current again because the scene above it was popped.
     //---------------------------------------------------------------------------------------------
-    fn focus(
+    fn on_resume(
         &mut self,
-        server: &mut Server,
-        terminal: &mut Terminal,
-        input: &InputManager,
-    ) -> Result<()>;
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    // Called when the scene is made no longer current because a scene was pushed or overlaid
+    // above it (e.g. to pause work that only matters while it is current).
     //---------------------------------------------------------------------------------------------
-    fn unfocus(
+    fn on_suspend(
         &mut self,
-        server: &mut Server,
-        terminal: &mut Terminal,
-        input: &InputManager,
-    ) -> Result<()>;
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     //---------------------------------------------------------------------------------------------
     // Called whenever the scene's (non-visual) internal state should be updated.
@@ -99,14 +119,56 @@ pub trait Scene {
     // Called whenever the scene's (visual) internal state should be updated and rendered.
     //---------------------------------------------------------------------------------------------
     fn render(&mut self, terminal: &mut Terminal, dt: &Duration) -> Result<()>;
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the window loses or regains focus (e.g. to pause real-time effects).
+    // (updates stop while the window does not have focus if configured to)
+    //---------------------------------------------------------------------------------------------
+    fn on_focus_changed(
+        &mut self,
+        _focused: bool,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::Map
+    }
 }
 
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Prefix of the names the terminal is stashed under beneath overlays (followed by the depth).
+const OVERLAY_STASH_PREFIX: &str = "scene_stack.overlay.";
+
 //-------------------------------------------------------------------------------------------------
 // Scene stack describes a stack of scene objects.
 //-------------------------------------------------------------------------------------------------
 pub struct SceneStack {
     // The stack of scenes.
     scenes: Vec<Box<dyn Scene>>,
+    // Depth of the input context stack before each scene pushed its input context.
+    context_depths: Vec<usize>,
 }
 
 impl SceneStack {
@@ -115,7 +177,7 @@ impl SceneStack {
     // (there should only ever be one)
     //---------------------------------------------------------------------------------------------
     pub fn new() -> Self {
-        Self { scenes: Default::default() }
+        Self { scenes: Default::default(), context_depths: Default::default() }
     }
 
     //---------------------------------------------------------------------------------------------
@@ -126,7 +188,7 @@ impl SceneStack {
         scene: Box<dyn Scene>,
         server: &mut Server,
         terminal: &mut Terminal,
-        input: &InputManager,
+        input: &mut InputManager,
     ) -> Result<()> {
         #[cfg(debug_assertions)]
         println!("[SceneStack] Push - current stack len: {}.", self.scenes.len());
@@ -134,21 +196,49 @@ impl SceneStack {
         // Reset the cursor
         input.set_cursor(Cursor::Arrow);
 
-        // Unfocus the current scene if present.
+        // Suspend the current scene if present.
         match self.scenes.last_mut() {
-            Some(s) => s.unfocus(server, terminal, input),
+            Some(s) => s.on_suspend(server, terminal, input),
             _ => Ok(()),
         }?;
 
-        // Push the new scene.
+        // Push the new scene and its input context.
+        self.context_depths.push(input.context_depth());
+        input.push_context(scene.input_context());
         self.scenes.push(scene);
 
-        // Call load on the new scene.
-        self.scenes.last_mut().unwrap().load(server, terminal, input)?;
+        // Enter the new scene.
+        self.scenes.last_mut().unwrap().on_enter(server, terminal, input)?;
 
         Ok(())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Pushes a new scene over the current scene, stashing the terminal until it is popped.
+    //---------------------------------------------------------------------------------------------
+    pub fn overlay(
+        &mut self,
+        scene: Box<dyn Scene>,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &mut InputManager,
+    ) -> Result<()> {
+        // Stash the terminal before the current scene is suspended and the overlay draws.
+        if !self.scenes.is_empty() {
+            terminal.stash(Self::overlay_stash_name(self.scenes.len()));
+        }
+
+        self.push(scene, server, terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the name the terminal is stashed under beneath an overlay at a
+    // depth of the stack.
+    //---------------------------------------------------------------------------------------------
+    fn overlay_stash_name(depth: usize) -> String {
+        format!("{}{}", OVERLAY_STASH_PREFIX, depth)
+    }
+
     //---------------------------------------------------------------------------------------------
     // Pops the current scene off the stack.
     //---------------------------------------------------------------------------------------------
@@ -156,7 +246,7 @@ impl SceneStack {
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
-        input: &InputManager,
+        input: &mut InputManager,
     ) -> Result<()> {
         #[cfg(debug_assertions)]
         println!("[SceneStack] Pop  - current stack len: {}.", self.scenes.len());
@@ -164,15 +254,21 @@ impl SceneStack {
         // Reset the cursor
         input.set_cursor(Cursor::Arrow);
 
-        // Call unload on the current scene.
-        self.scenes.last_mut().unwrap().unload(server, terminal, input)?;
+        // Exit the current scene.
+        self.scenes.last_mut().unwrap().on_exit(server, terminal, input)?;
 
-        // Pop the current scene.
+        // Pop the current scene and restore the input contexts to how they were before it.
         let _ = self.scenes.pop();
+        if let Some(depth) = self.context_depths.pop() {
+            input.truncate_contexts(depth);
+        }
+
+        // Restore the terminal if the popped scene was an overlay.
+        terminal.unstash(&Self::overlay_stash_name(self.scenes.len()));
 
-        // If a previous scene exists, call focus on it.
+        // If a previous scene exists, resume it.
         match self.scenes.last_mut() {
-            Some(s) => s.focus(server, terminal, input),
+            Some(s) => s.on_resume(server, terminal, input),
             _ => Ok(()),
         }?;
 
@@ -187,7 +283,7 @@ impl SceneStack {
         scene: Box<dyn Scene>,
         server: &mut Server,
         terminal: &mut Terminal,
-        input: &InputManager,
+        input: &mut InputManager,
     ) -> Result<()> {
         #[cfg(debug_assertions)]
         println!("[SceneStack] Swap - current stack len: {}.", self.scenes.len());
@@ -195,17 +291,22 @@ impl SceneStack {
         // Reset the cursor
         input.set_cursor(Cursor::Arrow);
 
-        // Call unload on the current scene.
-        self.scenes.last_mut().unwrap().unload(server, terminal, input)?;
+        // Exit the current scene.
+        self.scenes.last_mut().unwrap().on_exit(server, terminal, input)?;
 
-        // Pop the current scene.
+        // Pop the current scene and restore the input contexts to how they were before it.
         let _ = self.scenes.pop();
+        if let Some(depth) = self.context_depths.pop() {
+            input.truncate_contexts(depth);
+        }
 
-        // Push the new scene.
+        // Push the new scene and its input context.
+        self.context_depths.push(input.context_depth());
+        input.push_context(scene.input_context());
         self.scenes.push(scene);
 
-        // Call load on the new scene.
-        self.scenes.last_mut().unwrap().load(server, terminal, input)?;
+        // Enter the new scene.
+        self.scenes.last_mut().unwrap().on_enter(server, terminal, input)?;
 
         Ok(())
     }
@@ -218,7 +319,7 @@ impl SceneStack {
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
-        input: &InputManager,
+        input: &mut InputManager,
         dt: &Duration,
     ) -> Result<bool> {
         // Return false if no scenes exist on the stack.
@@ -232,12 +333,45 @@ impl SceneStack {
             SceneAction::Push(scene) => self.push(scene, server, terminal, input)?,
             SceneAction::Pop => self.pop(server, terminal, input)?,
             SceneAction::Swap(scene) => self.swap(scene, server, terminal, input)?,
+            SceneAction::Overlay(scene) => self.overlay(scene, server, terminal, input)?,
         }
 
         // Return false if no scenes exist on the stack.
         Ok(!self.scenes.is_empty())
     }
 
+    //---------------------------------------------------------------------------------------------
+    // Notifies the current scene that the terminal has been resized.
+    // (other scenes redraw when they are focused again)
+    //---------------------------------------------------------------------------------------------
+    pub fn resize(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        match self.scenes.last_mut() {
+            Some(s) => s.resize(server, terminal, input),
+            _ => Ok(()),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Notifies the current scene that the window lost or regained focus.
+    //---------------------------------------------------------------------------------------------
+    pub fn focus_changed(
+        &mut self,
+        focused: bool,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        match self.scenes.last_mut() {
+            Some(s) => s.on_focus_changed(focused, server, terminal, input),
+            _ => Ok(()),
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // Renders the current scene.
     //---------------------------------------------------------------------------------------------
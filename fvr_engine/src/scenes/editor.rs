@@ -0,0 +1,696 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Dimensions of the editable canvas (drawn at the terminal origin).
+const CANVAS_DIMENSIONS: ICoord = (55, 33);
+
+// Number of layers in the canvas.
+const LAYER_COUNT: usize = 3;
+
+// Number of undo steps kept in the history.
+const HISTORY_CAPACITY: usize = 100;
+
+// Path of the prefab file used for saving and loading, relative to the save directory.
+const PREFAB_PATH: &str = "prefabs/editor.xp";
+
+// Origin and inner dimensions of the sidebar frame.
+const SIDEBAR_ORIGIN: ICoord = (55, 0);
+const SIDEBAR_INNER_DIMENSIONS: ICoord = (28, 31);
+
+// Origins of the sidebar contents.
+const TOOLS_ORIGIN: ICoord = (59, 2);
+const PALETTE_ORIGIN: ICoord = (61, 8);
+const LAYERS_ORIGIN: ICoord = (57, 20);
+const STATUS_ORIGIN: ICoord = (57, 25);
+const HELP_ORIGIN: ICoord = (57, 27);
+
+// Column of the palette tile previews.
+const PREVIEW_X: i32 = 59;
+
+// Column and glyph of the marker drawn next to the selected tool and palette entry.
+const MARKER_X: i32 = 57;
+const SELECTED_MARKER: char = '►';
+
+// Max length of the status message.
+const STATUS_MAX_LEN: usize = 26;
+
+// Help text for the keyboard shortcuts.
+const HELP_TEXT: &str = "[1-3] layer  [v] show/hide\n\
+                         [ctrl+s] save [ctrl+o] load\n\
+                         [ctrl+z] undo [ctrl+y] redo\n\
+                         [rmb] cancel [esc] quit";
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the brush tools of the editor.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    // Paints single tiles while the mouse is held.
+    Point,
+    // Paints the outline of a rect between two clicks.
+    Rect,
+    // Flood fills the area under the cursor.
+    Fill,
+    // Paints a line between two clicks.
+    Line,
+}
+
+impl Tool {
+    //---------------------------------------------------------------------------------------------
+    // Returns the tool for an index into the tools button list.
+    //---------------------------------------------------------------------------------------------
+    fn from_index(index: i32) -> Self {
+        match index {
+            0 => Tool::Point,
+            1 => Tool::Rect,
+            2 => Tool::Fill,
+            _ => Tool::Line,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the index of the tool in the tools button list.
+    //---------------------------------------------------------------------------------------------
+    fn index(&self) -> i32 {
+        match self {
+            Tool::Point => 0,
+            Tool::Rect => 1,
+            Tool::Fill => 2,
+            Tool::Line => 3,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Records the tiles of a layer changed by a single use of a tool.
+//-------------------------------------------------------------------------------------------------
+struct PaintCommand {
+    // Index of the painted layer.
+    layer: usize,
+    // Changed tiles as (index, old tile, new tile).
+    changes: Vec<(usize, Tile, Tile)>,
+}
+
+impl Command<Vec<GridMap<Tile>>> for PaintCommand {
+    //---------------------------------------------------------------------------------------------
+    // Applies the new tiles to the layer.
+    //---------------------------------------------------------------------------------------------
+    fn execute(&mut self, layers: &mut Vec<GridMap<Tile>>) {
+        for (index, _, new) in self.changes.iter() {
+            *layers[self.layer].get_mut(*index) = *new;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Restores the old tiles to the layer.
+    //---------------------------------------------------------------------------------------------
+    fn undo(&mut self, layers: &mut Vec<GridMap<Tile>>) {
+        for (index, old, _) in self.changes.iter() {
+            *layers[self.layer].get_mut(*index) = *old;
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Wraps a layer while a tool paints it, remembering the original tile of every cell written to,
+// so that a stroke records only the cells it touched.
+//-------------------------------------------------------------------------------------------------
+struct StrokeRecorder<'a> {
+    // The painted layer.
+    layer: &'a mut GridMap<Tile>,
+    // Original tiles of the written cells by index.
+    originals: BTreeMap<usize, Tile>,
+}
+
+impl<'a> StrokeRecorder<'a> {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new stroke recorder for a layer.
+    //---------------------------------------------------------------------------------------------
+    fn new(layer: &'a mut GridMap<Tile>) -> Self {
+        Self { layer, originals: BTreeMap::new() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the cells that look different after the stroke as (index, old tile, new tile).
+    //---------------------------------------------------------------------------------------------
+    fn changes(self) -> Vec<(usize, Tile, Tile)> {
+        let layer = self.layer;
+
+        self.originals
+            .into_iter()
+            .map(|(i, old)| (i, old, *layer.get(i)))
+            .filter(|(_, old, new)| !TerminalDraw::tiles_match(old, new))
+            .collect()
+    }
+}
+
+impl<'a> Map2dView for StrokeRecorder<'a> {
+    type Type = Tile;
+
+    fn width(&self) -> i32 {
+        self.layer.width()
+    }
+
+    fn height(&self) -> i32 {
+        self.layer.height()
+    }
+
+    fn dimensions(&self) -> ICoord {
+        self.layer.dimensions()
+    }
+
+    fn get(&self, index: usize) -> &Tile {
+        self.layer.get(index)
+    }
+
+    fn get_xy(&self, xy: ICoord) -> &Tile {
+        self.layer.get_xy(xy)
+    }
+}
+
+impl<'a> Map2dViewMut for StrokeRecorder<'a> {
+    type Type = Tile;
+
+    fn get_mut(&mut self, index: usize) -> &mut Tile {
+        let original = *self.layer.get(index);
+        self.originals.entry(index).or_insert(original);
+        self.layer.get_mut(index)
+    }
+
+    fn get_xy_mut(&mut self, xy: ICoord) -> &mut Tile {
+        let index = Misc::index_2d(xy, self.layer.width());
+        self.get_mut(index)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Creates a palette tile with a glyph and foreground color on a black background.
+//-------------------------------------------------------------------------------------------------
+fn palette_tile(glyph: char, color: PaletteColor) -> Tile {
+    Tile {
+        glyph,
+        foreground_color: color.into(),
+        background_color: TileColor::BLACK,
+        ..BLANK_TILE
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// A tile and terrain editor for authoring prefabs.
+// Layers are saved to and loaded from REXPaint .xp files.
+//-------------------------------------------------------------------------------------------------
+pub struct Editor {
+    // Canvas layers, drawn bottom to top.
+    layers: Vec<GridMap<Tile>>,
+    // Visibility of each layer.
+    visible: Vec<bool>,
+    // Index of the layer being painted.
+    current_layer: usize,
+    // The active brush tool.
+    tool: Tool,
+    // Named tiles available for painting.
+    palette: Vec<(&'static str, Tile)>,
+    // Index of the selected palette tile.
+    palette_index: usize,
+    // First coord of a two-click tool (rect or line).
+    anchor: Option<ICoord>,
+    // Buttons for selecting a tool.
+    tool_buttons: ButtonList,
+    // Buttons for selecting a palette tile.
+    palette_buttons: ButtonList,
+    // Status message shown in the sidebar.
+    status: String,
+    // Undo/redo history of the painted layers.
+    history: CommandStack<Vec<GridMap<Tile>>>,
+}
+
+impl Editor {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new editor scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        let palette = vec![
+            ("Eraser", BLANK_TILE),
+            ("Floor", palette_tile('.', PaletteColor::DarkGrey)),
+            ("Wall", palette_tile('#', PaletteColor::BrightGrey)),
+            ("Grass", palette_tile('"', PaletteColor::DarkGreen)),
+            ("Tree", palette_tile('♣', PaletteColor::BrightGreen)),
+            ("Water", palette_tile('≈', PaletteColor::BrightBlue)),
+            ("Door", palette_tile('+', PaletteColor::Brown)),
+            ("Stairs", palette_tile('>', PaletteColor::Yellow)),
+        ];
+
+        let tool_buttons = ButtonList::from_buttons_vec(
+            TOOLS_ORIGIN,
+            vec![
+                Button::new((0, 0), String::from("[p] Point"), ButtonLayout::Text),
+                Button::new((0, 0), String::from("[r] Rect"), ButtonLayout::Text),
+                Button::new((0, 0), String::from("[f] Fill"), ButtonLayout::Text),
+                Button::new((0, 0), String::from("[l] Line"), ButtonLayout::Text),
+            ],
+            false,
+        );
+
+        let palette_buttons = ButtonList::from_buttons_vec(
+            PALETTE_ORIGIN,
+            palette
+                .iter()
+                .map(|(name, _)| Button::new((0, 0), String::from(*name), ButtonLayout::Text))
+                .collect(),
+            false,
+        );
+
+        let layers = (0..LAYER_COUNT)
+            .map(|_| {
+                let mut layer = GridMap::new(CANVAS_DIMENSIONS);
+                layer.data_mut().fill(BLANK_TILE);
+                layer
+            })
+            .collect();
+
+        Self {
+            layers,
+            visible: vec![true; LAYER_COUNT],
+            current_layer: 0,
+            tool: Tool::Point,
+            palette,
+            palette_index: 1,
+            anchor: None,
+            tool_buttons,
+            palette_buttons,
+            status: String::new(),
+            history: CommandStack::new(HISTORY_CAPACITY),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether a tile is empty (and should let lower layers show through).
+    //---------------------------------------------------------------------------------------------
+    fn is_empty(tile: &Tile) -> bool {
+        tile.glyph == ' ' && tile.background_color.0.a == 0
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the visible layers onto the canvas area of the terminal.
+    //---------------------------------------------------------------------------------------------
+    fn redraw_canvas(&self, terminal: &mut Terminal) {
+        for x in 0..CANVAS_DIMENSIONS.0 {
+            for y in 0..CANVAS_DIMENSIONS.1 {
+                let mut tile = BLANK_TILE;
+
+                for (layer, _) in self.layers.iter().zip(self.visible.iter()).filter(|(_, v)| **v)
+                {
+                    let layer_tile = layer.get_xy((x, y));
+
+                    if !Self::is_empty(layer_tile) {
+                        tile = *layer_tile;
+                    }
+                }
+
+                *terminal.get_xy_mut((x, y)) = tile;
+            }
+        }
+
+        // Highlight the anchor of a pending two-click tool.
+        if let Some(anchor) = self.anchor {
+            let tile = terminal.get_xy_mut(anchor);
+            tile.background_color = PaletteColor::White.into();
+            tile.background_opacity = 0.25;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Draws the sidebar frame, tools, palette, layers, and status.
+    //---------------------------------------------------------------------------------------------
+    fn redraw_sidebar(&self, terminal: &mut Terminal) -> Result<()> {
+        let mut frame = Frame::new(SIDEBAR_ORIGIN, SIDEBAR_INNER_DIMENSIONS, FrameStyle::Line);
+        frame.top_left_text = Some("Editor".into());
+        frame.draw_clear(terminal)?;
+
+        // Tools, with a marker next to the active tool.
+        self.tool_buttons.redraw(terminal);
+        let tool_y = TOOLS_ORIGIN.1 + self.tool.index();
+        terminal.get_xy_mut((MARKER_X, tool_y)).glyph = SELECTED_MARKER;
+
+        // Palette, with a preview of each tile and a marker next to the selected tile.
+        self.palette_buttons.redraw(terminal);
+
+        for (i, (_, tile)) in self.palette.iter().enumerate() {
+            *terminal.get_xy_mut((PREVIEW_X, PALETTE_ORIGIN.1 + i as i32)) = *tile;
+        }
+
+        let palette_y = PALETTE_ORIGIN.1 + self.palette_index as i32;
+        terminal.get_xy_mut((MARKER_X, palette_y)).glyph = SELECTED_MARKER;
+
+        // Layers.
+        for i in 0..LAYER_COUNT {
+            let text = format!(
+                "{} Layer {} [{}]",
+                if i == self.current_layer { SELECTED_MARKER } else { ' ' },
+                i + 1,
+                if self.visible[i] { 'x' } else { ' ' },
+            );
+            RichTextWriter::write_plain(
+                terminal,
+                (LAYERS_ORIGIN.0, LAYERS_ORIGIN.1 + i as i32),
+                &text,
+            );
+        }
+
+        // Status and help.
+        let status: String = self.status.chars().take(STATUS_MAX_LEN).collect();
+        RichTextWriter::write_plain(terminal, STATUS_ORIGIN, &status);
+        RichTextWriter::write_plain(terminal, HELP_ORIGIN, HELP_TEXT);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Redraws the entire scene.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, terminal: &mut Terminal) -> Result<()> {
+        self.redraw_canvas(terminal);
+        self.redraw_sidebar(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Applies the active tool at a canvas coord, recording the changed tiles in the history.
+    // (returns whether the canvas needs to be redrawn)
+    //---------------------------------------------------------------------------------------------
+    fn apply_tool(&mut self, xy: ICoord, clicked: bool) -> bool {
+        let tile = self.palette[self.palette_index].1;
+        let mut layer = StrokeRecorder::new(&mut self.layers[self.current_layer]);
+
+        if !Self::paint(self.tool, &mut self.anchor, &mut layer, &tile, xy, clicked) {
+            return false;
+        }
+
+        // Record the changed tiles so they can be undone.
+        let changes = layer.changes();
+
+        if !changes.is_empty() {
+            let command = PaintCommand { layer: self.current_layer, changes };
+            self.history.execute(Box::new(command), &mut self.layers);
+        }
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Paints a tile with a tool at a canvas coord, returning whether the canvas changed.
+    //---------------------------------------------------------------------------------------------
+    fn paint(
+        tool: Tool,
+        anchor: &mut Option<ICoord>,
+        layer: &mut StrokeRecorder,
+        tile: &Tile,
+        xy: ICoord,
+        clicked: bool,
+    ) -> bool {
+        let tile = *tile;
+
+        match tool {
+            Tool::Point => {
+                *layer.get_xy_mut(xy) = tile;
+                true
+            }
+            Tool::Fill if clicked => {
+                TerminalDraw::flood_fill(layer, xy, &tile);
+                true
+            }
+            Tool::Rect | Tool::Line if clicked => match anchor.take() {
+                None => {
+                    *anchor = Some(xy);
+                    true
+                }
+                Some(anchor) => {
+                    if tool == Tool::Rect {
+                        let origin = (anchor.0.min(xy.0), anchor.1.min(xy.1));
+                        let width = (anchor.0 - xy.0).abs() + 1;
+                        let height = (anchor.1 - xy.1).abs() + 1;
+                        TerminalDraw::draw_rect(layer, &Rect::new(origin, width, height), &tile);
+                    } else {
+                        TerminalDraw::draw_line(layer, anchor, xy, &tile);
+                    }
+                    true
+                }
+            },
+            _ => false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves the layers to the prefab file.
+    //---------------------------------------------------------------------------------------------
+    fn save(&mut self) -> Result<()> {
+        let path = PLATFORM_DIRS.save_dir().join(PREFAB_PATH);
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let layers: Vec<&GridMap<Tile>> = self.layers.iter().collect();
+        RexPaint::save(path, &layers)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Loads the layers from the prefab file, clipping them to the canvas.
+    //---------------------------------------------------------------------------------------------
+    fn load_prefab(&mut self) -> Result<()> {
+        let loaded = RexPaint::load(PLATFORM_DIRS.save_dir().join(PREFAB_PATH))?;
+        self.history.clear();
+
+        for (layer, loaded) in self.layers.iter_mut().zip(loaded.iter()) {
+            layer.data_mut().fill(BLANK_TILE);
+
+            for x in 0..loaded.width().min(CANVAS_DIMENSIONS.0) {
+                for y in 0..loaded.height().min(CANVAS_DIMENSIONS.1) {
+                    *layer.get_xy_mut((x, y)) = *loaded.get_xy((x, y));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Handles keyboard shortcuts, returning whether anything changed.
+    //---------------------------------------------------------------------------------------------
+    fn handle_keys(&mut self, input: &InputManager) -> bool {
+        let ctrl = input.modifier_pressed(&ModifierKey::Ctrl);
+
+        if ctrl && input.key_just_pressed(InputKey::Z) {
+            self.status = if self.history.undo(&mut self.layers) {
+                "Undo".into()
+            } else {
+                "Nothing to undo".into()
+            };
+        } else if ctrl && input.key_just_pressed(InputKey::Y) {
+            self.status = if self.history.redo(&mut self.layers) {
+                "Redo".into()
+            } else {
+                "Nothing to redo".into()
+            };
+        } else if ctrl && input.key_just_pressed(InputKey::S) {
+            self.status = match self.save() {
+                Ok(_) => format!("Saved {}", PREFAB_PATH),
+                Err(e) => format!("Save failed: {}", e),
+            };
+        } else if ctrl && input.key_just_pressed(InputKey::O) {
+            self.status = match self.load_prefab() {
+                Ok(_) => format!("Loaded {}", PREFAB_PATH),
+                Err(e) => format!("Load failed: {}", e),
+            };
+        } else if input.key_just_pressed(InputKey::P) {
+            self.tool = Tool::Point;
+        } else if input.key_just_pressed(InputKey::R) {
+            self.tool = Tool::Rect;
+        } else if input.key_just_pressed(InputKey::F) {
+            self.tool = Tool::Fill;
+        } else if input.key_just_pressed(InputKey::L) {
+            self.tool = Tool::Line;
+        } else if input.key_just_pressed(InputKey::Num1) {
+            self.current_layer = 0;
+        } else if input.key_just_pressed(InputKey::Num2) {
+            self.current_layer = 1;
+        } else if input.key_just_pressed(InputKey::Num3) {
+            self.current_layer = 2;
+        } else if input.key_just_pressed(InputKey::V) {
+            self.visible[self.current_layer] = !self.visible[self.current_layer];
+        } else {
+            return false;
+        }
+
+        self.anchor = None;
+        true
+    }
+}
+
+impl Scene for Editor {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)?;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        terminal.set_all_tiles_blank();
+        self.tool_buttons.reset();
+        self.palette_buttons.reset();
+        self.redraw(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        if input.action_just_pressed(InputAction::Quit) || input.key_just_pressed(InputKey::Escape)
+        {
+            return Ok(SceneAction::Pop);
+        }
+
+        let mut dirty = self.handle_keys(input);
+
+        // Sidebar buttons.
+        let tool_action = self.tool_buttons.update(input, terminal);
+        let palette_action = self.palette_buttons.update(input, terminal);
+
+        if let ButtonListAction::Triggered(i) = tool_action {
+            self.tool = Tool::from_index(i);
+            self.anchor = None;
+            dirty = true;
+        }
+
+        if let ButtonListAction::Triggered(i) = palette_action {
+            self.palette_index = i as usize;
+            dirty = true;
+        }
+
+        if tool_action != ButtonListAction::Noop || palette_action != ButtonListAction::Noop {
+            input.set_cursor(Cursor::Hand);
+        } else if let Some(xy) = input.mouse_coord() {
+            let in_canvas =
+                Rect::new((0, 0), CANVAS_DIMENSIONS.0, CANVAS_DIMENSIONS.1).contains(xy);
+
+            if in_canvas {
+                input.set_cursor(Cursor::Crosshair);
+
+                if input.mouse_clicked(InputMouse::Right) && self.anchor.is_some() {
+                    self.anchor = None;
+                    dirty = true;
+                } else if input.mouse_pressed(InputMouse::Left) {
+                    let clicked = input.mouse_clicked(InputMouse::Left);
+
+                    // Strokes painted while the button is held are undone as a single step.
+                    if clicked {
+                        self.history.begin_group();
+                    }
+
+                    dirty |= self.apply_tool(xy, clicked);
+                }
+            } else {
+                input.set_cursor(Cursor::Arrow);
+            }
+        }
+
+        if !input.mouse_pressed(InputMouse::Left) {
+            self.history.end_group();
+        }
+
+        if dirty {
+            self.redraw(terminal)?;
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_all_tiles_blank();
+        self.redraw(terminal)
+    }
+}
@@ -0,0 +1,169 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns and rows left between the keybinding help and the edges of the terminal.
+const MARGIN: ICoord = (4, 2);
+
+//-------------------------------------------------------------------------------------------------
+// The help scene, showing a searchable reference of the current keybindings.
+//-------------------------------------------------------------------------------------------------
+pub struct Help {
+    // Keybinding reference, created when the scene is loaded.
+    keybinding_help: Option<KeybindingHelp>,
+}
+
+impl Help {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new help scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self { keybinding_help: None }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the keybinding help for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, terminal: &mut Terminal, input: &InputManager) -> Result<()> {
+        terminal.set_all_tiles_blank();
+
+        // The inner dimensions exclude the panel's border and shadow.
+        let inner_dimensions = (
+            (terminal.width() - MARGIN.0 * 2 - 3).max(1),
+            (terminal.height() - MARGIN.1 * 2 - 3).max(1),
+        );
+        let keybinding_help = KeybindingHelp::new(MARGIN, inner_dimensions, input);
+        keybinding_help.redraw(terminal)?;
+        self.keybinding_help = Some(keybinding_help);
+
+        Ok(())
+    }
+}
+
+impl Scene for Help {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        self.draw(terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        let keybinding_help = match self.keybinding_help.as_mut() {
+            Some(keybinding_help) => keybinding_help,
+            None => return Ok(SceneAction::Noop),
+        };
+
+        // Escape clears the search first, and leaves the scene once there is nothing to clear.
+        if keybinding_help.filter().is_empty()
+            && (input.key_just_pressed(InputKey::Escape)
+                || input.action_just_pressed(InputAction::Decline))
+        {
+            return Ok(SceneAction::Pop);
+        }
+
+        let _ = keybinding_help.update(input, terminal);
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.draw(terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    // (typed keys search the bindings rather than triggering actions)
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::TextEntry
+    }
+}
@@ -19,7 +19,7 @@ use fvr_engine_server::prelude::*;
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::scene_stack::*;
-use crate::scenes::main_menu::*;
+use crate::scenes::title::*;
 use crate::scenes::transitions::*;
 
 //-------------------------------------------------------------------------------------------------
@@ -57,7 +57,7 @@ enum State {
     FadeOut,
     // The final blank state.
     FinalBlank,
-    // Ready to swap to the main menu.
+    // Ready to swap to the title screen.
     Finished,
 }
 
@@ -87,20 +87,11 @@ impl Initial {
             fade_out: Fade::new(&FADE_DURATION, 1.0, 0.0),
         }
     }
-}
 
-impl Scene for Initial {
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is added to the stack.
+    // Helper function that draws the logo centered in the terminal.
     //---------------------------------------------------------------------------------------------
-    fn load(
-        &mut self,
-        _server: &mut Server,
-        terminal: &mut Terminal,
-        _input: &InputManager,
-    ) -> Result<()> {
-        // Reset the terminal.
-        terminal.set_transparent();
+    fn draw_logo(terminal: &mut Terminal) -> Result<()> {
         terminal.set_all_tiles_blank();
 
         // Find dimensions of the title text.
@@ -128,11 +119,27 @@ impl Scene for Initial {
 
         Ok(())
     }
+}
+
+impl Scene for Initial {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        // Reset the terminal.
+        terminal.set_transparent();
+        Self::draw_logo(terminal)
+    }
 
     //---------------------------------------------------------------------------------------------
     // Called when the scene is removed from the stack.
     //---------------------------------------------------------------------------------------------
-    fn unload(
+    fn on_exit(
         &mut self,
         _server: &mut Server,
         _terminal: &mut Terminal,
@@ -144,7 +151,7 @@ impl Scene for Initial {
     //---------------------------------------------------------------------------------------------
     // Called when the scene is made current again (e.g. a the next scene was popped).
     //---------------------------------------------------------------------------------------------
-    fn focus(
+    fn on_resume(
         &mut self,
         _server: &mut Server,
         _terminal: &mut Terminal,
@@ -156,7 +163,7 @@ impl Scene for Initial {
     //---------------------------------------------------------------------------------------------
     // Called when the scene is made no longer current (e.g. a new scene is pushed).
     //---------------------------------------------------------------------------------------------
-    fn unfocus(
+    fn on_suspend(
         &mut self,
         _server: &mut Server,
         _terminal: &mut Terminal,
@@ -176,7 +183,7 @@ impl Scene for Initial {
         dt: &Duration,
     ) -> Result<SceneAction> {
         if input.any_key_pressed() {
-            return Ok(SceneAction::Swap(Box::new(MainMenu::new())));
+            return Ok(SceneAction::Swap(Box::new(Title::new())));
         }
 
         match self.state {
@@ -213,7 +220,7 @@ impl Scene for Initial {
         }
 
         if self.state == State::Finished {
-            Ok(SceneAction::Swap(Box::new(MainMenu::new())))
+            Ok(SceneAction::Swap(Box::new(Title::new())))
         } else {
             Ok(SceneAction::Noop)
         }
@@ -235,4 +242,15 @@ impl Scene for Initial {
 
         Ok(())
     }
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Self::draw_logo(terminal)
+    }
 }
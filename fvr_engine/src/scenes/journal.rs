@@ -0,0 +1,395 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns and rows left between the journal and the edges of the terminal.
+const MARGIN: ICoord = (4, 2);
+
+// Texts of the panel's frame.
+const TITLE_TEXT: &str = "Journal";
+const HINT_TEXT: &str = "tab switch, esc close";
+
+// Titles of the tabs, in the order of JournalTab.
+const TAB_TITLES: [&str; 4] = ["Active quests", "Completed quests", "Bestiary", "Lore"];
+
+// Widths of the bestiary table's columns (the last takes the remaining width).
+const KIND_COLUMN_WIDTH: i32 = 24;
+const GLYPH_COLUMN_WIDTH: i32 = 6;
+
+// Max # of lines of the quest and lore texts.
+const MAX_TEXT_LINES: i32 = 1024;
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the tabs of the journal.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JournalTab {
+    ActiveQuests,
+    CompletedQuests,
+    Bestiary,
+    Lore,
+}
+
+impl JournalTab {
+    //---------------------------------------------------------------------------------------------
+    // Returns the tab at an index of the tab container.
+    //---------------------------------------------------------------------------------------------
+    fn from_index(index: i32) -> Self {
+        match index {
+            1 => JournalTab::CompletedQuests,
+            2 => JournalTab::Bestiary,
+            3 => JournalTab::Lore,
+            _ => JournalTab::ActiveQuests,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// The journal scene, showing the quests, bestiary and lore recorded in the server's journal.
+//-------------------------------------------------------------------------------------------------
+pub struct JournalScene {
+    // Panel drawn behind the journal.
+    panel: Panel,
+    // Tab headers, created when the scene is laid out.
+    tabs: Option<TabContainer>,
+    // Text of the quest and lore tabs, created when the scene is laid out.
+    text: Option<RichTextWrapper>,
+    // Table of the bestiary tab, created when the scene is laid out.
+    bestiary: Option<Table>,
+}
+
+impl JournalScene {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new journal scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self {
+            panel: Panel::new((0, 0), (1, 1), FrameStyle::Line),
+            tabs: None,
+            text: None,
+            bestiary: None,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the current tab.
+    //---------------------------------------------------------------------------------------------
+    fn current_tab(&self) -> JournalTab {
+        JournalTab::from_index(self.tabs.as_ref().map(|tabs| tabs.current()).unwrap_or(0))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that fills the text with the quests of a status.
+    //---------------------------------------------------------------------------------------------
+    fn fill_quests(
+        text: &mut RichTextWrapper,
+        journal: &Journal,
+        status: QuestStatus,
+    ) -> Result<()> {
+        let mut empty = true;
+
+        for quest in journal.quests_with_status(status) {
+            let _ = text.push_line(&format!("<l:t><st:b><fc:$>{}", quest.title), Vec::new())?;
+            let _ = text.push_line(&format!("<l:t><fc:y>{}", quest.description), Vec::new())?;
+            let _ = text.push_line("", Vec::new())?;
+            empty = false;
+        }
+
+        if empty {
+            let message = match status {
+                QuestStatus::Active => "No active quests.",
+                QuestStatus::Completed => "No completed quests.",
+            };
+            let _ = text.push_line(&format!("<l:t><fc:K>{}", message), Vec::new())?;
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that fills the text with the discovered lore.
+    //---------------------------------------------------------------------------------------------
+    fn fill_lore(text: &mut RichTextWrapper, journal: &Journal) -> Result<()> {
+        for lore in journal.lore() {
+            let _ = text.push_line(&format!("<l:t><st:b><fc:$>{}", lore.title), Vec::new())?;
+            let _ = text.push_line(&format!("<l:t><fc:y>{}", lore.text), Vec::new())?;
+            let _ = text.push_line("", Vec::new())?;
+        }
+
+        if journal.lore().is_empty() {
+            let _ = text.push_line("<l:t><fc:K>No lore discovered.", Vec::new())?;
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that fills the bestiary table with the monster kinds seen.
+    //---------------------------------------------------------------------------------------------
+    fn fill_bestiary(bestiary: &mut Table, journal: &Journal) {
+        bestiary.clear();
+
+        for entry in journal.bestiary() {
+            bestiary.push_row(vec![
+                entry.kind.clone(),
+                entry.glyph.to_string(),
+                entry.kills.to_string(),
+            ]);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that fills and draws the content of the current tab.
+    //---------------------------------------------------------------------------------------------
+    fn draw_tab(&mut self, server: &Server, terminal: &mut Terminal) -> Result<()> {
+        let tab = self.current_tab();
+
+        if tab == JournalTab::Bestiary {
+            if let Some(bestiary) = self.bestiary.as_mut() {
+                Self::fill_bestiary(bestiary, server.journal());
+                bestiary.redraw(terminal);
+            }
+
+            return Ok(());
+        }
+
+        let text = match self.text.as_mut() {
+            Some(text) => text,
+            None => return Ok(()),
+        };
+        text.clear();
+
+        match tab {
+            JournalTab::ActiveQuests => {
+                Self::fill_quests(text, server.journal(), QuestStatus::Active)?
+            }
+            JournalTab::CompletedQuests => {
+                Self::fill_quests(text, server.journal(), QuestStatus::Completed)?
+            }
+            _ => Self::fill_lore(text, server.journal())?,
+        }
+
+        text.scroll_to_top();
+        text.draw_clear(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the journal for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, server: &Server, terminal: &mut Terminal) -> Result<()> {
+        terminal.set_all_tiles_blank();
+
+        // The inner dimensions exclude the panel's border and shadow.
+        let inner_dimensions = (
+            (terminal.width() - MARGIN.0 * 2 - 3).max(1),
+            (terminal.height() - MARGIN.1 * 2 - 3).max(1),
+        );
+        let current = self.tabs.as_ref().map(|tabs| tabs.current()).unwrap_or(0);
+
+        self.panel = Panel::new(MARGIN, inner_dimensions, FrameStyle::Line);
+        self.panel.frame.top_left_text = Some(TITLE_TEXT.into());
+        self.panel.frame.bottom_right_text = Some(HINT_TEXT.into());
+
+        let mut tabs = TabContainer::new(
+            self.panel.inner_origin(),
+            inner_dimensions,
+            TAB_TITLES.iter().map(|title| String::from(*title)).collect(),
+        );
+        tabs.set_current(current);
+
+        let columns = vec![
+            TableColumn { header: "Kind".into(), width: KIND_COLUMN_WIDTH },
+            TableColumn { header: "Glyph".into(), width: GLYPH_COLUMN_WIDTH },
+            TableColumn { header: "Kills".into(), width: 0 },
+        ];
+        self.bestiary =
+            Some(Table::new(tabs.content_origin(), tabs.content_dimensions(), columns));
+        self.text = Some(RichTextWrapper::new(
+            tabs.content_origin(),
+            tabs.content_dimensions(),
+            MAX_TEXT_LINES,
+        ));
+
+        self.panel.draw(terminal)?;
+        tabs.redraw(terminal);
+        self.tabs = Some(tabs);
+        self.draw_tab(server, terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that scrolls the text of the current tab with the keyboard and mouse wheel.
+    //---------------------------------------------------------------------------------------------
+    fn scroll_text(&mut self, terminal: &mut Terminal, input: &InputManager) -> Result<()> {
+        let text = match self.text.as_mut() {
+            Some(text) => text,
+            None => return Ok(()),
+        };
+
+        let page = text.height().max(1);
+        let delta = if input.key_just_pressed(InputKey::Up) {
+            -1
+        } else if input.key_just_pressed(InputKey::Down) {
+            1
+        } else if input.key_just_pressed(InputKey::PageUp) {
+            -page
+        } else if input.key_just_pressed(InputKey::PageDown) {
+            page
+        } else {
+            -input.mouse_wheel()
+        };
+
+        if delta < 0 {
+            text.scroll_up(-delta);
+        } else if delta > 0 {
+            text.scroll_down(delta);
+        } else {
+            return Ok(());
+        }
+
+        text.draw_clear(terminal)
+    }
+}
+
+impl Scene for JournalScene {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        self.draw(server, terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        let filtering = self.current_tab() == JournalTab::Bestiary
+            && self
+                .bestiary
+                .as_ref()
+                .map(|bestiary| !bestiary.filter().is_empty())
+                .unwrap_or(false);
+
+        // Escape clears the bestiary's filter first, and leaves the scene once there is nothing
+        // to clear.
+        if !filtering
+            && (input.key_just_pressed(InputKey::Escape)
+                || input.action_just_pressed(InputAction::Decline))
+        {
+            return Ok(SceneAction::Pop);
+        }
+
+        let changed = match self.tabs.as_mut() {
+            Some(tabs) => matches!(tabs.update(input, terminal), TabContainerAction::Changed(_)),
+            None => false,
+        };
+
+        if changed {
+            self.draw_tab(server, terminal)?;
+        } else if self.current_tab() == JournalTab::Bestiary {
+            if let Some(bestiary) = self.bestiary.as_mut() {
+                let _ = bestiary.update(input, terminal);
+            }
+        } else {
+            self.scroll_text(terminal, input)?;
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(server, terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    // (typed keys filter the bestiary rather than triggering actions)
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::TextEntry
+    }
+}
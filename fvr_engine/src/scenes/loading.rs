@@ -0,0 +1,429 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, Context, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+const TIPS_PATH: &str = "data/tips.txt";
+const TIP_INTERVAL: Duration = Duration::from_secs(6);
+const TIP_PREFIX: &str = "Tip: ";
+const PROGRESS_BAR_WIDTH: i32 = 40;
+const FAILED_HINT: &str = "[esc] Back";
+
+//-------------------------------------------------------------------------------------------------
+// Loads a zone by generating it off of the main thread, then entering it once ready.
+//-------------------------------------------------------------------------------------------------
+pub struct ZoneJob {
+    // Id of the zone to generate.
+    id: ZoneId,
+    // Whether the zone has been requested from the server.
+    requested: bool,
+    // Latest generation progress reported by the server.
+    progress: f32,
+}
+
+impl ZoneJob {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new job loading a zone.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(id: ZoneId) -> Self {
+        Self { id, requested: false, progress: 0.0 }
+    }
+}
+
+impl Job<Server> for ZoneJob {
+    //---------------------------------------------------------------------------------------------
+    // Returns the label shown above the progress bar.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &str {
+        "Generating zone..."
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Requests the zone from the server on the first step, then polls the zone events of the
+    // server until the zone has been generated and entered.
+    //---------------------------------------------------------------------------------------------
+    fn step(&mut self, server: &mut Server) -> Result<JobStep> {
+        if !self.requested {
+            let _ = server.request_zone(self.id);
+            self.requested = true;
+        }
+
+        for event in server.poll_zone_events() {
+            match event {
+                ZoneEvent::Progress { id, progress } if id == self.id => {
+                    self.progress = progress;
+                }
+                ZoneEvent::Complete { id } if id == self.id => {
+                    return match server.enter_zone(id)? {
+                        ServerResult::Success => Ok(JobStep::Complete),
+                        _ => Err(anyhow!("Zone {} was not ready.", id)),
+                    };
+                }
+                ZoneEvent::Failed { id, error } if id == self.id => {
+                    return Err(anyhow!(error));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(JobStep::Wait(self.progress))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Saves the server's journal to a file (e.g. before leaving a zone).
+//-------------------------------------------------------------------------------------------------
+pub struct JournalSaveJob {
+    // Path of the journal save file.
+    path: PathBuf,
+}
+
+impl JournalSaveJob {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new job saving the journal to a path.
+    //---------------------------------------------------------------------------------------------
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Job<Server> for JournalSaveJob {
+    //---------------------------------------------------------------------------------------------
+    // Returns the label shown above the progress bar.
+    //---------------------------------------------------------------------------------------------
+    fn name(&self) -> &str {
+        "Saving journal..."
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Saves the journal in a single step.
+    //---------------------------------------------------------------------------------------------
+    fn step(&mut self, server: &mut Server) -> Result<JobStep> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create save dir {}.", dir.display()))?;
+        }
+
+        server.journal().save(&self.path, CONFIG.save_compression)?;
+        Ok(JobStep::Complete)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Represents the possible states of the loading scene.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, PartialEq)]
+enum State {
+    // The state while waiting on the jobs.
+    Loading,
+    // The state after a job failed, waiting for the user to go back.
+    Failed(String),
+}
+
+//-------------------------------------------------------------------------------------------------
+// The loading scene runs jobs (such as saving and zone generation) within a per-frame budget,
+// showing their progress (and rotating tips) until they complete, then swaps itself for the scene
+// that was waiting on them.
+//-------------------------------------------------------------------------------------------------
+pub struct Loading {
+    // The state of the loading scene.
+    state: State,
+    // Runs the jobs being waited on, in order.
+    scheduler: JobScheduler<Server>,
+    // # of jobs pushed onto the scheduler.
+    job_count: usize,
+    // The scene swapped to once the jobs complete.
+    next_scene: Option<Box<dyn Scene>>,
+    // Bar showing the progress over all of the jobs.
+    progress_bar: ProgressBar,
+    // The tips shown while waiting.
+    tips: Vec<String>,
+    // Index of the tip currently shown.
+    tip_index: usize,
+    // Timer for rotating the tips.
+    tip_timer: Timer,
+}
+
+impl Loading {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new loading scene, swapping to a scene once all of the jobs complete.
+    //---------------------------------------------------------------------------------------------
+    pub fn new(jobs: Vec<Box<dyn Job<Server>>>, next_scene: Box<dyn Scene>) -> Self {
+        // A missing tips file should never keep the game from loading.
+        let tips = Self::load_tips().unwrap_or_else(|e| {
+            println!("[Loading] {:#}", e);
+            Vec::new()
+        });
+
+        let job_count = jobs.len();
+        let mut scheduler = JobScheduler::default();
+        jobs.into_iter().for_each(|job| {
+            let _ = scheduler.push(job);
+        });
+
+        Self {
+            state: State::Loading,
+            scheduler,
+            job_count,
+            next_scene: Some(next_scene),
+            progress_bar: ProgressBar::new((0, 0), PROGRESS_BAR_WIDTH),
+            tips,
+            tip_index: 0,
+            tip_timer: Timer::new(TIP_INTERVAL),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that loads the tips from the tips file, skipping blank and comment lines.
+    //---------------------------------------------------------------------------------------------
+    fn load_tips() -> Result<Vec<String>> {
+        let path = PLATFORM_DIRS.asset_path(TIPS_PATH);
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read tips file {}.", path.display()))?;
+
+        Ok(text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that writes a line of text centered horizontally, clearing the row first.
+    //---------------------------------------------------------------------------------------------
+    fn write_centered(terminal: &mut Terminal, y: i32, text: &str, color: PaletteColor) {
+        for x in 0..terminal.width() {
+            *terminal.get_xy_mut((x, y)) = BLANK_TILE;
+        }
+
+        let text = RichTextWriter::truncate_plain(text, terminal.width() as usize);
+        let width = RichTextWriter::display_width(&text) as i32;
+        let settings = RichTextFormatSettings {
+            layout: Some(TileLayout::Text),
+            foreground_color: Some(color.into()),
+            ..Default::default()
+        };
+
+        RichTextWriter::write_plain_with_settings(
+            terminal,
+            ((terminal.width() - width) / 2, y),
+            &text,
+            &settings,
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws the current tip, if there are any.
+    //---------------------------------------------------------------------------------------------
+    fn draw_tip(&self, terminal: &mut Terminal) {
+        if let Some(tip) = self.tips.get(self.tip_index) {
+            let y = terminal.height() - 3;
+            Self::write_centered(
+                terminal,
+                y,
+                &format!("{}{}", TIP_PREFIX, tip),
+                PaletteColor::DarkGrey,
+            );
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws the label of the current job and the progress bar.
+    //---------------------------------------------------------------------------------------------
+    fn draw_progress(&self, terminal: &mut Terminal) {
+        let label = self.scheduler.current().map_or("Loading...", |(name, _)| name);
+        let y = terminal.height() / 2 - 2;
+        Self::write_centered(terminal, y, label, PaletteColor::BrightGrey);
+        self.progress_bar.redraw(terminal);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the loading scene for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, terminal: &mut Terminal) {
+        terminal.set_all_tiles_blank();
+
+        let center_y = terminal.height() / 2;
+
+        match &self.state {
+            State::Loading => {
+                self.progress_bar
+                    .set_origin(((terminal.width() - self.progress_bar.width()) / 2, center_y));
+                self.draw_progress(terminal);
+            }
+            State::Failed(error) => {
+                Self::write_centered(
+                    terminal,
+                    center_y - 2,
+                    "Loading failed.",
+                    PaletteColor::BrightRed,
+                );
+                Self::write_centered(terminal, center_y, error, PaletteColor::BrightGrey);
+                Self::write_centered(terminal, center_y + 2, FAILED_HINT, PaletteColor::DarkGrey);
+            }
+        }
+
+        self.draw_tip(terminal);
+    }
+}
+
+impl Scene for Loading {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)?;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        self.draw(terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        match self.state {
+            State::Loading => {
+                let outcomes = self.scheduler.update(server);
+
+                if let Some(error) = outcomes.into_iter().find_map(|outcome| outcome.result.err())
+                {
+                    self.scheduler = JobScheduler::default();
+                    self.state = State::Failed(format!("{:#}", error));
+                    self.draw(terminal);
+                    return Ok(SceneAction::Noop);
+                }
+
+                if self.scheduler.is_empty() {
+                    let next_scene = self
+                        .next_scene
+                        .take()
+                        .ok_or_else(|| anyhow!("Failure: The next scene was empty."))?;
+                    return Ok(SceneAction::Swap(next_scene));
+                }
+
+                // Progress over all of the jobs, with each finished job counting as complete.
+                let current = self.scheduler.current().map_or(0.0, |(_, progress)| progress);
+                let finished = self.job_count - self.scheduler.len();
+                let progress = (finished as f32 + current) / self.job_count as f32;
+
+                if self.progress_bar.set_progress(progress) {
+                    self.draw_progress(terminal);
+                }
+            }
+            State::Failed(_) => {
+                if input.action_just_pressed(InputAction::Quit)
+                    || input.action_just_pressed(InputAction::Accept)
+                    || input.key_just_pressed(InputKey::Escape)
+                {
+                    return Ok(SceneAction::Pop);
+                }
+            }
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, terminal: &mut Terminal, dt: &Duration) -> Result<()> {
+        // Rotate the tips.
+        if !self.tips.is_empty() && self.tip_timer.update(dt) {
+            self.tip_index = (self.tip_index + 1) % self.tips.len();
+            self.draw_tip(terminal);
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::Menu
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(terminal);
+        Ok(())
+    }
+}
@@ -19,6 +19,9 @@ use fvr_engine_server::prelude::*;
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::scene_stack::*;
+use crate::scenes::editor::*;
+use crate::scenes::help::*;
+use crate::scenes::options::*;
 use crate::scenes::scratch::*;
 use crate::scenes::transitions::*;
 
@@ -63,8 +66,10 @@ pub struct MainMenu {
     fade_out: Fade,
     // Contains the final scene action to return after the user has made a selection.
     next_scene: Option<SceneAction>,
-    // ButtonList containing the main menu options.
-    button_list: ButtonList,
+    // Widgets of the main menu.
+    widgets: WidgetTree,
+    // Id of the ButtonList containing the main menu options.
+    button_list: WidgetId,
 }
 
 impl MainMenu {
@@ -80,66 +85,45 @@ impl MainMenu {
             Button::new((0, 0), String::from("[h] Help"), ButtonLayout::Text),
             Button::new((0, 0), String::from("[c] Credits"), ButtonLayout::Text),
             Button::new((0, 0), String::from("[d] Debug"), ButtonLayout::Text),
+            Button::new((0, 0), String::from("[e] Editor"), ButtonLayout::Text),
             Button::new((0, 0), String::from("[s] Scratch"), ButtonLayout::Text),
             Button::new((0, 0), String::from("[esc] Quit"), ButtonLayout::Text),
         ];
 
+        let mut widgets = WidgetTree::new();
+        let button_list = widgets
+            .insert(ButtonList::from_buttons_vec((0, 0), menu_buttons, false), None)
+            .expect("Failed to insert the main menu buttons.");
+
         Self {
             state: State::FadeIn,
             fade_in: Fade::new(&FADE_DURATION, 0.0, 1.0),
             fade_out: Fade::new(&FADE_DURATION, 1.0, 0.0),
             next_scene: None,
-            button_list: ButtonList::from_buttons_vec((0, 0), menu_buttons, false),
+            widgets,
+            button_list,
         }
     }
-}
-
-impl Scene for MainMenu {
-    //---------------------------------------------------------------------------------------------
-    // Called when the scene is added to the stack.
-    //---------------------------------------------------------------------------------------------
-    fn load(
-        &mut self,
-        server: &mut Server,
-        terminal: &mut Terminal,
-        input: &InputManager,
-    ) -> Result<()> {
-        self.focus(server, terminal, input)?;
-        Ok(())
-    }
-
-    //---------------------------------------------------------------------------------------------
-    // Called when the scene is removed from the stack.
-    //---------------------------------------------------------------------------------------------
-    fn unload(
-        &mut self,
-        _server: &mut Server,
-        _terminal: &mut Terminal,
-        _input: &InputManager,
-    ) -> Result<()> {
-        Ok(())
-    }
 
     //---------------------------------------------------------------------------------------------
-    // Called when the scene is made current again (e.g. a the next scene was popped).
+    // Helper function that lays out and draws the main menu for the current terminal size.
     //---------------------------------------------------------------------------------------------
-    fn focus(
-        &mut self,
-        _server: &mut Server,
-        terminal: &mut Terminal,
-        _input: &InputManager,
-    ) -> Result<()> {
-        // Reset state.
-        self.state = State::FadeIn;
-        self.fade_in.reset();
-        self.fade_out.reset();
-        self.next_scene = None;
-        self.button_list.reset();
-
-        // Reset the terminal.
-        terminal.set_transparent();
+    fn draw(&mut self, terminal: &mut Terminal) -> Result<()> {
         terminal.set_all_tiles_blank();
 
+        // Position and draw the menu buttons.
+        let button_list = self
+            .widgets
+            .get_mut::<ButtonList>(self.button_list)
+            .ok_or_else(|| anyhow!("Main menu buttons missing."))?;
+        let buttons_origin = (
+            (terminal.width() - button_list.width()) / 2,
+            ((terminal.height() - button_list.height()) / 2) + MENU_BUTTONS_OFFSET,
+        );
+        button_list.set_origin(buttons_origin);
+        self.widgets.mark_all_dirty();
+        let _ = self.widgets.draw(terminal)?;
+
         // Find dimensions of the title text.
         let mut title_width = 0;
 
@@ -170,14 +154,6 @@ impl Scene for MainMenu {
         format_settings.foreground_color = Some(TileColor::TRANSPARENT);
         format_settings.outline_opacity = Some(0.5);
 
-        // Position and draw the menu buttons.
-        let buttons_origin = (
-            (terminal.width() - self.button_list.width()) / 2,
-            ((terminal.height() - self.button_list.height()) / 2) + MENU_BUTTONS_OFFSET,
-        );
-        self.button_list.set_origin(buttons_origin);
-        self.button_list.redraw(terminal);
-
         // Draw the version text.
         let version_xy =
             ((terminal.width() - VERSION_TEXT.len() as i32) / 2, terminal.height() - 2);
@@ -200,11 +176,63 @@ impl Scene for MainMenu {
 
         Ok(())
     }
+}
+
+impl Scene for MainMenu {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)?;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        // Reset state.
+        self.state = State::FadeIn;
+        self.fade_in.reset();
+        self.fade_out.reset();
+        self.next_scene = None;
+        self.widgets.set_focus(None);
+
+        if let Some(button_list) = self.widgets.get_mut::<ButtonList>(self.button_list) {
+            button_list.reset();
+        }
+
+        // Reset the terminal.
+        terminal.set_transparent();
+        self.draw(terminal)
+    }
 
     //---------------------------------------------------------------------------------------------
     // Called when the scene is made no longer current (e.g. a new scene is pushed).
     //---------------------------------------------------------------------------------------------
-    fn unfocus(
+    fn on_suspend(
         &mut self,
         _server: &mut Server,
         _terminal: &mut Terminal,
@@ -236,42 +264,65 @@ impl Scene for MainMenu {
                     return Ok(SceneAction::Pop);
                 } else if input.action_just_pressed(InputAction::Accept) {
                     terminal.randomize();
+                } else if input.key_just_pressed(InputKey::E) {
+                    self.next_scene = Some(SceneAction::Push(Box::new(Editor::new())));
+                    self.state = State::FadeOut;
+                } else if input.key_just_pressed(InputKey::H) {
+                    self.next_scene = Some(SceneAction::Push(Box::new(Help::new())));
+                    self.state = State::FadeOut;
+                } else if input.key_just_pressed(InputKey::O) {
+                    self.next_scene = Some(SceneAction::Push(Box::new(Options::new())));
+                    self.state = State::FadeOut;
                 } else if input.key_just_pressed(InputKey::S) {
                     self.next_scene = Some(SceneAction::Push(Box::new(Scratch::new())));
                     self.state = State::FadeOut;
                 } else {
-                    let button_list_action = self.button_list.update(input, terminal);
+                    let _ = self.widgets.update(input, terminal)?;
+                    let button_list_action = self.widgets.action(self.button_list);
+                    let _ = self.widgets.draw(terminal)?;
 
                     // If a button has been triggered, prepare the next scene.
-                    if let ButtonListAction::Triggered(i) = button_list_action {
+                    if let Some(WidgetAction::Triggered(i)) = button_list_action {
                         match i {
                             // New.
                             0 => {}
                             // Resume.
                             1 => {}
                             // Options.
-                            2 => {}
+                            2 => {
+                                self.next_scene =
+                                    Some(SceneAction::Push(Box::new(Options::new())));
+                                self.state = State::FadeOut;
+                            }
                             // Help.
-                            3 => {}
+                            3 => {
+                                self.next_scene = Some(SceneAction::Push(Box::new(Help::new())));
+                                self.state = State::FadeOut;
+                            }
                             // Credits.
                             4 => {}
                             // Debug.
                             5 => {}
-                            // Scratch.
+                            // Editor.
                             6 => {
+                                self.next_scene = Some(SceneAction::Push(Box::new(Editor::new())));
+                                self.state = State::FadeOut;
+                            }
+                            // Scratch.
+                            7 => {
                                 self.next_scene =
                                     Some(SceneAction::Push(Box::new(Scratch::new())));
                                 self.state = State::FadeOut;
                             }
                             // Quit.
-                            7 => {
+                            8 => {
                                 return Ok(SceneAction::Pop);
                             }
                             _ => bail!("Invalid menu option."),
                         }
 
                         input.set_cursor(Cursor::Hand);
-                    } else if button_list_action == ButtonListAction::Interactable {
+                    } else if button_list_action == Some(WidgetAction::Interactable) {
                         input.set_cursor(Cursor::Hand);
                     } else {
                         input.set_cursor(Cursor::Arrow);
@@ -308,4 +359,23 @@ impl Scene for MainMenu {
 
         Ok(())
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::Menu
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(terminal)
+    }
 }
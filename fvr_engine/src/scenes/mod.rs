@@ -1,7 +1,14 @@
+pub mod editor;
+pub mod help;
 pub mod initial;
+pub mod journal;
+pub mod loading;
 pub mod main_menu;
+pub mod options;
 pub mod scratch;
+pub mod title;
 
 pub mod transitions;
+pub mod world_map;
 
 pub use crate::scenes::initial::Initial;
@@ -0,0 +1,507 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Texts of the panel's frame.
+const TITLE_TEXT: &str = "Options";
+const HINT_TEXT: &str = "ctrl+s save, esc cancel";
+
+// Width of the options list, and of the label column within it.
+const LIST_WIDTH: i32 = 44;
+const LABEL_WIDTH: usize = 26;
+
+// Rows between the options list and the status line.
+const STATUS_OFFSET: i32 = 1;
+
+// Step and range of the volume options.
+const VOLUME_STEP: f32 = 0.1;
+const VOLUME_RANGE: (f32, f32) = (0.0, 1.0);
+
+// Step and range of the animation speed option.
+const ANIMATION_SPEED_STEP: f32 = 0.25;
+const ANIMATION_SPEED_RANGE: (f32, f32) = (0.25, 2.0);
+
+//-------------------------------------------------------------------------------------------------
+// Enumerates the rows of the options list.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OptionRow {
+    // Disabled row naming the following section (empty for a spacer).
+    Heading(&'static str),
+    Font,
+    Vsync,
+    Vignette,
+    ShowFps,
+    ActorBars,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    SoftwareCursor,
+    AutoPickup,
+    PickupConsumables,
+    PickupThrowables,
+    ReduceMotion,
+    DisableFlashing,
+    AnimationSpeed,
+    Save,
+    Cancel,
+}
+
+// Rows of the options list, in order.
+const OPTION_ROWS: [OptionRow; 23] = [
+    OptionRow::Heading("Display"),
+    OptionRow::Font,
+    OptionRow::Vsync,
+    OptionRow::Vignette,
+    OptionRow::ShowFps,
+    OptionRow::ActorBars,
+    OptionRow::Heading("Audio"),
+    OptionRow::MasterVolume,
+    OptionRow::MusicVolume,
+    OptionRow::EffectsVolume,
+    OptionRow::Heading("Input"),
+    OptionRow::SoftwareCursor,
+    OptionRow::Heading("Items"),
+    OptionRow::AutoPickup,
+    OptionRow::PickupConsumables,
+    OptionRow::PickupThrowables,
+    OptionRow::Heading("Accessibility"),
+    OptionRow::ReduceMotion,
+    OptionRow::DisableFlashing,
+    OptionRow::AnimationSpeed,
+    OptionRow::Heading(""),
+    OptionRow::Save,
+    OptionRow::Cancel,
+];
+
+//-------------------------------------------------------------------------------------------------
+// The options scene, editing the settings by section. Changes are applied as they are made, then
+// saved to the config on confirm or reverted on cancel.
+//-------------------------------------------------------------------------------------------------
+pub struct Options {
+    // Panel drawn behind the options list.
+    panel: Panel,
+    // List of the option rows.
+    list_menu: ListMenu,
+    // Settings when the scene was loaded (restored on cancel).
+    original: Settings,
+    // Settings as edited.
+    edited: Settings,
+    // Names of the fonts that can be chosen.
+    fonts: Vec<String>,
+    // Message shown beneath the options list (e.g. a failure to save).
+    status: String,
+}
+
+impl Options {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new options scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        let settings = Settings::current();
+        let inner_dimensions = (LIST_WIDTH, OPTION_ROWS.len() as i32 + STATUS_OFFSET + 1);
+
+        let mut panel = Panel::new((0, 0), inner_dimensions, FrameStyle::Line);
+        panel.frame.top_left_text = Some(TITLE_TEXT.into());
+        panel.frame.bottom_right_text = Some(HINT_TEXT.into());
+
+        Self {
+            panel,
+            list_menu: ListMenu::new((0, 0), (LIST_WIDTH, OPTION_ROWS.len() as i32), false),
+            original: settings.clone(),
+            edited: settings,
+            fonts: Vec::new(),
+            status: String::new(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the text of an option row for the edited settings.
+    //---------------------------------------------------------------------------------------------
+    fn row_text(&self, row: OptionRow) -> String {
+        let on_off = |value: bool| String::from(if value { "On" } else { "Off" });
+        let percent = |value: f32| format!("{}%", (value * 100.0).round() as i32);
+        let settings = &self.edited;
+
+        let (label, value) = match row {
+            OptionRow::Heading(name) if name.is_empty() => return String::new(),
+            OptionRow::Heading(name) => return format!("-- {} --", name),
+            OptionRow::Font => ("Font", settings.font_name.clone()),
+            OptionRow::Vsync => ("Vsync", on_off(settings.vsync)),
+            OptionRow::Vignette => ("Vignette", on_off(settings.enable_vignette)),
+            OptionRow::ShowFps => ("Log FPS", on_off(settings.show_fps)),
+            OptionRow::ActorBars => {
+                let mode = match settings.actor_bars {
+                    ActorBarMode::Always => "Always",
+                    ActorBarMode::OnDamage => "On damage",
+                    ActorBarMode::Never => "Never",
+                };
+                ("Health bars", String::from(mode))
+            }
+            OptionRow::MasterVolume => ("Master volume", percent(settings.audio.master_volume)),
+            OptionRow::MusicVolume => ("Music volume", percent(settings.audio.music_volume)),
+            OptionRow::EffectsVolume => ("Effects volume", percent(settings.audio.effects_volume)),
+            OptionRow::SoftwareCursor => ("Software cursor", on_off(settings.software_cursor)),
+            OptionRow::AutoPickup => ("Auto-pickup", on_off(settings.auto_pickup.enabled)),
+            OptionRow::PickupConsumables => {
+                ("  Consumables", on_off(settings.auto_pickup.consumables))
+            }
+            OptionRow::PickupThrowables => {
+                ("  Throwables", on_off(settings.auto_pickup.throwables))
+            }
+            OptionRow::ReduceMotion => {
+                ("Reduce motion", on_off(settings.accessibility.reduce_motion))
+            }
+            OptionRow::DisableFlashing => {
+                ("Disable flashing", on_off(settings.accessibility.disable_flashing))
+            }
+            OptionRow::AnimationSpeed => (
+                "Max animation speed",
+                format!("{:.2}x", settings.accessibility.max_animation_speed),
+            ),
+            OptionRow::Save => return String::from("Save"),
+            OptionRow::Cancel => return String::from("Cancel"),
+        };
+
+        format!("{:<width$}< {} >", label, value, width = LABEL_WIDTH)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that fills the options list with the rows for the edited settings.
+    //---------------------------------------------------------------------------------------------
+    fn fill_list(&mut self) {
+        self.list_menu.clear();
+
+        for row in OPTION_ROWS.iter() {
+            let disabled = matches!(row, OptionRow::Heading(_));
+            self.list_menu.push(self.row_text(*row), disabled);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the options for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, terminal: &mut Terminal) -> Result<()> {
+        terminal.set_all_tiles_blank();
+
+        let origin = (
+            ((terminal.width() - self.panel.rect().width) / 2).max(0),
+            ((terminal.height() - self.panel.rect().height) / 2).max(0),
+        );
+        self.panel.set_origin(origin);
+        self.list_menu.set_origin(self.panel.inner_origin());
+
+        self.panel.draw(terminal)?;
+        self.list_menu.redraw(terminal);
+        self.draw_status(terminal);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws the status line beneath the options list.
+    //---------------------------------------------------------------------------------------------
+    fn draw_status(&self, terminal: &mut Terminal) {
+        let status_settings = RichTextFormatSettings {
+            layout: Some(TileLayout::Text),
+            foreground_color: Some(PaletteColor::BrightRed.into()),
+            ..Default::default()
+        };
+
+        // Pad the status to clear the last one.
+        let status = format!("{:<width$}", self.status, width = LIST_WIDTH as usize);
+        let xy = (
+            self.panel.inner_origin().0,
+            self.panel.inner_origin().1 + OPTION_ROWS.len() as i32 + STATUS_OFFSET,
+        );
+
+        RichTextWriter::write_plain_with_settings(
+            terminal,
+            xy,
+            &RichTextWriter::truncate_plain(&status, LIST_WIDTH as usize),
+            &status_settings,
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that steps a value within a range, wrapping around at either end.
+    //---------------------------------------------------------------------------------------------
+    fn step(value: &mut f32, direction: i32, step: f32, (min, max): (f32, f32)) {
+        let stepped = ((*value + step * direction as f32) / step).round() * step;
+
+        *value = if stepped > max + f32::EPSILON {
+            min
+        } else if stepped < min - f32::EPSILON {
+            max
+        } else {
+            stepped
+        };
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that changes the option of a row by one step forward (positive) or back,
+    // applying the edited settings immediately. Returns whether the option changed.
+    //---------------------------------------------------------------------------------------------
+    fn adjust(&mut self, index: i32, direction: i32) -> bool {
+        let row = match OPTION_ROWS.get(index as usize) {
+            Some(row) => *row,
+            None => return false,
+        };
+        let settings = &mut self.edited;
+
+        match row {
+            OptionRow::Font => {
+                if self.fonts.is_empty() {
+                    return false;
+                }
+
+                let count = self.fonts.len() as i32;
+                let current = self.fonts.iter().position(|font| *font == settings.font_name);
+                let next = match current {
+                    Some(i) => (i as i32 + direction).rem_euclid(count),
+                    None => 0,
+                };
+                settings.font_name = self.fonts[next as usize].clone();
+            }
+            OptionRow::Vsync => settings.vsync = !settings.vsync,
+            OptionRow::Vignette => settings.enable_vignette = !settings.enable_vignette,
+            OptionRow::ShowFps => settings.show_fps = !settings.show_fps,
+            OptionRow::ActorBars => settings.actor_bars = settings.actor_bars.step(direction),
+            OptionRow::MasterVolume => {
+                Self::step(&mut settings.audio.master_volume, direction, VOLUME_STEP, VOLUME_RANGE)
+            }
+            OptionRow::MusicVolume => {
+                Self::step(&mut settings.audio.music_volume, direction, VOLUME_STEP, VOLUME_RANGE)
+            }
+            OptionRow::EffectsVolume => Self::step(
+                &mut settings.audio.effects_volume,
+                direction,
+                VOLUME_STEP,
+                VOLUME_RANGE,
+            ),
+            OptionRow::SoftwareCursor => settings.software_cursor = !settings.software_cursor,
+            OptionRow::AutoPickup => settings.auto_pickup.enabled = !settings.auto_pickup.enabled,
+            OptionRow::PickupConsumables => {
+                settings.auto_pickup.consumables = !settings.auto_pickup.consumables
+            }
+            OptionRow::PickupThrowables => {
+                settings.auto_pickup.throwables = !settings.auto_pickup.throwables
+            }
+            OptionRow::ReduceMotion => {
+                settings.accessibility.reduce_motion = !settings.accessibility.reduce_motion
+            }
+            OptionRow::DisableFlashing => {
+                settings.accessibility.disable_flashing = !settings.accessibility.disable_flashing
+            }
+            OptionRow::AnimationSpeed => Self::step(
+                &mut settings.accessibility.max_animation_speed,
+                direction,
+                ANIMATION_SPEED_STEP,
+                ANIMATION_SPEED_RANGE,
+            ),
+            OptionRow::Heading(_) | OptionRow::Save | OptionRow::Cancel => return false,
+        }
+
+        Settings::apply(self.edited.clone());
+        self.list_menu.set_text(index, self.row_text(row));
+
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that saves the edited settings. Returns whether they were saved.
+    //---------------------------------------------------------------------------------------------
+    fn save(&mut self, terminal: &mut Terminal) -> bool {
+        match self.edited.save() {
+            Ok(()) => {
+                self.original = self.edited.clone();
+                true
+            }
+            Err(e) => {
+                println!("[Options] {:#}", e);
+                self.status = String::from("Failed to save the options.");
+                self.draw_status(terminal);
+                false
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that restores the settings from when the scene was loaded.
+    //---------------------------------------------------------------------------------------------
+    fn revert(&mut self) {
+        if self.edited != self.original {
+            self.edited = self.original.clone();
+            Settings::apply(self.original.clone());
+        }
+    }
+}
+
+impl Scene for Options {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.original = Settings::current();
+        self.edited = self.original.clone();
+
+        // Without a list of fonts the current font is kept.
+        self.fonts = Settings::available_fonts().unwrap_or_else(|e| {
+            println!("[Options] {:#}", e);
+            Vec::new()
+        });
+
+        self.fill_list();
+        self.on_resume(server, terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        self.draw(terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        // Ctrl+S saves and leaves the scene.
+        if input.modifier_pressed(&ModifierKey::Ctrl) && input.key_just_pressed(InputKey::S) {
+            if self.save(terminal) {
+                return Ok(SceneAction::Pop);
+            }
+
+            return Ok(SceneAction::Noop);
+        }
+
+        // Escape clears the filter first, and reverts and leaves the scene once there is nothing
+        // to clear.
+        if self.list_menu.filter().is_empty()
+            && (input.key_just_pressed(InputKey::Escape)
+                || input.action_just_pressed(InputAction::Decline))
+        {
+            self.revert();
+            return Ok(SceneAction::Pop);
+        }
+
+        // Left and right step the option under the cursor.
+        if let Some(index) = self.list_menu.cursor_item() {
+            if input.key_just_pressed(InputKey::Left) {
+                let _ = self.adjust(index, -1);
+            } else if input.key_just_pressed(InputKey::Right) {
+                let _ = self.adjust(index, 1);
+            }
+        }
+
+        match self.list_menu.update(input, terminal) {
+            ListMenuAction::Triggered(index) => match OPTION_ROWS[index as usize] {
+                OptionRow::Save => {
+                    if self.save(terminal) {
+                        return Ok(SceneAction::Pop);
+                    }
+                }
+                OptionRow::Cancel => {
+                    self.revert();
+                    return Ok(SceneAction::Pop);
+                }
+                _ => {
+                    let _ = self.adjust(index, 1);
+                }
+            },
+            ListMenuAction::Interactable => input.set_cursor(Cursor::Hand),
+            _ => input.set_cursor(Cursor::Arrow),
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::Menu
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(terminal)
+    }
+}
@@ -1,6 +1,7 @@
 //-------------------------------------------------------------------------------------------------
 // STD includes.
 //-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
 use std::time::Duration;
 
 //-------------------------------------------------------------------------------------------------
@@ -12,18 +13,71 @@ use anyhow::Result;
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
 use fvr_engine_client::prelude::*;
-use fvr_engine_core::prelude::*;
+use fvr_engine_core::{prelude::*, xy_iter};
 use fvr_engine_server::prelude::*;
 
 //-------------------------------------------------------------------------------------------------
 // Local includes.
 //-------------------------------------------------------------------------------------------------
 use crate::scene_stack::*;
+use crate::scenes::journal::*;
+use crate::scenes::loading::*;
+use crate::scenes::world_map::*;
 
 //-------------------------------------------------------------------------------------------------
 // Constants.
 //-------------------------------------------------------------------------------------------------
 const SHOW_FOV: bool = true;
+const JOURNAL_SAVE_FILE: &str = "journal.sav";
+const ZONE_SAVE_DIR: &str = "zones";
+const COMBAT_DETAIL_CAPACITY: usize = 64;
+const COMBAT_DETAIL_WIDTH: i32 = 28;
+const TURN_ORDER_CAPACITY: usize = 14;
+const TURN_ORDER_LABEL: &str = "<l:t><fc:K>Turn order";
+const STATUS_ORIGIN: ICoord = (85 - 30 + 1, 4);
+const STATUS_WIDTH: usize = 26;
+const UNBOUND_KEYS: &str = "?";
+const PROMPT_KEYS: [InputKey; 9] = [
+    InputKey::Num1,
+    InputKey::Num2,
+    InputKey::Num3,
+    InputKey::Num4,
+    InputKey::Num5,
+    InputKey::Num6,
+    InputKey::Num7,
+    InputKey::Num8,
+    InputKey::Num9,
+];
+const PROMPT_CANCEL: &str = "[Esc] Cancel";
+const DROP_COUNTS: [u32; 2] = [5, 10];
+const TRAVEL_STEP_INTERVAL: Duration = Duration::from_millis(60);
+const MOVE_ACTIONS: [InputAction; 8] = [
+    InputAction::North,
+    InputAction::South,
+    InputAction::East,
+    InputAction::West,
+    InputAction::Northeast,
+    InputAction::Southeast,
+    InputAction::Southwest,
+    InputAction::Northwest,
+];
+
+//-------------------------------------------------------------------------------------------------
+// The item of the inventory being aimed and the coord it is aimed at.
+//-------------------------------------------------------------------------------------------------
+struct ItemTarget {
+    index: usize,
+    item: ItemKind,
+    xy: ICoord,
+}
+
+//-------------------------------------------------------------------------------------------------
+// The stages of the drop popup: choosing the stack to drop, then how many of its items.
+//-------------------------------------------------------------------------------------------------
+enum DropPrompt {
+    Stack,
+    Quantity { index: usize, quantities: Vec<ItemQuantity> },
+}
 
 //-------------------------------------------------------------------------------------------------
 // An empty scene used for testing and other development tasks.
@@ -31,105 +85,1418 @@ const SHOW_FOV: bool = true;
 pub struct Scratch {
     scroll_log: ScrollLog,
     view: Rect,
-    path: Vec<ICoord>,
-    last_offset: ICoord,
+    hovered: Option<ICoord>,
+    last_rejection: Option<Rejection>,
+    preview: Option<TravelPreview>,
+    camera: Camera,
     show_path: bool,
-    repeat: InputRepeat,
-    moved_with_mouse: bool,
+    travel_timer: Duration,
+    refresh_preview: bool,
+    move_repeats: Vec<(InputRepeat, Direction)>,
+    scent_overlay: Option<ScentKind>,
+    animator: AmbientAnimator,
+    new_game: bool,
+    combat_details: VecDeque<(RichTextLineId, RollBreakdown)>,
+    shown_combat_detail: Option<RichTextLineId>,
+    floating_texts: FloatingTextLayer,
+    turn_order: TurnOrder,
+    action_bar: ActionBar,
+    bump_prompt: Option<BumpRequest>,
+    inventory_prompt: bool,
+    item_target: Option<ItemTarget>,
+    loot_prompt: Option<(Interaction, Vec<PickupFilter>)>,
+    drop_prompt: Option<DropPrompt>,
+    macro_prompt: bool,
+    shown_encumbrance: Option<Encumbrance>,
+    dash_armed: bool,
+    journal_save_queued: bool,
 }
 
-impl Scratch {
+impl Scratch {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new scratch scene, starting a new game when first focused.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self { new_game: true, ..Self::resume() }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Creates a new scratch scene, resuming the game already held by the server.
+    //---------------------------------------------------------------------------------------------
+    pub fn resume() -> Self {
+        Self {
+            scroll_log: ScrollLog::new(
+                (85 - 30, 33 - 11),
+                (30, 11),
+                FrameStyle::LineBlockCorner,
+                9,
+            ),
+            view: Rect::new((0, 0), 55, 32),
+            hovered: None,
+            last_rejection: None,
+            preview: None,
+            camera: Camera::new((55, 32)),
+            show_path: true,
+            travel_timer: Duration::default(),
+            refresh_preview: false,
+            move_repeats: MOVE_ACTIONS
+                .iter()
+                .map(|action| {
+                    let repeat =
+                        InputRepeat::for_action_with_profile(*action, &RepeatProfile::default());
+                    (repeat, action.direction().expect("Movement action without a direction."))
+                })
+                .collect(),
+            scent_overlay: None,
+            animator: AmbientAnimator::new(),
+            new_game: false,
+            combat_details: VecDeque::new(),
+            shown_combat_detail: None,
+            floating_texts: FloatingTextLayer::new(),
+            turn_order: TurnOrder::new((85 - 30 + 1, 2), TURN_ORDER_CAPACITY),
+            action_bar: ActionBar::new((0, 32), 55),
+            bump_prompt: None,
+            inventory_prompt: false,
+            item_target: None,
+            loot_prompt: None,
+            drop_prompt: None,
+            macro_prompt: false,
+            shown_encumbrance: None,
+            dash_armed: false,
+            journal_save_queued: false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that saves the server's journal to the journal save file.
+    //---------------------------------------------------------------------------------------------
+    fn save_journal(server: &Server) -> Result<()> {
+        std::fs::create_dir_all(PLATFORM_DIRS.save_dir())?;
+        server
+            .journal()
+            .save(PLATFORM_DIRS.save_dir().join(JOURNAL_SAVE_FILE), CONFIG.save_compression)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that empties the directory the zones left are saved to (removing those of
+    // the previous game), returning its path.
+    //---------------------------------------------------------------------------------------------
+    fn clear_zone_dir() -> Result<std::path::PathBuf> {
+        let dir = PLATFORM_DIRS.save_dir().join(ZONE_SAVE_DIR);
+
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn handle_move(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        direction: &Direction,
+    ) -> Result<bool> {
+        let result = match server.move_player(*direction)? {
+            // Ask the player what to do with the actor they bumped into.
+            ServerResult::Ambiguous(request) => {
+                self.show_bump_prompt(terminal, request)?;
+                return Ok(false);
+            }
+            result => result,
+        };
+
+        // Report why the move was rejected, if it was (once, while the same move is repeated).
+        if let ServerResult::Rejected(rejection) = result {
+            if self.last_rejection.as_ref() != Some(&rejection) {
+                self.scroll_log.append(&format!("\n<fc:y>> {}", rejection.message))?;
+                self.scroll_log.scroll_to_bottom();
+                self.last_rejection = Some(rejection);
+            }
+
+            self.blit_map(server, terminal);
+            return Ok(false);
+        }
+
+        self.last_rejection = None;
+        self.blit_map(server, terminal);
+
+        Ok(true)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that arms the player's dash (the next move dashes), reporting how long it
+    // has left to recharge if it is not ready.
+    //---------------------------------------------------------------------------------------------
+    fn arm_dash(&mut self, server: &Server) -> Result<()> {
+        let msg = match server.player_charges(DASH_CHARGES).and_then(|c| c.turns_until_ready()) {
+            Some(0) => {
+                self.dash_armed = true;
+                "Dash in which direction?".into()
+            }
+            Some(turns) => format!("You can dash again in {} turns.", turns),
+            None => "You cannot dash.".into(),
+        };
+
+        self.scroll_log.append(&format!("\n<fc:y>> {}", msg))?;
+        self.scroll_log.scroll_to_bottom();
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that dashes the player in a direction, reporting why if they cannot.
+    //---------------------------------------------------------------------------------------------
+    fn handle_dash(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        direction: &Direction,
+    ) -> Result<()> {
+        self.dash_armed = false;
+
+        if let ServerResult::Rejected(rejection) = server.dash_player(*direction)? {
+            self.scroll_log.append(&format!("\n<fc:y>> {}", rejection.message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that closes an open door next to the player, reporting why if it cannot.
+    //---------------------------------------------------------------------------------------------
+    fn handle_close_door(&mut self, server: &mut Server, terminal: &mut Terminal) -> Result<()> {
+        // Prefer a door that can be closed, so that an occupied door is only reported if it is the
+        // only one around.
+        let mut doors: Vec<Interaction> = server
+            .available_interactions(server.player_id())
+            .into_iter()
+            .filter(|interaction| interaction.verb == InteractionVerb::CloseDoor)
+            .collect();
+        doors.sort_by_key(|interaction| !interaction.validity.is_valid());
+
+        let result = match doors.first() {
+            Some(interaction) => server.interact(interaction)?,
+            None => ServerResult::Rejected(Rejection::new(
+                RejectionReason::NoTarget,
+                server.snapshot().player_xy(),
+            )),
+        };
+
+        if let ServerResult::Rejected(rejection) = result {
+            self.scroll_log.append(&format!("\n<fc:y>> {}", rejection.message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws a popup beside a zone coord, listing options numbered by their
+    // keys (at most one per prompt key) followed by a cancel hint.
+    //---------------------------------------------------------------------------------------------
+    fn draw_prompt(
+        &self,
+        terminal: &mut Terminal,
+        xy: ICoord,
+        title: &str,
+        options: &[&str],
+    ) -> Result<()> {
+        let lines: Vec<String> = options
+            .iter()
+            .take(PROMPT_KEYS.len())
+            .enumerate()
+            .map(|(i, option)| format!("[{}] {}", i + 1, option))
+            .chain(std::iter::once(PROMPT_CANCEL.into()))
+            .collect();
+        let width = lines.iter().map(|l| RichTextWriter::display_width(l)).max().unwrap_or(0);
+        let inner_dimensions = (width as i32, lines.len() as i32);
+
+        // Keep the popup (and its frame) within the map view.
+        let offset = self.camera.offset();
+        let view_xy = (xy.0 - offset.0, xy.1 - offset.1);
+        let origin = (
+            (view_xy.0 + 1).clamp(0, (self.view.width - inner_dimensions.0 - 2).max(0)),
+            (view_xy.1 + 1).clamp(0, (self.view.height - inner_dimensions.1 - 2).max(0)),
+        );
+
+        let mut panel = Panel::new(origin, inner_dimensions, FrameStyle::Line);
+        panel.frame.top_left_text = Some(title.into());
+        panel.draw(terminal)?;
+
+        for (i, line) in lines.iter().enumerate() {
+            let xy = panel.inner_origin();
+            let color = if i + 1 == lines.len() { "K" } else { "Y" };
+            RichTextWriter::write(
+                terminal,
+                (xy.0, xy.1 + i as i32),
+                &format!("<fc:{}>{}", color, line),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the index of the option chosen by its key in a prompt with a #
+    // of options, if any.
+    //---------------------------------------------------------------------------------------------
+    fn prompt_choice(input: &InputManager, options: usize) -> Option<usize> {
+        PROMPT_KEYS.iter().take(options).position(|key| input.key_just_pressed(*key))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that shows a popup beside an actor the player bumped into, listing the
+    // actions to choose from.
+    //---------------------------------------------------------------------------------------------
+    fn show_bump_prompt(&mut self, terminal: &mut Terminal, request: BumpRequest) -> Result<()> {
+        let options: Vec<&str> = request.actions.iter().map(|action| action.name()).collect();
+        self.draw_prompt(terminal, request.xy, request.kind, &options)?;
+        self.bump_prompt = Some(request);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that answers the shown bump popup with the action chosen by its key, or
+    // dismisses it on escape.
+    //---------------------------------------------------------------------------------------------
+    fn update_bump_prompt(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let request = match &self.bump_prompt {
+            Some(request) => request,
+            None => return Ok(()),
+        };
+
+        let action = Self::prompt_choice(input, request.actions.len()).map(|i| request.actions[i]);
+
+        if action.is_none() && !input.key_just_pressed(InputKey::Escape) {
+            return Ok(());
+        }
+
+        let request = self.bump_prompt.take().expect("Bump prompt missing.");
+        self.redraw(server, terminal)?;
+
+        let message = match action.map(|action| server.resolve_bump(&request, action)) {
+            Some(result) => match result? {
+                ServerResult::Rejected(rejection) => Some(rejection.message),
+                _ if action == Some(BumpAction::Talk) => {
+                    Some(format!("<fc:y>The {} has nothing to say.", request.kind))
+                }
+                _ => None,
+            },
+            None => None,
+        };
+
+        if let Some(message) = message {
+            self.scroll_log.append(&format!("\n<fc:y>> {}", message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that shows a popup beside the player listing the carried items to use.
+    //---------------------------------------------------------------------------------------------
+    fn show_inventory_prompt(&mut self, server: &Server, terminal: &mut Terminal) -> Result<()> {
+        if server.inventory().is_empty() {
+            self.scroll_log.append("\n<fc:y>> You carry nothing.")?;
+            self.scroll_log.scroll_to_bottom();
+            return Ok(());
+        }
+
+        let options = Self::stack_names(server.inventory().items());
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        self.draw_prompt(terminal, server.snapshot().player_xy(), "Use", &options)?;
+        self.inventory_prompt = true;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the names of stacks with their counts, for prompts.
+    //---------------------------------------------------------------------------------------------
+    fn stack_names(stacks: &[ItemStack]) -> Vec<String> {
+        stacks
+            .iter()
+            .map(|stack| match stack.count {
+                1 => stack.kind.definition().name.into(),
+                count => format!("{} x{}", stack.kind.definition().name, count),
+            })
+            .collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that shows a popup beside the player listing the carried stacks to drop.
+    //---------------------------------------------------------------------------------------------
+    fn show_drop_prompt(&mut self, server: &Server, terminal: &mut Terminal) -> Result<()> {
+        if server.inventory().is_empty() {
+            self.scroll_log.append("\n<fc:y>> You carry nothing.")?;
+            self.scroll_log.scroll_to_bottom();
+            return Ok(());
+        }
+
+        let options = Self::stack_names(server.inventory().items());
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        self.draw_prompt(terminal, server.snapshot().player_xy(), "Drop", &options)?;
+        self.drop_prompt = Some(DropPrompt::Stack);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that answers the shown drop popup. Choosing a stack of a single item drops
+    // it, while choosing a larger stack asks how many of its items to drop. Escape dismisses the
+    // popup.
+    //---------------------------------------------------------------------------------------------
+    fn update_drop_prompt(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let options = match &self.drop_prompt {
+            Some(DropPrompt::Stack) => server.inventory().len(),
+            Some(DropPrompt::Quantity { quantities, .. }) => quantities.len(),
+            None => return Ok(()),
+        };
+
+        let choice = Self::prompt_choice(input, options);
+
+        if choice.is_none() && !input.key_just_pressed(InputKey::Escape) {
+            return Ok(());
+        }
+
+        let prompt = self.drop_prompt.take().expect("Drop prompt missing.");
+        self.redraw(server, terminal)?;
+
+        let choice = match choice {
+            Some(choice) => choice,
+            None => return Ok(()),
+        };
+
+        let (index, quantity) = match prompt {
+            DropPrompt::Quantity { index, quantities } => (index, quantities[choice]),
+            DropPrompt::Stack => {
+                let stack = server.inventory().get(choice).expect("Item stack missing.");
+
+                if stack.count == 1 {
+                    (choice, ItemQuantity::All)
+                } else {
+                    let mut quantities = vec![ItemQuantity::Count(1)];
+                    quantities.extend(
+                        DROP_COUNTS
+                            .iter()
+                            .filter(|count| **count < stack.count)
+                            .map(|count| ItemQuantity::Count(*count)),
+                    );
+                    quantities.push(ItemQuantity::All);
+
+                    let options: Vec<String> = quantities
+                        .iter()
+                        .map(|quantity| match quantity {
+                            ItemQuantity::All => format!("All ({})", stack.count),
+                            ItemQuantity::Count(count) => count.to_string(),
+                        })
+                        .collect();
+                    let options: Vec<&str> = options.iter().map(String::as_str).collect();
+
+                    self.draw_prompt(
+                        terminal,
+                        server.snapshot().player_xy(),
+                        stack.kind.definition().name,
+                        &options,
+                    )?;
+                    self.drop_prompt = Some(DropPrompt::Quantity { index: choice, quantities });
+                    return Ok(());
+                }
+            }
+        };
+
+        self.handle_drop_item(server, terminal, index, quantity)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that drops a quantity of a stack of the inventory, reporting why if it
+    // cannot.
+    //---------------------------------------------------------------------------------------------
+    fn handle_drop_item(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        index: usize,
+        quantity: ItemQuantity,
+    ) -> Result<()> {
+        let dropped = server
+            .inventory()
+            .get(index)
+            .map(|stack| ItemStack { kind: stack.kind, count: quantity.of(stack.count) });
+
+        let message = match server.drop_item(index, quantity)? {
+            ServerResult::Rejected(rejection) => rejection.message,
+            _ => match dropped {
+                Some(stack) => format!("You drop {}.", Self::describe_items(&[stack])),
+                None => return Ok(()),
+            },
+        };
+
+        self.scroll_log.append(&format!("\n<fc:y>> {}", message))?;
+        self.scroll_log.scroll_to_bottom();
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that shows a popup beside the player asking for the key to bind the macro
+    // just recorded to.
+    //---------------------------------------------------------------------------------------------
+    fn show_macro_prompt(
+        &mut self,
+        server: &Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let count = input.recorded_macro().map(|actions| actions.len()).unwrap_or(0);
+
+        if count == 0 {
+            input.discard_recorded_macro();
+            self.scroll_log.append("\n<fc:y>> No actions were recorded.")?;
+            self.scroll_log.scroll_to_bottom();
+            return Ok(());
+        }
+
+        self.draw_prompt(terminal, server.snapshot().player_xy(), "Bind macro", &[])?;
+        self.scroll_log.append(&format!(
+            "\n<fc:y>> Press a key to bind the {} recorded actions to.",
+            count
+        ))?;
+        self.scroll_log.scroll_to_bottom();
+        self.macro_prompt = true;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that answers the shown macro popup by binding the recorded macro to the
+    // next key pressed (unless the key is bound to an action) and saving it to the keybindings,
+    // or discards the macro on escape.
+    //---------------------------------------------------------------------------------------------
+    fn update_macro_prompt(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let key = match input.just_pressed_key() {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+
+        let message = if key == InputKey::Escape {
+            input.discard_recorded_macro();
+            "The macro was discarded.".into()
+        } else if let Some(action) = input.key_action(key) {
+            let message =
+                format!("{} is bound to {}, choose another key.", key.name(), action.name());
+            self.scroll_log.append(&format!("\n<fc:y>> {}", message))?;
+            self.scroll_log.scroll_to_bottom();
+            return Ok(());
+        } else {
+            match input.bind_recorded_macro(key) {
+                Ok(()) => format!("The macro was bound to {}.", key.name()),
+                Err(e) => format!("Failed to save the macro: {}", e),
+            }
+        };
+
+        self.macro_prompt = false;
+        self.redraw(server, terminal)?;
+        self.scroll_log.append(&format!("\n<fc:y>> {}", message))?;
+        self.scroll_log.scroll_to_bottom();
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that answers the shown inventory popup: items used on the player are used
+    // at once, while items used at a target enter targeting. Escape dismisses the popup.
+    //---------------------------------------------------------------------------------------------
+    fn update_inventory_prompt(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let choice = Self::prompt_choice(input, server.inventory().len());
+
+        if choice.is_none() && !input.key_just_pressed(InputKey::Escape) {
+            return Ok(());
+        }
+
+        self.redraw(server, terminal)?;
+
+        let chosen = choice.and_then(|i| server.inventory().get(i).map(|stack| (i, stack.kind)));
+
+        let (index, item) = match chosen {
+            Some(chosen) => chosen,
+            None => return Ok(()),
+        };
+
+        let definition = item.definition();
+        let player_xy = server.snapshot().player_xy();
+
+        if !definition.targeted() {
+            return self.handle_use_item(server, terminal, index, player_xy);
+        }
+
+        // Aim at the nearest visible actor in range, if any.
+        let xy = server
+            .snapshot()
+            .entities()
+            .iter()
+            .filter(|e| e.xy != player_xy && definition.in_range(player_xy, e.xy))
+            .min_by_key(|e| (e.xy.0 - player_xy.0).abs().max((e.xy.1 - player_xy.1).abs()))
+            .map_or(player_xy, |e| e.xy);
+
+        self.item_target = Some(ItemTarget { index, item, xy });
+        self.scroll_log.append(&format!(
+            "\n<fc:y>> Throw the {} where? <fc:K>(move to aim, accept or click to throw)",
+            definition.name
+        ))?;
+        self.scroll_log.scroll_to_bottom();
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that moves the aim of the item being targeted with the movement actions and
+    // uses the item on accept (or at a clicked cell). Escape stops targeting.
+    //---------------------------------------------------------------------------------------------
+    fn update_item_target(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        move_direction: Option<Direction>,
+    ) -> Result<()> {
+        let target = match &mut self.item_target {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+
+        if input.key_just_pressed(InputKey::Escape) {
+            self.item_target = None;
+            self.blit_map(server, terminal);
+            return Ok(());
+        }
+
+        if let Some(direction) = move_direction {
+            target.xy = (target.xy.0 + direction.dx(), target.xy.1 + direction.dy());
+            self.blit_map(server, terminal);
+            return Ok(());
+        }
+
+        let clicked = if input.mouse_clicked(InputMouse::Left) {
+            input.mouse_world_coord().and_then(|xy| {
+                terminal.map_layer().and_then(|layer| layer.world_to_layer_coords(xy))
+            })
+        } else {
+            None
+        };
+
+        if let Some(xy) = clicked {
+            target.xy = self.camera.view_to_map(xy);
+        } else if !input.action_just_pressed(InputAction::Accept) {
+            return Ok(());
+        }
+
+        let target = self.item_target.take().expect("Item target missing.");
+        self.handle_use_item(server, terminal, target.index, target.xy)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that uses an item of the inventory at a coord, reporting why if it cannot.
+    //---------------------------------------------------------------------------------------------
+    fn handle_use_item(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        index: usize,
+        xy: ICoord,
+    ) -> Result<()> {
+        if let ServerResult::Rejected(rejection) = server.use_item(index, xy)? {
+            self.scroll_log.append(&format!("\n<fc:y>> {}", rejection.message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that picks up the items in the player's cell. If the pile holds more than
+    // one kind of item, a popup offers to pick up all of them or only those of a category or kind.
+    //---------------------------------------------------------------------------------------------
+    fn handle_pick_up(&mut self, server: &mut Server, terminal: &mut Terminal) -> Result<()> {
+        let interaction = server
+            .available_interactions(server.player_id())
+            .into_iter()
+            .find(|interaction| interaction.verb == InteractionVerb::PickUp);
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+            None => {
+                self.scroll_log.append("\n<fc:y>> There is nothing here to pick up.")?;
+                self.scroll_log.scroll_to_bottom();
+                return Ok(());
+            }
+        };
+
+        let pile = server.ground_items(interaction.xy);
+        let mut kinds: Vec<ItemKind> = Vec::new();
+        let mut categories: Vec<ItemCategory> = Vec::new();
+
+        for stack in pile.iter() {
+            if !kinds.contains(&stack.kind) {
+                kinds.push(stack.kind);
+            }
+
+            if !categories.contains(&stack.kind.definition().category) {
+                categories.push(stack.kind.definition().category);
+            }
+        }
+
+        let mut filters = vec![PickupFilter::All];
+
+        if categories.len() > 1 {
+            filters.extend(categories.into_iter().map(PickupFilter::Category));
+        }
+
+        if kinds.len() > 1 {
+            filters.extend(kinds.into_iter().map(PickupFilter::Kind));
+        }
+
+        if filters.len() == 1 {
+            return self.handle_interaction(server, terminal, &interaction);
+        }
+
+        let options: Vec<String> = filters
+            .iter()
+            .map(|filter| {
+                let count: u32 = pile
+                    .iter()
+                    .filter(|stack| filter.matches(stack.kind))
+                    .map(|stack| stack.count)
+                    .sum();
+
+                match filter {
+                    PickupFilter::All => format!("All ({})", count),
+                    PickupFilter::Category(category) => format!("{} ({})", category.name(), count),
+                    PickupFilter::Kind(kind) => format!("{} ({})", kind.definition().name, count),
+                }
+            })
+            .collect();
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+
+        self.draw_prompt(terminal, interaction.xy, "Pick up", &options)?;
+        self.loot_prompt = Some((interaction, filters));
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that answers the shown loot popup by picking up the items passing the
+    // filter chosen by its key, or dismisses it on escape.
+    //---------------------------------------------------------------------------------------------
+    fn update_loot_prompt(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        let filters = match &self.loot_prompt {
+            Some((_, filters)) => filters,
+            None => return Ok(()),
+        };
+
+        let filter = Self::prompt_choice(input, filters.len()).map(|i| filters[i]);
+
+        if filter.is_none() && !input.key_just_pressed(InputKey::Escape) {
+            return Ok(());
+        }
+
+        let (mut interaction, _) = self.loot_prompt.take().expect("Loot prompt missing.");
+        self.redraw(server, terminal)?;
+
+        match filter {
+            Some(filter) => {
+                interaction.intention = InteractionIntention::PickUp(filter, ItemQuantity::All);
+                self.handle_interaction(server, terminal, &interaction)
+            }
+            None => Ok(()),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that carries out an interaction, reporting why if it cannot.
+    //---------------------------------------------------------------------------------------------
+    fn handle_interaction(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        interaction: &Interaction,
+    ) -> Result<()> {
+        if let ServerResult::Rejected(rejection) = server.interact(interaction)? {
+            self.scroll_log.append(&format!("\n<fc:y>> {}", rejection.message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        self.blit_map(server, terminal);
+        Ok(())
+    }
+
+    fn _handle_teleport(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        xy: ICoord,
+    ) -> Result<()> {
+        let zone_xy = self.camera.view_to_map(xy);
+        let layer = terminal.map_layer_mut().expect("Scratch map layer missing.");
+        // let response = server.handle(Request::Teleport(zone_xy));
+
+        // match response {
+        //     Response::Fail(resp) => {
+        //         if let Some(msg) = resp {
+        //             self.scroll_log.append(&format!("\n<fc:y>> {}", msg))?;
+        //         }
+        //     }
+        //     Response::Success(resp) => {
+        //         if let Some(msg) = resp {
+        //             self.scroll_log.append(&format!("\n<fc:y>> {}", msg))?;
+        //         }
+        //     }
+        // }
+
+        self.camera.unfollow();
+        self.camera.snap_to(zone_xy);
+        server.snapshot().blit_camera(&self.camera, layer, (0, 0), SHOW_FOV);
+
+        Ok(())
+    }
+
+    fn blit_map(&mut self, server: &mut Server, terminal: &mut Terminal) {
+        let layer = terminal.map_layer_mut().expect("Scratch map layer missing.");
+        let dimensions = layer.dimensions();
+        let snapshot = server.snapshot();
+
+        // Follow the player within the zone. The view snaps rather than eases, as the overlays
+        // below are only redrawn when the map is.
+        self.camera.set_dimensions(dimensions);
+        let (width, height) = snapshot.dimensions();
+        self.camera.set_bounds(Some(Rect::new((0, 0), width, height)));
+        self.camera.follow(snapshot.player_xy());
+        self.camera.update(Duration::default());
+        snapshot.blit_camera(&self.camera, layer, (0, 0), SHOW_FOV);
+
+        // Animate the visible idle cells (e.g. torches) from their freshly blitted tiles.
+        self.animator.clear();
+
+        for (xy, id) in snapshot.animated_cells() {
+            if let Some(view_xy) = self.camera.map_to_view(*xy) {
+                self.animator.track(&*layer, view_xy, *xy, *id);
+            }
+        }
+
+        // Draw the topmost item of each visible pile, unless an actor stands on it.
+        for (xy, item) in server.visible_ground_items() {
+            let view_xy = match self.camera.map_to_view(xy) {
+                Some(view_xy) => view_xy,
+                None => continue,
+            };
+
+            if snapshot.entities().iter().any(|e| e.xy == xy) {
+                continue;
+            }
+
+            let definition = item.definition();
+            let tile = layer.get_xy_mut(view_xy);
+            tile.glyph = definition.glyph;
+            tile.foreground_color = definition.color.const_into();
+        }
+
+        // Tint the map by the strength of the debug scent overlay, if enabled.
+        if let Some(kind) = self.scent_overlay {
+            xy_iter!(x, y, dimensions.0, dimensions.1, {
+                let xy = self.camera.view_to_map((x, y));
+                let strength = server.scent_strength(kind, xy);
+
+                if strength > 0.0 {
+                    let tile = layer.get_xy_mut((x, y));
+                    tile.background_color = PaletteColor::BrightMagenta.const_into();
+                    tile.background_opacity = strength.min(1.0) * 0.6;
+                }
+            });
+        }
+
+        // Draw the health bars of the visible actors other than the player, in the cells above
+        // them (or below them along the top of the view).
+        let mode = Settings::current().actor_bars;
+
+        if mode != ActorBarMode::Never {
+            let player_xy = server.snapshot().player_xy();
+
+            for entity in server.snapshot().entities() {
+                if entity.xy == player_xy || !mode.shows(entity.health.is_damaged()) {
+                    continue;
+                }
+
+                let view_xy = match self.camera.map_to_view(entity.xy) {
+                    Some(view_xy) => view_xy,
+                    None => continue,
+                };
+                let bar_xy = if view_xy.1 > 0 {
+                    (view_xy.0, view_xy.1 - 1)
+                } else {
+                    (view_xy.0, view_xy.1 + 1)
+                };
+
+                let fraction = entity.health.fraction();
+                let color = if fraction > 0.5 {
+                    PaletteColor::BrightGreen
+                } else if fraction > 0.25 {
+                    PaletteColor::Gold
+                } else {
+                    PaletteColor::BrightRed
+                };
+
+                let mut bar = ProgressBar::new(bar_xy, 1);
+                let _ = bar.set_progress(fraction);
+                bar.set_color(color.into());
+                bar.redraw(layer);
+            }
+        }
+
+        // Highlight the range of the item being aimed and the area it would affect (greyed out
+        // if the target is out of range or out of sight).
+        if let Some(target) = &self.item_target {
+            let definition = target.item.definition();
+            let player_xy = server.snapshot().player_xy();
+            let valid = definition.in_range(player_xy, target.xy)
+                && server.snapshot().is_visible(target.xy);
+
+            xy_iter!(x, y, dimensions.0, dimensions.1, {
+                let xy = self.camera.view_to_map((x, y));
+
+                if definition.in_range(player_xy, xy) && server.snapshot().is_visible(xy) {
+                    let tile = layer.get_xy_mut((x, y));
+                    tile.background_color = PaletteColor::White.const_into();
+                    tile.background_opacity = 0.08;
+                }
+            });
+
+            for xy in definition.area(target.xy) {
+                let view_xy = match self.camera.map_to_view(xy) {
+                    Some(view_xy) => view_xy,
+                    None => continue,
+                };
+
+                let tile = layer.get_xy_mut(view_xy);
+                tile.background_color = if valid {
+                    PaletteColor::BrightRed.const_into()
+                } else {
+                    PaletteColor::DarkGrey.const_into()
+                };
+                tile.background_opacity = 0.4;
+            }
+        }
+    }
+
+    fn draw_path(&mut self, server: &mut Server, terminal: &mut Terminal, xy: ICoord) {
+        self.blit_map(server, terminal);
+        let layer = terminal.map_layer_mut().expect("Scratch map layer missing.");
+        let rect = self.camera.view();
+
+        // Preview the path to the hovered coord (the remaining path while traveling).
+        self.preview =
+            if server.is_traveling() { None } else { server.preview_travel(rect.insert_xy(xy)) };
+
+        if !self.show_path {
+            return;
+        }
+
+        let path: Vec<ICoord> = match (&self.preview, server.travel_path()) {
+            (Some(preview), _) => preview.path.clone(),
+            (None, Some(path)) => path.iter().copied().collect(),
+            _ => return,
+        };
+
+        // Highlight the path.
+        for coord in path.iter() {
+            if let Some(norm) = &rect.extract_xy(*coord) {
+                let tile = layer.get_xy_mut(*norm);
+                tile.background_color = PaletteColor::White.const_into();
+                tile.background_opacity = 0.15;
+            }
+        }
+    }
+
+    fn update_move_repeats(&mut self, input: &InputManager, dt: &Duration) -> Option<Direction> {
+        let mut direction = None;
+
+        for (repeat, repeat_direction) in self.move_repeats.iter_mut() {
+            if repeat.update(dt, input) && direction.is_none() {
+                direction = Some(*repeat_direction);
+            }
+        }
+
+        direction
+    }
+
+    fn handle_travel_status(&mut self, status: TravelStatus) -> Result<()> {
+        let msg = match status {
+            TravelStatus::Idle | TravelStatus::Traveling => return Ok(()),
+            TravelStatus::Arrived => "Arrived.",
+            TravelStatus::Interrupted(TravelInterruption::Blocked) => "The way is blocked.",
+            TravelStatus::Interrupted(TravelInterruption::ActorSighted) => "You see something!",
+            TravelStatus::Interrupted(TravelInterruption::OffPath) => "You lost your way.",
+            TravelStatus::Interrupted(TravelInterruption::Cancelled) => "You stop traveling.",
+        };
+
+        self.scroll_log.append(&format!("\n<fc:y>> <fc:$>{}", msg))?;
+        self.scroll_log.scroll_to_bottom();
+        self.refresh_preview = true;
+
+        Ok(())
+    }
+
     //---------------------------------------------------------------------------------------------
-    // Creates a new scratch scene.
+    // Helper function that logs the item events since the last poll. Thrown items fly from the
+    // player to their target, and the change in hit points of each actor hit floats up from it.
     //---------------------------------------------------------------------------------------------
-    pub fn new() -> Self {
-        Self {
-            scroll_log: ScrollLog::new(
-                (85 - 30, 33 - 11),
-                (30, 11),
-                FrameStyle::LineBlockCorner,
-                9,
-            ),
-            view: Rect::new((0, 0), 55, 33),
-            path: Vec::new(),
-            last_offset: (0, 0),
-            show_path: true,
-            repeat: InputRepeat::for_mouse(InputMouse::Left, Duration::from_millis(330), None),
-            moved_with_mouse: false,
+    fn log_item_events(&mut self, server: &mut Server, terminal: &Terminal) -> Result<()> {
+        for event in server.poll_item_events() {
+            let definition = event.item.definition();
+            let verb = match definition.usage {
+                ItemUsage::Consume => "use",
+                ItemUsage::Throw => "throw",
+            };
+
+            self.scroll_log.append(&format!("\n<fc:y>> You {} the {}.", verb, definition.name))?;
+            self.scroll_log.scroll_to_bottom();
+
+            let layer = match terminal.map_layer() {
+                Some(layer) => layer,
+                None => continue,
+            };
+            let offset = self.camera.offset();
+            let to_view = |xy: ICoord| (xy.0 - offset.0, xy.1 - offset.1);
+
+            if definition.usage == ItemUsage::Throw && Accessibility::motion_enabled() {
+                self.floating_texts.spawn(FloatingText::projectile(
+                    layer,
+                    to_view(event.from),
+                    to_view(event.to),
+                    &format!("<o:t><fc:{}>{}", definition.color.format_hint(), definition.glyph),
+                )?);
+            }
+
+            for hit in event.hits.iter().filter(|hit| hit.change != 0) {
+                let text = if hit.change > 0 {
+                    format!("<o:t><fc:G>+{}", hit.change)
+                } else {
+                    format!("<o:t><fc:R>{}", hit.change)
+                };
+
+                if layer.in_bounds(to_view(hit.xy)) {
+                    self.floating_texts.spawn(FloatingText::above_tile(
+                        layer,
+                        to_view(hit.xy),
+                        &text,
+                        DEFAULT_FLOATING_TEXT_LIFETIME,
+                    )?);
+                }
+            }
         }
+
+        Ok(())
     }
 
-    fn handle_move(
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lists stacks of items in their colors, counting the items of the same
+    // kind together.
+    //---------------------------------------------------------------------------------------------
+    fn describe_items(stacks: &[ItemStack]) -> String {
+        let mut counts: Vec<(ItemKind, u32)> = Vec::new();
+
+        for stack in stacks.iter() {
+            match counts.iter_mut().find(|(kind, _)| *kind == stack.kind) {
+                Some((_, count)) => *count += stack.count,
+                None => counts.push((stack.kind, stack.count)),
+            }
+        }
+
+        counts
+            .iter()
+            .map(|(kind, count)| {
+                let definition = kind.definition();
+                let name =
+                    format!("<fc:{}>{}<fc:y>", definition.color.format_hint(), definition.name);
+
+                if *count > 1 {
+                    format!("{} x{}", name, count)
+                } else {
+                    name
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that logs the pickup events since the last poll, summarizing the items
+    // picked up and those left behind.
+    //---------------------------------------------------------------------------------------------
+    fn log_pickup_events(&mut self, server: &mut Server) -> Result<()> {
+        for event in server.poll_pickup_events() {
+            if !event.picked_up.is_empty() {
+                self.scroll_log.append(&format!(
+                    "\n<fc:y>> You pick up {}.",
+                    Self::describe_items(&event.picked_up)
+                ))?;
+            }
+
+            if !event.left.is_empty() {
+                self.scroll_log.append(&format!(
+                    "\n<fc:y>> You see here {}.",
+                    Self::describe_items(&event.left)
+                ))?;
+            }
+
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that logs the charge events since the last poll, telling the player when
+    // their pools of charges (e.g. the dash) are full again.
+    //---------------------------------------------------------------------------------------------
+    fn log_charge_events(&mut self, server: &mut Server) -> Result<()> {
+        let player_id = server.player_id();
+
+        for event in server.poll_charge_events() {
+            if event.entity != player_id || !event.full {
+                continue;
+            }
+
+            self.scroll_log.append(&format!("\n<fc:y>> Your {} is ready.", event.name))?;
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that logs the combat events since the last poll. Each entry is an anchor
+    // that shows the roll breakdown of the attack when clicked. The damage (or miss) also floats
+    // up from the defender's cell.
+    //---------------------------------------------------------------------------------------------
+    fn log_combat_events(&mut self, server: &mut Server, terminal: &Terminal) -> Result<()> {
+        let events = server.poll_combat_events();
+        let player_id = server.player_id();
+
+        for event in events.iter() {
+            let breakdown = &event.breakdown;
+
+            if let Some(layer) = terminal.map_layer() {
+                if let Some(view_xy) = self.camera.map_to_view(event.xy) {
+                    let text = if breakdown.hit() {
+                        format!("<o:t><fc:R>-{}", breakdown.damage())
+                    } else {
+                        "<o:t><fc:y>miss".into()
+                    };
+                    self.floating_texts.spawn(FloatingText::above_tile(
+                        layer,
+                        view_xy,
+                        &text,
+                        DEFAULT_FLOATING_TEXT_LIFETIME,
+                    )?);
+                }
+            }
+
+            // The player's attacks are described in the second person.
+            let (hits, misses) =
+                if event.attacker == player_id { ("hit", "miss") } else { ("hits", "misses") };
+            let text = if breakdown.hit() {
+                format!(
+                    "<fc:y>> {} {} {} for {}. <fc:K>[+]",
+                    event.attacker_name,
+                    hits,
+                    event.defender_name,
+                    breakdown.damage()
+                )
+            } else {
+                format!(
+                    "<fc:y>> {} {} {}. <fc:K>[+]",
+                    event.attacker_name, misses, event.defender_name
+                )
+            };
+
+            let tags = vec![format!("{}combat", ANCHOR_TAG_PREFIX)];
+            let id = self.scroll_log.push_line(&text, tags)?;
+            self.combat_details.push_back((id, breakdown.clone()));
+
+            if self.combat_details.len() > COMBAT_DETAIL_CAPACITY {
+                let _ = self.combat_details.pop_front();
+            }
+        }
+
+        if !events.is_empty() {
+            self.scroll_log.scroll_to_bottom();
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the lines of rich text showing the math of an attack.
+    //---------------------------------------------------------------------------------------------
+    fn combat_detail_lines(breakdown: &RollBreakdown) -> Vec<String> {
+        let row = |label: &str, value: String| {
+            format!(
+                "<l:t><fc:y>{:<width$}{:>4}",
+                label,
+                value,
+                width = COMBAT_DETAIL_WIDTH as usize - 4
+            )
+        };
+        let modifier =
+            |m: &RollModifier| row(&format!("  {}", m.source), format!("{:+}", m.value));
+
+        let mut lines = vec![String::from("<l:t><st:b><fc:$>Attack")];
+        lines.push(row("  roll", breakdown.attack_roll.to_string()));
+        lines.extend(breakdown.attack_modifiers.iter().map(modifier));
+        lines.push(row("  total", breakdown.attack_total().to_string()));
+        lines.push(row("  vs defense", breakdown.defense.to_string()));
+        lines.push(if breakdown.hit() {
+            String::from("<l:t><fc:G>  Hit")
+        } else {
+            String::from("<l:t><fc:R>  Miss")
+        });
+
+        if breakdown.hit() {
+            lines.push(String::from("<l:t><st:b><fc:$>Damage"));
+            lines.push(row("  roll", breakdown.damage_roll.to_string()));
+            lines.extend(breakdown.damage_modifiers.iter().map(modifier));
+            lines.push(row("  mitigation", format!("{:+}", -breakdown.mitigation)));
+            lines.push(row("  dealt", breakdown.damage().to_string()));
+        }
+
+        lines
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that shows the roll breakdown of a logged attack in a panel beside the log,
+    // or hides it if it was already shown.
+    //---------------------------------------------------------------------------------------------
+    fn toggle_combat_detail(
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
-        direction: &Direction,
+        id: RichTextLineId,
     ) -> Result<()> {
-        // Don't move if the new coord is blocked.
-        let player_xy = server.zone().player_xy;
-        let new_xy = (player_xy.0 + direction.dx(), player_xy.1 + direction.dy());
-        if server.zone().is_blocked(new_xy) {
-            return Ok(());
+        let breakdown = match self.combat_details.iter().find(|(line, _)| *line == id) {
+            Some((_, breakdown)) if self.shown_combat_detail != Some(id) => breakdown.clone(),
+            _ => return self.hide_combat_detail(server, terminal),
+        };
+
+        // Clear any detail already shown.
+        self.redraw(server, terminal)?;
+
+        let lines = Self::combat_detail_lines(&breakdown);
+        let height = lines.len() as i32;
+        let origin = (
+            (self.scroll_log.origin().0 - COMBAT_DETAIL_WIDTH - 3).max(0),
+            (self.scroll_log.origin().1 + self.scroll_log.height() - height - 3).max(0),
+        );
+
+        let mut panel = Panel::new(origin, (COMBAT_DETAIL_WIDTH, height), FrameStyle::Line);
+        panel.frame.top_left_text = Some("Roll".into());
+        panel.draw(terminal)?;
+
+        for (i, line) in lines.iter().enumerate() {
+            let xy = panel.inner_origin();
+            RichTextWriter::write(terminal, (xy.0, xy.1 + i as i32), line)?;
         }
 
-        let _ = server.move_player(*direction);
-        self.last_offset = server.blit_centered_on_player(terminal, (55, 33), (0, 0), SHOW_FOV);
+        self.shown_combat_detail = Some(id);
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that hides the shown roll breakdown, if any.
+    //---------------------------------------------------------------------------------------------
+    fn hide_combat_detail(&mut self, server: &mut Server, terminal: &mut Terminal) -> Result<()> {
+        if self.shown_combat_detail.take().is_some() {
+            self.redraw(server, terminal)?;
+        }
 
         Ok(())
     }
 
-    fn _handle_teleport(
+    //---------------------------------------------------------------------------------------------
+    // Helper function that refreshes the turn order from the snapshot, redrawing it if it changed
+    // (or always, if forced).
+    //---------------------------------------------------------------------------------------------
+    fn refresh_turn_order(&mut self, server: &Server, terminal: &mut Terminal, force: bool) {
+        let player_xy = server.snapshot().player_xy();
+        let entries = server.snapshot().turn_order().map(|entity| TurnOrderEntry {
+            glyph: entity.tile.glyph,
+            color: entity.tile.foreground_color,
+            player: entity.xy == player_xy,
+        });
+
+        if self.turn_order.set_entries(entries) || force {
+            self.turn_order.redraw(terminal);
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that refreshes the player's load and status markers from the snapshot,
+    // redrawing them if they changed (or always, if forced). Changes of the tier of encumbrance
+    // are also logged.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_status(
         &mut self,
-        server: &mut Server,
+        server: &Server,
         terminal: &mut Terminal,
-        xy: ICoord,
+        force: bool,
     ) -> Result<()> {
-        let zone_xy = (xy.0 + self.last_offset.0, xy.1 + self.last_offset.1);
-        // let response = server.handle(Request::Teleport(zone_xy));
+        let encumbrance = server.snapshot().encumbrance();
+        let previous = self.shown_encumbrance.replace(encumbrance);
 
-        // match response {
-        //     Response::Fail(resp) => {
-        //         if let Some(msg) = resp {
-        //             self.scroll_log.append(&format!("\n<fc:y>> {}", msg))?;
-        //         }
-        //     }
-        //     Response::Success(resp) => {
-        //         if let Some(msg) = resp {
-        //             self.scroll_log.append(&format!("\n<fc:y>> {}", msg))?;
-        //         }
-        //     }
-        // }
+        if let Some(previous) = previous.filter(|previous| previous.tier != encumbrance.tier) {
+            let heavier = encumbrance.tier > previous.tier;
+            let message = match encumbrance.tier.marker() {
+                Some(marker) => format!(
+                    "You are {} <fc:{}>{}<fc:y>.",
+                    if heavier { "now" } else { "only" },
+                    marker.color().format_hint(),
+                    marker.name().to_lowercase()
+                ),
+                None => "You are no longer encumbered.".into(),
+            };
 
-        // self.last_offset = server.blit_player_centered(terminal, (55, 33), (0, 0), true);
-        self.last_offset = server.blit_centered(terminal, zone_xy, (55, 33), (0, 0), SHOW_FOV);
+            self.scroll_log.append(&format!("\n<fc:y>> {}", message))?;
+            self.scroll_log.scroll_to_bottom();
+        }
 
-        Ok(())
-    }
+        if previous == Some(encumbrance) && !force {
+            return Ok(());
+        }
 
-    fn draw_path(&mut self, server: &mut Server, terminal: &mut Terminal, xy: ICoord) {
-        if !self.show_path {
-            return;
+        let markers: Vec<String> = server
+            .snapshot()
+            .status_markers()
+            .iter()
+            .map(|marker| format!("<fc:{}>{}", marker.color().format_hint(), marker.name()))
+            .collect();
+        let lines = [
+            format!("<fc:K>Load <fc:Y>{}<fc:K>/{}", encumbrance.weight, encumbrance.capacity),
+            markers.join(" "),
+        ];
+
+        for (i, line) in lines.iter().enumerate() {
+            let xy = (STATUS_ORIGIN.0, STATUS_ORIGIN.1 + i as i32);
+            RichTextWriter::write(terminal, xy, &format!("{:<width$}", "", width = STATUS_WIDTH))?;
+            RichTextWriter::write(terminal, xy, line)?;
         }
 
-        self.last_offset = server.blit_centered_on_player(terminal, (55, 33), (0, 0), SHOW_FOV);
-        let rect = Rect::new(self.last_offset, 55, 33);
-        let player_xy = server.zone().player_xy;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that refreshes the action bar from the interactions available to the
+    // player, with the keys bound to each, redrawing it if it changed.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_action_bar(
+        &mut self,
+        server: &Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) {
+        let mut entries: Vec<ActionBarEntry> = Vec::new();
 
-        self.path.clear();
-        Lines::push_dda(player_xy, rect.insert_xy(xy), &mut self.path);
+        let interactions = server.available_interactions(server.player_id());
 
-        for coord in self.path.iter().skip(1) {
-            if let Some(norm) = &Rect::new(self.last_offset, 55, 33).extract_xy(*coord) {
-                let tile = terminal.get_xy_mut(*norm);
+        for interaction in interactions.iter().filter(|i| i.validity.is_valid()) {
+            let action = match interaction.intention {
+                InteractionIntention::Move(direction) => MOVE_ACTIONS
+                    .iter()
+                    .copied()
+                    .find(|action| action.direction() == Some(direction)),
+                InteractionIntention::CloseDoor(_) => Some(InputAction::CloseDoor),
+                InteractionIntention::PickUp(..) => Some(InputAction::PickUp),
+            };
 
-                if server.zone().is_blocked(*coord) || tile.foreground_opacity == 0.0 {
-                    break;
-                }
+            let keys = action
+                .and_then(|action| input.action_bindings(action))
+                .map(InputBinding::describe_all)
+                .unwrap_or_else(|| UNBOUND_KEYS.into());
+            let entry = ActionBarEntry { keys, label: interaction.verb.name().into() };
 
-                tile.background_color = PaletteColor::White.const_into();
-                tile.background_opacity = 0.15;
+            if !entries.contains(&entry) {
+                entries.push(entry);
             }
         }
+
+        // Show the dash, counting down the turns until it is ready.
+        if let Some(turns) =
+            server.player_charges(DASH_CHARGES).and_then(|c| c.turns_until_ready())
+        {
+            let keys = input
+                .action_bindings(InputAction::Dash)
+                .map(InputBinding::describe_all)
+                .unwrap_or_else(|| UNBOUND_KEYS.into());
+            let label = if turns == 0 { "Dash".into() } else { format!("Dash ({})", turns) };
+            entries.push(ActionBarEntry { keys, label });
+        }
+
+        if self.action_bar.set_entries(entries) {
+            self.action_bar.redraw(terminal);
+        }
+    }
+
+    fn redraw(&mut self, server: &mut Server, terminal: &mut Terminal) -> Result<()> {
+        terminal.set_all_tiles_blank();
+        self.shown_combat_detail = None;
+        self.bump_prompt = None;
+        self.inventory_prompt = false;
+        self.loot_prompt = None;
+        self.drop_prompt = None;
+        self.dash_armed = false;
+
+        self.blit_map(server, terminal);
+
+        let mut stats_frame =
+            Frame::new((85 - 30, 0), (28, 33 - 11 - 1), FrameStyle::LineBlockCorner);
+        stats_frame.top_left_text = Some("<character name>".into());
+        stats_frame.draw(terminal)?;
+
+        let turn_order_xy = self.turn_order.origin();
+        RichTextWriter::write(terminal, (turn_order_xy.0, turn_order_xy.1 - 1), TURN_ORDER_LABEL)?;
+        self.refresh_turn_order(server, terminal, true);
+        self.refresh_status(server, terminal, true)?;
+        self.action_bar.redraw(terminal);
+
+        self.scroll_log.redraw(terminal)?;
+
+        Ok(())
     }
 }
 
@@ -137,63 +1504,99 @@ impl Scene for Scratch {
     //---------------------------------------------------------------------------------------------
     // Called when the scene is added to the stack.
     //---------------------------------------------------------------------------------------------
-    fn load(
+    fn on_enter(
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
         input: &InputManager,
     ) -> Result<()> {
-        self.focus(server, terminal, input)?;
+        self.on_resume(server, terminal, input)?;
         Ok(())
     }
 
     //---------------------------------------------------------------------------------------------
     // Called when the scene is removed from the stack.
     //---------------------------------------------------------------------------------------------
-    fn unload(
+    fn on_exit(
         &mut self,
-        _server: &mut Server,
-        _terminal: &mut Terminal,
+        server: &mut Server,
+        terminal: &mut Terminal,
         _input: &InputManager,
     ) -> Result<()> {
+        // The journal is saved by the loading scene when leaving for another zone.
+        if !self.journal_save_queued {
+            if let Err(e) = Self::save_journal(server) {
+                eprintln!("[Scratch] Failed to save journal: {:?}", e);
+            }
+        }
+
+        self.floating_texts.clear();
+        self.floating_texts.draw(terminal);
+        terminal.set_map_layer(None);
+        terminal.set_color_grade(ColorGrade::default());
         Ok(())
     }
 
     //---------------------------------------------------------------------------------------------
     // Called when the scene is made current again (e.g. a the next scene was popped).
     //---------------------------------------------------------------------------------------------
-    fn focus(
+    fn on_resume(
         &mut self,
         server: &mut Server,
         terminal: &mut Terminal,
-        _input: &InputManager,
+        input: &InputManager,
     ) -> Result<()> {
+        // Pick up the configured repeat profiles of the movement actions.
+        for (action, (repeat, _)) in MOVE_ACTIONS.iter().zip(self.move_repeats.iter_mut()) {
+            repeat.set_profile(&input.repeat_profile(*action));
+        }
+
         terminal.set_opaque();
-        terminal.set_all_tiles_blank();
+        terminal.set_map_layer(Some(TileLayer::new(self.view)));
 
-        *server = Server::new()?;
-        self.last_offset = server.blit_centered_on_player(terminal, (55, 33), (0, 0), SHOW_FOV);
+        if self.new_game {
+            *server = Server::new()?;
+            self.new_game = false;
 
-        let mut stats_frame =
-            Frame::new((85 - 30, 0), (28, 33 - 11 - 1), FrameStyle::LineBlockCorner);
-        stats_frame.top_left_text = Some("<character name>".into());
-        stats_frame.draw(terminal)?;
+            // There is no saved game yet, so the journal carries over between games.
+            let path = PLATFORM_DIRS.save_dir().join(JOURNAL_SAVE_FILE);
 
-        self.scroll_log.append("<l:t><fc:$>Welcome to FVR_ENGINE")?;
-        self.scroll_log.redraw(terminal)?;
+            if path.exists() {
+                match Journal::load(&path) {
+                    Ok(journal) => server.set_journal(journal),
+                    Err(e) => eprintln!("[Scratch] Failed to load journal: {:?}", e),
+                }
+            }
 
-        Ok(())
+            // Zones left are saved beside the journal (or kept in memory if that fails).
+            match Self::clear_zone_dir() {
+                Ok(dir) => server.set_zone_dir(dir, CONFIG.save_compression),
+                Err(e) => eprintln!("[Scratch] Failed to prepare zone directory: {:?}", e),
+            }
+        }
+
+        // Pick up the items allowed by the player's settings (which may have been edited while
+        // the scene was suspended).
+        server.set_auto_pickup(Settings::current().auto_pickup);
+
+        terminal.set_color_grade(server.zone_color_grade());
+        self.scroll_log.append("<l:t><fc:$>Welcome to FVR_ENGINE")?;
+        self.redraw(server, terminal)
     }
 
     //---------------------------------------------------------------------------------------------
     // Called when the scene is made no longer current (e.g. a new scene is pushed).
     //---------------------------------------------------------------------------------------------
-    fn unfocus(
+    fn on_suspend(
         &mut self,
         _server: &mut Server,
-        _terminal: &mut Terminal,
+        terminal: &mut Terminal,
         _input: &InputManager,
     ) -> Result<()> {
+        self.floating_texts.clear();
+        self.floating_texts.draw(terminal);
+        terminal.set_map_layer(None);
+        terminal.set_color_grade(ColorGrade::default());
         Ok(())
     }
 
@@ -207,68 +1610,190 @@ impl Scene for Scratch {
         input: &InputManager,
         dt: &Duration,
     ) -> Result<SceneAction> {
+        // Start the fast travel chosen on the world map.
+        if let Some(id) = server.zone_graph_mut().take_destination() {
+            let jobs: Vec<Box<dyn Job<Server>>> = vec![
+                Box::new(JournalSaveJob::new(PLATFORM_DIRS.save_dir().join(JOURNAL_SAVE_FILE))),
+                Box::new(ZoneJob::new(id)),
+            ];
+            self.journal_save_queued = true;
+            let loading = Loading::new(jobs, Box::new(Scratch::resume()));
+            return Ok(SceneAction::Swap(Box::new(loading)));
+        }
+
         let scroll_log_action = self.scroll_log.update(input, terminal)?;
 
-        if input.action_just_pressed(InputAction::Quit) || input.key_just_pressed(InputKey::Escape)
+        // Any key stops travel.
+        if server.is_traveling() && (input.any_key_pressed() || input.any_action_pressed()) {
+            let status = server.cancel_travel();
+            self.handle_travel_status(status)?;
+        }
+
+        // Cycle the debug scent overlay.
+        if input.key_just_pressed(InputKey::F9) {
+            self.scent_overlay = match self.scent_overlay {
+                None => Some(ScentKind::Player),
+                Some(ScentKind::Player) => Some(ScentKind::Blood),
+                Some(ScentKind::Blood) => None,
+            };
+            self.blit_map(server, terminal);
+        }
+
+        // Pass any turns queued by the debug step command.
+        if server.update() {
+            self.blit_map(server, terminal);
+        }
+
+        // Update the movement repeats (all of them, so that each tracks its held state).
+        let move_direction = self.update_move_repeats(input, dt);
+
+        // Popups and targeting take the input until they are answered or dismissed. Escape hides
+        // the shown roll breakdown before leaving the scene.
+        let prompting = self.bump_prompt.is_some()
+            || self.inventory_prompt
+            || self.item_target.is_some()
+            || self.loot_prompt.is_some()
+            || self.drop_prompt.is_some()
+            || self.macro_prompt;
+
+        if self.bump_prompt.is_some() {
+            self.update_bump_prompt(server, terminal, input)?;
+        } else if self.loot_prompt.is_some() {
+            self.update_loot_prompt(server, terminal, input)?;
+        } else if self.drop_prompt.is_some() {
+            self.update_drop_prompt(server, terminal, input)?;
+        } else if self.inventory_prompt {
+            self.update_inventory_prompt(server, terminal, input)?;
+        } else if self.item_target.is_some() {
+            self.update_item_target(server, terminal, input, move_direction)?;
+        } else if self.macro_prompt {
+            self.update_macro_prompt(server, terminal, input)?;
+        } else if input.recorded_macro().is_some() {
+            self.show_macro_prompt(server, terminal, input)?;
+        } else if self.dash_armed && input.key_just_pressed(InputKey::Escape) {
+            self.dash_armed = false;
+        } else if self.shown_combat_detail.is_some() && input.key_just_pressed(InputKey::Escape) {
+            self.hide_combat_detail(server, terminal)?;
+        } else if input.action_just_pressed(InputAction::Quit)
+            || input.key_just_pressed(InputKey::Escape)
         {
             return Ok(SceneAction::Pop);
+        } else if input.key_just_pressed(InputKey::J) {
+            return Ok(SceneAction::Overlay(Box::new(JournalScene::new())));
+        } else if input.key_just_pressed(InputKey::M) {
+            return Ok(SceneAction::Push(Box::new(WorldMap::new())));
         } else if input.action_just_pressed(InputAction::Accept) {
             let _ = server.tick();
-            self.last_offset =
-                server.blit_centered_on_player(terminal, (55, 33), (0, 0), SHOW_FOV);
-        } else if input.action_just_pressed(InputAction::North) {
-            self.handle_move(server, terminal, &NORTH_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::South) {
-            self.handle_move(server, terminal, &SOUTH_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::East) {
-            self.handle_move(server, terminal, &EAST_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::West) {
-            self.handle_move(server, terminal, &WEST_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::Northeast) {
-            self.handle_move(server, terminal, &NORTHEAST_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::Southeast) {
-            self.handle_move(server, terminal, &SOUTHEAST_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::Southwest) {
-            self.handle_move(server, terminal, &SOUTHWEST_DIRECTION)?;
-        } else if input.action_just_pressed(InputAction::Northwest) {
-            self.handle_move(server, terminal, &NORTHWEST_DIRECTION)?;
-        } else if scroll_log_action == ScrollLogAction::Interactable {
+            self.blit_map(server, terminal);
+        } else if input.action_just_pressed(InputAction::CloseDoor) {
+            self.handle_close_door(server, terminal)?;
+        } else if input.action_just_pressed(InputAction::Inventory) {
+            self.show_inventory_prompt(server, terminal)?;
+        } else if input.action_just_pressed(InputAction::PickUp) {
+            self.handle_pick_up(server, terminal)?;
+        } else if input.action_just_pressed(InputAction::Drop) {
+            self.show_drop_prompt(server, terminal)?;
+        } else if input.action_just_pressed(InputAction::Dash) {
+            self.arm_dash(server)?;
+        } else if let Some(direction) = move_direction.filter(|_| self.dash_armed) {
+            self.handle_dash(server, terminal, &direction)?;
+        } else if let Some(direction) = move_direction {
+            // Stop any macro being replayed if the move was blocked.
+            if !self.handle_move(server, terminal, &direction)? {
+                input.cancel_macro();
+            }
+        }
+
+        // Log the attacks resolved by any turns passed, and show the math of clicked entries.
+        self.log_combat_events(server, terminal)?;
+        self.log_item_events(server, terminal)?;
+        self.log_pickup_events(server)?;
+        self.log_charge_events(server)?;
+        self.refresh_turn_order(server, terminal, false);
+        self.refresh_status(server, terminal, false)?;
+        self.refresh_action_bar(server, terminal, input);
+
+        if let ScrollLogAction::AnchorClicked(id) = scroll_log_action {
+            self.toggle_combat_detail(server, terminal, id)?;
+        }
+
+        // Animate the idle cells of the map.
+        if let Some(layer) = terminal.map_layer_mut() {
+            self.animator.update(*dt, layer);
+        }
+
+        // Zoom the map with the mouse wheel.
+        if input.mouse_wheel() != 0 {
+            let layer = terminal.map_layer_mut().expect("Scratch map layer missing.");
+            let zoomed = if input.mouse_wheel() > 0 { layer.zoom_in() } else { layer.zoom_out() };
+            self.refresh_preview |= zoomed;
+        }
+
+        // Find the map coord under the mouse.
+        let hovered = input
+            .mouse_world_coord()
+            .and_then(|xy| terminal.map_layer().and_then(|layer| layer.world_to_layer_coords(xy)));
+        let hovered_moved = hovered != self.hovered;
+        self.hovered = hovered;
+
+        // Show a crosshair over the map.
+        if matches!(
+            scroll_log_action,
+            ScrollLogAction::Interactable | ScrollLogAction::AnchorClicked(_)
+        ) {
             input.set_cursor(Cursor::Hand);
+        } else if hovered.is_some() {
+            input.set_cursor(Cursor::Crosshair);
         } else {
-            input.set_cursor(Cursor::Arrow);
+            input.reset_cursor();
         }
 
-        let mouse_coord = input.mouse_coord();
+        // Begin traveling to the previewed destination on click.
+        if input.mouse_clicked(InputMouse::Left) && !prompting {
+            let destination = self.preview.as_ref().and_then(|preview| preview.destination());
 
-        if self.moved_with_mouse || input.mouse_moved() {
-            self.moved_with_mouse = false;
-            if let Some(xy) = mouse_coord {
-                self.draw_path(server, terminal, xy);
-                let zone_xy = (self.last_offset.0 + xy.0, self.last_offset.1 + xy.1);
-                self.scroll_log.append(&format!("\n<fc:y>> mouse: <fc:$>{:?}", zone_xy))?;
-                self.scroll_log.scroll_to_bottom();
+            if let (Some(_), Some(destination)) = (hovered, destination) {
+                if let ServerResult::Success = server.travel_to(destination) {
+                    self.travel_timer = TRAVEL_STEP_INTERVAL;
+                }
+            }
+        }
+
+        // Step along the travel path at a fixed interval.
+        if server.is_traveling() {
+            self.travel_timer += *dt;
+
+            if self.travel_timer >= TRAVEL_STEP_INTERVAL {
+                self.travel_timer = Duration::default();
+                let status = server.travel_step()?;
+                self.handle_travel_status(status)?;
+                self.refresh_preview = true;
             }
         }
 
-        if self.repeat.update(dt, input) {
-            if let Some(xy) = mouse_coord {
-                if self.view.contains(xy) {
-                    // The first coord in the path is always the player's coord.
-                    let path_coord = self.path.get(1).copied();
+        // Refresh the path preview and show the turn count and cost of the hovered path.
+        if self.refresh_preview || hovered_moved {
+            self.refresh_preview = false;
 
-                    if let Some(path) = path_coord {
-                        if !server.zone().is_blocked(path) {
-                            let player_xy = server.zone().player_xy;
+            match hovered {
+                Some(xy) => {
+                    self.draw_path(server, terminal, xy);
 
-                            self.moved_with_mouse = true;
-                            self.handle_move(
-                                server,
-                                terminal,
-                                &Direction::closest_direction(player_xy, path),
-                            )?;
+                    if hovered_moved {
+                        if let Some(preview) = &self.preview {
+                            self.scroll_log.append(&format!(
+                                "\n<fc:y>> path: <fc:$>{} turns (cost {:.1})",
+                                preview.turns(),
+                                preview.cost
+                            ))?;
+                            self.scroll_log.scroll_to_bottom();
                         }
                     }
                 }
+                _ => {
+                    self.preview = None;
+                    self.blit_map(server, terminal);
+                }
             }
         }
 
@@ -278,7 +1803,21 @@ impl Scene for Scratch {
     //---------------------------------------------------------------------------------------------
     // Called whenever the scene's (visual) internal state should be updated and rendered.
     //---------------------------------------------------------------------------------------------
-    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+    fn render(&mut self, terminal: &mut Terminal, dt: &Duration) -> Result<()> {
+        self.floating_texts.update(*dt);
+        self.floating_texts.draw(terminal);
         Ok(())
     }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.redraw(server, terminal)
+    }
 }
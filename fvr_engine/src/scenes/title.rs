@@ -0,0 +1,405 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::{anyhow, bail, Result};
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+use crate::scenes::loading::*;
+use crate::scenes::main_menu::*;
+use crate::scenes::options::*;
+use crate::scenes::scratch::*;
+use crate::scenes::transitions::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+const FADE_DURATION: Duration = Duration::from_millis(400);
+const BANNER_TEXT: &str = "FVR ENGINE";
+const BANNER_TOP_OFFSET: i32 = 4;
+const MENU_BUTTONS_OFFSET: i32 = 4;
+const VERSION_TEXT: &str = "Alpha v0.0.1";
+
+// Indices of the menu buttons.
+const NEW_GAME_BUTTON: i32 = 0;
+const CONTINUE_BUTTON: i32 = 1;
+const OPTIONS_BUTTON: i32 = 2;
+const QUIT_BUTTON: i32 = 3;
+
+// Seed and settings of the background wash noise.
+const WASH_SEED: u64 = 0x7171E;
+const WASH_NOISE: NoiseSettings = NoiseSettings {
+    noise_type: NoiseType::Simplex,
+    frequency: 0.06,
+    octaves: 2,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+
+// Drift of the background wash in cells per second.
+const WASH_DRIFT: (f32, f32) = (1.2, 0.5);
+
+// Colors the background wash blends between, and its maximum opacity.
+const WASH_DARK_COLOR: PaletteColor = PaletteColor::Black;
+const WASH_LIGHT_COLOR: PaletteColor = PaletteColor::DarkBlue;
+const WASH_OPACITY: f32 = 0.6;
+
+//-------------------------------------------------------------------------------------------------
+// Represents the possible states of the title scene.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    // The state during the brief initial fade in.
+    FadeIn,
+    // The state when waiting for the user to pick an option.
+    WaitForInput,
+    // The state during the brief final fade out to the next scene.
+    FadeOut,
+}
+
+//-------------------------------------------------------------------------------------------------
+// The title screen, showing the game's name over a slowly drifting color wash.
+//-------------------------------------------------------------------------------------------------
+pub struct Title {
+    // The state of the title scene.
+    state: State,
+    // Fade in transition helper.
+    fade_in: Fade,
+    // Fade out transition helper.
+    fade_out: Fade,
+    // Contains the final scene action to return after the user has made a selection.
+    next_scene: Option<SceneAction>,
+    // ButtonList containing the title menu options.
+    button_list: ButtonList,
+    // Noise sampled by the background wash.
+    noise: Noise,
+    // Time elapsed animating the background wash.
+    elapsed: Duration,
+    // Whether a game has been started (and can be continued).
+    game_started: bool,
+}
+
+impl Title {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new title scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        let menu_buttons = vec![
+            Button::new((0, 0), String::from("[n] New Game"), ButtonLayout::Text),
+            Button::new((0, 0), String::from("[c] Continue"), ButtonLayout::Text),
+            Button::new((0, 0), String::from("[o] Options"), ButtonLayout::Text),
+            Button::new((0, 0), String::from("[esc] Quit"), ButtonLayout::Text),
+        ];
+
+        Self {
+            state: State::FadeIn,
+            fade_in: Fade::new(&FADE_DURATION, 0.0, 1.0),
+            fade_out: Fade::new(&FADE_DURATION, 1.0, 0.0),
+            next_scene: None,
+            button_list: ButtonList::from_buttons_vec((0, 0), menu_buttons, true),
+            noise: Noise::new(WASH_SEED),
+            elapsed: Duration::default(),
+            game_started: false,
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that paints the background wash for the elapsed time.
+    // (only the background colors are touched, so the banner and menu are left intact)
+    //---------------------------------------------------------------------------------------------
+    fn paint_wash(&self, terminal: &mut Terminal) {
+        let t = self.elapsed.as_secs_f32();
+        let (dark, light): (TileColor, TileColor) =
+            (WASH_DARK_COLOR.into(), WASH_LIGHT_COLOR.into());
+        let mix = |a: u8, b: u8, t: f32| (a as f32 * (1.0 - t) + b as f32 * t).round() as u8;
+
+        for y in 0..terminal.height() {
+            for x in 0..terminal.width() {
+                let noise = self.noise.fbm(
+                    x as f32 + t * WASH_DRIFT.0,
+                    y as f32 + t * WASH_DRIFT.1,
+                    &WASH_NOISE,
+                );
+
+                // Map the noise from [-1.0, 1.0] to [0.0, 1.0].
+                let n = ((noise + 1.0) * 0.5).clamp(0.0, 1.0);
+                let (r, g, b) = (
+                    mix(dark.0.r, light.0.r, n),
+                    mix(dark.0.g, light.0.g, n),
+                    mix(dark.0.b, light.0.b, n),
+                );
+
+                let tile = terminal.get_xy_mut((x, y));
+                tile.background_color = TileColor::rgba(r, g, b, (WASH_OPACITY * 255.0) as u8);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the title screen for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, terminal: &mut Terminal) {
+        terminal.set_all_tiles_blank();
+
+        // Draw the banner.
+        let banner_settings = RichTextFormatSettings {
+            layout: Some(TileLayout::Center),
+            outlined: Some(true),
+            foreground_color: Some(PaletteColor::Gold.into()),
+            outline_color: Some(PaletteColor::Brown.into()),
+            ..Default::default()
+        };
+
+        let banner_dimensions = Banner::measure(BANNER_TEXT);
+        let banner_xy = ((terminal.width() - banner_dimensions.0) / 2, BANNER_TOP_OFFSET);
+        Banner::write(terminal, banner_xy, BANNER_TEXT, &banner_settings);
+
+        // Position and draw the menu buttons.
+        self.button_list.set_disabled(CONTINUE_BUTTON, !self.game_started);
+
+        let buttons_origin = (
+            (terminal.width() - self.button_list.width()) / 2,
+            BANNER_TOP_OFFSET + banner_dimensions.1 + MENU_BUTTONS_OFFSET,
+        );
+        self.button_list.set_origin(buttons_origin);
+        self.button_list.redraw(terminal);
+
+        // Draw the version text.
+        let version_settings = RichTextFormatSettings {
+            layout: Some(TileLayout::Text),
+            foreground_color: Some(PaletteColor::DarkGrey.into()),
+            ..Default::default()
+        };
+
+        let version_xy =
+            ((terminal.width() - VERSION_TEXT.len() as i32) / 2, terminal.height() - 2);
+        RichTextWriter::write_plain_with_settings(
+            terminal,
+            version_xy,
+            VERSION_TEXT,
+            &version_settings,
+        );
+
+        self.paint_wash(terminal);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that prepares the scene action of a menu option, if it has one.
+    //---------------------------------------------------------------------------------------------
+    fn select(&mut self, index: i32) -> Result<Option<SceneAction>> {
+        match index {
+            NEW_GAME_BUTTON => {
+                // Seed the starting zone from the clock, so that every new game differs.
+                let id: ZoneId = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.subsec_nanos());
+                let jobs: Vec<Box<dyn Job<Server>>> = vec![Box::new(ZoneJob::new(id))];
+                let loading = Loading::new(jobs, Box::new(Scratch::resume()));
+
+                self.game_started = true;
+                self.next_scene = Some(SceneAction::Push(Box::new(loading)));
+                self.state = State::FadeOut;
+            }
+            CONTINUE_BUTTON if self.game_started => {
+                self.next_scene = Some(SceneAction::Push(Box::new(Scratch::resume())));
+                self.state = State::FadeOut;
+            }
+            CONTINUE_BUTTON => {}
+            OPTIONS_BUTTON => {
+                self.next_scene = Some(SceneAction::Push(Box::new(Options::new())));
+                self.state = State::FadeOut;
+            }
+            QUIT_BUTTON => return Ok(Some(SceneAction::Pop)),
+            _ => bail!("Invalid menu option."),
+        }
+
+        Ok(None)
+    }
+}
+
+impl Scene for Title {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        self.on_resume(server, terminal, input)?;
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        // Reset state.
+        self.state = State::FadeIn;
+        self.fade_in.reset();
+        self.fade_out.reset();
+        self.next_scene = None;
+        self.button_list.reset();
+
+        // Reset the terminal.
+        terminal.set_transparent();
+        self.draw(terminal);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        match self.state {
+            State::FadeIn => {
+                if self.fade_in.finished() {
+                    self.state = State::WaitForInput;
+                }
+            }
+            State::WaitForInput => {
+                let hotkeys = [
+                    (InputKey::N, NEW_GAME_BUTTON),
+                    (InputKey::C, CONTINUE_BUTTON),
+                    (InputKey::O, OPTIONS_BUTTON),
+                ];
+
+                if input.action_just_pressed(InputAction::Quit)
+                    || input.key_just_pressed(InputKey::Escape)
+                {
+                    return Ok(SceneAction::Pop);
+                } else if input.key_just_pressed(InputKey::D) {
+                    // The development menu (editor, scratch, etc.) is only reachable by hotkey.
+                    self.next_scene = Some(SceneAction::Push(Box::new(MainMenu::new())));
+                    self.state = State::FadeOut;
+                } else if let Some((_, index)) =
+                    hotkeys.iter().find(|(key, _)| input.key_just_pressed(*key))
+                {
+                    if let Some(action) = self.select(*index)? {
+                        return Ok(action);
+                    }
+                } else {
+                    let button_list_action = self.button_list.update(input, terminal);
+
+                    // If a button has been triggered, prepare the next scene.
+                    if let ButtonListAction::Triggered(i) = button_list_action {
+                        if let Some(action) = self.select(i)? {
+                            return Ok(action);
+                        }
+
+                        input.set_cursor(Cursor::Hand);
+                    } else if button_list_action == ButtonListAction::Interactable {
+                        input.set_cursor(Cursor::Hand);
+                    } else {
+                        input.set_cursor(Cursor::Arrow);
+                    }
+                }
+            }
+            State::FadeOut => {
+                if self.fade_out.finished() {
+                    let next_scene = self
+                        .next_scene
+                        .take()
+                        .ok_or_else(|| anyhow!("Failure: The next scene was empty."))?;
+                    return Ok(next_scene);
+                }
+            }
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, terminal: &mut Terminal, dt: &Duration) -> Result<()> {
+        match self.state {
+            State::FadeIn => {
+                let _ = self.fade_in.update(terminal, dt);
+            }
+            State::FadeOut => {
+                let _ = self.fade_out.update(terminal, dt);
+            }
+            _ => {}
+        }
+
+        // Drift the background wash (it holds still when motion is reduced).
+        if Accessibility::motion_enabled() {
+            self.elapsed += Accessibility::animation_dt(*dt);
+            self.paint_wash(terminal);
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the input context pushed while the scene is on the stack.
+    //---------------------------------------------------------------------------------------------
+    fn input_context(&self) -> InputContext {
+        InputContext::Menu
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        _server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(terminal);
+        Ok(())
+    }
+}
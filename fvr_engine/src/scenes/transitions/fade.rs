@@ -7,6 +7,7 @@ use std::time::Duration;
 // Workspace includes.
 //-------------------------------------------------------------------------------------------------
 use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
 
 //-------------------------------------------------------------------------------------------------
 // Represents the possible states of the fade transition.
@@ -64,7 +65,8 @@ impl Fade {
                 // Find the diff between the initial and final opacity.
                 let diff = (self.final_opacity - self.initial_opacity).abs();
 
-                // Find the corresponding change in opacity.
+                // Find the corresponding change in opacity (respecting the animation speed cap).
+                let dt = Accessibility::animation_dt(*dt);
                 let change = diff * (dt.as_secs_f32() / self.timespan.as_secs_f32());
 
                 // Update the opacity and check if the final opacity has been met.
@@ -0,0 +1,537 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::collections::VecDeque;
+use std::time::Duration;
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Local includes.
+//-------------------------------------------------------------------------------------------------
+use crate::scene_stack::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// Columns and rows left between the world map and the edges of the terminal.
+const MARGIN: ICoord = (4, 2);
+
+// Texts of the panel's frame.
+const TITLE_TEXT: &str = "World map";
+const HINT_TEXT: &str = "arrows select, enter travel, esc close";
+
+// Legend of the connection colors, drawn above the graph.
+const LEGEND_TEXT: &str =
+    "<l:t><fc:w>·· <fc:y>stairs   <fc:M>·· <fc:y>portal   <fc:$>gold <fc:y>current zone";
+
+// Rows taken by the legend above the graph and the status line beneath it.
+const LEGEND_ROWS: i32 = 2;
+const STATUS_ROWS: i32 = 2;
+
+// Dimensions of a node, and the spacing between the columns and rows of nodes.
+const NODE_DIMENSIONS: ICoord = (16, 3);
+const NODE_SPACING: ICoord = (22, 5);
+
+// Glyph of the connections between nodes.
+const LINK_GLYPH: char = '·';
+
+//-------------------------------------------------------------------------------------------------
+// Statics.
+//-------------------------------------------------------------------------------------------------
+
+// Format settings of the status line.
+static STATUS_SETTINGS: RichTextFormatSettings = RichTextFormatSettings {
+    layout: Some(TileLayout::Text),
+    style: Some(TileStyle::Regular),
+    size: None,
+    outlined: None,
+    background_color: Some(PaletteColor::Black.const_into()),
+    foreground_color: Some(PaletteColor::BrightGrey.const_into()),
+    outline_color: None,
+    background_opacity: Some(1.0),
+    foreground_opacity: None,
+    outline_opacity: None,
+};
+
+//-------------------------------------------------------------------------------------------------
+// The world map scene, showing the discovered zones as a graph of their connections and allowing
+// fast travel to the zones that allow it.
+//-------------------------------------------------------------------------------------------------
+pub struct WorldMap {
+    // Panel drawn behind the graph.
+    panel: Panel,
+    // Grid coords (column, row) of the nodes, indexed as the zone graph's nodes.
+    cells: Vec<ICoord>,
+    // Index of the selected node.
+    selected: usize,
+    // Offset of the visible part of the graph, keeping the selected node in view.
+    scroll: ICoord,
+    // Message shown beneath the graph (e.g. why fast travel is not allowed).
+    status: String,
+}
+
+impl WorldMap {
+    //---------------------------------------------------------------------------------------------
+    // Creates a new world map scene.
+    //---------------------------------------------------------------------------------------------
+    pub fn new() -> Self {
+        Self {
+            panel: Panel::new((0, 0), (1, 1), FrameStyle::Line),
+            cells: Vec::new(),
+            selected: 0,
+            scroll: (0, 0),
+            status: String::new(),
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out the nodes of a zone graph on a grid. Each connected group of
+    // zones is laid out breadth first from the zone of it discovered first, one column per
+    // connection, with the groups stacked beneath one another.
+    //---------------------------------------------------------------------------------------------
+    fn layout(graph: &ZoneGraph) -> Vec<ICoord> {
+        let nodes = graph.nodes();
+        let index_of = |id: ZoneId| nodes.iter().position(|node| node.id == id);
+        let mut cells: Vec<Option<ICoord>> = vec![None; nodes.len()];
+        let mut row_offset = 0;
+
+        while let Some(root) = cells.iter().position(Option::is_none) {
+            let mut rows_at_depth: Vec<i32> = Vec::new();
+            let mut queue = VecDeque::new();
+            cells[root] = Some((0, row_offset));
+            queue.push_back((root, 0));
+
+            while let Some((index, depth)) = queue.pop_front() {
+                if rows_at_depth.len() <= depth {
+                    rows_at_depth.push(0);
+                }
+
+                cells[index] = Some((depth as i32, row_offset + rows_at_depth[depth]));
+                rows_at_depth[depth] += 1;
+
+                for neighbor in graph.neighbors(nodes[index].id).filter_map(index_of) {
+                    if cells[neighbor].is_none() {
+                        // Mark the neighbor as queued, its cell is assigned when dequeued.
+                        cells[neighbor] = Some((0, 0));
+                        queue.push_back((neighbor, depth + 1));
+                    }
+                }
+            }
+
+            row_offset += rows_at_depth.iter().max().copied().unwrap_or(1);
+        }
+
+        cells.into_iter().map(|cell| cell.unwrap_or((0, 0))).collect()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the rect of a node in graph space.
+    //---------------------------------------------------------------------------------------------
+    fn node_rect((column, row): ICoord) -> Rect {
+        Rect::new(
+            (column * NODE_SPACING.0, row * NODE_SPACING.1),
+            NODE_DIMENSIONS.0,
+            NODE_DIMENSIONS.1,
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the center of a node in graph space.
+    //---------------------------------------------------------------------------------------------
+    fn node_center(cell: ICoord) -> ICoord {
+        let rect = Self::node_rect(cell);
+        (rect.x + rect.width / 2, rect.y + rect.height / 2)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the rect of the graph's view within the panel.
+    //---------------------------------------------------------------------------------------------
+    fn view_rect(&self) -> Rect {
+        let origin = self.panel.inner_origin();
+        let (width, height) = self.panel.inner_dimensions();
+
+        Rect::new(
+            (origin.0, origin.1 + LEGEND_ROWS),
+            width.max(1),
+            (height - LEGEND_ROWS - STATUS_ROWS).max(1),
+        )
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that scrolls the view so that the selected node is visible.
+    //---------------------------------------------------------------------------------------------
+    fn scroll_to_selected(&mut self) {
+        let view = self.view_rect();
+        let rect = match self.cells.get(self.selected) {
+            Some(cell) => Self::node_rect(*cell),
+            None => return,
+        };
+
+        if rect.x < self.scroll.0 {
+            self.scroll.0 = rect.x;
+        } else if rect.x + rect.width > self.scroll.0 + view.width {
+            self.scroll.0 = rect.x + rect.width - view.width;
+        }
+
+        if rect.y < self.scroll.1 {
+            self.scroll.1 = rect.y;
+        } else if rect.y + rect.height > self.scroll.1 + view.height {
+            self.scroll.1 = rect.y + rect.height - view.height;
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the index of the nearest node from the selected node in a
+    // direction, if any.
+    //---------------------------------------------------------------------------------------------
+    fn node_in_direction(&self, (dx, dy): ICoord) -> Option<usize> {
+        let from = *self.cells.get(self.selected)?;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| {
+                let offset = (cell.0 - from.0, cell.1 - from.1);
+
+                // Weigh the offset along the direction over the offset across it.
+                let (along, across) =
+                    if dx != 0 { (offset.0 * dx, offset.1) } else { (offset.1 * dy, offset.0) };
+
+                if along > 0 {
+                    Some((along * 10 + across.abs(), index))
+                } else {
+                    None
+                }
+            })
+            .min()
+            .map(|(_, index)| index)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that returns the index of the node under a coord of the terminal, if any.
+    //---------------------------------------------------------------------------------------------
+    fn node_at(&self, xy: ICoord) -> Option<usize> {
+        let view = self.view_rect();
+
+        if !view.contains(xy) {
+            return None;
+        }
+
+        let graph_xy = (xy.0 - view.x + self.scroll.0, xy.1 - view.y + self.scroll.1);
+        self.cells.iter().position(|cell| Self::node_rect(*cell).contains(graph_xy))
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that updates the status line for the selected node.
+    //---------------------------------------------------------------------------------------------
+    fn refresh_status(&mut self, graph: &ZoneGraph) {
+        let node = match graph.nodes().get(self.selected) {
+            Some(node) => node,
+            None => {
+                self.status = String::from("No zones discovered.");
+                return;
+            }
+        };
+
+        self.status = if graph.current() == Some(node.id) {
+            format!("{} - you are here.", node.name)
+        } else if graph.can_fast_travel(node.id) {
+            format!("{} - press enter to fast travel.", node.name)
+        } else {
+            format!("{} - fast travel is not possible.", node.name)
+        };
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws the graph into a canvas the size of the view, so that nodes and
+    // connections outside of the view are clipped.
+    //---------------------------------------------------------------------------------------------
+    fn draw_graph(&self, graph: &ZoneGraph, canvas: &mut GridMap<Tile>) {
+        let to_view = |(x, y): ICoord| (x - self.scroll.0, y - self.scroll.1);
+        let index_of = |id: ZoneId| graph.nodes().iter().position(|node| node.id == id);
+
+        // Draw the connections first, so that the nodes are drawn over their ends.
+        for link in graph.links() {
+            let (from, to) = match (index_of(link.from), index_of(link.to)) {
+                (Some(from), Some(to)) => (from, to),
+                _ => continue,
+            };
+            let color = match link.kind {
+                ZoneLinkKind::Stairs => PaletteColor::Brown,
+                ZoneLinkKind::Portal => PaletteColor::BrightMagenta,
+            };
+
+            TerminalDraw::draw_line_with(
+                canvas,
+                to_view(Self::node_center(self.cells[from])),
+                to_view(Self::node_center(self.cells[to])),
+                |tile| {
+                    tile.glyph = LINK_GLYPH;
+                    tile.layout = TileLayout::Center;
+                    tile.foreground_color = color.into();
+                },
+            );
+        }
+
+        for (index, (node, cell)) in graph.nodes().iter().zip(self.cells.iter()).enumerate() {
+            let rect = Self::node_rect(*cell);
+            let rect = Rect::new(to_view((rect.x, rect.y)), rect.width, rect.height);
+            let current = graph.current() == Some(node.id);
+
+            let background = if index == self.selected {
+                PaletteColor::DarkBlue
+            } else {
+                PaletteColor::DarkGrey
+            };
+            let foreground = if current {
+                PaletteColor::Gold
+            } else if node.fast_travel {
+                PaletteColor::White
+            } else {
+                PaletteColor::BrightGrey
+            };
+
+            TerminalDraw::fill_rect_with(canvas, &rect, |tile| {
+                *tile = BLANK_TILE;
+                tile.background_color = background.into();
+            });
+
+            let settings = RichTextFormatSettings {
+                layout: Some(TileLayout::Text),
+                style: Some(if current { TileStyle::Bold } else { TileStyle::Regular }),
+                background_color: Some(background.into()),
+                foreground_color: Some(foreground.into()),
+                background_opacity: Some(1.0),
+                ..Default::default()
+            };
+            let name =
+                RichTextWriter::truncate_plain(&node.name, (rect.width - 2).max(0) as usize);
+            let offset = (rect.width - RichTextWriter::display_width(&name) as i32) / 2;
+
+            RichTextWriter::write_plain_with_settings(
+                canvas,
+                (rect.x + offset, rect.y + rect.height / 2),
+                &name,
+                &settings,
+            );
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that draws the graph and the status line.
+    //---------------------------------------------------------------------------------------------
+    fn redraw(&self, graph: &ZoneGraph, terminal: &mut Terminal) {
+        let view = self.view_rect();
+        let mut canvas = GridMap::new((view.width, view.height));
+        canvas.data_mut().fill(BLANK_TILE);
+
+        self.draw_graph(graph, &mut canvas);
+
+        for x in 0..view.width {
+            for y in 0..view.height {
+                let xy = (view.x + x, view.y + y);
+
+                if terminal.in_bounds(xy) {
+                    *terminal.get_xy_mut(xy) = *canvas.get_xy((x, y));
+                }
+            }
+        }
+
+        // Pad the status to clear the last one.
+        let width = view.width.max(0) as usize;
+        let status = format!("{:<width$}", self.status, width = width);
+        RichTextWriter::write_plain_with_settings(
+            terminal,
+            (view.x, view.y + view.height + STATUS_ROWS - 1),
+            &RichTextWriter::truncate_plain(&status, width),
+            &STATUS_SETTINGS,
+        );
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that lays out and draws the world map for the current terminal size.
+    //---------------------------------------------------------------------------------------------
+    fn draw(&mut self, server: &Server, terminal: &mut Terminal) -> Result<()> {
+        terminal.set_all_tiles_blank();
+
+        // The inner dimensions exclude the panel's border and shadow.
+        let inner_dimensions = (
+            (terminal.width() - MARGIN.0 * 2 - 3).max(1),
+            (terminal.height() - MARGIN.1 * 2 - 3).max(1),
+        );
+
+        self.panel = Panel::new(MARGIN, inner_dimensions, FrameStyle::Line);
+        self.panel.frame.top_left_text = Some(TITLE_TEXT.into());
+        self.panel.frame.bottom_right_text = Some(HINT_TEXT.into());
+        self.panel.draw(terminal)?;
+
+        RichTextWriter::write(terminal, self.panel.inner_origin(), LEGEND_TEXT)?;
+
+        self.scroll_to_selected();
+        self.refresh_status(server.zone_graph());
+        self.redraw(server.zone_graph(), terminal);
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Helper function that chooses the selected node as the fast travel destination.
+    // Returns whether travel was chosen.
+    //---------------------------------------------------------------------------------------------
+    fn travel(&mut self, server: &mut Server) -> bool {
+        let id = match server.zone_graph().nodes().get(self.selected) {
+            Some(node) => node.id,
+            None => return false,
+        };
+
+        server.zone_graph_mut().choose_destination(id)
+    }
+}
+
+impl Scene for WorldMap {
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is added to the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_enter(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+    ) -> Result<()> {
+        // Start with the current zone selected.
+        let graph = server.zone_graph();
+        self.selected = graph
+            .current()
+            .and_then(|id| graph.nodes().iter().position(|node| node.id == id))
+            .unwrap_or(0);
+
+        self.on_resume(server, terminal, input)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is removed from the stack.
+    //---------------------------------------------------------------------------------------------
+    fn on_exit(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made current again (e.g. a the next scene was popped).
+    //---------------------------------------------------------------------------------------------
+    fn on_resume(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        terminal.set_opaque();
+        self.cells = Self::layout(server.zone_graph());
+        self.draw(server, terminal)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the scene is made no longer current (e.g. a new scene is pushed).
+    //---------------------------------------------------------------------------------------------
+    fn on_suspend(
+        &mut self,
+        _server: &mut Server,
+        _terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (non-visual) internal state should be updated.
+    //---------------------------------------------------------------------------------------------
+    fn update(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        input: &InputManager,
+        _dt: &Duration,
+    ) -> Result<SceneAction> {
+        if input.key_just_pressed(InputKey::Escape)
+            || input.action_just_pressed(InputAction::Decline)
+        {
+            return Ok(SceneAction::Pop);
+        }
+
+        let previous = self.selected;
+        let clicked = match input.mouse_coord() {
+            Some(xy) if input.mouse_clicked(InputMouse::Left) => self.node_at(xy),
+            _ => None,
+        };
+
+        // Select with the arrow keys or by clicking.
+        let direction = if input.key_just_pressed(InputKey::Left) {
+            Some((-1, 0))
+        } else if input.key_just_pressed(InputKey::Right) {
+            Some((1, 0))
+        } else if input.key_just_pressed(InputKey::Up) {
+            Some((0, -1))
+        } else if input.key_just_pressed(InputKey::Down) {
+            Some((0, 1))
+        } else {
+            None
+        };
+
+        if let Some(index) = direction.and_then(|direction| self.node_in_direction(direction)) {
+            self.selected = index;
+        } else if let Some(index) = clicked {
+            self.selected = index;
+        }
+
+        // Travel with enter or by clicking the selected node (the scratch scene starts the
+        // travel once the map is closed).
+        if (input.action_just_pressed(InputAction::Accept) || clicked == Some(previous))
+            && self.travel(server)
+        {
+            return Ok(SceneAction::Pop);
+        }
+
+        if self.selected != previous {
+            self.scroll_to_selected();
+            self.refresh_status(server.zone_graph());
+            self.redraw(server.zone_graph(), terminal);
+        }
+
+        Ok(SceneAction::Noop)
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called whenever the scene's (visual) internal state should be updated and rendered.
+    //---------------------------------------------------------------------------------------------
+    fn render(&mut self, _terminal: &mut Terminal, _dt: &Duration) -> Result<()> {
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Called when the terminal has been resized (and cleared) so that the scene can reflow.
+    //---------------------------------------------------------------------------------------------
+    fn resize(
+        &mut self,
+        server: &mut Server,
+        terminal: &mut Terminal,
+        _input: &InputManager,
+    ) -> Result<()> {
+        self.draw(server, terminal)
+    }
+}
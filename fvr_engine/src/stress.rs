@@ -0,0 +1,202 @@
+//-------------------------------------------------------------------------------------------------
+// STD includes.
+//-------------------------------------------------------------------------------------------------
+use std::fmt;
+use std::time::{Duration, Instant};
+
+//-------------------------------------------------------------------------------------------------
+// Extern crate includes.
+//-------------------------------------------------------------------------------------------------
+use anyhow::Result;
+use rand::Rng as _;
+
+//-------------------------------------------------------------------------------------------------
+// Workspace includes.
+//-------------------------------------------------------------------------------------------------
+use fvr_engine_client::prelude::*;
+use fvr_engine_core::prelude::*;
+use fvr_engine_server::prelude::*;
+
+//-------------------------------------------------------------------------------------------------
+// Constants.
+//-------------------------------------------------------------------------------------------------
+
+// # of particle bursts emitted over the terminal every frame.
+const BURSTS_PER_FRAME: usize = 4;
+
+// Stream of the stress seed the client's randomness is drawn from (apart from the server's).
+const STRESS_STREAM: &str = "stress";
+
+//-------------------------------------------------------------------------------------------------
+// Samples collects durations and reports their percentiles.
+//-------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Default)]
+pub struct Samples {
+    // The collected durations.
+    durations: Vec<Duration>,
+}
+
+impl Samples {
+    //---------------------------------------------------------------------------------------------
+    // Adds a duration to the samples.
+    //---------------------------------------------------------------------------------------------
+    pub fn push(&mut self, duration: Duration) {
+        self.durations.push(duration);
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the # of samples.
+    //---------------------------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns whether there are no samples.
+    //---------------------------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+
+    //---------------------------------------------------------------------------------------------
+    // Returns the duration at a percentile in the range [0.0, 100.0] (zero if there are none).
+    //---------------------------------------------------------------------------------------------
+    pub fn percentile(&self, percentile: f32) -> Duration {
+        if self.is_empty() {
+            return Duration::default();
+        }
+
+        let mut sorted = self.durations.clone();
+        sorted.sort_unstable();
+
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f32).round();
+        sorted[rank as usize]
+    }
+}
+
+impl fmt::Display for Samples {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |percentile| self.percentile(percentile).as_secs_f64() * 1000.0;
+
+        write!(
+            f,
+            "p50 {:.3}ms, p95 {:.3}ms, p99 {:.3}ms, max {:.3}ms ({} samples)",
+            ms(50.0),
+            ms(95.0),
+            ms(99.0),
+            ms(100.0),
+            self.len()
+        )
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Runs the server for a # of turns, returning how long each turn took.
+//-------------------------------------------------------------------------------------------------
+pub fn stress_server(server: &mut Server, turns: u32) -> Samples {
+    let mut turn_times = Samples::default();
+
+    for _ in 0..turns {
+        let start = Instant::now();
+        server.tick();
+        turn_times.push(start.elapsed());
+    }
+
+    turn_times
+}
+
+//-------------------------------------------------------------------------------------------------
+// Runs the server for a # of turns (one per loop) while rendering a terminal of random outlined,
+// multi-style tiles and particles that change every frame. The tiles and particles are drawn from
+// the seed, so that runs can be reproduced. Stops early if the window is closed.
+//-------------------------------------------------------------------------------------------------
+pub fn stress_client(
+    server: &mut Server,
+    client: &mut Client,
+    terminal: &mut Terminal,
+    input: &mut InputManager,
+    turns: u32,
+    seed: u64,
+) -> Result<()> {
+    let mut rng = Rng::new(seed).fork(STRESS_STREAM);
+    *terminal.particles_mut() = ParticleSystem::with_seed(rng.gen());
+    let mut turn_times = Samples::default();
+    let mut frame_times = Samples::default();
+    let mut last_frame = Instant::now();
+    let start = Instant::now();
+
+    'main: for _ in 0..turns {
+        while let Some(event) = client.poll_event() {
+            if let EngineEvent::Quit = event {
+                break 'main;
+            }
+        }
+
+        let _ = client.update_input(input);
+        input.reset();
+
+        // Run a turn.
+        let turn_start = Instant::now();
+        server.tick();
+        turn_times.push(turn_start.elapsed());
+
+        // Change every tile (and add particles over them) before rendering.
+        terminal.randomize_with(&mut rng);
+
+        for _ in 0..BURSTS_PER_FRAME {
+            let origin = (rng.gen_range(0..terminal.width()), rng.gen_range(0..terminal.height()));
+            terminal.particles_mut().emit(ParticlePreset::Sparks, origin);
+        }
+
+        if client.render_frame(terminal)? {
+            frame_times.push(last_frame.elapsed());
+            last_frame = Instant::now();
+        }
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "[Stress] {} frames in {:.2?} ({:.1} fps).",
+        frame_times.len(),
+        elapsed,
+        frame_times.len() as f64 / elapsed.as_secs_f64()
+    );
+    println!("[Stress] Frame times: {}.", frame_times);
+    println!("[Stress] Turn times: {}.", turn_times);
+
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------
+// Tests.
+//-------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_samples_percentiles() {
+    let empty = Samples::default();
+    assert!(empty.is_empty());
+    assert_eq!(empty.percentile(50.0), Duration::default());
+
+    // 1ms to 100ms, pushed out of order.
+    let mut samples = Samples::default();
+
+    for ms in (1..=100).rev() {
+        samples.push(Duration::from_millis(ms));
+    }
+
+    assert_eq!(samples.len(), 100);
+    assert_eq!(samples.percentile(0.0), Duration::from_millis(1));
+    assert_eq!(samples.percentile(50.0), Duration::from_millis(51));
+    assert_eq!(samples.percentile(95.0), Duration::from_millis(95));
+    assert_eq!(samples.percentile(99.0), Duration::from_millis(99));
+    assert_eq!(samples.percentile(100.0), Duration::from_millis(100));
+
+    // Percentiles outside of [0.0, 100.0] are clamped.
+    assert_eq!(samples.percentile(-5.0), Duration::from_millis(1));
+    assert_eq!(samples.percentile(150.0), Duration::from_millis(100));
+
+    assert_eq!(
+        samples.to_string(),
+        "p50 51.000ms, p95 95.000ms, p99 99.000ms, max 100.000ms (100 samples)"
+    );
+}